@@ -37,6 +37,8 @@ async fn main() {
         url: Url::parse("https://client.example.com/webhook").unwrap(),
         token: Some("client-secret-token".to_string()),
         authentication: None,
+        filter: None,
+        payload_format: None,
     };
     
     let params = MessageSendParams::new(message)
@@ -0,0 +1,36 @@
+//! Rebuild Tasks From An Event Log
+//!
+//! Reconstructs every task recorded in the event log of an
+//! `EventSourcedTaskStore` and prints it as a JSON line. With a database
+//! URL, the tasks are also saved to a SQLite task store, e.g. to seed it
+//! from the log.
+//!
+//! ```text
+//! cargo run --example rebuild_tasks -- a2a-events.jsonl [sqlite://tasks.db]
+//! ```
+
+use a2a_rust::a2a::server::tasks::{EventSourcedTaskStore, FileTaskEventLog, SqliteTaskStore, TaskStore};
+use std::sync::Arc;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let Some(log_path) = args.next() else {
+        eprintln!("usage: rebuild_tasks <event log> [database url]");
+        std::process::exit(2);
+    };
+
+    let store = EventSourcedTaskStore::new(Arc::new(FileTaskEventLog::open(&log_path).await?));
+    let tasks = store.rebuild().await?;
+    for task in &tasks {
+        println!("{}", serde_json::to_string(task)?);
+    }
+    eprintln!("Rebuilt {} tasks from {}", tasks.len(), log_path);
+
+    if let Some(url) = args.next() {
+        let target = SqliteTaskStore::connect(&url).await?;
+        target.save_many(tasks).await?;
+        eprintln!("Saved the tasks to {}", url);
+    }
+    Ok(())
+}
@@ -8,7 +8,7 @@ use a2a_rust::a2a::{
     server::{
         apps::jsonrpc::{A2AServerBuilder, ServerConfig},
         context::DefaultServerCallContextBuilder,
-        request_handlers::{RequestHandler, MessageSendResult, TaskPushNotificationConfigQueryParams},
+        request_handlers::{RequestHandler, MessageSendResult},
     },
     core_types::{Message, Part, Role, TaskState, TaskStatus},
     error::A2AError,
@@ -86,6 +86,7 @@ impl RequestHandler for TaskAwareHandler {
             status: TaskStatus::new(TaskState::Completed),
             artifacts: None,
             history: None,
+            state_transitions: None,
             metadata: None,
             kind: "task".to_string(),
         };
@@ -108,38 +109,6 @@ impl RequestHandler for TaskAwareHandler {
 
         Ok(MessageSendResult::Task(task))
     }
-
-    async fn on_set_task_push_notification_config(
-        &self,
-        _params: TaskPushNotificationConfig,
-        _context: Option<&a2a_rust::a2a::server::context::ServerCallContext>,
-    ) -> Result<TaskPushNotificationConfig, A2AError> {
-        Err(A2AError::unsupported_operation("Push notifications not supported"))
-    }
-
-    async fn on_get_task_push_notification_config(
-        &self,
-        _params: TaskPushNotificationConfigQueryParams,
-        _context: Option<&a2a_rust::a2a::server::context::ServerCallContext>,
-    ) -> Result<TaskPushNotificationConfig, A2AError> {
-        Err(A2AError::unsupported_operation("Push notifications not supported"))
-    }
-
-    async fn on_list_task_push_notification_config(
-        &self,
-        _params: TaskIdParams,
-        _context: Option<&a2a_rust::a2a::server::context::ServerCallContext>,
-    ) -> Result<Vec<TaskPushNotificationConfig>, A2AError> {
-        Ok(vec![])
-    }
-
-    async fn on_delete_task_push_notification_config(
-        &self,
-        _params: DeleteTaskPushNotificationConfigParams,
-        _context: Option<&a2a_rust::a2a::server::context::ServerCallContext>,
-    ) -> Result<(), A2AError> {
-        Ok(())
-    }
 }
 
 #[tokio::main]
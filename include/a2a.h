@@ -0,0 +1,51 @@
+/*
+ * C API of the a2a-rust client, exported by the shared library built with
+ * `cargo rustc --lib --release --features ffi --crate-type cdylib`.
+ *
+ * Functions returning int32_t return A2A_OK, one of the A2A_ERR_* codes
+ * below, or the JSON-RPC / A2A error code of a failed request (for example
+ * -32001 when the task is not found). a2a_last_error_message() describes
+ * the last failure on the calling thread.
+ *
+ * Strings returned through out parameters are JSON and must be released
+ * with a2a_string_free().
+ */
+
+#ifndef A2A_H
+#define A2A_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define A2A_OK 0
+#define A2A_ERR_NULL_POINTER -1
+#define A2A_ERR_INVALID_UTF8 -2
+#define A2A_ERR_INVALID_JSON -3
+#define A2A_ERR_PANIC -4
+
+typedef struct A2AClient A2AClient;
+
+/* Resolves the agent card under base_url and creates a client for it */
+int32_t a2a_client_new(const char *base_url, A2AClient **out_client);
+
+/* Sends message/send with MessageSendParams JSON; returns a task or message */
+int32_t a2a_client_send_message(const A2AClient *client, const char *params_json, char **out_json);
+
+/* Fetches a task with tasks/get */
+int32_t a2a_client_get_task(const A2AClient *client, const char *task_id, char **out_json);
+
+void a2a_client_free(A2AClient *client);
+
+void a2a_string_free(char *value);
+
+/* Message of the last failure on this thread, or NULL */
+const char *a2a_last_error_message(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* A2A_H */
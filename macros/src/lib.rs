@@ -0,0 +1,292 @@
+//! Procedural macros for a2a-rust
+//!
+//! `#[a2a_skills]` turns the `#[a2a_skill(...)]` methods of an impl block
+//! into an `AgentExecutor` for the type and an `agent_skills()` function
+//! listing its `AgentSkill` entries. The code it generates calls into
+//! `a2a_rust::a2a::server::agent_execution::skills`, whose documentation
+//! describes how messages are routed and arguments filled in.
+//!
+//! Enable the `macros` feature of `a2a-rust` rather than depending on this
+//! crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{Attribute, Expr, ExprArray, FnArg, ImplItem, ImplItemFn, ItemImpl, Lit, LitStr, ReturnType, Type};
+
+/// Generates an `AgentExecutor` and `agent_skills()` from skill methods
+///
+/// Every method marked `#[a2a_skill(...)]` must be `async` and take `&self`
+/// or no receiver. The attribute accepts:
+///
+/// - `id = "..."`: the skill id, by default the method name
+/// - `name = "..."`: the human readable name, by default the id
+/// - `description = "..."`: by default the doc comment of the method
+/// - `tags = ["...", ...]` and `examples = ["...", ...]`
+#[proc_macro_attribute]
+pub fn a2a_skills(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(TokenStream2::from(attr).span(), "a2a_skills takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+    let item = syn::parse_macro_input!(item as ItemImpl);
+    expand(item).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// One `#[a2a_skill]` method
+struct Skill {
+    id: LitStr,
+    name: LitStr,
+    description: String,
+    tags: Vec<LitStr>,
+    examples: Vec<LitStr>,
+    method: syn::Ident,
+    has_receiver: bool,
+    inputs: Vec<Input>,
+    returns_result: bool,
+}
+
+/// Where an argument of a skill method comes from
+enum Input {
+    Context,
+    Text,
+    Data(Box<Type>),
+}
+
+fn expand(mut item: ItemImpl) -> syn::Result<TokenStream2> {
+    if let Some((_, path, _)) = &item.trait_ {
+        return Err(syn::Error::new(path.span(), "a2a_skills must be used on an inherent impl block"));
+    }
+
+    let mut skills: Vec<Skill> = Vec::new();
+    for impl_item in &mut item.items {
+        let ImplItem::Fn(method) = impl_item else { continue };
+        let Some(index) = method.attrs.iter().position(|attr| attr.path().is_ident("a2a_skill")) else {
+            continue;
+        };
+        let attr = method.attrs.remove(index);
+        let skill = parse_skill(&attr, method)?;
+        if skills.iter().any(|other| other.id.value() == skill.id.value()) {
+            return Err(syn::Error::new(skill.id.span(), format!("duplicate skill id '{}'", skill.id.value())));
+        }
+        skills.push(skill);
+    }
+    if skills.is_empty() {
+        return Err(syn::Error::new(
+            item.self_ty.span(),
+            "a2a_skills needs at least one method marked #[a2a_skill]",
+        ));
+    }
+
+    let krate = quote!(::a2a_rust::a2a);
+    let support = quote!(#krate::server::agent_execution::skills);
+    let self_ty = &item.self_ty;
+    let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+
+    let entries = skills.iter().map(|skill| {
+        let Skill { id, name, tags, examples, .. } = skill;
+        let description = &skill.description;
+        let examples = (!examples.is_empty())
+            .then(|| quote!(.with_examples(::std::vec![#(#examples.to_string()),*])));
+        quote! {
+            #krate::models::AgentSkill::new(
+                #id.to_string(),
+                #name.to_string(),
+                #description.to_string(),
+                ::std::vec![#(#tags.to_string()),*],
+            )#examples
+        }
+    });
+    let ids: Vec<&LitStr> = skills.iter().map(|skill| &skill.id).collect();
+    let arms = skills.iter().map(|skill| dispatch(skill, &krate, &support));
+
+    Ok(quote! {
+        #item
+
+        impl #impl_generics #self_ty #where_clause {
+            /// The skills served by this executor, for the agent card
+            pub fn agent_skills() -> ::std::vec::Vec<#krate::models::AgentSkill> {
+                ::std::vec![#(#entries),*]
+            }
+        }
+
+        #[#support::async_trait]
+        impl #impl_generics #krate::server::agent_execution::AgentExecutor for #self_ty #where_clause {
+            async fn execute(
+                &self,
+                context: #krate::server::agent_execution::RequestContext,
+                event_queue: ::std::sync::Arc<dyn #krate::server::events::EventQueue>,
+            ) -> ::std::result::Result<(), #krate::error::A2AError> {
+                match #support::select_skill(&context, &[#(#ids),*])? {
+                    #(#arms)*
+                    other => ::std::result::Result::Err(#krate::error::A2AError::invalid_params(
+                        &::std::format!("The agent has no skill '{}'", other),
+                    )),
+                }
+            }
+
+            async fn cancel(
+                &self,
+                context: #krate::server::agent_execution::RequestContext,
+                event_queue: ::std::sync::Arc<dyn #krate::server::events::EventQueue>,
+            ) -> ::std::result::Result<(), #krate::error::A2AError> {
+                #support::cancel_task(&context, event_queue).await
+            }
+        }
+    })
+}
+
+/// The match arm running `skill`
+fn dispatch(skill: &Skill, krate: &TokenStream2, support: &TokenStream2) -> TokenStream2 {
+    let id = &skill.id;
+    let method = &skill.method;
+    let args: Vec<_> = (0..skill.inputs.len()).map(|i| format_ident!("__a2a_arg{}", i)).collect();
+    let bindings = skill.inputs.iter().zip(&args).map(|(input, arg)| match input {
+        Input::Context => quote!(let #arg = &context;),
+        Input::Text => quote!(let #arg = #support::text_input(&context);),
+        Input::Data(ty) => quote!(let #arg = #support::data_input::<#ty>(&context)?;),
+    });
+    let call = if skill.has_receiver {
+        quote!(self.#method(#(#args),*).await)
+    } else {
+        quote!(Self::#method(#(#args),*).await)
+    };
+    let output = if skill.returns_result {
+        quote!(#call.map_err(::std::convert::Into::<#krate::error::A2AError>::into))
+    } else {
+        quote!(::std::result::Result::Ok::<_, #krate::error::A2AError>(#call))
+    };
+    quote! {
+        #id => {
+            let skill = async {
+                #(#bindings)*
+                #output
+            };
+            #support::run_skill(#id, &context, event_queue, skill).await
+        }
+    }
+}
+
+fn parse_skill(attr: &Attribute, method: &ImplItemFn) -> syn::Result<Skill> {
+    let signature = &method.sig;
+    if signature.asyncness.is_none() {
+        return Err(syn::Error::new(signature.fn_token.span(), "skill methods must be async"));
+    }
+
+    let mut id = None;
+    let mut name = None;
+    let mut description = None;
+    let mut tags = Vec::new();
+    let mut examples = Vec::new();
+    if !matches!(attr.meta, syn::Meta::Path(_)) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("id") {
+                id = Some(meta.value()?.parse::<LitStr>()?);
+            } else if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse::<LitStr>()?);
+            } else if meta.path.is_ident("description") {
+                description = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("tags") {
+                tags = string_list(meta.value()?.parse()?)?;
+            } else if meta.path.is_ident("examples") {
+                examples = string_list(meta.value()?.parse()?)?;
+            } else {
+                return Err(meta.error("expected one of id, name, description, tags, examples"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let id = id.unwrap_or_else(|| LitStr::new(&signature.ident.to_string(), signature.ident.span()));
+    let name = name.unwrap_or_else(|| id.clone());
+    let description = match description.or_else(|| doc_comment(&method.attrs)) {
+        Some(description) => description,
+        None => {
+            return Err(syn::Error::new(
+                signature.ident.span(),
+                "skills need a description, from the attribute or a doc comment",
+            ))
+        }
+    };
+
+    let mut has_receiver = false;
+    let mut inputs = Vec::new();
+    for arg in &signature.inputs {
+        match arg {
+            FnArg::Receiver(receiver) => {
+                if receiver.reference.is_none() || receiver.mutability.is_some() {
+                    return Err(syn::Error::new(receiver.span(), "skill methods must take &self"));
+                }
+                has_receiver = true;
+            }
+            FnArg::Typed(arg) => inputs.push(match &*arg.ty {
+                Type::Reference(reference) if is_named(&reference.elem, "RequestContext") => Input::Context,
+                ty if is_named(ty, "String") => Input::Text,
+                ty => Input::Data(Box::new(ty.clone())),
+            }),
+        }
+    }
+    let returns_result = matches!(&signature.output, ReturnType::Type(_, ty) if is_result(ty));
+
+    Ok(Skill {
+        id,
+        name,
+        description,
+        tags,
+        examples,
+        method: signature.ident.clone(),
+        has_receiver,
+        inputs,
+        returns_result,
+    })
+}
+
+fn string_list(array: ExprArray) -> syn::Result<Vec<LitStr>> {
+    array
+        .elems
+        .into_iter()
+        .map(|elem| match elem {
+            Expr::Lit(syn::ExprLit { lit: Lit::Str(lit), .. }) => Ok(lit),
+            other => Err(syn::Error::new(other.span(), "expected a string literal")),
+        })
+        .collect()
+}
+
+/// The doc comment of a method as one line
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value: Expr::Lit(syn::ExprLit { lit: Lit::Str(lit), .. }),
+                ..
+            }) => Some(lit.value().trim().to_string()),
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+    (!lines.is_empty()).then(|| lines.join(" "))
+}
+
+/// Whether `ty` is a path ending in `name`, without generic arguments
+fn is_named(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Path(path) => path.qself.is_none()
+            && path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == name && segment.arguments.is_none()),
+        _ => false,
+    }
+}
+
+fn is_result(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.path.segments.last().is_some_and(|segment| segment.ident == "Result"),
+        _ => false,
+    }
+}
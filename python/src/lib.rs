@@ -0,0 +1,12 @@
+//! The `a2a_rust` Python extension module
+//!
+//! The classes live in `a2a_rust::a2a::python`; this crate only builds them
+//! as a `cdylib`, so that the library itself needs no Python to link.
+
+use pyo3::prelude::*;
+
+#[pymodule]
+#[pyo3(name = "a2a_rust")]
+fn a2a_rust_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    a2a::a2a::python::register(m)
+}
@@ -0,0 +1,299 @@
+//! Credential caching for the authentication interceptor
+//!
+//! Resolving a credential can mean a round trip to a token endpoint, so
+//! `CredentialCache` keeps the credential of each security scheme until it
+//! expires. Credential services may resolve different credentials per call
+//! context, e.g. per session or tenant, so credentials are cached per scheme
+//! and context metadata. The expiry is, in order of preference, the one reported by the
+//! `CredentialService`, the `exp` claim of a JWT credential, or the cache's
+//! default TTL. Credentials close to expiry are refreshed in the background
+//! while the current one is still used, and credentials the agent rejects
+//! with 401 or 403 are evicted so the next call fetches a fresh credential.
+
+use crate::a2a::client::auth::credentials::{Credential, CredentialService};
+use crate::a2a::client::client_trait::ClientCallContext;
+use crate::a2a::error::A2AError;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Default lifetime of credentials without a known expiry
+pub const DEFAULT_CREDENTIAL_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Default time before expiry at which credentials are refreshed
+pub const DEFAULT_REFRESH_BEFORE: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone)]
+struct CachedCredential {
+    value: String,
+    expires_at: DateTime<Utc>,
+    refreshing: bool,
+}
+
+/// Key of a cached credential: the scheme name and the context it was
+/// resolved for
+type CacheKey = (String, String);
+
+fn cache_key(scheme_name: &str, context: Option<&ClientCallContext>) -> CacheKey {
+    let partition = context
+        .filter(|context| !context.metadata.is_empty())
+        .map(|context| {
+            let metadata: BTreeMap<_, _> = context.metadata.iter().collect();
+            serde_json::to_string(&metadata).unwrap_or_default()
+        })
+        .unwrap_or_default();
+    (scheme_name.to_string(), partition)
+}
+
+/// Per-scheme and per-context cache of resolved credentials
+#[derive(Debug, Clone)]
+pub struct CredentialCache {
+    entries: Arc<Mutex<HashMap<CacheKey, CachedCredential>>>,
+    default_ttl: Duration,
+    refresh_before: Duration,
+}
+
+impl CredentialCache {
+    /// Creates a cache with `DEFAULT_CREDENTIAL_TTL` and `DEFAULT_REFRESH_BEFORE`
+    pub fn new() -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            default_ttl: DEFAULT_CREDENTIAL_TTL,
+            refresh_before: DEFAULT_REFRESH_BEFORE,
+        }
+    }
+
+    /// Sets the lifetime of credentials without a known expiry
+    pub fn with_default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = ttl;
+        self
+    }
+
+    /// Sets how long before expiry credentials are refreshed
+    pub fn with_refresh_before(mut self, refresh_before: Duration) -> Self {
+        self.refresh_before = refresh_before;
+        self
+    }
+
+    /// Drops the cached credentials of `scheme_name`, for every context
+    pub fn invalidate(&self, scheme_name: &str) {
+        self.entries.lock().unwrap().retain(|(scheme, _), _| scheme != scheme_name);
+    }
+
+    /// Drops the credential of `scheme_name` cached for `context`
+    pub fn evict(&self, scheme_name: &str, context: Option<&ClientCallContext>) {
+        self.entries.lock().unwrap().remove(&cache_key(scheme_name, context));
+    }
+
+    /// Drops every cached credential
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// When the credential of `scheme_name` cached for `context` expires, if
+    /// one is cached
+    pub fn expires_at(&self, scheme_name: &str, context: Option<&ClientCallContext>) -> Option<DateTime<Utc>> {
+        self.entries.lock().unwrap().get(&cache_key(scheme_name, context)).map(|entry| entry.expires_at)
+    }
+
+    fn store(&self, key: CacheKey, credential: &Credential) {
+        let expires_at = credential
+            .expires_at
+            .or_else(|| jwt_expiry(&credential.value))
+            .unwrap_or_else(|| Utc::now() + chrono::Duration::from_std(self.default_ttl).unwrap_or_default());
+        self.entries.lock().unwrap().insert(
+            key,
+            CachedCredential {
+                value: credential.value.clone(),
+                expires_at,
+                refreshing: false,
+            },
+        );
+    }
+
+    /// Returns the credential of `scheme_name` for `context`, from the cache
+    /// if it is still valid or else from `service`
+    pub async fn get(
+        &self,
+        service: &Arc<dyn CredentialService>,
+        scheme_name: &str,
+        context: Option<&ClientCallContext>,
+    ) -> Result<Option<String>, A2AError> {
+        let key = cache_key(scheme_name, context);
+        let now = Utc::now();
+        let refresh_at = now + chrono::Duration::from_std(self.refresh_before).unwrap_or_default();
+        let cached = {
+            let mut entries = self.entries.lock().unwrap();
+            match entries.get_mut(&key) {
+                Some(entry) if entry.expires_at > now => {
+                    let refresh = entry.expires_at <= refresh_at && !entry.refreshing;
+                    entry.refreshing |= refresh;
+                    Some((entry.value.clone(), refresh))
+                }
+                _ => None,
+            }
+        };
+
+        match cached {
+            Some((value, refresh)) => {
+                // Without a runtime to refresh in the background, the call refreshes
+                #[cfg(feature = "runtime")]
+                if refresh {
+                    let (cache, service, scheme_name, context) =
+                        (self.clone(), service.clone(), scheme_name.to_string(), context.cloned());
+                    tokio::spawn(async move { cache.refresh(&service, &scheme_name, context.as_ref()).await });
+                }
+                #[cfg(not(feature = "runtime"))]
+                if refresh {
+                    self.refresh(service, scheme_name, context).await;
+                }
+                Ok(Some(value))
+            }
+            None => {
+                let credential = service.get_credential(scheme_name, context).await?;
+                match &credential {
+                    Some(credential) => self.store(key, credential),
+                    None => {
+                        self.entries.lock().unwrap().remove(&key);
+                    }
+                }
+                Ok(credential.map(|credential| credential.value))
+            }
+        }
+    }
+
+    async fn refresh(&self, service: &Arc<dyn CredentialService>, scheme_name: &str, context: Option<&ClientCallContext>) {
+        let key = cache_key(scheme_name, context);
+        match service.get_credential(scheme_name, context).await {
+            Ok(Some(credential)) => self.store(key, &credential),
+            Ok(None) => {
+                self.entries.lock().unwrap().remove(&key);
+            }
+            Err(e) => {
+                tracing::warn!("Failed to refresh credentials for scheme '{}': {}", scheme_name, e);
+                if let Some(entry) = self.entries.lock().unwrap().get_mut(&key) {
+                    entry.refreshing = false;
+                }
+            }
+        }
+    }
+}
+
+impl Default for CredentialCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The `exp` claim of a JWT, if `token` is one
+pub fn jwt_expiry(token: &str) -> Option<DateTime<Utc>> {
+    let mut segments = token.split('.');
+    let (_header, payload, _signature) = (segments.next()?, segments.next()?, segments.next()?);
+    if segments.next().is_some() {
+        return None;
+    }
+    let claims: serde_json::Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?).ok()?;
+    DateTime::from_timestamp(claims.get("exp")?.as_i64()?, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingService {
+        calls: AtomicUsize,
+        ttl: Option<chrono::Duration>,
+    }
+
+    #[async_trait]
+    impl CredentialService for CountingService {
+        async fn get_credentials(&self, _: &str, _: Option<&ClientCallContext>) -> Result<Option<String>, A2AError> {
+            Ok(Some(format!("token-{}", self.calls.fetch_add(1, Ordering::SeqCst))))
+        }
+
+        async fn get_credential(&self, scheme: &str, context: Option<&ClientCallContext>) -> Result<Option<Credential>, A2AError> {
+            let value = self.get_credentials(scheme, context).await?.unwrap();
+            Ok(Some(match self.ttl {
+                Some(ttl) => Credential::new(value).with_expires_at(Utc::now() + ttl),
+                None => Credential::new(value),
+            }))
+        }
+    }
+
+    fn service(ttl: Option<chrono::Duration>) -> (Arc<CountingService>, Arc<dyn CredentialService>) {
+        let service = Arc::new(CountingService {
+            calls: AtomicUsize::new(0),
+            ttl,
+        });
+        (service.clone(), service)
+    }
+
+    #[test]
+    fn test_jwt_expiry_is_read_from_exp_claim() {
+        let payload = URL_SAFE_NO_PAD.encode(br#"{"sub":"agent","exp":1700000000}"#);
+        let token = format!("eyJhbGciOiJIUzI1NiJ9.{}.c2ln", payload);
+        assert_eq!(jwt_expiry(&token), DateTime::from_timestamp(1_700_000_000, 0));
+        assert_eq!(jwt_expiry("opaque-token"), None);
+    }
+
+    #[tokio::test]
+    async fn test_caches_until_invalidated() {
+        let (counter, service) = service(None);
+        let cache = CredentialCache::new();
+
+        assert_eq!(cache.get(&service, "bearer", None).await.unwrap().as_deref(), Some("token-0"));
+        assert_eq!(cache.get(&service, "bearer", None).await.unwrap().as_deref(), Some("token-0"));
+        assert_eq!(counter.calls.load(Ordering::SeqCst), 1);
+
+        cache.invalidate("bearer");
+        assert_eq!(cache.get(&service, "bearer", None).await.unwrap().as_deref(), Some("token-1"));
+    }
+
+    #[tokio::test]
+    async fn test_credentials_are_cached_per_context() {
+        let (counter, service) = service(None);
+        let cache = CredentialCache::new();
+        let alice = ClientCallContext::new().with_metadata("session", "alice");
+        let bob = ClientCallContext::new().with_metadata("session", "bob");
+
+        assert_eq!(cache.get(&service, "bearer", Some(&alice)).await.unwrap().as_deref(), Some("token-0"));
+        assert_eq!(cache.get(&service, "bearer", Some(&bob)).await.unwrap().as_deref(), Some("token-1"));
+        assert_eq!(cache.get(&service, "bearer", Some(&alice)).await.unwrap().as_deref(), Some("token-0"));
+        assert_eq!(cache.get(&service, "bearer", None).await.unwrap().as_deref(), Some("token-2"));
+        assert_eq!(counter.calls.load(Ordering::SeqCst), 3);
+
+        cache.evict("bearer", Some(&alice));
+        assert!(cache.expires_at("bearer", Some(&alice)).is_none());
+        assert!(cache.expires_at("bearer", Some(&bob)).is_some());
+
+        cache.invalidate("bearer");
+        assert!(cache.expires_at("bearer", Some(&bob)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_refreshes_before_expiry_and_refetches_after() {
+        let (counter, service) = service(Some(chrono::Duration::seconds(10)));
+        let cache = CredentialCache::new().with_refresh_before(Duration::from_secs(20));
+
+        assert_eq!(cache.get(&service, "bearer", None).await.unwrap().as_deref(), Some("token-0"));
+        // Within the refresh window the current token is used while a new one is fetched
+        assert_eq!(cache.get(&service, "bearer", None).await.unwrap().as_deref(), Some("token-0"));
+        for _ in 0..50 {
+            if counter.calls.load(Ordering::SeqCst) == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(cache.get(&service, "bearer", None).await.unwrap().as_deref(), Some("token-1"));
+
+        let (_, service) = self::service(Some(chrono::Duration::seconds(-1)));
+        let cache = CredentialCache::new();
+        cache.get(&service, "bearer", None).await.unwrap();
+        assert_eq!(cache.get(&service, "bearer", None).await.unwrap().as_deref(), Some("token-1"));
+    }
+}
@@ -0,0 +1,191 @@
+//! Authentication challenges and security requirement fallback
+//!
+//! When an agent answers 401 or 403, its `WWW-Authenticate` header names the
+//! schemes it accepts, e.g. `Bearer realm="agents", error="invalid_token"`.
+//! The transport then retries the call with the next security requirement
+//! set of the agent card, preferring sets using a challenged scheme, until
+//! the attempt cap is reached.
+//!
+//! The transport and `AuthInterceptor` coordinate through `http_kwargs`:
+//! the interceptor records which requirement set it applied under
+//! `APPLIED_REQUIREMENT_KWARG`, and the transport passes the rejected sets
+//! and the received challenges back under `REJECTED_REQUIREMENTS_KWARG` and
+//! `CHALLENGES_KWARG` on the retry.
+
+use crate::a2a::models::*;
+use std::collections::HashMap;
+
+/// Default number of attempts of a call, across security requirement sets
+pub const DEFAULT_MAX_AUTH_ATTEMPTS: usize = 3;
+
+/// `http_kwargs` key of the index of the applied security requirement set
+pub const APPLIED_REQUIREMENT_KWARG: &str = "auth_requirement";
+
+/// `http_kwargs` key of the indices of requirement sets the agent rejected
+pub const REJECTED_REQUIREMENTS_KWARG: &str = "auth_rejected_requirements";
+
+/// `http_kwargs` key of the `WWW-Authenticate` values of the last rejection
+pub const CHALLENGES_KWARG: &str = "auth_challenges";
+
+/// One challenge of a `WWW-Authenticate` header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthChallenge {
+    /// The authentication scheme, e.g. `Bearer`
+    pub scheme: String,
+    /// The auth-params, with lowercase names
+    pub params: HashMap<String, String>,
+}
+
+impl AuthChallenge {
+    /// The `realm` parameter
+    pub fn realm(&self) -> Option<&str> {
+        self.params.get("realm").map(String::as_str)
+    }
+
+    /// The `error` parameter of RFC 6750 bearer challenges, e.g. `invalid_token`
+    pub fn error(&self) -> Option<&str> {
+        self.params.get("error").map(String::as_str)
+    }
+
+    /// The scopes of the `scope` parameter
+    pub fn scopes(&self) -> Vec<&str> {
+        self.params
+            .get("scope")
+            .map(|scope| scope.split_whitespace().collect())
+            .unwrap_or_default()
+    }
+
+    /// Whether a credential for `scheme` answers this challenge
+    pub fn matches(&self, scheme: &SecurityScheme) -> bool {
+        let challenged = self.scheme.to_ascii_lowercase();
+        match scheme {
+            SecurityScheme::HTTPAuth(http_scheme) => http_scheme.scheme.eq_ignore_ascii_case(&challenged),
+            SecurityScheme::OAuth2(_) | SecurityScheme::OpenIdConnect(_) => challenged == "bearer",
+            SecurityScheme::APIKey(_) => challenged == "apikey",
+            SecurityScheme::MutualTLS(_) => false,
+        }
+    }
+}
+
+/// Parses the challenges of a `WWW-Authenticate` header value
+///
+/// Values with several challenges, quoted parameters and `token68`
+/// credentials are supported; text that is not a challenge is skipped.
+pub fn parse_www_authenticate(header: &str) -> Vec<AuthChallenge> {
+    let mut challenges = Vec::new();
+    let mut rest = header;
+
+    loop {
+        let (scheme, after_scheme) = take_token(rest.trim_start_matches([',', ' ', '\t']));
+        if scheme.is_empty() {
+            break;
+        }
+        let mut challenge = AuthChallenge {
+            scheme: scheme.to_string(),
+            params: HashMap::new(),
+        };
+        let after_scheme = after_scheme.trim_start_matches([' ', '\t']);
+
+        let token68_end = after_scheme.find([',', ' ', '\t']).unwrap_or(after_scheme.len());
+        let (candidate, after_candidate) = after_scheme.split_at(token68_end);
+        let after_candidate = after_candidate.trim_start_matches([' ', '\t']);
+        if is_token68(candidate) && (after_candidate.is_empty() || after_candidate.starts_with(',')) {
+            challenge.params.insert("token68".to_string(), candidate.to_string());
+            rest = after_candidate;
+        } else {
+            rest = after_scheme;
+            loop {
+                let param = rest.trim_start_matches([',', ' ', '\t']);
+                let (name, after_name) = take_token(param);
+                let Some(value) = after_name.trim_start_matches([' ', '\t']).strip_prefix('=') else {
+                    // Not a parameter, so the start of the next challenge
+                    rest = param;
+                    break;
+                };
+                let (value, remaining) = parse_param_value(value.trim_start_matches([' ', '\t']));
+                challenge.params.insert(name.to_ascii_lowercase(), value);
+                rest = remaining;
+            }
+        }
+
+        challenges.push(challenge);
+    }
+
+    challenges
+}
+
+fn take_token(input: &str) -> (&str, &str) {
+    input.split_at(input.find([' ', '\t', ',', '=', '"']).unwrap_or(input.len()))
+}
+
+fn is_token68(candidate: &str) -> bool {
+    let trimmed = candidate.trim_end_matches('=');
+    !trimmed.is_empty()
+        && trimmed
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~' | '+' | '/'))
+}
+
+/// Parses a token or quoted-string parameter value, returning the rest
+fn parse_param_value(input: &str) -> (String, &str) {
+    match input.strip_prefix('"') {
+        Some(quoted) => {
+            let mut value = String::new();
+            let mut chars = quoted.char_indices();
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '\\' => {
+                        if let Some((_, escaped)) = chars.next() {
+                            value.push(escaped);
+                        }
+                    }
+                    '"' => return (value, &quoted[i + 1..]),
+                    _ => value.push(c),
+                }
+            }
+            (value, "")
+        }
+        None => {
+            let end = input.find([',', ' ', '\t']).unwrap_or(input.len());
+            (input[..end].to_string(), &input[end..])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_multiple_challenges_with_params() {
+        let challenges = parse_www_authenticate(
+            r#"Bearer realm="agents", error="insufficient_scope", scope="tasks:read tasks:write", Basic realm="legacy", Negotiate abc123=="#,
+        );
+        assert_eq!(challenges.len(), 3);
+        assert_eq!(challenges[0].scheme, "Bearer");
+        assert_eq!(challenges[0].realm(), Some("agents"));
+        assert_eq!(challenges[0].error(), Some("insufficient_scope"));
+        assert_eq!(challenges[0].scopes(), vec!["tasks:read", "tasks:write"]);
+        assert_eq!(challenges[1].scheme, "Basic");
+        assert_eq!(challenges[1].realm(), Some("legacy"));
+        assert_eq!(challenges[2].scheme, "Negotiate");
+        assert_eq!(challenges[2].params.get("token68").map(String::as_str), Some("abc123=="));
+    }
+
+    #[test]
+    fn test_challenge_matches_security_schemes() {
+        let bearer = &parse_www_authenticate("Bearer")[0];
+        let oauth = SecurityScheme::OAuth2(OAuth2SecurityScheme {
+            flows: HashMap::new(),
+            description: None,
+        });
+        let basic = SecurityScheme::HTTPAuth(HTTPAuthSecurityScheme {
+            scheme: "basic".to_string(),
+            bearer_format: None,
+            description: None,
+        });
+        assert!(bearer.matches(&oauth));
+        assert!(!bearer.matches(&basic));
+        assert!(parse_www_authenticate(r#"Basic realm="x""#)[0].matches(&basic));
+    }
+}
@@ -6,8 +6,34 @@
 use crate::a2a::client::client_trait::ClientCallContext;
 use crate::a2a::error::A2AError;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 
+/// A credential with its expiry, when the service knows it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credential {
+    /// The credential as sent to the agent
+    pub value: String,
+    /// When the credential stops being valid
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Credential {
+    /// A credential without a known expiry
+    pub fn new(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            expires_at: None,
+        }
+    }
+
+    /// Sets when the credential expires, e.g. from a token endpoint's `expires_in`
+    pub fn with_expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+}
+
 /// Trait for providing credentials for authentication
 #[async_trait]
 pub trait CredentialService: Send + Sync {
@@ -17,6 +43,18 @@ pub trait CredentialService: Send + Sync {
         scheme_name: &str,
         context: Option<&ClientCallContext>,
     ) -> Result<Option<String>, A2AError>;
+
+    /// Get credentials with their expiry
+    ///
+    /// Services issuing tokens with a known lifetime override this so cached
+    /// credentials are refreshed in time; by default the expiry is unknown.
+    async fn get_credential(
+        &self,
+        scheme_name: &str,
+        context: Option<&ClientCallContext>,
+    ) -> Result<Option<Credential>, A2AError> {
+        Ok(self.get_credentials(scheme_name, context).await?.map(Credential::new))
+    }
 }
 
 /// In-memory credential store for contexts
@@ -72,7 +110,16 @@ impl CredentialService for InMemoryContextCredentialStore {
     }
 }
 
+/// Prefix of the credential variables read by `EnvironmentCredentialService::standard`
+pub const CREDENTIAL_ENV_PREFIX: &str = "A2A_CRED_";
+
 /// Environment-based credential service
+///
+/// The variable of a scheme is the prefix followed by the scheme name in
+/// upper case, with characters other than letters and digits replaced by
+/// `_`, e.g. `A2A_CRED_OAUTH_CLIENT` for `oauth.client`. When that variable
+/// is unset, the name used by earlier releases, which only replaced `-`
+/// (`A2A_CRED_OAUTH.CLIENT`), is read instead.
 #[derive(Debug, Clone)]
 pub struct EnvironmentCredentialService {
     /// Prefix for environment variables
@@ -92,8 +139,22 @@ impl EnvironmentCredentialService {
         Self::new("A2A_")
     }
     
+    /// Create an environment credential service reading `A2A_CRED_{SCHEME}`
+    pub fn standard() -> Self {
+        Self::new(CREDENTIAL_ENV_PREFIX)
+    }
+    
     /// Get environment variable name for a scheme
     fn env_var_name(&self, scheme_name: &str) -> String {
+        let scheme: String = scheme_name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+            .collect();
+        format!("{}{}", self.prefix, scheme)
+    }
+    
+    /// Variable name of a scheme as read before non-alphanumeric characters were mapped
+    fn legacy_env_var_name(&self, scheme_name: &str) -> String {
         format!("{}{}", self.prefix, scheme_name.to_uppercase().replace("-", "_"))
     }
 }
@@ -106,7 +167,10 @@ impl CredentialService for EnvironmentCredentialService {
         _context: Option<&ClientCallContext>,
     ) -> Result<Option<String>, A2AError> {
         let env_var = self.env_var_name(scheme_name);
-        Ok(std::env::var(&env_var).ok())
+        let credential = std::env::var(&env_var)
+            .or_else(|_| std::env::var(self.legacy_env_var_name(scheme_name)))
+            .ok();
+        Ok(credential)
     }
 }
 
@@ -156,6 +220,19 @@ impl CredentialService for CompositeCredentialService {
         }
         Ok(None)
     }
+
+    async fn get_credential(
+        &self,
+        scheme_name: &str,
+        context: Option<&ClientCallContext>,
+    ) -> Result<Option<Credential>, A2AError> {
+        for service in &self.services {
+            if let Ok(Some(credential)) = service.get_credential(scheme_name, context).await {
+                return Ok(Some(credential));
+            }
+        }
+        Ok(None)
+    }
 }
 
 #[cfg(test)]
@@ -197,6 +274,43 @@ mod tests {
         std::env::remove_var("A2A_API_KEY");
     }
 
+    #[tokio::test]
+    async fn test_standard_environment_credential_service() {
+        std::env::set_var("A2A_CRED_OAUTH_CLIENT", "cred-token");
+
+        let service = EnvironmentCredentialService::standard();
+        let credential = service.get_credentials("oauth.client", None).await.unwrap();
+        assert_eq!(credential, Some("cred-token".to_string()));
+        assert_eq!(service.get_credentials("missing", None).await.unwrap(), None);
+
+        std::env::remove_var("A2A_CRED_OAUTH_CLIENT");
+    }
+
+    #[test]
+    fn test_environment_variable_names() {
+        let service = EnvironmentCredentialService::standard();
+        assert_eq!(service.env_var_name("my-scheme"), "A2A_CRED_MY_SCHEME");
+        assert_eq!(service.env_var_name("oauth.client"), "A2A_CRED_OAUTH_CLIENT");
+        assert_eq!(service.env_var_name("Bearer"), "A2A_CRED_BEARER");
+        assert_eq!(service.legacy_env_var_name("oauth.client"), "A2A_CRED_OAUTH.CLIENT");
+    }
+
+    #[tokio::test]
+    async fn test_environment_credential_service_reads_legacy_name() {
+        std::env::set_var("A2A_LEGACY.SCHEME", "legacy-token");
+
+        let service = EnvironmentCredentialService::default();
+        let credential = service.get_credentials("legacy.scheme", None).await.unwrap();
+        assert_eq!(credential, Some("legacy-token".to_string()));
+
+        std::env::set_var("A2A_LEGACY_SCHEME", "current-token");
+        let credential = service.get_credentials("legacy.scheme", None).await.unwrap();
+        assert_eq!(credential, Some("current-token".to_string()));
+
+        std::env::remove_var("A2A_LEGACY.SCHEME");
+        std::env::remove_var("A2A_LEGACY_SCHEME");
+    }
+
     #[tokio::test]
     async fn test_composite_credential_service() {
         let mut memory_store = InMemoryContextCredentialStore::new();
@@ -0,0 +1,195 @@
+//! File-backed credential service
+//!
+//! `FileCredentialService` reads credentials from a JSON or TOML file,
+//! chosen by extension, mapping scheme names to either the credential or a
+//! table with the credential and its expiry:
+//!
+//! ```toml
+//! bearerAuth = "eyJhbGciOi..."
+//!
+//! [apiKey]
+//! value = "k-123"
+//! expires_at = "2026-12-31T00:00:00Z"
+//! ```
+//!
+//! The file is read again whenever its modification time changes, so
+//! credentials rotated by another process are picked up without a restart.
+//! A rewrite that fails to parse keeps the previously loaded credentials.
+
+use crate::a2a::client::auth::credentials::{Credential, CredentialService};
+use crate::a2a::client::client_trait::ClientCallContext;
+use crate::a2a::error::A2AError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum FileCredential {
+    Value(String),
+    Entry {
+        value: String,
+        #[serde(default)]
+        expires_at: Option<DateTime<Utc>>,
+    },
+}
+
+impl From<FileCredential> for Credential {
+    fn from(credential: FileCredential) -> Self {
+        match credential {
+            FileCredential::Value(value) => Credential::new(value),
+            FileCredential::Entry { value, expires_at } => Credential {
+                value,
+                expires_at,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Loaded {
+    modified: Option<SystemTime>,
+    credentials: HashMap<String, Credential>,
+}
+
+/// Credential service reading a JSON or TOML credentials file
+#[derive(Debug)]
+pub struct FileCredentialService {
+    path: PathBuf,
+    loaded: Mutex<Loaded>,
+}
+
+impl FileCredentialService {
+    /// Reads credentials from `path`, which must end in `.json` or `.toml`
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, A2AError> {
+        let service = Self {
+            path: path.as_ref().to_path_buf(),
+            loaded: Mutex::default(),
+        };
+        service.reload_if_changed()?;
+        Ok(service)
+    }
+
+    /// The credentials file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn parse(&self, contents: &str) -> Result<HashMap<String, Credential>, A2AError> {
+        let invalid = |e: &dyn std::fmt::Display| {
+            A2AError::invalid_params(&format!("Invalid credentials file {}: {}", self.path.display(), e))
+        };
+        let credentials: HashMap<String, FileCredential> = match self.path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(contents).map_err(|e| invalid(&e))?,
+            Some("toml") => toml::from_str(contents).map_err(|e| invalid(&e))?,
+            _ => {
+                return Err(A2AError::invalid_params(&format!(
+                    "Unsupported credentials file format: {}",
+                    self.path.display()
+                )))
+            }
+        };
+        Ok(credentials.into_iter().map(|(scheme, credential)| (scheme, credential.into())).collect())
+    }
+
+    fn reload_if_changed(&self) -> Result<(), A2AError> {
+        let read_error = |e: std::io::Error| {
+            A2AError::invalid_params(&format!("Failed to read credentials file {}: {}", self.path.display(), e))
+        };
+        let modified = std::fs::metadata(&self.path).map_err(read_error)?.modified().ok();
+        let mut loaded = self.loaded.lock().unwrap();
+        if loaded.modified.is_some() && loaded.modified == modified {
+            return Ok(());
+        }
+
+        let contents = std::fs::read_to_string(&self.path).map_err(read_error)?;
+        match self.parse(&contents) {
+            Ok(credentials) => {
+                tracing::debug!("Loaded {} credentials from {}", credentials.len(), self.path.display());
+                *loaded = Loaded { modified, credentials };
+                Ok(())
+            }
+            Err(e) if loaded.modified.is_some() => {
+                tracing::warn!("Keeping previously loaded credentials: {}", e);
+                loaded.modified = modified;
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialService for FileCredentialService {
+    async fn get_credentials(
+        &self,
+        scheme_name: &str,
+        context: Option<&ClientCallContext>,
+    ) -> Result<Option<String>, A2AError> {
+        Ok(self.get_credential(scheme_name, context).await?.map(|credential| credential.value))
+    }
+
+    async fn get_credential(
+        &self,
+        scheme_name: &str,
+        _context: Option<&ClientCallContext>,
+    ) -> Result<Option<Credential>, A2AError> {
+        self.reload_if_changed()?;
+        Ok(self.loaded.lock().unwrap().credentials.get(scheme_name).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn write(path: &Path, contents: &str, modified: SystemTime) {
+        std::fs::write(path, contents).unwrap();
+        std::fs::File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reads_toml_and_reloads_on_change() {
+        let path = std::env::temp_dir().join(format!("a2a-credentials-{}.toml", uuid::Uuid::new_v4()));
+        let start = SystemTime::now() - Duration::from_secs(60);
+        write(
+            &path,
+            "bearerAuth = \"token-1\"\n\n[apiKey]\nvalue = \"key-1\"\nexpires_at = \"2030-01-01T00:00:00Z\"\n",
+            start,
+        );
+        let service = FileCredentialService::new(&path).unwrap();
+
+        assert_eq!(service.get_credentials("bearerAuth", None).await.unwrap().as_deref(), Some("token-1"));
+        let api_key = service.get_credential("apiKey", None).await.unwrap().unwrap();
+        assert_eq!(api_key.value, "key-1");
+        assert_eq!(api_key.expires_at, "2030-01-01T00:00:00Z".parse().ok());
+
+        write(&path, "bearerAuth = \"token-2\"\n", start + Duration::from_secs(1));
+        assert_eq!(service.get_credentials("bearerAuth", None).await.unwrap().as_deref(), Some("token-2"));
+        assert_eq!(service.get_credentials("apiKey", None).await.unwrap(), None);
+
+        // A broken rewrite keeps the last good credentials
+        write(&path, "bearerAuth = ", start + Duration::from_secs(2));
+        assert_eq!(service.get_credentials("bearerAuth", None).await.unwrap().as_deref(), Some("token-2"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reads_json_and_rejects_unknown_formats() {
+        let path = std::env::temp_dir().join(format!("a2a-credentials-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&path, r#"{"bearerAuth": "token", "apiKey": {"value": "key"}}"#).unwrap();
+        let service = FileCredentialService::new(&path).unwrap();
+        assert_eq!(service.get_credentials("apiKey", None).await.unwrap().as_deref(), Some("key"));
+        std::fs::remove_file(&path).unwrap();
+
+        let path = std::env::temp_dir().join(format!("a2a-credentials-{}.ini", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "bearerAuth=token").unwrap();
+        assert!(FileCredentialService::new(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -4,6 +4,10 @@
 //! details to requests based on the agent's security schemes,
 //! matching a2a-python's AuthInterceptor.
 
+use crate::a2a::client::auth::cache::CredentialCache;
+use crate::a2a::client::auth::challenge::{
+    parse_www_authenticate, AuthChallenge, APPLIED_REQUIREMENT_KWARG, CHALLENGES_KWARG, REJECTED_REQUIREMENTS_KWARG,
+};
 use crate::a2a::client::auth::credentials::CredentialService;
 use crate::a2a::client::client_trait::ClientCallContext;
 use crate::a2a::client::client_trait::ClientCallInterceptor;
@@ -22,6 +26,8 @@ use std::sync::Arc;
 pub struct AuthInterceptor {
     /// Credential service for retrieving authentication credentials
     credential_service: Arc<dyn CredentialService>,
+    /// Cache of resolved credentials, if enabled
+    cache: Option<CredentialCache>,
 }
 
 impl AuthInterceptor {
@@ -29,8 +35,55 @@ impl AuthInterceptor {
     pub fn new(credential_service: Arc<dyn CredentialService>) -> Self {
         Self {
             credential_service,
+            cache: None,
         }
     }
+
+    /// Cache resolved credentials per scheme instead of resolving them on
+    /// every call
+    pub fn with_credential_cache(mut self, cache: CredentialCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// The credential cache, if enabled
+    pub fn credential_cache(&self) -> Option<&CredentialCache> {
+        self.cache.as_ref()
+    }
+
+    /// Feeds an error of a call sent with `http_kwargs` back to the interceptor
+    ///
+    /// A 401 or 403 from the agent means the credentials of the security
+    /// requirement applied to the call (`APPLIED_REQUIREMENT_KWARG`) were
+    /// rejected, so they are evicted for the call's context and the next
+    /// call resolves fresh ones. Credentials of other schemes and contexts
+    /// are kept. The JSON-RPC transport feeds every HTTP error back through
+    /// `ClientCallInterceptor::on_http_error`. Returns whether credentials
+    /// were evicted.
+    pub fn handle_unauthorized(
+        &self,
+        error: &A2AError,
+        http_kwargs: &HashMap<String, Value>,
+        agent_card: &AgentCard,
+        context: Option<&ClientCallContext>,
+    ) -> bool {
+        let (Some(cache), Some(401 | 403)) = (&self.cache, error.http_status()) else {
+            return false;
+        };
+        let requirement = http_kwargs
+            .get(APPLIED_REQUIREMENT_KWARG)
+            .and_then(Value::as_u64)
+            .and_then(|index| agent_card.security.as_ref()?.get(index as usize));
+        let Some(requirement) = requirement else {
+            return false;
+        };
+        for scheme_name in requirement.keys() {
+            tracing::debug!("Agent rejected credentials of scheme '{}', evicting them", scheme_name);
+            cache.evict(scheme_name, context);
+        }
+        true
+    }
+
     
     /// Create an authentication interceptor with an in-memory credential store
     pub fn with_memory_store() -> (Self, crate::a2a::client::auth::credentials::InMemoryContextCredentialStore) {
@@ -71,11 +124,43 @@ impl ClientCallInterceptor for AuthInterceptor {
             None => return Ok((request_payload, http_kwargs)),
         };
         
+        // Requirement sets the agent already rejected are skipped, and sets
+        // answering its challenges are tried first
+        let rejected: Vec<usize> = http_kwargs
+            .get(REJECTED_REQUIREMENTS_KWARG)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        let challenges: Vec<AuthChallenge> = http_kwargs
+            .get(CHALLENGES_KWARG)
+            .and_then(Value::as_array)
+            .map(|values| values.iter().filter_map(Value::as_str).flat_map(parse_www_authenticate).collect())
+            .unwrap_or_default();
+        if let Some(cache) = &self.cache {
+            for scheme_name in rejected.iter().filter_map(|&index| security.get(index)).flat_map(|r| r.keys()) {
+                cache.evict(scheme_name, context);
+            }
+        }
+        let mut candidates: Vec<usize> = (0..security.len()).filter(|index| !rejected.contains(index)).collect();
+        if !challenges.is_empty() {
+            candidates.sort_by_key(|&index| {
+                !security[index].keys().any(|scheme_name| {
+                    security_schemes
+                        .get(scheme_name)
+                        .is_some_and(|scheme| challenges.iter().any(|challenge| challenge.matches(scheme)))
+                })
+            });
+        }
+
         // Try each security requirement until we find one with available credentials
-        for requirement in security {
+        for index in candidates {
+            let requirement = &security[index];
             for (scheme_name, _scopes) in requirement {
                 // Get credentials for this scheme
-                let credential = match self.credential_service.get_credentials(scheme_name, context).await {
+                let resolved = match &self.cache {
+                    Some(cache) => cache.get(&self.credential_service, scheme_name, context).await,
+                    None => self.credential_service.get_credentials(scheme_name, context).await,
+                };
+                let credential = match resolved {
                     Ok(Some(cred)) => cred,
                     Ok(None) => continue, // No credentials available for this scheme
                     Err(e) => {
@@ -93,6 +178,7 @@ impl ClientCallInterceptor for AuthInterceptor {
                 
                 // Apply authentication based on scheme type
                 if self.apply_authentication(&mut http_kwargs, scheme_name, &credential, scheme_def).await? {
+                    http_kwargs.insert(APPLIED_REQUIREMENT_KWARG.to_string(), Value::from(index));
                     // Successfully applied authentication, return early
                     tracing::debug!(
                         "Applied authentication for scheme '{}' (method: {})",
@@ -108,6 +194,17 @@ impl ClientCallInterceptor for AuthInterceptor {
         tracing::debug!("No authentication applied for method: {}", method_name);
         Ok((request_payload, http_kwargs))
     }
+
+    fn on_http_error(
+        &self,
+        _method_name: &str,
+        error: &A2AError,
+        http_kwargs: &HashMap<String, Value>,
+        agent_card: &AgentCard,
+        context: Option<&ClientCallContext>,
+    ) {
+        self.handle_unauthorized(error, http_kwargs, agent_card, context);
+    }
 }
 
 impl AuthInterceptor {
@@ -319,6 +416,107 @@ mod tests {
         assert_eq!(api_key_header, "test-api-key");
     }
     
+    #[tokio::test]
+    async fn test_cached_credentials_are_dropped_on_401() {
+        let mut store = InMemoryContextCredentialStore::new();
+        store.add_credential("bearerAuth", "test-jwt-token");
+        let interceptor = AuthInterceptor::new(Arc::new(store)).with_credential_cache(CredentialCache::new());
+
+        let card = create_test_agent_card();
+        let (_, http_kwargs) = interceptor
+            .intercept("message/send", serde_json::json!({}), HashMap::new(), &card, None)
+            .await
+            .unwrap();
+        let cache = interceptor.credential_cache().unwrap();
+        assert!(cache.expires_at("bearerAuth", None).is_some());
+
+        let server_error = A2AError::http_error(500, "boom".to_string());
+        assert!(!interceptor.handle_unauthorized(&server_error, &http_kwargs, &card, None));
+        let unauthorized = A2AError::http_error(401, "Unauthorized".to_string());
+        assert!(interceptor.handle_unauthorized(&unauthorized, &http_kwargs, &card, None));
+        assert!(cache.expires_at("bearerAuth", None).is_none());
+
+        let (_, http_kwargs) = interceptor
+            .intercept("message/send", serde_json::json!({}), HashMap::new(), &card, None)
+            .await
+            .unwrap();
+        let forbidden = A2AError::http_error(403, "Forbidden".to_string());
+        interceptor.on_http_error("message/send", &forbidden, &http_kwargs, &card, None);
+        assert!(cache.expires_at("bearerAuth", None).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_401_evicts_only_the_applied_requirement_of_the_call_context() {
+        let mut card = create_test_agent_card();
+        card.security = Some(vec![
+            HashMap::from([("bearerAuth".to_string(), vec![])]),
+            HashMap::from([("apiKey".to_string(), vec![])]),
+        ]);
+        let mut store = InMemoryContextCredentialStore::new();
+        store.add_credential("bearerAuth", "test-jwt-token");
+        store.add_credential("apiKey", "test-api-key");
+        let interceptor = AuthInterceptor::new(Arc::new(store)).with_credential_cache(CredentialCache::new());
+        let cache = interceptor.credential_cache().unwrap();
+        let alice = ClientCallContext::new().with_metadata("session", "alice");
+        let bob = ClientCallContext::new().with_metadata("session", "bob");
+
+        let (_, alice_kwargs) = interceptor
+            .intercept("message/send", serde_json::json!({}), HashMap::new(), &card, Some(&alice))
+            .await
+            .unwrap();
+        interceptor
+            .intercept("message/send", serde_json::json!({}), HashMap::new(), &card, Some(&bob))
+            .await
+            .unwrap();
+        let retry = HashMap::from([(REJECTED_REQUIREMENTS_KWARG.to_string(), serde_json::json!([0]))]);
+        interceptor
+            .intercept("message/send", serde_json::json!({}), retry, &card, Some(&alice))
+            .await
+            .unwrap();
+        assert!(cache.expires_at("apiKey", Some(&alice)).is_some());
+
+        let unauthorized = A2AError::http_error(401, "Unauthorized".to_string());
+        interceptor.on_http_error("message/send", &unauthorized, &alice_kwargs, &card, Some(&alice));
+        assert!(cache.expires_at("bearerAuth", Some(&alice)).is_none());
+        assert!(cache.expires_at("apiKey", Some(&alice)).is_some());
+        assert!(cache.expires_at("bearerAuth", Some(&bob)).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_requirements_are_skipped_and_challenges_preferred() {
+        let mut card = create_test_agent_card();
+        card.security = Some(vec![
+            HashMap::from([("bearerAuth".to_string(), vec![])]),
+            HashMap::from([("apiKey".to_string(), vec![])]),
+        ]);
+        let mut store = InMemoryContextCredentialStore::new();
+        store.add_credential("bearerAuth", "test-jwt-token");
+        store.add_credential("apiKey", "test-api-key");
+        let interceptor = AuthInterceptor::new(Arc::new(store));
+
+        let (_, http_kwargs) = interceptor
+            .intercept("tasks/get", serde_json::json!({}), HashMap::new(), &card, None)
+            .await
+            .unwrap();
+        assert_eq!(http_kwargs[APPLIED_REQUIREMENT_KWARG], 0);
+
+        let retry = HashMap::from([(REJECTED_REQUIREMENTS_KWARG.to_string(), serde_json::json!([0]))]);
+        let (_, http_kwargs) = interceptor
+            .intercept("tasks/get", serde_json::json!({}), retry, &card, None)
+            .await
+            .unwrap();
+        assert_eq!(http_kwargs[APPLIED_REQUIREMENT_KWARG], 1);
+        assert_eq!(http_kwargs["headers"]["X-API-Key"], "test-api-key");
+        assert!(http_kwargs["headers"].get("Authorization").is_none());
+
+        let challenged = HashMap::from([(CHALLENGES_KWARG.to_string(), serde_json::json!(["ApiKey realm=\"agents\""]))]);
+        let (_, http_kwargs) = interceptor
+            .intercept("tasks/get", serde_json::json!({}), challenged, &card, None)
+            .await
+            .unwrap();
+        assert_eq!(http_kwargs[APPLIED_REQUIREMENT_KWARG], 1);
+    }
+
     #[tokio::test]
     async fn test_no_authentication_when_no_credentials() {
         let store = InMemoryContextCredentialStore::new(); // Empty store
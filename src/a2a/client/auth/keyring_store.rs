@@ -0,0 +1,75 @@
+//! OS keyring credential service
+//!
+//! `KeyringCredentialService` looks credentials up in the platform keyring
+//! (macOS Keychain, Windows Credential Manager, Linux kernel keyutils)
+//! under a service name, with the scheme name as the account. Keyring
+//! access is blocking, so lookups run on the blocking thread pool.
+
+use crate::a2a::client::auth::credentials::CredentialService;
+use crate::a2a::client::client_trait::ClientCallContext;
+use crate::a2a::error::A2AError;
+use async_trait::async_trait;
+
+/// Credential service reading the OS keyring
+#[derive(Debug, Clone)]
+pub struct KeyringCredentialService {
+    service: String,
+}
+
+impl KeyringCredentialService {
+    /// Reads the entries of `service`, one per security scheme
+    pub fn new(service: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+        }
+    }
+
+    /// Stores the credential of `scheme_name`
+    pub fn store(&self, scheme_name: &str, credential: &str) -> Result<(), A2AError> {
+        self.entry(scheme_name)?
+            .set_password(credential)
+            .map_err(|e| self.error(scheme_name, e))
+    }
+
+    fn entry(&self, scheme_name: &str) -> Result<keyring::Entry, A2AError> {
+        keyring::Entry::new(&self.service, scheme_name).map_err(|e| self.error(scheme_name, e))
+    }
+
+    fn error(&self, scheme_name: &str, e: keyring::Error) -> A2AError {
+        A2AError::internal(&format!("Keyring entry {}/{}: {}", self.service, scheme_name, e))
+    }
+
+    fn lookup(&self, scheme_name: &str) -> Result<Option<String>, A2AError> {
+        match self.entry(scheme_name)?.get_password() {
+            Ok(credential) => Ok(Some(credential)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(self.error(scheme_name, e)),
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialService for KeyringCredentialService {
+    async fn get_credentials(
+        &self,
+        scheme_name: &str,
+        _context: Option<&ClientCallContext>,
+    ) -> Result<Option<String>, A2AError> {
+        let (service, scheme_name) = (self.clone(), scheme_name.to_string());
+        tokio::task::spawn_blocking(move || service.lookup(&scheme_name))
+            .await
+            .map_err(|e| A2AError::internal(&format!("Keyring lookup failed: {}", e)))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_missing_entry_is_no_credential() {
+        keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+        let service = KeyringCredentialService::new("a2a-rust-test");
+        assert_eq!(service.get_credentials("bearerAuth", None).await.unwrap(), None);
+    }
+}
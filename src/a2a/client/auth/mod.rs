@@ -3,15 +3,28 @@
 //! This module contains client-side authentication functionality
 //! matching a2a-python/src/a2a/client/auth/
 
+pub mod cache;
+pub mod challenge;
 pub mod credentials;
+#[cfg(feature = "client")]
+pub mod file_store;
 pub mod interceptor;
+#[cfg(feature = "keyring")]
+pub mod keyring_store;
 
 // Re-export auth types
+pub use cache::CredentialCache;
+pub use challenge::{parse_www_authenticate, AuthChallenge};
 pub use credentials::{
+    Credential,
     CredentialService,
     InMemoryContextCredentialStore,
     EnvironmentCredentialService,
     CompositeCredentialService,
 };
+#[cfg(feature = "client")]
+pub use file_store::FileCredentialService;
+#[cfg(feature = "keyring")]
+pub use keyring_store::KeyringCredentialService;
 
 pub use interceptor::AuthInterceptor;
@@ -3,6 +3,7 @@
 //! This module provides functionality to resolve and fetch agent cards,
 //! mirroring the functionality of a2a-python's card resolver.
 
+use crate::a2a::client::errors::ClientError;
 use crate::a2a::models::*;
 use crate::a2a::error::A2AError;
 use reqwest;
@@ -33,7 +34,7 @@ impl A2ACardResolver {
         let response = client.get(&card_url)
             .send()
             .await
-            .map_err(|e| A2AError::transport_error(format!("Failed to fetch agent card: {}", e)))?;
+            .map_err(|e| A2AError::from(ClientError::from_reqwest("Failed to fetch agent card", &e)))?;
         
         if !response.status().is_success() {
             return Err(A2AError::http_error(
@@ -45,10 +46,10 @@ impl A2ACardResolver {
         let card_json: Value = response
             .json()
             .await
-            .map_err(|e| A2AError::json_error(format!("Failed to parse agent card JSON: {}", e)))?;
+            .map_err(|e| A2AError::from(ClientError::Protocol(format!("Failed to parse agent card JSON: {}", e))))?;
         
         serde_json::from_value(card_json)
-            .map_err(|e| A2AError::json_error(format!("Failed to deserialize agent card: {}", e)))
+            .map_err(|e| A2AError::from(ClientError::Protocol(format!("Failed to deserialize agent card: {}", e))))
     }
     
     /// Get agent card with optional relative path and additional HTTP kwargs
@@ -104,7 +105,7 @@ impl A2ACardResolver {
         let response = request
             .send()
             .await
-            .map_err(|e| A2AError::transport_error(format!("Failed to fetch agent card: {}", e)))?;
+            .map_err(|e| A2AError::from(ClientError::from_reqwest("Failed to fetch agent card", &e)))?;
         
         if !response.status().is_success() {
             return Err(A2AError::http_error(
@@ -116,10 +117,10 @@ impl A2ACardResolver {
         let card_json: Value = response
             .json()
             .await
-            .map_err(|e| A2AError::json_error(format!("Failed to parse agent card JSON: {}", e)))?;
+            .map_err(|e| A2AError::from(ClientError::Protocol(format!("Failed to parse agent card JSON: {}", e))))?;
         
         serde_json::from_value(card_json)
-            .map_err(|e| A2AError::json_error(format!("Failed to deserialize agent card: {}", e)))
+            .map_err(|e| A2AError::from(ClientError::Protocol(format!("Failed to deserialize agent card: {}", e))))
     }
 }
 
@@ -16,12 +16,32 @@ pub enum TaskUpdateEvent {
     Status(TaskStatusUpdateEvent),
     Artifact(TaskArtifactUpdateEvent),
 }
+
+impl TaskUpdateEvent {
+    /// The ID of the task the event belongs to
+    pub fn task_id(&self) -> &str {
+        match self {
+            TaskUpdateEvent::Status(update) => &update.task_id,
+            TaskUpdateEvent::Artifact(update) => &update.task_id,
+        }
+    }
+
+    /// The sequence number the server assigned to the event, if any
+    pub fn sequence(&self) -> Option<u64> {
+        match self {
+            TaskUpdateEvent::Status(update) => event_sequence(update),
+            TaskUpdateEvent::Artifact(update) => event_sequence(update),
+        }
+    }
+}
+use crate::a2a::utils::sequence::{event_sequence, SequenceCheck, SequenceTracker};
 use async_stream::stream;
 use async_trait::async_trait;
 use futures::{Stream, StreamExt};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::pin::Pin;
+use std::sync::{Arc, Mutex};
 
 /// Type alias for client events - either a task with optional update, or a message
 pub type ClientEvent = (Task, Option<TaskUpdateEvent>);
@@ -86,6 +106,20 @@ pub trait ClientCallInterceptor: Send + Sync {
         agent_card: &AgentCard,
         context: Option<&ClientCallContext>,
     ) -> Result<(Value, HashMap<String, Value>), crate::a2a::error::A2AError>;
+
+    /// Called when the agent answers an intercepted call with an HTTP error
+    ///
+    /// `http_kwargs` are the arguments the call was sent with, after all
+    /// interceptors ran. Does nothing by default.
+    fn on_http_error(
+        &self,
+        _method_name: &str,
+        _error: &crate::a2a::error::A2AError,
+        _http_kwargs: &HashMap<String, Value>,
+        _agent_card: &AgentCard,
+        _context: Option<&ClientCallContext>,
+    ) {
+    }
 }
 
 /// Main client trait that defines the interface for interacting with A2A agents
@@ -144,6 +178,12 @@ pub trait Client: Send + Sync {
         extensions: Option<Vec<String>>,
     ) -> Pin<Box<dyn Stream<Item = Result<ClientEvent, crate::a2a::error::A2AError>> + Send + 'a>>;
     
+    /// The resumption id of the last streamed event received for a task,
+    /// which `resubscribe` resumes after
+    fn last_event_id(&self, _task_id: &str) -> Option<String> {
+        None
+    }
+    
     /// Retrieve the agent's card
     async fn get_card(
         &self,
@@ -174,6 +214,7 @@ pub struct BaseClient {
     consumers: Vec<Consumer>,
     #[allow(dead_code)] // TODO: Implement middleware functionality
     middleware: Vec<Box<dyn ClientCallInterceptor>>,
+    sequence_tracker: Arc<Mutex<SequenceTracker>>,
 }
 
 impl BaseClient {
@@ -191,6 +232,7 @@ impl BaseClient {
             transport,
             consumers,
             middleware,
+            sequence_tracker: Arc::default(),
         }
     }
     
@@ -208,6 +250,44 @@ impl BaseClient {
     pub fn transport(&self) -> &dyn ClientTransport {
         &*self.transport
     }
+    
+    /// Checks a streamed update against the sequence numbers seen so far
+    ///
+    /// Returns `Ok(false)` for an update that was already received, e.g.
+    /// replayed after a reconnect, and an error if updates are missing.
+    /// Always accepts the update if validation is disabled.
+    fn check_sequence(&self, update: Option<&TaskUpdateEvent>) -> Result<bool, crate::a2a::error::A2AError> {
+        let Some(update) = update.filter(|_| self.config.validate_event_sequence) else {
+            return Ok(true);
+        };
+        let check = self.sequence_tracker.lock().unwrap().observe(update.task_id(), update.sequence());
+        match check {
+            SequenceCheck::Duplicate => Ok(false),
+            SequenceCheck::Gap { expected, received } => Err(crate::a2a::error::A2AError::invalid_response(&format!(
+                "Missing events for task {}: expected sequence {}, received {}",
+                update.task_id(),
+                expected,
+                received
+            ))),
+            SequenceCheck::InOrder | SequenceCheck::Unsequenced => Ok(true),
+        }
+    }
+    
+    /// Drops or fails streamed items according to `check_sequence`
+    fn sequence_filter<T>(
+        &self,
+        item: Result<T, crate::a2a::error::A2AError>,
+        update: fn(&T) -> Option<&TaskUpdateEvent>,
+    ) -> Option<Result<T, crate::a2a::error::A2AError>> {
+        match item {
+            Ok(value) => match self.check_sequence(update(&value)) {
+                Ok(true) => Some(Ok(value)),
+                Ok(false) => None,
+                Err(e) => Some(Err(e)),
+            },
+            Err(e) => Some(Err(e)),
+        }
+    }
 }
 
 #[async_trait]
@@ -274,7 +354,13 @@ impl Client for BaseClient {
                             Err(e) => Err(e),
                         }
                     });
-                    Box::pin(mapped_stream)
+                    let checked_stream = mapped_stream.filter_map(move |item| {
+                        futures::future::ready(self.sequence_filter(item, |event| match event {
+                            ClientEventOrMessage::Event((_, update)) => update.as_ref(),
+                            ClientEventOrMessage::Message(_) => None,
+                        }))
+                    });
+                    Box::pin(checked_stream)
                 }
                 Err(_) => {
                     // Fall back to non-streaming if streaming fails
@@ -392,13 +478,19 @@ impl Client for BaseClient {
         }
         
         match self.transport.resubscribe(request, context, extensions).await {
-            Ok(stream) => stream,
+            Ok(stream) => Box::pin(stream.filter_map(move |item| {
+                futures::future::ready(self.sequence_filter(item, |(_, update)| update.as_ref()))
+            })),
             Err(e) => Box::pin(stream! {
                 yield Err(e);
             }),
         }
     }
     
+    fn last_event_id(&self, task_id: &str) -> Option<String> {
+        self.transport.last_event_id(task_id)
+    }
+    
     async fn get_card(
         &self,
         context: Option<&ClientCallContext>,
@@ -503,4 +595,12 @@ pub trait ClientTransport: Send + Sync {
     
     /// Close the transport
     async fn close(&self) -> Result<(), crate::a2a::error::A2AError>;
+
+    /// The resumption id of the last streamed event received for a task
+    ///
+    /// Transports that track it fill in `from_event_id` on `resubscribe`
+    /// when the request leaves it unset.
+    fn last_event_id(&self, _task_id: &str) -> Option<String> {
+        None
+    }
 }
@@ -0,0 +1,536 @@
+//! Waiting for tasks to finish
+//!
+//! Most callers send a message and want the finished task back.
+//! `ClientExt::send_and_wait` does this in one call. It sends the message,
+//! follows the task to a terminal state and returns the final task with its
+//! artifacts assembled.
+//!
+//! - With `WaitStrategy::Stream`, the events of the response are applied to
+//!   the task as they arrive. Artifact chunks sent with `append` are joined.
+//!   If the stream ends before the task does, polling takes over.
+//! - With `WaitStrategy::Poll`, the response is only used to learn the task
+//!   id. The task is then fetched with `tasks/get`, with exponential backoff
+//!   between attempts.
+//!
+//! `ClientExt::wait_for_task` polls a task the caller already knows the id of.
+//! When a polled task carries a hint under `POLL_AFTER_METADATA_KEY`, as
+//! added by servers using `PollHintRequestHandler`, the next poll waits that
+//! long instead of the backoff delay.
+//!
+//! Tasks that do not complete end as a typed `WaitError`, which carries the
+//! last known task. This covers the failed, canceled and rejected states, and
+//! tasks waiting for input or authentication.
+//!
+//! ```ignore
+//! let task = client.send_and_wait(MessageSendParams::new(message), WaitOptions::new()).await?;
+//! ```
+
+use crate::a2a::client::client_trait::{Client, ClientEventOrMessage, TaskUpdateEvent};
+use crate::a2a::core_types::{Message, PartRoot, TaskState};
+use crate::a2a::error::A2AError;
+use crate::a2a::models::*;
+use crate::a2a::utils::constants::POLL_AFTER_METADATA_KEY;
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::time::Duration;
+use thiserror::Error;
+
+/// How `send_and_wait` follows a task
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WaitStrategy {
+    /// Consume the response stream, polling if it ends early
+    #[default]
+    Stream,
+    /// Poll `tasks/get` once the task is known
+    Poll,
+}
+
+/// Options of `send_and_wait`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaitOptions {
+    pub strategy: WaitStrategy,
+    /// Delay before the first poll
+    pub poll_interval: Duration,
+    /// Longest delay between polls; the delay doubles after every poll
+    pub max_poll_interval: Duration,
+    /// Time allowed for the whole exchange; `None` waits indefinitely
+    pub timeout: Option<Duration>,
+    /// Whether the poll interval hints of the server replace the backoff;
+    /// hints are kept between `poll_interval` and `max_poll_interval`
+    pub poll_hints: bool,
+}
+
+impl WaitOptions {
+    /// Streams the response, without a timeout
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Polls `tasks/get` instead of consuming the stream
+    pub fn polling() -> Self {
+        Self {
+            strategy: WaitStrategy::Poll,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the first and the longest delay between polls
+    pub fn with_poll_interval(mut self, initial: Duration, max: Duration) -> Self {
+        self.poll_interval = initial;
+        self.max_poll_interval = max.max(initial);
+        self
+    }
+
+    /// Gives up after `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets whether the poll interval hints of the server are honored
+    pub fn with_poll_hints(mut self, poll_hints: bool) -> Self {
+        self.poll_hints = poll_hints;
+        self
+    }
+}
+
+impl Default for WaitOptions {
+    fn default() -> Self {
+        Self {
+            strategy: WaitStrategy::Stream,
+            poll_interval: Duration::from_millis(500),
+            max_poll_interval: Duration::from_secs(10),
+            timeout: None,
+            poll_hints: true,
+        }
+    }
+}
+
+/// Why a task did not complete
+#[derive(Error, Debug, Clone)]
+pub enum WaitError {
+    #[error("Task {} failed{}", .0.id, reason(.0))]
+    Failed(Box<Task>),
+
+    #[error("Task {} was canceled{}", .0.id, reason(.0))]
+    Canceled(Box<Task>),
+
+    #[error("Task {} was rejected{}", .0.id, reason(.0))]
+    Rejected(Box<Task>),
+
+    #[error("Task {} requires input{}", .0.id, reason(.0))]
+    InputRequired(Box<Task>),
+
+    #[error("Task {} requires authentication{}", .0.id, reason(.0))]
+    AuthRequired(Box<Task>),
+
+    /// The agent answered with a message instead of creating a task
+    #[error("The agent replied with a message instead of a task")]
+    Message(Box<Message>),
+
+    /// The timeout expired; carries the last task received, if any
+    #[error("Timed out waiting for the task to finish")]
+    TimedOut(Option<Box<Task>>),
+
+    #[error("The agent returned neither a task nor a message")]
+    NoTask,
+
+    #[error(transparent)]
+    Client(#[from] A2AError),
+}
+
+impl WaitError {
+    /// The last known state of the task, if one was created
+    pub fn task(&self) -> Option<&Task> {
+        match self {
+            WaitError::Failed(task)
+            | WaitError::Canceled(task)
+            | WaitError::Rejected(task)
+            | WaitError::InputRequired(task)
+            | WaitError::AuthRequired(task) => Some(task),
+            WaitError::TimedOut(task) => task.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// The text of the status message of a task, for error messages
+fn reason(task: &Task) -> String {
+    let text: Vec<&str> = task
+        .status
+        .message
+        .iter()
+        .flat_map(|message| message.parts.iter())
+        .filter_map(|part| match part.root() {
+            PartRoot::Text(text) => Some(text.text.as_str()),
+            _ => None,
+        })
+        .collect();
+    if text.is_empty() {
+        String::new()
+    } else {
+        format!(": {}", text.join(" "))
+    }
+}
+
+/// The delay the server suggests before polling `task` again
+pub fn poll_hint(task: &Task) -> Option<Duration> {
+    let millis = task.metadata.as_ref()?.get(POLL_AFTER_METADATA_KEY)?.as_u64()?;
+    Some(Duration::from_millis(millis))
+}
+
+/// Whether the task stops making progress without the caller
+fn settled(state: &TaskState) -> bool {
+    state.is_terminal() || matches!(state, TaskState::InputRequired | TaskState::AuthRequired)
+}
+
+/// The outcome of a settled task
+fn outcome(task: Task) -> Result<Task, WaitError> {
+    let task = Box::new(task);
+    match task.status.state {
+        TaskState::Failed => Err(WaitError::Failed(task)),
+        TaskState::Canceled => Err(WaitError::Canceled(task)),
+        TaskState::Rejected => Err(WaitError::Rejected(task)),
+        TaskState::InputRequired => Err(WaitError::InputRequired(task)),
+        TaskState::AuthRequired => Err(WaitError::AuthRequired(task)),
+        _ => Ok(*task),
+    }
+}
+
+/// Applies a streamed update to the task, joining appended artifact chunks
+fn apply_update(task: &mut Task, update: TaskUpdateEvent) {
+    match update {
+        TaskUpdateEvent::Status(update) => task.status = update.status,
+        TaskUpdateEvent::Artifact(update) => {
+            let artifacts = task.artifacts.get_or_insert_with(Vec::new);
+            let existing = artifacts
+                .iter_mut()
+                .find(|artifact| artifact.artifact_id == update.artifact.artifact_id);
+            match existing {
+                Some(artifact) if update.append == Some(true) => artifact.parts.extend(update.artifact.parts),
+                Some(artifact) => *artifact = update.artifact,
+                None => artifacts.push(update.artifact),
+            }
+        }
+    }
+}
+
+/// Convenience calls built on `Client`
+#[async_trait]
+pub trait ClientExt {
+    /// Sends a message and waits until its task reaches a terminal state
+    ///
+    /// The message and request metadata are taken from `params`. The send
+    /// configuration comes from the client's own configuration. Returns the
+    /// completed task, or the `WaitError` describing why it did not complete.
+    async fn send_and_wait(&self, params: MessageSendParams, options: WaitOptions) -> Result<Task, WaitError>;
+
+    /// Polls the task `task_id` until it reaches a terminal state
+    ///
+    /// The task is fetched right away, then as `send_and_wait` polls it.
+    /// `options.strategy` is ignored.
+    async fn wait_for_task(&self, task_id: String, options: WaitOptions) -> Result<Task, WaitError>;
+}
+
+#[async_trait]
+impl<C: Client + ?Sized> ClientExt for C {
+    async fn send_and_wait(&self, params: MessageSendParams, options: WaitOptions) -> Result<Task, WaitError> {
+        let mut latest = None;
+        let result = {
+            let wait = follow(self, params, &options, &mut latest);
+            match options.timeout {
+                Some(timeout) => tokio::time::timeout(timeout, wait).await.ok(),
+                None => Some(wait.await),
+            }
+        };
+        result.unwrap_or_else(|| Err(WaitError::TimedOut(latest.map(Box::new))))
+    }
+
+    async fn wait_for_task(&self, task_id: String, options: WaitOptions) -> Result<Task, WaitError> {
+        let mut latest = None;
+        let result = {
+            let wait = async {
+                let task = self.get_task(TaskQueryParams::new(task_id), None, None).await?;
+                latest = Some(task.clone());
+                poll(self, task, &options, &mut latest).await
+            };
+            match options.timeout {
+                Some(timeout) => tokio::time::timeout(timeout, wait).await.ok(),
+                None => Some(wait.await),
+            }
+        };
+        result.unwrap_or_else(|| Err(WaitError::TimedOut(latest.map(Box::new))))
+    }
+}
+
+/// Follows the task of `params` until it settles, keeping `latest` current
+async fn follow<C: Client + ?Sized>(
+    client: &C,
+    params: MessageSendParams,
+    options: &WaitOptions,
+    latest: &mut Option<Task>,
+) -> Result<Task, WaitError> {
+    let mut reply = None;
+    {
+        let mut events = client.send_message(params.message, None, params.metadata, None).await;
+        while let Some(event) = events.next().await {
+            match event? {
+                ClientEventOrMessage::Event((task, None)) => *latest = Some(task),
+                ClientEventOrMessage::Event((task, Some(update))) => apply_update(latest.get_or_insert(task), update),
+                ClientEventOrMessage::Message(message) => reply = Some(message),
+            }
+            match latest {
+                Some(task) if settled(&task.status.state) => break,
+                Some(_) if options.strategy == WaitStrategy::Poll => break,
+                _ => {}
+            }
+        }
+    }
+
+    let task = match (latest.clone(), reply) {
+        (Some(task), _) => task,
+        (None, Some(message)) => return Err(WaitError::Message(Box::new(message))),
+        (None, None) => return Err(WaitError::NoTask),
+    };
+    poll(client, task, options, latest).await
+}
+
+/// Polls `task` until it settles, keeping `latest` current
+async fn poll<C: Client + ?Sized>(
+    client: &C,
+    mut task: Task,
+    options: &WaitOptions,
+    latest: &mut Option<Task>,
+) -> Result<Task, WaitError> {
+    let hint = |task: &Task| {
+        poll_hint(task)
+            .filter(|_| options.poll_hints)
+            .map(|hint| hint.clamp(options.poll_interval, options.max_poll_interval))
+    };
+    let mut backoff = options.poll_interval;
+    let mut delay = hint(&task).unwrap_or(backoff);
+    while !settled(&task.status.state) {
+        tokio::time::sleep(delay).await;
+        backoff = (backoff * 2).min(options.max_poll_interval);
+        task = client.get_task(TaskQueryParams::new(task.id.clone()), None, None).await?;
+        *latest = Some(task.clone());
+        delay = hint(&task).unwrap_or(backoff);
+    }
+    outcome(task)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::client::client_trait::{ClientCallContext, ClientCallInterceptor, ClientEvent, Consumer};
+    use crate::a2a::core_types::{Part, Role, TaskStatus};
+    use futures::Stream;
+    use std::collections::{HashMap, VecDeque};
+    use std::pin::Pin;
+    use std::sync::Mutex;
+
+    /// Streams `events` in response to a message and answers polls from `polls`
+    struct ScriptedClient {
+        events: Vec<ClientEventOrMessage>,
+        polls: Mutex<VecDeque<Task>>,
+    }
+
+    #[async_trait]
+    impl Client for ScriptedClient {
+        async fn send_message<'life0, 'life1>(
+            &'life0 self,
+            _request: Message,
+            _context: Option<&'life1 ClientCallContext>,
+            _request_metadata: Option<HashMap<String, serde_json::Value>>,
+            _extensions: Option<Vec<String>>,
+        ) -> Pin<Box<dyn Stream<Item = Result<ClientEventOrMessage, A2AError>> + Send + 'life0>>
+        where
+            'life1: 'life0,
+        {
+            Box::pin(futures::stream::iter(self.events.clone().into_iter().map(Ok)))
+        }
+
+        async fn get_task(&self, _: TaskQueryParams, _: Option<&ClientCallContext>, _: Option<Vec<String>>) -> Result<Task, A2AError> {
+            let mut polls = self.polls.lock().unwrap();
+            match polls.len() {
+                0 => Err(A2AError::task_not_found("task-1")),
+                1 => Ok(polls[0].clone()),
+                _ => Ok(polls.pop_front().unwrap()),
+            }
+        }
+
+        async fn cancel_task(&self, _: TaskIdParams, _: Option<&ClientCallContext>, _: Option<Vec<String>>) -> Result<Task, A2AError> {
+            Err(A2AError::unsupported_operation("cancel_task"))
+        }
+
+        async fn set_task_callback(
+            &self,
+            _: TaskPushNotificationConfig,
+            _: Option<&ClientCallContext>,
+            _: Option<Vec<String>>,
+        ) -> Result<TaskPushNotificationConfig, A2AError> {
+            Err(A2AError::unsupported_operation("set_task_callback"))
+        }
+
+        async fn get_task_callback(
+            &self,
+            _: GetTaskPushNotificationConfigParams,
+            _: Option<&ClientCallContext>,
+            _: Option<Vec<String>>,
+        ) -> Result<TaskPushNotificationConfig, A2AError> {
+            Err(A2AError::unsupported_operation("get_task_callback"))
+        }
+
+        async fn resubscribe<'a>(
+            &'a self,
+            _: TaskIdParams,
+            _: Option<&ClientCallContext>,
+            _: Option<Vec<String>>,
+        ) -> Pin<Box<dyn Stream<Item = Result<ClientEvent, A2AError>> + Send + 'a>> {
+            Box::pin(futures::stream::empty())
+        }
+
+        async fn get_card(&self, _: Option<&ClientCallContext>, _: Option<Vec<String>>) -> Result<AgentCard, A2AError> {
+            Err(A2AError::unsupported_operation("get_card"))
+        }
+
+        async fn add_event_consumer(&self, _consumer: Consumer) {}
+
+        async fn add_request_middleware(&self, _middleware: Box<dyn ClientCallInterceptor>) {}
+
+        async fn consume(&self, _event: Option<ClientEventOrMessage>, _card: &AgentCard) -> Result<(), A2AError> {
+            Ok(())
+        }
+    }
+
+    fn task(state: TaskState) -> Task {
+        Task::new("ctx".to_string(), TaskStatus::new(state)).with_task_id("task-1".to_string())
+    }
+
+    fn update(update: TaskUpdateEvent) -> ClientEventOrMessage {
+        ClientEventOrMessage::Event((task(TaskState::Working), Some(update)))
+    }
+
+    fn chunk(text: &str, append: bool) -> TaskUpdateEvent {
+        let mut artifact = Artifact::new(vec![Part::text(text.to_string())]);
+        artifact.artifact_id = "answer".to_string();
+        TaskUpdateEvent::Artifact(
+            TaskArtifactUpdateEvent::new("task-1".to_string(), "ctx".to_string(), artifact).with_append(append),
+        )
+    }
+
+    fn status(state: TaskState) -> TaskUpdateEvent {
+        TaskUpdateEvent::Status(TaskStatusUpdateEvent::new(
+            "task-1".to_string(),
+            "ctx".to_string(),
+            TaskStatus::new(state),
+            true,
+        ))
+    }
+
+    fn params() -> MessageSendParams {
+        MessageSendParams::new(Message::new(Role::User, vec![Part::text("hi".to_string())]))
+    }
+
+    fn quick_polls() -> WaitOptions {
+        WaitOptions::polling().with_poll_interval(Duration::from_millis(1), Duration::from_millis(2))
+    }
+
+    #[tokio::test]
+    async fn test_streamed_artifact_chunks_are_assembled() {
+        let client = ScriptedClient {
+            events: vec![
+                ClientEventOrMessage::Event((task(TaskState::Working), None)),
+                update(chunk("Hello, ", false)),
+                update(chunk("world", true)),
+                update(status(TaskState::Completed)),
+            ],
+            polls: Mutex::new(VecDeque::new()),
+        };
+        let task = client.send_and_wait(params(), WaitOptions::new()).await.unwrap();
+        assert_eq!(task.status.state, TaskState::Completed);
+        let artifacts = task.artifacts.unwrap();
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].parts.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_polling_until_the_task_fails() {
+        let mut failed = task(TaskState::Failed);
+        failed.status = failed
+            .status
+            .with_message(Message::new(Role::Agent, vec![Part::text("model overloaded".to_string())]));
+        let client = ScriptedClient {
+            events: vec![ClientEventOrMessage::Event((task(TaskState::Submitted), None))],
+            polls: Mutex::new(VecDeque::from([task(TaskState::Working), failed])),
+        };
+        let err = client.send_and_wait(params(), quick_polls()).await.unwrap_err();
+        assert!(matches!(err, WaitError::Failed(_)));
+        assert_eq!(err.to_string(), "Task task-1 failed: model overloaded");
+        assert_eq!(err.task().unwrap().id, "task-1");
+    }
+
+    #[tokio::test]
+    async fn test_timeouts_and_message_replies() {
+        let client = ScriptedClient {
+            events: vec![ClientEventOrMessage::Event((task(TaskState::Working), None))],
+            polls: Mutex::new(VecDeque::from([task(TaskState::Working)])),
+        };
+        let err = client
+            .send_and_wait(params(), quick_polls().with_timeout(Duration::from_millis(20)))
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, WaitError::TimedOut(Some(task)) if task.status.state == TaskState::Working));
+
+        let client = ScriptedClient {
+            events: vec![ClientEventOrMessage::Message(Message::new(Role::Agent, vec![]))],
+            polls: Mutex::new(VecDeque::new()),
+        };
+        let err = client.send_and_wait(params(), WaitOptions::new()).await.unwrap_err();
+        assert!(matches!(err, WaitError::Message(_)));
+    }
+
+    #[tokio::test]
+    async fn test_poll_hints_replace_the_backoff() {
+        let mut hinted = task(TaskState::Working);
+        hinted.metadata = Some(HashMap::from([(POLL_AFTER_METADATA_KEY.to_string(), serde_json::json!(200))]));
+        let polls = || Mutex::new(VecDeque::from([hinted.clone(), task(TaskState::Completed)]));
+        let options = || {
+            WaitOptions::polling()
+                .with_poll_interval(Duration::from_millis(1), Duration::from_secs(1))
+                .with_timeout(Duration::from_millis(100))
+        };
+
+        // The hinted delay outlasts the timeout, the backoff does not
+        let client = ScriptedClient { events: vec![], polls: polls() };
+        let err = client.wait_for_task("task-1".to_string(), options()).await.unwrap_err();
+        assert!(matches!(err, WaitError::TimedOut(Some(_))));
+        assert_eq!(poll_hint(err.task().unwrap()), Some(Duration::from_millis(200)));
+
+        let client = ScriptedClient { events: vec![], polls: polls() };
+        let task = client.wait_for_task("task-1".to_string(), options().with_poll_hints(false)).await.unwrap();
+        assert_eq!(task.status.state, TaskState::Completed);
+
+        // Hints beyond the longest delay are cut to it
+        let client = ScriptedClient { events: vec![], polls: polls() };
+        let capped = options().with_poll_interval(Duration::from_millis(1), Duration::from_millis(2));
+        let task = client.wait_for_task("task-1".to_string(), capped).await.unwrap();
+        assert_eq!(task.status.state, TaskState::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_zero_poll_hint_waits_the_poll_interval() {
+        let mut hinted = task(TaskState::Working);
+        hinted.metadata = Some(HashMap::from([(POLL_AFTER_METADATA_KEY.to_string(), serde_json::json!(0))]));
+        let client = ScriptedClient {
+            events: vec![],
+            polls: Mutex::new(VecDeque::from([hinted, task(TaskState::Completed)])),
+        };
+        let options = WaitOptions::polling()
+            .with_poll_interval(Duration::from_millis(200), Duration::from_secs(1))
+            .with_timeout(Duration::from_millis(100));
+
+        let err = client.wait_for_task("task-1".to_string(), options).await.unwrap_err();
+        assert!(matches!(err, WaitError::TimedOut(Some(_))));
+    }
+}
@@ -5,6 +5,7 @@
 
 use crate::a2a::models::*;
 use crate::a2a::core_types::*;
+use crate::a2a::client::validation::ResponseValidation;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
@@ -40,6 +41,24 @@ pub struct ClientConfig {
     
     /// HTTP headers to include in all requests
     pub headers: HashMap<String, String>,
+    
+    /// Whether to check the sequence numbers of streamed update events,
+    /// dropping replayed events and failing the stream when events are missing
+    #[serde(default)]
+    pub validate_event_sequence: bool,
+    
+    /// Attempts of a call across the agent card's security requirement sets
+    /// when the agent answers 401 or 403
+    #[serde(default = "default_max_auth_attempts")]
+    pub max_auth_attempts: usize,
+    
+    /// What to do with responses that do not match the typed models
+    #[serde(default)]
+    pub response_validation: ResponseValidation,
+}
+
+fn default_max_auth_attempts() -> usize {
+    crate::a2a::client::auth::challenge::DEFAULT_MAX_AUTH_ATTEMPTS
 }
 
 impl Default for ClientConfig {
@@ -54,6 +73,9 @@ impl Default for ClientConfig {
             push_notification_configs: vec![],
             extensions: vec![],
             headers: HashMap::new(),
+            validate_event_sequence: false,
+            max_auth_attempts: default_max_auth_attempts(),
+            response_validation: ResponseValidation::Off,
         }
     }
 }
@@ -118,6 +140,24 @@ impl ClientConfig {
         self
     }
     
+    /// Set whether streamed event sequence numbers are validated
+    pub fn with_event_sequence_validation(mut self, validate: bool) -> Self {
+        self.validate_event_sequence = validate;
+        self
+    }
+    
+    /// Set how many attempts a call makes across security requirement sets
+    pub fn with_max_auth_attempts(mut self, max_auth_attempts: usize) -> Self {
+        self.max_auth_attempts = max_auth_attempts.max(1);
+        self
+    }
+    
+    /// Set how responses are checked against the typed models
+    pub fn with_response_validation(mut self, validation: ResponseValidation) -> Self {
+        self.response_validation = validation;
+        self
+    }
+    
     /// Add a single HTTP header
     pub fn with_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.headers.insert(key.into(), value.into());
@@ -1,25 +1,264 @@
 //! Client-specific error types
+//!
+//! Client calls return `A2AError` like the rest of the crate. Converting it
+//! into a `ClientError` tells apart what went wrong on the way to the agent:
+//!
+//! - `Transport`: the request did not get an HTTP response, e.g. the host
+//!   did not resolve, the TLS handshake failed or the request timed out
+//! - `HttpStatus`: the agent answered with a non-success HTTP status
+//! - `JsonRpc`: the agent answered with a JSON-RPC error, whose code and data
+//!   are kept as sent
+//! - `Protocol`: the agent answered with something that is not a valid A2A
+//!   response
+//!
+//! `ClientError::is_retryable` tells whether sending the same request again
+//! may succeed.
 
-use crate::a2a::error::A2AError;
+use crate::a2a::error::{A2AError, ErrorOrigin, InvalidAgentResponseError};
+use crate::a2a::jsonrpc::JSONRPCError;
 use crate::InternalError;
 
+pub use crate::a2a::error::TransportErrorKind;
+
+impl TransportErrorKind {
+    /// Classifies a reqwest error by its flags and the errors it wraps
+    #[cfg(feature = "client")]
+    fn of_reqwest(error: &reqwest::Error) -> Self {
+        if error.is_timeout() {
+            return TransportErrorKind::Timeout;
+        }
+        if error.is_body() || error.is_decode() {
+            return TransportErrorKind::Body;
+        }
+        let mut causes = String::new();
+        let mut source = std::error::Error::source(error);
+        while let Some(cause) = source {
+            causes.push_str(&cause.to_string().to_lowercase());
+            causes.push('\n');
+            source = cause.source();
+        }
+        if causes.contains("dns") || causes.contains("resolve") || causes.contains("lookup") {
+            TransportErrorKind::Dns
+        } else if causes.contains("certificate") || causes.contains("tls") || causes.contains("ssl") {
+            TransportErrorKind::Tls
+        } else if error.is_connect() {
+            TransportErrorKind::Connect
+        } else {
+            TransportErrorKind::Other
+        }
+    }
+}
+
 /// Client-specific errors
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum ClientError {
-    #[error("Transport error: {0}")]
-    Transport(String),
+    #[error("Transport error ({kind}): {message}")]
+    Transport { kind: TransportErrorKind, message: String },
+    #[error("HTTP error {status}: {message}")]
+    HttpStatus {
+        status: u16,
+        message: String,
+        /// `WWW-Authenticate` values of a 401 or 403 response
+        www_authenticate: Vec<String>,
+    },
+    #[error("JSON-RPC error {}: {}", .0.code, .0.message)]
+    JsonRpc(JSONRPCError),
+    #[error("Protocol violation: {0}")]
+    Protocol(String),
     #[error("Authentication error: {0}")]
     Authentication(String),
     #[error("Configuration error: {0}")]
     Configuration(String),
 }
 
+impl ClientError {
+    /// A failed reqwest request, described by `context`
+    #[cfg(feature = "client")]
+    pub fn from_reqwest(context: &str, error: &reqwest::Error) -> Self {
+        if let Some(status) = error.status() {
+            return ClientError::HttpStatus {
+                status: status.as_u16(),
+                message: format!("{}: {}", context, error),
+                www_authenticate: Vec::new(),
+            };
+        }
+        ClientError::Transport {
+            kind: TransportErrorKind::of_reqwest(error),
+            message: format!("{}: {}", context, error),
+        }
+    }
+
+    /// Whether sending the same request again may succeed
+    ///
+    /// Transport failures are, except TLS failures. HTTP statuses are when
+    /// they are 408, 425, 429, 500, 502, 503 or 504. JSON-RPC errors are when
+    /// the agent says so in the error details, or their code is retryable by
+    /// `A2AError::details`. Protocol violations, authentication and
+    /// configuration errors are not.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ClientError::Transport { kind, .. } => *kind != TransportErrorKind::Tls,
+            ClientError::HttpStatus { status, .. } => matches!(status, 408 | 425 | 429 | 500 | 502 | 503 | 504),
+            ClientError::JsonRpc(error) => A2AError::from(error.clone()).details().retryable,
+            ClientError::Protocol(_) | ClientError::Authentication(_) | ClientError::Configuration(_) => false,
+        }
+    }
+
+    /// The HTTP status of an `HttpStatus` error
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            ClientError::HttpStatus { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+}
+
 impl From<ClientError> for A2AError {
     fn from(err: ClientError) -> Self {
-        A2AError::Internal(InternalError {
-            code: -32603,
-            message: err.to_string(),
-            data: None,
-        })
+        match err {
+            ClientError::Transport { kind, message } => A2AError::transport_failure(kind, message),
+            ClientError::HttpStatus { status, message, www_authenticate } if www_authenticate.is_empty() => {
+                A2AError::http_error(status, message)
+            }
+            ClientError::HttpStatus { status, message, www_authenticate } => {
+                A2AError::http_challenge(status, message, www_authenticate)
+            }
+            ClientError::JsonRpc(error) => error.into(),
+            ClientError::Protocol(message) => A2AError::InvalidAgentResponse(InvalidAgentResponseError {
+                code: -32006,
+                message,
+                data: None,
+            }),
+            err => A2AError::Internal(InternalError {
+                code: -32603,
+                message: err.to_string(),
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+impl From<&A2AError> for ClientError {
+    /// Recovers what failed from an error returned by a client call
+    ///
+    /// Transport and HTTP failures are told apart by `A2AError::origin`,
+    /// which only the client transports set. Errors the agent sent are
+    /// `JsonRpc` whatever their data holds, as are errors raised by the
+    /// client that none of the other variants describe.
+    fn from(err: &A2AError) -> Self {
+        let message = err.message();
+        match err.origin() {
+            Some(ErrorOrigin::Transport(kind)) => {
+                return ClientError::Transport {
+                    kind: *kind,
+                    message: message.strip_prefix("Transport error: ").unwrap_or(message).to_string(),
+                }
+            }
+            Some(ErrorOrigin::Http { status, www_authenticate }) => {
+                let prefix = format!("HTTP error {}: ", status);
+                return ClientError::HttpStatus {
+                    status: *status,
+                    message: message.strip_prefix(prefix.as_str()).unwrap_or(message).to_string(),
+                    www_authenticate: www_authenticate.clone(),
+                };
+            }
+            None => {}
+        }
+        let data = err.data();
+        match err {
+            A2AError::JSONParse(e) => ClientError::Protocol(e.message.clone()),
+            A2AError::InvalidAgentResponse(e) => ClientError::Protocol(e.message.clone()),
+            err => {
+                let error = JSONRPCError::new(err.code(), err.message().to_string());
+                ClientError::JsonRpc(match data {
+                    Some(data) => error.with_data(data.clone()),
+                    None => error,
+                })
+            }
+        }
+    }
+}
+
+impl From<A2AError> for ClientError {
+    fn from(err: A2AError) -> Self {
+        ClientError::from(&err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_errors_survive_the_a2a_conversion() {
+        let errors = [
+            ClientError::Transport {
+                kind: TransportErrorKind::Timeout,
+                message: "HTTP request failed: timed out".to_string(),
+            },
+            ClientError::HttpStatus {
+                status: 401,
+                message: "Unauthorized".to_string(),
+                www_authenticate: vec!["Bearer".to_string()],
+            },
+            ClientError::JsonRpc(JSONRPCError::new(-32001, "Task not found".to_string()).with_data(serde_json::json!({"task_id": "t1"}))),
+            ClientError::Protocol("missing result or error".to_string()),
+        ];
+        for error in errors {
+            let converted = ClientError::from(A2AError::from(error.clone()));
+            assert_eq!(converted.to_string(), error.to_string());
+            assert_eq!(converted.is_retryable(), error.is_retryable());
+        }
+    }
+
+    #[test]
+    fn test_retryability() {
+        let transport = |kind| ClientError::Transport { kind, message: String::new() };
+        assert!(transport(TransportErrorKind::Dns).is_retryable());
+        assert!(transport(TransportErrorKind::Timeout).is_retryable());
+        assert!(!transport(TransportErrorKind::Tls).is_retryable());
+
+        let status = |status| ClientError::HttpStatus { status, message: String::new(), www_authenticate: Vec::new() };
+        assert!(status(503).is_retryable());
+        assert!(status(429).is_retryable());
+        assert!(!status(404).is_retryable());
+
+        assert!(!ClientError::from(A2AError::task_not_found("t1")).is_retryable());
+        let quota = A2AError::jsonrpc_error(crate::a2a::jsonrpc::error_codes::QUOTA_EXCEEDED, "slow down".to_string());
+        assert!(ClientError::from(quota).is_retryable());
+        assert!(!ClientError::from(A2AError::invalid_response("not a task")).is_retryable());
+        assert!(matches!(
+            ClientError::from(A2AError::transport_error("connection reset".to_string())),
+            ClientError::Transport { kind: TransportErrorKind::Other, .. }
+        ));
+    }
+
+    #[test]
+    fn test_agent_errors_cannot_pass_for_local_failures() {
+        let data = serde_json::json!({"transport": "connect", "http_status": 503});
+        let internal: A2AError = JSONRPCError::new(-32603, "Transport error: refused".to_string())
+            .with_data(data.clone())
+            .into();
+        let response = serde_json::json!({"code": -32603, "message": "HTTP error 503: down", "data": data});
+        let deserialized: A2AError = serde_json::from_value(response).unwrap();
+        for error in [internal, deserialized] {
+            assert!(error.origin().is_none());
+            assert_eq!(error.http_status(), None);
+            let converted = ClientError::from(&error);
+            assert!(!matches!(converted, ClientError::Transport { .. } | ClientError::HttpStatus { .. }));
+        }
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn test_refused_connections_are_connect_errors() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        drop(listener);
+
+        let error = reqwest::get(&url).await.unwrap_err();
+        let error = ClientError::from_reqwest("HTTP request failed", &error);
+        assert!(matches!(error, ClientError::Transport { kind: TransportErrorKind::Connect, .. }));
+        assert!(error.is_retryable());
     }
 }
@@ -0,0 +1,334 @@
+//! Liveness probing of remote agents
+//!
+//! A `LivenessMonitor` probes a remote agent at a fixed interval, by default
+//! by fetching its agent card, and tracks whether it is available and how
+//! long probes take. The current `AgentHealth` is exposed through a
+//! `LivenessHandle`, as a watch channel or a stream, and changes of
+//! availability are reported to callbacks, so orchestrators can route work
+//! away from agents that stopped responding.
+
+use crate::a2a::client::card_resolver::A2ACardResolver;
+use crate::a2a::error::A2AError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// A check of whether a remote agent responds
+#[async_trait]
+pub trait LivenessProbe: Send + Sync {
+    /// Succeeds if the agent responded
+    async fn probe(&self) -> Result<(), A2AError>;
+}
+
+/// Probes an agent by fetching its agent card
+pub struct AgentCardProbe {
+    resolver: A2ACardResolver,
+}
+
+impl AgentCardProbe {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            resolver: A2ACardResolver::new(base_url.into()),
+        }
+    }
+}
+
+#[async_trait]
+impl LivenessProbe for AgentCardProbe {
+    async fn probe(&self) -> Result<(), A2AError> {
+        self.resolver.get_agent_card().await.map(|_| ())
+    }
+}
+
+/// Probes an agent with a GET to a health endpoint, expecting a 2xx status
+pub struct HealthEndpointProbe {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl HealthEndpointProbe {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl LivenessProbe for HealthEndpointProbe {
+    async fn probe(&self) -> Result<(), A2AError> {
+        let response = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .map_err(|e| A2AError::transport_error(format!("Health check failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(A2AError::http_error(
+                response.status().as_u16(),
+                format!("Health check failed: {}", response.status()),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Whether a remote agent is considered available
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Availability {
+    /// Not probed yet
+    #[default]
+    Unknown,
+    /// The last probe succeeded
+    Available,
+    /// The failure threshold of consecutive probes failed
+    Unavailable,
+}
+
+/// The observed health of a remote agent
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AgentHealth {
+    pub availability: Availability,
+    /// Duration of the last successful probe
+    pub latency: Option<Duration>,
+    /// Number of probes that failed since the last success
+    pub consecutive_failures: u32,
+    /// Error of the last failed probe
+    pub last_error: Option<String>,
+    /// When the agent was last probed
+    pub checked_at: Option<DateTime<Utc>>,
+}
+
+impl AgentHealth {
+    pub fn is_available(&self) -> bool {
+        self.availability == Availability::Available
+    }
+}
+
+type HealthCallback = Arc<dyn Fn(&AgentHealth) + Send + Sync>;
+
+/// Periodically probes a remote agent
+pub struct LivenessMonitor {
+    probe: Arc<dyn LivenessProbe>,
+    interval: Duration,
+    timeout: Duration,
+    failure_threshold: u32,
+    callbacks: Vec<HealthCallback>,
+}
+
+impl LivenessMonitor {
+    /// Creates a monitor probing every 30 seconds with a 5 second timeout,
+    /// marking the agent unavailable after 3 consecutive failures
+    pub fn new(probe: Arc<dyn LivenessProbe>) -> Self {
+        Self {
+            probe,
+            interval: Duration::from_secs(30),
+            timeout: Duration::from_secs(5),
+            failure_threshold: 3,
+            callbacks: Vec::new(),
+        }
+    }
+
+    /// Creates a monitor probing the agent card of the agent at `base_url`
+    pub fn for_agent(base_url: impl Into<String>) -> Self {
+        Self::new(Arc::new(AgentCardProbe::new(base_url)))
+    }
+
+    /// Sets the time between probes
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the time after which a probe counts as failed
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets how many consecutive failures make the agent unavailable
+    pub fn with_failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self
+    }
+
+    /// Calls `callback` whenever the availability of the agent changes
+    pub fn on_change(mut self, callback: impl Fn(&AgentHealth) + Send + Sync + 'static) -> Self {
+        self.callbacks.push(Arc::new(callback));
+        self
+    }
+
+    /// Probes the agent once, updating `previous`
+    pub async fn check(&self, previous: &AgentHealth) -> AgentHealth {
+        let started = Instant::now();
+        let result = match tokio::time::timeout(self.timeout, self.probe.probe()).await {
+            Ok(result) => result,
+            Err(_) => Err(A2AError::transport_error(format!(
+                "Probe timed out after {:?}",
+                self.timeout
+            ))),
+        };
+        let mut health = previous.clone();
+        health.checked_at = Some(Utc::now());
+        match result {
+            Ok(()) => {
+                health.availability = Availability::Available;
+                health.latency = Some(started.elapsed());
+                health.consecutive_failures = 0;
+                health.last_error = None;
+            }
+            Err(e) => {
+                health.consecutive_failures += 1;
+                health.last_error = Some(e.message().to_string());
+                if health.consecutive_failures >= self.failure_threshold {
+                    health.availability = Availability::Unavailable;
+                }
+            }
+        }
+        health
+    }
+
+    /// Starts probing in a background task, stopped when the handle is dropped
+    pub fn spawn(self) -> LivenessHandle {
+        let (sender, receiver) = watch::channel(AgentHealth::default());
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut health = AgentHealth::default();
+            loop {
+                ticker.tick().await;
+                let previous = health.availability;
+                health = self.check(&health).await;
+                if health.availability != previous {
+                    for callback in &self.callbacks {
+                        callback(&health);
+                    }
+                }
+                sender.send_replace(health.clone());
+            }
+        });
+        LivenessHandle { receiver, task }
+    }
+}
+
+/// Access to the health tracked by a running `LivenessMonitor`
+pub struct LivenessHandle {
+    receiver: watch::Receiver<AgentHealth>,
+    task: JoinHandle<()>,
+}
+
+impl LivenessHandle {
+    /// The health observed by the last probe
+    pub fn health(&self) -> AgentHealth {
+        self.receiver.borrow().clone()
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.receiver.borrow().is_available()
+    }
+
+    /// A receiver notified after every probe
+    pub fn subscribe(&self) -> watch::Receiver<AgentHealth> {
+        self.receiver.clone()
+    }
+
+    /// A stream of the health observed by each subsequent probe
+    pub fn updates(&self) -> BoxStream<'static, AgentHealth> {
+        Box::pin(futures::stream::unfold(self.subscribe(), |mut receiver| async move {
+            receiver.changed().await.ok()?;
+            let health = receiver.borrow_and_update().clone();
+            Some((health, receiver))
+        }))
+    }
+}
+
+impl Drop for LivenessHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// A probe whose outcome the test switches
+    struct SwitchProbe(AtomicBool);
+
+    #[async_trait]
+    impl LivenessProbe for SwitchProbe {
+        async fn probe(&self) -> Result<(), A2AError> {
+            if self.0.load(Ordering::SeqCst) {
+                Ok(())
+            } else {
+                Err(A2AError::transport_error("connection refused".to_string()))
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failures_reach_threshold() {
+        let probe = Arc::new(SwitchProbe(AtomicBool::new(true)));
+        let monitor = LivenessMonitor::new(probe.clone()).with_failure_threshold(2);
+
+        let health = monitor.check(&AgentHealth::default()).await;
+        assert!(health.is_available());
+        assert!(health.latency.is_some());
+
+        probe.0.store(false, Ordering::SeqCst);
+        let health = monitor.check(&health).await;
+        assert_eq!(health.availability, Availability::Available);
+        assert_eq!(health.consecutive_failures, 1);
+        let health = monitor.check(&health).await;
+        assert_eq!(health.availability, Availability::Unavailable);
+        assert!(health.last_error.unwrap().contains("connection refused"));
+    }
+
+    #[tokio::test]
+    async fn test_agent_card_probe() {
+        let mut server = mockito::Server::new_async().await;
+        let _card = server
+            .mock("GET", "/.well-known/agent-card.json")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let monitor = LivenessMonitor::for_agent(server.url()).with_failure_threshold(1);
+        let health = monitor.check(&AgentHealth::default()).await;
+        assert_eq!(health.availability, Availability::Unavailable);
+
+        let _health = server.mock("GET", "/healthz").with_status(200).create_async().await;
+        let monitor = LivenessMonitor::new(Arc::new(HealthEndpointProbe::new(format!("{}/healthz", server.url()))));
+        assert!(monitor.check(&AgentHealth::default()).await.is_available());
+    }
+
+    #[tokio::test]
+    async fn test_spawned_monitor_reports_changes() {
+        let probe = Arc::new(SwitchProbe(AtomicBool::new(true)));
+        let changes = Arc::new(AtomicUsize::new(0));
+        let handle = LivenessMonitor::new(probe.clone())
+            .with_interval(Duration::from_millis(10))
+            .with_failure_threshold(1)
+            .on_change({
+                let changes = changes.clone();
+                move |_| {
+                    changes.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .spawn();
+
+        let mut updates = handle.updates();
+        assert!(updates.next().await.unwrap().is_available());
+        probe.0.store(false, Ordering::SeqCst);
+        while updates.next().await.unwrap().is_available() {}
+        assert!(!handle.is_available());
+        assert_eq!(changes.load(Ordering::SeqCst), 2);
+    }
+}
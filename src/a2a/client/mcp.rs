@@ -0,0 +1,451 @@
+//! Model Context Protocol client for agent executors
+//!
+//! Available with the `mcp-bridge` feature. This is the reverse of
+//! `server::mcp_bridge`: an `AgentExecutor` uses `McpClient` to call the tools of
+//! an MCP server and turns the results into A2A artifacts, so MCP capabilities
+//! can be composed behind an A2A agent without hand-written protocol glue.
+//!
+//! Servers are reached either as a child process speaking newline-delimited
+//! JSON-RPC on stdio (`StdioMcpTransport`) or over the streamable HTTP
+//! transport (`HttpMcpTransport`).
+
+use crate::a2a::core_types::{FileContent, FilePart, Part, PartRoot};
+use crate::a2a::error::A2AError;
+use crate::a2a::models::{Artifact, TaskArtifactUpdateEvent};
+use crate::a2a::server::agent_execution::RequestContext;
+use crate::a2a::server::events::{Event, EventQueue};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::oneshot;
+use tracing::warn;
+
+/// MCP protocol revision requested by the client
+pub const MCP_CLIENT_PROTOCOL_VERSION: &str = "2025-03-26";
+
+/// Carries JSON-RPC messages to an MCP server
+#[async_trait]
+pub trait McpTransport: Send + Sync {
+    /// Sends a request and waits for the response with the same id
+    async fn request(&self, message: Value) -> Result<Value, A2AError>;
+
+    /// Sends a notification, which has no response
+    async fn notify(&self, message: Value) -> Result<(), A2AError>;
+}
+
+type PendingResponses = Arc<Mutex<HashMap<String, oneshot::Sender<Value>>>>;
+
+/// Talks to an MCP server running as a child process
+pub struct StdioMcpTransport {
+    stdin: tokio::sync::Mutex<ChildStdin>,
+    pending: PendingResponses,
+    _child: Child,
+}
+
+impl StdioMcpTransport {
+    /// Spawns `program` with `args` and connects to its stdin and stdout
+    pub fn spawn(program: &str, args: &[&str]) -> Result<Self, A2AError> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| A2AError::transport_error(format!("Failed to start MCP server '{}': {}", program, e)))?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        let pending: PendingResponses = Arc::new(Mutex::new(HashMap::new()));
+        let responses = pending.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                    warn!("Ignoring malformed line from MCP server");
+                    continue;
+                };
+                let Some(id) = message.get("id").map(Value::to_string) else {
+                    continue;
+                };
+                if let Some(sender) = responses.lock().unwrap().remove(&id) {
+                    let _ = sender.send(message);
+                }
+            }
+        });
+
+        Ok(Self {
+            stdin: tokio::sync::Mutex::new(stdin),
+            pending,
+            _child: child,
+        })
+    }
+
+    async fn write(&self, message: &Value) -> Result<(), A2AError> {
+        let mut stdin = self.stdin.lock().await;
+        let mut line = message.to_string();
+        line.push('\n');
+        stdin
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| A2AError::transport_error(format!("Failed to write to MCP server: {}", e)))?;
+        stdin
+            .flush()
+            .await
+            .map_err(|e| A2AError::transport_error(format!("Failed to write to MCP server: {}", e)))
+    }
+}
+
+#[async_trait]
+impl McpTransport for StdioMcpTransport {
+    async fn request(&self, message: Value) -> Result<Value, A2AError> {
+        let id = message.get("id").map(Value::to_string).unwrap_or_default();
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id.clone(), sender);
+
+        if let Err(e) = self.write(&message).await {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(e);
+        }
+        receiver
+            .await
+            .map_err(|_| A2AError::transport_error("MCP server exited before responding".to_string()))
+    }
+
+    async fn notify(&self, message: Value) -> Result<(), A2AError> {
+        self.write(&message).await
+    }
+}
+
+/// Talks to an MCP server over the streamable HTTP transport
+pub struct HttpMcpTransport {
+    client: reqwest::Client,
+    url: String,
+    session_id: Mutex<Option<String>>,
+}
+
+impl HttpMcpTransport {
+    /// Creates a transport posting to the MCP endpoint at `url`
+    pub fn new(url: &str) -> Self {
+        Self::with_client(reqwest::Client::new(), url)
+    }
+
+    /// Creates a transport using a custom HTTP client
+    pub fn with_client(client: reqwest::Client, url: &str) -> Self {
+        Self {
+            client,
+            url: url.to_string(),
+            session_id: Mutex::new(None),
+        }
+    }
+
+    async fn post(&self, message: &Value) -> Result<reqwest::Response, A2AError> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header("Accept", "application/json, text/event-stream")
+            .json(message);
+        if let Some(session_id) = self.session_id.lock().unwrap().clone() {
+            request = request.header("Mcp-Session-Id", session_id);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| A2AError::transport_error(format!("MCP request failed: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(A2AError::http_error(
+                response.status().as_u16(),
+                format!("MCP server returned {}", response.status()),
+            ));
+        }
+        if let Some(session_id) = response.headers().get("Mcp-Session-Id").and_then(|v| v.to_str().ok()) {
+            *self.session_id.lock().unwrap() = Some(session_id.to_string());
+        }
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl McpTransport for HttpMcpTransport {
+    async fn request(&self, message: Value) -> Result<Value, A2AError> {
+        let id = message.get("id").cloned();
+        let response = self.post(&message).await?;
+        let is_sse = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|content_type| content_type.starts_with("text/event-stream"));
+        let body = response
+            .text()
+            .await
+            .map_err(|e| A2AError::transport_error(format!("Failed to read MCP response: {}", e)))?;
+
+        if !is_sse {
+            return Ok(serde_json::from_str(&body)?);
+        }
+        // The server may stream notifications before the response
+        body.lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .filter_map(|data| serde_json::from_str::<Value>(data.trim()).ok())
+            .find(|message| message.get("id") == id.as_ref())
+            .ok_or_else(|| A2AError::invalid_response("MCP event stream ended without a response"))
+    }
+
+    async fn notify(&self, message: Value) -> Result<(), A2AError> {
+        self.post(&message).await.map(|_| ())
+    }
+}
+
+/// A tool advertised by an MCP server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpTool {
+    /// The tool name, used to call it
+    pub name: String,
+    /// What the tool does
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// JSON Schema of the tool arguments
+    #[serde(rename = "inputSchema", default)]
+    pub input_schema: Value,
+}
+
+/// One content block of a tool result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum McpContent {
+    /// Plain text
+    Text {
+        /// The text
+        text: String,
+    },
+    /// A base64 encoded image
+    Image {
+        /// Base64 encoded bytes
+        data: String,
+        /// MIME type of the image
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+    /// A base64 encoded audio clip
+    Audio {
+        /// Base64 encoded bytes
+        data: String,
+        /// MIME type of the clip
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+    /// An embedded resource
+    Resource {
+        /// The resource contents
+        resource: Value,
+    },
+}
+
+impl McpContent {
+    /// Converts the content block into an A2A part
+    pub fn to_part(&self) -> Part {
+        match self {
+            McpContent::Text { text } => Part::text(text.clone()),
+            McpContent::Image { data, mime_type } | McpContent::Audio { data, mime_type } => {
+                let mut file = FilePart::new_bytes(data.clone());
+                if let FileContent::Bytes(bytes) = &mut file.file {
+                    bytes.mime_type = Some(mime_type.clone());
+                }
+                Part::Direct(PartRoot::File(file))
+            }
+            McpContent::Resource { resource } => match resource.get("text").and_then(Value::as_str) {
+                Some(text) => Part::text(text.to_string()),
+                None => Part::data(resource.clone()),
+            },
+        }
+    }
+}
+
+/// The result of a tool call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpToolResult {
+    /// The content blocks returned by the tool
+    #[serde(default)]
+    pub content: Vec<McpContent>,
+    /// Structured output, if the tool declares an output schema
+    #[serde(rename = "structuredContent", default, skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
+    /// Whether the tool reported a failure
+    #[serde(rename = "isError", default)]
+    pub is_error: bool,
+}
+
+impl McpToolResult {
+    /// Converts the result into an artifact named after the tool
+    pub fn to_artifact(&self, tool_name: &str) -> Artifact {
+        let mut parts: Vec<Part> = self.content.iter().map(McpContent::to_part).collect();
+        if let Some(structured) = &self.structured_content {
+            parts.push(Part::data(structured.clone()));
+        }
+        Artifact::new(parts).with_name(tool_name.to_string())
+    }
+
+    /// Concatenates the text content blocks
+    pub fn text(&self) -> String {
+        self.content
+            .iter()
+            .filter_map(|content| match content {
+                McpContent::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Client for the tools of one MCP server
+pub struct McpClient {
+    transport: Arc<dyn McpTransport>,
+    next_id: AtomicU64,
+}
+
+impl McpClient {
+    /// Creates a client over `transport`; call `initialize` before use
+    pub fn new(transport: Arc<dyn McpTransport>) -> Self {
+        Self {
+            transport,
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Performs the MCP handshake, returning the server's `initialize` result
+    pub async fn initialize(&self) -> Result<Value, A2AError> {
+        let result = self
+            .call(
+                "initialize",
+                json!({
+                    "protocolVersion": MCP_CLIENT_PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": { "name": "a2a-rust", "version": env!("CARGO_PKG_VERSION") },
+                }),
+            )
+            .await?;
+        self.transport
+            .notify(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .await?;
+        Ok(result)
+    }
+
+    /// Lists the tools of the server
+    pub async fn list_tools(&self) -> Result<Vec<McpTool>, A2AError> {
+        let result = self.call("tools/list", json!({})).await?;
+        Ok(serde_json::from_value(result.get("tools").cloned().unwrap_or_else(|| json!([])))?)
+    }
+
+    /// Calls a tool
+    pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<McpToolResult, A2AError> {
+        let result = self
+            .call("tools/call", json!({ "name": name, "arguments": arguments }))
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Calls a tool and publishes its result as an artifact of the context's task
+    pub async fn call_tool_into_queue(
+        &self,
+        context: &RequestContext,
+        event_queue: &dyn EventQueue,
+        name: &str,
+        arguments: Value,
+    ) -> Result<McpToolResult, A2AError> {
+        let result = self.call_tool(name, arguments).await?;
+        let task_id = context.task_id.clone().unwrap_or_default();
+        let context_id = context.context_id.clone().unwrap_or_default();
+        event_queue
+            .enqueue_event(Event::TaskArtifactUpdate(TaskArtifactUpdateEvent::new(
+                task_id,
+                context_id,
+                result.to_artifact(name),
+            )))
+            .await?;
+        Ok(result)
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, A2AError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let response = self
+            .transport
+            .request(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .await?;
+        if let Some(error) = response.get("error") {
+            let code = error.get("code").and_then(Value::as_i64).unwrap_or(-32603) as i32;
+            let message = error.get("message").and_then(Value::as_str).unwrap_or("MCP error");
+            return Err(A2AError::jsonrpc_error(code, message.to_string()));
+        }
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| A2AError::invalid_response("MCP response has neither result nor error"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+
+    #[test]
+    fn test_tool_result_to_artifact() {
+        let result: McpToolResult = serde_json::from_value(json!({
+            "content": [
+                { "type": "text", "text": "3 results" },
+                { "type": "image", "data": "aGk=", "mimeType": "image/png" },
+                { "type": "resource", "resource": { "uri": "file:///a.txt", "text": "contents" } },
+            ],
+            "isError": false,
+        }))
+        .unwrap();
+        let artifact = result.to_artifact("search");
+        assert_eq!(artifact.name.as_deref(), Some("search"));
+        assert_eq!(artifact.parts.len(), 3);
+        assert_eq!(result.text(), "3 results");
+
+        let json = serde_json::to_value(&artifact).unwrap();
+        assert_eq!(json["parts"][1]["file"]["mimeType"], "image/png");
+        assert_eq!(json["parts"][2]["text"], "contents");
+    }
+
+    #[tokio::test]
+    async fn test_http_transport_calls_tool() {
+        let mut server = Server::new_async().await;
+        let _init = server
+            .mock("POST", "/mcp")
+            .match_body(mockito::Matcher::PartialJson(json!({"method": "initialize"})))
+            .with_header("content-type", "application/json")
+            .with_header("Mcp-Session-Id", "session-1")
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-03-26","capabilities":{}}}"#)
+            .create_async()
+            .await;
+        let _initialized = server
+            .mock("POST", "/mcp")
+            .match_body(mockito::Matcher::PartialJson(json!({"method": "notifications/initialized"})))
+            .with_status(202)
+            .create_async()
+            .await;
+        let call = server
+            .mock("POST", "/mcp")
+            .match_header("Mcp-Session-Id", "session-1")
+            .match_body(mockito::Matcher::PartialJson(json!({"method": "tools/call"})))
+            .with_header("content-type", "text/event-stream")
+            .with_body("event: message\ndata: {\"jsonrpc\":\"2.0\",\"id\":2,\"result\":{\"content\":[{\"type\":\"text\",\"text\":\"42\"}]}}\n\n")
+            .create_async()
+            .await;
+
+        let client = McpClient::new(Arc::new(HttpMcpTransport::new(&format!("{}/mcp", server.url()))));
+        client.initialize().await.unwrap();
+        let result = client.call_tool("answer", json!({})).await.unwrap();
+        assert_eq!(result.text(), "42");
+        assert!(!result.is_error);
+        call.assert_async().await;
+    }
+}
@@ -0,0 +1,384 @@
+//! Client call middleware
+//!
+//! This module provides general-purpose interceptors for outgoing client calls.
+
+use crate::a2a::client::client_trait::{ClientCallContext, ClientCallInterceptor};
+use crate::a2a::core_types::Part;
+use crate::a2a::error::A2AError;
+use crate::a2a::models::AgentCard;
+use crate::a2a::utils::mime::{accepted_input_modes, normalize_part_mime_types, validate_part_mime_types};
+use crate::a2a::utils::replay::{replay_headers, ReplayRequest};
+use crate::a2a::utils::request_signing::{RequestSignature, REQUEST_SIGNATURE_HEADER};
+use crate::a2a::utils::telemetry::{TraceContext, TRACEPARENT_HEADER, TRACESTATE_HEADER};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// An interceptor that propagates the W3C trace context to the remote agent
+///
+/// The outgoing call is recorded as a child span of the trace context active
+/// for the current task (see `TraceContext::scope`). When no context is active,
+/// a new root trace is started. Headers already present in `http_kwargs` are
+/// left untouched so callers can supply their own trace context.
+#[derive(Debug, Clone, Default)]
+pub struct TraceContextInterceptor;
+
+impl TraceContextInterceptor {
+    /// Create a new trace context interceptor
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl ClientCallInterceptor for TraceContextInterceptor {
+    async fn intercept(
+        &self,
+        method_name: &str,
+        request_payload: Value,
+        mut http_kwargs: HashMap<String, Value>,
+        _agent_card: &AgentCard,
+        _context: Option<&ClientCallContext>,
+    ) -> Result<(Value, HashMap<String, Value>), A2AError> {
+        let headers = http_kwargs
+            .entry("headers".to_string())
+            .or_insert_with(|| Value::Object(serde_json::Map::new()))
+            .as_object_mut()
+            .ok_or_else(|| A2AError::invalid_request("headers must be an object"))?;
+
+        if headers.keys().any(|k| k.eq_ignore_ascii_case(TRACEPARENT_HEADER)) {
+            return Ok((request_payload, http_kwargs));
+        }
+
+        let trace_context = TraceContext::current()
+            .map(|parent| parent.child())
+            .unwrap_or_else(TraceContext::new_root);
+
+        headers.insert(
+            TRACEPARENT_HEADER.to_string(),
+            Value::String(trace_context.to_traceparent()),
+        );
+        if let Some(trace_state) = &trace_context.trace_state {
+            headers.insert(TRACESTATE_HEADER.to_string(), Value::String(trace_state.clone()));
+        }
+
+        tracing::debug!(
+            trace_id = %trace_context.trace_id,
+            span_id = %trace_context.span_id,
+            "Propagating trace context for method: {}",
+            method_name
+        );
+
+        Ok((request_payload, http_kwargs))
+    }
+}
+
+/// An interceptor signing requests with a secret shared with the remote agent
+///
+/// The signature covers the method, the time of signing and the serialized
+/// payload, which is what the JSON-RPC transport sends, so the interceptor
+/// must be the last to run. The agent verifies it with
+/// `HmacRequestVerifier`.
+#[derive(Clone)]
+pub struct HmacSigningInterceptor {
+    key_id: String,
+    secret: Vec<u8>,
+}
+
+impl HmacSigningInterceptor {
+    /// Signs with `secret`, which the agent knows as `key_id`
+    pub fn new(key_id: &str, secret: &[u8]) -> Self {
+        Self {
+            key_id: key_id.to_string(),
+            secret: secret.to_vec(),
+        }
+    }
+}
+
+impl std::fmt::Debug for HmacSigningInterceptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HmacSigningInterceptor").field("key_id", &self.key_id).finish()
+    }
+}
+
+#[async_trait]
+impl ClientCallInterceptor for HmacSigningInterceptor {
+    async fn intercept(
+        &self,
+        method_name: &str,
+        request_payload: Value,
+        mut http_kwargs: HashMap<String, Value>,
+        _agent_card: &AgentCard,
+        _context: Option<&ClientCallContext>,
+    ) -> Result<(Value, HashMap<String, Value>), A2AError> {
+        let body = serde_json::to_vec(&request_payload)?;
+        let signature = RequestSignature::sign(
+            &self.key_id,
+            &self.secret,
+            method_name,
+            &body,
+            chrono::Utc::now().timestamp(),
+        );
+
+        let headers = http_kwargs
+            .entry("headers".to_string())
+            .or_insert_with(|| Value::Object(serde_json::Map::new()))
+            .as_object_mut()
+            .ok_or_else(|| A2AError::invalid_request("headers must be an object"))?;
+        headers.insert(REQUEST_SIGNATURE_HEADER.to_string(), Value::String(signature.to_header()));
+
+        Ok((request_payload, http_kwargs))
+    }
+}
+
+/// An interceptor adding signed nonce headers to outgoing calls, for agents
+/// configured with `ReplayProtection`
+///
+/// The nonce is signed with a key shared with the agent for replay
+/// protection only, over the method, the path of the agent card URL and the
+/// serialized payload, so like `HmacSigningInterceptor` it must be the last
+/// to run.
+#[derive(Clone)]
+pub struct ReplayProtectionInterceptor {
+    key: Vec<u8>,
+}
+
+impl ReplayProtectionInterceptor {
+    /// Signs nonces with `key`
+    pub fn new(key: &[u8]) -> Self {
+        Self { key: key.to_vec() }
+    }
+}
+
+impl std::fmt::Debug for ReplayProtectionInterceptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReplayProtectionInterceptor").finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl ClientCallInterceptor for ReplayProtectionInterceptor {
+    async fn intercept(
+        &self,
+        method_name: &str,
+        request_payload: Value,
+        mut http_kwargs: HashMap<String, Value>,
+        agent_card: &AgentCard,
+        _context: Option<&ClientCallContext>,
+    ) -> Result<(Value, HashMap<String, Value>), A2AError> {
+        let body = serde_json::to_vec(&request_payload)?;
+        let url = url::Url::parse(&agent_card.url)
+            .map_err(|e| A2AError::invalid_request(&format!("Invalid agent URL '{}': {}", agent_card.url, e)))?;
+        let request = ReplayRequest::new(method_name, url.path(), &body);
+
+        let headers = http_kwargs
+            .entry("headers".to_string())
+            .or_insert_with(|| Value::Object(serde_json::Map::new()))
+            .as_object_mut()
+            .ok_or_else(|| A2AError::invalid_request("headers must be an object"))?;
+        for (name, value) in replay_headers(&self.key, &request) {
+            headers.insert(name.to_string(), Value::String(value));
+        }
+
+        Ok((request_payload, http_kwargs))
+    }
+}
+
+/// An interceptor giving outgoing message parts canonical MIME types
+///
+/// File parts of `message/send` and `message/stream` calls have their
+/// declared MIME types normalized, and missing ones sniffed from inline
+/// content. Unless disabled with `without_validation`, a message with a
+/// part outside the agent card's input modes fails with a content type not
+/// supported error before it is sent.
+#[derive(Debug, Clone)]
+pub struct MimeTypeInterceptor {
+    validate: bool,
+}
+
+impl MimeTypeInterceptor {
+    /// Create an interceptor that normalizes and validates parts
+    pub fn new() -> Self {
+        Self { validate: true }
+    }
+
+    /// Only normalize parts, leaving validation to the agent
+    pub fn without_validation(mut self) -> Self {
+        self.validate = false;
+        self
+    }
+}
+
+impl Default for MimeTypeInterceptor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ClientCallInterceptor for MimeTypeInterceptor {
+    async fn intercept(
+        &self,
+        method_name: &str,
+        mut request_payload: Value,
+        http_kwargs: HashMap<String, Value>,
+        agent_card: &AgentCard,
+        _context: Option<&ClientCallContext>,
+    ) -> Result<(Value, HashMap<String, Value>), A2AError> {
+        if !matches!(method_name, "message/send" | "message/stream") {
+            return Ok((request_payload, http_kwargs));
+        }
+        // The payload is either the JSON-RPC request or its params
+        let pointer = match request_payload.get("params") {
+            Some(_) => "/params/message/parts",
+            None => "/message/parts",
+        };
+        let Some(parts) = request_payload.pointer_mut(pointer) else {
+            return Ok((request_payload, http_kwargs));
+        };
+
+        let mut typed: Vec<Part> = serde_json::from_value(parts.take())?;
+        normalize_part_mime_types(&mut typed);
+        *parts = serde_json::to_value(&typed)?;
+        if self.validate {
+            validate_part_mime_types(&typed, &accepted_input_modes(agent_card))?;
+        }
+
+        Ok((request_payload, http_kwargs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::models::AgentCapabilities;
+
+    fn test_card() -> AgentCard {
+        AgentCard::new(
+            "Test Agent".to_string(),
+            "Test agent".to_string(),
+            "http://localhost:8080".to_string(),
+            "1.0.0".to_string(),
+            vec!["text/plain".to_string()],
+            vec!["text/plain".to_string()],
+            AgentCapabilities::new(),
+            vec![],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_injects_child_of_current_context() {
+        let parent = TraceContext::new_root().with_trace_state("vendor=value".to_string());
+        let trace_id = parent.trace_id.clone();
+        let card = test_card();
+
+        let (_, kwargs) = parent
+            .scope(TraceContextInterceptor::new().intercept(
+                "message/send",
+                Value::Null,
+                HashMap::new(),
+                &card,
+                None,
+            ))
+            .await
+            .unwrap();
+
+        let headers = kwargs.get("headers").and_then(|h| h.as_object()).unwrap();
+        let traceparent = headers.get(TRACEPARENT_HEADER).and_then(|v| v.as_str()).unwrap();
+        let ctx = TraceContext::parse(traceparent, None).unwrap();
+        assert_eq!(ctx.trace_id, trace_id);
+        assert_eq!(
+            headers.get(TRACESTATE_HEADER).and_then(|v| v.as_str()),
+            Some("vendor=value")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_existing_traceparent_is_kept() {
+        let card = test_card();
+        let mut kwargs = HashMap::new();
+        kwargs.insert(
+            "headers".to_string(),
+            serde_json::json!({"traceparent": "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"}),
+        );
+
+        let (_, kwargs) = TraceContextInterceptor::new()
+            .intercept("message/send", Value::Null, kwargs, &card, None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            kwargs["headers"]["traceparent"],
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_signs_serialized_payload() {
+        let payload = serde_json::json!({"jsonrpc": "2.0", "method": "message/send", "id": 1});
+        let (payload, kwargs) = HmacSigningInterceptor::new("peer-a", b"secret")
+            .intercept("message/send", payload, HashMap::new(), &test_card(), None)
+            .await
+            .unwrap();
+
+        let header = kwargs["headers"][REQUEST_SIGNATURE_HEADER].as_str().unwrap();
+        let signature = RequestSignature::parse(header).unwrap();
+        assert_eq!(signature.key_id, "peer-a");
+        assert!(signature.verify(b"secret", "message/send", &serde_json::to_vec(&payload).unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_replay_nonce_covers_the_serialized_payload() {
+        use crate::a2a::utils::replay::{verify_replay_signature, NONCE_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+
+        let card = test_card();
+        let payload = serde_json::json!({"jsonrpc": "2.0", "method": "message/send", "id": 1});
+        let (payload, kwargs) = ReplayProtectionInterceptor::new(b"replay-key")
+            .intercept("message/send", payload, HashMap::new(), &card, None)
+            .await
+            .unwrap();
+
+        let header = |name: &str| kwargs["headers"][name].as_str().unwrap().to_string();
+        let body = serde_json::to_vec(&payload).unwrap();
+        let path = url::Url::parse(&card.url).unwrap().path().to_string();
+        assert!(verify_replay_signature(
+            b"replay-key",
+            &ReplayRequest::new("message/send", &path, &body),
+            header(TIMESTAMP_HEADER).parse().unwrap(),
+            &header(NONCE_HEADER),
+            &header(SIGNATURE_HEADER),
+        ));
+        assert!(!format!("{:?}", ReplayProtectionInterceptor::new(b"replay-key")).contains("replay-key"));
+    }
+
+    #[tokio::test]
+    async fn test_mime_types_are_normalized_and_validated() {
+        let mut card = test_card();
+        card.default_input_modes.push("image/jpeg".to_string());
+        let jpeg = crate::a2a::core_types::FilePart::new_bytes("/9j/4AAQSkZJRgAB".to_string());
+        let payload = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "message/send",
+            "params": {"message": {"role": "user", "parts": [Part::from(jpeg)], "message_id": "m-1"}},
+        });
+
+        let (payload, _) = MimeTypeInterceptor::new()
+            .intercept("message/send", payload, HashMap::new(), &card, None)
+            .await
+            .unwrap();
+        assert_eq!(payload["params"]["message"]["parts"][0]["file"]["mimeType"], "image/jpeg");
+
+        card.default_input_modes = vec!["text/plain".to_string()];
+        let err = MimeTypeInterceptor::new()
+            .intercept("message/send", payload.clone(), HashMap::new(), &card, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, A2AError::ContentTypeNotSupported(_)));
+        assert!(MimeTypeInterceptor::new()
+            .without_validation()
+            .intercept("message/send", payload, HashMap::new(), &card, None)
+            .await
+            .is_ok());
+    }
+}
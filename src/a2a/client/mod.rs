@@ -4,19 +4,35 @@
 //! matching a2a-python/src/a2a/client/
 
 pub mod base_client;
+#[cfg(feature = "client")]
 pub mod card_resolver;
 pub mod client_factory;
 pub mod client_task_manager;
 pub mod client_trait;
 pub mod client;
+#[cfg(feature = "client")]
+pub mod completion;
 pub mod config;
 pub mod errors;
+#[cfg(feature = "client")]
 pub mod factory;
 pub mod helpers;
 pub mod legacy_grpc;
 pub mod legacy;
+#[cfg(feature = "client")]
+pub mod liveness;
+#[cfg(feature = "mcp-bridge")]
+pub mod mcp;
+#[cfg(feature = "client")]
 pub mod middleware;
+#[cfg(feature = "client")]
+pub mod multi_endpoint;
 pub mod optionals;
+pub mod pagination;
+pub mod validation;
+#[cfg(feature = "server")]
+pub mod orchestration;
+pub mod workflow;
 
 // Auth submodule
 pub mod auth;
@@ -31,12 +47,32 @@ pub use client_trait::{
     ClientEvent, ClientEventOrMessage, Consumer, TaskUpdateEvent
 };
 pub use client::*;
+#[cfg(feature = "client")]
+pub use completion::{poll_hint, ClientExt, WaitError, WaitOptions, WaitStrategy};
 pub use config::*;
 pub use errors::*;
+pub use pagination::{paginate, Page};
+pub use validation::{ResponseValidation, SchemaViolation};
+#[cfg(feature = "client")]
 pub use factory::*;
+#[cfg(feature = "client")]
+pub use liveness::{AgentHealth, Availability, LivenessHandle, LivenessMonitor, LivenessProbe};
+#[cfg(feature = "client")]
+pub use middleware::{HmacSigningInterceptor, MimeTypeInterceptor, ReplayProtectionInterceptor, TraceContextInterceptor};
+#[cfg(feature = "client")]
+pub use multi_endpoint::{Endpoint, MultiEndpointClient, Routing};
+#[cfg(feature = "server")]
+pub use orchestration::{ChildOutcome, ChildStatus, FanOut, FanOutResult, ParentTask};
+#[cfg(feature = "sqlite")]
+pub use workflow::SqliteWorkflowStore;
+pub use workflow::{InMemoryWorkflowStore, Workflow, WorkflowState, WorkflowStatus, WorkflowStore};
 
 // Re-export auth types
 pub use auth::{
     CredentialService, InMemoryContextCredentialStore, EnvironmentCredentialService,
-    CompositeCredentialService, AuthInterceptor
+    CompositeCredentialService, AuthInterceptor, Credential, CredentialCache, AuthChallenge
 };
+#[cfg(feature = "client")]
+pub use auth::FileCredentialService;
+#[cfg(feature = "keyring")]
+pub use auth::KeyringCredentialService;
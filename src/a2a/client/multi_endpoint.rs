@@ -0,0 +1,651 @@
+//! Failover across several endpoints of the same agent
+//!
+//! `MultiEndpointClient` is a `ClientTransport` over an ordered list of
+//! endpoints, e.g. the replicas of one agent, and can be used wherever a
+//! single transport is, including inside `BaseClient`. Calls go to the first
+//! healthy endpoint, or rotate between them with `Routing::RoundRobin`. A
+//! call is retried on the next endpoint when the request could not reach its
+//! endpoint, i.e. the host did not resolve or refused the connection, and,
+//! except for `message/send` and `message/stream`, when the endpoint answered
+//! with a 5xx status. A message that reached an agent is never sent again,
+//! as the agent may already be working on it; errors after the request was
+//! sent, 4xx statuses and JSON-RPC errors are returned as they are. An
+//! endpoint that keeps failing is skipped for a cooldown period. Endpoints
+//! can additionally be watched by a `LivenessMonitor`.
+//!
+//! Streaming tasks are sticky: once a stream reports a task id, later calls
+//! for that task, including `resubscribe`, go to the endpoint serving the
+//! stream first, until the task reaches a final state.
+
+use crate::a2a::client::client_trait::{ClientCallContext, ClientEvent, ClientTransport};
+use crate::a2a::client::errors::{ClientError, TransportErrorKind};
+use crate::a2a::client::liveness::{Availability, LivenessHandle, LivenessMonitor};
+use crate::a2a::client::transports::jsonrpc::JsonRpcTransport;
+use crate::a2a::error::A2AError;
+use crate::a2a::models::*;
+use async_trait::async_trait;
+use futures::{Future, Stream, StreamExt};
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How calls are spread across healthy endpoints
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Routing {
+    /// Always prefer endpoints earlier in the list
+    #[default]
+    Failover,
+    /// Start each call at the next endpoint in turn
+    RoundRobin,
+}
+
+/// One endpoint of a `MultiEndpointClient`
+pub struct Endpoint {
+    url: String,
+    transport: Box<dyn ClientTransport>,
+    liveness: Option<LivenessHandle>,
+}
+
+impl Endpoint {
+    pub fn new(url: impl Into<String>, transport: Box<dyn ClientTransport>) -> Self {
+        Self {
+            url: url.into(),
+            transport,
+            liveness: None,
+        }
+    }
+
+    /// Skips the endpoint while `liveness` reports it unavailable
+    pub fn with_liveness(mut self, liveness: LivenessHandle) -> Self {
+        self.liveness = Some(liveness);
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+/// Failures observed on one endpoint
+#[derive(Debug, Default)]
+struct EndpointHealth {
+    consecutive_failures: u32,
+    down_until: Option<Instant>,
+}
+
+/// A transport failing over between endpoints of the same agent
+pub struct MultiEndpointClient {
+    endpoints: Vec<Endpoint>,
+    health: Mutex<Vec<EndpointHealth>>,
+    routing: Routing,
+    failure_threshold: u32,
+    cooldown: Duration,
+    next: AtomicUsize,
+    sticky: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+impl MultiEndpointClient {
+    /// Creates a client over `endpoints`, in order of preference
+    pub fn new(endpoints: Vec<Endpoint>) -> Result<Self, A2AError> {
+        if endpoints.is_empty() {
+            return Err(A2AError::invalid_params("At least one endpoint is required"));
+        }
+        Ok(Self {
+            health: Mutex::new(endpoints.iter().map(|_| EndpointHealth::default()).collect()),
+            endpoints,
+            routing: Routing::default(),
+            failure_threshold: 1,
+            cooldown: Duration::from_secs(30),
+            next: AtomicUsize::new(0),
+            sticky: Arc::default(),
+        })
+    }
+
+    /// Creates a client with a JSON-RPC transport for each base URL
+    pub fn from_urls(urls: Vec<String>, agent_card: Option<AgentCard>) -> Result<Self, A2AError> {
+        let endpoints = urls
+            .into_iter()
+            .map(|url| {
+                let transport = JsonRpcTransport::new(url.clone(), agent_card.clone())?;
+                Ok(Endpoint::new(url, Box::new(transport)))
+            })
+            .collect::<Result<Vec<_>, A2AError>>()?;
+        Self::new(endpoints)
+    }
+
+    /// Probes the agent card of every endpoint every `interval`
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn with_liveness_probes(mut self, interval: Duration) -> Self {
+        for endpoint in &mut self.endpoints {
+            let monitor = LivenessMonitor::for_agent(endpoint.url.clone()).with_interval(interval);
+            endpoint.liveness = Some(monitor.spawn());
+        }
+        self
+    }
+
+    /// Sets how calls are spread across healthy endpoints
+    pub fn with_routing(mut self, routing: Routing) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    /// Sets how many consecutive failures take an endpoint out of rotation
+    pub fn with_failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self
+    }
+
+    /// Sets how long a failing endpoint is skipped
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    pub fn endpoints(&self) -> &[Endpoint] {
+        &self.endpoints
+    }
+
+    /// The URL of the endpoint a streaming task is pinned to, if any
+    pub fn sticky_endpoint(&self, task_id: &str) -> Option<&str> {
+        let index = *self.sticky.lock().unwrap().get(task_id)?;
+        Some(self.endpoints[index].url())
+    }
+
+    /// Whether the endpoint at `index` is currently considered healthy
+    fn is_healthy(&self, index: usize, health: &[EndpointHealth]) -> bool {
+        let recovered = health[index].down_until.is_none_or(|until| Instant::now() >= until);
+        let alive = self.endpoints[index]
+            .liveness
+            .as_ref()
+            .is_none_or(|liveness| liveness.health().availability != Availability::Unavailable);
+        recovered && alive
+    }
+
+    /// The endpoints to try for a call, best first
+    ///
+    /// Unhealthy endpoints are kept at the end as a last resort.
+    fn order(&self, task_id: Option<&str>) -> Vec<usize> {
+        let count = self.endpoints.len();
+        let start = match self.routing {
+            Routing::Failover => 0,
+            Routing::RoundRobin => self.next.fetch_add(1, Ordering::Relaxed) % count,
+        };
+        let mut order: Vec<usize> = (0..count).map(|i| (start + i) % count).collect();
+        if let Some(index) = task_id.and_then(|id| self.sticky.lock().unwrap().get(id).copied()) {
+            order.retain(|&i| i != index);
+            order.insert(0, index);
+        }
+        let health = self.health.lock().unwrap();
+        let (healthy, unhealthy): (Vec<usize>, Vec<usize>) =
+            order.into_iter().partition(|&i| self.is_healthy(i, &health));
+        healthy.into_iter().chain(unhealthy).collect()
+    }
+
+    fn record_success(&self, index: usize) {
+        let mut health = self.health.lock().unwrap();
+        health[index] = EndpointHealth::default();
+    }
+
+    fn record_failure(&self, index: usize, error: &A2AError) {
+        let mut health = self.health.lock().unwrap();
+        let endpoint = &mut health[index];
+        endpoint.consecutive_failures += 1;
+        if endpoint.consecutive_failures >= self.failure_threshold {
+            endpoint.down_until = Some(Instant::now() + self.cooldown);
+        }
+        tracing::warn!(
+            endpoint = %self.endpoints[index].url,
+            error = %error.message(),
+            "Agent endpoint failed, trying the next one"
+        );
+    }
+
+    /// Runs `attempt` on each endpoint in turn until one succeeds or fails
+    /// with an error that is not worth retrying elsewhere
+    ///
+    /// `idempotent` calls are also retried after a 5xx response.
+    async fn call<'a, T, F, Fut>(
+        &'a self,
+        task_id: Option<&str>,
+        idempotent: bool,
+        mut attempt: F,
+    ) -> Result<(usize, T), A2AError>
+    where
+        F: FnMut(&'a dyn ClientTransport) -> Fut + Send,
+        Fut: Future<Output = Result<T, A2AError>> + Send,
+    {
+        let mut last_error = None;
+        for index in self.order(task_id) {
+            match attempt(&*self.endpoints[index].transport).await {
+                Ok(value) => {
+                    self.record_success(index);
+                    return Ok((index, value));
+                }
+                Err(error) if fails_over(&error, idempotent) => {
+                    self.record_failure(index, &error);
+                    last_error = Some(error);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| A2AError::internal("No agent endpoint available")))
+    }
+}
+
+/// Whether a call failing with `error` is retried on the next endpoint
+fn fails_over(error: &A2AError, idempotent: bool) -> bool {
+    match ClientError::from(error) {
+        ClientError::Transport { kind, .. } => matches!(kind, TransportErrorKind::Dns | TransportErrorKind::Connect),
+        ClientError::HttpStatus { status, .. } => idempotent && (500..600).contains(&status),
+        _ => false,
+    }
+}
+
+/// The task an event belongs to and whether it ends the task's stream
+fn task_of(event: &TaskOrMessage) -> Option<(&str, bool)> {
+    match event {
+        TaskOrMessage::Task(task) => Some((&task.id, task.status.state.is_terminal())),
+        TaskOrMessage::TaskUpdate(update) => Some((&update.task_id, update.r#final)),
+        TaskOrMessage::TaskArtifactUpdateEvent(update) => Some((&update.task_id, false)),
+        TaskOrMessage::Message(_) => None,
+    }
+}
+
+#[async_trait]
+impl ClientTransport for MultiEndpointClient {
+    async fn send_message(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<TaskOrMessage, A2AError> {
+        let task_id = params.message.task_id.clone();
+        let (_, result) = self
+            .call(task_id.as_deref(), false, |transport| {
+                transport.send_message(params.clone(), context, extensions.clone())
+            })
+            .await?;
+        Ok(result)
+    }
+
+    async fn send_message_streaming<'a>(
+        &'a self,
+        params: MessageSendParams,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<TaskOrMessage, A2AError>> + Send + 'a>>, A2AError> {
+        let task_id = params.message.task_id.clone();
+        let (index, stream) = self
+            .call(task_id.as_deref(), false, |transport| {
+                transport.send_message_streaming(params.clone(), context, extensions.clone())
+            })
+            .await?;
+
+        // Pin the task to this endpoint while its stream is open
+        let sticky = self.sticky.clone();
+        Ok(Box::pin(stream.inspect(move |event| {
+            if let Some((task_id, done)) = event.as_ref().ok().and_then(task_of) {
+                let mut sticky = sticky.lock().unwrap();
+                if done {
+                    sticky.remove(task_id);
+                } else {
+                    sticky.insert(task_id.to_string(), index);
+                }
+            }
+        })))
+    }
+
+    async fn get_task(
+        &self,
+        request: TaskQueryParams,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<Task, A2AError> {
+        let (_, task) = self
+            .call(Some(&request.id), true, |transport| {
+                transport.get_task(request.clone(), context, extensions.clone())
+            })
+            .await?;
+        Ok(task)
+    }
+
+    async fn cancel_task(
+        &self,
+        request: TaskIdParams,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<Task, A2AError> {
+        let (_, task) = self
+            .call(Some(&request.id), true, |transport| {
+                transport.cancel_task(request.clone(), context, extensions.clone())
+            })
+            .await?;
+        Ok(task)
+    }
+
+    async fn set_task_callback(
+        &self,
+        request: TaskPushNotificationConfig,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        let (_, config) = self
+            .call(Some(&request.task_id), true, |transport| {
+                transport.set_task_callback(request.clone(), context, extensions.clone())
+            })
+            .await?;
+        Ok(config)
+    }
+
+    async fn get_task_callback(
+        &self,
+        request: GetTaskPushNotificationConfigParams,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        let (_, config) = self
+            .call(Some(&request.id), true, |transport| {
+                transport.get_task_callback(request.clone(), context, extensions.clone())
+            })
+            .await?;
+        Ok(config)
+    }
+
+    async fn resubscribe<'a>(
+        &'a self,
+        request: TaskIdParams,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<ClientEvent, A2AError>> + Send + 'a>>, A2AError> {
+        let mut request = request;
+        if request.from_event_id.is_none() {
+            request.from_event_id = self.last_event_id(&request.id);
+        }
+        let task_id = request.id.clone();
+        let (_, stream) = self
+            .call(Some(&task_id), true, |transport| {
+                transport.resubscribe(request.clone(), context, extensions.clone())
+            })
+            .await?;
+        Ok(stream)
+    }
+
+    async fn get_card(
+        &self,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<AgentCard, A2AError> {
+        let (_, card) = self
+            .call(None, true, |transport| transport.get_card(context, extensions.clone()))
+            .await?;
+        Ok(card)
+    }
+
+    async fn close(&self) -> Result<(), A2AError> {
+        for endpoint in &self.endpoints {
+            endpoint.transport.close().await?;
+        }
+        Ok(())
+    }
+
+    fn last_event_id(&self, task_id: &str) -> Option<String> {
+        self.order(Some(task_id))
+            .into_iter()
+            .find_map(|index| self.endpoints[index].transport.last_event_id(task_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::{Message, Part, Role, TaskState, TaskStatus};
+    use crate::a2a::models::TaskStatusUpdateEvent;
+
+    fn task_response(id: &str, state: &str) -> String {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {"kind": "task", "id": id, "contextId": "ctx-1", "status": {"state": state}}
+        })
+        .to_string()
+    }
+
+    fn card() -> Option<AgentCard> {
+        Some(AgentCard::new(
+            "Agent".to_string(),
+            "Replicated agent".to_string(),
+            "http://localhost".to_string(),
+            "1.0.0".to_string(),
+            vec!["text".to_string()],
+            vec!["text".to_string()],
+            AgentCapabilities::new(),
+            vec![],
+        ))
+    }
+
+    fn query(id: &str) -> TaskQueryParams {
+        serde_json::from_value(serde_json::json!({ "id": id })).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_to_next_endpoint() {
+        let mut down = mockito::Server::new_async().await;
+        let mut up = mockito::Server::new_async().await;
+        let failing = down.mock("POST", "/").with_status(503).expect(1).create_async().await;
+        let _ok = up
+            .mock("POST", "/")
+            .with_header("content-type", "application/json")
+            .with_body(task_response("task-1", "working"))
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = MultiEndpointClient::from_urls(vec![down.url(), up.url()], card()).unwrap();
+        assert_eq!(client.get_task(query("task-1"), None, None).await.unwrap().id, "task-1");
+        // The failed endpoint is skipped during its cooldown
+        client.get_task(query("task-1"), None, None).await.unwrap();
+        failing.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_application_errors_are_not_retried() {
+        let mut first = mockito::Server::new_async().await;
+        let mut second = mockito::Server::new_async().await;
+        let _not_found = first
+            .mock("POST", "/")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32001,"message":"Task not found"}}"#)
+            .create_async()
+            .await;
+        let unused = second.mock("POST", "/").expect(0).create_async().await;
+
+        let client = MultiEndpointClient::from_urls(vec![first.url(), second.url()], card()).unwrap();
+        let err = client.get_task(query("missing"), None, None).await.unwrap_err();
+        assert_eq!(err.code(), crate::a2a::jsonrpc::error_codes::TASK_NOT_FOUND);
+        unused.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_agent_errors_with_transport_data_are_not_retried() {
+        let mut first = mockito::Server::new_async().await;
+        let mut second = mockito::Server::new_async().await;
+        let _error = first
+            .mock("POST", "/")
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32603,"message":"Transport error: refused","data":{"transport":"connect","http_status":503}}}"#,
+            )
+            .create_async()
+            .await;
+        let unused = second.mock("POST", "/").expect(0).create_async().await;
+
+        let client = MultiEndpointClient::from_urls(vec![first.url(), second.url()], card()).unwrap();
+        let err = client.get_task(query("task-1"), None, None).await.unwrap_err();
+        assert_eq!(err.http_status(), None);
+        unused.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_sent_messages_are_not_retried() {
+        let mut busy = mockito::Server::new_async().await;
+        let mut up = mockito::Server::new_async().await;
+        let _busy = busy.mock("POST", "/").with_status(503).expect(1).create_async().await;
+        let ok = up
+            .mock("POST", "/")
+            .with_header("content-type", "application/json")
+            .with_body(task_response("task-1", "working"))
+            .expect(1)
+            .create_async()
+            .await;
+        let message = || MessageSendParams::new(Message::new(Role::User, vec![Part::text("hi".to_string())]));
+
+        // The message reached the busy endpoint, so it is not sent again
+        let client = MultiEndpointClient::from_urls(vec![busy.url(), up.url()], card()).unwrap();
+        let err = client.send_message(message(), None, None).await.unwrap_err();
+        assert_eq!(err.http_status(), Some(503));
+
+        // An endpoint refusing the connection never saw it
+        let client = MultiEndpointClient::from_urls(vec!["http://127.0.0.1:1".to_string(), up.url()], card()).unwrap();
+        client.send_message(message(), None, None).await.unwrap();
+        ok.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let mut first = mockito::Server::new_async().await;
+        let mut second = mockito::Server::new_async().await;
+        let _limited = first.mock("POST", "/").with_status(429).create_async().await;
+        let unused = second.mock("POST", "/").expect(0).create_async().await;
+
+        let client = MultiEndpointClient::from_urls(vec![first.url(), second.url()], card()).unwrap();
+        let err = client.get_task(query("task-1"), None, None).await.unwrap_err();
+        assert_eq!(err.http_status(), Some(429));
+        unused.assert_async().await;
+    }
+
+    /// A transport answering every call with tasks in its own context
+    struct StubTransport(&'static str);
+
+    impl StubTransport {
+        fn task(&self, id: &str) -> Task {
+            Task::new(self.0.to_string(), TaskStatus::new(TaskState::Working)).with_task_id(id.to_string())
+        }
+    }
+
+    #[async_trait]
+    impl ClientTransport for StubTransport {
+        async fn send_message(
+            &self,
+            params: MessageSendParams,
+            _context: Option<&ClientCallContext>,
+            _extensions: Option<Vec<String>>,
+        ) -> Result<TaskOrMessage, A2AError> {
+            Ok(TaskOrMessage::Task(self.task(&params.message.task_id.unwrap_or_default())))
+        }
+
+        async fn send_message_streaming<'a>(
+            &'a self,
+            _params: MessageSendParams,
+            _context: Option<&ClientCallContext>,
+            _extensions: Option<Vec<String>>,
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<TaskOrMessage, A2AError>> + Send + 'a>>, A2AError> {
+            let done = TaskStatusUpdateEvent::new(
+                "task-1".to_string(),
+                self.0.to_string(),
+                TaskStatus::new(TaskState::Completed),
+                true,
+            );
+            Ok(Box::pin(futures::stream::iter(vec![
+                Ok(TaskOrMessage::Task(self.task("task-1"))),
+                Ok(TaskOrMessage::TaskUpdate(done)),
+            ])))
+        }
+
+        async fn get_task(
+            &self,
+            request: TaskQueryParams,
+            _context: Option<&ClientCallContext>,
+            _extensions: Option<Vec<String>>,
+        ) -> Result<Task, A2AError> {
+            Ok(self.task(&request.id))
+        }
+
+        async fn cancel_task(
+            &self,
+            request: TaskIdParams,
+            _context: Option<&ClientCallContext>,
+            _extensions: Option<Vec<String>>,
+        ) -> Result<Task, A2AError> {
+            Ok(self.task(&request.id))
+        }
+
+        async fn set_task_callback(
+            &self,
+            request: TaskPushNotificationConfig,
+            _context: Option<&ClientCallContext>,
+            _extensions: Option<Vec<String>>,
+        ) -> Result<TaskPushNotificationConfig, A2AError> {
+            Ok(request)
+        }
+
+        async fn get_task_callback(
+            &self,
+            _request: GetTaskPushNotificationConfigParams,
+            _context: Option<&ClientCallContext>,
+            _extensions: Option<Vec<String>>,
+        ) -> Result<TaskPushNotificationConfig, A2AError> {
+            Err(A2AError::unsupported_operation("stub"))
+        }
+
+        async fn resubscribe<'a>(
+            &'a self,
+            _request: TaskIdParams,
+            _context: Option<&ClientCallContext>,
+            _extensions: Option<Vec<String>>,
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<ClientEvent, A2AError>> + Send + 'a>>, A2AError> {
+            Ok(Box::pin(futures::stream::empty()))
+        }
+
+        async fn get_card(
+            &self,
+            _context: Option<&ClientCallContext>,
+            _extensions: Option<Vec<String>>,
+        ) -> Result<AgentCard, A2AError> {
+            Err(A2AError::unsupported_operation("stub"))
+        }
+
+        async fn close(&self) -> Result<(), A2AError> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_and_sticky_streams() {
+        let client = MultiEndpointClient::new(vec![
+            Endpoint::new("a", Box::new(StubTransport("a"))),
+            Endpoint::new("b", Box::new(StubTransport("b"))),
+        ])
+        .unwrap()
+        .with_routing(Routing::RoundRobin);
+        assert_eq!(client.get_task(query("t"), None, None).await.unwrap().context_id, "a");
+        assert_eq!(client.get_task(query("t"), None, None).await.unwrap().context_id, "b");
+
+        // The stream opens on "a"; the task stays there while it streams
+        let message = Message::new(Role::User, vec![Part::text("hi".to_string())]);
+        let mut stream = client
+            .send_message_streaming(MessageSendParams::new(message), None, None)
+            .await
+            .unwrap();
+        stream.next().await.unwrap().unwrap();
+        assert_eq!(client.sticky_endpoint("task-1"), Some("a"));
+        for _ in 0..2 {
+            assert_eq!(client.get_task(query("task-1"), None, None).await.unwrap().context_id, "a");
+        }
+
+        stream.next().await.unwrap().unwrap();
+        assert_eq!(client.sticky_endpoint("task-1"), None);
+    }
+}
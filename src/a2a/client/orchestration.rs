@@ -0,0 +1,549 @@
+//! Fan-out/fan-in orchestration across several agents
+//!
+//! `FanOut` sends one message to a set of downstream agents concurrently and
+//! collects what each of them produced. When given a `ParentTask`, the child
+//! artifacts and status messages are republished on the parent's event queue as
+//! they arrive, so an `AgentExecutor` can present the combined work as a single
+//! task. Children that fail do not abort the others; once the quorum of
+//! successful children is reached, or the timeout expires, the remaining
+//! children are cancelled.
+
+use crate::a2a::client::client_trait::{Client, ClientEventOrMessage, TaskUpdateEvent};
+use crate::a2a::core_types::{Message, Part, Role, TaskState, TaskStatus};
+use crate::a2a::error::A2AError;
+use crate::a2a::models::{Artifact, Task, TaskArtifactUpdateEvent, TaskIdParams, TaskStatusUpdateEvent};
+use crate::a2a::server::agent_execution::RequestContext;
+use crate::a2a::server::events::{Event, EventQueue};
+use futures::StreamExt;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Metadata key naming the child agent that produced a forwarded event
+pub const CHILD_AGENT_METADATA_KEY: &str = "child_agent";
+
+/// Metadata key holding the child task id of a forwarded event
+pub const CHILD_TASK_METADATA_KEY: &str = "child_task_id";
+
+/// How a child call ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChildStatus {
+    /// The child completed, or replied with a message
+    Completed,
+    /// The child failed, was rejected or the call errored
+    Failed(String),
+    /// The child was still running when the timeout expired
+    TimedOut,
+    /// The child was cancelled because the quorum was already reached
+    Cancelled,
+}
+
+/// What one child agent produced
+#[derive(Debug, Clone)]
+pub struct ChildOutcome {
+    /// The name the agent was registered under
+    pub agent: String,
+    /// How the call ended
+    pub status: ChildStatus,
+    /// The last task snapshot received, if the agent created a task
+    pub task: Option<Task>,
+    /// Artifacts received from the agent
+    pub artifacts: Vec<Artifact>,
+    /// The reply, if the agent answered with a message instead of a task
+    pub message: Option<Message>,
+}
+
+impl ChildOutcome {
+    fn new(agent: &str) -> Self {
+        Self {
+            agent: agent.to_string(),
+            status: ChildStatus::TimedOut,
+            task: None,
+            artifacts: Vec::new(),
+            message: None,
+        }
+    }
+
+    /// Whether the child completed successfully
+    pub fn is_success(&self) -> bool {
+        self.status == ChildStatus::Completed
+    }
+}
+
+/// The combined result of a fan-out
+#[derive(Debug, Clone)]
+pub struct FanOutResult {
+    /// One outcome per agent, in registration order
+    pub children: Vec<ChildOutcome>,
+    /// Number of successful children required
+    pub quorum: usize,
+}
+
+impl FanOutResult {
+    /// Number of children that completed successfully
+    pub fn succeeded(&self) -> usize {
+        self.children.iter().filter(|child| child.is_success()).count()
+    }
+
+    /// Whether the quorum was reached
+    pub fn is_success(&self) -> bool {
+        self.succeeded() >= self.quorum
+    }
+
+    /// Returns the outcome of `agent`
+    pub fn child(&self, agent: &str) -> Option<&ChildOutcome> {
+        self.children.iter().find(|child| child.agent == agent)
+    }
+}
+
+/// The task that child results are published to
+#[derive(Clone)]
+pub struct ParentTask {
+    /// Id of the parent task
+    pub task_id: String,
+    /// Context id of the parent task
+    pub context_id: String,
+    /// Queue receiving the forwarded events
+    pub event_queue: Arc<dyn EventQueue>,
+}
+
+impl ParentTask {
+    /// Publishes to the task of the request being executed
+    pub fn from_context(context: &RequestContext, event_queue: Arc<dyn EventQueue>) -> Self {
+        Self {
+            task_id: context.task_id.clone().unwrap_or_default(),
+            context_id: context.context_id.clone().unwrap_or_default(),
+            event_queue,
+        }
+    }
+
+    fn metadata(agent: &str, child_task_id: Option<&str>) -> HashMap<String, serde_json::Value> {
+        let mut metadata = HashMap::from([(CHILD_AGENT_METADATA_KEY.to_string(), json!(agent))]);
+        if let Some(child_task_id) = child_task_id {
+            metadata.insert(CHILD_TASK_METADATA_KEY.to_string(), json!(child_task_id));
+        }
+        metadata
+    }
+
+    async fn forward_artifact(&self, agent: &str, child_task_id: Option<&str>, artifact: &Artifact) -> Result<(), A2AError> {
+        let mut artifact = artifact.clone();
+        // Prefix the id so artifacts from different children cannot collide
+        artifact.artifact_id = format!("{}/{}", agent, artifact.artifact_id);
+        artifact
+            .metadata
+            .get_or_insert_with(HashMap::new)
+            .extend(Self::metadata(agent, child_task_id));
+        let mut event = TaskArtifactUpdateEvent::new(self.task_id.clone(), self.context_id.clone(), artifact);
+        event.metadata = Some(Self::metadata(agent, child_task_id));
+        self.event_queue.enqueue_event(Event::TaskArtifactUpdate(event)).await
+    }
+
+    async fn publish_status(&self, state: TaskState, message: Option<Message>, r#final: bool) -> Result<(), A2AError> {
+        let mut status = TaskStatus::new(state);
+        if let Some(message) = message {
+            status = status.with_message(message);
+        }
+        let event = TaskStatusUpdateEvent::new(self.task_id.clone(), self.context_id.clone(), status, r#final);
+        self.event_queue.enqueue_event(Event::TaskStatusUpdate(event)).await
+    }
+
+    async fn forward_status(&self, agent: &str, child_task_id: Option<&str>, status: &TaskStatus) -> Result<(), A2AError> {
+        let Some(child_message) = status.message.as_deref() else {
+            return Ok(());
+        };
+        let message = Message::new(Role::Agent, child_message.parts.clone())
+            .with_task_id(self.task_id.clone())
+            .with_context_id(self.context_id.clone())
+            .with_metadata(Self::metadata(agent, child_task_id));
+        self.publish_status(TaskState::Working, Some(message), false).await
+    }
+}
+
+enum ChildUpdate {
+    Task(Box<(Task, Option<TaskUpdateEvent>)>),
+    Message(Message),
+    Failed(String),
+    Finished,
+}
+
+/// Sends a message to several agents and gathers their results
+#[derive(Clone, Default)]
+pub struct FanOut {
+    agents: Vec<(String, Arc<dyn Client>)>,
+    timeout: Option<Duration>,
+    quorum: Option<usize>,
+}
+
+impl FanOut {
+    /// Creates an orchestrator without agents
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a downstream agent under `name`
+    pub fn with_agent(mut self, name: &str, client: Arc<dyn Client>) -> Self {
+        self.agents.push((name.to_string(), client));
+        self
+    }
+
+    /// Gives up on children still running after `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Stops once `quorum` children succeeded, cancelling the rest
+    ///
+    /// Defaults to all children.
+    pub fn with_quorum(mut self, quorum: usize) -> Self {
+        self.quorum = Some(quorum);
+        self
+    }
+
+    /// Sends `message` to every agent and waits for the results
+    pub async fn run(&self, message: Message) -> Result<FanOutResult, A2AError> {
+        self.execute(message, None).await
+    }
+
+    /// Like `run`, also forwarding child events to `parent`
+    ///
+    /// The parent receives the child artifacts, status messages tagged with the
+    /// child agent, and a final `completed` or `failed` status depending on
+    /// whether the quorum was reached.
+    pub async fn run_into(&self, message: Message, parent: &ParentTask) -> Result<FanOutResult, A2AError> {
+        parent.publish_status(TaskState::Working, None, false).await?;
+        let result = self.execute(message, Some(parent)).await?;
+
+        let summary = format!("{} of {} agents succeeded", result.succeeded(), result.children.len());
+        let state = if result.is_success() { TaskState::Completed } else { TaskState::Failed };
+        let message = Message::new(Role::Agent, vec![Part::text(summary)])
+            .with_task_id(parent.task_id.clone())
+            .with_context_id(parent.context_id.clone());
+        parent.publish_status(state, Some(message), true).await?;
+        Ok(result)
+    }
+
+    async fn execute(&self, message: Message, parent: Option<&ParentTask>) -> Result<FanOutResult, A2AError> {
+        let quorum = self.quorum.unwrap_or(self.agents.len()).min(self.agents.len());
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let handles: Vec<JoinHandle<()>> = self
+            .agents
+            .iter()
+            .enumerate()
+            .map(|(index, (_, client))| tokio::spawn(call_child(index, client.clone(), message.clone(), sender.clone())))
+            .collect();
+        drop(sender);
+
+        let mut outcomes: Vec<ChildOutcome> = self.agents.iter().map(|(name, _)| ChildOutcome::new(name)).collect();
+        let mut forwarded: Vec<HashSet<String>> = vec![HashSet::new(); self.agents.len()];
+        let mut running: HashSet<usize> = (0..self.agents.len()).collect();
+        let deadline = self.timeout.map(|timeout| tokio::time::Instant::now() + timeout);
+
+        while !running.is_empty() && outcomes.iter().filter(|child| child.is_success()).count() < quorum {
+            let next = match deadline {
+                Some(deadline) => match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                    Ok(next) => next,
+                    Err(_) => break,
+                },
+                None => receiver.recv().await,
+            };
+            let Some((index, update)) = next else {
+                break;
+            };
+            let outcome = &mut outcomes[index];
+            match update {
+                ChildUpdate::Task(event) => {
+                    let (task, update) = *event;
+                    let agent = outcome.agent.clone();
+                    match &update {
+                        Some(TaskUpdateEvent::Artifact(event)) => {
+                            if forwarded[index].insert(event.artifact.artifact_id.clone()) {
+                                outcome.artifacts.push(event.artifact.clone());
+                            }
+                            if let Some(parent) = parent {
+                                parent.forward_artifact(&agent, Some(&task.id), &event.artifact).await?;
+                            }
+                        }
+                        Some(TaskUpdateEvent::Status(event)) => {
+                            if let Some(parent) = parent {
+                                parent.forward_status(&agent, Some(&task.id), &event.status).await?;
+                            }
+                        }
+                        None => {
+                            // Snapshots carry artifacts that may not have been streamed
+                            for artifact in task.artifacts.iter().flatten() {
+                                if forwarded[index].insert(artifact.artifact_id.clone()) {
+                                    outcome.artifacts.push(artifact.clone());
+                                    if let Some(parent) = parent {
+                                        parent.forward_artifact(&agent, Some(&task.id), artifact).await?;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    outcome.task = Some(task);
+                }
+                ChildUpdate::Message(reply) => {
+                    if let Some(parent) = parent {
+                        let status = TaskStatus::new(TaskState::Working).with_message(reply.clone());
+                        parent.forward_status(&outcome.agent, None, &status).await?;
+                    }
+                    outcome.message = Some(reply);
+                }
+                ChildUpdate::Failed(error) => {
+                    outcome.status = ChildStatus::Failed(error);
+                    running.remove(&index);
+                }
+                ChildUpdate::Finished => {
+                    outcome.status = match outcome.task.as_ref().map(|task| &task.status) {
+                        Some(status) if matches!(status.state, TaskState::Failed | TaskState::Rejected | TaskState::Canceled) => {
+                            ChildStatus::Failed(format!("Task ended in state {:?}", status.state))
+                        }
+                        _ => ChildStatus::Completed,
+                    };
+                    running.remove(&index);
+                }
+            }
+        }
+
+        // Whatever is still running is a laggard
+        let timed_out = deadline.is_some_and(|deadline| tokio::time::Instant::now() >= deadline);
+        for index in running {
+            handles[index].abort();
+            outcomes[index].status = if timed_out { ChildStatus::TimedOut } else { ChildStatus::Cancelled };
+            if let Some(task) = &outcomes[index].task {
+                let client = self.agents[index].1.clone();
+                let params = TaskIdParams::new(task.id.clone());
+                tokio::spawn(async move {
+                    if let Err(e) = client.cancel_task(params, None, None).await {
+                        tracing::warn!("Failed to cancel child task: {}", e);
+                    }
+                });
+            }
+        }
+
+        Ok(FanOutResult {
+            children: outcomes,
+            quorum,
+        })
+    }
+}
+
+impl std::fmt::Debug for FanOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FanOut")
+            .field("agents", &self.agents.iter().map(|(name, _)| name).collect::<Vec<_>>())
+            .field("timeout", &self.timeout)
+            .field("quorum", &self.quorum)
+            .finish()
+    }
+}
+
+async fn call_child(
+    index: usize,
+    client: Arc<dyn Client>,
+    message: Message,
+    sender: mpsc::UnboundedSender<(usize, ChildUpdate)>,
+) {
+    let mut stream = client.send_message(message, None, None, None).await;
+    while let Some(item) = stream.next().await {
+        let update = match item {
+            Ok(ClientEventOrMessage::Event(event)) => ChildUpdate::Task(Box::new(event)),
+            Ok(ClientEventOrMessage::Message(reply)) => ChildUpdate::Message(reply),
+            Err(e) => {
+                let _ = sender.send((index, ChildUpdate::Failed(e.to_string())));
+                return;
+            }
+        };
+        if sender.send((index, update)).is_err() {
+            return;
+        }
+    }
+    let _ = sender.send((index, ChildUpdate::Finished));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::client::client_trait::{ClientCallContext, ClientCallInterceptor, ClientEvent, Consumer};
+    use crate::a2a::models::{
+        AgentCard, GetTaskPushNotificationConfigParams, TaskPushNotificationConfig, TaskQueryParams,
+    };
+    use crate::a2a::server::events::InMemoryEventQueue;
+    use async_trait::async_trait;
+    use futures::Stream;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Replies with a completed task carrying one artifact, after `delay`
+    struct ScriptedClient {
+        name: &'static str,
+        delay: Duration,
+        fail: bool,
+        cancelled: Arc<AtomicBool>,
+    }
+
+    impl ScriptedClient {
+        fn new(name: &'static str, delay_ms: u64) -> Self {
+            Self {
+                name,
+                delay: Duration::from_millis(delay_ms),
+                fail: false,
+                cancelled: Arc::new(AtomicBool::new(false)),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Client for ScriptedClient {
+        async fn send_message<'life0, 'life1>(
+            &'life0 self,
+            _request: Message,
+            _context: Option<&'life1 ClientCallContext>,
+            _request_metadata: Option<HashMap<String, serde_json::Value>>,
+            _extensions: Option<Vec<String>>,
+        ) -> Pin<Box<dyn Stream<Item = Result<ClientEventOrMessage, A2AError>> + Send + 'life0>>
+        where
+            'life1: 'life0,
+        {
+            let mut task = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working));
+            task.id = format!("{}-task", self.name);
+            let started = task.clone();
+            let artifact = Artifact::new(vec![Part::text(format!("from {}", self.name))]);
+            let (delay, fail) = (self.delay, self.fail);
+            Box::pin(async_stream::stream! {
+                yield Ok(ClientEventOrMessage::Event((started, None)));
+                tokio::time::sleep(delay).await;
+                if fail {
+                    yield Err(A2AError::internal("agent unavailable"));
+                    return;
+                }
+                let event = TaskArtifactUpdateEvent::new(task.id.clone(), "ctx".to_string(), artifact);
+                yield Ok(ClientEventOrMessage::Event((task.clone(), Some(TaskUpdateEvent::Artifact(event)))));
+                task.status = TaskStatus::new(TaskState::Completed);
+                yield Ok(ClientEventOrMessage::Event((task, None)));
+            })
+        }
+
+        async fn get_task(&self, _: TaskQueryParams, _: Option<&ClientCallContext>, _: Option<Vec<String>>) -> Result<Task, A2AError> {
+            Err(A2AError::unsupported_operation("get_task"))
+        }
+
+        async fn cancel_task(&self, request: TaskIdParams, _: Option<&ClientCallContext>, _: Option<Vec<String>>) -> Result<Task, A2AError> {
+            self.cancelled.store(true, Ordering::SeqCst);
+            let mut task = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Canceled));
+            task.id = request.id;
+            Ok(task)
+        }
+
+        async fn set_task_callback(
+            &self,
+            _: TaskPushNotificationConfig,
+            _: Option<&ClientCallContext>,
+            _: Option<Vec<String>>,
+        ) -> Result<TaskPushNotificationConfig, A2AError> {
+            Err(A2AError::unsupported_operation("set_task_callback"))
+        }
+
+        async fn get_task_callback(
+            &self,
+            _: GetTaskPushNotificationConfigParams,
+            _: Option<&ClientCallContext>,
+            _: Option<Vec<String>>,
+        ) -> Result<TaskPushNotificationConfig, A2AError> {
+            Err(A2AError::unsupported_operation("get_task_callback"))
+        }
+
+        async fn resubscribe<'a>(
+            &'a self,
+            _: TaskIdParams,
+            _: Option<&ClientCallContext>,
+            _: Option<Vec<String>>,
+        ) -> Pin<Box<dyn Stream<Item = Result<ClientEvent, A2AError>> + Send + 'a>> {
+            Box::pin(futures::stream::empty())
+        }
+
+        async fn get_card(&self, _: Option<&ClientCallContext>, _: Option<Vec<String>>) -> Result<AgentCard, A2AError> {
+            Err(A2AError::unsupported_operation("get_card"))
+        }
+
+        async fn add_event_consumer(&self, _consumer: Consumer) {}
+
+        async fn add_request_middleware(&self, _middleware: Box<dyn ClientCallInterceptor>) {}
+
+        async fn consume(&self, _event: Option<ClientEventOrMessage>, _card: &AgentCard) -> Result<(), A2AError> {
+            Ok(())
+        }
+    }
+
+    fn message() -> Message {
+        Message::new(Role::User, vec![Part::text("go".to_string())])
+    }
+
+    #[tokio::test]
+    async fn test_aggregates_children_and_tolerates_failures() {
+        let mut failing = ScriptedClient::new("c", 0);
+        failing.fail = true;
+        let fan_out = FanOut::new()
+            .with_agent("a", Arc::new(ScriptedClient::new("a", 0)))
+            .with_agent("b", Arc::new(ScriptedClient::new("b", 10)))
+            .with_agent("c", Arc::new(failing))
+            .with_quorum(2);
+
+        let queue = Arc::new(InMemoryEventQueue::new().unwrap());
+        let parent = ParentTask {
+            task_id: "parent".to_string(),
+            context_id: "ctx".to_string(),
+            event_queue: queue.clone(),
+        };
+        let result = fan_out.run_into(message(), &parent).await.unwrap();
+        assert!(result.is_success());
+        assert_eq!(result.succeeded(), 2);
+        assert!(matches!(result.child("c").unwrap().status, ChildStatus::Failed(_)));
+
+        let mut artifact_ids = Vec::new();
+        let mut final_state = None;
+        while let Ok(event) = queue.dequeue_event(true).await {
+            match event {
+                Event::TaskArtifactUpdate(update) => {
+                    assert_eq!(update.task_id, "parent");
+                    artifact_ids.push(update.artifact.artifact_id);
+                }
+                Event::TaskStatusUpdate(update) if update.r#final => final_state = Some(update.status.state),
+                _ => {}
+            }
+        }
+        assert_eq!(artifact_ids.len(), 2);
+        assert!(artifact_ids.iter().any(|id| id.starts_with("a/")));
+        assert_eq!(final_state, Some(TaskState::Completed));
+    }
+
+    #[tokio::test]
+    async fn test_cancels_laggards_after_quorum_and_timeout() {
+        let slow = ScriptedClient::new("slow", 5_000);
+        let cancelled = slow.cancelled.clone();
+        let result = FanOut::new()
+            .with_agent("fast", Arc::new(ScriptedClient::new("fast", 0)))
+            .with_agent("slow", Arc::new(slow))
+            .with_quorum(1)
+            .run(message())
+            .await
+            .unwrap();
+        assert!(result.is_success());
+        assert_eq!(result.child("slow").unwrap().status, ChildStatus::Cancelled);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(cancelled.load(Ordering::SeqCst));
+
+        let result = FanOut::new()
+            .with_agent("slow", Arc::new(ScriptedClient::new("slow", 5_000)))
+            .with_timeout(Duration::from_millis(50))
+            .run(message())
+            .await
+            .unwrap();
+        assert!(!result.is_success());
+        assert_eq!(result.child("slow").unwrap().status, ChildStatus::TimedOut);
+    }
+}
@@ -0,0 +1,129 @@
+//! Following paged results
+//!
+//! List calls that return results in pages pass back a token for the next
+//! page. `paginate` turns such a call into a `Stream` of items that requests
+//! each page when the one before it runs out, so callers don't write cursor
+//! loops.
+//!
+//! The protocol methods this is meant for, `tasks/list` and paged
+//! `tasks/pushNotificationConfig/list`, are not implemented by the transports
+//! yet. Client methods such as `list_tasks_stream` should be built on
+//! `paginate` once they are.
+//!
+//! ```ignore
+//! let tasks = paginate(|token| fetch_page(filter.clone(), token));
+//! ```
+
+use crate::a2a::error::A2AError;
+use futures::Stream;
+use std::future::Future;
+
+/// One page of a listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Token of the following page; `None` or empty on the last page
+    pub next_page_token: Option<String>,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>) -> Self {
+        Self {
+            items,
+            next_page_token: None,
+        }
+    }
+
+    pub fn with_next_page_token(mut self, token: String) -> Self {
+        self.next_page_token = Some(token);
+        self
+    }
+
+    /// The token to request next, if there are more pages
+    fn next(&self) -> Option<String> {
+        self.next_page_token.clone().filter(|token| !token.is_empty())
+    }
+}
+
+/// Streams the items of every page returned by `fetch`
+///
+/// `fetch` is called with `None` for the first page and with the previous
+/// page's token after that. The stream ends after the last page, or after
+/// the first error. Fetching stops if a page repeats the token it was
+/// requested with, to avoid looping on a misbehaving server.
+pub fn paginate<T, F, Fut>(mut fetch: F) -> impl Stream<Item = Result<T, A2AError>>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<Page<T>, A2AError>>,
+{
+    async_stream::stream! {
+        let mut token = None;
+        loop {
+            let page = match fetch(token.clone()).await {
+                Ok(page) => page,
+                Err(err) => {
+                    yield Err(err);
+                    break;
+                }
+            };
+            let next = page.next();
+            for item in page.items {
+                yield Ok(item);
+            }
+            match next {
+                Some(next) if Some(&next) != token.as_ref() => token = Some(next),
+                _ => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_pages_are_followed_until_the_last_one() {
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let seen = requested.clone();
+        let items: Vec<_> = paginate(move |token: Option<String>| {
+            seen.lock().unwrap().push(token.clone());
+            async move {
+                Ok(match token.as_deref() {
+                    None => Page::new(vec![1, 2]).with_next_page_token("p2".to_string()),
+                    Some("p2") => Page::new(vec![]).with_next_page_token("p3".to_string()),
+                    _ => Page::new(vec![3]).with_next_page_token(String::new()),
+                })
+            }
+        })
+        .map(Result::unwrap)
+        .collect()
+        .await;
+        assert_eq!(items, vec![1, 2, 3]);
+        assert_eq!(
+            *requested.lock().unwrap(),
+            vec![None, Some("p2".to_string()), Some("p3".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_errors_and_repeated_tokens_end_the_stream() {
+        let items: Vec<_> = paginate(|token: Option<String>| async move {
+            match token {
+                None => Ok(Page::new(vec![1]).with_next_page_token("p2".to_string())),
+                Some(_) => Err(A2AError::internal("listing failed")),
+            }
+        })
+        .collect()
+        .await;
+        assert_eq!(items.len(), 2);
+        assert!(items[1].is_err());
+
+        let items: Vec<_> = paginate(|_token| async { Ok(Page::new(vec![1]).with_next_page_token("same".to_string())) })
+            .collect()
+            .await;
+        assert_eq!(items.len(), 2);
+    }
+}
@@ -0,0 +1,465 @@
+//! Browser transport built on the fetch API
+//!
+//! `FetchTransport` speaks the same JSON-RPC protocol as `JsonRpcTransport`,
+//! but sends requests with the global `fetch` of a window or worker and reads
+//! streaming responses from the body's `ReadableStream`, so it runs on
+//! wasm32-unknown-unknown without reqwest or tokio. The agent card is taken
+//! as given, since the page usually fetched it already.
+
+use crate::a2a::client::client_trait::{ClientCallContext, ClientCallInterceptor, ClientEvent, ClientTransport, TaskUpdateEvent};
+use crate::a2a::client::transports::sse::{SseDecoder, SseEvent};
+use crate::a2a::core_types::{TaskState, TaskStatus};
+use crate::a2a::error::A2AError;
+use crate::a2a::jsonrpc::{JSONRPCError, SendStreamingMessageResult};
+use crate::a2a::models::*;
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// Marks a future or stream holding JavaScript handles as `Send`
+///
+/// wasm32-unknown-unknown has a single thread, so these values never cross
+/// threads. On other targets the web-sys bindings panic when called, before
+/// any such value exists.
+struct SingleThreaded<T>(T);
+
+// SAFETY: see the type documentation
+unsafe impl<T> Send for SingleThreaded<T> {}
+
+impl<F: Future> Future for SingleThreaded<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        // SAFETY: the inner value is never moved out of the pinned wrapper
+        unsafe { self.map_unchecked_mut(|s| &mut s.0) }.poll(cx)
+    }
+}
+
+impl<S: Stream> Stream for SingleThreaded<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        // SAFETY: as for the future implementation
+        unsafe { self.map_unchecked_mut(|s| &mut s.0) }.poll_next(cx)
+    }
+}
+
+fn js_error(context: &str, value: JsValue) -> A2AError {
+    let detail = value.as_string().unwrap_or_else(|| format!("{:?}", value));
+    A2AError::transport_error(format!("{}: {}", context, detail))
+}
+
+/// Calls the `fetch` of the current window or worker
+fn global_fetch(request: &web_sys::Request) -> Result<js_sys::Promise, A2AError> {
+    let global = js_sys::global();
+    if let Some(window) = global.dyn_ref::<web_sys::Window>() {
+        return Ok(window.fetch_with_request(request));
+    }
+    if let Some(worker) = global.dyn_ref::<web_sys::WorkerGlobalScope>() {
+        return Ok(worker.fetch_with_request(request));
+    }
+    Err(A2AError::transport_error("fetch is not available in this environment".to_string()))
+}
+
+/// Extracts the result of a JSON-RPC response
+fn jsonrpc_result(mut response: Value) -> Result<Value, A2AError> {
+    if let Some(error) = response.get("error") {
+        let error: JSONRPCError = serde_json::from_value(error.clone())
+            .map_err(|e| A2AError::json_error(format!("Failed to parse JSON-RPC error: {}", e)))?;
+        return Err(error.into());
+    }
+    response
+        .get_mut("result")
+        .map(Value::take)
+        .ok_or_else(|| A2AError::json_error("Invalid JSON-RPC response: missing result or error".to_string()))
+}
+
+fn task_or_message(result: Value) -> Result<TaskOrMessage, A2AError> {
+    if let Ok(task_or_message) = serde_json::from_value::<TaskOrMessage>(result.clone()) {
+        return Ok(task_or_message);
+    }
+    match serde_json::from_value::<SendStreamingMessageResult>(result) {
+        Ok(SendStreamingMessageResult::Task(task)) => Ok(TaskOrMessage::Task(task)),
+        Ok(SendStreamingMessageResult::Message(message)) => Ok(TaskOrMessage::Message(message)),
+        Ok(SendStreamingMessageResult::TaskStatusUpdate(update)) => Ok(TaskOrMessage::TaskUpdate(update)),
+        Ok(SendStreamingMessageResult::TaskArtifactUpdate(update)) => Ok(TaskOrMessage::TaskArtifactUpdateEvent(update)),
+        Err(e) => Err(A2AError::json_error(format!("Failed to parse response as Task or Message: {}", e))),
+    }
+}
+
+fn task_id(item: &TaskOrMessage) -> Option<&String> {
+    match item {
+        TaskOrMessage::Task(task) => Some(&task.id),
+        TaskOrMessage::TaskUpdate(update) => Some(&update.task_id),
+        TaskOrMessage::TaskArtifactUpdateEvent(update) => Some(&update.task_id),
+        TaskOrMessage::Message(message) => message.task_id.as_ref(),
+    }
+}
+
+type ItemStream<'a> = Pin<Box<dyn Stream<Item = Result<TaskOrMessage, A2AError>> + Send + 'a>>;
+
+/// JSON-RPC transport for browsers and other fetch-capable JavaScript hosts
+pub struct FetchTransport {
+    /// The URL endpoint for the agent
+    url: String,
+
+    /// Agent card (optional)
+    agent_card: Option<AgentCard>,
+
+    /// List of interceptors for requests
+    interceptors: Vec<Box<dyn ClientCallInterceptor>>,
+
+    /// Extensions to include in requests
+    extensions: Vec<String>,
+
+    /// Headers added to every request
+    headers: Vec<(String, String)>,
+
+    /// Resumption id of the last streamed event received, by task id
+    last_event_ids: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl FetchTransport {
+    /// Create a new fetch transport
+    pub fn new(url: String, agent_card: Option<AgentCard>) -> Self {
+        Self {
+            url,
+            agent_card,
+            interceptors: Vec::new(),
+            extensions: Vec::new(),
+            headers: Vec::new(),
+            last_event_ids: Arc::default(),
+        }
+    }
+
+    /// Add interceptors to the transport; they require an agent card
+    pub fn with_interceptors(mut self, interceptors: Vec<Box<dyn ClientCallInterceptor>>) -> Self {
+        self.interceptors = interceptors;
+        self
+    }
+
+    /// Set extensions for the transport
+    pub fn with_extensions(mut self, extensions: Vec<String>) -> Self {
+        self.extensions = extensions;
+        self
+    }
+
+    /// Add a header sent with every request
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Builds the request payload and HTTP options, applying interceptors
+    async fn prepare(
+        &self,
+        method: &str,
+        params: Value,
+        context: Option<&ClientCallContext>,
+    ) -> Result<(Value, HashMap<String, Value>), A2AError> {
+        let mut payload = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+            "id": uuid::Uuid::new_v4().to_string()
+        });
+        let mut http_kwargs: HashMap<String, Value> = context
+            .and_then(|ctx| ctx.http_kwargs.get("http_kwargs"))
+            .and_then(|v| v.as_object())
+            .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default();
+
+        if !self.interceptors.is_empty() {
+            let agent_card = self
+                .agent_card
+                .as_ref()
+                .ok_or_else(|| A2AError::invalid_request("No agent card available for interceptors"))?;
+            for interceptor in &self.interceptors {
+                (payload, http_kwargs) = interceptor
+                    .intercept(method, payload, http_kwargs, agent_card, context)
+                    .await?;
+            }
+        }
+        Ok((payload, http_kwargs))
+    }
+
+    fn build_headers(
+        &self,
+        accept: &str,
+        extensions: Option<&Vec<String>>,
+        http_kwargs: &HashMap<String, Value>,
+    ) -> Result<web_sys::Headers, JsValue> {
+        let headers = web_sys::Headers::new()?;
+        headers.set("Content-Type", "application/json")?;
+        headers.set("Accept", accept)?;
+
+        let extension_list = extensions.unwrap_or(&self.extensions);
+        if !extension_list.is_empty() {
+            headers.set("A2A-Extensions", &extension_list.join(","))?;
+        }
+        for (name, value) in &self.headers {
+            headers.set(name, value)?;
+        }
+        if let Some(extra) = http_kwargs.get("headers").and_then(|v| v.as_object()) {
+            for (name, value) in extra {
+                if let Some(value) = value.as_str() {
+                    headers.set(name, value)?;
+                }
+            }
+        }
+        Ok(headers)
+    }
+
+    /// POSTs a JSON-RPC request and checks the HTTP status
+    async fn post(
+        &self,
+        method: &str,
+        params: Value,
+        accept: &str,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<web_sys::Response, A2AError> {
+        let (payload, http_kwargs) = self.prepare(method, params, context).await?;
+
+        let headers = self
+            .build_headers(accept, extensions.as_ref(), &http_kwargs)
+            .map_err(|e| js_error("Invalid request headers", e))?;
+        let init = web_sys::RequestInit::new();
+        init.set_method("POST");
+        init.set_headers(&headers);
+        init.set_body(&JsValue::from_str(&payload.to_string()));
+        let request = web_sys::Request::new_with_str_and_init(&self.url, &init)
+            .map_err(|e| js_error("Failed to build request", e))?;
+
+        let response: web_sys::Response = JsFuture::from(global_fetch(&request)?)
+            .await
+            .map_err(|e| js_error("HTTP request failed", e))?
+            .dyn_into()
+            .map_err(|e| js_error("fetch did not return a Response", e))?;
+
+        if !response.ok() {
+            return Err(A2AError::http_error(
+                response.status(),
+                format!("HTTP error: {} {}", response.status(), response.status_text()),
+            ));
+        }
+        Ok(response)
+    }
+
+    async fn response_result(response: web_sys::Response) -> Result<Value, A2AError> {
+        let text = JsFuture::from(response.text().map_err(|e| js_error("Failed to read response", e))?)
+            .await
+            .map_err(|e| js_error("Failed to read response", e))?
+            .as_string()
+            .unwrap_or_default();
+        let value: Value = serde_json::from_str(&text)
+            .map_err(|e| A2AError::json_error(format!("Failed to parse JSON response: {}", e)))?;
+        jsonrpc_result(value)
+    }
+
+    async fn call(
+        &self,
+        method: &str,
+        params: Value,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<Value, A2AError> {
+        let response = self.post(method, params, "application/json", context, extensions).await?;
+        Self::response_result(response).await
+    }
+
+    async fn call_streaming(
+        &self,
+        method: &str,
+        params: Value,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<ItemStream<'_>, A2AError> {
+        let response = self.post(method, params, "text/event-stream", context, extensions).await?;
+        let content_type = response.headers().get("content-type").ok().flatten().unwrap_or_default();
+
+        if !content_type.contains("text/event-stream") {
+            // The agent answered with a single JSON-RPC response
+            let item = task_or_message(Self::response_result(response).await?);
+            return Ok(Box::pin(futures::stream::once(async move { item })));
+        }
+
+        let body = response
+            .body()
+            .ok_or_else(|| A2AError::transport_error("Streaming response has no body".to_string()))?;
+        let chunks = wasm_streams::ReadableStream::from_raw(body).into_stream();
+        let stream = async_stream::stream! {
+            let mut decoder = SseDecoder::new();
+            futures::pin_mut!(chunks);
+            while let Some(chunk) = chunks.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        yield Err(js_error("Stream error", e));
+                        break;
+                    }
+                };
+                for event in decoder.feed(&js_sys::Uint8Array::new(&chunk).to_vec()) {
+                    if let Some(item) = self.parse_event(event) {
+                        yield item;
+                    }
+                }
+            }
+            if let Some(item) = decoder.finish().and_then(|event| self.parse_event(event)) {
+                yield item;
+            }
+        };
+        Ok(Box::pin(SingleThreaded(stream)))
+    }
+
+    /// Parses a streamed event, remembering its resumption id
+    fn parse_event(&self, event: SseEvent) -> Option<Result<TaskOrMessage, A2AError>> {
+        if event.data.trim().is_empty() {
+            return None;
+        }
+        let item = serde_json::from_str::<Value>(&event.data)
+            .map_err(|e| A2AError::json_error(format!("Failed to parse SSE data as JSON: {}", e)))
+            .and_then(jsonrpc_result)
+            .and_then(task_or_message);
+        if let (Ok(item), Some(event_id)) = (&item, event.id) {
+            if let Some(task_id) = task_id(item) {
+                self.last_event_ids.lock().unwrap().insert(task_id.clone(), event_id);
+            }
+        }
+        Some(item)
+    }
+
+    async fn call_typed<P: serde::Serialize, R: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: P,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<R, A2AError> {
+        let params = serde_json::to_value(params)
+            .map_err(|e| A2AError::json_error(format!("Failed to serialize params: {}", e)))?;
+        let result = SingleThreaded(self.call(method, params, context, extensions)).await?;
+        serde_json::from_value(result)
+            .map_err(|e| A2AError::json_error(format!("Failed to parse {} response: {}", method, e)))
+    }
+}
+
+#[async_trait]
+impl ClientTransport for FetchTransport {
+    async fn send_message(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<TaskOrMessage, A2AError> {
+        let result: Value = self.call_typed("message/send", params, context, extensions).await?;
+        task_or_message(result)
+    }
+
+    async fn send_message_streaming<'a>(
+        &'a self,
+        params: MessageSendParams,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<ItemStream<'a>, A2AError> {
+        let params = serde_json::to_value(params)
+            .map_err(|e| A2AError::json_error(format!("Failed to serialize params: {}", e)))?;
+        SingleThreaded(self.call_streaming("message/stream", params, context, extensions)).await
+    }
+
+    async fn get_task(
+        &self,
+        request: TaskQueryParams,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<Task, A2AError> {
+        self.call_typed("tasks/get", request, context, extensions).await
+    }
+
+    async fn cancel_task(
+        &self,
+        request: TaskIdParams,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<Task, A2AError> {
+        self.call_typed("tasks/cancel", request, context, extensions).await
+    }
+
+    async fn set_task_callback(
+        &self,
+        request: TaskPushNotificationConfig,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.call_typed("tasks/pushNotificationConfig/set", request, context, extensions).await
+    }
+
+    async fn get_task_callback(
+        &self,
+        request: GetTaskPushNotificationConfigParams,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.call_typed("tasks/pushNotificationConfig/get", request, context, extensions).await
+    }
+
+    async fn resubscribe<'a>(
+        &'a self,
+        request: TaskIdParams,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<ClientEvent, A2AError>> + Send + 'a>>, A2AError> {
+        let mut request = request;
+        if request.from_event_id.is_none() {
+            request.from_event_id = self.last_event_id(&request.id);
+        }
+        let params = serde_json::to_value(request)
+            .map_err(|e| A2AError::json_error(format!("Failed to serialize params: {}", e)))?;
+        let stream = SingleThreaded(self.call_streaming("tasks/resubscribe", params, context, extensions)).await?;
+
+        Ok(Box::pin(stream.map(|item| match item? {
+            TaskOrMessage::Task(task) => Ok((task, None)),
+            TaskOrMessage::TaskUpdate(update) => {
+                // A resumed stream may start without a task snapshot
+                let task = Task::new(update.context_id.clone(), update.status.clone()).with_task_id(update.task_id.clone());
+                Ok((task, Some(TaskUpdateEvent::Status(update))))
+            }
+            TaskOrMessage::TaskArtifactUpdateEvent(update) => {
+                let task = Task::new(update.context_id.clone(), TaskStatus::new(TaskState::Working))
+                    .with_task_id(update.task_id.clone());
+                Ok((task, Some(TaskUpdateEvent::Artifact(update))))
+            }
+            TaskOrMessage::Message(_) => Err(A2AError::invalid_response("Unexpected message in resubscribe stream")),
+        })))
+    }
+
+    async fn get_card(
+        &self,
+        context: Option<&ClientCallContext>,
+        extensions: Option<Vec<String>>,
+    ) -> Result<AgentCard, A2AError> {
+        match &self.agent_card {
+            Some(card) if card.supports_authenticated_extended_card.unwrap_or(false) => {
+                self.call_typed("agent/authenticatedExtendedCard", Value::Null, context, extensions)
+                    .await
+            }
+            Some(card) => Ok(card.clone()),
+            None => Err(A2AError::invalid_request("FetchTransport requires an agent card")),
+        }
+    }
+
+    fn last_event_id(&self, task_id: &str) -> Option<String> {
+        self.last_event_ids.lock().unwrap().get(task_id).cloned()
+    }
+
+    async fn close(&self) -> Result<(), A2AError> {
+        Ok(())
+    }
+}
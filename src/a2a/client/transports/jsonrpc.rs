@@ -3,8 +3,13 @@
 //! This module provides a JSON-RPC transport that mirrors the functionality
 //! of a2a-python's JsonRpcTransport.
 
-use crate::a2a::client::client_trait::{ClientCallContext, ClientTransport, ClientEvent, ClientCallInterceptor};
+use crate::a2a::client::client_trait::{ClientCallContext, ClientTransport, ClientEvent, ClientCallInterceptor, TaskUpdateEvent};
 use crate::a2a::client::card_resolver::A2ACardResolver;
+use crate::a2a::client::errors::ClientError;
+use crate::a2a::client::validation::{self, ResponseValidation};
+use crate::a2a::client::auth::challenge::{
+    APPLIED_REQUIREMENT_KWARG, CHALLENGES_KWARG, DEFAULT_MAX_AUTH_ATTEMPTS, REJECTED_REQUIREMENTS_KWARG,
+};
 use crate::a2a::models::*;
 use crate::a2a::core_types::*;
 use crate::a2a::error::A2AError;
@@ -16,6 +21,7 @@ use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::pin::Pin;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 /// Create a JSON-RPC 2.0 request
@@ -32,7 +38,7 @@ fn create_jsonrpc_request(method: &str, params: Value) -> Result<Value, A2AError
 fn parse_jsonrpc_response(value: Value) -> Result<JSONRPCResponse, A2AError> {
     if let Some(error) = value.get("error") {
         let error: JSONRPCError = serde_json::from_value(error.clone())
-            .map_err(|e| A2AError::json_error(format!("Failed to parse JSON-RPC error: {}", e)))?;
+            .map_err(|e| A2AError::from(ClientError::Protocol(format!("Failed to parse JSON-RPC error: {}", e))))?;
         Ok(JSONRPCResponse::Error(JSONRPCErrorResponse {
             id: None,
             jsonrpc: "2.0".to_string(),
@@ -45,7 +51,7 @@ fn parse_jsonrpc_response(value: Value) -> Result<JSONRPCResponse, A2AError> {
             jsonrpc: "2.0".to_string(),
         }))
     } else {
-        Err(A2AError::json_error("Invalid JSON-RPC response: missing result or error".to_string()))
+        Err(A2AError::from(ClientError::Protocol("Invalid JSON-RPC response: missing result or error".to_string())))
     }
 }
 
@@ -71,6 +77,15 @@ pub struct JsonRpcTransport {
     
     /// Whether we need to fetch the extended card
     needs_extended_card: bool,
+    
+    /// Resumption id of the last streamed event received, by task id
+    last_event_ids: Arc<Mutex<HashMap<String, String>>>,
+    
+    /// Attempts of a call across security requirement sets on 401 and 403
+    max_auth_attempts: usize,
+    
+    /// What to do with responses that do not match the typed models
+    response_validation: ResponseValidation,
 }
 
 impl JsonRpcTransport {
@@ -96,6 +111,9 @@ impl JsonRpcTransport {
             interceptors: Vec::new(),
             extensions: Vec::new(),
             needs_extended_card,
+            last_event_ids: Arc::default(),
+            max_auth_attempts: DEFAULT_MAX_AUTH_ATTEMPTS,
+            response_validation: ResponseValidation::Off,
         })
     }
     
@@ -125,6 +143,9 @@ impl JsonRpcTransport {
             interceptors: Vec::new(),
             extensions: config.extensions,
             needs_extended_card,
+            last_event_ids: Arc::default(),
+            max_auth_attempts: config.max_auth_attempts,
+            response_validation: config.response_validation,
         })
     }
     
@@ -146,6 +167,9 @@ impl JsonRpcTransport {
             interceptors: Vec::new(),
             extensions: Vec::new(),
             needs_extended_card,
+            last_event_ids: Arc::default(),
+            max_auth_attempts: DEFAULT_MAX_AUTH_ATTEMPTS,
+            response_validation: ResponseValidation::Off,
         }
     }
     
@@ -161,6 +185,24 @@ impl JsonRpcTransport {
         self
     }
     
+    /// Set how many attempts a call makes across the agent card's security
+    /// requirement sets when the agent answers 401 or 403
+    pub fn with_max_auth_attempts(mut self, max_auth_attempts: usize) -> Self {
+        self.max_auth_attempts = max_auth_attempts.max(1);
+        self
+    }
+    
+    /// Set how responses are checked against the typed models
+    pub fn with_response_validation(mut self, validation: ResponseValidation) -> Self {
+        self.response_validation = validation;
+        self
+    }
+    
+    /// Applies the response validation mode to `raw`, parsed into `typed`
+    fn validate<T: serde::Serialize>(&self, what: &str, raw: &Value, typed: &T) -> Result<(), A2AError> {
+        validation::check(self.response_validation, what, raw, typed)
+    }
+    
     /// Apply interceptors to a request
     async fn apply_interceptors(
         &self,
@@ -187,6 +229,22 @@ impl JsonRpcTransport {
         
         Ok((request_payload, http_kwargs))
     }
+
+    /// Feeds an HTTP error of `method`, sent with `http_kwargs`, back to the interceptors
+    fn notify_http_error(
+        &self,
+        method: &str,
+        error: &A2AError,
+        http_kwargs: &HashMap<String, Value>,
+        context: Option<&ClientCallContext>,
+    ) {
+        let Some(agent_card) = self.agent_card.as_ref() else {
+            return;
+        };
+        for interceptor in &self.interceptors {
+            interceptor.on_http_error(method, error, http_kwargs, agent_card, context);
+        }
+    }
     
     /// Build HTTP headers for a request
     fn build_headers(&self, extensions: Option<&Vec<String>>, http_kwargs: &HashMap<String, Value>) -> HeaderMap {
@@ -219,6 +277,111 @@ impl JsonRpcTransport {
         headers
     }
     
+    /// Send a JSON-RPC request over HTTP
+    ///
+    /// When the agent rejects the credentials applied by the interceptors
+    /// with 401 or 403, the request is retried with the next security
+    /// requirement set of the agent card, up to `max_auth_attempts`
+    /// attempts, before the rejection is surfaced.
+    async fn send_request(
+        &self,
+        method: &str,
+        request: Value,
+        context: Option<&ClientCallContext>,
+        extensions: Option<&Vec<String>>,
+        accept: &str,
+    ) -> Result<reqwest::Response, A2AError> {
+        // Get HTTP args from context
+        let base_kwargs: HashMap<String, Value> = context
+            .and_then(|ctx| ctx.http_kwargs.get("http_kwargs"))
+            .and_then(|v| v.as_object())
+            .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default();
+
+        let mut rejected: Vec<usize> = Vec::new();
+        let mut challenges: Vec<String> = Vec::new();
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let mut http_kwargs = base_kwargs.clone();
+            if !rejected.is_empty() {
+                http_kwargs.insert(REJECTED_REQUIREMENTS_KWARG.to_string(), serde_json::json!(rejected));
+                http_kwargs.insert(CHALLENGES_KWARG.to_string(), serde_json::json!(challenges));
+            }
+
+            // Apply interceptors
+            let (payload, mut http_kwargs) = self.apply_interceptors(method, request.clone(), http_kwargs, context).await?;
+
+            // Build headers
+            let mut headers = self.build_headers(extensions, &http_kwargs);
+            headers.insert("Accept", HeaderValue::from_str(accept).unwrap());
+
+            // Remove headers from http_kwargs since they're handled separately
+            http_kwargs.remove("headers");
+
+            // Extract request options
+            let timeout = http_kwargs.get("timeout")
+                .and_then(|v| v.as_u64())
+                .map(Duration::from_secs);
+
+            // Build request
+            let mut request_builder = self.client.post(&self.url).headers(headers).json(&payload);
+
+            if let Some(timeout_duration) = timeout {
+                request_builder = request_builder.timeout(timeout_duration);
+            }
+
+            // Send request
+            let response = request_builder
+                .send()
+                .await
+                .map_err(|e| A2AError::from(ClientError::from_reqwest("HTTP request failed", &e)))?;
+
+            // Check response status
+            let status = response.status();
+            if status.is_success() {
+                return Ok(response);
+            }
+            if status != reqwest::StatusCode::UNAUTHORIZED && status != reqwest::StatusCode::FORBIDDEN {
+                let error = A2AError::http_error(status.as_u16(), format!("HTTP error: {}", status));
+                self.notify_http_error(method, &error, &http_kwargs, context);
+                return Err(error);
+            }
+
+            challenges = response
+                .headers()
+                .get_all(reqwest::header::WWW_AUTHENTICATE)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .map(String::from)
+                .collect();
+            self.notify_http_error(
+                method,
+                &A2AError::http_challenge(status.as_u16(), format!("HTTP error: {}", status), challenges.clone()),
+                &http_kwargs,
+                context,
+            );
+            match http_kwargs.get(APPLIED_REQUIREMENT_KWARG).and_then(Value::as_u64) {
+                Some(index) if attempt < self.max_auth_attempts => {
+                    tracing::debug!(
+                        "Agent answered {} to security requirement {} (method: {}), retrying with the next one",
+                        status,
+                        index,
+                        method
+                    );
+                    rejected.push(index as usize);
+                }
+                _ => {
+                    return Err(A2AError::http_challenge(
+                        status.as_u16(),
+                        format!("HTTP error: {}", status),
+                        challenges,
+                    ))
+                }
+            }
+        }
+    }
+    
     /// Send a JSON-RPC request and get the response
     async fn send_jsonrpc_request(
         &self,
@@ -228,58 +391,13 @@ impl JsonRpcTransport {
         extensions: Option<Vec<String>>,
     ) -> Result<Value, A2AError> {
         let request = create_jsonrpc_request(method, params)?;
-        
-        // Get HTTP args from context
-        let http_kwargs = context
-            .and_then(|ctx| ctx.http_kwargs.get("http_kwargs"))
-            .and_then(|v| v.as_object())
-            .map(|obj| {
-                obj.iter()
-                    .filter_map(|(k, v)| Some((k.clone(), v.clone())))
-                    .collect()
-            })
-            .unwrap_or_default();
-        
-        // Apply interceptors
-        let (payload, mut http_kwargs) = self.apply_interceptors(method, request, http_kwargs, context).await?;
-        
-        // Build headers
-        let headers = self.build_headers(extensions.as_ref(), &http_kwargs);
-        
-        // Remove headers from http_kwargs since they're handled separately
-        http_kwargs.remove("headers");
-        
-        // Extract request options
-        let timeout = http_kwargs.get("timeout")
-            .and_then(|v| v.as_u64())
-            .map(Duration::from_secs);
-        
-        // Build request
-        let mut request_builder = self.client.post(&self.url).headers(headers).json(&payload);
-        
-        if let Some(timeout_duration) = timeout {
-            request_builder = request_builder.timeout(timeout_duration);
-        }
-        
-        // Send request
-        let response = request_builder
-            .send()
-            .await
-            .map_err(|e| A2AError::transport_error(format!("HTTP request failed: {}", e)))?;
-        
-        // Check response status
-        if !response.status().is_success() {
-            return Err(A2AError::http_error(
-                response.status().as_u16(),
-                format!("HTTP error: {}", response.status()),
-            ));
-        }
+        let response = self.send_request(method, request, context, extensions.as_ref(), "application/json").await?;
         
         // Parse response
         let response_value: Value = response
             .json()
             .await
-            .map_err(|e| A2AError::json_error(format!("Failed to parse JSON response: {}", e)))?;
+            .map_err(|e| A2AError::from(ClientError::Protocol(format!("Failed to parse JSON response: {}", e))))?;
         
         // Parse JSON-RPC response
         let jsonrpc_response = parse_jsonrpc_response(response_value)?;
@@ -287,7 +405,7 @@ impl JsonRpcTransport {
         match jsonrpc_response {
             JSONRPCResponse::Success(success_response) => Ok(success_response.result),
             JSONRPCResponse::Error(error_response) => {
-                Err(A2AError::jsonrpc_error(error_response.error.code, error_response.error.message))
+                Err(error_response.error.into())
             }
         }
     }
@@ -301,54 +419,7 @@ impl JsonRpcTransport {
         extensions: Option<Vec<String>>,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<TaskOrMessage, A2AError>> + Send + '_>>, A2AError> {
         let request = create_jsonrpc_request(method, params)?;
-        
-        // Get HTTP args from context
-        let http_kwargs = context
-            .and_then(|ctx| ctx.http_kwargs.get("http_kwargs"))
-            .and_then(|v| v.as_object())
-            .map(|obj| {
-                obj.iter()
-                    .filter_map(|(k, v)| Some((k.clone(), v.clone())))
-                    .collect()
-            })
-            .unwrap_or_default();
-        
-        // Apply interceptors
-        let (payload, mut http_kwargs) = self.apply_interceptors(method, request, http_kwargs, context).await?;
-        
-        // Build headers for SSE
-        let mut headers = self.build_headers(extensions.as_ref(), &http_kwargs);
-        
-        // Override Accept header for SSE
-        headers.insert("Accept", "text/event-stream".parse().unwrap());
-        
-        // Remove headers from http_kwargs since they're handled separately
-        http_kwargs.remove("headers");
-        
-        // Extract request options
-        let timeout = http_kwargs.get("timeout")
-            .and_then(|v| v.as_u64())
-            .map(Duration::from_secs);
-        
-        // Send the streaming POST request
-        let mut request_builder = self.client.post(&self.url).headers(headers).json(&payload);
-        
-        if let Some(timeout_duration) = timeout {
-            request_builder = request_builder.timeout(timeout_duration);
-        }
-        
-        let response = request_builder
-            .send()
-            .await
-            .map_err(|e| A2AError::transport_error(format!("HTTP request failed: {}", e)))?;
-        
-        // Check response status
-        if !response.status().is_success() {
-            return Err(A2AError::http_error(
-                response.status().as_u16(),
-                format!("HTTP error: {}", response.status()),
-            ));
-        }
+        let response = self.send_request(method, request, context, extensions.as_ref(), "text/event-stream").await?;
         
         // Check if response is SSE
         let content_type = response.headers().get("content-type")
@@ -360,25 +431,28 @@ impl JsonRpcTransport {
             let response_value: Value = response
                 .json()
                 .await
-                .map_err(|e| A2AError::json_error(format!("Failed to parse JSON response: {}", e)))?;
+                .map_err(|e| A2AError::from(ClientError::Protocol(format!("Failed to parse JSON response: {}", e))))?;
             
             let jsonrpc_response = parse_jsonrpc_response(response_value)?;
             
             let result = match jsonrpc_response {
                 JSONRPCResponse::Success(success_response) => {
+                    let raw = success_response.result;
                     // Try to parse the result as TaskOrMessage
-                    if let Ok(task_or_message) = serde_json::from_value::<TaskOrMessage>(success_response.result.clone()) {
+                    let result = if let Ok(task_or_message) = serde_json::from_value::<TaskOrMessage>(raw.clone()) {
                         task_or_message
-                    } else if let Ok(task) = serde_json::from_value::<Task>(success_response.result.clone()) {
+                    } else if let Ok(task) = serde_json::from_value::<Task>(raw.clone()) {
                         TaskOrMessage::Task(task)
-                    } else if let Ok(message) = serde_json::from_value::<Message>(success_response.result) {
+                    } else if let Ok(message) = serde_json::from_value::<Message>(raw.clone()) {
                         TaskOrMessage::Message(message)
                     } else {
-                        return Err(A2AError::json_error("Failed to parse response as Task or Message".to_string()));
-                    }
+                        return Err(A2AError::from(ClientError::Protocol("Failed to parse response as Task or Message".to_string())));
+                    };
+                    self.validate("Task or Message", &raw, &result)?;
+                    result
                 }
                 JSONRPCResponse::Error(error_response) => {
-                    return Err(A2AError::jsonrpc_error(error_response.error.code, error_response.error.message));
+                    return Err(error_response.error.into());
                 }
             };
             
@@ -429,7 +503,7 @@ impl JsonRpcTransport {
                         }
                     }
                     Err(e) => {
-                        yield Err(A2AError::transport_error(format!("Stream error: {}", e)));
+                        yield Err(A2AError::from(ClientError::from_reqwest("Stream error", &e)));
                         break;
                     }
                 }
@@ -458,6 +532,7 @@ impl JsonRpcTransport {
     fn parse_sse_message(&self, message: &str) -> Result<Option<TaskOrMessage>, A2AError> {
         let mut data_lines = Vec::new();
         let mut _event_type = None;
+        let mut event_id = None;
         
         // Parse SSE fields
         for line in message.lines() {
@@ -477,6 +552,8 @@ impl JsonRpcTransport {
             } else if line.starts_with("event:") {
                 let event_content = line[6..].trim_start();
                 _event_type = Some(event_content);
+            } else if let Some(id) = line.strip_prefix("id:") {
+                event_id = Some(id.trim_start());
             }
         }
         
@@ -486,6 +563,31 @@ impl JsonRpcTransport {
         
         // Combine data lines (SSE spec says to join with newline)
         let data = data_lines.join("\n");
+        let parsed = self.parse_sse_data(&data)?;
+        if let (Some(event_id), Some(task_or_message)) = (event_id, parsed.as_ref()) {
+            self.record_event_id(task_or_message, event_id);
+        }
+        Ok(parsed)
+    }
+
+    /// Remember the resumption id of a streamed event for its task
+    fn record_event_id(&self, task_or_message: &TaskOrMessage, event_id: &str) {
+        let task_id = match task_or_message {
+            TaskOrMessage::Task(task) => Some(&task.id),
+            TaskOrMessage::TaskUpdate(update) => Some(&update.task_id),
+            TaskOrMessage::TaskArtifactUpdateEvent(update) => Some(&update.task_id),
+            TaskOrMessage::Message(message) => message.task_id.as_ref(),
+        };
+        if let Some(task_id) = task_id {
+            self.last_event_ids
+                .lock()
+                .unwrap()
+                .insert(task_id.clone(), event_id.to_string());
+        }
+    }
+
+    /// Parse the data of a single SSE message
+    fn parse_sse_data(&self, data: &str) -> Result<Option<TaskOrMessage>, A2AError> {
         
         // Skip empty data
         if data.trim().is_empty() {
@@ -493,43 +595,49 @@ impl JsonRpcTransport {
         }
         
         // Parse JSON data
-        let json_value: Value = serde_json::from_str(&data)
-            .map_err(|e| A2AError::json_error(format!("Failed to parse SSE data as JSON: {} (data: {})", e, data)))?;
+        let json_value: Value = serde_json::from_str(data)
+            .map_err(|e| A2AError::from(ClientError::Protocol(format!("Failed to parse SSE data as JSON: {} (data: {})", e, data))))?;
         
         // Check if this is a JSON-RPC streaming response
         if let Some(result) = json_value.get("result") {
             // Try to parse as SendStreamingMessageResult
             if let Ok(streaming_result) = serde_json::from_value::<SendStreamingMessageResult>(result.clone()) {
+                self.validate("Streamed event", result, &streaming_result)?;
                 return Ok(Some(self.convert_streaming_result(streaming_result)?));
             }
         }
         
         // Try to parse directly as TaskOrMessage
         if let Ok(task_or_message) = serde_json::from_value::<TaskOrMessage>(json_value.clone()) {
+            self.validate("Streamed event", &json_value, &task_or_message)?;
             return Ok(Some(task_or_message));
         }
         
         // Try to parse as Task
         if let Ok(task) = serde_json::from_value::<Task>(json_value.clone()) {
+            self.validate("Streamed event", &json_value, &task)?;
             return Ok(Some(TaskOrMessage::Task(task)));
         }
         
         // Try to parse as Message
         if let Ok(message) = serde_json::from_value::<Message>(json_value.clone()) {
+            self.validate("Streamed event", &json_value, &message)?;
             return Ok(Some(TaskOrMessage::Message(message)));
         }
         
         // Try to parse as TaskStatusUpdateEvent
         if let Ok(task_update) = serde_json::from_value::<TaskStatusUpdateEvent>(json_value.clone()) {
+            self.validate("Streamed event", &json_value, &task_update)?;
             return Ok(Some(TaskOrMessage::TaskUpdate(task_update)));
         }
         
         // Try to parse as TaskArtifactUpdateEvent
         if let Ok(artifact_update) = serde_json::from_value::<TaskArtifactUpdateEvent>(json_value.clone()) {
+            self.validate("Streamed event", &json_value, &artifact_update)?;
             return Ok(Some(TaskOrMessage::TaskArtifactUpdateEvent(artifact_update)));
         }
         
-        Err(A2AError::json_error(format!("Failed to parse SSE data as TaskOrMessage. JSON: {}", json_value)))
+        Err(A2AError::from(ClientError::Protocol(format!("Failed to parse SSE data as TaskOrMessage. JSON: {}", json_value))))
     }
     
     /// Convert SendStreamingMessageResult to TaskOrMessage
@@ -563,15 +671,17 @@ impl ClientTransport for JsonRpcTransport {
         let result = self.send_jsonrpc_request("message/send", params_value, context, extensions).await?;
         
         // Try to parse as TaskOrMessage
-        if let Ok(task_or_message) = serde_json::from_value::<TaskOrMessage>(result.clone()) {
-            Ok(task_or_message)
+        let task_or_message = if let Ok(task_or_message) = serde_json::from_value::<TaskOrMessage>(result.clone()) {
+            task_or_message
         } else if let Ok(task) = serde_json::from_value::<Task>(result.clone()) {
-            Ok(TaskOrMessage::Task(task))
-        } else if let Ok(message) = serde_json::from_value::<Message>(result) {
-            Ok(TaskOrMessage::Message(message))
+            TaskOrMessage::Task(task)
+        } else if let Ok(message) = serde_json::from_value::<Message>(result.clone()) {
+            TaskOrMessage::Message(message)
         } else {
-            Err(A2AError::json_error("Failed to parse response as Task or Message".to_string()))
-        }
+            return Err(A2AError::from(ClientError::Protocol("Failed to parse response as Task or Message".to_string())));
+        };
+        self.validate("Task or Message", &result, &task_or_message)?;
+        Ok(task_or_message)
     }
     
     async fn send_message_streaming<'a>(
@@ -597,8 +707,10 @@ impl ClientTransport for JsonRpcTransport {
         
         let result = self.send_jsonrpc_request("tasks/get", params_value, context, extensions).await?;
         
-        serde_json::from_value(result)
-            .map_err(|e| A2AError::json_error(format!("Failed to parse Task response: {}", e)))
+        let response: Task = serde_json::from_value(result.clone())
+            .map_err(|e| A2AError::from(ClientError::Protocol(format!("Failed to parse Task response: {}", e))))?;
+        self.validate("Task", &result, &response)?;
+        Ok(response)
     }
     
     async fn cancel_task(
@@ -612,8 +724,10 @@ impl ClientTransport for JsonRpcTransport {
         
         let result = self.send_jsonrpc_request("tasks/cancel", params_value, context, extensions).await?;
         
-        serde_json::from_value(result)
-            .map_err(|e| A2AError::json_error(format!("Failed to parse Task response: {}", e)))
+        let response: Task = serde_json::from_value(result.clone())
+            .map_err(|e| A2AError::from(ClientError::Protocol(format!("Failed to parse Task response: {}", e))))?;
+        self.validate("Task", &result, &response)?;
+        Ok(response)
     }
     
     async fn set_task_callback(
@@ -627,8 +741,10 @@ impl ClientTransport for JsonRpcTransport {
         
         let result = self.send_jsonrpc_request("tasks/pushNotificationConfig/set", params_value, context, extensions).await?;
         
-        serde_json::from_value(result)
-            .map_err(|e| A2AError::json_error(format!("Failed to parse TaskPushNotificationConfig response: {}", e)))
+        let response: TaskPushNotificationConfig = serde_json::from_value(result.clone())
+            .map_err(|e| A2AError::from(ClientError::Protocol(format!("Failed to parse TaskPushNotificationConfig response: {}", e))))?;
+        self.validate("TaskPushNotificationConfig", &result, &response)?;
+        Ok(response)
     }
     
     async fn get_task_callback(
@@ -642,8 +758,10 @@ impl ClientTransport for JsonRpcTransport {
         
         let result = self.send_jsonrpc_request("tasks/pushNotificationConfig/get", params_value, context, extensions).await?;
         
-        serde_json::from_value(result)
-            .map_err(|e| A2AError::json_error(format!("Failed to parse TaskPushNotificationConfig response: {}", e)))
+        let response: TaskPushNotificationConfig = serde_json::from_value(result.clone())
+            .map_err(|e| A2AError::from(ClientError::Protocol(format!("Failed to parse TaskPushNotificationConfig response: {}", e))))?;
+        self.validate("TaskPushNotificationConfig", &result, &response)?;
+        Ok(response)
     }
     
     async fn resubscribe<'a>(
@@ -652,6 +770,10 @@ impl ClientTransport for JsonRpcTransport {
         context: Option<&ClientCallContext>,
         extensions: Option<Vec<String>>,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<ClientEvent, A2AError>> + Send + 'a>>, A2AError> {
+        let mut request = request;
+        if request.from_event_id.is_none() {
+            request.from_event_id = self.last_event_id(&request.id);
+        }
         let params_value = serde_json::to_value(request)
             .map_err(|e| A2AError::json_error(format!("Failed to serialize params: {}", e)))?;
         
@@ -660,15 +782,16 @@ impl ClientTransport for JsonRpcTransport {
         let mapped_stream = task_stream.map(|result| {
             match result {
                 Ok(TaskOrMessage::Task(task)) => Ok((task, None)),
-                Ok(TaskOrMessage::TaskUpdate(_task_update)) => {
-                    // For task updates, we need to construct a task
-                    // This is a simplified implementation
-                    Err(A2AError::unsupported_operation("Task updates not fully implemented in resubscribe"))
+                Ok(TaskOrMessage::TaskUpdate(task_update)) => {
+                    // A resumed stream may start without a task snapshot
+                    let task = Task::new(task_update.context_id.clone(), task_update.status.clone())
+                        .with_task_id(task_update.task_id.clone());
+                    Ok((task, Some(TaskUpdateEvent::Status(task_update))))
                 }
-                Ok(TaskOrMessage::TaskArtifactUpdateEvent(_artifact_update)) => {
-                    // For artifact updates, we need to construct a task
-                    // This is a simplified implementation
-                    Err(A2AError::unsupported_operation("Task artifact updates not fully implemented in resubscribe"))
+                Ok(TaskOrMessage::TaskArtifactUpdateEvent(artifact_update)) => {
+                    let task = Task::new(artifact_update.context_id.clone(), TaskStatus::new(TaskState::Working))
+                        .with_task_id(artifact_update.task_id.clone());
+                    Ok((task, Some(TaskUpdateEvent::Artifact(artifact_update))))
                 }
                 Ok(TaskOrMessage::Message(_)) => {
                     Err(A2AError::invalid_response("Unexpected message in resubscribe stream"))
@@ -700,8 +823,9 @@ impl ClientTransport for JsonRpcTransport {
         if self.needs_extended_card && card.supports_authenticated_extended_card.unwrap_or(false) {
             let result = self.send_jsonrpc_request("agent/authenticatedExtendedCard", Value::Null, context, extensions).await?;
             
-            let extended_card: AgentCard = serde_json::from_value(result)
-                .map_err(|e| A2AError::json_error(format!("Failed to parse extended AgentCard: {}", e)))?;
+            let extended_card: AgentCard = serde_json::from_value(result.clone())
+                .map_err(|e| A2AError::from(ClientError::Protocol(format!("Failed to parse extended AgentCard: {}", e))))?;
+            self.validate("AgentCard", &result, &extended_card)?;
             
             card = extended_card;
         }
@@ -709,6 +833,10 @@ impl ClientTransport for JsonRpcTransport {
         Ok(card)
     }
     
+    fn last_event_id(&self, task_id: &str) -> Option<String> {
+        self.last_event_ids.lock().unwrap().get(task_id).cloned()
+    }
+    
     async fn close(&self) -> Result<(), A2AError> {
         // reqwest::Client doesn't need explicit closing
         // This is a placeholder for any cleanup that might be needed
@@ -726,6 +854,9 @@ impl Clone for JsonRpcTransport {
             interceptors: Vec::new(), // Note: interceptors are not cloned as they're trait objects
             extensions: self.extensions.clone(),
             needs_extended_card: self.needs_extended_card,
+            last_event_ids: self.last_event_ids.clone(),
+            max_auth_attempts: self.max_auth_attempts,
+            response_validation: self.response_validation,
         }
     }
 }
@@ -756,4 +887,135 @@ mod tests {
         let transport = JsonRpcTransport::new("http://localhost:8080".to_string(), Some(card));
         assert!(transport.is_ok());
     }
+
+    #[test]
+    fn test_sse_event_id_is_tracked_per_task() {
+        let transport = JsonRpcTransport::new("http://localhost:8080".to_string(), None).unwrap();
+        let message = r#"id: ev1-2a
+data: {"jsonrpc":"2.0","id":1,"result":{"kind":"status-update","task_id":"t1","context_id":"c1","status":{"state":"working"},"final":false}}"#;
+
+        assert!(transport.parse_sse_message(message).unwrap().is_some());
+        assert_eq!(transport.last_event_id("t1").as_deref(), Some("ev1-2a"));
+        assert_eq!(transport.clone().last_event_id("t1").as_deref(), Some("ev1-2a"));
+        assert!(transport.last_event_id("t2").is_none());
+    }
+
+    fn secured_card(url: &str) -> AgentCard {
+        let schemes = HashMap::from([
+            (
+                "bearerAuth".to_string(),
+                SecurityScheme::HTTPAuth(HTTPAuthSecurityScheme {
+                    scheme: "bearer".to_string(),
+                    bearer_format: None,
+                    description: None,
+                }),
+            ),
+            (
+                "apiKey".to_string(),
+                SecurityScheme::APIKey(APIKeySecurityScheme {
+                    name: "X-API-Key".to_string(),
+                    in_: In::Header,
+                    description: None,
+                }),
+            ),
+        ]);
+        AgentCard::new(
+            "Test".to_string(),
+            "Test agent".to_string(),
+            url.to_string(),
+            "1.0.0".to_string(),
+            vec!["text/plain".to_string()],
+            vec!["text/plain".to_string()],
+            AgentCapabilities::new(),
+            vec![],
+        )
+        .with_security_schemes(schemes)
+        .with_security(vec![
+            HashMap::from([("bearerAuth".to_string(), vec![])]),
+            HashMap::from([("apiKey".to_string(), vec![])]),
+        ])
+    }
+
+    fn secured_transport(url: &str) -> JsonRpcTransport {
+        let mut store = crate::a2a::client::auth::InMemoryContextCredentialStore::new();
+        store.add_credential("bearerAuth", "expired-token");
+        store.add_credential("apiKey", "agent-key");
+        let interceptor = crate::a2a::client::auth::AuthInterceptor::new(Arc::new(store));
+        JsonRpcTransport::new(url.to_string(), Some(secured_card(url)))
+            .unwrap()
+            .with_interceptors(vec![Box::new(interceptor)])
+    }
+
+    #[tokio::test]
+    async fn test_retries_next_security_requirement_on_401() {
+        let mut server = mockito::Server::new_async().await;
+        let rejected = server
+            .mock("POST", "/")
+            .match_header("authorization", "Bearer expired-token")
+            .with_status(401)
+            .with_header("www-authenticate", r#"Bearer error="invalid_token""#)
+            .expect(1)
+            .create_async()
+            .await;
+        let accepted = server
+            .mock("POST", "/")
+            .match_header("x-api-key", "agent-key")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"kind":"task","id":"t1","contextId":"c1","status":{"state":"working"}}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let task = secured_transport(&server.url())
+            .get_task(TaskQueryParams::new("t1".to_string()), None, None)
+            .await
+            .unwrap();
+        assert_eq!(task.id, "t1");
+        rejected.assert_async().await;
+        accepted.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_surfaces_challenge_when_attempts_are_exhausted() {
+        let mut server = mockito::Server::new_async().await;
+        let rejected = server
+            .mock("POST", "/")
+            .with_status(403)
+            .with_header("www-authenticate", r#"Bearer error="insufficient_scope", scope="tasks:read""#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let error = secured_transport(&server.url())
+            .with_max_auth_attempts(1)
+            .get_task(TaskQueryParams::new("t1".to_string()), None, None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.http_status(), Some(403));
+        let challenges = crate::a2a::client::auth::parse_www_authenticate(&error.www_authenticate()[0]);
+        assert_eq!(challenges[0].scopes(), vec!["tasks:read"]);
+        rejected.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_strict_validation_rejects_unexpected_fields() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":{"kind":"task","id":"t1","contextId":"c1","status":{"state":"working"},"priority":"high"}}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let transport = JsonRpcTransport::new(server.url(), Some(secured_card(&server.url()))).unwrap();
+        let lenient = transport.clone().with_response_validation(ResponseValidation::Log);
+        assert!(lenient.get_task(TaskQueryParams::new("t1".to_string()), None, None).await.is_ok());
+        let error = transport
+            .with_response_validation(ResponseValidation::Strict)
+            .get_task(TaskQueryParams::new("t1".to_string()), None, None)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("unexpected field '$.priority'"), "{}", error);
+    }
 }
@@ -4,8 +4,15 @@
 //! matching a2a-python/src/a2a/client/transports/
 
 pub mod base;
+#[cfg(feature = "wasm")]
+pub mod fetch;
 pub mod grpc;
+#[cfg(feature = "client")]
 pub mod jsonrpc;
 pub mod rest;
+pub mod sse;
 
 // Re-export transport types
+#[cfg(feature = "wasm")]
+pub use fetch::FetchTransport;
+pub use sse::{SseDecoder, SseEvent};
@@ -0,0 +1,130 @@
+//! Incremental Server-Sent Events decoding
+//!
+//! `SseDecoder` turns the raw chunks of an `text/event-stream` body into
+//! events, independently of the HTTP stack that produced them. Chunks may
+//! split lines, events and UTF-8 sequences anywhere.
+
+/// A single decoded event
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SseEvent {
+    /// The `event` field, if present
+    pub event: Option<String>,
+    /// The `data` lines joined with newlines
+    pub data: String,
+    /// The `id` field, if present
+    pub id: Option<String>,
+}
+
+/// Decoder of an event stream fed chunk by chunk
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buffer: Vec<u8>,
+    current: SseEvent,
+    has_data: bool,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds a chunk and returns the events it completed
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let mut line: Vec<u8> = self.buffer.drain(..=end).collect();
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            if let Some(event) = self.process_line(&String::from_utf8_lossy(&line)) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// Ends the stream, returning an event left without a trailing blank line
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        if !self.buffer.is_empty() {
+            let line = std::mem::take(&mut self.buffer);
+            let line = String::from_utf8_lossy(&line).trim_end_matches('\r').to_string();
+            if let Some(event) = self.process_line(&line) {
+                return Some(event);
+            }
+        }
+        self.dispatch()
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "data" => {
+                if self.has_data {
+                    self.current.data.push('\n');
+                }
+                self.current.data.push_str(value);
+                self.has_data = true;
+            }
+            "event" => self.current.event = Some(value.to_string()),
+            "id" => self.current.id = Some(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event = std::mem::take(&mut self.current);
+        let has_data = std::mem::replace(&mut self.has_data, false);
+        has_data.then_some(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_split_across_chunks() {
+        let body = "id: ev1-1\ndata: {\"a\":\ndata: 1}\n\n: keep-alive\n\r\nevent: custom\r\ndata: two\r\n\r\n";
+        let mut decoder = SseDecoder::new();
+        let events: Vec<SseEvent> = body
+            .as_bytes()
+            .chunks(4)
+            .flat_map(|chunk| decoder.feed(chunk))
+            .collect();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].id.as_deref(), Some("ev1-1"));
+        assert_eq!(events[0].data, "{\"a\":\n1}");
+        assert_eq!(events[1].event.as_deref(), Some("custom"));
+        assert_eq!(events[1].data, "two");
+        assert!(decoder.finish().is_none());
+    }
+
+    #[test]
+    fn test_multibyte_characters_split_across_chunks() {
+        let body = "data: héllo\n\n".as_bytes();
+        let mut decoder = SseDecoder::new();
+        assert!(decoder.feed(&body[..8]).is_empty());
+        let events = decoder.feed(&body[8..]);
+        assert_eq!(events[0].data, "héllo");
+    }
+
+    #[test]
+    fn test_finish_flushes_unterminated_event() {
+        let mut decoder = SseDecoder::new();
+        assert!(decoder.feed(b"data: last").is_empty());
+        assert_eq!(decoder.finish().unwrap().data, "last");
+    }
+}
@@ -0,0 +1,171 @@
+//! Schema validation of agent responses
+//!
+//! Deserializing a response into the typed models silently drops fields the
+//! models do not know and fills in defaults for fields the agent left out,
+//! so a misbehaving agent often shows up much later as a confusing failure.
+//! With `ClientConfig::with_response_validation`, the JSON-RPC transport
+//! compares every response with the typed value it was parsed into and
+//! reports the differences as `SchemaViolation`s: fields the models do not
+//! define, and fields the models expect that the agent did not send. A
+//! response lacking a field the models require fails to parse in any mode.
+//!
+//! Field names are compared ignoring case and underscores, so the snake_case
+//! and camelCase spellings the models accept are equivalent. Null values,
+//! empty lists and empty objects count as absent.
+
+#[cfg(feature = "client")]
+use crate::a2a::client::errors::ClientError;
+#[cfg(feature = "client")]
+use crate::a2a::error::A2AError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+#[cfg(feature = "client")]
+use tracing::warn;
+
+/// What the client does with responses that do not match the typed models
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseValidation {
+    /// Responses are not checked
+    #[default]
+    Off,
+    /// Violations are logged as warnings and the response is used
+    Log,
+    /// Violations fail the call with a protocol error
+    Strict,
+}
+
+/// A difference between a response and the typed model it was parsed into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaViolation {
+    /// The response has a field the model does not define
+    ExtraField(String),
+    /// The response lacks a field the model expects
+    MissingField(String),
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaViolation::ExtraField(path) => write!(f, "unexpected field '{}'", path),
+            SchemaViolation::MissingField(path) => write!(f, "missing field '{}'", path),
+        }
+    }
+}
+
+/// The differences between the `raw` response and `typed`, the value parsed from it
+pub fn schema_violations<T: Serialize>(raw: &Value, typed: &T) -> Vec<SchemaViolation> {
+    let mut violations = Vec::new();
+    if let Ok(typed) = serde_json::to_value(typed) {
+        compare("$", raw, &typed, &mut violations);
+    }
+    violations
+}
+
+/// Applies `mode` to the response `raw` of `what`, parsed into `typed`
+#[cfg(feature = "client")]
+pub(crate) fn check<T: Serialize>(mode: ResponseValidation, what: &str, raw: &Value, typed: &T) -> Result<(), A2AError> {
+    if mode == ResponseValidation::Off {
+        return Ok(());
+    }
+    let violations = schema_violations(raw, typed);
+    if violations.is_empty() {
+        return Ok(());
+    }
+    let summary = violations.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+    if mode == ResponseValidation::Strict {
+        return Err(A2AError::from(ClientError::Protocol(format!("{} response does not match the schema: {}", what, summary))));
+    }
+    warn!("{} response does not match the schema: {}", what, summary);
+    Ok(())
+}
+
+fn compare(path: &str, raw: &Value, typed: &Value, violations: &mut Vec<SchemaViolation>) {
+    match (raw, typed) {
+        (Value::Object(raw), Value::Object(typed)) => {
+            for (key, value) in raw.iter().filter(|(_, value)| !is_absent(value)) {
+                match find(typed, key) {
+                    Some(typed) => compare(&format!("{}.{}", path, key), value, typed, violations),
+                    None => violations.push(SchemaViolation::ExtraField(format!("{}.{}", path, key))),
+                }
+            }
+            for (key, _) in typed.iter().filter(|(_, value)| !is_absent(value)) {
+                if find(raw, key).is_none() {
+                    violations.push(SchemaViolation::MissingField(format!("{}.{}", path, key)));
+                }
+            }
+        }
+        (Value::Array(raw), Value::Array(typed)) => {
+            for (i, (raw, typed)) in raw.iter().zip(typed).enumerate() {
+                compare(&format!("{}[{}]", path, i), raw, typed, violations);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The present field of `fields` named `key`, in any spelling
+fn find<'a>(fields: &'a serde_json::Map<String, Value>, key: &str) -> Option<&'a Value> {
+    let key = normalize(key);
+    fields.iter().find(|(name, value)| normalize(name) == key && !is_absent(value)).map(|(_, value)| value)
+}
+
+fn normalize(key: &str) -> String {
+    key.chars().filter(|c| *c != '_').map(|c| c.to_ascii_lowercase()).collect()
+}
+
+fn is_absent(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(values) => values.is_empty(),
+        Value::Object(fields) => fields.is_empty(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Task, TaskState, TaskStatus};
+    use serde_json::json;
+
+    #[test]
+    fn test_extra_and_missing_fields_are_reported() {
+        let raw = json!({
+            "id": "t1",
+            "contextId": "c1",
+            "status": {"state": "working", "progress": 0.5},
+            "history": [],
+            "debug": null
+        });
+        let status = TaskStatus {
+            state: TaskState::Working,
+            message: None,
+            timestamp: None,
+        };
+        let task = Task::new("c1".to_string(), status).with_task_id("t1".to_string());
+        assert_eq!(
+            schema_violations(&raw, &task),
+            vec![
+                SchemaViolation::ExtraField("$.status.progress".to_string()),
+                SchemaViolation::MissingField("$.kind".to_string()),
+            ]
+        );
+
+        let raw = json!({"id": "t1", "context_id": "c1", "kind": "task", "status": {"state": "working"}});
+        let task: Task = serde_json::from_value(raw.clone()).unwrap();
+        assert!(schema_violations(&raw, &task).is_empty());
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_modes() {
+        let raw = json!({"id": "t1", "contextId": "c1", "kind": "task", "status": {"state": "working"}, "extra": 1});
+        let task: Task = serde_json::from_value(raw.clone()).unwrap();
+        assert!(check(ResponseValidation::Off, "Task", &raw, &task).is_ok());
+        assert!(check(ResponseValidation::Log, "Task", &raw, &task).is_ok());
+        let err = check(ResponseValidation::Strict, "Task", &raw, &task).unwrap_err();
+        assert!(err.to_string().contains("unexpected field '$.extra'"), "{}", err);
+    }
+}
@@ -0,0 +1,532 @@
+//! Declarative workflows of chained agent calls
+//!
+//! A `Workflow` is an ordered list of steps. Each step either calls an A2A
+//! agent or runs a local async function, may be guarded by a condition, and
+//! receives a message built from the artifacts of the previous step. The run
+//! state is saved to a `WorkflowStore` after every step, so a run interrupted
+//! by a crash can be continued with `Workflow::resume`. A step that was in
+//! progress during the crash is executed again.
+
+use crate::a2a::client::client_trait::{Client, ClientEventOrMessage, TaskUpdateEvent};
+use crate::a2a::core_types::{Message, Part, Role, TaskState};
+use crate::a2a::error::A2AError;
+use crate::a2a::models::Artifact;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "sqlite")]
+use sqlx::sqlite::SqliteConnectOptions;
+#[cfg(feature = "sqlite")]
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::future::Future;
+#[cfg(feature = "sqlite")]
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+type LocalStepFn = Arc<dyn Fn(Message) -> BoxFuture<'static, Result<Vec<Artifact>, A2AError>> + Send + Sync>;
+type StepCondition = Arc<dyn Fn(&WorkflowState) -> bool + Send + Sync>;
+type InputMapper = Arc<dyn Fn(&WorkflowState) -> Message + Send + Sync>;
+
+/// Progress of a workflow run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkflowStatus {
+    /// Steps remain to be executed
+    Running,
+    /// All steps were executed or skipped
+    Completed,
+    /// A step failed; `resume` retries it
+    Failed,
+}
+
+/// The result of one executed or skipped step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepRecord {
+    /// The step name
+    pub name: String,
+    /// Whether the condition of the step was false
+    pub skipped: bool,
+    /// Artifacts produced by the step
+    pub artifacts: Vec<Artifact>,
+}
+
+/// Persisted state of a workflow run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowState {
+    /// Id of the run
+    pub run_id: String,
+    /// Name of the workflow
+    pub workflow: String,
+    /// Progress of the run
+    pub status: WorkflowStatus,
+    /// Index of the next step to execute
+    pub next_step: usize,
+    /// The message the run was started with
+    pub input: Message,
+    /// Records of the steps executed so far
+    pub steps: Vec<StepRecord>,
+    /// Error of the last failed step
+    pub error: Option<String>,
+    /// When the state was last saved
+    pub updated_at: String,
+}
+
+impl WorkflowState {
+    /// Returns the record of the step called `name`
+    pub fn step(&self, name: &str) -> Option<&StepRecord> {
+        self.steps.iter().find(|step| step.name == name)
+    }
+
+    /// Artifacts of the most recent step that was not skipped
+    pub fn last_artifacts(&self) -> Option<&[Artifact]> {
+        self.steps
+            .iter()
+            .rev()
+            .find(|step| !step.skipped)
+            .map(|step| step.artifacts.as_slice())
+    }
+
+    /// Builds the default input of the next step
+    ///
+    /// The parts of the previous step's artifacts become the parts of a user
+    /// message; the first step receives the workflow input.
+    pub fn next_message(&self) -> Message {
+        match self.last_artifacts() {
+            Some(artifacts) => {
+                let parts = artifacts.iter().flat_map(|artifact| artifact.parts.clone()).collect();
+                let mut message = Message::new(Role::User, parts);
+                message.context_id = self.input.context_id.clone();
+                message
+            }
+            None => self.input.clone(),
+        }
+    }
+}
+
+/// Persists workflow runs
+#[async_trait]
+pub trait WorkflowStore: Send + Sync {
+    /// Saves the state of a run, replacing any previous state
+    async fn save(&self, state: &WorkflowState) -> Result<(), A2AError>;
+
+    /// Loads the state of a run
+    async fn load(&self, run_id: &str) -> Result<Option<WorkflowState>, A2AError>;
+
+    /// Deletes the state of a run
+    async fn delete(&self, run_id: &str) -> Result<(), A2AError>;
+}
+
+/// Keeps workflow runs in memory
+#[derive(Debug, Default)]
+pub struct InMemoryWorkflowStore {
+    runs: Mutex<HashMap<String, WorkflowState>>,
+}
+
+impl InMemoryWorkflowStore {
+    /// Creates an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl WorkflowStore for InMemoryWorkflowStore {
+    async fn save(&self, state: &WorkflowState) -> Result<(), A2AError> {
+        self.runs.lock().unwrap().insert(state.run_id.clone(), state.clone());
+        Ok(())
+    }
+
+    async fn load(&self, run_id: &str) -> Result<Option<WorkflowState>, A2AError> {
+        Ok(self.runs.lock().unwrap().get(run_id).cloned())
+    }
+
+    async fn delete(&self, run_id: &str) -> Result<(), A2AError> {
+        self.runs.lock().unwrap().remove(run_id);
+        Ok(())
+    }
+}
+
+/// Keeps workflow runs in SQLite
+#[cfg(feature = "sqlite")]
+pub struct SqliteWorkflowStore {
+    pool: SqlitePool,
+    table_name: String,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteWorkflowStore {
+    /// Creates a store using an existing pool; call `initialize` before use
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            table_name: "workflow_runs".to_string(),
+        }
+    }
+
+    /// Connects to a SQLite database and initializes the store
+    pub async fn connect(url: &str) -> Result<Self, A2AError> {
+        let options = SqliteConnectOptions::from_str(url)
+            .map_err(|e| A2AError::internal(&format!("Invalid database URL: {}", e)))?
+            .create_if_missing(true);
+        let pool = SqlitePool::connect_with(options)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to connect to database: {}", e)))?;
+
+        let store = Self::new(pool);
+        store.initialize().await?;
+        Ok(store)
+    }
+
+    /// Initializes the database schema
+    pub async fn initialize(&self) -> Result<(), A2AError> {
+        let query = format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                run_id TEXT PRIMARY KEY,
+                state_data TEXT NOT NULL
+            )",
+            self.table_name
+        );
+        sqlx::query(&query)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to initialize database: {}", e)))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[async_trait]
+impl WorkflowStore for SqliteWorkflowStore {
+    async fn save(&self, state: &WorkflowState) -> Result<(), A2AError> {
+        let query = format!(
+            "INSERT OR REPLACE INTO {} (run_id, state_data) VALUES (?, ?)",
+            self.table_name
+        );
+        sqlx::query(&query)
+            .bind(&state.run_id)
+            .bind(serde_json::to_string(state)?)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to save workflow run: {}", e)))?;
+        Ok(())
+    }
+
+    async fn load(&self, run_id: &str) -> Result<Option<WorkflowState>, A2AError> {
+        let query = format!("SELECT state_data FROM {} WHERE run_id = ?", self.table_name);
+        let row: Option<(String,)> = sqlx::query_as(&query)
+            .bind(run_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to load workflow run: {}", e)))?;
+        row.map(|(data,)| serde_json::from_str(&data).map_err(A2AError::from))
+            .transpose()
+    }
+
+    async fn delete(&self, run_id: &str) -> Result<(), A2AError> {
+        let query = format!("DELETE FROM {} WHERE run_id = ?", self.table_name);
+        sqlx::query(&query)
+            .bind(run_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to delete workflow run: {}", e)))?;
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+enum StepAction {
+    Agent(Arc<dyn Client>),
+    Local(LocalStepFn),
+}
+
+#[derive(Clone)]
+struct Step {
+    name: String,
+    action: StepAction,
+    condition: Option<StepCondition>,
+    input: Option<InputMapper>,
+}
+
+/// A sequence of agent calls and local functions
+#[derive(Clone)]
+pub struct Workflow {
+    name: String,
+    steps: Vec<Step>,
+    store: Arc<dyn WorkflowStore>,
+}
+
+impl Workflow {
+    /// Creates an empty workflow keeping its runs in memory
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            steps: Vec::new(),
+            store: Arc::new(InMemoryWorkflowStore::new()),
+        }
+    }
+
+    /// Persists runs in `store`
+    pub fn with_store(mut self, store: Arc<dyn WorkflowStore>) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Appends a step sending the input message to an agent
+    ///
+    /// The artifacts of the resulting task, or the parts of a message reply,
+    /// become the step output.
+    pub fn agent_step(mut self, name: &str, client: Arc<dyn Client>) -> Self {
+        self.push(name, StepAction::Agent(client));
+        self
+    }
+
+    /// Appends a step running a local function on the input message
+    pub fn local_step<F, Fut>(mut self, name: &str, step: F) -> Self
+    where
+        F: Fn(Message) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Vec<Artifact>, A2AError>> + Send + 'static,
+    {
+        let step: LocalStepFn = Arc::new(move |message| Box::pin(step(message)));
+        self.push(name, StepAction::Local(step));
+        self
+    }
+
+    /// Only runs the last added step when `condition` holds
+    pub fn when<F>(mut self, condition: F) -> Self
+    where
+        F: Fn(&WorkflowState) -> bool + Send + Sync + 'static,
+    {
+        if let Some(step) = self.steps.last_mut() {
+            step.condition = Some(Arc::new(condition));
+        }
+        self
+    }
+
+    /// Builds the input of the last added step with `mapper`
+    pub fn map_input<F>(mut self, mapper: F) -> Self
+    where
+        F: Fn(&WorkflowState) -> Message + Send + Sync + 'static,
+    {
+        if let Some(step) = self.steps.last_mut() {
+            step.input = Some(Arc::new(mapper));
+        }
+        self
+    }
+
+    fn push(&mut self, name: &str, action: StepAction) {
+        self.steps.push(Step {
+            name: name.to_string(),
+            action,
+            condition: None,
+            input: None,
+        });
+    }
+
+    /// Starts a run with id `run_id`
+    pub async fn run(&self, run_id: &str, input: Message) -> Result<WorkflowState, A2AError> {
+        if self.store.load(run_id).await?.is_some() {
+            return Err(A2AError::invalid_params(&format!("Workflow run '{}' already exists", run_id)));
+        }
+        let state = WorkflowState {
+            run_id: run_id.to_string(),
+            workflow: self.name.clone(),
+            status: WorkflowStatus::Running,
+            next_step: 0,
+            input,
+            steps: Vec::new(),
+            error: None,
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        };
+        self.store.save(&state).await?;
+        self.drive(state).await
+    }
+
+    /// Continues a stored run from its next step
+    ///
+    /// Completed runs are returned unchanged; failed runs retry the failed step.
+    pub async fn resume(&self, run_id: &str) -> Result<WorkflowState, A2AError> {
+        let state = self
+            .store
+            .load(run_id)
+            .await?
+            .ok_or_else(|| A2AError::invalid_params(&format!("Unknown workflow run '{}'", run_id)))?;
+        if state.workflow != self.name {
+            return Err(A2AError::invalid_params(&format!(
+                "Run '{}' belongs to workflow '{}'",
+                run_id, state.workflow
+            )));
+        }
+        if state.status == WorkflowStatus::Completed {
+            return Ok(state);
+        }
+        self.drive(state).await
+    }
+
+    async fn drive(&self, mut state: WorkflowState) -> Result<WorkflowState, A2AError> {
+        state.status = WorkflowStatus::Running;
+        state.error = None;
+        while let Some(step) = self.steps.get(state.next_step) {
+            let record = if step.condition.as_ref().is_some_and(|condition| !condition(&state)) {
+                StepRecord {
+                    name: step.name.clone(),
+                    skipped: true,
+                    artifacts: Vec::new(),
+                }
+            } else {
+                let message = match &step.input {
+                    Some(mapper) => mapper(&state),
+                    None => state.next_message(),
+                };
+                match run_step(&step.action, message).await {
+                    Ok(artifacts) => StepRecord {
+                        name: step.name.clone(),
+                        skipped: false,
+                        artifacts,
+                    },
+                    Err(e) => {
+                        state.status = WorkflowStatus::Failed;
+                        state.error = Some(format!("Step '{}' failed: {}", step.name, e));
+                        state.updated_at = chrono::Utc::now().to_rfc3339();
+                        self.store.save(&state).await?;
+                        return Ok(state);
+                    }
+                }
+            };
+            state.steps.push(record);
+            state.next_step += 1;
+            state.updated_at = chrono::Utc::now().to_rfc3339();
+            self.store.save(&state).await?;
+        }
+        state.status = WorkflowStatus::Completed;
+        self.store.save(&state).await?;
+        Ok(state)
+    }
+}
+
+impl std::fmt::Debug for Workflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Workflow")
+            .field("name", &self.name)
+            .field("steps", &self.steps.iter().map(|step| &step.name).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+async fn run_step(action: &StepAction, message: Message) -> Result<Vec<Artifact>, A2AError> {
+    match action {
+        StepAction::Local(step) => step(message).await,
+        StepAction::Agent(client) => {
+            let mut artifacts: Vec<Artifact> = Vec::new();
+            let mut stream = client.send_message(message, None, None, None).await;
+            while let Some(item) = stream.next().await {
+                match item? {
+                    ClientEventOrMessage::Message(reply) => {
+                        artifacts.push(Artifact::new(reply.parts));
+                    }
+                    ClientEventOrMessage::Event((task, update)) => {
+                        if let Some(TaskUpdateEvent::Artifact(event)) = update {
+                            artifacts.retain(|known| known.artifact_id != event.artifact.artifact_id);
+                            artifacts.push(event.artifact);
+                        }
+                        for artifact in task.artifacts.into_iter().flatten() {
+                            artifacts.retain(|known| known.artifact_id != artifact.artifact_id);
+                            artifacts.push(artifact);
+                        }
+                        if matches!(task.status.state, TaskState::Failed | TaskState::Rejected | TaskState::Canceled) {
+                            return Err(A2AError::internal(&format!(
+                                "Agent task ended in state {:?}",
+                                task.status.state
+                            )));
+                        }
+                    }
+                }
+            }
+            Ok(artifacts)
+        }
+    }
+}
+
+/// Wraps text in a single-part artifact, for local steps
+pub fn text_artifact(text: impl Into<String>) -> Artifact {
+    Artifact::new(vec![Part::text(text.into())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::PartRoot;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn text_of(state: &WorkflowState, step: &str) -> String {
+        state.step(step).unwrap().artifacts[0]
+            .parts
+            .iter()
+            .filter_map(|part| match part.root() {
+                PartRoot::Text(text) => Some(text.text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn input_text(message: &Message) -> String {
+        message
+            .parts
+            .iter()
+            .filter_map(|part| match part.root() {
+                PartRoot::Text(text) => Some(text.text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_steps_chain_artifacts_and_skip_on_condition() {
+        let workflow = Workflow::new("shout")
+            .local_step("upper", |message| async move { Ok(vec![text_artifact(input_text(&message).to_uppercase())]) })
+            .local_step("never", |_| async move { Ok(vec![text_artifact("unreachable")]) })
+            .when(|state| state.step("upper").is_none())
+            .local_step("exclaim", |message| async move { Ok(vec![text_artifact(format!("{}!", input_text(&message)))]) });
+
+        let input = Message::new(Role::User, vec![Part::text("hi".to_string())]);
+        let state = workflow.run("run-1", input).await.unwrap();
+        assert_eq!(state.status, WorkflowStatus::Completed);
+        assert!(state.step("never").unwrap().skipped);
+        assert_eq!(text_of(&state, "exclaim"), "HI!");
+        assert!(workflow.run("run-1", Message::new(Role::User, vec![])).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_run_resumes_from_failed_step() {
+        let store = Arc::new(SqliteWorkflowStore::connect("sqlite::memory:").await.unwrap());
+        let first_calls = Arc::new(AtomicUsize::new(0));
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let (first, flaky) = (first_calls.clone(), attempts.clone());
+        let workflow = Workflow::new("flaky")
+            .with_store(store.clone())
+            .local_step("first", move |_| {
+                first.fetch_add(1, Ordering::SeqCst);
+                async move { Ok(vec![text_artifact("one")]) }
+            })
+            .local_step("second", move |message| {
+                let attempt = flaky.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if attempt == 0 {
+                        return Err(A2AError::internal("crashed"));
+                    }
+                    Ok(vec![text_artifact(format!("{} two", input_text(&message)))])
+                }
+            });
+
+        let state = workflow.run("run-1", Message::new(Role::User, vec![])).await.unwrap();
+        assert_eq!(state.status, WorkflowStatus::Failed);
+        assert_eq!(state.next_step, 1);
+        assert_eq!(store.load("run-1").await.unwrap().unwrap(), state);
+
+        let state = workflow.resume("run-1").await.unwrap();
+        assert_eq!(state.status, WorkflowStatus::Completed);
+        assert_eq!(text_of(&state, "second"), "one two");
+        assert_eq!(first_calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -42,6 +42,16 @@ pub enum TaskState {
     Unknown,
 }
 
+impl TaskState {
+    /// Returns true for states from which a task can no longer progress
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            TaskState::Completed | TaskState::Canceled | TaskState::Failed | TaskState::Rejected
+        )
+    }
+}
+
 /// Supported A2A transport protocols
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
@@ -76,13 +86,14 @@ impl FromStr for TransportProtocol {
 
 /// Represents a structured data segment (e.g., JSON) within a message or artifact
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DataPart {
     /// The structured data content
     pub data: serde_json::Value,
     /// The type of this part, used as a discriminator. Always 'data'
-    #[serde(rename = "kind")]
     pub kind: String,
     /// Optional metadata associated with this part
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
@@ -103,13 +114,14 @@ impl DataPart {
 
 /// Represents a text segment within a message or artifact
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TextPart {
     /// The string content of the text part
     pub text: String,
     /// The type of this part, used as a discriminator. Always 'text'
-    #[serde(rename = "kind")]
     pub kind: String,
     /// Optional metadata associated with this part
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
@@ -130,47 +142,54 @@ impl TextPart {
 
 /// Defines base properties for a file
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct FileBase {
     /// The MIME type of the file (e.g., "application/pdf")
-    #[serde(rename = "mime_type")]
+    #[serde(alias = "mime_type", skip_serializing_if = "Option::is_none")]
     pub mime_type: Option<String>,
     /// An optional name for the file (e.g., "document.pdf")
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
 }
 
 /// Represents a file with its content provided directly as a base64-encoded string
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct FileWithBytes {
     /// The base64-encoded content of the file
     pub bytes: String,
     /// The MIME type of the file (e.g., "application/pdf")
-    #[serde(rename = "mime_type")]
+    #[serde(alias = "mime_type", skip_serializing_if = "Option::is_none")]
     pub mime_type: Option<String>,
     /// An optional name for the file (e.g., "document.pdf")
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
 }
 
 /// Represents a file with its content located at a specific URI
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct FileWithUri {
     /// A URL pointing to the file's content
     pub uri: String, // Changed from Url to String to match Python's str type
     /// The MIME type of the file (e.g., "application/pdf")
-    #[serde(rename = "mime_type")]
+    #[serde(alias = "mime_type", skip_serializing_if = "Option::is_none")]
     pub mime_type: Option<String>,
     /// An optional name for the file (e.g., "document.pdf")
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
 }
 
 /// Represents a file segment within a message or artifact
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct FilePart {
     /// The file content, represented as either a URI or as base64-encoded bytes
     pub file: FileContent,
     /// The type of this part, used as a discriminator. Always 'file'
-    #[serde(rename = "kind")]
     pub kind: String,
     /// Optional metadata associated with this part
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
@@ -333,22 +352,73 @@ impl From<DataPart> for Part {
 
 /// Defines base properties common to all message or artifact parts
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PartBase {
     /// Optional metadata associated with this part
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
 /// Represents the status of a task at a specific point in time
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TaskStatus {
     /// The current state of the task's lifecycle
     pub state: TaskState,
     /// An optional, human-readable message providing more details about the current status
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<Box<Message>>,
     /// An ISO 8601 datetime string indicating when this status was recorded
+    ///
+    /// Kept as received so that round trips preserve the peer's formatting;
+    /// it is validated on deserialization and `recorded_at` reads it as a
+    /// `DateTime<Utc>`.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_timestamp",
+        skip_serializing_if = "Option::is_none",
+    )]
     pub timestamp: Option<String>,
 }
 
+/// Parses a status timestamp as A2A peers write them
+///
+/// Accepts RFC 3339 and, since some peers omit the offset, ISO 8601 date
+/// times without one, which are read as UTC. Also accepts the
+/// `2025-06-12 09:41:05.101331 UTC` format earlier versions of this crate
+/// stored.
+pub fn parse_timestamp(value: &str) -> Result<chrono::DateTime<chrono::Utc>, chrono::ParseError> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|at| at.with_timezone(&chrono::Utc))
+        .or_else(|_| {
+            chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").map(|at| at.and_utc())
+        })
+        .or_else(|e| parse_legacy_timestamp(value).ok_or(e))
+}
+
+/// Parses the `Display` format of `DateTime<Utc>`, which earlier versions
+/// wrote as status timestamps
+fn parse_legacy_timestamp(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let value = value.strip_suffix(" UTC")?;
+    chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f").ok().map(|at| at.and_utc())
+}
+
+fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let timestamp = Option::<String>::deserialize(deserializer)?;
+    if let Some(value) = &timestamp {
+        // Stored legacy timestamps are rewritten as RFC 3339
+        if let Some(at) = parse_legacy_timestamp(value) {
+            return Ok(Some(at.to_rfc3339()));
+        }
+        parse_timestamp(value)
+            .map_err(|e| serde::de::Error::custom(format!("invalid timestamp {:?}: {}", value, e)))?;
+    }
+    Ok(timestamp)
+}
+
 impl TaskStatus {
     pub fn new(state: TaskState) -> Self {
         Self {
@@ -367,6 +437,31 @@ impl TaskStatus {
         self.timestamp = Some(timestamp);
         self
     }
+
+    /// Sets the timestamp, formatted as RFC 3339
+    pub fn recorded(mut self, at: chrono::DateTime<chrono::Utc>) -> Self {
+        self.timestamp = Some(at.to_rfc3339());
+        self
+    }
+
+    /// When this status was recorded, or `None` without a valid timestamp
+    pub fn recorded_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.timestamp.as_deref().and_then(|value| parse_timestamp(value).ok())
+    }
+
+    /// Orders statuses by when they were recorded, those without a valid
+    /// timestamp first
+    ///
+    /// Comparing the strings directly breaks on differing offsets and
+    /// fractional second precision.
+    pub fn cmp_recorded(&self, other: &Self) -> std::cmp::Ordering {
+        self.recorded_at().cmp(&other.recorded_at())
+    }
+
+    /// Whether this status was recorded after `other`
+    pub fn is_newer_than(&self, other: &Self) -> bool {
+        self.cmp_recorded(other) == std::cmp::Ordering::Greater
+    }
 }
 
 // Forward declaration for Message
@@ -376,18 +471,23 @@ pub struct Message {
     /// A unique identifier for the message, typically a UUID, generated by the sender
     pub message_id: String,
     /// The context ID for this message, used to group related interactions
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub context_id: Option<String>,
     /// The ID of the task this message is part of
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub task_id: Option<String>,
     /// Identifies the sender of the message
     pub role: Role,
     /// An array of content parts that form the message body
     pub parts: Vec<Part>,
     /// Optional metadata for extensions
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
     /// The URIs of extensions that are relevant to this message
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub extensions: Option<Vec<String>>,
     /// A list of other task IDs that this message references for additional context
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub reference_task_ids: Option<Vec<String>>,
     /// The type of this object, used as a discriminator. Always 'message'
     pub kind: String,
@@ -116,6 +116,60 @@ impl Default for InvalidParamsError {
     }
 }
 
+/// What failed while sending a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportErrorKind {
+    /// The host name did not resolve
+    Dns,
+    /// No connection could be opened
+    Connect,
+    /// The TLS handshake or certificate verification failed
+    Tls,
+    /// The request or its response timed out
+    Timeout,
+    /// The response body could not be read
+    Body,
+    /// Any other failure
+    Other,
+}
+
+impl TransportErrorKind {
+    /// The snake_case name of the kind
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransportErrorKind::Dns => "dns",
+            TransportErrorKind::Connect => "connect",
+            TransportErrorKind::Tls => "tls",
+            TransportErrorKind::Timeout => "timeout",
+            TransportErrorKind::Body => "body",
+            TransportErrorKind::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for TransportErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The local failure an `InternalError` stands for
+///
+/// Only set by the constructors for failures of an outgoing request, such
+/// as `transport_error` and `http_error`, and never serialized, so an error
+/// received from an agent cannot pass for one whatever its `data` holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorOrigin {
+    /// The request did not get an HTTP response
+    Transport(TransportErrorKind),
+    /// The request got a non-success HTTP status
+    Http {
+        status: u16,
+        /// `WWW-Authenticate` values of a 401 or 403 response
+        www_authenticate: Vec<String>,
+    },
+}
+
 /// An error indicating an internal error on the server
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InternalError {
@@ -125,6 +179,9 @@ pub struct InternalError {
     pub message: String,
     /// A primitive or structured value containing additional information about the error
     pub data: Option<serde_json::Value>,
+    /// The local failure this error stands for, if any
+    #[serde(skip)]
+    pub origin: Option<ErrorOrigin>,
 }
 
 impl Default for InternalError {
@@ -133,6 +190,7 @@ impl Default for InternalError {
             code: -32603,
             message: "Internal error".to_string(),
             data: None,
+            origin: None,
         }
     }
 }
@@ -475,7 +533,7 @@ impl A2AError {
         InternalError {
             code: -32603,
             message: message.to_string(),
-            data: None,
+            ..Default::default()
         }.into()
     }
 
@@ -487,12 +545,63 @@ impl A2AError {
         }.into()
     }
 
+    pub fn push_notification_not_supported() -> Self {
+        PushNotificationNotSupportedError::default().into()
+    }
+
     pub fn transport_error(message: String) -> Self {
-        A2AError::internal(&format!("Transport error: {}", message))
+        A2AError::transport_failure(TransportErrorKind::Other, message)
+    }
+
+    /// A request that got no response because of a `kind` failure
+    pub fn transport_failure(kind: TransportErrorKind, message: String) -> Self {
+        InternalError {
+            code: -32603,
+            message: format!("Transport error: {}", message),
+            data: None,
+            origin: Some(ErrorOrigin::Transport(kind)),
+        }.into()
     }
 
     pub fn http_error(status: u16, message: String) -> Self {
-        A2AError::internal(&format!("HTTP error {}: {}", status, message))
+        A2AError::http_challenge(status, message, Vec::new())
+    }
+
+    /// An HTTP 401 or 403 error with the `WWW-Authenticate` header values of
+    /// the response
+    pub fn http_challenge(status: u16, message: String, www_authenticate: Vec<String>) -> Self {
+        InternalError {
+            code: -32603,
+            message: format!("HTTP error {}: {}", status, message),
+            data: None,
+            origin: Some(ErrorOrigin::Http { status, www_authenticate }),
+        }.into()
+    }
+
+    /// The local failure an error created by `transport_error`,
+    /// `transport_failure`, `http_error` or `http_challenge` stands for
+    pub fn origin(&self) -> Option<&ErrorOrigin> {
+        match self {
+            A2AError::Internal(e) => e.origin.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// The `WWW-Authenticate` header values of an error created by
+    /// `http_challenge`
+    pub fn www_authenticate(&self) -> Vec<String> {
+        match self.origin() {
+            Some(ErrorOrigin::Http { www_authenticate, .. }) => www_authenticate.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The HTTP status of an error created by `http_error`
+    pub fn http_status(&self) -> Option<u16> {
+        match self.origin() {
+            Some(ErrorOrigin::Http { status, .. }) => Some(*status),
+            _ => None,
+        }
     }
 
     pub fn json_error(message: String) -> Self {
@@ -532,6 +641,145 @@ impl A2AError {
     }
 }
 
+/// Base URL of the error section of the A2A specification
+pub const ERROR_DOCS_URL: &str = "https://a2a-protocol.org/latest/specification/#8-error-handling";
+
+/// Machine-readable description of an error, sent as the JSON-RPC `data`
+///
+/// Clients can use it to show an actionable message instead of the raw
+/// error string: `kind` is stable, `field` names the offending parameter
+/// when known and `retryable` tells whether the same request may succeed
+/// later.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorDetails {
+    /// Stable snake_case name of the error
+    pub kind: String,
+    /// Request field the error is about, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Whether retrying the same request may succeed
+    pub retryable: bool,
+    /// What the caller can do about the error
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+    /// Documentation of the error
+    pub docs_url: String,
+}
+
+impl A2AError {
+    /// Stable snake_case name of the error
+    pub fn kind(&self) -> &'static str {
+        use crate::a2a::jsonrpc::error_codes;
+        match self {
+            A2AError::JSONParse(_) => "parse_error",
+            A2AError::InvalidRequest(_) => "invalid_request",
+            A2AError::MethodNotFound(_) => "method_not_found",
+            A2AError::InvalidParams(_) => "invalid_params",
+            A2AError::Internal(_) => "internal_error",
+            A2AError::TaskNotFound(_) => "task_not_found",
+            A2AError::TaskNotCancelable(_) => "task_not_cancelable",
+            A2AError::PushNotificationNotSupported(_) => "push_notification_not_supported",
+            A2AError::UnsupportedOperation(_) => "unsupported_operation",
+            A2AError::ContentTypeNotSupported(_) => "content_type_not_supported",
+            A2AError::InvalidAgentResponse(_) => "invalid_agent_response",
+            A2AError::AuthenticatedExtendedCardNotConfigured(_) => "authenticated_extended_card_not_configured",
+            A2AError::Generic(e) => match e.code {
+                error_codes::QUOTA_EXCEEDED => "quota_exceeded",
+                error_codes::CONTENT_REJECTED => "content_rejected",
+                error_codes::CONCURRENCY_LIMIT_EXCEEDED => "concurrency_limit_exceeded",
+                error_codes::REPLAY_REJECTED => "replay_rejected",
+                error_codes::REQUEST_SIGNATURE_INVALID => "request_signature_invalid",
+                error_codes::PEER_NOT_AUTHORIZED => "peer_not_authorized",
+                error_codes::BUDGET_EXCEEDED => "budget_exceeded",
+                -32700 => "parse_error",
+                -32600 => "invalid_request",
+                -32601 => "method_not_found",
+                -32602 => "invalid_params",
+                -32603 => "internal_error",
+                _ => "error",
+            },
+        }
+    }
+
+    /// Details of the error
+    ///
+    /// Errors received from a remote agent carry the details it sent; other
+    /// errors derive them from their variant and message.
+    pub fn details(&self) -> ErrorDetails {
+        if let Some(details) = self
+            .data()
+            .filter(|data| data.get("kind").is_some())
+            .and_then(|data| serde_json::from_value(data.clone()).ok())
+        {
+            return details;
+        }
+
+        let kind = self.kind();
+        let (retryable, hint) = match kind {
+            "parse_error" => (false, Some("Send a well-formed JSON body")),
+            "invalid_request" => (false, Some("Send a JSON-RPC 2.0 request object with a method")),
+            "method_not_found" => (false, Some("Check the method name against the A2A specification")),
+            "invalid_params" => (false, Some("Fix the named field and resend the request")),
+            "internal_error" => (true, Some("Retry later; report the error if it persists")),
+            "task_not_found" => (false, Some("Check the task id; the task may have expired or been deleted")),
+            "task_not_cancelable" => (false, Some("The task already finished and can no longer be canceled")),
+            "push_notification_not_supported" => (false, Some("The agent card does not advertise push notifications")),
+            "unsupported_operation" => (false, Some("The agent does not support this operation")),
+            "content_type_not_supported" => (false, Some("Use one of the modes listed in the agent card")),
+            "invalid_agent_response" => (true, Some("The agent returned malformed data; retry or contact its operator")),
+            "authenticated_extended_card_not_configured" => (false, Some("Request the public agent card instead")),
+            "quota_exceeded" => (true, Some("Wait for the quota window to reset before retrying")),
+            "content_rejected" => (false, Some("Remove the rejected content and resend the request")),
+            "concurrency_limit_exceeded" => (true, Some("Wait for the running message to finish, then resend")),
+            "replay_rejected" => (true, Some("Resend with a fresh nonce and timestamp signed with the credential")),
+            "request_signature_invalid" => (false, Some("Sign the exact body with a key the agent knows, with a current timestamp")),
+            "peer_not_authorized" => (false, Some("Ask the agent's operator to allow this SPIFFE ID for the method")),
+            "budget_exceeded" => (false, Some("Ask the agent's operator to raise the budget, or continue in a new context")),
+            _ => (false, Some("")),
+        };
+        let field = match self {
+            A2AError::InvalidParams(_) => offending_field(self.message()),
+            _ => None,
+        };
+
+        ErrorDetails {
+            kind: kind.to_string(),
+            field,
+            retryable,
+            hint: hint.map(str::to_string),
+            docs_url: ERROR_DOCS_URL.to_string(),
+        }
+    }
+
+    /// The `data` member sent with the error: its details merged with any
+    /// data the error already carries
+    pub fn error_data(&self) -> serde_json::Value {
+        let mut data = serde_json::to_value(self.details()).unwrap_or_default();
+        if let (Some(own), Some(merged)) = (self.data(), data.as_object_mut()) {
+            match own.as_object() {
+                Some(own) => {
+                    for (key, value) in own {
+                        merged.entry(key.clone()).or_insert_with(|| value.clone());
+                    }
+                }
+                None => {
+                    merged.insert("detail".to_string(), own.clone());
+                }
+            }
+        }
+        data
+    }
+}
+
+/// Extracts the field named by a serde error message such as
+/// "missing field `message`" or "unknown field `foo`, expected ..."
+fn offending_field(message: &str) -> Option<String> {
+    let start = message.find("field `")? + "field `".len();
+    let len = message[start..].find('`')?;
+    Some(message[start..start + len].to_string())
+}
+
 // Add conversions from common error types
 impl From<serde_json::Error> for A2AError {
     fn from(err: serde_json::Error) -> Self {
@@ -545,6 +793,7 @@ impl From<std::io::Error> for A2AError {
     }
 }
 
+#[cfg(feature = "runtime")]
 impl From<tokio::task::JoinError> for A2AError {
     fn from(err: tokio::task::JoinError) -> Self {
         A2AError::internal(&format!("Task join error: {}", err))
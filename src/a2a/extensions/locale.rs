@@ -0,0 +1,162 @@
+//! Locale negotiation for agent responses
+//!
+//! Agents localize their responses with a small convention on top of the
+//! protocol:
+//!
+//! - The agent card declares the locales it can answer in with an extension
+//!   under `LOCALE_EXTENSION_URI`, whose `supported_locales` param lists BCP
+//!   47 language tags, most preferred first (see `locale_extension`).
+//! - Callers state their preferences with the `Accept-Language` header, on
+//!   HTTP requests and in gRPC metadata alike. The server records them in
+//!   `ServerCallContext::accepted_languages`.
+//! - A message may override the header with a `locale` metadata entry
+//!   (`LOCALE_METADATA_KEY`), for clients that cannot set headers.
+//! - Executors pick the response locale with `negotiate_locale`, usually
+//!   through `RequestContext::negotiate_locale`, and record it under the
+//!   same `locale` key in the metadata of the messages and artifacts they
+//!   produce.
+
+use crate::a2a::models::{AgentCard, AgentExtension};
+use std::collections::HashMap;
+
+/// URI of the agent card extension declaring supported locales
+pub const LOCALE_EXTENSION_URI: &str = "urn:a2a:extension:locale:v1";
+
+/// Extension param listing the supported locales
+pub const SUPPORTED_LOCALES_PARAM: &str = "supported_locales";
+
+/// Metadata key carrying the locale of a message or artifact
+pub const LOCALE_METADATA_KEY: &str = "locale";
+
+/// HTTP header carrying the caller's language preferences
+pub const ACCEPT_LANGUAGE_HEADER: &str = "accept-language";
+
+/// Declares the locales an agent answers in, most preferred first
+pub fn locale_extension<I, S>(supported: I) -> AgentExtension
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let supported: Vec<String> = supported.into_iter().map(Into::into).collect();
+    AgentExtension {
+        uri: LOCALE_EXTENSION_URI.to_string(),
+        description: Some("Responses are localized to the caller's Accept-Language".to_string()),
+        required: Some(false),
+        params: Some(HashMap::from([(SUPPORTED_LOCALES_PARAM.to_string(), serde_json::json!(supported))])),
+    }
+}
+
+/// The locales declared by the card's locale extension
+pub fn supported_locales(card: &AgentCard) -> Vec<String> {
+    card.capabilities
+        .extensions
+        .iter()
+        .flatten()
+        .filter(|extension| extension.uri == LOCALE_EXTENSION_URI)
+        .filter_map(|extension| extension.params.as_ref()?.get(SUPPORTED_LOCALES_PARAM)?.as_array())
+        .flatten()
+        .filter_map(|locale| locale.as_str().map(str::to_string))
+        .collect()
+}
+
+/// Parses an `Accept-Language` header into language ranges, most preferred first
+///
+/// Ranges with equal quality keep their order, and ranges with a quality of
+/// zero or a malformed quality are dropped.
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut ranges: Vec<(String, f32)> = header
+        .split(',')
+        .filter_map(|entry| {
+            let mut fields = entry.split(';');
+            let range = fields.next()?.trim();
+            if range.is_empty() {
+                return None;
+            }
+            let mut quality = 1.0;
+            for field in fields {
+                if let Some(value) = field.trim().strip_prefix("q=") {
+                    quality = value.trim().parse().ok()?;
+                }
+            }
+            (quality > 0.0).then(|| (range.to_string(), quality))
+        })
+        .collect();
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranges.into_iter().map(|(range, _)| range).collect()
+}
+
+/// Picks the supported locale best matching the caller's preferences
+///
+/// Each requested range is tried in order: an exact match first, then a
+/// supported locale the range is a prefix of (`en` matches `en-GB`), then
+/// one that is a prefix of the range (`de-AT` matches `de`). `*` matches the
+/// first supported locale. Tags compare case-insensitively, and `_` is read
+/// as `-`.
+pub fn negotiate_locale(requested: &[String], supported: &[String]) -> Option<String> {
+    let canonical = |tag: &str| tag.trim().replace('_', "-").to_lowercase();
+    let supported_tags: Vec<String> = supported.iter().map(|tag| canonical(tag)).collect();
+    let is_prefix = |prefix: &str, tag: &str| tag.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('-'));
+
+    for range in requested {
+        let range = canonical(range);
+        if range == "*" {
+            return supported.first().cloned();
+        }
+        let found = supported_tags
+            .iter()
+            .position(|tag| *tag == range)
+            .or_else(|| supported_tags.iter().position(|tag| is_prefix(&range, tag)))
+            .or_else(|| supported_tags.iter().position(|tag| is_prefix(tag, &range)));
+        if let Some(index) = found {
+            return Some(supported[index].clone());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::models::AgentCapabilities;
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|tag| tag.to_string()).collect()
+    }
+
+    #[test]
+    fn test_accept_language_is_ordered_by_quality() {
+        assert_eq!(
+            parse_accept_language("fr-CH, fr;q=0.9, en;q=0.8, de;q=0.7, *;q=0.5"),
+            tags(&["fr-CH", "fr", "en", "de", "*"])
+        );
+        assert_eq!(parse_accept_language("en;q=0.2, de, it;q=0, xx;q=abc"), tags(&["de", "en"]));
+        assert!(parse_accept_language("").is_empty());
+    }
+
+    #[test]
+    fn test_negotiation_prefers_exact_then_related_tags() {
+        let supported = tags(&["en-US", "en-GB", "de", "pt_BR"]);
+        assert_eq!(negotiate_locale(&tags(&["en-gb"]), &supported).as_deref(), Some("en-GB"));
+        assert_eq!(negotiate_locale(&tags(&["en"]), &supported).as_deref(), Some("en-US"));
+        assert_eq!(negotiate_locale(&tags(&["de-AT"]), &supported).as_deref(), Some("de"));
+        assert_eq!(negotiate_locale(&tags(&["fr", "pt-BR"]), &supported).as_deref(), Some("pt_BR"));
+        assert_eq!(negotiate_locale(&tags(&["fr", "*"]), &supported).as_deref(), Some("en-US"));
+        assert_eq!(negotiate_locale(&tags(&["fr"]), &supported), None);
+    }
+
+    #[test]
+    fn test_card_declares_supported_locales() {
+        let capabilities = AgentCapabilities::new().with_extensions(vec![locale_extension(["de", "en"])]);
+        let card = AgentCard::new(
+            "Agent".to_string(),
+            "A multilingual agent".to_string(),
+            "http://localhost:8080".to_string(),
+            "1.0.0".to_string(),
+            vec!["text/plain".to_string()],
+            vec!["text/plain".to_string()],
+            capabilities,
+            vec![],
+        );
+        assert_eq!(supported_locales(&card), tags(&["de", "en"]));
+    }
+}
@@ -4,5 +4,6 @@
 //! matching a2a-python/src/a2a/extensions/
 
 pub mod common;
+pub mod locale;
 
 // Re-export extension types
@@ -0,0 +1,341 @@
+//! C ABI for the JSON-RPC client
+//!
+//! With the `ffi` feature the crate exports a handful of `a2a_*` functions,
+//! declared in `include/a2a.h`, for applications that embed the client from
+//! C, C++, Go or Swift. Requests and responses cross the boundary as JSON
+//! strings in the A2A wire shape. The shared library is built with
+//! `cargo rustc --lib --release --features ffi --crate-type cdylib`.
+//!
+//! Every fallible function returns a status code: [`A2A_OK`], one of the
+//! small negative `A2A_ERR_*` codes below for failures on the calling side,
+//! or the JSON-RPC / A2A error code reported for the request (for example
+//! `-32001` for an unknown task). The codes are part of the ABI and do not
+//! change between releases. The message of the last failure on the calling
+//! thread is available from [`a2a_last_error_message`].
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+use crate::a2a::client::card_resolver::A2ACardResolver;
+use crate::a2a::client::transports::jsonrpc::JsonRpcTransport;
+use crate::a2a::client::ClientTransport;
+use crate::a2a::models::{MessageSendParams, TaskOrMessage, TaskQueryParams};
+use crate::A2AError;
+
+/// The call succeeded
+pub const A2A_OK: i32 = 0;
+/// A required pointer argument was null
+pub const A2A_ERR_NULL_POINTER: i32 = -1;
+/// A string argument was not valid UTF-8
+pub const A2A_ERR_INVALID_UTF8: i32 = -2;
+/// A JSON argument could not be parsed
+pub const A2A_ERR_INVALID_JSON: i32 = -3;
+/// The library panicked; the client handle should be freed
+pub const A2A_ERR_PANIC: i32 = -4;
+
+/// Opaque client handle returned by [`a2a_client_new`]
+pub struct A2AClient {
+    transport: JsonRpcTransport,
+    runtime: tokio::runtime::Runtime,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: &str) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Failure of an FFI call, reported as a status code
+struct FfiError {
+    code: i32,
+    message: String,
+}
+
+impl FfiError {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+impl From<A2AError> for FfiError {
+    fn from(error: A2AError) -> Self {
+        Self::new(error.code(), error.message())
+    }
+}
+
+/// Runs an FFI body, recording failures and containing panics
+fn ffi_call(body: impl FnOnce() -> Result<(), FfiError>) -> i32 {
+    let result = catch_unwind(AssertUnwindSafe(body))
+        .unwrap_or_else(|_| Err(FfiError::new(A2A_ERR_PANIC, "a2a-rust panicked")));
+    match result {
+        Ok(()) => A2A_OK,
+        Err(error) => {
+            set_last_error(&error.message);
+            error.code
+        }
+    }
+}
+
+/// Borrows a C string argument as UTF-8
+///
+/// # Safety
+/// `value` must be null or point to a NUL-terminated string.
+unsafe fn str_arg<'a>(value: *const c_char, name: &str) -> Result<&'a str, FfiError> {
+    if value.is_null() {
+        return Err(FfiError::new(A2A_ERR_NULL_POINTER, format!("{} is null", name)));
+    }
+    CStr::from_ptr(value)
+        .to_str()
+        .map_err(|_| FfiError::new(A2A_ERR_INVALID_UTF8, format!("{} is not valid UTF-8", name)))
+}
+
+/// Borrows the client handle argument
+///
+/// # Safety
+/// `client` must be null or a handle returned by [`a2a_client_new`].
+unsafe fn client_arg<'a>(client: *const A2AClient) -> Result<&'a A2AClient, FfiError> {
+    client
+        .as_ref()
+        .ok_or_else(|| FfiError::new(A2A_ERR_NULL_POINTER, "client is null"))
+}
+
+/// Hands a JSON string to the caller through `out`
+///
+/// # Safety
+/// `out` must be null or valid for writes.
+unsafe fn write_json(out: *mut *mut c_char, json: String) -> Result<(), FfiError> {
+    if out.is_null() {
+        return Err(FfiError::new(A2A_ERR_NULL_POINTER, "output pointer is null"));
+    }
+    // JSON escapes NUL, so serialized text never contains one
+    *out = CString::new(json).unwrap_or_default().into_raw();
+    Ok(())
+}
+
+/// Creates a client for the agent served at `base_url`
+///
+/// The agent card is fetched from `/.well-known/agent-card.json` and
+/// requests go to the URL it advertises. On success `*out_client` receives a handle to release with
+/// [`a2a_client_free`].
+///
+/// # Safety
+/// `base_url` must be a NUL-terminated string and `out_client` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn a2a_client_new(base_url: *const c_char, out_client: *mut *mut A2AClient) -> i32 {
+    ffi_call(|| {
+        let url = str_arg(base_url, "base_url")?;
+        if out_client.is_null() {
+            return Err(FfiError::new(A2A_ERR_NULL_POINTER, "output pointer is null"));
+        }
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| A2AError::internal(&format!("Failed to start runtime: {}", e)))?;
+        let card = runtime.block_on(A2ACardResolver::new(url.to_string()).get_agent_card())?;
+        let transport = JsonRpcTransport::new(card.url.clone(), Some(card))?;
+        *out_client = Box::into_raw(Box::new(A2AClient { transport, runtime }));
+        Ok(())
+    })
+}
+
+/// Sends `message/send` with the given `MessageSendParams` JSON
+///
+/// On success `*out_json` receives the resulting task or message as JSON, to
+/// release with [`a2a_string_free`].
+///
+/// # Safety
+/// `client` must be a live handle, `params_json` a NUL-terminated string and
+/// `out_json` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn a2a_client_send_message(
+    client: *const A2AClient,
+    params_json: *const c_char,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    ffi_call(|| {
+        let client = client_arg(client)?;
+        let params: MessageSendParams = serde_json::from_str(str_arg(params_json, "params_json")?)
+            .map_err(|e| FfiError::new(A2A_ERR_INVALID_JSON, format!("Invalid params: {}", e)))?;
+        let result = client
+            .runtime
+            .block_on(client.transport.send_message(params, None, None))?;
+        let json = match result {
+            TaskOrMessage::Task(task) => serde_json::to_string(&task),
+            TaskOrMessage::Message(message) => serde_json::to_string(&message),
+            TaskOrMessage::TaskUpdate(update) => serde_json::to_string(&update),
+            TaskOrMessage::TaskArtifactUpdateEvent(update) => serde_json::to_string(&update),
+        }
+        .map_err(A2AError::from)?;
+        write_json(out_json, json)
+    })
+}
+
+/// Fetches the current state of a task with `tasks/get`
+///
+/// On success `*out_json` receives the task as JSON, to release with
+/// [`a2a_string_free`]. Poll this until the task reaches a terminal state.
+///
+/// # Safety
+/// `client` must be a live handle, `task_id` a NUL-terminated string and
+/// `out_json` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn a2a_client_get_task(
+    client: *const A2AClient,
+    task_id: *const c_char,
+    out_json: *mut *mut c_char,
+) -> i32 {
+    ffi_call(|| {
+        let client = client_arg(client)?;
+        let params = TaskQueryParams::new(str_arg(task_id, "task_id")?.to_string());
+        let task = client.runtime.block_on(client.transport.get_task(params, None, None))?;
+        write_json(out_json, serde_json::to_string(&task).map_err(A2AError::from)?)
+    })
+}
+
+/// Releases a client handle; null is ignored
+///
+/// # Safety
+/// `client` must be null or a handle returned by [`a2a_client_new`] that has
+/// not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn a2a_client_free(client: *mut A2AClient) {
+    if !client.is_null() {
+        drop(Box::from_raw(client));
+    }
+}
+
+/// Releases a string returned by this library; null is ignored
+///
+/// # Safety
+/// `value` must be null or a string returned by this library that has not
+/// been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn a2a_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
+
+/// Message of the last failed call on this thread, or null
+///
+/// The pointer stays valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn a2a_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(a2a_last_error_message()) }.to_string_lossy().into_owned()
+    }
+
+    fn serve_card(server: &mut mockito::Server) {
+        let card = crate::AgentCard::new(
+            "Test Agent".to_string(),
+            "A test agent".to_string(),
+            server.url(),
+            "1.0.0".to_string(),
+            vec!["text/plain".to_string()],
+            vec!["text/plain".to_string()],
+            crate::AgentCapabilities::new(),
+            vec![],
+        );
+        server
+            .mock("GET", "/.well-known/agent-card.json")
+            .with_body(serde_json::to_string(&card).unwrap())
+            .create();
+    }
+
+    fn new_client(server: &mut mockito::Server) -> *mut A2AClient {
+        serve_card(server);
+        let url = CString::new(server.url()).unwrap();
+        let mut client = ptr::null_mut();
+        assert_eq!(unsafe { a2a_client_new(url.as_ptr(), &mut client) }, A2A_OK);
+        client
+    }
+
+    #[test]
+    fn test_send_message_and_poll_task() {
+        let mut server = mockito::Server::new();
+        let task = r#"{"kind":"task","id":"task-1","context_id":"ctx-1","status":{"state":"completed"}}"#;
+        let send = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJsonString(r#"{"method":"message/send"}"#.to_string()))
+            .with_body(format!(r#"{{"jsonrpc":"2.0","id":"1","result":{}}}"#, task))
+            .create();
+        let get = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJsonString(r#"{"method":"tasks/get"}"#.to_string()))
+            .with_body(format!(r#"{{"jsonrpc":"2.0","id":"2","result":{}}}"#, task))
+            .create();
+
+        let client = new_client(&mut server);
+        let params = CString::new(
+            r#"{"message":{"kind":"message","messageId":"m1","role":"user","parts":[{"kind":"text","text":"hi"}]}}"#,
+        )
+        .unwrap();
+        let mut out = ptr::null_mut();
+        assert_eq!(unsafe { a2a_client_send_message(client, params.as_ptr(), &mut out) }, A2A_OK);
+        let sent: serde_json::Value = serde_json::from_str(&unsafe { CStr::from_ptr(out) }.to_string_lossy()).unwrap();
+        assert_eq!(sent["id"], "task-1");
+        unsafe { a2a_string_free(out) };
+
+        let task_id = CString::new("task-1").unwrap();
+        let mut out = ptr::null_mut();
+        assert_eq!(unsafe { a2a_client_get_task(client, task_id.as_ptr(), &mut out) }, A2A_OK);
+        let polled: serde_json::Value = serde_json::from_str(&unsafe { CStr::from_ptr(out) }.to_string_lossy()).unwrap();
+        assert_eq!(polled["status"]["state"], "completed");
+        unsafe {
+            a2a_string_free(out);
+            a2a_client_free(client);
+        }
+        send.assert();
+        get.assert();
+    }
+
+    #[test]
+    fn test_protocol_errors_return_their_code() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("POST", "/")
+            .with_body(r#"{"jsonrpc":"2.0","id":"1","error":{"code":-32001,"message":"Task not found: nope"}}"#)
+            .create();
+
+        let client = new_client(&mut server);
+        let task_id = CString::new("nope").unwrap();
+        let mut out = ptr::null_mut();
+        assert_eq!(unsafe { a2a_client_get_task(client, task_id.as_ptr(), &mut out) }, -32001);
+        assert!(out.is_null());
+        assert!(last_error().contains("nope"));
+        unsafe { a2a_client_free(client) };
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let mut client = ptr::null_mut();
+        assert_eq!(unsafe { a2a_client_new(ptr::null(), &mut client) }, A2A_ERR_NULL_POINTER);
+        assert_eq!(last_error(), "base_url is null");
+
+        let mut server = mockito::Server::new();
+        let client = new_client(&mut server);
+        let params = CString::new("{not json").unwrap();
+        let mut out = ptr::null_mut();
+        assert_eq!(
+            unsafe { a2a_client_send_message(client, params.as_ptr(), &mut out) },
+            A2A_ERR_INVALID_JSON
+        );
+        assert_eq!(
+            unsafe { a2a_client_get_task(ptr::null(), params.as_ptr(), &mut out) },
+            A2A_ERR_NULL_POINTER
+        );
+        unsafe { a2a_client_free(client) };
+    }
+}
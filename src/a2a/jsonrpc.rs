@@ -87,6 +87,7 @@ pub struct JSONRPCError {
     /// A string providing a short description of the error
     pub message: String,
     /// A primitive or structured value containing additional information about the error
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<serde_json::Value>,
 }
 
@@ -105,6 +106,31 @@ impl JSONRPCError {
     }
 }
 
+impl From<&crate::a2a::error::A2AError> for JSONRPCError {
+    /// Keeps the error's code and message and sends its details as `data`
+    fn from(error: &crate::a2a::error::A2AError) -> Self {
+        Self::new(error.code(), error.message().to_string()).with_data(error.error_data())
+    }
+}
+
+impl From<crate::a2a::error::A2AError> for JSONRPCError {
+    fn from(error: crate::a2a::error::A2AError) -> Self {
+        Self::from(&error)
+    }
+}
+
+impl From<JSONRPCError> for crate::a2a::error::A2AError {
+    /// Keeps the `data` a remote agent sent, including its error details
+    fn from(error: JSONRPCError) -> Self {
+        crate::a2a::error::JSONRPCError {
+            code: error.code,
+            message: error.message,
+            data: error.data,
+        }
+        .into()
+    }
+}
+
 /// JSON-RPC 2.0 Error Response object
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct JSONRPCErrorResponse {
@@ -160,6 +186,13 @@ pub mod error_codes {
     pub const CONTENT_TYPE_NOT_SUPPORTED: i32 = -32005;
     pub const INVALID_AGENT_RESPONSE: i32 = -32006;
     pub const AUTHENTICATED_EXTENDED_CARD_NOT_CONFIGURED: i32 = -32007;
+    pub const QUOTA_EXCEEDED: i32 = -32008;
+    pub const CONTENT_REJECTED: i32 = -32009;
+    pub const CONCURRENCY_LIMIT_EXCEEDED: i32 = -32010;
+    pub const REPLAY_REJECTED: i32 = -32011;
+    pub const REQUEST_SIGNATURE_INVALID: i32 = -32012;
+    pub const PEER_NOT_AUTHORIZED: i32 = -32013;
+    pub const BUDGET_EXCEEDED: i32 = -32014;
 }
 
 /// Standard JSON-RPC error codes
@@ -2,6 +2,16 @@
 //! 
 //! This crate provides a Rust implementation of the A2A protocol,
 //! which enables communication between AI agents and clients.
+//!
+//! The data model (`core_types`, `models`, `jsonrpc`, `error`) only depends on
+//! serde. The `server` and `client` features, both enabled by default, add
+//! the runtime-dependent parts, so consumers that only need the types can
+//! build with `default-features = false`. The `wasm` feature enables the
+//! client without tokio or reqwest, using `FetchTransport` on
+//! wasm32-unknown-unknown. The `python` feature provides the bindings of the
+//! `a2a_rust` Python extension module built by the `python/` crate, and the
+//! `ffi` feature exports a C ABI for the client. The `scaffold` feature
+//! generates agent projects.
 
 // Core modules
 pub mod core_types;
@@ -13,11 +23,23 @@ pub mod jsonrpc;
 
 // Sub-modules matching a2a-python structure
 pub mod auth;
+#[cfg(any(feature = "client", feature = "wasm"))]
 pub mod client;
+#[cfg(feature = "server")]
 pub mod server;
 pub mod utils;
+#[cfg(feature = "spiffe")]
+pub mod spiffe;
+#[cfg(feature = "runtime")]
+pub mod secrets;
 pub mod extensions;
 pub mod grpc;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "scaffold")]
+pub mod scaffold;
 
 // Re-export main types for convenience
 pub use types::*;
@@ -2,6 +2,11 @@
 //! 
 //! This module contains the more complex data structures used in the A2A protocol,
 //! including tasks, artifacts, agent cards, and various request/response types.
+//!
+//! Wire types are serialized with camelCase field names, as the spec
+//! requires, omit unset optional fields and also accept the snake_case names
+//! a2a-python clients may send. With the `strict-params` feature request
+//! parameter types reject unknown fields.
 
 use crate::a2a::core_types::*;
 use serde::{Deserialize, Serialize};
@@ -88,19 +93,24 @@ impl Skill {
 
 /// Represents a file, data structure, or other resource generated by an agent during a task
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Artifact {
     /// A unique identifier (e.g. UUID) for the artifact within the scope of the task
-    #[serde(rename = "artifact_id")]
+    #[serde(alias = "artifact_id")]
     pub artifact_id: String,
     /// An optional, human-readable name for the artifact
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     /// An optional, human-readable description of the artifact
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     /// An array of content parts that make up the artifact
     pub parts: Vec<Part>,
     /// Optional metadata for extensions
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
     /// The URIs of extensions that are relevant to this artifact
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub extensions: Option<Vec<String>>,
 }
 
@@ -210,6 +220,7 @@ impl TaskOrMessage {
 
 /// Represents the service provider of an agent
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct AgentProvider {
     /// The name of the agent provider's organization
     pub organization: String,
@@ -225,6 +236,7 @@ impl AgentProvider {
 
 /// Represents a distinct capability or function that an agent can perform
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct AgentSkill {
     /// A unique identifier for the agent's skill
     pub id: String,
@@ -235,15 +247,20 @@ pub struct AgentSkill {
     /// A set of keywords describing the skill's capabilities
     pub tags: Vec<String>,
     /// Example prompts or scenarios that this skill can handle
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub examples: Option<Vec<String>>,
     /// The set of supported input MIME types for this skill
-    #[serde(rename = "input_modes")]
+    #[serde(alias = "input_modes", skip_serializing_if = "Option::is_none")]
     pub input_modes: Option<Vec<String>>,
     /// The set of supported output MIME types for this skill
-    #[serde(rename = "output_modes")]
+    #[serde(alias = "output_modes", skip_serializing_if = "Option::is_none")]
     pub output_modes: Option<Vec<String>>,
     /// Security schemes necessary for the agent to leverage this skill
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub security: Option<Vec<HashMap<String, Vec<String>>>>,
+    /// Optional metadata for extensions, such as the schema of the skill's data parts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
 impl AgentSkill {
@@ -257,6 +274,7 @@ impl AgentSkill {
             input_modes: None,
             output_modes: None,
             security: None,
+            metadata: None,
         }
     }
 
@@ -279,18 +297,42 @@ impl AgentSkill {
         self.security = Some(security);
         self
     }
+
+    pub fn with_metadata(mut self, metadata: HashMap<String, serde_json::Value>) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Declare the JSON Schema that data parts sent to this skill must match
+    pub fn with_data_schema(mut self, schema: serde_json::Value) -> Self {
+        self.metadata
+            .get_or_insert_with(HashMap::new)
+            .insert(crate::a2a::utils::constants::SKILL_DATA_SCHEMA_METADATA_KEY.to_string(), schema);
+        self
+    }
+
+    /// The JSON Schema declared for this skill's data parts
+    pub fn data_schema(&self) -> Option<&serde_json::Value> {
+        self.metadata
+            .as_ref()?
+            .get(crate::a2a::utils::constants::SKILL_DATA_SCHEMA_METADATA_KEY)
+    }
 }
 
 /// A declaration of a protocol extension supported by an Agent
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct AgentExtension {
     /// The unique URI identifying the extension
     pub uri: String,
     /// A human-readable description of how this agent uses the extension
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     /// If true, the client must understand and comply with the extension's requirements
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub required: Option<bool>,
     /// Optional, extension-specific configuration parameters
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub params: Option<HashMap<String, serde_json::Value>>,
 }
 
@@ -322,16 +364,19 @@ impl AgentExtension {
 
 /// Defines optional capabilities supported by an agent
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct AgentCapabilities {
     /// Indicates if the agent supports Server-Sent Events (SSE) for streaming responses
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub streaming: Option<bool>,
     /// Indicates if the agent supports sending push notifications for asynchronous task updates
-    #[serde(rename = "push_notifications")]
+    #[serde(alias = "push_notifications", skip_serializing_if = "Option::is_none")]
     pub push_notifications: Option<bool>,
     /// Indicates if the agent provides a history of state transitions for a task
-    #[serde(rename = "state_transition_history")]
+    #[serde(alias = "state_transition_history", skip_serializing_if = "Option::is_none")]
     pub state_transition_history: Option<bool>,
     /// A list of protocol extensions supported by the agent
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub extensions: Option<Vec<AgentExtension>>,
 }
 
@@ -368,6 +413,7 @@ impl AgentCapabilities {
 
 /// Declares a combination of a target URL and a transport protocol for interacting with an agent
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct AgentInterface {
     /// The URL where this interface is available
     pub url: String,
@@ -383,6 +429,7 @@ impl AgentInterface {
 
 /// The AgentCard is a self-describing manifest for an agent
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct AgentCard {
     /// A human-readable name for the agent
     pub name: String,
@@ -393,41 +440,47 @@ pub struct AgentCard {
     /// The agent's own version number
     pub version: String,
     /// The version of the A2A protocol this agent supports
-    #[serde(rename = "protocol_version")]
+    #[serde(alias = "protocol_version", skip_serializing_if = "Option::is_none")]
     pub protocol_version: Option<String>,
     /// An optional URL to an icon for the agent
-    #[serde(rename = "icon_url")]
+    #[serde(alias = "icon_url", skip_serializing_if = "Option::is_none")]
     pub icon_url: Option<String>,
     /// An optional URL to the agent's documentation
-    #[serde(rename = "documentation_url")]
+    #[serde(alias = "documentation_url", skip_serializing_if = "Option::is_none")]
     pub documentation_url: Option<String>,
     /// Information about the agent's service provider
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub provider: Option<AgentProvider>,
     /// The transport protocol for the preferred endpoint
-    #[serde(rename = "preferred_transport")]
+    #[serde(alias = "preferred_transport", skip_serializing_if = "Option::is_none")]
     pub preferred_transport: Option<String>,
     /// A list of additional supported interfaces
-    #[serde(rename = "additional_interfaces")]
+    #[serde(alias = "additional_interfaces", skip_serializing_if = "Option::is_none")]
     pub additional_interfaces: Option<Vec<AgentInterface>>,
     /// Default set of supported input MIME types for all skills
-    #[serde(rename = "default_input_modes")]
+    #[serde(alias = "default_input_modes")]
     pub default_input_modes: Vec<String>,
     /// Default set of supported output MIME types for all skills
-    #[serde(rename = "default_output_modes")]
+    #[serde(alias = "default_output_modes")]
     pub default_output_modes: Vec<String>,
     /// A declaration of optional capabilities supported by the agent
     pub capabilities: AgentCapabilities,
     /// The set of skills that the agent can perform
     pub skills: Vec<AgentSkill>,
     /// A list of security requirement objects that apply to all agent interactions
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub security: Option<Vec<HashMap<String, Vec<String>>>>,
     /// A declaration of the security schemes available to authorize requests
-    #[serde(rename = "security_schemes")]
+    #[serde(alias = "security_schemes", skip_serializing_if = "Option::is_none")]
     pub security_schemes: Option<HashMap<String, SecurityScheme>>,
     /// JSON Web Signatures computed for this AgentCard
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub signatures: Option<Vec<serde_json::Value>>,
     /// If true, the agent can provide an extended agent card with additional details to authenticated users
-    #[serde(rename = "supports_authenticated_extended_card")]
+    #[serde(
+        alias = "supports_authenticated_extended_card",
+        skip_serializing_if = "Option::is_none",
+    )]
     pub supports_authenticated_extended_card: Option<bool>,
 }
 
@@ -517,19 +570,27 @@ impl AgentCard {
 
 /// Represents a single, stateful operation or conversation between a client and an agent
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Task {
     /// A unique identifier (e.g. UUID) for the task, generated by the server for a new task
     pub id: String,
     /// A server-generated unique identifier (e.g. UUID) for maintaining context across multiple related tasks or interactions
-    #[serde(rename = "context_id")]
+    #[serde(alias = "context_id")]
     pub context_id: String,
     /// The current status of the task, including its state and a descriptive message
     pub status: TaskStatus,
     /// A collection of artifacts generated by the agent during the execution of the task
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub artifacts: Option<Vec<Artifact>>,
     /// An array of messages exchanged during the task, representing the conversation history
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub history: Option<Vec<Message>>,
+    /// The status transitions of the task, oldest first, recorded when the
+    /// agent has the state transition history capability
+    #[serde(alias = "state_transitions", default, skip_serializing_if = "Option::is_none")]
+    pub state_transitions: Option<Vec<TaskStateTransition>>,
     /// Optional metadata for extensions
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
     /// The type of this object, used as a discriminator. Always 'task'
     pub kind: String,
@@ -543,6 +604,7 @@ impl Task {
             status,
             artifacts: None,
             history: None,
+            state_transitions: None,
             metadata: None,
             kind: "task".to_string(),
         }
@@ -569,20 +631,35 @@ impl Task {
     }
 }
 
+/// A status a task went through, as recorded in `Task::state_transitions`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskStateTransition {
+    /// The state the task entered
+    pub state: TaskState,
+    /// An ISO 8601 datetime string indicating when the task entered the state
+    pub timestamp: String,
+    /// The status message of the transition, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<Message>,
+}
+
 /// An event sent by the agent to notify the client of a change in a task's status
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TaskStatusUpdateEvent {
     /// The ID of the task that was updated
-    #[serde(rename = "task_id")]
+    #[serde(alias = "task_id")]
     pub task_id: String,
     /// The context ID associated with the task
-    #[serde(rename = "context_id")]
+    #[serde(alias = "context_id")]
     pub context_id: String,
     /// The new status of the task
     pub status: TaskStatus,
     /// If true, this is the final event in the stream for this interaction
     pub r#final: bool,
     /// Optional metadata for extensions
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
     /// The type of this event, used as a discriminator. Always 'status-update'
     pub kind: String,
@@ -608,21 +685,24 @@ impl TaskStatusUpdateEvent {
 
 /// An event sent by the agent to notify the client that an artifact has been generated or updated
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TaskArtifactUpdateEvent {
     /// The ID of the task this artifact belongs to
-    #[serde(rename = "task_id")]
+    #[serde(alias = "task_id")]
     pub task_id: String,
     /// The context ID associated with the task
-    #[serde(rename = "context_id")]
+    #[serde(alias = "context_id")]
     pub context_id: String,
     /// The artifact that was generated or updated
     pub artifact: Artifact,
     /// If true, the content of this artifact should be appended to a previously sent artifact with the same ID
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub append: Option<bool>,
     /// If true, this is the final chunk of the artifact
-    #[serde(rename = "last_chunk")]
+    #[serde(alias = "last_chunk", skip_serializing_if = "Option::is_none")]
     pub last_chunk: Option<bool>,
     /// Optional metadata for extensions
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
     /// The type of this event, used as a discriminator. Always 'artifact-update'
     pub kind: String,
@@ -659,10 +739,13 @@ impl TaskArtifactUpdateEvent {
 
 /// Defines authentication details for a push notification endpoint
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-params", serde(deny_unknown_fields))]
 pub struct PushNotificationAuthenticationInfo {
     /// A list of supported authentication schemes (e.g., 'Basic', 'Bearer')
     pub schemes: Vec<String>,
     /// Optional credentials required by the push notification endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub credentials: Option<String>,
 }
 
@@ -682,15 +765,31 @@ impl PushNotificationAuthenticationInfo {
 
 /// Defines the configuration for setting up push notifications for task updates
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-params", serde(deny_unknown_fields))]
 pub struct PushNotificationConfig {
     /// A unique identifier (e.g. UUID) for the push notification configuration, set by the client
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
     /// The callback URL where the agent should send push notifications
     pub url: Url,
     /// A unique token for this task or session to validate incoming push notifications
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub token: Option<String>,
     /// Optional authentication details for the agent to use when calling the notification URL
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub authentication: Option<PushNotificationAuthenticationInfo>,
+    /// Optional filter restricting which task events are notified
+    ///
+    /// This is an extension field; implementations that don't support it ignore it
+    /// and notify on every event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<NotificationFilter>,
+    /// Optional payload format overriding the server default
+    ///
+    /// This is an extension field, like `filter`.
+    #[serde(alias = "payload_format", default, skip_serializing_if = "Option::is_none")]
+    pub payload_format: Option<NotificationPayloadFormat>,
 }
 
 impl PushNotificationConfig {
@@ -700,6 +799,8 @@ impl PushNotificationConfig {
             url,
             token: None,
             authentication: None,
+            filter: None,
+            payload_format: None,
         }
     }
 
@@ -717,16 +818,111 @@ impl PushNotificationConfig {
         self.authentication = Some(authentication);
         self
     }
+
+    pub fn with_filter(mut self, filter: NotificationFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    pub fn with_payload_format(mut self, payload_format: NotificationPayloadFormat) -> Self {
+        self.payload_format = Some(payload_format);
+        self
+    }
+
+    /// Returns true if this config should be notified about the event
+    pub fn accepts(&self, kind: Option<NotificationEventKind>, state: &TaskState) -> bool {
+        self.filter.as_ref().is_none_or(|f| f.matches(kind, state))
+    }
+}
+
+/// The body format of a push notification
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NotificationPayloadFormat {
+    /// The complete task
+    #[default]
+    Full,
+    /// Only the task id, context id and status
+    Slim,
+    /// The task wrapped in a CloudEvents envelope
+    CloudEvents,
+}
+
+/// The kind of task event a push notification is sent for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NotificationEventKind {
+    /// A task status update
+    StatusUpdate,
+    /// A task artifact update
+    ArtifactUpdate,
+    /// A full task snapshot
+    Task,
+}
+
+/// Subscribes a push notification config to specific task states or event kinds
+///
+/// Empty lists match everything, so the default filter notifies on every event.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-params", serde(deny_unknown_fields))]
+pub struct NotificationFilter {
+    /// Only notify while the task is in one of these states
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub states: Vec<TaskState>,
+    /// Only notify for these event kinds
+    #[serde(alias = "event_kinds", default, skip_serializing_if = "Vec::is_empty")]
+    pub event_kinds: Vec<NotificationEventKind>,
+}
+
+impl NotificationFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A filter matching only terminal task states
+    pub fn terminal_states() -> Self {
+        Self::new().with_states(vec![
+            TaskState::Completed,
+            TaskState::Canceled,
+            TaskState::Failed,
+            TaskState::Rejected,
+        ])
+    }
+
+    pub fn with_states(mut self, states: Vec<TaskState>) -> Self {
+        self.states = states;
+        self
+    }
+
+    pub fn with_event_kinds(mut self, event_kinds: Vec<NotificationEventKind>) -> Self {
+        self.event_kinds = event_kinds;
+        self
+    }
+
+    /// Checks an event against the filter
+    ///
+    /// An unknown event kind only has to match the state requirement.
+    pub fn matches(&self, kind: Option<NotificationEventKind>, state: &TaskState) -> bool {
+        let state_matches = self.states.is_empty() || self.states.contains(state);
+        let kind_matches = match kind {
+            Some(kind) => self.event_kinds.is_empty() || self.event_kinds.contains(&kind),
+            None => true,
+        };
+        state_matches && kind_matches
+    }
 }
 
 /// A container associating a push notification configuration with a specific task
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-params", serde(deny_unknown_fields))]
 pub struct TaskPushNotificationConfig {
     /// The unique identifier (e.g. UUID) of the task
-    #[serde(rename = "task_id")]
+    #[serde(alias = "task_id")]
     pub task_id: String,
     /// The push notification configuration for this task
-    #[serde(rename = "push_notification_config")]
+    #[serde(alias = "push_notification_config")]
     pub push_notification_config: PushNotificationConfig,
 }
 
@@ -741,17 +937,20 @@ impl TaskPushNotificationConfig {
 
 /// Defines configuration options for a message/send or message/stream request
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-params", serde(deny_unknown_fields))]
 pub struct MessageSendConfiguration {
     /// A list of output MIME types the client is prepared to accept in the response
-    #[serde(rename = "accepted_output_modes")]
+    #[serde(alias = "accepted_output_modes", skip_serializing_if = "Option::is_none")]
     pub accepted_output_modes: Option<Vec<String>>,
     /// If true, the client will wait for the task to complete
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub blocking: Option<bool>,
     /// The number of most recent messages from the task's history to retrieve in the response
-    #[serde(rename = "history_length")]
+    #[serde(alias = "history_length", skip_serializing_if = "Option::is_none")]
     pub history_length: Option<i32>,
     /// Configuration for the agent to send push notifications for updates after the initial response
-    #[serde(rename = "push_notification_config")]
+    #[serde(alias = "push_notification_config", skip_serializing_if = "Option::is_none")]
     pub push_notification_config: Option<PushNotificationConfig>,
 }
 
@@ -788,12 +987,16 @@ impl MessageSendConfiguration {
 
 /// Defines the parameters for a request to send a message to an agent
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-params", serde(deny_unknown_fields))]
 pub struct MessageSendParams {
     /// The message object being sent to the agent
     pub message: Message,
     /// Optional configuration for the send request
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub configuration: Option<MessageSendConfiguration>,
     /// Optional metadata for extensions
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
@@ -815,15 +1018,44 @@ impl MessageSendParams {
         self.metadata = Some(metadata);
         self
     }
+
+    /// Asks for a dry run: the message is validated and checked like any
+    /// other, and the server answers with the task it would create, without
+    /// executing or persisting anything
+    pub fn with_dry_run(mut self) -> Self {
+        self.metadata
+            .get_or_insert_with(HashMap::new)
+            .insert(crate::a2a::utils::constants::DRY_RUN_METADATA_KEY.to_string(), serde_json::Value::Bool(true));
+        self
+    }
+
+    /// Whether the request asks for a dry run
+    pub fn is_dry_run(&self) -> bool {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get(crate::a2a::utils::constants::DRY_RUN_METADATA_KEY))
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false)
+    }
 }
 
 /// Defines parameters containing a task ID, used for simple task operations
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-params", serde(deny_unknown_fields))]
 pub struct TaskIdParams {
     /// The unique identifier (e.g. UUID) of the task
     pub id: String,
     /// Optional metadata associated with the request
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
+    /// For `tasks/resubscribe`, the id of the last streamed event the client
+    /// received; only later events are sent
+    #[serde(alias = "from_event_id", default, skip_serializing_if = "Option::is_none")]
+    pub from_event_id: Option<String>,
+    /// For `tasks/resubscribe`, which events to stream
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<ResubscribeFilter>,
 }
 
 impl TaskIdParams {
@@ -831,6 +1063,8 @@ impl TaskIdParams {
         Self {
             id,
             metadata: None,
+            from_event_id: None,
+            filter: None,
         }
     }
 
@@ -838,17 +1072,84 @@ impl TaskIdParams {
         self.metadata = Some(metadata);
         self
     }
+
+    pub fn with_from_event_id(mut self, from_event_id: String) -> Self {
+        self.from_event_id = Some(from_event_id);
+        self
+    }
+
+    pub fn with_filter(mut self, filter: ResubscribeFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+}
+
+/// The kinds of events a resubscription can be limited to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StreamEventKind {
+    Task,
+    Message,
+    StatusUpdate,
+    ArtifactUpdate,
+}
+
+/// Limits the events streamed by `tasks/resubscribe`
+///
+/// The filter applies to every event of the stream, including the task
+/// snapshot it starts with. Events are resumed after a sequence with
+/// `TaskIdParams::from_event_id`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-params", serde(deny_unknown_fields))]
+pub struct ResubscribeFilter {
+    /// Only stream events of these kinds; all kinds when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kinds: Option<Vec<StreamEventKind>>,
+    /// Only stream events with a status timestamp after this RFC 3339 time;
+    /// events without a timestamp, such as artifact updates, are kept
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+}
+
+impl ResubscribeFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only stream status updates, for state changes
+    pub fn status_updates() -> Self {
+        Self::new().with_kinds(vec![StreamEventKind::StatusUpdate])
+    }
+
+    /// Only stream artifact updates
+    pub fn artifact_updates() -> Self {
+        Self::new().with_kinds(vec![StreamEventKind::ArtifactUpdate])
+    }
+
+    pub fn with_kinds(mut self, kinds: Vec<StreamEventKind>) -> Self {
+        self.kinds = Some(kinds);
+        self
+    }
+
+    pub fn with_since(mut self, since: String) -> Self {
+        self.since = Some(since);
+        self
+    }
 }
 
 /// Defines parameters for querying a task, with an option to limit history length
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-params", serde(deny_unknown_fields))]
 pub struct TaskQueryParams {
     /// The unique identifier (e.g. UUID) of the task
     pub id: String,
     /// The number of most recent messages from the task's history to retrieve
-    #[serde(rename = "history_length")]
+    #[serde(alias = "history_length", skip_serializing_if = "Option::is_none")]
     pub history_length: Option<i32>,
     /// Optional metadata associated with the request
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
@@ -874,13 +1175,16 @@ impl TaskQueryParams {
 
 /// Defines parameters for deleting a specific push notification configuration for a task
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-params", serde(deny_unknown_fields))]
 pub struct DeleteTaskPushNotificationConfigParams {
     /// The unique identifier (e.g. UUID) of the task
     pub id: String,
     /// The ID of the push notification configuration to delete
-    #[serde(rename = "push_notification_config_id")]
+    #[serde(alias = "push_notification_config_id")]
     pub push_notification_config_id: String,
     /// Optional metadata associated with the request
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
@@ -901,13 +1205,16 @@ impl DeleteTaskPushNotificationConfigParams {
 
 /// Defines parameters for fetching a specific push notification configuration for a task
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-params", serde(deny_unknown_fields))]
 pub struct GetTaskPushNotificationConfigParams {
     /// The unique identifier (e.g. UUID) of the task
     pub id: String,
     /// The ID of the push notification configuration to retrieve
-    #[serde(rename = "push_notification_config_id")]
+    #[serde(alias = "push_notification_config_id", skip_serializing_if = "Option::is_none")]
     pub push_notification_config_id: Option<String>,
     /// Optional metadata associated with the request
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
@@ -933,10 +1240,13 @@ impl GetTaskPushNotificationConfigParams {
 
 /// Defines parameters for listing all push notification configurations associated with a task
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-params", serde(deny_unknown_fields))]
 pub struct ListTaskPushNotificationConfigParams {
     /// The unique identifier (e.g. UUID) of the task
     pub id: String,
     /// Optional metadata associated with the request
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
 }
 
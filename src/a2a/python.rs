@@ -0,0 +1,564 @@
+//! Python bindings for the server core
+//!
+//! The `python` feature provides the classes of the `a2a_rust` extension
+//! module, which is built from the `python/` crate (`maturin develop`, see
+//! `pyproject.toml`) so that this crate stays a plain library. The module
+//! exposes the task stores, the JSON-RPC handler and the push notification
+//! sender; values cross the boundary as plain dicts in the A2A JSON shape.
+//! Blocking calls release the GIL and run on a shared Tokio runtime.
+//!
+//! Executors stay in Python: [`PythonAgentExecutor`] adapts a Python callable
+//! to [`AgentExecutor`]. Given an `executor` callable, `JsonRpcHandler` runs
+//! it for every message and stores the task events it returns.
+
+use std::sync::{Arc, OnceLock};
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::a2a::jsonrpc::SendStreamingMessageResult;
+use crate::a2a::models::*;
+use crate::a2a::server::agent_execution::{AgentExecutor, RequestContext};
+use crate::a2a::server::context::ServerCallContext;
+use crate::a2a::server::events::{Event, EventQueue, InMemoryEventQueue};
+use crate::a2a::server::request_handlers::request_handler::{
+    Event as HandlerEvent, MessageSendResult, RequestHandler, TaskPushNotificationConfigQueryParams,
+};
+use crate::a2a::server::request_handlers::{DefaultRequestHandler, JSONRPCHandler};
+use crate::a2a::server::tasks::{
+    HttpPushNotificationSender, InMemoryPushNotificationConfigStore, InMemoryTaskStore,
+    PushNotificationConfigStore, PushNotificationSender, SqliteTaskStore, TaskManager, TaskStore,
+};
+use crate::{A2AError, Message, Role};
+
+create_exception!(a2a_rust, A2AException, PyException, "An A2A error carrying `(code, message)`");
+
+/// Runtime shared by all blocking calls
+fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("failed to start the Tokio runtime")
+    })
+}
+
+/// Runs a future to completion with the GIL released
+fn block_on<F>(py: Python<'_>, future: F) -> Result<F::Output, PyErr>
+where
+    F: std::future::Future + Send,
+    F::Output: Send,
+{
+    Ok(py.allow_threads(|| runtime().block_on(future)))
+}
+
+fn py_err(error: A2AError) -> PyErr {
+    A2AException::new_err((error.code(), error.message().to_string()))
+}
+
+/// Converts JSON into the equivalent Python object
+fn to_py(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    let json = py.import("json")?;
+    Ok(json.call_method1("loads", (value.to_string(),))?.unbind())
+}
+
+/// Converts a JSON-compatible Python object into `T`
+fn from_py<T: DeserializeOwned>(value: &Bound<'_, PyAny>) -> PyResult<T> {
+    let json = value.py().import("json")?;
+    let text: String = json.call_method1("dumps", (value,))?.extract()?;
+    serde_json::from_str(&text).map_err(|e| py_err(A2AError::invalid_params(&e.to_string())))
+}
+
+/// Task store usable from Python
+#[pyclass(name = "TaskStore", module = "a2a_rust")]
+#[derive(Clone)]
+pub struct PyTaskStore {
+    inner: Arc<dyn TaskStore>,
+}
+
+#[pymethods]
+impl PyTaskStore {
+    /// Creates a store keeping tasks in memory
+    #[staticmethod]
+    fn in_memory() -> Self {
+        Self { inner: Arc::new(InMemoryTaskStore::new()) }
+    }
+
+    /// Opens a SQLite store, creating the schema if needed
+    #[staticmethod]
+    fn sqlite(py: Python<'_>, url: &str) -> PyResult<Self> {
+        let store = block_on(py, SqliteTaskStore::connect(url))?.map_err(py_err)?;
+        Ok(Self { inner: Arc::new(store) })
+    }
+
+    fn get(&self, py: Python<'_>, task_id: &str) -> PyResult<Option<PyObject>> {
+        let task = block_on(py, self.inner.get(task_id))?.map_err(py_err)?;
+        task.map(|task| to_py(py, &json!(task))).transpose()
+    }
+
+    fn save(&self, py: Python<'_>, task: &Bound<'_, PyAny>) -> PyResult<()> {
+        let task: Task = from_py(task)?;
+        block_on(py, self.inner.save(task))?.map_err(py_err)
+    }
+
+    fn delete(&self, py: Python<'_>, task_id: &str) -> PyResult<()> {
+        block_on(py, self.inner.delete(task_id))?.map_err(py_err)
+    }
+}
+
+/// Webhook push notification sender with its own config store
+#[pyclass(name = "PushNotificationSender", module = "a2a_rust")]
+pub struct PyPushNotificationSender {
+    config_store: Arc<dyn PushNotificationConfigStore>,
+    sender: Arc<HttpPushNotificationSender>,
+}
+
+#[pymethods]
+impl PyPushNotificationSender {
+    #[new]
+    fn new() -> Self {
+        let config_store: Arc<dyn PushNotificationConfigStore> =
+            Arc::new(InMemoryPushNotificationConfigStore::new());
+        let sender = Arc::new(HttpPushNotificationSender::new(config_store.clone()));
+        Self { config_store, sender }
+    }
+
+    /// Registers a webhook for a task
+    fn set_config(&self, py: Python<'_>, task_id: &str, config: &Bound<'_, PyAny>) -> PyResult<()> {
+        let config: PushNotificationConfig = from_py(config)?;
+        block_on(py, self.config_store.set_info(task_id, config))?.map_err(py_err)
+    }
+
+    /// Notifies every webhook registered for the task
+    fn send(&self, py: Python<'_>, task: &Bound<'_, PyAny>) -> PyResult<()> {
+        let task: Task = from_py(task)?;
+        block_on(py, self.sender.send_notification(&task))?.map_err(py_err)
+    }
+}
+
+/// JSON-RPC handler over a [`DefaultRequestHandler`]
+#[pyclass(name = "JsonRpcHandler", module = "a2a_rust")]
+pub struct PyJsonRpcHandler {
+    inner: Arc<JSONRPCHandler>,
+}
+
+#[pymethods]
+impl PyJsonRpcHandler {
+    /// Without an `executor`, messages only create working tasks
+    #[new]
+    #[pyo3(signature = (agent_card, task_store, push_sender = None, executor = None, cancel = None))]
+    fn new(
+        agent_card: &Bound<'_, PyAny>,
+        task_store: &PyTaskStore,
+        push_sender: Option<PyRef<'_, PyPushNotificationSender>>,
+        executor: Option<PyObject>,
+        cancel: Option<PyObject>,
+    ) -> PyResult<Self> {
+        let agent_card: AgentCard = from_py(agent_card)?;
+        let (config_store, sender) = match push_sender {
+            Some(push) => (
+                Some(push.config_store.clone()),
+                Some(push.sender.clone() as Arc<dyn PushNotificationSender>),
+            ),
+            None => (None, None),
+        };
+        // Subscribing the push sender spawns onto the runtime
+        let _guard = runtime().enter();
+        let request_handler = Arc::new(DefaultRequestHandler::new(task_store.inner.clone(), config_store, sender));
+        let request_handler: Arc<dyn RequestHandler> = match executor {
+            Some(execute) => {
+                let mut executor = PythonAgentExecutor::new(execute);
+                if let Some(cancel) = cancel {
+                    executor = executor.with_cancel(cancel);
+                }
+                Arc::new(ExecutingRequestHandler {
+                    inner: request_handler,
+                    task_store: task_store.inner.clone(),
+                    executor: Arc::new(executor),
+                })
+            }
+            None => request_handler,
+        };
+        Ok(Self {
+            inner: Arc::new(JSONRPCHandler::new(agent_card, request_handler)),
+        })
+    }
+
+    /// Handles a JSON-RPC request dict and returns the response envelope
+    fn handle(&self, py: Python<'_>, request: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        let request: Value = from_py(request)?;
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let handler = self.inner.clone();
+        let response = block_on(py, async move {
+            handler.handle_request(request, &ServerCallContext::new()).await
+        })?;
+        let response = response.unwrap_or_else(|error| {
+            json!({"jsonrpc": "2.0", "id": id, "error": error})
+        });
+        to_py(py, &response)
+    }
+}
+
+/// Agent executor delegating to a Python callable
+///
+/// The callable receives the request context as a dict with `taskId`,
+/// `contextId`, `message` and `currentTask`, and returns `None`, one event
+/// dict or a list of them. Events are told apart by their `kind`, as on the
+/// wire. Cancellation calls the optional cancel callable the same way.
+pub struct PythonAgentExecutor {
+    execute: Arc<PyObject>,
+    cancel: Option<Arc<PyObject>>,
+}
+
+impl PythonAgentExecutor {
+    /// Runs `execute` for every request
+    pub fn new(execute: PyObject) -> Self {
+        Self { execute: Arc::new(execute), cancel: None }
+    }
+
+    /// Sets the callable invoked on cancellation
+    pub fn with_cancel(mut self, cancel: PyObject) -> Self {
+        self.cancel = Some(Arc::new(cancel));
+        self
+    }
+
+    fn context_json(context: &RequestContext) -> Value {
+        json!({
+            "taskId": context.task_id,
+            "contextId": context.context_id,
+            "message": context.request.as_ref().map(|params| &params.message),
+            "currentTask": context.current_task,
+        })
+    }
+
+    /// Calls the callable off the async runtime and parses the events it returns
+    async fn call(callback: Arc<PyObject>, context: Value) -> Result<Vec<Event>, A2AError> {
+        let returned = tokio::task::spawn_blocking(move || {
+            Python::with_gil(|py| -> PyResult<Value> {
+                let result = callback.call1(py, (to_py(py, &context)?,))?;
+                let result = result.bind(py);
+                if result.is_none() {
+                    return Ok(Value::Null);
+                }
+                from_py(result)
+            })
+            .map_err(|e| A2AError::internal(&format!("Python executor failed: {}", e)))
+        })
+        .await??;
+
+        let items = match returned {
+            Value::Null => Vec::new(),
+            Value::Array(items) => items,
+            item => vec![item],
+        };
+        items
+            .into_iter()
+            .map(|item| {
+                let event: SendStreamingMessageResult = serde_json::from_value(item)
+                    .map_err(|e| A2AError::invalid_response(&format!("Invalid event from executor: {}", e)))?;
+                Ok(match event {
+                    SendStreamingMessageResult::Task(task) => Event::Task(task),
+                    SendStreamingMessageResult::Message(message) => Event::Message(message),
+                    SendStreamingMessageResult::TaskStatusUpdate(update) => Event::TaskStatusUpdate(update),
+                    SendStreamingMessageResult::TaskArtifactUpdate(update) => Event::TaskArtifactUpdate(update),
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl AgentExecutor for PythonAgentExecutor {
+    async fn execute(
+        &self,
+        context: RequestContext,
+        event_queue: Arc<dyn EventQueue>,
+    ) -> Result<(), A2AError> {
+        for event in Self::call(self.execute.clone(), Self::context_json(&context)).await? {
+            event_queue.enqueue_event(event).await?;
+        }
+        Ok(())
+    }
+
+    async fn cancel(
+        &self,
+        context: RequestContext,
+        event_queue: Arc<dyn EventQueue>,
+    ) -> Result<(), A2AError> {
+        let cancel = self
+            .cancel
+            .clone()
+            .ok_or_else(|| A2AError::unsupported_operation("Python executor has no cancel callback"))?;
+        for event in Self::call(cancel, Self::context_json(&context)).await? {
+            event_queue.enqueue_event(event).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs an executor for messages and cancellations, storing the task events
+/// it produces, and leaves every other method to `inner`
+struct ExecutingRequestHandler {
+    inner: Arc<DefaultRequestHandler>,
+    task_store: Arc<dyn TaskStore>,
+    executor: Arc<dyn AgentExecutor>,
+}
+
+impl ExecutingRequestHandler {
+    /// Runs the executor for `message` and saves the events it enqueued
+    ///
+    /// Returns the saved events, and the task if there were task events.
+    async fn run(
+        &self,
+        message: Message,
+        params: Option<MessageSendParams>,
+        context: Option<&ServerCallContext>,
+        cancel: bool,
+    ) -> Result<(Vec<Event>, Option<Task>), A2AError> {
+        let task_id = message.task_id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let context_id = message.context_id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let existing = self.task_store.get(&task_id).await?;
+        let request_context = RequestContext::new(
+            params,
+            Some(task_id.clone()),
+            Some(context_id.clone()),
+            existing,
+            None,
+            context.cloned(),
+            None,
+            None,
+        )
+        .await?;
+
+        let queue = Arc::new(InMemoryEventQueue::new()?);
+        if cancel {
+            self.executor.cancel(request_context, queue.clone()).await?;
+        } else {
+            self.executor.execute(request_context, queue.clone()).await?;
+        }
+
+        let mut task_manager = TaskManager::new(Some(task_id), Some(context_id), self.task_store.clone(), Some(message), None)?
+            .with_event_bus(self.inner.event_bus().clone());
+        let mut events = Vec::new();
+        let mut has_task = false;
+        while let Ok(event) = queue.dequeue_event(true).await {
+            has_task |= !matches!(event, Event::Message(_));
+            events.push(task_manager.process_event(&event).await?);
+        }
+        let task = if has_task { task_manager.get_task().await? } else { None };
+        Ok((events, task))
+    }
+}
+
+#[async_trait]
+impl RequestHandler for ExecutingRequestHandler {
+    async fn on_get_task(
+        &self,
+        params: TaskQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        self.inner.on_get_task(params, context).await
+    }
+
+    async fn on_cancel_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        let Some(task) = self.task_store.get(&params.id).await? else {
+            return Ok(None);
+        };
+        let message = Message::new(Role::User, Vec::new())
+            .with_task_id(task.id.clone())
+            .with_context_id(task.context_id.clone());
+        let (_, cancelled) = self.run(message, None, context, true).await?;
+        Ok(cancelled.or(Some(task)))
+    }
+
+    async fn on_message_send(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<MessageSendResult, A2AError> {
+        let (events, task) = self.run(params.message.clone(), Some(params), context, false).await?;
+        if let Some(task) = task {
+            return Ok(MessageSendResult::Task(task));
+        }
+        match events.into_iter().last() {
+            Some(Event::Message(message)) => Ok(MessageSendResult::Message(message)),
+            _ => Err(A2AError::internal("Python executor returned no events")),
+        }
+    }
+
+    async fn on_message_send_stream(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<HandlerEvent, A2AError>>, A2AError> {
+        let (events, _) = self.run(params.message.clone(), Some(params), context, false).await?;
+        Ok(Box::pin(futures::stream::iter(events.into_iter().map(|event| Ok(HandlerEvent::from(event))))))
+    }
+
+    async fn on_set_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfig,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_set_task_push_notification_config(params, context).await
+    }
+
+    async fn on_get_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfigQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_get_task_push_notification_config(params, context).await
+    }
+
+    async fn on_resubscribe_to_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<HandlerEvent, A2AError>>, A2AError> {
+        self.inner.on_resubscribe_to_task(params, context).await
+    }
+
+    async fn on_list_task_push_notification_config(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Vec<TaskPushNotificationConfig>, A2AError> {
+        self.inner.on_list_task_push_notification_config(params, context).await
+    }
+
+    async fn on_delete_task_push_notification_config(
+        &self,
+        params: DeleteTaskPushNotificationConfigParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<(), A2AError> {
+        self.inner.on_delete_task_push_notification_config(params, context).await
+    }
+}
+
+/// Adds the classes of the `a2a_rust` extension module to `m`
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("A2AError", m.py().get_type::<A2AException>())?;
+    m.add_class::<PyTaskStore>()?;
+    m.add_class::<PyPushNotificationSender>()?;
+    m.add_class::<PyJsonRpcHandler>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AgentCard, Part};
+
+    fn context_message() -> Message {
+        Message::new(Role::User, vec![Part::text("hello".to_string())])
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_python_executor_enqueues_returned_events() {
+        pyo3::prepare_freethreaded_python();
+        let callback = Python::with_gil(|py| -> PyResult<PyObject> {
+            let module = PyModule::from_code(
+                py,
+                c"def execute(ctx):\n    text = ctx['message']['parts'][0]['text']\n    return {'kind': 'message', 'messageId': 'm2', 'role': 'agent', 'parts': [{'kind': 'text', 'text': text.upper()}]}\n",
+                c"executor.py",
+                c"executor",
+            )?;
+            Ok(module.getattr("execute")?.unbind())
+        })
+        .unwrap();
+
+        let queue = Arc::new(InMemoryEventQueue::new().unwrap());
+        let context = RequestContext::new(
+            Some(crate::MessageSendParams::new(context_message())),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        PythonAgentExecutor::new(callback).execute(context, queue.clone()).await.unwrap();
+
+        match queue.dequeue_event(true).await.unwrap() {
+            Event::Message(message) => assert_eq!(message.message_id, "m2"),
+            other => panic!("expected message, got {:?}", other),
+        }
+    }
+
+    fn agent_card(py: Python<'_>) -> PyObject {
+        let card = AgentCard::new(
+                "Test Agent".to_string(),
+                "A test agent".to_string(),
+                "http://localhost:8080".to_string(),
+                "1.0.0".to_string(),
+                vec!["text/plain".to_string()],
+                vec!["text/plain".to_string()],
+                crate::AgentCapabilities::new(),
+            vec![],
+        );
+        to_py(py, &json!(card)).unwrap()
+    }
+
+    #[test]
+    fn test_handler_returns_error_envelope() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let card = agent_card(py);
+            let store = PyTaskStore::in_memory();
+            let handler = PyJsonRpcHandler::new(card.bind(py), &store, None, None, None).unwrap();
+            let request = to_py(py, &json!({"jsonrpc": "2.0", "id": 7, "method": "tasks/unknown", "params": {}})).unwrap();
+            let response: Value = from_py(handler.handle(py, request.bind(py)).unwrap().bind(py)).unwrap();
+            assert_eq!(response["id"], 7);
+            assert_eq!(response["error"]["code"], -32601);
+        });
+    }
+
+    #[test]
+    fn test_handler_runs_the_python_executor() {
+        const TASK_ID: &str = "6f1c2b9e-3d4a-4e8b-9c1d-2a3b4c5d6e7f";
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::from_code(
+                py,
+                c"def execute(ctx):\n    return {'kind': 'status-update', 'taskId': ctx['taskId'], 'contextId': ctx['contextId'], 'status': {'state': 'completed'}, 'final': True}\n",
+                c"executor.py",
+                c"executor",
+            )
+            .unwrap();
+            let execute = module.getattr("execute").unwrap().unbind();
+            let card = agent_card(py);
+            let store = PyTaskStore::in_memory();
+            let handler = PyJsonRpcHandler::new(card.bind(py), &store, None, Some(execute), None).unwrap();
+            let request = to_py(
+                py,
+                &json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "message/send",
+                    "params": {"message": {"kind": "message", "messageId": "m1", "role": "user", "taskId": TASK_ID, "parts": [{"kind": "text", "text": "hi"}]}}
+                }),
+            )
+            .unwrap();
+            let response: Value = from_py(handler.handle(py, request.bind(py)).unwrap().bind(py)).unwrap();
+            assert_eq!(response["result"]["id"], TASK_ID, "{}", response);
+            assert_eq!(response["result"]["status"]["state"], "completed");
+
+            let stored: Value = from_py(store.get(py, TASK_ID).unwrap().unwrap().bind(py)).unwrap();
+            assert_eq!(stored["status"]["state"], "completed");
+        });
+    }
+}
@@ -0,0 +1,308 @@
+//! Generator of ready-to-run agent projects
+//!
+//! `Scaffold` renders a Cargo project serving an A2A agent from templates
+//! embedded in the crate: a `main.rs` setting up the server, the chosen task
+//! store and authentication, and an `agent.rs` stub answering messages with
+//! an echo, to be replaced by the agent's logic. The `a2a-scaffold` binary
+//! writes such a project from the command line:
+//!
+//! ```text
+//! cargo run --features scaffold --bin a2a-scaffold -- my-agent --store sqlite --auth api-key
+//! ```
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use thiserror::Error;
+
+const CARGO_TOML: &str = include_str!("templates/Cargo.toml.tmpl");
+const GITIGNORE: &str = include_str!("templates/gitignore.tmpl");
+const README: &str = include_str!("templates/README.md.tmpl");
+const MAIN_RS: &str = include_str!("templates/main.rs.tmpl");
+const AGENT_RS: &str = include_str!("templates/agent.rs.tmpl");
+const STORE_MEMORY: &str = include_str!("templates/store_memory.rs.tmpl");
+const STORE_SQLITE: &str = include_str!("templates/store_sqlite.rs.tmpl");
+const AUTH_NONE: &str = include_str!("templates/auth_none.rs.tmpl");
+const AUTH_API_KEY: &str = include_str!("templates/auth_api_key.rs.tmpl");
+const AUTHORIZE_API_KEY: &str = include_str!("templates/authorize_api_key.rs.tmpl");
+
+/// Where the generated agent keeps its tasks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StoreKind {
+    /// `InMemoryTaskStore`, lost on restart
+    #[default]
+    Memory,
+    /// `SqliteTaskStore` at `DATABASE_URL`
+    Sqlite,
+}
+
+/// How callers of the generated agent authenticate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AuthMode {
+    /// Every caller is accepted
+    #[default]
+    None,
+    /// Callers send the key in `A2A_API_KEY` as the `X-API-Key` header
+    ApiKey,
+}
+
+impl FromStr for StoreKind {
+    type Err = ScaffoldError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "memory" => Ok(StoreKind::Memory),
+            "sqlite" => Ok(StoreKind::Sqlite),
+            _ => Err(ScaffoldError::UnknownOption { option: "store", value: s.to_string() }),
+        }
+    }
+}
+
+impl FromStr for AuthMode {
+    type Err = ScaffoldError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(AuthMode::None),
+            "api-key" => Ok(AuthMode::ApiKey),
+            _ => Err(ScaffoldError::UnknownOption { option: "auth", value: s.to_string() }),
+        }
+    }
+}
+
+/// Errors of generating a project
+#[derive(Error, Debug)]
+pub enum ScaffoldError {
+    #[error("'{0}' is not a valid crate name: use ASCII letters, digits, '-' and '_', starting with a letter")]
+    InvalidName(String),
+
+    #[error("Unknown {option} '{value}'")]
+    UnknownOption { option: &'static str, value: String },
+
+    #[error("'{}' already exists and is not empty", .0.display())]
+    DirectoryNotEmpty(PathBuf),
+
+    #[error("Failed to write '{}': {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+}
+
+/// A file of a generated project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedFile {
+    /// Path relative to the project directory
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+/// The choices a project is generated from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scaffold {
+    name: String,
+    store: StoreKind,
+    auth: AuthMode,
+    a2a_path: Option<PathBuf>,
+}
+
+impl Scaffold {
+    /// A project for the crate `name`, with an in-memory store and no
+    /// authentication
+    pub fn new(name: &str) -> Result<Self, ScaffoldError> {
+        let valid = name.starts_with(|c: char| c.is_ascii_alphabetic())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(ScaffoldError::InvalidName(name.to_string()));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            store: StoreKind::default(),
+            auth: AuthMode::default(),
+            a2a_path: None,
+        })
+    }
+
+    /// Set the task store
+    pub fn with_store(mut self, store: StoreKind) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Set the authentication mode
+    pub fn with_auth(mut self, auth: AuthMode) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Depend on the a2a-rust checkout at `path` instead of the released
+    /// version of this crate
+    pub fn with_a2a_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.a2a_path = Some(path.into());
+        self
+    }
+
+    /// The files of the project
+    pub fn render(&self) -> Vec<GeneratedFile> {
+        let agent_name = self
+            .name
+            .split(['-', '_'])
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                let mut chars = word.chars();
+                chars.next().map(|first| first.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let a2a_dependency = match &self.a2a_path {
+            Some(path) => format!("path = {:?}", path.display().to_string()),
+            None => format!("version = \"{}\"", env!("CARGO_PKG_VERSION")),
+        };
+        let (store_setup, store_description) = match self.store {
+            StoreKind::Memory => (STORE_MEMORY, "in memory, lost on restart"),
+            StoreKind::Sqlite => (STORE_SQLITE, "in the SQLite database at `DATABASE_URL`"),
+        };
+        let (auth_setup, authorize, context, auth_description, run_command) = match self.auth {
+            AuthMode::None => (AUTH_NONE, "", "_context", "none, every caller is accepted", "cargo run"),
+            AuthMode::ApiKey => (
+                AUTH_API_KEY,
+                AUTHORIZE_API_KEY,
+                "context",
+                "callers send the key in `A2A_API_KEY` as the `X-API-Key` header",
+                "A2A_API_KEY=change-me cargo run",
+            ),
+        };
+
+        // Fragments first, so that the placeholders they contain are filled in too
+        let vars = [
+            ("store_setup", store_setup),
+            ("auth_setup", auth_setup),
+            ("authorize", authorize),
+            ("context", context),
+            ("crate_name", &self.name),
+            ("agent_name", &agent_name),
+            ("a2a_dependency", &a2a_dependency),
+            ("store_description", store_description),
+            ("auth_description", auth_description),
+            ("run_command", run_command),
+        ];
+        let render = |template: &str| {
+            vars.iter()
+                .fold(template.to_string(), |text, (key, value)| text.replace(&format!("{{{{{}}}}}", key), value))
+        };
+
+        [
+            ("Cargo.toml", CARGO_TOML),
+            (".gitignore", GITIGNORE),
+            ("README.md", README),
+            ("src/main.rs", MAIN_RS),
+            ("src/agent.rs", AGENT_RS),
+        ]
+        .into_iter()
+        .map(|(path, template)| GeneratedFile {
+            path: PathBuf::from(path),
+            contents: render(template),
+        })
+        .collect()
+    }
+
+    /// Writes the project to `dir`, which must not exist or be empty
+    ///
+    /// Returns the paths of the written files.
+    pub fn write_to(&self, dir: &Path) -> Result<Vec<PathBuf>, ScaffoldError> {
+        let io = |path: &Path| {
+            let path = path.to_path_buf();
+            move |source| ScaffoldError::Io { path, source }
+        };
+        if dir.exists() && std::fs::read_dir(dir).map_err(io(dir))?.next().is_some() {
+            return Err(ScaffoldError::DirectoryNotEmpty(dir.to_path_buf()));
+        }
+        let mut written = Vec::new();
+        for file in self.render() {
+            let path = dir.join(&file.path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(io(parent))?;
+            }
+            std::fs::write(&path, file.contents).map_err(io(&path))?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+}
+
+impl fmt::Display for StoreKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StoreKind::Memory => "memory",
+            StoreKind::Sqlite => "sqlite",
+        })
+    }
+}
+
+impl fmt::Display for AuthMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AuthMode::None => "none",
+            AuthMode::ApiKey => "api-key",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file<'a>(files: &'a [GeneratedFile], path: &str) -> &'a str {
+        &files.iter().find(|file| file.path == Path::new(path)).unwrap().contents
+    }
+
+    #[test]
+    fn test_every_placeholder_is_filled_in() {
+        for store in [StoreKind::Memory, StoreKind::Sqlite] {
+            for auth in [AuthMode::None, AuthMode::ApiKey] {
+                let files = Scaffold::new("weather-agent").unwrap().with_store(store).with_auth(auth).render();
+                assert_eq!(files.len(), 5);
+                for file in &files {
+                    assert!(!file.contents.contains("{{"), "{} ({}, {})", file.path.display(), store, auth);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_choices_select_the_setup() {
+        let files = Scaffold::new("weather_agent")
+            .unwrap()
+            .with_store(StoreKind::Sqlite)
+            .with_auth(AuthMode::ApiKey)
+            .with_a2a_path("../a2a-rust")
+            .render();
+        assert!(file(&files, "Cargo.toml").contains("name = \"weather_agent\""));
+        assert!(file(&files, "Cargo.toml").contains("path = \"../a2a-rust\""));
+        assert!(file(&files, "src/main.rs").contains("SqliteTaskStore::connect"));
+        assert!(file(&files, "src/main.rs").contains("ApiKeyContextBuilder"));
+        assert!(file(&files, "src/main.rs").contains("\"Weather Agent\""));
+        assert!(file(&files, "src/agent.rs").contains("Missing or unknown API key"));
+
+        let files = Scaffold::new("echo").unwrap().render();
+        assert!(file(&files, "Cargo.toml").contains(&format!("version = \"{}\"", env!("CARGO_PKG_VERSION"))));
+        assert!(file(&files, "src/main.rs").contains("InMemoryTaskStore"));
+        assert!(!file(&files, "src/agent.rs").contains("API key"));
+    }
+
+    #[test]
+    fn test_names_and_options_are_checked() {
+        assert!(matches!(Scaffold::new("1agent"), Err(ScaffoldError::InvalidName(_))));
+        assert!(matches!(Scaffold::new("my agent"), Err(ScaffoldError::InvalidName(_))));
+        assert_eq!("sqlite".parse::<StoreKind>().unwrap(), StoreKind::Sqlite);
+        assert_eq!("api-key".parse::<AuthMode>().unwrap(), AuthMode::ApiKey);
+        assert!("postgres".parse::<StoreKind>().is_err());
+    }
+
+    #[test]
+    fn test_write_refuses_non_empty_directories() {
+        let dir = std::env::temp_dir().join(format!("a2a-scaffold-{}", uuid::Uuid::new_v4()));
+        let scaffold = Scaffold::new("echo").unwrap();
+        let written = scaffold.write_to(&dir).unwrap();
+        assert!(written.contains(&dir.join("src/agent.rs")));
+        assert!(matches!(scaffold.write_to(&dir), Err(ScaffoldError::DirectoryNotEmpty(_))));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
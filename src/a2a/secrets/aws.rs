@@ -0,0 +1,56 @@
+//! AWS Secrets Manager provider
+//!
+//! Secrets are named by name or ARN and fetched at their `AWSCURRENT`
+//! stage. Credentials and region come from the default AWS chain
+//! (environment, shared config, SSO, instance and task roles).
+
+use super::{Secret, SecretProvider};
+use crate::a2a::error::A2AError;
+use async_trait::async_trait;
+use aws_sdk_secretsmanager::Client;
+
+/// Secrets stored in AWS Secrets Manager
+#[derive(Debug, Clone)]
+pub struct AwsSecretsManager {
+    client: Client,
+}
+
+impl AwsSecretsManager {
+    /// Uses an already configured Secrets Manager client
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Configures the client from the default AWS credential chain
+    pub async fn from_env() -> Self {
+        let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        Self::new(Client::new(&config))
+    }
+}
+
+#[async_trait]
+impl SecretProvider for AwsSecretsManager {
+    async fn get_secret(&self, name: &str) -> Result<Option<Secret>, A2AError> {
+        let output = match self.client.get_secret_value().secret_id(name).send().await {
+            Ok(output) => output,
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_resource_not_found_exception()) => return Ok(None),
+            Err(e) => {
+                return Err(A2AError::internal(&format!(
+                    "Failed to read secret '{}' from AWS Secrets Manager: {}",
+                    name,
+                    aws_sdk_secretsmanager::error::DisplayErrorContext(e)
+                )))
+            }
+        };
+        let value = match (output.secret_string(), output.secret_binary()) {
+            (Some(text), _) => text.as_bytes().to_vec(),
+            (None, Some(binary)) => binary.as_ref().to_vec(),
+            (None, None) => return Ok(None),
+        };
+        let secret = Secret::new(value);
+        Ok(Some(match output.version_id() {
+            Some(version) => secret.with_version(version),
+            None => secret,
+        }))
+    }
+}
@@ -0,0 +1,181 @@
+//! GCP Secret Manager provider
+//!
+//! Secrets are named either by secret id, read at the `latest` version of
+//! the configured project, or by full resource name
+//! (`projects/<p>/secrets/<s>[/versions/<v>]`). Access tokens come from the
+//! GCE metadata server, which serves the attached service account on GCE,
+//! GKE and Cloud Run, unless a static token is set with `with_access_token`.
+
+use super::{Secret, SecretProvider};
+use crate::a2a::error::A2AError;
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Deserialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Default Secret Manager API endpoint
+pub const DEFAULT_ENDPOINT: &str = "https://secretmanager.googleapis.com";
+
+/// Metadata server endpoint issuing tokens of the attached service account
+pub const METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+#[derive(Deserialize)]
+struct AccessResponse {
+    name: String,
+    payload: Payload,
+}
+
+#[derive(Deserialize)]
+struct Payload {
+    data: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+/// Secrets stored in GCP Secret Manager
+pub struct GcpSecretManager {
+    client: reqwest::Client,
+    project: String,
+    endpoint: String,
+    static_token: Option<String>,
+    token: Mutex<Option<(String, Instant)>>,
+}
+
+impl std::fmt::Debug for GcpSecretManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GcpSecretManager")
+            .field("project", &self.project)
+            .field("endpoint", &self.endpoint)
+            .field("static_token", &self.static_token.as_ref().map(|_| "<redacted>"))
+            .finish_non_exhaustive()
+    }
+}
+
+impl GcpSecretManager {
+    /// Reads secrets of `project`, authenticating as the attached service
+    /// account
+    pub fn new(project: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            project: project.into(),
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            static_token: None,
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Authenticates with `token` instead of the metadata server
+    pub fn with_access_token(mut self, token: impl Into<String>) -> Self {
+        self.static_token = Some(token.into());
+        self
+    }
+
+    /// Sets the API endpoint, e.g. a regional or private endpoint
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    fn resource(&self, name: &str) -> String {
+        match name {
+            name if name.starts_with("projects/") && name.contains("/versions/") => name.to_string(),
+            name if name.starts_with("projects/") => format!("{}/versions/latest", name),
+            name => format!("projects/{}/secrets/{}/versions/latest", self.project, name),
+        }
+    }
+
+    async fn access_token(&self) -> Result<String, A2AError> {
+        if let Some(token) = &self.static_token {
+            return Ok(token.clone());
+        }
+        if let Some((token, expires_at)) = self.token.lock().unwrap().as_ref() {
+            if Instant::now() < *expires_at {
+                return Ok(token.clone());
+            }
+        }
+        let error = |e: reqwest::Error| A2AError::internal(&format!("Failed to get a GCP access token: {}", e));
+        let response: TokenResponse = self
+            .client
+            .get(METADATA_TOKEN_URL)
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(error)?
+            .json()
+            .await
+            .map_err(error)?;
+        // Renew a minute early so tokens do not expire in flight
+        let expires_at = Instant::now() + Duration::from_secs(response.expires_in.saturating_sub(60));
+        *self.token.lock().unwrap() = Some((response.access_token.clone(), expires_at));
+        Ok(response.access_token)
+    }
+}
+
+#[async_trait]
+impl SecretProvider for GcpSecretManager {
+    async fn get_secret(&self, name: &str) -> Result<Option<Secret>, A2AError> {
+        let error = |e: &dyn std::fmt::Display| {
+            A2AError::internal(&format!("Failed to read secret '{}' from GCP Secret Manager: {}", name, e))
+        };
+        let response = self
+            .client
+            .get(format!("{}/v1/{}:access", self.endpoint, self.resource(name)))
+            .bearer_auth(self.access_token().await?)
+            .send()
+            .await
+            .map_err(|e| error(&e))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response: AccessResponse = response
+            .error_for_status()
+            .map_err(|e| error(&e))?
+            .json()
+            .await
+            .map_err(|e| error(&e))?;
+        let value = STANDARD.decode(&response.payload.data).map_err(|e| error(&e))?;
+        let version = response.name.rsplit('/').next().unwrap_or_default().to_string();
+        Ok(Some(Secret::new(value).with_version(version)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reads_latest_version_of_project_secret() {
+        let mut server = mockito::Server::new_async().await;
+        let _found = server
+            .mock("GET", "/v1/projects/agents/secrets/push-key/versions/latest:access")
+            .match_header("authorization", "Bearer test-token")
+            .with_body(format!(
+                r#"{{"name":"projects/123/secrets/push-key/versions/7","payload":{{"data":"{}"}}}}"#,
+                STANDARD.encode("s3cret")
+            ))
+            .create_async()
+            .await;
+        let _missing = server
+            .mock("GET", "/v1/projects/agents/secrets/missing/versions/latest:access")
+            .with_status(404)
+            .create_async()
+            .await;
+        let provider = GcpSecretManager::new("agents")
+            .with_access_token("test-token")
+            .with_endpoint(server.url());
+
+        let secret = provider.get_secret("push-key").await.unwrap().unwrap();
+        assert_eq!(secret.as_str().unwrap(), "s3cret");
+        assert_eq!(secret.version.as_deref(), Some("7"));
+        assert_eq!(provider.get_secret("missing").await.unwrap(), None);
+        assert!(!format!("{:?}", provider).contains("test-token"));
+    }
+}
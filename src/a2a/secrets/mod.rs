@@ -0,0 +1,327 @@
+//! Secrets managers as a source of credentials and encryption keys
+//!
+//! A `SecretProvider` fetches named secrets from a secrets manager: AWS
+//! Secrets Manager (feature `secrets-aws`), GCP Secret Manager (feature
+//! `secrets-gcp`) or HashiCorp Vault's KV engine (feature `secrets-vault`).
+//! `SecretCache` keeps fetched secrets for a TTL, re-fetches them
+//! periodically with `watch`, and calls the registered rotation callbacks
+//! when a secret's value changes.
+//!
+//! On the client, `SecretCredentialService` serves the credentials of
+//! security schemes from secrets. On the server, `Secret::encryption_key`
+//! turns a secret into the key of `SqlitePushNotificationConfigStore`, so
+//! neither has to be written into configuration files.
+
+#[cfg(feature = "secrets-aws")]
+pub mod aws;
+#[cfg(feature = "secrets-gcp")]
+pub mod gcp;
+#[cfg(feature = "secrets-vault")]
+pub mod vault;
+
+#[cfg(feature = "secrets-aws")]
+pub use aws::AwsSecretsManager;
+#[cfg(feature = "secrets-gcp")]
+pub use gcp::GcpSecretManager;
+#[cfg(feature = "secrets-vault")]
+pub use vault::VaultSecrets;
+
+use crate::a2a::error::A2AError;
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Default time fetched secrets are served from the cache
+pub const DEFAULT_SECRET_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// A secret value with the version the secrets manager gave it
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret {
+    /// The secret bytes
+    pub value: Vec<u8>,
+    /// The version of the secret, if the secrets manager versions secrets
+    pub version: Option<String>,
+}
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Secret")
+            .field("value", &"<redacted>")
+            .field("version", &self.version)
+            .finish()
+    }
+}
+
+impl Secret {
+    /// A secret without a version
+    pub fn new(value: impl Into<Vec<u8>>) -> Self {
+        Self {
+            value: value.into(),
+            version: None,
+        }
+    }
+
+    /// Sets the version of the secret
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// The secret as UTF-8 text
+    pub fn as_str(&self) -> Result<&str, A2AError> {
+        std::str::from_utf8(&self.value).map_err(|_| A2AError::invalid_params("Secret is not UTF-8 text"))
+    }
+
+    /// The secret as a 32 byte encryption key, stored either as raw bytes
+    /// or base64 encoded
+    pub fn encryption_key(&self) -> Result<[u8; 32], A2AError> {
+        if let Ok(key) = <[u8; 32]>::try_from(self.value.as_slice()) {
+            return Ok(key);
+        }
+        let decoded = STANDARD
+            .decode(self.as_str()?.trim())
+            .map_err(|e| A2AError::invalid_params(&format!("Invalid encryption key secret: {}", e)))?;
+        decoded
+            .try_into()
+            .map_err(|_| A2AError::invalid_params("Encryption key secret must be 32 bytes"))
+    }
+}
+
+/// A source of named secrets
+#[async_trait]
+pub trait SecretProvider: Send + Sync {
+    /// Fetches the current version of the secret `name`, or None if the
+    /// secret does not exist
+    async fn get_secret(&self, name: &str) -> Result<Option<Secret>, A2AError>;
+}
+
+/// Secrets held in memory, for tests and local development
+#[derive(Debug, Clone, Default)]
+pub struct InMemorySecretProvider {
+    secrets: Arc<Mutex<HashMap<String, Secret>>>,
+}
+
+impl InMemorySecretProvider {
+    /// Creates an empty provider
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the secret `name`, replacing its previous value
+    pub fn set(&self, name: impl Into<String>, secret: Secret) {
+        self.secrets.lock().unwrap().insert(name.into(), secret);
+    }
+
+    /// Removes the secret `name`
+    pub fn remove(&self, name: &str) {
+        self.secrets.lock().unwrap().remove(name);
+    }
+}
+
+#[async_trait]
+impl SecretProvider for InMemorySecretProvider {
+    async fn get_secret(&self, name: &str) -> Result<Option<Secret>, A2AError> {
+        Ok(self.secrets.lock().unwrap().get(name).cloned())
+    }
+}
+
+/// Callback run when a cached secret changes, with its name and new value
+pub type RotationCallback = Arc<dyn Fn(&str, &Secret) + Send + Sync>;
+
+/// Caching layer over a `SecretProvider` with rotation callbacks
+pub struct SecretCache {
+    provider: Arc<dyn SecretProvider>,
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Option<Secret>, Instant)>>,
+    callbacks: Mutex<Vec<RotationCallback>>,
+}
+
+impl SecretCache {
+    /// Caches the secrets of `provider` for `DEFAULT_SECRET_TTL`
+    pub fn new(provider: Arc<dyn SecretProvider>) -> Self {
+        Self {
+            provider,
+            ttl: DEFAULT_SECRET_TTL,
+            entries: Mutex::new(HashMap::new()),
+            callbacks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Sets how long fetched secrets are served from the cache
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Runs `callback` whenever a secret fetched again differs from the
+    /// cached one
+    pub fn on_rotation(&self, callback: impl Fn(&str, &Secret) + Send + Sync + 'static) {
+        self.callbacks.lock().unwrap().push(Arc::new(callback));
+    }
+
+    /// The secret `name`, from the cache while it is fresh
+    pub async fn get(&self, name: &str) -> Result<Option<Secret>, A2AError> {
+        let cached = self
+            .entries
+            .lock()
+            .unwrap()
+            .get(name)
+            .filter(|(_, fetched_at)| fetched_at.elapsed() < self.ttl)
+            .map(|(secret, _)| secret.clone());
+        match cached {
+            Some(secret) => Ok(secret),
+            None => self.refresh(name).await,
+        }
+    }
+
+    /// Fetches the secret `name` from the provider, running the rotation
+    /// callbacks if it changed
+    pub async fn refresh(&self, name: &str) -> Result<Option<Secret>, A2AError> {
+        let secret = self.provider.get_secret(name).await?;
+        let previous = self
+            .entries
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), (secret.clone(), Instant::now()));
+        if let (Some((Some(previous), _)), Some(secret)) = (&previous, &secret) {
+            if previous != secret {
+                tracing::info!("Secret '{}' rotated to version {:?}", name, secret.version);
+                let callbacks = self.callbacks.lock().unwrap().clone();
+                for callback in callbacks {
+                    callback(name, secret);
+                }
+            }
+        }
+        Ok(secret)
+    }
+
+    /// Fetches every cached secret again
+    pub async fn refresh_all(&self) {
+        let names: Vec<String> = self.entries.lock().unwrap().keys().cloned().collect();
+        for name in names {
+            if let Err(e) = self.refresh(&name).await {
+                tracing::warn!("Failed to refresh secret '{}', keeping the cached value: {}", name, e);
+            }
+        }
+    }
+
+    /// Refreshes every cached secret each `interval` until the returned
+    /// task is aborted
+    pub fn watch(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let cache = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            ticks.tick().await;
+            loop {
+                ticks.tick().await;
+                cache.refresh_all().await;
+            }
+        })
+    }
+}
+
+/// Credential service reading the credentials of security schemes from
+/// secrets
+///
+/// The secret of a scheme is the one mapped with `with_secret`, or else the
+/// scheme name after the prefix.
+#[cfg(feature = "client")]
+pub struct SecretCredentialService {
+    cache: Arc<SecretCache>,
+    prefix: String,
+    names: HashMap<String, String>,
+}
+
+#[cfg(feature = "client")]
+impl SecretCredentialService {
+    /// Reads credentials through `cache`
+    pub fn new(cache: Arc<SecretCache>) -> Self {
+        Self {
+            cache,
+            prefix: String::new(),
+            names: HashMap::new(),
+        }
+    }
+
+    /// Reads unmapped schemes from the secret `<prefix><scheme name>`
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Reads the credential of `scheme_name` from the secret `secret_name`
+    pub fn with_secret(mut self, scheme_name: impl Into<String>, secret_name: impl Into<String>) -> Self {
+        self.names.insert(scheme_name.into(), secret_name.into());
+        self
+    }
+}
+
+#[cfg(feature = "client")]
+#[async_trait]
+impl crate::a2a::client::auth::CredentialService for SecretCredentialService {
+    async fn get_credentials(
+        &self,
+        scheme_name: &str,
+        _context: Option<&crate::a2a::client::client_trait::ClientCallContext>,
+    ) -> Result<Option<String>, A2AError> {
+        let secret_name = match self.names.get(scheme_name) {
+            Some(name) => name.clone(),
+            None => format!("{}{}", self.prefix, scheme_name),
+        };
+        match self.cache.get(&secret_name).await? {
+            Some(secret) => Ok(Some(secret.as_str()?.to_string())),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_cache_serves_until_ttl_and_reports_rotation() {
+        let provider = InMemorySecretProvider::new();
+        provider.set("push-key", Secret::new([1u8; 32]).with_version("v1"));
+        let cache = SecretCache::new(Arc::new(provider.clone())).with_ttl(Duration::from_secs(60));
+        let rotations = Arc::new(AtomicUsize::new(0));
+        let counter = rotations.clone();
+        cache.on_rotation(move |name, secret| {
+            assert_eq!(name, "push-key");
+            assert_eq!(secret.version.as_deref(), Some("v2"));
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        assert_eq!(cache.get("push-key").await.unwrap().unwrap().encryption_key().unwrap(), [1u8; 32]);
+        provider.set("push-key", Secret::new(STANDARD.encode([2u8; 32])).with_version("v2"));
+        // Served from the cache until refreshed
+        assert_eq!(cache.get("push-key").await.unwrap().unwrap().version.as_deref(), Some("v1"));
+
+        cache.refresh_all().await;
+        assert_eq!(rotations.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.get("push-key").await.unwrap().unwrap().encryption_key().unwrap(), [2u8; 32]);
+        cache.refresh_all().await;
+        assert_eq!(rotations.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn test_credential_service_maps_schemes_to_secrets() {
+        use crate::a2a::client::auth::CredentialService;
+
+        let provider = InMemorySecretProvider::new();
+        provider.set("a2a/bearerAuth", Secret::new("token"));
+        provider.set("partner-api-key", Secret::new("key"));
+        let service = SecretCredentialService::new(Arc::new(SecretCache::new(Arc::new(provider))))
+            .with_prefix("a2a/")
+            .with_secret("apiKey", "partner-api-key");
+
+        assert_eq!(service.get_credentials("bearerAuth", None).await.unwrap().as_deref(), Some("token"));
+        assert_eq!(service.get_credentials("apiKey", None).await.unwrap().as_deref(), Some("key"));
+        assert_eq!(service.get_credentials("oauth", None).await.unwrap(), None);
+    }
+}
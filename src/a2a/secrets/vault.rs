@@ -0,0 +1,138 @@
+//! HashiCorp Vault provider
+//!
+//! Secrets are read from a KV version 2 engine, by path within the mount.
+//! A KV secret holds several fields, so the name selects one with
+//! `<path>#<field>`, defaulting to the provider's field (`value` unless
+//! changed with `with_field`).
+
+use super::{Secret, SecretProvider};
+use crate::a2a::error::A2AError;
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Default mount of the KV engine
+pub const DEFAULT_MOUNT: &str = "secret";
+
+/// Default field read from KV secrets
+pub const DEFAULT_FIELD: &str = "value";
+
+/// Secrets stored in a Vault KV version 2 engine
+#[derive(Clone)]
+pub struct VaultSecrets {
+    client: reqwest::Client,
+    address: String,
+    token: String,
+    mount: String,
+    field: String,
+}
+
+impl std::fmt::Debug for VaultSecrets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VaultSecrets")
+            .field("address", &self.address)
+            .field("token", &"<redacted>")
+            .field("mount", &self.mount)
+            .field("field", &self.field)
+            .finish_non_exhaustive()
+    }
+}
+
+impl VaultSecrets {
+    /// Reads secrets from the Vault at `address`, authenticating with `token`
+    pub fn new(address: impl Into<String>, token: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            address: address.into().trim_end_matches('/').to_string(),
+            token: token.into(),
+            mount: DEFAULT_MOUNT.to_string(),
+            field: DEFAULT_FIELD.to_string(),
+        }
+    }
+
+    /// Reads the address and token from `VAULT_ADDR` and `VAULT_TOKEN`
+    pub fn from_env() -> Result<Self, A2AError> {
+        let var = |name: &str| {
+            std::env::var(name).map_err(|_| A2AError::invalid_params(&format!("{} is not set", name)))
+        };
+        Ok(Self::new(var("VAULT_ADDR")?, var("VAULT_TOKEN")?))
+    }
+
+    /// Sets the mount of the KV engine
+    pub fn with_mount(mut self, mount: impl Into<String>) -> Self {
+        self.mount = mount.into().trim_matches('/').to_string();
+        self
+    }
+
+    /// Sets the field read from secrets named without one
+    pub fn with_field(mut self, field: impl Into<String>) -> Self {
+        self.field = field.into();
+        self
+    }
+}
+
+#[async_trait]
+impl SecretProvider for VaultSecrets {
+    async fn get_secret(&self, name: &str) -> Result<Option<Secret>, A2AError> {
+        let (path, field) = name.split_once('#').unwrap_or((name, &self.field));
+        let error = |e: &dyn std::fmt::Display| {
+            A2AError::internal(&format!("Failed to read secret '{}' from Vault: {}", name, e))
+        };
+        let response = self
+            .client
+            .get(format!("{}/v1/{}/data/{}", self.address, self.mount, path.trim_start_matches('/')))
+            .header("X-Vault-Token", &self.token)
+            .send()
+            .await
+            .map_err(|e| error(&e))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let body: Value = response
+            .error_for_status()
+            .map_err(|e| error(&e))?
+            .json()
+            .await
+            .map_err(|e| error(&e))?;
+        let value = match &body["data"]["data"][field] {
+            Value::Null => return Ok(None),
+            Value::String(value) => value.clone(),
+            value => value.to_string(),
+        };
+        let secret = Secret::new(value);
+        Ok(Some(match body["data"]["metadata"]["version"].as_u64() {
+            Some(version) => secret.with_version(version.to_string()),
+            None => secret,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reads_fields_of_kv_secrets() {
+        let mut server = mockito::Server::new_async().await;
+        let _secret = server
+            .mock("GET", "/v1/kv/data/agents/planner")
+            .match_header("x-vault-token", "root")
+            .with_body(r#"{"data":{"data":{"value":"token","api_key":"key"},"metadata":{"version":3}}}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let _missing = server
+            .mock("GET", "/v1/kv/data/agents/missing")
+            .with_status(404)
+            .create_async()
+            .await;
+        let provider = VaultSecrets::new(server.url(), "root").with_mount("kv");
+
+        let secret = provider.get_secret("agents/planner").await.unwrap().unwrap();
+        assert_eq!(secret.as_str().unwrap(), "token");
+        assert_eq!(secret.version.as_deref(), Some("3"));
+        let api_key = provider.get_secret("agents/planner#api_key").await.unwrap().unwrap();
+        assert_eq!(api_key.as_str().unwrap(), "key");
+        assert_eq!(provider.get_secret("agents/missing").await.unwrap(), None);
+        assert!(!format!("{:?}", provider).contains("root"));
+    }
+}
@@ -0,0 +1,223 @@
+//! Runtime-swappable agent cards
+//!
+//! `AgentCardHandle` holds the public and extended agent cards served by an
+//! `A2AServer`. Every request reads the current card through the handle, so
+//! skills and capability flags can be changed while the server is running by
+//! calling `update`. Changes are broadcast on a watch channel and passed to
+//! registered `AgentCardListener`s, e.g. a `RegistryNotifier` announcing the new
+//! card to a discovery service.
+
+use crate::a2a::error::A2AError;
+use crate::a2a::models::AgentCard;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+use tracing::warn;
+
+/// Receives agent card changes
+#[async_trait]
+pub trait AgentCardListener: Send + Sync {
+    /// Called after the public agent card was replaced
+    async fn on_agent_card_changed(&self, card: &AgentCard) -> Result<(), A2AError>;
+}
+
+struct Inner {
+    card: watch::Sender<Arc<AgentCard>>,
+    extended: watch::Sender<Option<Arc<AgentCard>>>,
+    updated_at: Mutex<DateTime<Utc>>,
+    listeners: Mutex<Vec<Arc<dyn AgentCardListener>>>,
+}
+
+/// A shared, swappable pair of public and extended agent cards
+#[derive(Clone)]
+pub struct AgentCardHandle {
+    inner: Arc<Inner>,
+}
+
+impl AgentCardHandle {
+    /// Creates a handle serving `card` without an extended card
+    pub fn new(card: AgentCard) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                card: watch::Sender::new(Arc::new(card)),
+                extended: watch::Sender::new(None),
+                updated_at: Mutex::new(Utc::now()),
+                listeners: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Sets the initial extended card
+    pub fn with_extended(self, card: Option<AgentCard>) -> Self {
+        self.inner.extended.send_replace(card.map(Arc::new));
+        self
+    }
+
+    /// Returns the current public card
+    pub fn current(&self) -> Arc<AgentCard> {
+        self.inner.card.borrow().clone()
+    }
+
+    /// Returns the current extended card
+    pub fn extended(&self) -> Option<Arc<AgentCard>> {
+        self.inner.extended.borrow().clone()
+    }
+
+    /// Returns when either card was last replaced
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        *self.inner.updated_at.lock().unwrap()
+    }
+
+    /// Replaces the public card and notifies the listeners
+    ///
+    /// Listener failures are logged and do not fail the update.
+    pub async fn update(&self, card: AgentCard) {
+        let card = Arc::new(card);
+        self.inner.card.send_replace(card.clone());
+        self.touch();
+
+        let listeners = self.inner.listeners.lock().unwrap().clone();
+        for listener in listeners {
+            if let Err(e) = listener.on_agent_card_changed(&card).await {
+                warn!("Failed to announce agent card change: {}", e);
+            }
+        }
+    }
+
+    /// Applies `change` to a copy of the public card and serves the result
+    pub async fn modify(&self, change: impl FnOnce(&mut AgentCard)) {
+        let mut card = (*self.current()).clone();
+        change(&mut card);
+        self.update(card).await;
+    }
+
+    /// Replaces the public card without notifying the listeners
+    pub(crate) fn replace(&self, card: AgentCard) {
+        self.inner.card.send_replace(Arc::new(card));
+        self.touch();
+    }
+
+    /// Replaces the extended card
+    pub fn update_extended(&self, card: Option<AgentCard>) {
+        self.inner.extended.send_replace(card.map(Arc::new));
+        self.touch();
+    }
+
+    /// Subscribes to public card changes
+    pub fn subscribe(&self) -> watch::Receiver<Arc<AgentCard>> {
+        self.inner.card.subscribe()
+    }
+
+    /// Registers a listener called on every public card change
+    pub fn add_listener(&self, listener: Arc<dyn AgentCardListener>) {
+        self.inner.listeners.lock().unwrap().push(listener);
+    }
+
+    fn touch(&self) {
+        *self.inner.updated_at.lock().unwrap() = Utc::now();
+    }
+}
+
+impl std::fmt::Debug for AgentCardHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AgentCardHandle")
+            .field("card", &self.current().name)
+            .field("has_extended", &self.extended().is_some())
+            .finish()
+    }
+}
+
+/// Announces card changes to a registry by POSTing the new card as JSON
+pub struct RegistryNotifier {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl RegistryNotifier {
+    /// Creates a notifier posting to `url`
+    pub fn new(url: &str) -> Self {
+        Self::with_client(reqwest::Client::new(), url)
+    }
+
+    /// Creates a notifier using a custom HTTP client
+    pub fn with_client(client: reqwest::Client, url: &str) -> Self {
+        Self {
+            client,
+            url: url.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl AgentCardListener for RegistryNotifier {
+    async fn on_agent_card_changed(&self, card: &AgentCard) -> Result<(), A2AError> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(card)
+            .send()
+            .await
+            .map_err(|e| A2AError::transport_error(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(A2AError::http_error(
+                response.status().as_u16(),
+                format!("Registry rejected agent card: {}", response.status()),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::models::AgentCapabilities;
+    use mockito::Server;
+
+    fn card(name: &str) -> AgentCard {
+        AgentCard::new(
+            name.to_string(),
+            "test".to_string(),
+            "http://localhost:8080".to_string(),
+            "1.0.0".to_string(),
+            vec!["text/plain".to_string()],
+            vec!["text/plain".to_string()],
+            AgentCapabilities::new(),
+            vec![],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_update_is_visible_to_readers_and_subscribers() {
+        let handle = AgentCardHandle::new(card("v1"));
+        let mut changes = handle.subscribe();
+        let reader = handle.clone();
+
+        handle
+            .modify(|card| card.capabilities.streaming = Some(true))
+            .await;
+        assert_eq!(reader.current().capabilities.streaming, Some(true));
+        assert!(changes.has_changed().unwrap());
+        assert_eq!(changes.borrow_and_update().name, "v1");
+
+        handle.update_extended(Some(card("extended")));
+        assert_eq!(reader.extended().unwrap().name, "extended");
+    }
+
+    #[tokio::test]
+    async fn test_registry_notifier_posts_new_card() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/agents")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({"name": "v2"})))
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let handle = AgentCardHandle::new(card("v1"));
+        handle.add_listener(Arc::new(RegistryNotifier::new(&format!("{}/agents", server.url()))));
+        handle.update(card("v2")).await;
+        mock.assert_async().await;
+    }
+}
@@ -0,0 +1,546 @@
+//! Human approval gate for agent executors
+//!
+//! `ApprovalGate` wraps another `AgentExecutor`. Before the wrapped executor
+//! runs, the task is moved to `input-required` with a message asking for
+//! approval, and a one-time approval token is handed to the approvers through
+//! the gate's `ApprovalNotifier`, never to the requester. Execution stays
+//! paused until a decision arrives, either through the HTTP endpoint served by
+//! `ApprovalGate::router` with the token, or as a follow-up message on the
+//! same task ("approve", "reject", or a data part `{"approved": bool}`).
+//! Approved tasks continue with the wrapped executor on the original request;
+//! rejected or expired ones end in the `rejected` state.
+//!
+//! The principal who sent the request can never approve it: follow-up
+//! approvals are accepted only from another authenticated user, and decisions
+//! naming the requester as approver are refused.
+
+use crate::a2a::core_types::PartRoot;
+use crate::a2a::server::agent_execution::{AgentExecutor, RequestContext};
+use crate::a2a::server::events::{Event, EventQueue};
+use crate::{A2AError, Message, Part, Role, TaskState, TaskStatus, TaskStatusUpdateEvent};
+use async_trait::async_trait;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// Default text of the approval request sent to the user
+pub const DEFAULT_APPROVAL_PROMPT: &str = "This task requires approval before it can continue.";
+
+/// An approval request, as handed to the approvers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    /// The task waiting for approval
+    pub task_id: String,
+    /// The context of the task
+    pub context_id: String,
+    /// The one-time token deciding the approval through `ApprovalGate::router`
+    pub approval_token: String,
+    /// Where the decision can be posted, if the gate has an approval URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_url: Option<String>,
+    /// The authenticated user who sent the request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested_by: Option<String>,
+}
+
+/// Channel delivering approval requests to the approvers, e.g. a chat
+/// webhook or a ticketing system
+#[async_trait]
+pub trait ApprovalNotifier: Send + Sync {
+    /// Hands `request` to the approvers
+    async fn request_approval(&self, request: &ApprovalRequest) -> Result<(), A2AError>;
+}
+
+/// An approver's answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApprovalDecision {
+    /// Whether execution may continue
+    pub approved: bool,
+    /// Optional explanation, shown when the task is rejected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Who made the decision
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approver: Option<String>,
+}
+
+impl ApprovalDecision {
+    /// An approval
+    pub fn approve() -> Self {
+        Self {
+            approved: true,
+            reason: None,
+            approver: None,
+        }
+    }
+
+    /// A rejection with a reason
+    pub fn reject(reason: &str) -> Self {
+        Self {
+            approved: false,
+            reason: Some(reason.to_string()),
+            approver: None,
+        }
+    }
+
+    /// Reads a decision from a follow-up message
+    ///
+    /// Returns `None` when the message is neither an approval nor a rejection.
+    pub fn from_message(message: &Message) -> Option<Self> {
+        for part in &message.parts {
+            match part.root() {
+                PartRoot::Data(data) => {
+                    if let Ok(decision) = serde_json::from_value::<ApprovalDecision>(data.data.clone()) {
+                        return Some(decision);
+                    }
+                }
+                PartRoot::Text(text) => match text.text.trim().to_lowercase().as_str() {
+                    "approve" | "approved" | "yes" | "y" | "ok" => return Some(Self::approve()),
+                    "reject" | "rejected" | "deny" | "no" | "n" => {
+                        return Some(Self {
+                            approved: false,
+                            reason: None,
+                            approver: None,
+                        })
+                    }
+                    _ => {}
+                },
+                PartRoot::File(_) => {}
+            }
+        }
+        None
+    }
+}
+
+struct PendingApproval {
+    request: ApprovalRequest,
+    sender: oneshot::Sender<ApprovalDecision>,
+}
+
+#[derive(Default)]
+struct Pending {
+    by_token: HashMap<String, PendingApproval>,
+    by_task: HashMap<String, String>,
+}
+
+impl Pending {
+    fn take_by_token(&mut self, token: &str) -> Option<PendingApproval> {
+        let pending = self.by_token.remove(token)?;
+        self.by_task.remove(&pending.request.task_id);
+        Some(pending)
+    }
+
+    fn take_by_task(&mut self, task_id: &str) -> Option<PendingApproval> {
+        let token = self.by_task.remove(task_id)?;
+        self.by_token.remove(&token)
+    }
+
+    fn requester_of_task(&self, task_id: &str) -> Option<Option<String>> {
+        let token = self.by_task.get(task_id)?;
+        self.by_token.get(token).map(|pending| pending.request.requested_by.clone())
+    }
+}
+
+/// Executor wrapper requiring human approval before execution
+#[derive(Clone)]
+pub struct ApprovalGate {
+    inner: Arc<dyn AgentExecutor>,
+    pending: Arc<Mutex<Pending>>,
+    notifier: Option<Arc<dyn ApprovalNotifier>>,
+    timeout: Option<Duration>,
+    approval_url: Option<String>,
+    prompt: String,
+}
+
+impl ApprovalGate {
+    /// Wraps `inner`
+    pub fn new(inner: Arc<dyn AgentExecutor>) -> Self {
+        Self {
+            inner,
+            pending: Arc::new(Mutex::new(Pending::default())),
+            notifier: None,
+            timeout: None,
+            approval_url: None,
+            prompt: DEFAULT_APPROVAL_PROMPT.to_string(),
+        }
+    }
+
+    /// Hands approval requests, with their tokens, to `notifier`
+    ///
+    /// Without a notifier, tokens are only available from
+    /// `ApprovalGate::pending_approvals`.
+    pub fn with_notifier(mut self, notifier: Arc<dyn ApprovalNotifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Rejects tasks that did not receive a decision within `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Includes `<base_url>/<token>` in the approval requests handed to the approvers
+    ///
+    /// `base_url` should point to where `router` is mounted, e.g.
+    /// `https://agent.example.com/approvals`.
+    pub fn with_approval_url(mut self, base_url: &str) -> Self {
+        self.approval_url = Some(base_url.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Sets the text of approval requests
+    pub fn with_prompt(mut self, prompt: &str) -> Self {
+        self.prompt = prompt.to_string();
+        self
+    }
+
+    /// Ids of the tasks waiting for approval
+    pub fn pending_tasks(&self) -> Vec<String> {
+        self.pending.lock().unwrap().by_task.keys().cloned().collect()
+    }
+
+    /// The approval requests waiting for a decision, with their tokens
+    pub fn pending_approvals(&self) -> Vec<ApprovalRequest> {
+        self.pending.lock().unwrap().by_token.values().map(|pending| pending.request.clone()).collect()
+    }
+
+    /// Delivers a decision for the approval identified by `token`
+    ///
+    /// An approval naming the requester as approver is refused and leaves
+    /// the task waiting.
+    pub fn decide(&self, token: &str, decision: ApprovalDecision) -> Result<(), A2AError> {
+        let mut pending = self.pending.lock().unwrap();
+        let requester = pending
+            .by_token
+            .get(token)
+            .ok_or_else(|| A2AError::invalid_params("Unknown or already used approval token"))?
+            .request
+            .requested_by
+            .clone();
+        if decision.approved && requester.is_some() && decision.approver == requester {
+            return Err(A2AError::invalid_params("The requester cannot approve their own task"));
+        }
+        if let Some(pending) = pending.take_by_token(token) {
+            let _ = pending.sender.send(decision);
+        }
+        Ok(())
+    }
+
+    /// Builds a router accepting decisions as `POST /:token` with an `ApprovalDecision` body
+    pub fn router(&self) -> Router {
+        Router::new().route("/:token", post(post_decision)).with_state(self.clone())
+    }
+
+    async fn wait_for_decision(
+        &self,
+        context: &RequestContext,
+        event_queue: &Arc<dyn EventQueue>,
+    ) -> Result<Option<ApprovalDecision>, A2AError> {
+        let task_id = context.task_id.clone().unwrap_or_default();
+        let token = uuid::Uuid::new_v4().to_string();
+        let request = ApprovalRequest {
+            task_id: task_id.clone(),
+            context_id: context.context_id.clone().unwrap_or_default(),
+            approval_url: self.approval_url.as_ref().map(|base_url| format!("{}/{}", base_url, token)),
+            approval_token: token.clone(),
+            requested_by: principal(context),
+        };
+        let (sender, receiver) = oneshot::channel();
+        {
+            let mut pending = self.pending.lock().unwrap();
+            pending.by_task.insert(task_id.clone(), token.clone());
+            pending.by_token.insert(
+                token,
+                PendingApproval {
+                    request: request.clone(),
+                    sender,
+                },
+            );
+        }
+
+        if let Some(notifier) = &self.notifier {
+            if let Err(e) = notifier.request_approval(&request).await {
+                self.pending.lock().unwrap().take_by_task(&task_id);
+                return Err(e);
+            }
+        }
+
+        let message = agent_message(context, vec![Part::text(self.prompt.clone())]);
+        publish(context, event_queue, TaskState::InputRequired, Some(message), false).await?;
+
+        let decision = match self.timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, receiver).await {
+                Ok(decision) => decision.ok(),
+                Err(_) => {
+                    self.pending.lock().unwrap().take_by_task(&task_id);
+                    return Ok(Some(ApprovalDecision::reject("Approval timed out")));
+                }
+            },
+            None => receiver.await.ok(),
+        };
+        Ok(decision)
+    }
+}
+
+impl std::fmt::Debug for ApprovalGate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApprovalGate")
+            .field("pending", &self.pending_tasks())
+            .field("notifier", &self.notifier.is_some())
+            .field("timeout", &self.timeout)
+            .field("approval_url", &self.approval_url)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl AgentExecutor for ApprovalGate {
+    async fn execute(
+        &self,
+        context: RequestContext,
+        event_queue: Arc<dyn EventQueue>,
+    ) -> Result<(), A2AError> {
+        let task_id = context.task_id.clone().unwrap_or_default();
+
+        // A follow-up message on a paused task carries the decision
+        let requester = self.pending.lock().unwrap().requester_of_task(&task_id);
+        if let Some(requester) = requester {
+            let Some(mut decision) = context.message().and_then(ApprovalDecision::from_message) else {
+                let message = agent_message(&context, vec![Part::text("Please reply with 'approve' or 'reject'.".to_string())]);
+                return publish(&context, &event_queue, TaskState::InputRequired, Some(message), false).await;
+            };
+            // Anonymous follow-ups cannot be told apart from the requester
+            decision.approver = principal(&context);
+            if decision.approved && (decision.approver.is_none() || decision.approver == requester) {
+                let message = agent_message(
+                    &context,
+                    vec![Part::text("This task must be approved by someone other than the requester.".to_string())],
+                );
+                return publish(&context, &event_queue, TaskState::InputRequired, Some(message), false).await;
+            }
+            if let Some(pending) = self.pending.lock().unwrap().take_by_task(&task_id) {
+                let _ = pending.sender.send(decision);
+            }
+            return Ok(());
+        }
+
+        match self.wait_for_decision(&context, &event_queue).await? {
+            Some(decision) if decision.approved => {
+                publish(&context, &event_queue, TaskState::Working, None, false).await?;
+                self.inner.execute(context, event_queue).await
+            }
+            Some(decision) => {
+                let reason = decision.reason.unwrap_or_else(|| "Rejected by approver".to_string());
+                let message = agent_message(&context, vec![Part::text(reason)]);
+                publish(&context, &event_queue, TaskState::Rejected, Some(message), true).await
+            }
+            // The pending approval was dropped by a cancellation
+            None => Ok(()),
+        }
+    }
+
+    async fn cancel(
+        &self,
+        context: RequestContext,
+        event_queue: Arc<dyn EventQueue>,
+    ) -> Result<(), A2AError> {
+        let task_id = context.task_id.clone().unwrap_or_default();
+        self.pending.lock().unwrap().take_by_task(&task_id);
+        self.inner.cancel(context, event_queue).await
+    }
+}
+
+async fn post_decision(
+    State(gate): State<ApprovalGate>,
+    Path(token): Path<String>,
+    Json(decision): Json<ApprovalDecision>,
+) -> StatusCode {
+    match gate.decide(&token, decision) {
+        Ok(()) => StatusCode::NO_CONTENT,
+        Err(_) => StatusCode::NOT_FOUND,
+    }
+}
+
+/// The authenticated user who sent the request of `context`
+fn principal(context: &RequestContext) -> Option<String> {
+    context
+        .call_context
+        .as_ref()
+        .map(|call_context| call_context.user.username())
+        .filter(|username| !username.is_empty())
+        .map(str::to_string)
+}
+
+fn agent_message(context: &RequestContext, parts: Vec<Part>) -> Message {
+    Message::new(Role::Agent, parts)
+        .with_task_id(context.task_id.clone().unwrap_or_default())
+        .with_context_id(context.context_id.clone().unwrap_or_default())
+}
+
+async fn publish(
+    context: &RequestContext,
+    event_queue: &Arc<dyn EventQueue>,
+    state: TaskState,
+    message: Option<Message>,
+    r#final: bool,
+) -> Result<(), A2AError> {
+    let mut status = TaskStatus::new(state);
+    if let Some(message) = message {
+        status = status.with_message(message);
+    }
+    let event = TaskStatusUpdateEvent::new(
+        context.task_id.clone().unwrap_or_default(),
+        context.context_id.clone().unwrap_or_default(),
+        status,
+        r#final,
+    );
+    event_queue.enqueue_event(Event::TaskStatusUpdate(event)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::auth::user::AuthenticatedUser;
+    use crate::a2a::server::agent_execution::agent_executor::MockAgentExecutor;
+    use crate::a2a::server::context::ServerCallContext;
+    use crate::a2a::server::events::InMemoryEventQueue;
+    use crate::MessageSendParams;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn context(text: &str) -> RequestContext {
+        context_from(text, None).await
+    }
+
+    async fn context_from(text: &str, user: Option<&str>) -> RequestContext {
+        let params = MessageSendParams {
+            message: Message::new(Role::User, vec![Part::text(text.to_string())]),
+            configuration: None,
+            metadata: None,
+        };
+        let call_context = user.map(|user| ServerCallContext::with_user(AuthenticatedUser::new(user.to_string())));
+        RequestContext::new(Some(params), Some("task-1".to_string()), Some("ctx-1".to_string()), None, None, call_context, None, None)
+            .await
+            .unwrap()
+    }
+
+    /// Records the approval requests handed to the approvers
+    #[derive(Default)]
+    struct RecordingNotifier {
+        requests: Mutex<Vec<ApprovalRequest>>,
+    }
+
+    #[async_trait]
+    impl ApprovalNotifier for RecordingNotifier {
+        async fn request_approval(&self, request: &ApprovalRequest) -> Result<(), A2AError> {
+            self.requests.lock().unwrap().push(request.clone());
+            Ok(())
+        }
+    }
+
+    async fn next_state(queue: &InMemoryEventQueue) -> (TaskState, Option<Message>) {
+        match queue.dequeue_event(false).await.unwrap() {
+            Event::TaskStatusUpdate(update) => (update.status.state, update.status.message.map(|message| *message)),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_follow_up_message_approves_task() {
+        let gate = ApprovalGate::new(Arc::new(MockAgentExecutor::new()));
+        let queue = Arc::new(InMemoryEventQueue::new().unwrap());
+        let running = tokio::spawn({
+            let (gate, queue) = (gate.clone(), queue.clone());
+            async move { gate.execute(context_from("deploy", Some("alice")).await, queue).await }
+        });
+
+        let (state, _) = next_state(&queue).await;
+        assert_eq!(state, TaskState::InputRequired);
+        assert_eq!(gate.pending_tasks(), vec!["task-1".to_string()]);
+
+        let follow_up = Arc::new(InMemoryEventQueue::new().unwrap());
+        gate.execute(context_from("approve", Some("bob")).await, follow_up).await.unwrap();
+        running.await.unwrap().unwrap();
+
+        let mut states = Vec::new();
+        while let Ok(Event::TaskStatusUpdate(update)) = queue.dequeue_event(true).await {
+            states.push(update.status.state);
+        }
+        assert_eq!(states.last(), Some(&TaskState::Completed));
+        assert!(gate.pending_tasks().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_requester_cannot_approve_their_own_task() {
+        let gate = ApprovalGate::new(Arc::new(MockAgentExecutor::new()));
+        let queue = Arc::new(InMemoryEventQueue::new().unwrap());
+        tokio::spawn({
+            let (gate, queue) = (gate.clone(), queue.clone());
+            async move { gate.execute(context_from("deploy", Some("alice")).await, queue).await }
+        });
+        assert_eq!(next_state(&queue).await.0, TaskState::InputRequired);
+
+        for approver in [Some("alice"), None] {
+            let follow_up = Arc::new(InMemoryEventQueue::new().unwrap());
+            gate.execute(context_from("approve", approver).await, follow_up.clone()).await.unwrap();
+            assert_eq!(next_state(&follow_up).await.0, TaskState::InputRequired);
+        }
+
+        let token = gate.pending_approvals()[0].approval_token.clone();
+        let mut decision = ApprovalDecision::approve();
+        decision.approver = Some("alice".to_string());
+        assert!(gate.decide(&token, decision).is_err());
+        assert_eq!(gate.pending_tasks(), vec!["task-1".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_http_endpoint_rejects_task() {
+        let notifier = Arc::new(RecordingNotifier::default());
+        let gate = ApprovalGate::new(Arc::new(MockAgentExecutor::new()))
+            .with_approval_url("http://agent/approvals/")
+            .with_notifier(notifier.clone());
+        let queue = Arc::new(InMemoryEventQueue::new().unwrap());
+        let running = tokio::spawn({
+            let (gate, queue) = (gate.clone(), queue.clone());
+            async move { gate.execute(context_from("deploy", Some("alice")).await, queue).await }
+        });
+
+        // The token goes to the approvers only
+        let (_, message) = next_state(&queue).await;
+        assert!(message.unwrap().parts.iter().all(|part| !matches!(part.root(), PartRoot::Data(_))));
+        let request = notifier.requests.lock().unwrap()[0].clone();
+        assert_eq!(request.requested_by.as_deref(), Some("alice"));
+        let token = request.approval_token;
+        assert_eq!(request.approval_url, Some(format!("http://agent/approvals/{}", token)));
+        let request = Request::post(format!("/{}", token))
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"approved": false, "reason": "not today"}"#))
+            .unwrap();
+        let response = gate.router().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        running.await.unwrap().unwrap();
+
+        let (state, message) = next_state(&queue).await;
+        assert_eq!(state, TaskState::Rejected);
+        assert_eq!(crate::a2a::utils::message::get_message_text(&message.unwrap(), ""), "not today");
+        assert!(gate.decide(&token, ApprovalDecision::approve()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_timeout_rejects_task() {
+        let gate = ApprovalGate::new(Arc::new(MockAgentExecutor::new())).with_timeout(Duration::from_millis(20));
+        let queue = Arc::new(InMemoryEventQueue::new().unwrap());
+        gate.execute(context("deploy").await, queue.clone()).await.unwrap();
+        assert_eq!(next_state(&queue).await.0, TaskState::InputRequired);
+        assert_eq!(next_state(&queue).await.0, TaskState::Rejected);
+        assert!(gate.pending_tasks().is_empty());
+    }
+}
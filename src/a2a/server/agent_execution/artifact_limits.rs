@@ -0,0 +1,365 @@
+//! Artifact size limit for agent executors
+//!
+//! `ArtifactSizeLimit` wraps another `AgentExecutor` and bounds the size of
+//! the artifacts it publishes, measured as the JSON length of their parts and
+//! summed over the chunks of a streamed artifact. By default an artifact
+//! exceeding the limit is rejected: publishing the event fails with a
+//! `content_rejected` error and nothing reaches the client.
+//!
+//! With `with_spill`, oversized artifacts are truncated instead. Their full
+//! content is written to an `ArtifactStore` as newline-delimited JSON parts
+//! and the client receives a marker in their place: a single file part
+//! linking to the stored content under `ARTIFACT_TRUNCATED_METADATA_KEY` in
+//! the artifact metadata. Later chunks of a truncated artifact are only
+//! stored; the last one is announced with an empty chunk carrying the final
+//! size.
+
+use crate::a2a::core_types::{FileContent, FilePart, FileWithUri, PartRoot};
+use crate::a2a::jsonrpc::error_codes;
+use crate::a2a::server::agent_execution::{AgentExecutor, RequestContext};
+use crate::a2a::server::artifacts::{artifact_url, ArtifactStore};
+use crate::a2a::server::events::{Event, EventQueue};
+use crate::{A2AError, Artifact, JSONRPCError, Part, TaskArtifactUpdateEvent};
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Artifact metadata key of a truncated artifact, holding its `size`, the
+/// `limit` it exceeded and the `uri` of its full content
+pub const ARTIFACT_TRUNCATED_METADATA_KEY: &str = "artifact_truncated";
+
+/// MIME type of the full content of a truncated artifact, one JSON part per line
+pub const SPILLED_ARTIFACT_MIME_TYPE: &str = "application/x-ndjson";
+
+/// Where the full content of truncated artifacts goes
+#[derive(Clone)]
+struct Spill {
+    store: Arc<dyn ArtifactStore>,
+    base_url: url::Url,
+}
+
+/// Bounds the size of the artifacts published by the wrapped executor
+#[derive(Clone)]
+pub struct ArtifactSizeLimit {
+    inner: Arc<dyn AgentExecutor>,
+    max_bytes: u64,
+    spill: Option<Spill>,
+}
+
+impl ArtifactSizeLimit {
+    /// Wraps `inner`, rejecting artifacts larger than `max_bytes`
+    pub fn new(inner: Arc<dyn AgentExecutor>, max_bytes: u64) -> Self {
+        Self {
+            inner,
+            max_bytes,
+            spill: None,
+        }
+    }
+
+    /// Truncates oversized artifacts instead, storing their full content in
+    /// `store`, served under `base_url` by the artifact endpoint
+    pub fn with_spill(mut self, store: Arc<dyn ArtifactStore>, base_url: url::Url) -> Self {
+        self.spill = Some(Spill { store, base_url });
+        self
+    }
+
+    fn limit(&self, event_queue: Arc<dyn EventQueue>) -> Arc<dyn EventQueue> {
+        Arc::new(LimitedQueue {
+            inner: event_queue,
+            max_bytes: self.max_bytes,
+            spill: self.spill.clone(),
+            artifacts: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+}
+
+#[async_trait]
+impl AgentExecutor for ArtifactSizeLimit {
+    async fn execute(
+        &self,
+        context: RequestContext,
+        event_queue: Arc<dyn EventQueue>,
+    ) -> Result<(), A2AError> {
+        self.inner.execute(context, self.limit(event_queue)).await
+    }
+
+    async fn cancel(
+        &self,
+        context: RequestContext,
+        event_queue: Arc<dyn EventQueue>,
+    ) -> Result<(), A2AError> {
+        self.inner.cancel(context, self.limit(event_queue)).await
+    }
+}
+
+/// What was published so far of a streamed artifact
+#[derive(Default)]
+struct Streamed {
+    size: u64,
+    /// The chunks delivered inline, until the artifact is truncated
+    parts: Vec<Part>,
+    truncated: bool,
+}
+
+/// Event queue enforcing the limit on the events of one execution
+struct LimitedQueue {
+    inner: Arc<dyn EventQueue>,
+    max_bytes: u64,
+    spill: Option<Spill>,
+    /// Streamed artifacts by task and artifact ID
+    artifacts: Arc<Mutex<HashMap<(String, String), Streamed>>>,
+}
+
+impl LimitedQueue {
+    fn reject(&self, artifact_id: &str, size: u64) -> A2AError {
+        A2AError::Generic(JSONRPCError {
+            code: error_codes::CONTENT_REJECTED,
+            message: format!("Artifact '{}' of {} bytes exceeds the limit of {} bytes", artifact_id, size, self.max_bytes),
+            data: Some(json!({"artifact_id": artifact_id, "size": size, "limit": self.max_bytes})),
+        })
+    }
+
+    /// Replaces the parts of `artifact` by a link to its stored content
+    fn truncate(&self, artifact: &mut Artifact, uri: String, size: u64) {
+        artifact.parts = vec![Part::Direct(PartRoot::File(FilePart {
+            file: FileContent::Uri(FileWithUri {
+                uri: uri.clone(),
+                mime_type: Some(SPILLED_ARTIFACT_MIME_TYPE.to_string()),
+                name: artifact.name.clone(),
+            }),
+            kind: "file".to_string(),
+            metadata: None,
+        }))];
+        artifact.metadata.get_or_insert_with(HashMap::new).insert(
+            ARTIFACT_TRUNCATED_METADATA_KEY.to_string(),
+            json!({"size": size, "limit": self.max_bytes, "uri": uri}),
+        );
+    }
+
+    async fn limit_update(&self, mut event: TaskArtifactUpdateEvent) -> Result<Option<TaskArtifactUpdateEvent>, A2AError> {
+        let key = (event.task_id.clone(), event.artifact.artifact_id.clone());
+        let chunk_size = parts_size(&event.artifact.parts);
+        let (size, truncated, mut parts) = {
+            let mut artifacts = self.artifacts.lock().unwrap();
+            let streamed = artifacts.entry(key.clone()).or_default();
+            if event.append != Some(true) {
+                *streamed = Streamed::default();
+            }
+            let size = streamed.size + chunk_size;
+            if size <= self.max_bytes || self.spill.is_none() {
+                if size <= self.max_bytes {
+                    streamed.size = size;
+                    streamed.parts.extend(event.artifact.parts.iter().cloned());
+                }
+                (size, streamed.truncated, Vec::new())
+            } else {
+                streamed.size = size;
+                let parts = std::mem::take(&mut streamed.parts);
+                let truncated = std::mem::replace(&mut streamed.truncated, true);
+                (size, truncated, parts)
+            }
+        };
+        if size <= self.max_bytes {
+            return Ok(Some(event));
+        }
+        let Some(spill) = &self.spill else {
+            return Err(self.reject(&key.1, size));
+        };
+
+        let uri = artifact_url(&spill.base_url, &key.0, &key.1);
+        if truncated {
+            spill.store.append(&key.0, &key.1, ndjson(&event.artifact.parts)).await?;
+            if event.last_chunk != Some(true) {
+                return Ok(None);
+            }
+            event.artifact.parts = Vec::new();
+            event.artifact.metadata.get_or_insert_with(HashMap::new).insert(
+                ARTIFACT_TRUNCATED_METADATA_KEY.to_string(),
+                json!({"size": size, "limit": self.max_bytes, "uri": uri}),
+            );
+            return Ok(Some(event));
+        }
+
+        parts.append(&mut event.artifact.parts);
+        spill
+            .store
+            .put(&key.0, &key.1, ndjson(&parts), Some(SPILLED_ARTIFACT_MIME_TYPE.to_string()))
+            .await?;
+        self.truncate(&mut event.artifact, uri, size);
+        Ok(Some(event))
+    }
+
+    async fn limit_artifacts(&self, task_id: &str, artifacts: &mut [Artifact]) -> Result<(), A2AError> {
+        for artifact in artifacts {
+            let size = parts_size(&artifact.parts);
+            if size <= self.max_bytes {
+                continue;
+            }
+            let Some(spill) = &self.spill else {
+                return Err(self.reject(&artifact.artifact_id, size));
+            };
+            spill
+                .store
+                .put(task_id, &artifact.artifact_id, ndjson(&artifact.parts), Some(SPILLED_ARTIFACT_MIME_TYPE.to_string()))
+                .await?;
+            let uri = artifact_url(&spill.base_url, task_id, &artifact.artifact_id);
+            self.truncate(artifact, uri, size);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventQueue for LimitedQueue {
+    async fn enqueue_event(&self, event: Event) -> Result<(), A2AError> {
+        let event = match event {
+            Event::TaskArtifactUpdate(update) => match self.limit_update(update).await? {
+                Some(update) => Event::TaskArtifactUpdate(update),
+                None => return Ok(()),
+            },
+            Event::Task(mut task) => {
+                if let Some(artifacts) = task.artifacts.as_mut() {
+                    self.limit_artifacts(&task.id, artifacts).await?;
+                }
+                Event::Task(task)
+            }
+            event => event,
+        };
+        self.inner.enqueue_event(event).await
+    }
+
+    async fn dequeue_event(&self, no_wait: bool) -> Result<Event, A2AError> {
+        self.inner.dequeue_event(no_wait).await
+    }
+
+    fn tap(&self) -> Arc<dyn EventQueue> {
+        self.inner.tap()
+    }
+
+    async fn close(&self, immediate: bool) -> Result<(), A2AError> {
+        self.inner.close(immediate).await
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+
+    fn size(&self) -> usize {
+        self.inner.size()
+    }
+
+    fn task_done(&self) {
+        self.inner.task_done()
+    }
+}
+
+/// JSON length of `parts`
+fn parts_size(parts: &[Part]) -> u64 {
+    parts
+        .iter()
+        .map(|part| serde_json::to_vec(part).map(|json| json.len() as u64).unwrap_or(0))
+        .sum()
+}
+
+/// `parts` as newline-delimited JSON
+fn ndjson(parts: &[Part]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for part in parts {
+        if let Ok(json) = serde_json::to_vec(part) {
+            bytes.extend(json);
+            bytes.push(b'\n');
+        }
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::server::artifacts::InMemoryArtifactStore;
+    use crate::a2a::server::events::InMemoryEventQueue;
+    use crate::{Message, MessageSendParams, Role};
+
+    /// Publishes the chunks of one artifact, then the task holding it whole
+    struct Chunks(Vec<&'static str>);
+
+    #[async_trait]
+    impl AgentExecutor for Chunks {
+        async fn execute(&self, _context: RequestContext, event_queue: Arc<dyn EventQueue>) -> Result<(), A2AError> {
+            for (i, text) in self.0.iter().enumerate() {
+                let mut artifact = Artifact::new(vec![Part::text(text.to_string())]);
+                artifact.artifact_id = "report".to_string();
+                let event = TaskArtifactUpdateEvent::new("task-1".to_string(), "ctx-1".to_string(), artifact)
+                    .with_append(i > 0)
+                    .with_last_chunk(i + 1 == self.0.len());
+                event_queue.enqueue_event(Event::TaskArtifactUpdate(event)).await?;
+            }
+            Ok(())
+        }
+
+        async fn cancel(&self, _context: RequestContext, _event_queue: Arc<dyn EventQueue>) -> Result<(), A2AError> {
+            Ok(())
+        }
+    }
+
+    async fn context() -> RequestContext {
+        let params = MessageSendParams {
+            message: Message::new(Role::User, vec![Part::text("report".to_string())]),
+            configuration: None,
+            metadata: None,
+        };
+        RequestContext::new(Some(params), Some("task-1".to_string()), Some("ctx-1".to_string()), None, None, None, None, None)
+            .await
+            .unwrap()
+    }
+
+    async fn updates(queue: &InMemoryEventQueue) -> Vec<TaskArtifactUpdateEvent> {
+        let mut updates = Vec::new();
+        while let Ok(Event::TaskArtifactUpdate(update)) = queue.dequeue_event(true).await {
+            updates.push(update);
+        }
+        updates
+    }
+
+    #[tokio::test]
+    async fn test_oversized_artifacts_are_rejected() {
+        let chunk_size = parts_size(&[Part::text("0123456789".to_string())]);
+        let executor = Chunks(vec!["0123456789", "0123456789", "0123456789"]);
+        let limit = ArtifactSizeLimit::new(Arc::new(executor), chunk_size * 2);
+        let queue = Arc::new(InMemoryEventQueue::new().unwrap());
+        let err = limit.execute(context().await, queue.clone()).await.unwrap_err();
+        assert!(matches!(&err, A2AError::Generic(error) if error.code == error_codes::CONTENT_REJECTED));
+        assert_eq!(updates(&queue).await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_artifacts_are_truncated_and_spilled() {
+        let chunk_size = parts_size(&[Part::text("0123456789".to_string())]);
+        let store = Arc::new(InMemoryArtifactStore::new());
+        let executor = Chunks(vec!["0123456789", "0123456789", "0123456789", "0123456789"]);
+        let limit = ArtifactSizeLimit::new(Arc::new(executor), chunk_size * 2)
+            .with_spill(store.clone(), url::Url::parse("http://agent/artifacts/").unwrap());
+        let queue = Arc::new(InMemoryEventQueue::new().unwrap());
+        limit.execute(context().await, queue.clone()).await.unwrap();
+
+        let updates = updates(&queue).await;
+        assert_eq!(updates.len(), 4); // two chunks, the marker and the last chunk
+        let marker = &updates[2];
+        match marker.artifact.parts[0].root() {
+            PartRoot::File(FilePart { file: FileContent::Uri(file), .. }) => {
+                assert_eq!(file.uri, "http://agent/artifacts/task-1/report");
+            }
+            other => panic!("unexpected part {:?}", other),
+        }
+        let last = &updates[3];
+        assert_eq!(last.last_chunk, Some(true));
+        assert!(last.artifact.parts.is_empty());
+        let truncated = &last.artifact.metadata.as_ref().unwrap()[ARTIFACT_TRUNCATED_METADATA_KEY];
+        assert_eq!(truncated["size"], json!(chunk_size * 4));
+
+        let stored = store.stat("task-1", "report").await.unwrap().unwrap();
+        assert_eq!(stored.mime_type.as_deref(), Some(SPILLED_ARTIFACT_MIME_TYPE));
+        assert_eq!(stored.size, chunk_size * 4 + 4);
+    }
+}
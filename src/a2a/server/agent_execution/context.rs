@@ -5,6 +5,7 @@
 //! context identifiers, and related tasks.
 
 use crate::{A2AError, Message, MessageSendConfiguration, MessageSendParams, Task};
+use crate::a2a::extensions::locale::{negotiate_locale, LOCALE_METADATA_KEY};
 use crate::a2a::server::context::ServerCallContext;
 use crate::a2a::server::id_generator::{IDGenerator, IDGeneratorContext, UUIDGenerator};
 use std::collections::HashMap;
@@ -53,6 +54,7 @@ impl RequestContext {
     /// * `call_context` - The server call context associated with this request
     /// * `task_id_generator` - ID generator for new task IDs
     /// * `context_id_generator` - ID generator for new context IDs
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         request: Option<MessageSendParams>,
         task_id: Option<String>,
@@ -84,17 +86,17 @@ impl RequestContext {
                 {
                     let params = context.request.as_mut().unwrap();
                     if let Some(ref message) = params.message.task_id {
-                        if message.to_string() != *task_id {
+                        if message != task_id {
                             return Err(A2AError::invalid_params("bad task id"));
                         }
                     } else {
-                        params.message.task_id = Some(uuid::Uuid::parse_str(task_id).map_err(|_| A2AError::invalid_params("invalid task id format"))?.to_string());
+                        params.message.task_id = Some(task_id.clone());
                     }
                 }
                 
                 // Validate against current task if present
                 if let Some(ref current_task) = context.current_task {
-                    if current_task.id.to_string() != *task_id {
+                    if current_task.id != *task_id {
                         return Err(A2AError::invalid_params("bad task id"));
                     }
                 }
@@ -118,7 +120,7 @@ impl RequestContext {
                 
                 // Validate against current task if present
                 if let Some(ref current_task) = context.current_task {
-                    if current_task.context_id.to_string() != *context_id {
+                    if current_task.context_id != *context_id {
                         return Err(A2AError::invalid_params("bad context id"));
                     }
                 }
@@ -205,6 +207,32 @@ impl RequestContext {
             .unwrap_or(false)
     }
     
+    /// Gets the caller's language preferences, most preferred first
+    ///
+    /// A `locale` entry in the message metadata comes before the ranges of
+    /// the caller's `Accept-Language` header.
+    pub fn accepted_languages(&self) -> Vec<String> {
+        let mut languages: Vec<String> = self
+            .message()
+            .and_then(|message| message.metadata.as_ref())
+            .and_then(|metadata| metadata.get(LOCALE_METADATA_KEY))
+            .and_then(|locale| locale.as_str())
+            .map(|locale| vec![locale.to_string()])
+            .unwrap_or_default();
+        if let Some(call_context) = &self.call_context {
+            languages.extend(call_context.accepted_languages.iter().cloned());
+        }
+        languages
+    }
+
+    /// Picks the locale to respond in from those the agent `supported`
+    ///
+    /// Returns `None` when none of the caller's preferences can be met, in
+    /// which case the agent answers in its default locale.
+    pub fn negotiate_locale(&self, supported: &[String]) -> Option<String> {
+        negotiate_locale(&self.accepted_languages(), supported)
+    }
+
     /// Ensures a task ID is present, generating one if necessary
     async fn check_or_generate_task_id(&mut self) -> Result<(), A2AError> {
         if self.request.is_none() {
@@ -346,15 +374,16 @@ mod tests {
         };
         
         let task = Task {
-            id: Uuid::parse_str(&task_id).unwrap(),
-            context_id: Uuid::parse_str(&context_id).unwrap(),
+            id: task_id.clone(),
+            context_id: context_id.clone(),
             status: crate::TaskStatus {
                 state: TaskState::Working,
-                timestamp: Some(chrono::Utc::now()),
+                timestamp: Some(chrono::Utc::now().to_rfc3339()),
                 message: None,
             },
             artifacts: None,
             history: None,
+            state_transitions: None,
             metadata: None,
             kind: "task".to_string(),
         };
@@ -447,15 +476,16 @@ mod tests {
         assert!(context.related_tasks.is_empty());
         
         let task = Task {
-            id: Uuid::new_v4(),
-            context_id: Uuid::new_v4(),
+            id: Uuid::new_v4().to_string(),
+            context_id: Uuid::new_v4().to_string(),
             status: crate::TaskStatus {
                 state: TaskState::Working,
-                timestamp: Some(chrono::Utc::now()),
+                timestamp: Some(chrono::Utc::now().to_rfc3339()),
                 message: None,
             },
             artifacts: None,
             history: None,
+            state_transitions: None,
             metadata: None,
             kind: "task".to_string(),
         };
@@ -467,7 +497,7 @@ mod tests {
     #[test]
     fn test_add_activated_extension() {
         let user = AuthenticatedUser::new("user123".to_string());
-        let mut call_context = ServerCallContext::with_user(user);
+        let call_context = ServerCallContext::with_user(user);
         
         let mut context = RequestContext {
             request: None,
@@ -539,4 +569,29 @@ mod tests {
         let retrieved_metadata = context.metadata();
         assert_eq!(retrieved_metadata, metadata);
     }
+
+    #[test]
+    fn test_locale_negotiation_prefers_message_metadata() {
+        let mut call_context = ServerCallContext::new();
+        call_context.accepted_languages = vec!["fr".to_string(), "en".to_string()];
+        let message = Message::new(Role::User, vec![Part::text("Hallo".to_string())]);
+        let mut context = RequestContext {
+            request: Some(MessageSendParams::new(message.clone())),
+            task_id: None,
+            context_id: None,
+            current_task: None,
+            related_tasks: Vec::new(),
+            call_context: Some(call_context),
+            task_id_generator: Arc::new(UUIDGenerator::new()),
+            context_id_generator: Arc::new(UUIDGenerator::new()),
+        };
+        let supported = vec!["en".to_string(), "de".to_string()];
+        assert_eq!(context.negotiate_locale(&supported).as_deref(), Some("en"));
+
+        let locale = HashMap::from([(LOCALE_METADATA_KEY.to_string(), serde_json::json!("de-DE"))]);
+        let message = message.with_metadata(locale);
+        context.request = Some(MessageSendParams::new(message));
+        assert_eq!(context.accepted_languages(), vec!["de-DE", "fr", "en"]);
+        assert_eq!(context.negotiate_locale(&supported).as_deref(), Some("de"));
+    }
 }
@@ -0,0 +1,635 @@
+//! Reference executor backed by an LLM
+//!
+//! Available with the `llm` feature. `LlmAgentExecutor` forwards the user
+//! message, together with the history of the task, to an OpenAI-compatible
+//! `/chat/completions` endpoint. Tokens are streamed back as appended chunks
+//! of the `response` artifact; its last chunk replaces the streamed text with
+//! the complete reply and is followed by a final `completed` status. Status
+//! updates are only sent when the state changes, so the task history is not
+//! flooded with deltas.
+//!
+//! Tools registered in a `ToolRegistry` are offered to the model. Each call the
+//! model makes is run, announced as a `tool_call` data part appended to the
+//! `tool_calls` artifact, and its result, announced with a `tool_result` data
+//! part there, is fed back to the model for the next round.
+
+use crate::a2a::server::agent_execution::llm_tools::{ToolCall, ToolRegistry};
+use crate::a2a::server::agent_execution::{AgentExecutor, RequestContext};
+use crate::a2a::server::events::{Event, EventQueue};
+use crate::{A2AError, Artifact, Message, Part, Role, TaskArtifactUpdateEvent, TaskState, TaskStatus, TaskStatusUpdateEvent};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Default endpoint of the OpenAI API
+pub const DEFAULT_LLM_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Name of the artifact holding the complete reply
+pub const LLM_RESPONSE_ARTIFACT_NAME: &str = "response";
+
+/// Name of the artifact announcing tool calls and their results
+pub const LLM_TOOLS_ARTIFACT_NAME: &str = "tool_calls";
+
+/// Default limit on model turns that end in tool calls
+pub const DEFAULT_MAX_TOOL_ROUNDS: usize = 8;
+
+/// Settings of the chat completion endpoint
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct LlmConfig {
+    /// Base URL of the API, without the `/chat/completions` suffix
+    pub base_url: String,
+    /// Bearer token sent in the `Authorization` header
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// The model to use
+    pub model: String,
+    /// System prompt sent before the conversation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Sampling temperature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Maximum number of tokens to generate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+}
+
+impl LlmConfig {
+    /// Creates a configuration for `model` at the OpenAI API
+    pub fn new(model: &str) -> Self {
+        Self {
+            base_url: DEFAULT_LLM_BASE_URL.to_string(),
+            api_key: None,
+            model: model.to_string(),
+            system_prompt: None,
+            temperature: None,
+            max_tokens: None,
+        }
+    }
+
+    /// Sets the base URL, e.g. of a local OpenAI-compatible server
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets the API key
+    pub fn with_api_key(mut self, api_key: &str) -> Self {
+        self.api_key = Some(api_key.to_string());
+        self
+    }
+
+    /// Sets the system prompt
+    pub fn with_system_prompt(mut self, system_prompt: &str) -> Self {
+        self.system_prompt = Some(system_prompt.to_string());
+        self
+    }
+
+    /// Sets the sampling temperature
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Sets the maximum number of generated tokens
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+}
+
+impl std::fmt::Debug for LlmConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlmConfig")
+            .field("base_url", &self.base_url)
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field("model", &self.model)
+            .field("system_prompt", &self.system_prompt)
+            .field("temperature", &self.temperature)
+            .field("max_tokens", &self.max_tokens)
+            .finish()
+    }
+}
+
+/// Agent executor answering with an OpenAI-compatible chat model
+pub struct LlmAgentExecutor {
+    client: reqwest::Client,
+    config: LlmConfig,
+    tools: ToolRegistry,
+    max_tool_rounds: usize,
+}
+
+/// A streamed model turn
+#[derive(Debug, Default)]
+struct Completion {
+    content: String,
+    tool_calls: Vec<ToolCall>,
+}
+
+impl LlmAgentExecutor {
+    /// Creates an executor for the given endpoint settings
+    pub fn new(config: LlmConfig) -> Self {
+        Self::with_client(reqwest::Client::new(), config)
+    }
+
+    /// Creates an executor using a custom HTTP client
+    pub fn with_client(client: reqwest::Client, config: LlmConfig) -> Self {
+        Self {
+            client,
+            config,
+            tools: ToolRegistry::new(),
+            max_tool_rounds: DEFAULT_MAX_TOOL_ROUNDS,
+        }
+    }
+
+    /// Offers the tools in `registry` to the model
+    pub fn with_tools(mut self, registry: ToolRegistry) -> Self {
+        self.tools = registry;
+        self
+    }
+
+    /// Limits how many consecutive model turns may request tool calls
+    pub fn with_max_tool_rounds(mut self, max_tool_rounds: usize) -> Self {
+        self.max_tool_rounds = max_tool_rounds;
+        self
+    }
+
+    /// Returns the endpoint settings
+    pub fn config(&self) -> &LlmConfig {
+        &self.config
+    }
+
+    /// Builds the chat messages for a request: system prompt, task history, user input
+    pub fn chat_messages(&self, context: &RequestContext) -> Vec<Value> {
+        let mut messages = Vec::new();
+        if let Some(system_prompt) = &self.config.system_prompt {
+            messages.push(json!({ "role": "system", "content": system_prompt }));
+        }
+
+        let current_id = context.message().map(|message| message.message_id.as_str());
+        let history = context
+            .current_task
+            .as_ref()
+            .and_then(|task| task.history.as_ref())
+            .into_iter()
+            .flatten()
+            .filter(|message| Some(message.message_id.as_str()) != current_id);
+        for message in history {
+            let text = message_text(message);
+            if !text.is_empty() {
+                messages.push(json!({ "role": chat_role(&message.role), "content": text }));
+            }
+        }
+
+        messages.push(json!({ "role": "user", "content": context.get_user_input("\n") }));
+        messages
+    }
+
+    /// Streams one model turn, calling `on_delta` for every text fragment
+    async fn stream_completion<F, Fut>(&self, messages: &[Value], mut on_delta: F) -> Result<Completion, A2AError>
+    where
+        F: FnMut(String) -> Fut + Send,
+        Fut: std::future::Future<Output = Result<(), A2AError>> + Send,
+    {
+        let mut body = json!({
+            "model": self.config.model,
+            "messages": messages,
+            "stream": true,
+        });
+        if let Some(temperature) = self.config.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        if let Some(max_tokens) = self.config.max_tokens {
+            body["max_tokens"] = json!(max_tokens);
+        }
+        if !self.tools.is_empty() {
+            body["tools"] = json!(self.tools.to_openai());
+        }
+
+        let mut request = self
+            .client
+            .post(format!("{}/chat/completions", self.config.base_url))
+            .json(&body);
+        if let Some(api_key) = &self.config.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let mut response = request
+            .send()
+            .await
+            .map_err(|e| A2AError::transport_error(format!("Chat completion request failed: {}", e)))?;
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let text = response.text().await.unwrap_or_default();
+            return Err(A2AError::http_error(status, text));
+        }
+
+        let mut completion = Completion::default();
+        // Bytes are buffered so multi-byte characters split across chunks survive
+        let mut buffer: Vec<u8> = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| A2AError::transport_error(format!("Chat completion stream failed: {}", e)))?
+        {
+            buffer.extend_from_slice(&chunk);
+            while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line);
+                let Some(data) = line.trim().strip_prefix("data:").map(str::trim) else {
+                    continue;
+                };
+                if data == "[DONE]" {
+                    return Ok(completion);
+                }
+                let chunk: Value = serde_json::from_str(data)?;
+                let delta = &chunk["choices"][0]["delta"];
+                if let Some(text) = delta["content"].as_str() {
+                    if !text.is_empty() {
+                        completion.content.push_str(text);
+                        on_delta(text.to_string()).await?;
+                    }
+                }
+                // Tool calls arrive in fragments keyed by their index
+                for fragment in delta["tool_calls"].as_array().into_iter().flatten() {
+                    let index = fragment["index"].as_u64().unwrap_or(0) as usize;
+                    if completion.tool_calls.len() <= index {
+                        completion.tool_calls.resize_with(index + 1, ToolCall::default);
+                    }
+                    let call = &mut completion.tool_calls[index];
+                    if let Some(id) = fragment["id"].as_str() {
+                        call.id.push_str(id);
+                    }
+                    if let Some(name) = fragment["function"]["name"].as_str() {
+                        call.name.push_str(name);
+                    }
+                    if let Some(arguments) = fragment["function"]["arguments"].as_str() {
+                        call.arguments.push_str(arguments);
+                    }
+                }
+            }
+        }
+        Ok(completion)
+    }
+
+    /// Runs model turns and tool calls until the model answers in plain text
+    async fn converse(&self, messages: &mut Vec<Value>, publisher: &Publisher) -> Result<String, A2AError> {
+        let mut rounds = 0;
+        loop {
+            let completion = self
+                .stream_completion(messages, |delta| publisher.publish_text(delta))
+                .await?;
+            if completion.tool_calls.is_empty() {
+                return Ok(completion.content);
+            }
+            if rounds == self.max_tool_rounds {
+                return Err(A2AError::internal(&format!(
+                    "Model requested tools for more than {} rounds",
+                    self.max_tool_rounds
+                )));
+            }
+            rounds += 1;
+
+            messages.push(json!({
+                "role": "assistant",
+                "content": if completion.content.is_empty() { Value::Null } else { json!(completion.content) },
+                "tool_calls": completion.tool_calls.iter().map(ToolCall::to_openai).collect::<Vec<_>>(),
+            }));
+            for call in &completion.tool_calls {
+                publisher
+                    .publish_data(json!({ "tool_call": { "id": call.id, "name": call.name, "arguments": call.arguments } }))
+                    .await?;
+                let outcome = self.tools.execute(call).await;
+                publisher
+                    .publish_data(json!({ "tool_result": {
+                        "id": call.id,
+                        "name": call.name,
+                        "output": outcome.output,
+                        "is_error": outcome.is_error,
+                    } }))
+                    .await?;
+                messages.push(json!({ "role": "tool", "tool_call_id": call.id, "content": outcome.content() }));
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for LlmAgentExecutor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlmAgentExecutor")
+            .field("base_url", &self.config.base_url)
+            .field("model", &self.config.model)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl AgentExecutor for LlmAgentExecutor {
+    async fn execute(
+        &self,
+        context: RequestContext,
+        event_queue: Arc<dyn EventQueue>,
+    ) -> Result<(), A2AError> {
+        let publisher = Publisher {
+            event_queue,
+            task_id: context.task_id.clone().unwrap_or_default(),
+            context_id: context.context_id.clone().unwrap_or_default(),
+            response: StreamedArtifact::new(LLM_RESPONSE_ARTIFACT_NAME),
+            tools: StreamedArtifact::new(LLM_TOOLS_ARTIFACT_NAME),
+        };
+        publisher.publish_status(TaskState::Working, None, false).await?;
+
+        let mut messages = self.chat_messages(&context);
+        match self.converse(&mut messages, &publisher).await {
+            Ok(reply) => {
+                publisher.publish_reply(reply).await?;
+                publisher.publish_status(TaskState::Completed, None, true).await
+            }
+            Err(e) => {
+                let message = publisher.message(vec![Part::text(e.to_string())]);
+                publisher.publish_status(TaskState::Failed, Some(message), true).await?;
+                Err(e)
+            }
+        }
+    }
+
+    async fn cancel(
+        &self,
+        context: RequestContext,
+        event_queue: Arc<dyn EventQueue>,
+    ) -> Result<(), A2AError> {
+        let status = TaskStatusUpdateEvent::new(
+            context.task_id.clone().unwrap_or_default(),
+            context.context_id.clone().unwrap_or_default(),
+            TaskStatus::new(TaskState::Canceled),
+            true,
+        );
+        event_queue.enqueue_event(Event::TaskStatusUpdate(status)).await
+    }
+}
+
+/// An artifact published in chunks
+struct StreamedArtifact {
+    artifact_id: String,
+    name: &'static str,
+    started: AtomicBool,
+}
+
+impl StreamedArtifact {
+    fn new(name: &'static str) -> Self {
+        Self {
+            artifact_id: uuid::Uuid::new_v4().to_string(),
+            name,
+            started: AtomicBool::new(false),
+        }
+    }
+}
+
+/// Publishes the events of one task
+struct Publisher {
+    event_queue: Arc<dyn EventQueue>,
+    task_id: String,
+    context_id: String,
+    response: StreamedArtifact,
+    tools: StreamedArtifact,
+}
+
+impl Publisher {
+    fn message(&self, parts: Vec<Part>) -> Message {
+        Message::new(Role::Agent, parts)
+            .with_task_id(self.task_id.clone())
+            .with_context_id(self.context_id.clone())
+    }
+
+    async fn publish_status(&self, state: TaskState, message: Option<Message>, r#final: bool) -> Result<(), A2AError> {
+        let mut status = TaskStatus::new(state);
+        if let Some(message) = message {
+            status = status.with_message(message);
+        }
+        let event = TaskStatusUpdateEvent::new(self.task_id.clone(), self.context_id.clone(), status, r#final);
+        self.event_queue.enqueue_event(Event::TaskStatusUpdate(event)).await
+    }
+
+    /// Appends `parts` to `artifact`, creating it with the first chunk
+    async fn publish_chunk(&self, artifact: &StreamedArtifact, parts: Vec<Part>, append: bool, last_chunk: bool) -> Result<(), A2AError> {
+        let append = append && artifact.started.swap(true, Ordering::SeqCst);
+        let chunk = Artifact::new(parts)
+            .with_artifact_id(artifact.artifact_id.clone())
+            .with_name(artifact.name.to_string());
+        let event = TaskArtifactUpdateEvent::new(self.task_id.clone(), self.context_id.clone(), chunk)
+            .with_append(append)
+            .with_last_chunk(last_chunk);
+        self.event_queue.enqueue_event(Event::TaskArtifactUpdate(event)).await
+    }
+
+    async fn publish_text(&self, text: String) -> Result<(), A2AError> {
+        self.publish_chunk(&self.response, vec![Part::text(text)], true, false).await
+    }
+
+    /// Replaces the streamed text with the complete reply
+    async fn publish_reply(&self, reply: String) -> Result<(), A2AError> {
+        self.response.started.store(true, Ordering::SeqCst);
+        self.publish_chunk(&self.response, vec![Part::text(reply)], false, true).await
+    }
+
+    async fn publish_data(&self, data: Value) -> Result<(), A2AError> {
+        self.publish_chunk(&self.tools, vec![Part::data(data)], true, false).await
+    }
+}
+
+fn chat_role(role: &Role) -> &'static str {
+    match role {
+        Role::User => "user",
+        Role::Agent => "assistant",
+    }
+}
+
+fn message_text(message: &Message) -> String {
+    message
+        .parts
+        .iter()
+        .filter_map(|part| match part.root() {
+            crate::a2a::core_types::PartRoot::Text(text) => Some(text.text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::server::events::InMemoryEventQueue;
+    use crate::MessageSendParams;
+    use mockito::Server;
+
+    async fn request_context(text: &str) -> RequestContext {
+        let message = Message::new(Role::User, vec![Part::text(text.to_string())]);
+        let params = MessageSendParams {
+            message,
+            configuration: None,
+            metadata: None,
+        };
+        RequestContext::new(
+            Some(params),
+            Some("task-1".to_string()),
+            Some("ctx-1".to_string()),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_streams_deltas_and_final_artifact() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/chat/completions")
+            .match_header("authorization", "Bearer sk-test")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "model": "gpt-test",
+                "stream": true,
+                "messages": [
+                    { "role": "system", "content": "Be brief." },
+                    { "role": "user", "content": "Hi" },
+                ],
+            })))
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n",
+                "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+                "data: {\"choices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n",
+                "data: [DONE]\n\n",
+            ))
+            .create_async()
+            .await;
+
+        let executor = LlmAgentExecutor::new(
+            LlmConfig::new("gpt-test")
+                .with_base_url(&server.url())
+                .with_api_key("sk-test")
+                .with_system_prompt("Be brief."),
+        );
+        let queue = Arc::new(InMemoryEventQueue::new().unwrap());
+        executor.execute(request_context("Hi").await, queue.clone()).await.unwrap();
+        mock.assert_async().await;
+
+        let mut chunks = Vec::new();
+        let mut states = Vec::new();
+        while let Ok(event) = queue.dequeue_event(true).await {
+            match event {
+                Event::TaskStatusUpdate(update) => {
+                    assert!(update.status.message.is_none());
+                    states.push((update.status.state, update.r#final));
+                }
+                Event::TaskArtifactUpdate(update) => {
+                    assert_eq!(update.artifact.name.as_deref(), Some(LLM_RESPONSE_ARTIFACT_NAME));
+                    let text = message_text(&Message::new(Role::Agent, update.artifact.parts));
+                    chunks.push((text, update.append, update.last_chunk));
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(
+            chunks,
+            vec![
+                ("Hel".to_string(), Some(false), Some(false)),
+                ("lo".to_string(), Some(true), Some(false)),
+                ("Hello".to_string(), Some(false), Some(true)),
+            ]
+        );
+        assert_eq!(states, vec![(TaskState::Working, false), (TaskState::Completed, true)]);
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_are_run_and_fed_back() {
+        let mut server = Server::new_async().await;
+        let tool_turn = server
+            .mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "tools": [{ "type": "function", "function": { "name": "weather" } }],
+            })))
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"call_1\",\"function\":{\"name\":\"weather\",\"arguments\":\"{\\\"city\\\"\"}}]}}]}\n\n",
+                "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\":\\\"Oslo\\\"}\"}}]}}]}\n\n",
+                "data: [DONE]\n\n",
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+        let answer_turn = server
+            .mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "messages": [
+                    { "role": "user", "content": "Weather?" },
+                    { "role": "assistant", "tool_calls": [{ "id": "call_1" }] },
+                    { "role": "tool", "tool_call_id": "call_1", "content": "sunny in Oslo" },
+                ],
+            })))
+            .with_header("content-type", "text/event-stream")
+            .with_body("data: {\"choices\":[{\"delta\":{\"content\":\"Sunny.\"}}]}\n\ndata: [DONE]\n\n")
+            .create_async()
+            .await;
+
+        let tools = ToolRegistry::new().with_tool(
+            "weather",
+            "Current weather",
+            json!({ "type": "object", "properties": { "city": { "type": "string" } } }),
+            |arguments| async move { Ok(json!(format!("sunny in {}", arguments["city"].as_str().unwrap_or("?")))) },
+        );
+        let executor = LlmAgentExecutor::new(LlmConfig::new("gpt-test").with_base_url(&server.url())).with_tools(tools);
+        let queue = Arc::new(InMemoryEventQueue::new().unwrap());
+        executor.execute(request_context("Weather?").await, queue.clone()).await.unwrap();
+        tool_turn.assert_async().await;
+        answer_turn.assert_async().await;
+
+        let mut activity = Vec::new();
+        while let Ok(event) = queue.dequeue_event(true).await {
+            if let Event::TaskArtifactUpdate(update) = event {
+                if update.artifact.name.as_deref() != Some(LLM_TOOLS_ARTIFACT_NAME) {
+                    continue;
+                }
+                for part in update.artifact.parts {
+                    if let crate::a2a::core_types::PartRoot::Data(data) = part.root() {
+                        activity.push(data.data.clone());
+                    }
+                }
+            }
+        }
+        assert_eq!(activity[0]["tool_call"]["arguments"], r#"{"city":"Oslo"}"#);
+        assert_eq!(activity[1]["tool_result"]["output"], "sunny in Oslo");
+    }
+
+    #[test]
+    fn test_debug_redacts_api_key() {
+        let debug = format!("{:?}", LlmConfig::new("gpt-test").with_api_key("sk-secret"));
+        assert!(!debug.contains("sk-secret"), "{}", debug);
+        assert!(debug.contains("<redacted>"));
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_error_fails_task() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/chat/completions")
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let executor = LlmAgentExecutor::new(LlmConfig::new("gpt-test").with_base_url(&server.url()));
+        let queue = Arc::new(InMemoryEventQueue::new().unwrap());
+        assert!(executor.execute(request_context("Hi").await, queue.clone()).await.is_err());
+
+        let mut last = None;
+        while let Ok(Event::TaskStatusUpdate(update)) = queue.dequeue_event(true).await {
+            last = Some(update.status.state);
+        }
+        assert_eq!(last, Some(TaskState::Failed));
+    }
+}
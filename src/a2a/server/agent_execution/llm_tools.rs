@@ -0,0 +1,230 @@
+//! Tools callable by the LLM executor
+//!
+//! A `ToolRegistry` maps tool names to async Rust functions and their JSON
+//! Schema. `LlmAgentExecutor` advertises the registered tools to the model,
+//! runs the calls the model makes and feeds the results back until the model
+//! answers in plain text.
+
+use crate::A2AError;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+
+type ToolHandler = Arc<dyn Fn(Value) -> BoxFuture<'static, Result<Value, A2AError>> + Send + Sync>;
+
+/// A function the model can call
+#[derive(Clone)]
+pub struct LlmTool {
+    /// The function name
+    pub name: String,
+    /// What the function does, shown to the model
+    pub description: String,
+    /// JSON Schema of the arguments object
+    pub parameters: Value,
+    handler: ToolHandler,
+}
+
+impl LlmTool {
+    /// Returns the OpenAI `tools` entry for this function
+    pub fn to_openai(&self) -> Value {
+        json!({
+            "type": "function",
+            "function": {
+                "name": self.name,
+                "description": self.description,
+                "parameters": self.parameters,
+            },
+        })
+    }
+
+    /// Runs the function with the given arguments
+    pub async fn call(&self, arguments: Value) -> Result<Value, A2AError> {
+        (self.handler)(arguments).await
+    }
+}
+
+impl std::fmt::Debug for LlmTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlmTool")
+            .field("name", &self.name)
+            .field("description", &self.description)
+            .finish()
+    }
+}
+
+/// The set of tools offered to the model
+#[derive(Debug, Clone, Default)]
+pub struct ToolRegistry {
+    tools: BTreeMap<String, LlmTool>,
+}
+
+impl ToolRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `handler` as the tool `name`, replacing any tool with that name
+    pub fn register<F, Fut>(&mut self, name: &str, description: &str, parameters: Value, handler: F) -> &mut Self
+    where
+        F: Fn(Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value, A2AError>> + Send + 'static,
+    {
+        let handler: ToolHandler = Arc::new(move |arguments| Box::pin(handler(arguments)));
+        self.tools.insert(
+            name.to_string(),
+            LlmTool {
+                name: name.to_string(),
+                description: description.to_string(),
+                parameters,
+                handler,
+            },
+        );
+        self
+    }
+
+    /// Builder-style variant of `register`
+    pub fn with_tool<F, Fut>(mut self, name: &str, description: &str, parameters: Value, handler: F) -> Self
+    where
+        F: Fn(Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value, A2AError>> + Send + 'static,
+    {
+        self.register(name, description, parameters, handler);
+        self
+    }
+
+    /// Returns the tool called `name`
+    pub fn get(&self, name: &str) -> Option<&LlmTool> {
+        self.tools.get(name)
+    }
+
+    /// Whether no tools are registered
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+
+    /// Returns the OpenAI `tools` array for all registered tools
+    pub fn to_openai(&self) -> Vec<Value> {
+        self.tools.values().map(LlmTool::to_openai).collect()
+    }
+
+    /// Runs a call made by the model
+    ///
+    /// Unknown tools and malformed arguments are reported back to the model as
+    /// an error result instead of failing the task, so the model can recover.
+    pub async fn execute(&self, call: &ToolCall) -> ToolOutcome {
+        let Some(tool) = self.get(&call.name) else {
+            return ToolOutcome::error(format!("Unknown tool '{}'", call.name));
+        };
+        let arguments = if call.arguments.trim().is_empty() {
+            json!({})
+        } else {
+            match serde_json::from_str(&call.arguments) {
+                Ok(arguments) => arguments,
+                Err(e) => return ToolOutcome::error(format!("Invalid arguments: {}", e)),
+            }
+        };
+        match tool.call(arguments).await {
+            Ok(output) => ToolOutcome { output, is_error: false },
+            Err(e) => ToolOutcome::error(e.to_string()),
+        }
+    }
+}
+
+/// A tool call requested by the model
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ToolCall {
+    /// Id used to match the result to the call
+    pub id: String,
+    /// Name of the tool
+    pub name: String,
+    /// Arguments as the JSON text produced by the model
+    pub arguments: String,
+}
+
+impl ToolCall {
+    /// Returns the OpenAI `tool_calls` entry for this call
+    pub fn to_openai(&self) -> Value {
+        json!({
+            "id": self.id,
+            "type": "function",
+            "function": { "name": self.name, "arguments": self.arguments },
+        })
+    }
+}
+
+/// The result of running a tool call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolOutcome {
+    /// The value returned by the tool, or `{"error": ...}`
+    pub output: Value,
+    /// Whether the call failed
+    pub is_error: bool,
+}
+
+impl ToolOutcome {
+    fn error(message: String) -> Self {
+        Self {
+            output: json!({ "error": message }),
+            is_error: true,
+        }
+    }
+
+    /// The tool message content sent back to the model
+    pub fn content(&self) -> String {
+        match &self.output {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> ToolRegistry {
+        ToolRegistry::new().with_tool(
+            "add",
+            "Adds two numbers",
+            json!({ "type": "object", "properties": { "a": { "type": "number" }, "b": { "type": "number" } } }),
+            |arguments| async move {
+                let sum = arguments["a"].as_f64().unwrap_or(0.0) + arguments["b"].as_f64().unwrap_or(0.0);
+                Ok(json!(sum))
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_execute_registered_tool() {
+        let call = ToolCall {
+            id: "call_1".to_string(),
+            name: "add".to_string(),
+            arguments: r#"{"a": 2, "b": 3}"#.to_string(),
+        };
+        let outcome = registry().execute(&call).await;
+        assert!(!outcome.is_error);
+        assert_eq!(outcome.content(), "5.0");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_tool_and_bad_arguments_are_errors() {
+        let registry = registry();
+        let unknown = ToolCall {
+            name: "mul".to_string(),
+            ..Default::default()
+        };
+        assert!(registry.execute(&unknown).await.is_error);
+
+        let malformed = ToolCall {
+            name: "add".to_string(),
+            arguments: "{".to_string(),
+            ..Default::default()
+        };
+        assert!(registry.execute(&malformed).await.is_error);
+        assert_eq!(registry.to_openai()[0]["function"]["name"], "add");
+    }
+}
@@ -5,6 +5,22 @@
 
 pub mod context;
 pub mod agent_executor;
+pub mod approval;
+pub mod artifact_limits;
+pub mod skills;
+#[cfg(feature = "llm")]
+pub mod llm_executor;
+#[cfg(feature = "llm")]
+pub mod llm_tools;
 
 pub use context::RequestContext;
 pub use agent_executor::AgentExecutor;
+pub use approval::{ApprovalDecision, ApprovalGate, ApprovalNotifier, ApprovalRequest};
+pub use artifact_limits::{ArtifactSizeLimit, ARTIFACT_TRUNCATED_METADATA_KEY};
+pub use skills::SkillOutput;
+#[cfg(feature = "macros")]
+pub use a2a_rust_macros::a2a_skills;
+#[cfg(feature = "llm")]
+pub use llm_executor::{LlmAgentExecutor, LlmConfig};
+#[cfg(feature = "llm")]
+pub use llm_tools::{LlmTool, ToolCall, ToolOutcome, ToolRegistry};
@@ -0,0 +1,204 @@
+//! Runtime support for executors generated from skill functions
+//!
+//! With the `macros` feature, `#[a2a_skills]` on an impl block turns its
+//! `#[a2a_skill(...)]` methods into an `AgentExecutor` and a list of
+//! `AgentSkill` entries for the agent card:
+//!
+//! ```ignore
+//! struct Calculator;
+//!
+//! #[a2a_skills]
+//! impl Calculator {
+//!     /// Adds the numbers of a data part
+//!     #[a2a_skill(id = "add", tags = ["math"], examples = ["{\"numbers\": [1, 2]}"])]
+//!     async fn add(&self, args: AddArgs) -> Result<serde_json::Value, A2AError> {
+//!         Ok(serde_json::json!({ "sum": args.numbers.iter().sum::<f64>() }))
+//!     }
+//!
+//!     #[a2a_skill(id = "shout", description = "Repeats the text in capitals")]
+//!     async fn shout(&self, text: String) -> String {
+//!         text.to_uppercase()
+//!     }
+//! }
+//!
+//! let skills = Calculator::agent_skills(); // the `skills` of the agent card
+//! ```
+//!
+//! A message picks its skill with the `skill_id` key of its metadata. Agents
+//! with a single skill also accept messages that name none. The arguments of
+//! a skill function are filled in from the message by type:
+//!
+//! - `String` receives the text parts, joined by newlines
+//! - `&RequestContext` receives the request context
+//! - any other type is deserialized from the first data part. When there is
+//!   no data part it is deserialized from `null`, so use `Option` for
+//!   optional input
+//!
+//! A skill returns a `SkillOutput` or a `Result` of one. The output becomes
+//! an artifact named after the skill and the task completes. Errors fail the
+//! task with the error text as status message.
+//!
+//! The generated code calls the functions of this module. They are public
+//! so that executors written by hand can route and reply the same way.
+
+use crate::a2a::core_types::{Message, Part, PartRoot, Role, TaskState, TaskStatus};
+use crate::a2a::error::A2AError;
+use crate::a2a::models::{Artifact, TaskArtifactUpdateEvent, TaskStatusUpdateEvent};
+use crate::a2a::server::agent_execution::RequestContext;
+use crate::a2a::server::events::{Event, EventQueue};
+use crate::a2a::utils::constants::SKILL_ID_METADATA_KEY;
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::sync::Arc;
+
+#[doc(hidden)]
+pub use async_trait::async_trait;
+
+/// The value a skill function produces
+pub trait SkillOutput {
+    /// The parts of the artifact holding the output
+    fn into_parts(self) -> Vec<Part>;
+}
+
+impl SkillOutput for String {
+    fn into_parts(self) -> Vec<Part> {
+        vec![Part::text(self)]
+    }
+}
+
+impl SkillOutput for &str {
+    fn into_parts(self) -> Vec<Part> {
+        vec![Part::text(self.to_string())]
+    }
+}
+
+impl SkillOutput for serde_json::Value {
+    fn into_parts(self) -> Vec<Part> {
+        vec![Part::data(self)]
+    }
+}
+
+impl SkillOutput for Part {
+    fn into_parts(self) -> Vec<Part> {
+        vec![self]
+    }
+}
+
+impl SkillOutput for Vec<Part> {
+    fn into_parts(self) -> Vec<Part> {
+        self
+    }
+}
+
+/// Skills that produce no artifact
+impl SkillOutput for () {
+    fn into_parts(self) -> Vec<Part> {
+        Vec::new()
+    }
+}
+
+/// The skill named by the `skill_id` metadata of the message, if any
+pub fn requested_skill(context: &RequestContext) -> Option<String> {
+    context
+        .message()?
+        .metadata
+        .as_ref()?
+        .get(SKILL_ID_METADATA_KEY)?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// The skill among `skills` the message is addressed to
+///
+/// Fails with an invalid params error when the message names an unknown
+/// skill, or names none while there is more than one to choose from.
+pub fn select_skill<'a>(context: &RequestContext, skills: &[&'a str]) -> Result<&'a str, A2AError> {
+    match requested_skill(context) {
+        Some(requested) => skills
+            .iter()
+            .find(|skill| **skill == requested)
+            .copied()
+            .ok_or_else(|| A2AError::invalid_params(&format!("The agent has no skill '{}'", requested))),
+        None if skills.len() == 1 => Ok(skills[0]),
+        None => Err(A2AError::invalid_params(&format!(
+            "The message must name one of the skills {} in its '{}' metadata",
+            skills.join(", "),
+            SKILL_ID_METADATA_KEY
+        ))),
+    }
+}
+
+/// The text parts of the message, joined by newlines
+pub fn text_input(context: &RequestContext) -> String {
+    context.get_user_input("\n")
+}
+
+/// The first data part of the message, deserialized as `T`
+///
+/// Messages without a data part are read as `null`.
+pub fn data_input<T: DeserializeOwned>(context: &RequestContext) -> Result<T, A2AError> {
+    let data = context
+        .message()
+        .and_then(|message| {
+            message.parts.iter().find_map(|part| match part.root() {
+                PartRoot::Data(data) => Some(data.data.clone()),
+                _ => None,
+            })
+        })
+        .unwrap_or(serde_json::Value::Null);
+    serde_json::from_value(data).map_err(|e| A2AError::invalid_params(&format!("Invalid skill input: {}", e)))
+}
+
+/// Runs one skill for the task of `context` and publishes its outcome
+///
+/// Publishes a working status, then either the output as an artifact named
+/// `skill_id` followed by a completed status, or a failed status carrying
+/// the error. Errors are returned after they are published.
+pub async fn run_skill<F, O>(
+    skill_id: &str,
+    context: &RequestContext,
+    event_queue: Arc<dyn EventQueue>,
+    skill: F,
+) -> Result<(), A2AError>
+where
+    F: Future<Output = Result<O, A2AError>>,
+    O: SkillOutput,
+{
+    let task_id = context.task_id.clone().unwrap_or_default();
+    let context_id = context.context_id.clone().unwrap_or_default();
+    let publish = |status: TaskStatus, r#final: bool| {
+        let event = TaskStatusUpdateEvent::new(task_id.clone(), context_id.clone(), status, r#final);
+        event_queue.enqueue_event(Event::TaskStatusUpdate(event))
+    };
+    publish(TaskStatus::new(TaskState::Working), false).await?;
+
+    match skill.await {
+        Ok(output) => {
+            let parts = output.into_parts();
+            if !parts.is_empty() {
+                let artifact = Artifact::new(parts).with_name(skill_id.to_string());
+                let update = TaskArtifactUpdateEvent::new(task_id.clone(), context_id.clone(), artifact);
+                event_queue.enqueue_event(Event::TaskArtifactUpdate(update)).await?;
+            }
+            publish(TaskStatus::new(TaskState::Completed), true).await
+        }
+        Err(e) => {
+            let message = Message::new(Role::Agent, vec![Part::text(e.to_string())])
+                .with_task_id(task_id.clone())
+                .with_context_id(context_id.clone());
+            publish(TaskStatus::new(TaskState::Failed).with_message(message), true).await?;
+            Err(e)
+        }
+    }
+}
+
+/// Publishes the canceled status of the task of `context`
+pub async fn cancel_task(context: &RequestContext, event_queue: Arc<dyn EventQueue>) -> Result<(), A2AError> {
+    let status = TaskStatusUpdateEvent::new(
+        context.task_id.clone().unwrap_or_default(),
+        context.context_id.clone().unwrap_or_default(),
+        TaskStatus::new(TaskState::Canceled),
+        true,
+    );
+    event_queue.enqueue_event(Event::TaskStatusUpdate(status)).await
+}
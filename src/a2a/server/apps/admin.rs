@@ -0,0 +1,725 @@
+//! GraphQL admin API over tasks and push notification configs
+//!
+//! `AdminApi` serves a GraphQL endpoint for internal dashboards, separate
+//! from the A2A protocol routes. Queries page through tasks filtered by state
+//! or context and inspect their history, artifacts and push configs, and
+//! report the usage reported for tasks per task, context or principal;
+//! mutations cancel a task through the request handler, so subscribers see
+//! the cancellation, or delete, restore and purge tasks directly in the
+//! store. Deleted tasks stay restorable until they are purged from the
+//! store. `dataExport` and `eraseData` answer the access and erasure
+//! requests of a principal or context with `DataSubjectRequests`. Every
+//! request must authenticate with one of the admin bearer tokens.
+//!
+//! The router is merged into the server's own:
+//!
+//! ```ignore
+//! let mut admin = AdminApi::new(handler.clone(), task_store.clone(), AdminAuth::bearer(["s3cret"]));
+//! if let Some(push_configs) = handler.push_config_store() {
+//!     admin = admin.with_push_config_store(push_configs);
+//! }
+//! let router = server.build_router().await.merge(admin.router());
+//! ```
+
+use crate::a2a::core_types::{parse_timestamp, TaskState};
+use crate::a2a::error::A2AError;
+use crate::a2a::models::*;
+use crate::a2a::server::events::EventStore;
+use crate::a2a::server::request_handlers::RequestHandler;
+use crate::a2a::server::tasks::{
+    DataSubject, DataSubjectRequests, ErasureReport, PushNotificationConfigStore, TaskStore, Usage, UsageRecord,
+};
+use crate::a2a::server::usage::UsageRecorder;
+use async_graphql::{Context, EmptySubscription, ErrorExtensions, InputObject, Object, Schema, SimpleObject};
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use std::sync::Arc;
+
+/// Default path of the admin endpoint
+pub const DEFAULT_ADMIN_PATH: &str = "/admin/graphql";
+
+/// Default and maximum number of tasks per page
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+pub const MAX_PAGE_SIZE: usize = 200;
+
+/// How admin requests authenticate
+#[derive(Clone)]
+pub struct AdminAuth {
+    tokens: Vec<String>,
+}
+
+impl AdminAuth {
+    /// Accepts `Authorization: Bearer <token>` with any of `tokens`
+    pub fn bearer<I, S>(tokens: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            tokens: tokens.into_iter().map(Into::into).filter(|token: &String| !token.is_empty()).collect(),
+        }
+    }
+
+    /// Whether the request headers carry an admin token
+    pub fn authorizes(&self, headers: &HeaderMap) -> bool {
+        let Some(presented) = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+        else {
+            return false;
+        };
+        self.tokens.iter().any(|token| constant_time_eq(token.as_bytes(), presented.trim().as_bytes()))
+    }
+}
+
+impl std::fmt::Debug for AdminAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdminAuth").field("tokens", &self.tokens.len()).finish()
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn state_name(state: &TaskState) -> String {
+    serde_json::to_value(state)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> async_graphql::Json<serde_json::Value> {
+    async_graphql::Json(serde_json::to_value(value).unwrap_or_default())
+}
+
+fn graphql_error(err: A2AError) -> async_graphql::Error {
+    async_graphql::Error::new(err.to_string())
+        .extend_with(|_, extensions| extensions.set("code", err.code()))
+}
+
+/// A task as seen by the admin API
+pub struct TaskNode(Task);
+
+#[Object(name = "Task")]
+impl TaskNode {
+    async fn id(&self) -> &str {
+        &self.0.id
+    }
+
+    async fn context_id(&self) -> &str {
+        &self.0.context_id
+    }
+
+    /// The lifecycle state, e.g. `working`
+    async fn state(&self) -> String {
+        state_name(&self.0.status.state)
+    }
+
+    /// When the current status was recorded, as RFC 3339
+    async fn updated_at(&self) -> Option<String> {
+        self.0.status.recorded_at().map(|at| at.to_rfc3339())
+    }
+
+    async fn status(&self) -> async_graphql::Json<serde_json::Value> {
+        to_json(&self.0.status)
+    }
+
+    /// The messages of the task, or only the last `last` of them
+    async fn history(&self, last: Option<usize>) -> Vec<async_graphql::Json<serde_json::Value>> {
+        let history = self.0.history.as_deref().unwrap_or_default();
+        let skip = last.map_or(0, |last| history.len().saturating_sub(last));
+        history[skip..].iter().map(to_json).collect()
+    }
+
+    async fn artifacts(&self) -> Vec<async_graphql::Json<serde_json::Value>> {
+        self.0.artifacts.iter().flatten().map(to_json).collect()
+    }
+
+    async fn metadata(&self) -> Option<async_graphql::Json<serde_json::Value>> {
+        self.0.metadata.as_ref().map(to_json)
+    }
+
+    async fn push_configs(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<async_graphql::Json<serde_json::Value>>> {
+        push_configs(ctx, &self.0.id).await
+    }
+}
+
+/// One page of tasks
+#[derive(SimpleObject)]
+pub struct TaskPage {
+    tasks: Vec<TaskNode>,
+    /// Number of tasks matching the filter across all pages
+    total_count: usize,
+    /// Cursor for the next page, absent on the last one
+    next_cursor: Option<String>,
+}
+
+/// Which tasks a query returns
+#[derive(InputObject, Default)]
+pub struct TaskFilter {
+    /// Only tasks in one of these states, e.g. `["working", "submitted"]`
+    states: Option<Vec<String>>,
+    context_id: Option<String>,
+}
+
+impl TaskFilter {
+    fn matches(&self, task: &Task) -> bool {
+        let state = state_name(&task.status.state);
+        self.states.as_ref().is_none_or(|states| states.contains(&state))
+            && self.context_id.as_ref().is_none_or(|context_id| *context_id == task.context_id)
+    }
+}
+
+/// Usage totals
+#[derive(SimpleObject)]
+pub struct UsageTotals {
+    input_tokens: u64,
+    output_tokens: u64,
+    total_tokens: u64,
+    compute_units: f64,
+}
+
+impl From<Usage> for UsageTotals {
+    fn from(usage: Usage) -> Self {
+        Self {
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            total_tokens: usage.total_tokens(),
+            compute_units: usage.compute_units,
+        }
+    }
+}
+
+/// The usage totals of a task
+#[derive(SimpleObject)]
+pub struct TaskUsage {
+    task_id: String,
+    context_id: String,
+    /// The principal the task is billed to
+    principal: String,
+    usage: UsageTotals,
+}
+
+impl From<UsageRecord> for TaskUsage {
+    fn from(record: UsageRecord) -> Self {
+        Self {
+            task_id: record.task_id,
+            context_id: record.context_id,
+            principal: record.principal,
+            usage: record.usage.into(),
+        }
+    }
+}
+
+/// The usage of all tasks billed to a principal
+#[derive(SimpleObject)]
+pub struct PrincipalUsage {
+    principal: String,
+    usage: UsageTotals,
+}
+
+/// What `eraseData` removed
+#[derive(SimpleObject)]
+pub struct Erasure {
+    /// Tasks replaced by their audit stubs
+    tasks: usize,
+    /// Push configs deleted
+    push_configs: usize,
+}
+
+impl From<ErasureReport> for Erasure {
+    fn from(report: ErasureReport) -> Self {
+        Self {
+            tasks: report.tasks,
+            push_configs: report.push_configs,
+        }
+    }
+}
+
+struct AdminState {
+    handler: Arc<dyn RequestHandler>,
+    task_store: Arc<dyn TaskStore>,
+    push_config_store: Option<Arc<dyn PushNotificationConfigStore>>,
+    event_store: Option<Arc<dyn EventStore>>,
+}
+
+async fn push_configs(ctx: &Context<'_>, task_id: &str) -> async_graphql::Result<Vec<async_graphql::Json<serde_json::Value>>> {
+    let state = ctx.data::<Arc<AdminState>>()?;
+    match &state.push_config_store {
+        Some(store) => Ok(store.get_info(task_id).await.map_err(graphql_error)?.iter().map(to_json).collect()),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// The requests over the stores of the admin API
+fn data_subject_requests(state: &AdminState) -> DataSubjectRequests {
+    let mut requests = DataSubjectRequests::new(state.task_store.clone());
+    if let Some(store) = &state.push_config_store {
+        requests = requests.with_push_config_store(store.clone());
+    }
+    if let Some(store) = &state.event_store {
+        requests = requests.with_event_store(store.clone());
+    }
+    requests
+}
+
+/// The subject named by exactly one of `principal` and `context_id`
+fn data_subject(principal: Option<String>, context_id: Option<String>) -> async_graphql::Result<DataSubject> {
+    match (principal, context_id) {
+        (Some(principal), None) => Ok(DataSubject::Principal(principal)),
+        (None, Some(context_id)) => Ok(DataSubject::Context(context_id)),
+        _ => Err(async_graphql::Error::new("Set exactly one of principal and contextId")),
+    }
+}
+
+/// Tasks most recently updated first
+async fn sorted_tasks(state: &AdminState, filter: &TaskFilter) -> Result<Vec<Task>, A2AError> {
+    let mut tasks: Vec<Task> = state.task_store.list().await?.into_iter().filter(|task| filter.matches(task)).collect();
+    tasks.sort_by(|a, b| b.status.cmp_recorded(&a.status).then_with(|| a.id.cmp(&b.id)));
+    Ok(tasks)
+}
+
+pub struct AdminQuery;
+
+#[Object]
+impl AdminQuery {
+    /// Pages through the tasks matching `filter`, most recently updated first
+    async fn tasks(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<TaskFilter>,
+        first: Option<usize>,
+        after: Option<String>,
+    ) -> async_graphql::Result<TaskPage> {
+        let state = ctx.data::<Arc<AdminState>>()?;
+        let tasks = sorted_tasks(state, &filter.unwrap_or_default()).await.map_err(graphql_error)?;
+        let offset = match after {
+            Some(cursor) => cursor.parse::<usize>().map_err(|_| async_graphql::Error::new("Invalid cursor"))?,
+            None => 0,
+        };
+        let first = first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+        let total_count = tasks.len();
+        let end = (offset + first).min(total_count);
+        Ok(TaskPage {
+            tasks: tasks.into_iter().skip(offset).take(first).map(TaskNode).collect(),
+            total_count,
+            next_cursor: (end < total_count).then(|| end.to_string()),
+        })
+    }
+
+    async fn task(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<Option<TaskNode>> {
+        let state = ctx.data::<Arc<AdminState>>()?;
+        Ok(state.task_store.get(&id).await.map_err(graphql_error)?.map(TaskNode))
+    }
+
+    async fn push_configs(&self, ctx: &Context<'_>, task_id: String) -> async_graphql::Result<Vec<async_graphql::Json<serde_json::Value>>> {
+        push_configs(ctx, &task_id).await
+    }
+
+    async fn task_usage(&self, ctx: &Context<'_>, task_id: String) -> async_graphql::Result<Option<TaskUsage>> {
+        let state = ctx.data::<Arc<AdminState>>()?;
+        Ok(state.task_store.get_usage(&task_id).await.map_err(graphql_error)?.map(TaskUsage::from))
+    }
+
+    /// Sums the usage of the tasks in `context_id` and billed to `principal`
+    async fn usage(
+        &self,
+        ctx: &Context<'_>,
+        context_id: Option<String>,
+        principal: Option<String>,
+    ) -> async_graphql::Result<UsageTotals> {
+        let state = ctx.data::<Arc<AdminState>>()?;
+        let records = state.task_store.list_usage().await.map_err(graphql_error)?;
+        let total: Usage = records
+            .into_iter()
+            .filter(|record| context_id.as_ref().is_none_or(|context_id| *context_id == record.context_id))
+            .filter(|record| principal.as_ref().is_none_or(|principal| *principal == record.principal))
+            .map(|record| record.usage)
+            .sum();
+        Ok(total.into())
+    }
+
+    /// Everything stored about a principal or context, as a `DataExport`
+    async fn data_export(
+        &self,
+        ctx: &Context<'_>,
+        principal: Option<String>,
+        context_id: Option<String>,
+    ) -> async_graphql::Result<async_graphql::Json<serde_json::Value>> {
+        let state = ctx.data::<Arc<AdminState>>()?;
+        let subject = data_subject(principal, context_id)?;
+        let export = data_subject_requests(state).export(&subject).await.map_err(graphql_error)?;
+        Ok(to_json(&export))
+    }
+
+    /// The usage of all tasks per principal, for billing
+    async fn usage_by_principal(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<PrincipalUsage>> {
+        let state = ctx.data::<Arc<AdminState>>()?;
+        let totals = UsageRecorder::new(state.task_store.clone())
+            .usage_by_principal()
+            .await
+            .map_err(graphql_error)?;
+        Ok(totals
+            .into_iter()
+            .map(|(principal, usage)| PrincipalUsage { principal, usage: usage.into() })
+            .collect())
+    }
+}
+
+pub struct AdminMutation;
+
+#[Object]
+impl AdminMutation {
+    /// Cancels a task through the request handler
+    async fn cancel_task(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<Option<TaskNode>> {
+        let state = ctx.data::<Arc<AdminState>>()?;
+        let task = state
+            .handler
+            .on_cancel_task(TaskIdParams::new(id), None)
+            .await
+            .map_err(graphql_error)?;
+        Ok(task.map(TaskNode))
+    }
+
+    /// Deletes a task and its push configs; returns whether it existed
+    async fn delete_task(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<bool> {
+        let state = ctx.data::<Arc<AdminState>>()?;
+        let existed = state.task_store.get(&id).await.map_err(graphql_error)?.is_some();
+        state.task_store.delete(&id).await.map_err(graphql_error)?;
+        if let Some(store) = &state.push_config_store {
+            store.delete_info(&id, None).await.map_err(graphql_error)?;
+        }
+        Ok(existed)
+    }
+
+    /// Deletes tasks in a terminal state, optionally only those last updated
+    /// before `older_than` (RFC 3339); returns how many were deleted
+    async fn purge_tasks(&self, ctx: &Context<'_>, older_than: Option<String>) -> async_graphql::Result<usize> {
+        let state = ctx.data::<Arc<AdminState>>()?;
+        let cutoff = older_than
+            .map(|value| parse_timestamp(&value).map_err(|e| async_graphql::Error::new(format!("Invalid olderThan: {}", e))))
+            .transpose()?;
+        let ids: Vec<String> = state
+            .task_store
+            .list()
+            .await
+            .map_err(graphql_error)?
+            .into_iter()
+            .filter(|task| task.status.state.is_terminal())
+            .filter(|task| cutoff.is_none_or(|cutoff| task.status.recorded_at().is_some_and(|at| at < cutoff)))
+            .map(|task| task.id)
+            .collect();
+        state.task_store.delete_many(&ids).await.map_err(graphql_error)?;
+        if let Some(store) = &state.push_config_store {
+            for id in &ids {
+                store.delete_info(id, None).await.map_err(graphql_error)?;
+            }
+        }
+        Ok(ids.len())
+    }
+
+    /// Replaces the tasks of a principal or context by audit stubs and
+    /// deletes their push configs
+    async fn erase_data(
+        &self,
+        ctx: &Context<'_>,
+        principal: Option<String>,
+        context_id: Option<String>,
+    ) -> async_graphql::Result<Erasure> {
+        let state = ctx.data::<Arc<AdminState>>()?;
+        let subject = data_subject(principal, context_id)?;
+        let report = data_subject_requests(state).erase(&subject).await.map_err(graphql_error)?;
+        Ok(report.into())
+    }
+
+    /// Restores a deleted task, without the push configs removed on
+    /// deletion; returns whether it was deleted
+    async fn restore_task(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<bool> {
+        let state = ctx.data::<Arc<AdminState>>()?;
+        match state.task_store.restore(&id).await {
+            Ok(()) => Ok(true),
+            Err(A2AError::TaskNotFound(_)) => Ok(false),
+            Err(e) => Err(graphql_error(e)),
+        }
+    }
+
+    /// Removes for good the tasks deleted before `older_than` (RFC 3339), or
+    /// all deleted tasks; returns how many were removed
+    async fn purge_deleted_tasks(&self, ctx: &Context<'_>, older_than: Option<String>) -> async_graphql::Result<u64> {
+        let state = ctx.data::<Arc<AdminState>>()?;
+        let cutoff = match older_than {
+            Some(value) => {
+                parse_timestamp(&value).map_err(|e| async_graphql::Error::new(format!("Invalid olderThan: {}", e)))?
+            }
+            None => chrono::Utc::now(),
+        };
+        state.task_store.purge_deleted(cutoff).await.map_err(graphql_error)
+    }
+}
+
+/// The admin GraphQL schema
+pub type AdminSchema = Schema<AdminQuery, AdminMutation, EmptySubscription>;
+
+/// GraphQL admin endpoint
+#[derive(Clone)]
+pub struct AdminApi {
+    schema: AdminSchema,
+    auth: AdminAuth,
+    path: String,
+}
+
+impl AdminApi {
+    /// Serves tasks from `task_store`, cancelling them through `handler`
+    pub fn new(handler: Arc<dyn RequestHandler>, task_store: Arc<dyn TaskStore>, auth: AdminAuth) -> Self {
+        Self::build(
+            AdminState {
+                handler,
+                task_store,
+                push_config_store: None,
+                event_store: None,
+            },
+            auth,
+        )
+    }
+
+    fn build(state: AdminState, auth: AdminAuth) -> Self {
+        Self {
+            schema: Schema::build(AdminQuery, AdminMutation, EmptySubscription)
+                .data(Arc::new(state))
+                .finish(),
+            auth,
+            path: DEFAULT_ADMIN_PATH.to_string(),
+        }
+    }
+
+    /// Also expose and clean up the push configs held by `store`
+    ///
+    /// Pass `DefaultRequestHandler::push_config_store`, so the changes made
+    /// here invalidate the configs the push sender cached.
+    pub fn with_push_config_store(self, store: Arc<dyn PushNotificationConfigStore>) -> Self {
+        let state = self.schema.data::<Arc<AdminState>>().expect("admin state is always set");
+        let state = AdminState {
+            handler: state.handler.clone(),
+            task_store: state.task_store.clone(),
+            push_config_store: Some(store),
+            event_store: state.event_store.clone(),
+        };
+        Self {
+            path: self.path,
+            ..Self::build(state, self.auth)
+        }
+    }
+
+    /// Also drop the events `store` retains for erased tasks
+    ///
+    /// Pass `DefaultRequestHandler::event_store`, so `eraseData` leaves no
+    /// replayable stream events behind.
+    pub fn with_event_store(self, store: Arc<dyn EventStore>) -> Self {
+        let state = self.schema.data::<Arc<AdminState>>().expect("admin state is always set");
+        let state = AdminState {
+            handler: state.handler.clone(),
+            task_store: state.task_store.clone(),
+            push_config_store: state.push_config_store.clone(),
+            event_store: Some(store),
+        };
+        Self {
+            path: self.path,
+            ..Self::build(state, self.auth)
+        }
+    }
+
+    /// Serve the endpoint at `path` instead of `DEFAULT_ADMIN_PATH`
+    pub fn with_path(mut self, path: &str) -> Self {
+        self.path = path.to_string();
+        self
+    }
+
+    /// The schema in GraphQL SDL
+    pub fn sdl(&self) -> String {
+        self.schema.sdl()
+    }
+
+    /// Executes a request without authentication, for embedding and tests
+    pub async fn execute(&self, request: impl Into<async_graphql::Request>) -> async_graphql::Response {
+        self.schema.execute(request).await
+    }
+
+    /// Router serving the endpoint, to be merged into the server's router
+    pub fn router(&self) -> Router {
+        Router::new().route(&self.path, post(handle_admin_request)).with_state(self.clone())
+    }
+}
+
+async fn handle_admin_request(
+    State(api): State<AdminApi>,
+    headers: HeaderMap,
+    Json(request): Json<async_graphql::Request>,
+) -> Response {
+    if !api.auth.authorizes(&headers) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            "Admin token required",
+        )
+            .into_response();
+    }
+    Json(api.execute(request).await).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::{Message, Part, Role, TaskStatus};
+    use crate::a2a::server::request_handlers::DefaultRequestHandler;
+    use crate::a2a::server::tasks::{InMemoryPushNotificationConfigStore, InMemoryTaskStore};
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn admin() -> (AdminApi, Arc<InMemoryTaskStore>) {
+        let store = Arc::new(InMemoryTaskStore::new());
+        for (id, state, timestamp) in [
+            ("a", TaskState::Working, "2026-01-01T10:00:00Z"),
+            ("b", TaskState::Completed, "2026-01-01T11:00:00Z"),
+            ("c", TaskState::Failed, "2026-01-03T09:00:00Z"),
+        ] {
+            let task = Task::new("ctx".to_string(), TaskStatus::new(state).with_timestamp(timestamp.to_string()))
+                .with_task_id(id.to_string())
+                .with_history(vec![
+                    Message::new(Role::User, vec![Part::text("one".to_string())]),
+                    Message::new(Role::Agent, vec![Part::text("two".to_string())]),
+                ]);
+            store.save(task).await.unwrap();
+        }
+        let push_configs = Arc::new(InMemoryPushNotificationConfigStore::new());
+        let handler = Arc::new(DefaultRequestHandler::new(store.clone(), Some(push_configs.clone()), None));
+        let api = AdminApi::new(handler, store.clone(), AdminAuth::bearer(["s3cret"])).with_push_config_store(push_configs);
+        (api, store)
+    }
+
+    async fn query(api: &AdminApi, query: &str) -> serde_json::Value {
+        let response = api.execute(query).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        response.data.into_json().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_tasks_are_filtered_and_paginated() {
+        let (api, _) = admin().await;
+        let data = query(&api, r#"{ tasks(first: 2) { totalCount nextCursor tasks { id state } } }"#).await;
+        assert_eq!(data["tasks"]["totalCount"], 3);
+        assert_eq!(data["tasks"]["tasks"][0]["id"], "c");
+        assert_eq!(data["tasks"]["tasks"][1]["id"], "b");
+        let cursor = data["tasks"]["nextCursor"].as_str().unwrap().to_string();
+
+        let data = query(&api, &format!(r#"{{ tasks(first: 2, after: "{}") {{ nextCursor tasks {{ id }} }} }}"#, cursor)).await;
+        assert_eq!(data["tasks"]["tasks"][0]["id"], "a");
+        assert!(data["tasks"]["nextCursor"].is_null());
+
+        let data = query(&api, r#"{ tasks(filter: { states: ["working"] }) { tasks { id history(last: 1) } } }"#).await;
+        assert_eq!(data["tasks"]["tasks"].as_array().unwrap().len(), 1);
+        assert_eq!(data["tasks"]["tasks"][0]["history"][0]["role"], "agent");
+    }
+
+    #[tokio::test]
+    async fn test_mutations_cancel_delete_and_purge() {
+        let (api, store) = admin().await;
+        let data = query(&api, r#"mutation { cancelTask(id: "a") { state } }"#).await;
+        assert_eq!(data["cancelTask"]["state"], "canceled");
+
+        // The cancellation of a was recorded now, after the cutoff
+        let data = query(&api, r#"mutation { purgeTasks(olderThan: "2026-01-02T00:00:00Z") }"#).await;
+        assert_eq!(data["purgeTasks"], 1);
+        assert!(store.get("b").await.unwrap().is_none());
+        assert!(store.get("c").await.unwrap().is_some());
+
+        let data = query(&api, r#"mutation { deleteTask(id: "c") }"#).await;
+        assert_eq!(data["deleteTask"], true);
+        let remaining: Vec<String> = store.list().await.unwrap().into_iter().map(|task| task.id).collect();
+        assert_eq!(remaining, vec!["a"]);
+    }
+
+    #[tokio::test]
+    async fn test_mutations_restore_and_purge_deleted() {
+        let (api, store) = admin().await;
+        query(&api, r#"mutation { deleteTask(id: "b") }"#).await;
+        query(&api, r#"mutation { deleteTask(id: "c") }"#).await;
+
+        let data = query(&api, r#"mutation { restoreTask(id: "b") restoreAgain: restoreTask(id: "a") }"#).await;
+        assert_eq!(data["restoreTask"], true);
+        assert_eq!(data["restoreAgain"], false);
+        assert!(store.get("b").await.unwrap().is_some());
+
+        let data = query(&api, r#"mutation { purgeDeletedTasks(olderThan: "2000-01-01T00:00:00Z") }"#).await;
+        assert_eq!(data["purgeDeletedTasks"], 0);
+        let data = query(&api, r#"mutation { purgeDeletedTasks }"#).await;
+        assert_eq!(data["purgeDeletedTasks"], 1);
+        assert!(store.list_deleted().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_data_of_a_principal_is_exported_and_erased() {
+        let (api, store) = admin().await;
+        store.record_usage("a", "alice", Usage::tokens(1, 1)).await.unwrap();
+        store.record_usage("b", "bob", Usage::tokens(1, 1)).await.unwrap();
+
+        let data = query(&api, r#"{ dataExport(principal: "alice") }"#).await;
+        let export = &data["dataExport"];
+        assert_eq!(export["tasks"].as_array().unwrap().len(), 1);
+        assert_eq!(export["tasks"][0]["history"][0]["parts"][0]["text"], "one");
+        assert_eq!(export["usage"][0]["principal"], "alice");
+
+        let data = query(&api, r#"mutation { eraseData(principal: "alice") { tasks pushConfigs } }"#).await;
+        assert_eq!(data["eraseData"]["tasks"], 1);
+        assert!(store.get("a").await.unwrap().unwrap().history.is_none());
+        assert!(store.get("b").await.unwrap().unwrap().history.is_some());
+
+        let response = api.execute(r#"{ dataExport(principal: "alice", contextId: "ctx") }"#).await;
+        assert!(!response.errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_usage_is_reported_per_task_and_principal() {
+        let (api, store) = admin().await;
+        store.record_usage("a", "alice", Usage::tokens(10, 5)).await.unwrap();
+        store.record_usage("b", "bob", Usage::tokens(1, 1).with_compute_units(2.5)).await.unwrap();
+        store.record_usage("a", "bob", Usage::tokens(2, 0)).await.unwrap();
+
+        let data = query(&api, r#"{ taskUsage(taskId: "a") { principal usage { inputTokens totalTokens } } }"#).await;
+        assert_eq!(data["taskUsage"]["principal"], "alice");
+        assert_eq!(data["taskUsage"]["usage"]["inputTokens"], 12);
+        assert_eq!(data["taskUsage"]["usage"]["totalTokens"], 17);
+
+        let data = query(&api, r#"{ usage(contextId: "ctx") { totalTokens computeUnits } usageByPrincipal { principal usage { totalTokens } } }"#).await;
+        assert_eq!(data["usage"]["totalTokens"], 19);
+        assert_eq!(data["usage"]["computeUnits"], 2.5);
+        assert_eq!(data["usageByPrincipal"][0]["principal"], "alice");
+        assert_eq!(data["usageByPrincipal"][1]["usage"]["totalTokens"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_requires_admin_token() {
+        let (api, _) = admin().await;
+        let request = |token: Option<&str>| {
+            let mut request = Request::post(DEFAULT_ADMIN_PATH).header(header::CONTENT_TYPE, "application/json");
+            if let Some(token) = token {
+                request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+            }
+            request.body(Body::from(r#"{"query":"{ task(id: \"a\") { id } }"}"#)).unwrap()
+        };
+
+        let response = api.router().oneshot(request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = api.router().oneshot(request(Some("wrong"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = api.router().oneshot(request(Some("s3cret"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,169 @@
+//! Embedded web dashboard for inspecting tasks
+//!
+//! `Dashboard` serves a single page at `DASHBOARD_PATH` listing the tasks of a
+//! store, rendering the message history and artifacts of the selected one and
+//! following its status live over SSE. Tasks can be cancelled from the page.
+//! The page and its JSON endpoints are unauthenticated and meant for local
+//! demos and debugging, not for exposure to untrusted networks.
+//!
+//! ```ignore
+//! let dashboard = Dashboard::new(handler.clone(), task_store.clone());
+//! let router = server.build_router().await.merge(dashboard.router());
+//! ```
+
+use crate::a2a::models::*;
+use crate::a2a::server::request_handlers::{Event, RequestHandler};
+use crate::a2a::server::tasks::TaskStore;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{
+        sse::{Event as SseEvent, KeepAlive, Sse},
+        Html, IntoResponse, Response,
+    },
+    routing::{get, post},
+    Json, Router,
+};
+use futures::StreamExt;
+use std::sync::Arc;
+
+/// Where the dashboard is served
+pub const DASHBOARD_PATH: &str = "/_a2a/dashboard";
+
+const DASHBOARD_HTML: &str = include_str!("dashboard.html");
+
+/// Web dashboard over a task store
+#[derive(Clone)]
+pub struct Dashboard {
+    handler: Arc<dyn RequestHandler>,
+    task_store: Arc<dyn TaskStore>,
+}
+
+impl Dashboard {
+    /// Lists tasks from `task_store`; live updates and cancellation go
+    /// through `handler`
+    pub fn new(handler: Arc<dyn RequestHandler>, task_store: Arc<dyn TaskStore>) -> Self {
+        Self { handler, task_store }
+    }
+
+    /// Router serving the page and its API, to be merged into the server's
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route(DASHBOARD_PATH, get(page))
+            .route(&format!("{}/api/tasks", DASHBOARD_PATH), get(list_tasks))
+            .route(&format!("{}/api/tasks/:id", DASHBOARD_PATH), get(get_task))
+            .route(&format!("{}/api/tasks/:id/cancel", DASHBOARD_PATH), post(cancel_task))
+            .route(&format!("{}/api/tasks/:id/events", DASHBOARD_PATH), get(task_events))
+            .with_state(self.clone())
+    }
+}
+
+fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, message.into()).into_response()
+}
+
+async fn page() -> Html<&'static str> {
+    Html(DASHBOARD_HTML)
+}
+
+/// Lists tasks, most recently updated first
+async fn list_tasks(State(dashboard): State<Dashboard>) -> Response {
+    match dashboard.task_store.list().await {
+        Ok(mut tasks) => {
+            tasks.sort_by(|a, b| b.status.cmp_recorded(&a.status));
+            Json(tasks).into_response()
+        }
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn get_task(State(dashboard): State<Dashboard>, Path(id): Path<String>) -> Response {
+    match dashboard.task_store.get(&id).await {
+        Ok(Some(task)) => Json(task).into_response(),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, format!("Task {} not found", id)),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+async fn cancel_task(State(dashboard): State<Dashboard>, Path(id): Path<String>) -> Response {
+    match dashboard.handler.on_cancel_task(TaskIdParams::new(id.clone()), None).await {
+        Ok(Some(task)) => Json(task).into_response(),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, format!("Task {} not found", id)),
+        Err(e) => error_response(StatusCode::CONFLICT, e.to_string()),
+    }
+}
+
+/// Streams the events of a task, starting with its current state
+async fn task_events(State(dashboard): State<Dashboard>, Path(id): Path<String>) -> Response {
+    match dashboard.handler.on_resubscribe_to_task(TaskIdParams::new(id), None).await {
+        Ok(events) => {
+            let events = events.map(|event| {
+                let data = match event {
+                    Ok(Event::Task(task)) => serde_json::to_string(&task),
+                    Ok(Event::Message(message)) => serde_json::to_string(&message),
+                    Ok(Event::TaskStatusUpdate(update)) => serde_json::to_string(&update),
+                    Ok(Event::TaskArtifactUpdate(update)) => serde_json::to_string(&update),
+                    Err(e) => Ok(serde_json::json!({ "error": e.to_string() }).to_string()),
+                };
+                Ok::<_, std::convert::Infallible>(SseEvent::default().data(data.unwrap_or_default()))
+            });
+            Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+        }
+        Err(e) => error_response(StatusCode::NOT_FOUND, e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::{TaskState, TaskStatus};
+    use crate::a2a::server::request_handlers::DefaultRequestHandler;
+    use crate::a2a::server::tasks::InMemoryTaskStore;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn dashboard() -> Dashboard {
+        let store = Arc::new(InMemoryTaskStore::new());
+        let task = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id("t1".to_string());
+        store.save(task).await.unwrap();
+        Dashboard::new(Arc::new(DefaultRequestHandler::new(store.clone(), None, None)), store)
+    }
+
+    async fn call(dashboard: &Dashboard, method: &str, path: &str) -> (StatusCode, String) {
+        let request = Request::builder().method(method).uri(path).body(Body::empty()).unwrap();
+        let response = dashboard.router().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_page_and_task_endpoints() {
+        let dashboard = dashboard().await;
+        let (status, body) = call(&dashboard, "GET", DASHBOARD_PATH).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("<title>A2A dashboard</title>"));
+
+        let (_, body) = call(&dashboard, "GET", "/_a2a/dashboard/api/tasks").await;
+        let tasks: Vec<Task> = serde_json::from_str(&body).unwrap();
+        assert_eq!(tasks[0].id, "t1");
+
+        let (status, _) = call(&dashboard, "GET", "/_a2a/dashboard/api/tasks/missing").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_events() {
+        let dashboard = dashboard().await;
+        let (status, body) = call(&dashboard, "POST", "/_a2a/dashboard/api/tasks/t1/cancel").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(serde_json::from_str::<Task>(&body).unwrap().status.state, TaskState::Canceled);
+
+        // A terminal task's stream carries its current state and ends
+        let (status, body) = call(&dashboard, "GET", "/_a2a/dashboard/api/tasks/t1/events").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.starts_with("data: "));
+        assert!(body.contains("\"canceled\""));
+    }
+}
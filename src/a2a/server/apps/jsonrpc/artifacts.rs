@@ -0,0 +1,140 @@
+//! The endpoint streaming stored artifact bytes
+//!
+//! `GET {artifacts_path}/{task_id}/{artifact_id}` answers with the bytes an
+//! `ArtifactLinkingRequestHandler` moved into the artifact store. A single
+//! byte range in a `Range` header is answered with `206 Partial Content`,
+//! anything else with the whole artifact.
+//!
+//! The caller is authenticated by the server's context builder. Agents whose
+//! card declares security requirements refuse unauthenticated callers, and
+//! an artifact is only served to callers the request handler shows its task
+//! to, so tenancy rules of `tasks/get` apply here too.
+
+use super::{build_call_context, ServerState};
+use crate::a2a::models::TaskQueryParams;
+use crate::a2a::server::artifacts::{parse_byte_range, ArtifactStore, StoredArtifact};
+use crate::a2a::server::context::ServerCallContext;
+use crate::a2a::server::request_handlers::RequestHandler;
+use axum::body::{Body, Bytes};
+use axum::extract::{Path as UrlPath, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use futures::StreamExt;
+use std::sync::Arc;
+use tracing::error;
+
+/// The artifact store served and the handler authorizing access to it
+#[derive(Clone)]
+pub(super) struct ArtifactEndpoint {
+    pub store: Arc<dyn ArtifactStore>,
+    pub handler: Arc<dyn RequestHandler>,
+}
+
+/// HTTP handler streaming the bytes of a stored artifact
+pub(super) async fn get_artifact(
+    State(state): State<ServerState>,
+    UrlPath((task_id, artifact_id)): UrlPath<(String, String)>,
+    headers: HeaderMap,
+) -> Response {
+    let Some(endpoint) = state.artifacts.clone() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let context = match authenticated_context(&state, &headers).await {
+        Ok(context) => context,
+        Err(response) => return response,
+    };
+
+    let query = TaskQueryParams::new(task_id.clone()).with_history_length(0);
+    let visible = match endpoint.handler.on_get_task(query, Some(&context)).await {
+        Ok(Some(task)) => task
+            .artifacts
+            .iter()
+            .flatten()
+            .any(|artifact| artifact.artifact_id == artifact_id),
+        Ok(None) => false,
+        Err(e) => {
+            error!("Failed to authorize artifact {} of task {}: {}", artifact_id, task_id, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    if !visible {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let stored = match endpoint.store.stat(&task_id, &artifact_id).await {
+        Ok(Some(stored)) => stored,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            error!("Failed to look up artifact {} of task {}: {}", artifact_id, task_id, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    stored_response(endpoint.store.as_ref(), &task_id, &artifact_id, stored, &headers).await
+}
+
+/// Builds the call context of a request to a file endpoint
+///
+/// Agents whose card declares security requirements answer unauthenticated
+/// callers with `401 Unauthorized`.
+pub(super) async fn authenticated_context(state: &ServerState, headers: &HeaderMap) -> Result<ServerCallContext, Response> {
+    let context = build_call_context(state, headers).await;
+    let secured = state.cards.current().security.as_ref().is_some_and(|security| !security.is_empty());
+    if secured && context.user.username().is_empty() {
+        return Err(StatusCode::UNAUTHORIZED.into_response());
+    }
+    Ok(context)
+}
+
+/// Streams stored bytes, honouring a single byte range in `headers`
+pub(super) async fn stored_response(
+    store: &dyn ArtifactStore,
+    task_id: &str,
+    artifact_id: &str,
+    stored: StoredArtifact,
+    headers: &HeaderMap,
+) -> Response {
+    let range = match headers.get(header::RANGE).and_then(|value| value.to_str().ok()) {
+        Some(value) => parse_byte_range(value, stored.size),
+        None => Ok(None),
+    };
+    let (status, range) = match range {
+        Ok(Some(range)) => (StatusCode::PARTIAL_CONTENT, range),
+        Ok(None) => (StatusCode::OK, 0..stored.size),
+        Err(()) => {
+            let mut response = StatusCode::RANGE_NOT_SATISFIABLE.into_response();
+            if let Ok(value) = HeaderValue::from_str(&format!("bytes */{}", stored.size)) {
+                response.headers_mut().insert(header::CONTENT_RANGE, value);
+            }
+            return response;
+        }
+    };
+
+    let chunks = match store.read(task_id, artifact_id, range.clone()).await {
+        Ok(chunks) => chunks,
+        Err(e) => {
+            error!("Failed to read artifact {} of task {}: {}", artifact_id, task_id, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let mut response = Response::new(Body::from_stream(chunks.map(|chunk| chunk.map(Bytes::from))));
+    *response.status_mut() = status;
+    let response_headers = response.headers_mut();
+    response_headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    response_headers.insert(header::CONTENT_LENGTH, HeaderValue::from(range.end - range.start));
+    let content_type = stored
+        .mime_type
+        .as_deref()
+        .and_then(|mime_type| HeaderValue::from_str(mime_type).ok())
+        .unwrap_or_else(|| HeaderValue::from_static("application/octet-stream"));
+    response_headers.insert(header::CONTENT_TYPE, content_type);
+    if status == StatusCode::PARTIAL_CONTENT {
+        let content_range = format!("bytes {}-{}/{}", range.start, range.end - 1, stored.size);
+        if let Ok(value) = HeaderValue::from_str(&content_range) {
+            response_headers.insert(header::CONTENT_RANGE, value);
+        }
+    }
+    response
+}
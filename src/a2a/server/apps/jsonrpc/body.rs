@@ -0,0 +1,338 @@
+//! Incremental reading of JSON-RPC request bodies
+//!
+//! Bodies are scanned chunk by chunk while they are received instead of being
+//! buffered first. The scan extracts the top-level `method` and `id` as soon as
+//! they arrive, so a request for an unknown method is refused before the rest
+//! of the body is read, and enforces the body and string size limits on the
+//! bytes seen so far. Because inline file and text parts are single JSON
+//! strings, the string limit bounds parts without deserializing the message.
+
+use crate::a2a::jsonrpc::{error_codes, standard_error_codes, JSONRPCError};
+use axum::body::{Body, Bytes};
+use futures::StreamExt;
+use serde_json::Value;
+
+/// Longest top-level key or `method`/`id` value that is captured
+///
+/// Buffers keep one byte more, so a longer value is known to be truncated
+/// and is ignored instead of being read as its prefix.
+const MAX_CAPTURE_BYTES: usize = 256;
+
+/// Size limits applied while a body is read
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct BodyLimits {
+    /// Maximum number of body bytes
+    pub max_body_bytes: Option<usize>,
+    /// Maximum length of a single JSON string
+    pub max_string_bytes: Option<usize>,
+}
+
+/// Position within the top-level object
+#[derive(Debug)]
+enum Top {
+    Key,
+    KeyString(Vec<u8>),
+    Colon(String),
+    Value(String),
+    ValueString(String, Vec<u8>),
+    ValueScalar(String, Vec<u8>),
+    Nested,
+    AfterValue,
+}
+
+/// Byte-level scanner of a JSON-RPC request object
+#[derive(Debug)]
+pub(crate) struct RequestScanner<'a> {
+    known_methods: &'a [&'a str],
+    max_string_bytes: Option<usize>,
+    started: bool,
+    // Bodies that are not a single object (e.g. batches) are only size checked
+    opaque: bool,
+    depth: usize,
+    in_string: bool,
+    escape: bool,
+    string_len: usize,
+    top: Top,
+    method: Option<String>,
+    id: Option<Value>,
+}
+
+impl<'a> RequestScanner<'a> {
+    pub(crate) fn new(known_methods: &'a [&'a str], max_string_bytes: Option<usize>) -> Self {
+        Self {
+            known_methods,
+            max_string_bytes,
+            started: false,
+            opaque: false,
+            depth: 0,
+            in_string: false,
+            escape: false,
+            string_len: 0,
+            top: Top::Key,
+            method: None,
+            id: None,
+        }
+    }
+
+    /// The request id, once seen
+    pub(crate) fn id(&self) -> Option<Value> {
+        self.id.clone()
+    }
+
+    /// The method, once seen
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) fn method(&self) -> Option<&str> {
+        self.method.as_deref()
+    }
+
+    pub(crate) fn feed(&mut self, chunk: &[u8]) -> Result<(), JSONRPCError> {
+        for &byte in chunk {
+            self.step(byte)?;
+        }
+        Ok(())
+    }
+
+    fn step(&mut self, byte: u8) -> Result<(), JSONRPCError> {
+        if self.in_string {
+            if self.escape {
+                self.escape = false;
+            } else if byte == b'\\' {
+                self.escape = true;
+            } else if byte == b'"' {
+                self.in_string = false;
+                self.end_string();
+                return self.check_method();
+            }
+            self.string_len += 1;
+            if let Some(limit) = self.max_string_bytes {
+                if self.string_len > limit {
+                    return Err(JSONRPCError::new(
+                        error_codes::CONTENT_REJECTED,
+                        format!("Request contains a value larger than {} bytes", limit),
+                    )
+                    .with_data(serde_json::json!({ "limit": limit })));
+                }
+            }
+            if let Top::KeyString(buffer) | Top::ValueString(_, buffer) = &mut self.top {
+                capture(buffer, byte);
+            }
+            return Ok(());
+        }
+
+        if !self.started {
+            if byte.is_ascii_whitespace() {
+                return Ok(());
+            }
+            self.started = true;
+            self.opaque = byte != b'{';
+        }
+
+        match byte {
+            b'"' => {
+                self.in_string = true;
+                self.string_len = 0;
+                if self.depth == 1 && !self.opaque {
+                    self.top = match std::mem::replace(&mut self.top, Top::Nested) {
+                        Top::Key => Top::KeyString(Vec::new()),
+                        Top::Value(key) => Top::ValueString(key, Vec::new()),
+                        other => other,
+                    };
+                }
+            }
+            b'{' | b'[' => {
+                self.depth += 1;
+                if self.depth == 2 && matches!(self.top, Top::Value(_)) {
+                    self.top = Top::Nested;
+                }
+            }
+            b'}' | b']' => {
+                if self.depth == 1 {
+                    self.end_scalar();
+                }
+                self.depth = self.depth.saturating_sub(1);
+                if self.depth == 1 {
+                    self.top = Top::AfterValue;
+                }
+            }
+            b':' if self.depth == 1 => {
+                if let Top::Colon(key) = std::mem::replace(&mut self.top, Top::Nested) {
+                    self.top = Top::Value(key);
+                }
+            }
+            b',' if self.depth == 1 => {
+                self.end_scalar();
+                self.top = Top::Key;
+            }
+            _ if byte.is_ascii_whitespace() => {}
+            _ if self.depth == 1 => match &mut self.top {
+                Top::Value(key) => self.top = Top::ValueScalar(std::mem::take(key), vec![byte]),
+                Top::ValueScalar(_, buffer) => capture(buffer, byte),
+                _ => {}
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn end_string(&mut self) {
+        if self.depth != 1 || self.opaque {
+            return;
+        }
+        match std::mem::replace(&mut self.top, Top::AfterValue) {
+            // A key that cannot be decoded matches neither `method` nor `id`
+            Top::KeyString(key) => self.top = Top::Colon(decode_string(&key).unwrap_or_default()),
+            Top::ValueString(key, value) => {
+                let Some(value) = decode_string(&value) else {
+                    return;
+                };
+                match key.as_str() {
+                    "method" => self.method = Some(value),
+                    "id" => self.id = Some(Value::String(value)),
+                    _ => {}
+                }
+            }
+            other => self.top = other,
+        }
+    }
+
+    fn end_scalar(&mut self) {
+        if let Top::ValueScalar(key, value) = std::mem::replace(&mut self.top, Top::AfterValue) {
+            if key == "id" && value.len() <= MAX_CAPTURE_BYTES {
+                self.id = serde_json::from_slice(&value).ok();
+            }
+        }
+    }
+
+    fn check_method(&self) -> Result<(), JSONRPCError> {
+        match &self.method {
+            Some(method) if !self.known_methods.is_empty() && !self.known_methods.contains(&method.as_str()) => Err(
+                JSONRPCError::new(standard_error_codes::METHOD_NOT_FOUND, format!("Method '{}' not found", method)),
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Appends a byte to a capture buffer unless it is already truncated
+fn capture(buffer: &mut Vec<u8>, byte: u8) {
+    if buffer.len() <= MAX_CAPTURE_BYTES {
+        buffer.push(byte);
+    }
+}
+
+/// Decodes the raw bytes between the quotes of a captured JSON string
+///
+/// Returns `None` for truncated or invalid strings.
+fn decode_string(raw: &[u8]) -> Option<String> {
+    if raw.len() > MAX_CAPTURE_BYTES {
+        return None;
+    }
+    let mut quoted = Vec::with_capacity(raw.len() + 2);
+    quoted.push(b'"');
+    quoted.extend_from_slice(raw);
+    quoted.push(b'"');
+    serde_json::from_slice(&quoted).ok()
+}
+
+/// Reads a request body, aborting as soon as it violates a limit
+///
+/// On failure, returns the request id if it was already seen together with
+/// the error to send.
+pub(crate) async fn read_body(
+    body: Body,
+    limits: BodyLimits,
+    known_methods: &[&str],
+) -> Result<Bytes, (Option<Value>, JSONRPCError)> {
+    let mut scanner = RequestScanner::new(known_methods, limits.max_string_bytes);
+    let mut buffer = Vec::new();
+    let mut stream = body.into_data_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| {
+            tracing::error!("Failed to read request body: {}", e);
+            (
+                scanner.id(),
+                JSONRPCError::new(standard_error_codes::INVALID_REQUEST, "Failed to read request body".to_string()),
+            )
+        })?;
+        if limits.max_body_bytes.is_some_and(|max| buffer.len() + chunk.len() > max) {
+            return Err((
+                scanner.id(),
+                JSONRPCError::new(standard_error_codes::INVALID_REQUEST, "Payload too large".to_string()),
+            ));
+        }
+        scanner.feed(&chunk).map_err(|e| (scanner.id(), e))?;
+        buffer.extend_from_slice(&chunk);
+    }
+    Ok(Bytes::from(buffer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METHODS: &[&str] = &["message/send", "tasks/get"];
+
+    #[test]
+    fn test_extracts_method_and_id_across_chunks() {
+        let body = br#"{"jsonrpc": "2.0", "id": 42, "params": {"method": "nested", "id": "x"}, "method": "tasks/get"}"#;
+        let mut scanner = RequestScanner::new(METHODS, None);
+        for chunk in body.chunks(3) {
+            scanner.feed(chunk).unwrap();
+        }
+        assert_eq!(scanner.method(), Some("tasks/get"));
+        assert_eq!(scanner.id(), Some(serde_json::json!(42)));
+    }
+
+    #[test]
+    fn test_unknown_method_fails_before_params() {
+        let mut scanner = RequestScanner::new(METHODS, None);
+        let err = scanner
+            .feed(br#"{"jsonrpc":"2.0","id":"a","method":"bogus","params":{"#)
+            .unwrap_err();
+        assert_eq!(err.code, standard_error_codes::METHOD_NOT_FOUND);
+        assert_eq!(scanner.id(), Some(serde_json::json!("a")));
+    }
+
+    #[test]
+    fn test_decodes_escaped_keys_and_values() {
+        let mut scanner = RequestScanner::new(METHODS, None);
+        scanner
+            .feed(br#"{"jsonrpc":"2.0","\u0069d":"a\"b","method":"tasks\/g\u0065t","params":{}}"#)
+            .unwrap();
+        assert_eq!(scanner.method(), Some("tasks/get"));
+        assert_eq!(scanner.id(), Some(serde_json::json!("a\"b")));
+    }
+
+    #[test]
+    fn test_truncated_method_is_not_checked_early() {
+        let method = "x".repeat(MAX_CAPTURE_BYTES + 10);
+        let body = format!(r#"{{"jsonrpc":"2.0","id":1,"method":"{}","params":{{}}}}"#, method);
+        let mut scanner = RequestScanner::new(METHODS, None);
+        scanner.feed(body.as_bytes()).unwrap();
+        assert_eq!(scanner.method(), None);
+        assert_eq!(scanner.id(), Some(serde_json::json!(1)));
+    }
+
+    #[test]
+    fn test_string_limit_counts_escaped_strings() {
+        let mut scanner = RequestScanner::new(METHODS, Some(16));
+        scanner.feed(br#"{"method":"tasks/get","params":{"text":"a\"b\"c","#).unwrap();
+        let err = scanner.feed(br#""bytes":"0123456789abcdefXYZ"}}"#).unwrap_err();
+        assert_eq!(err.code, error_codes::CONTENT_REJECTED);
+    }
+
+    #[tokio::test]
+    async fn test_read_body_enforces_limits() {
+        let limits = BodyLimits {
+            max_body_bytes: Some(16),
+            max_string_bytes: None,
+        };
+        let ok = read_body(Body::from("[1, 2]"), limits, METHODS).await.unwrap();
+        assert_eq!(&ok[..], b"[1, 2]");
+
+        let (_, err) = read_body(Body::from(r#"{"method":"tasks/get","params":{}}"#), limits, METHODS)
+            .await
+            .unwrap_err();
+        assert_eq!(err.message, "Payload too large");
+    }
+}
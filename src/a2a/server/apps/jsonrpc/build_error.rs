@@ -0,0 +1,72 @@
+//! Errors of `A2AServerBuilder::build` and `A2AServerBuilder::validate`
+
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// A missing or conflicting piece of a server configuration
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BuildError {
+    #[error("Agent card is required")]
+    MissingAgentCard,
+
+    #[error("Request handler is required")]
+    MissingRequestHandler,
+
+    #[error("Context builder is required")]
+    MissingContextBuilder,
+
+    #[error("Budget limits require a usage recorder")]
+    BudgetWithoutUsageRecorder,
+
+    /// An extended card is served but the public card does not announce it
+    #[error("An extended agent card is set but the agent card does not declare supportsAuthenticatedExtendedCard")]
+    ExtendedCardNotDeclared,
+
+    #[error("TLS certificate file '{}' does not exist", .0.display())]
+    TlsCertificateMissing(PathBuf),
+
+    #[error("TLS private key file '{}' does not exist", .0.display())]
+    TlsKeyMissing(PathBuf),
+
+    #[error("Agent card name must not be empty")]
+    EmptyCardName,
+
+    #[error("Agent card version must not be empty")]
+    EmptyCardVersion,
+
+    #[error("Agent card url '{url}' is invalid: {reason}")]
+    InvalidCardUrl { url: String, reason: String },
+
+    #[error("Agent card must declare default input and output modes")]
+    MissingDefaultModes,
+
+    #[error("Agent card declares skill '{0}' more than once")]
+    DuplicateSkill(String),
+
+    /// The artifact endpoint URL cannot be derived from the card url and the artifacts path
+    #[error("Artifact links cannot be built: {0}")]
+    InvalidArtifactsUrl(String),
+
+    /// Several of the problems above, in the order they were found
+    #[error("{}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+    Multiple(Vec<BuildError>),
+}
+
+impl BuildError {
+    /// A single error for `problems`, if there are any
+    pub(super) fn from_problems(mut problems: Vec<BuildError>) -> Option<Self> {
+        match problems.len() {
+            0 => None,
+            1 => problems.pop(),
+            _ => Some(BuildError::Multiple(problems)),
+        }
+    }
+
+    /// The individual problems, flattening `Multiple`
+    pub fn problems(&self) -> Vec<&BuildError> {
+        match self {
+            BuildError::Multiple(problems) => problems.iter().collect(),
+            problem => vec![problem],
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Heartbeats and stale stream reaping for SSE responses
+//!
+//! An idle event stream sends an SSE comment every heartbeat interval, so
+//! proxies keep the connection open and writes to a vanished client fail.
+//! The HTTP stack only polls a response body while it can write to the
+//! connection; a stream that goes unpolled for longer than the stale timeout
+//! belongs to a client that stopped reading. Its event stream is dropped,
+//! releasing the queue taps and bus subscriptions behind it, without waiting
+//! for the TCP connection to time out.
+
+use crate::a2a::server::events::MetricsSubscriber;
+use axum::body::Bytes;
+use futures::stream::{BoxStream, Stream};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+
+/// The comment sent as a heartbeat
+const HEARTBEAT: &[u8] = b": heartbeat\n\n";
+
+type BodyStream = BoxStream<'static, Result<Bytes, axum::Error>>;
+
+/// Heartbeat and reaping configuration of an SSE response
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct HeartbeatSettings {
+    /// Interval between heartbeats of an idle stream
+    pub interval: Option<Duration>,
+    /// How long a stream may go unpolled before it is reaped
+    ///
+    /// Only applies with heartbeats, which make a healthy client poll at
+    /// least once per interval, and is raised to twice the interval.
+    pub stale_after: Option<Duration>,
+}
+
+/// Counts an open stream in the metrics until it is dropped
+struct StreamGauge(Arc<MetricsSubscriber>);
+
+impl StreamGauge {
+    fn new(metrics: Arc<MetricsSubscriber>) -> Self {
+        metrics.record_stream_opened();
+        Self(metrics)
+    }
+}
+
+impl Drop for StreamGauge {
+    fn drop(&mut self) {
+        self.0.record_stream_closed();
+    }
+}
+
+/// The event stream of a response, dropped when it ends or is reaped
+type Slot = Arc<Mutex<Option<(BodyStream, Option<StreamGauge>)>>>;
+
+/// An SSE body sending heartbeats and reaped when it is no longer polled
+pub(crate) struct HeartbeatStream {
+    inner: Slot,
+    ticker: Option<Interval>,
+    last_polled: Arc<Mutex<Instant>>,
+    watchdog: Option<JoinHandle<()>>,
+}
+
+impl HeartbeatStream {
+    /// Wraps `inner`; must be called from within a Tokio runtime
+    pub(crate) fn new(
+        inner: BodyStream,
+        settings: HeartbeatSettings,
+        metrics: Option<Arc<MetricsSubscriber>>,
+    ) -> Self {
+        let inner: Slot = Arc::new(Mutex::new(Some((inner, metrics.clone().map(StreamGauge::new)))));
+        let last_polled = Arc::new(Mutex::new(Instant::now()));
+        let ticker = settings.interval.map(|interval| {
+            let mut ticker = tokio::time::interval_at(Instant::now() + interval, interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            ticker
+        });
+        let watchdog = settings.interval.zip(settings.stale_after).map(|(interval, stale_after)| {
+            let stale_after = stale_after.max(interval * 2);
+            tokio::spawn(reap_when_stale(inner.clone(), last_polled.clone(), stale_after, metrics))
+        });
+        Self {
+            inner,
+            ticker,
+            last_polled,
+            watchdog,
+        }
+    }
+}
+
+/// Drops the event stream once it has gone unpolled for `stale_after`
+async fn reap_when_stale(
+    inner: Slot,
+    last_polled: Arc<Mutex<Instant>>,
+    stale_after: Duration,
+    metrics: Option<Arc<MetricsSubscriber>>,
+) {
+    loop {
+        let deadline = *last_polled.lock().unwrap() + stale_after;
+        if Instant::now() < deadline {
+            tokio::time::sleep_until(deadline).await;
+            continue;
+        }
+        if inner.lock().unwrap().take().is_some() {
+            tracing::info!("Reaped an SSE stream whose client stopped reading");
+            if let Some(metrics) = &metrics {
+                metrics.record_stream_reaped();
+            }
+        }
+        return;
+    }
+}
+
+impl Stream for HeartbeatStream {
+    type Item = Result<Bytes, axum::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        *self.last_polled.lock().unwrap() = Instant::now();
+        let polled = {
+            let mut slot = self.inner.lock().unwrap();
+            let Some((inner, _)) = slot.as_mut() else {
+                return Poll::Ready(None);
+            };
+            let polled = inner.as_mut().poll_next(cx);
+            if let Poll::Ready(None) = polled {
+                *slot = None;
+            }
+            polled
+        };
+        match polled {
+            Poll::Ready(item) => {
+                if let Some(ticker) = self.ticker.as_mut() {
+                    ticker.reset();
+                }
+                Poll::Ready(item)
+            }
+            Poll::Pending => match self.ticker.as_mut().map(|ticker| ticker.poll_tick(cx)) {
+                Some(Poll::Ready(_)) => Poll::Ready(Some(Ok(Bytes::from_static(HEARTBEAT)))),
+                _ => Poll::Pending,
+            },
+        }
+    }
+}
+
+impl Drop for HeartbeatStream {
+    fn drop(&mut self) {
+        // The aborted watchdog may outlive this stream briefly; release the
+        // event stream now rather than with it
+        self.inner.lock().unwrap().take();
+        if let Some(watchdog) = self.watchdog.take() {
+            watchdog.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    fn pending_events() -> BodyStream {
+        Box::pin(futures::stream::once(async { Ok(Bytes::from_static(b"data: 1\n\n")) }).chain(futures::stream::pending()))
+    }
+
+    #[tokio::test]
+    async fn test_idle_stream_sends_heartbeats() {
+        let settings = HeartbeatSettings {
+            interval: Some(Duration::from_millis(20)),
+            stale_after: None,
+        };
+        let mut stream = HeartbeatStream::new(pending_events(), settings, None);
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), Bytes::from_static(b"data: 1\n\n"));
+        let start = Instant::now();
+        assert_eq!(stream.next().await.unwrap().unwrap(), Bytes::from_static(HEARTBEAT));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn test_unpolled_stream_is_reaped() {
+        let metrics = Arc::new(MetricsSubscriber::new());
+        let settings = HeartbeatSettings {
+            interval: Some(Duration::from_millis(20)),
+            stale_after: Some(Duration::from_millis(80)),
+        };
+        let mut stream = HeartbeatStream::new(pending_events(), settings, Some(metrics.clone()));
+        stream.next().await.unwrap().unwrap();
+        assert_eq!(metrics.snapshot().active_streams, 1);
+
+        // The client stops reading
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(metrics.snapshot().active_streams, 0);
+        assert_eq!(metrics.snapshot().reaped_streams, 1);
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_polled_stream_is_kept() {
+        let metrics = Arc::new(MetricsSubscriber::new());
+        let settings = HeartbeatSettings {
+            interval: Some(Duration::from_millis(20)),
+            stale_after: Some(Duration::from_millis(80)),
+        };
+        let mut stream = HeartbeatStream::new(pending_events(), settings, Some(metrics.clone()));
+        for _ in 0..10 {
+            stream.next().await.unwrap().unwrap();
+        }
+        assert_eq!(metrics.snapshot().reaped_streams, 0);
+
+        drop(stream);
+        assert_eq!(metrics.snapshot().active_streams, 0);
+    }
+}
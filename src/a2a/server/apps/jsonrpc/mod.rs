@@ -3,30 +3,68 @@
 //! This module provides a JSON-RPC server implementation that handles
 //! A2A protocol requests over HTTP/HTTPS.
 
+mod artifacts;
+mod body;
+mod build_error;
+mod heartbeat;
+mod stream_tracing;
+mod uploads;
+
+use crate::a2a::error::A2AError;
 use crate::a2a::models::*;
-use crate::a2a::server::context::ServerCallContextBuilder;
+use crate::a2a::server::agent_card_handle::AgentCardHandle;
+use crate::a2a::server::artifacts::{ArtifactLinkingRequestHandler, ArtifactStore};
+use crate::a2a::server::card_bootstrap::PublicEndpoint;
+use crate::a2a::server::config::A2AConfig;
+use crate::a2a::server::context::{DefaultServerCallContextBuilder, ServerCallContext, ServerCallContextBuilder};
+use crate::a2a::server::events::{MetricsSubscriber, TaskTimeline};
+use crate::a2a::server::message_validation::{MessageValidator, ValidatingRequestHandler};
+use crate::a2a::server::part_transform::{PartTransformer, TransformingRequestHandler};
+use crate::a2a::server::budget::{BudgetLimits, BudgetRequestHandler};
+use crate::a2a::server::usage::{UsageMetadataRequestHandler, UsageRecorder};
+use crate::a2a::server::request_handlers::DefaultRequestHandler;
+use crate::a2a::server::uploads::UploadManager;
+use crate::a2a::server::tasks::{
+    HttpPushNotificationSender, InMemoryPushNotificationConfigStore, InMemoryTaskStore, PushNotificationConfigStore,
+    SqlitePushNotificationConfigStore, SqliteTaskStore, StoreInstrumentation, TaskRetention, TaskStore,
+};
+use crate::a2a::utils::canonical_json::to_canonical_vec;
+use crate::a2a::utils::jws::{sign_compact, JwsSigner, JOSE_CONTENT_TYPE};
+use crate::a2a::utils::logging::{init_logging, LogFormat};
+use crate::a2a::server::quota::{self, QuotaStore};
+use crate::a2a::server::replay::ReplayProtection;
+use crate::a2a::server::request_signing::HmacRequestVerifier;
+use crate::a2a::utils::telemetry::TraceContext;
 use crate::a2a::server::request_handlers::{RequestHandler, JSONRPCHandler};
 use crate::a2a::utils::constants::*;
 use axum::{
-    extract::{Request, State},
-    http::{HeaderMap, HeaderValue, StatusCode},
+    extract::{Path as UrlPath, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
     response::{IntoResponse, Json, Response},
     routing::{get, post},
     Router,
 };
-use futures::StreamExt;
+use futures::{FutureExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
 use serde_json::Value;
 use std::net::SocketAddr;
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tower_http::{
     cors::{Any, CorsLayer},
     trace::TraceLayer,
 };
-use tracing::{error, info};
+use tracing::{debug, error, info, warn, Instrument};
+
+pub use build_error::BuildError;
 
 /// Server configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ServerConfig {
     /// The address to bind the server to
     pub bind_addr: SocketAddr,
@@ -38,8 +76,46 @@ pub struct ServerConfig {
     pub extended_agent_card_path: String,
     /// Maximum content length for requests (in bytes)
     pub max_content_length: Option<usize>,
+    /// Maximum length of any single JSON string in a request (in bytes),
+    /// which bounds inline file and text parts while the body is read
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_part_bytes: Option<usize>,
     /// CORS configuration
     pub enable_cors: bool,
+    /// Serve HTTPS with these certificates instead of plain HTTP
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+    /// The URL path for the event metrics endpoint, served when metrics are enabled
+    pub metrics_path: String,
+    /// The URL path under which stored artifacts are served, when an artifact store is set
+    pub artifacts_path: String,
+    /// Seconds between SSE comment heartbeats of an idle stream
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sse_heartbeat_secs: Option<u64>,
+    /// Seconds an SSE stream may go unread by its client before it is dropped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sse_stale_secs: Option<u64>,
+    /// Reject JSON-RPC requests that do not match the 2.0 request object
+    /// exactly, see [`JSONRPCHandler::with_strict_protocol`]
+    pub strict_protocol: bool,
+}
+
+/// TLS certificate configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// PEM file with the certificate chain
+    pub cert_path: PathBuf,
+    /// PEM file with the private key
+    pub key_path: PathBuf,
+}
+
+impl ServerConfig {
+    fn heartbeat_settings(&self) -> heartbeat::HeartbeatSettings {
+        heartbeat::HeartbeatSettings {
+            interval: self.sse_heartbeat_secs.map(Duration::from_secs),
+            stale_after: self.sse_stale_secs.map(Duration::from_secs),
+        }
+    }
 }
 
 impl Default for ServerConfig {
@@ -50,7 +126,14 @@ impl Default for ServerConfig {
             rpc_path: DEFAULT_RPC_URL.to_string(),
             extended_agent_card_path: EXTENDED_AGENT_CARD_PATH.to_string(),
             max_content_length: Some(10 * 1024 * 1024), // 10MB
+            max_part_bytes: None,
             enable_cors: true,
+            tls: None,
+            metrics_path: "/metrics".to_string(),
+            artifacts_path: "/artifacts".to_string(),
+            sse_heartbeat_secs: Some(15),
+            sse_stale_secs: Some(60),
+            strict_protocol: false,
         }
     }
 }
@@ -58,10 +141,21 @@ impl Default for ServerConfig {
 /// Internal server state
 #[derive(Clone)]
 struct ServerState {
-    agent_card: AgentCard,
-    extended_agent_card: Option<AgentCard>,
+    cards: AgentCardHandle,
     handler: Arc<JSONRPCHandler>,
     context_builder: Arc<dyn ServerCallContextBuilder>,
+    quota_store: Option<Arc<dyn QuotaStore>>,
+    replay_protection: Option<ReplayProtection>,
+    request_verifier: Option<HmacRequestVerifier>,
+    #[cfg(feature = "spiffe")]
+    spiffe_tls: Option<crate::a2a::spiffe::SpiffeServerTls>,
+    card_signer: Option<Arc<dyn JwsSigner>>,
+    metrics: Option<Arc<MetricsSubscriber>>,
+    store_metrics: Option<StoreInstrumentation>,
+    timeline: Option<TaskTimeline>,
+    artifacts: Option<artifacts::ArtifactEndpoint>,
+    uploads: Option<UploadManager>,
+    task_retention: Option<(TaskRetention, Duration)>,
     config: ServerConfig,
 }
 
@@ -86,16 +180,28 @@ impl A2AServer {
         request_handler: Arc<dyn RequestHandler>,
         context_builder: Arc<dyn ServerCallContextBuilder>,
     ) -> Self {
-        let handler = Arc::new(JSONRPCHandler::new(
-            agent_card.clone(),
+        let cards = AgentCardHandle::new(agent_card);
+        let handler = Arc::new(JSONRPCHandler::with_card_handle(
+            cards.clone(),
             request_handler,
         ));
 
         let state = ServerState {
-            agent_card,
-            extended_agent_card: None,
+            cards,
             handler,
             context_builder,
+            quota_store: None,
+            replay_protection: None,
+            request_verifier: None,
+            #[cfg(feature = "spiffe")]
+            spiffe_tls: None,
+            card_signer: None,
+            metrics: None,
+            store_metrics: None,
+            timeline: None,
+            artifacts: None,
+            uploads: None,
+            task_retention: None,
             config: ServerConfig::default(),
         };
 
@@ -106,22 +212,64 @@ impl A2AServer {
 
     /// Set the extended agent card
     pub async fn with_extended_agent_card(self, card: AgentCard) -> Self {
-        {
-            let mut state = self.state.write().await;
-            state.extended_agent_card = Some(card);
-        }
+        self.state.read().await.cards.update_extended(Some(card));
         self
     }
 
+    /// Returns the handle of the served agent cards
+    ///
+    /// Cards updated through the handle are served immediately, including by
+    /// routers built before the update.
+    pub async fn agent_card_handle(&self) -> AgentCardHandle {
+        self.state.read().await.cards.clone()
+    }
+
     /// Set the server configuration
     pub async fn with_config(self, config: ServerConfig) -> Self {
         {
             let mut state = self.state.write().await;
+            state.handler = Arc::new(state.handler.as_ref().clone().with_strict_protocol(config.strict_protocol));
             state.config = config;
         }
         self
     }
 
+    /// Set the quota store used to meter and limit requests per tenant
+    pub async fn with_quota_store(self, quota_store: Arc<dyn QuotaStore>) -> Self {
+        {
+            let mut state = self.state.write().await;
+            state.quota_store = Some(quota_store);
+        }
+        self
+    }
+
+    /// Require signed nonces on JSON-RPC requests, rejecting replays
+    pub async fn with_replay_protection(self, replay_protection: ReplayProtection) -> Self {
+        {
+            let mut state = self.state.write().await;
+            state.replay_protection = Some(replay_protection);
+        }
+        self
+    }
+
+    /// Require requests to be HMAC-signed by one of the verifier's peers
+    pub async fn with_request_verifier(self, verifier: HmacRequestVerifier) -> Self {
+        {
+            let mut state = self.state.write().await;
+            state.request_verifier = Some(verifier);
+        }
+        self
+    }
+
+    /// Set the signer used to serve the agent card as a JWS
+    pub async fn with_card_signer(self, signer: Arc<dyn JwsSigner>) -> Self {
+        {
+            let mut state = self.state.write().await;
+            state.card_signer = Some(signer);
+        }
+        self
+    }
+
     /// Build the Axum router
     pub async fn build_router(&self) -> Router {
         let state = self.state.read().await.clone();
@@ -129,13 +277,37 @@ impl A2AServer {
             .route(&state.config.agent_card_path, get(get_agent_card))
             .route(&state.config.rpc_path, post(handle_jsonrpc_request));
 
-        // Add extended agent card endpoint if supported
-        if state.agent_card.supports_authenticated_extended_card.unwrap_or(false) {
+        // The extended card endpoint is always routed since support can be
+        // switched on at runtime; it answers 404 while unsupported
+        router = router.route(
+            &state.config.extended_agent_card_path,
+            get(get_authenticated_extended_agent_card),
+        );
+
+        if state.metrics.is_some() || state.store_metrics.is_some() {
+            router = router.route(&state.config.metrics_path, get(get_metrics));
+        }
+        if state.timeline.is_some() {
+            router = router.route(&format!("{}/timeline/:task_id", state.config.metrics_path), get(get_task_timeline));
+        }
+        if state.artifacts.is_some() {
             router = router.route(
-                &state.config.extended_agent_card_path,
-                get(get_authenticated_extended_agent_card),
+                &format!("{}/:task_id/:artifact_id", state.config.artifacts_path.trim_end_matches('/')),
+                get(artifacts::get_artifact),
             );
         }
+        if let Some(manager) = &state.uploads {
+            let path = manager.base_url().path().trim_end_matches('/');
+            router = router
+                .route(if path.is_empty() { "/" } else { path }, post(uploads::create_upload))
+                .route(
+                    &format!("{}/:upload_id", path),
+                    get(uploads::get_upload)
+                        .head(uploads::upload_status)
+                        .patch(uploads::append_upload)
+                        .delete(uploads::delete_upload),
+                );
+        }
 
         // Add deprecated endpoint for backward compatibility
         if state.config.agent_card_path == AGENT_CARD_WELL_KNOWN_PATH {
@@ -175,6 +347,24 @@ impl A2AServer {
             state.config.agent_card_path
         );
         info!("JSON-RPC endpoint at: {}", state.config.rpc_path);
+        let _purging = state
+            .task_retention
+            .as_ref()
+            .map(|(retention, interval)| retention.spawn(*interval));
+
+        #[cfg(feature = "spiffe")]
+        if let Some(spiffe_tls) = &state.spiffe_tls {
+            return spiffe_tls.serve(state.config.bind_addr, router).await;
+        }
+
+        if let Some(tls) = &state.config.tls {
+            let rustls_config =
+                axum_server::tls_rustls::RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path).await?;
+            axum_server::bind_rustls(state.config.bind_addr, rustls_config)
+                .serve(router.into_make_service())
+                .await?;
+            return Ok(());
+        }
 
         let listener = tokio::net::TcpListener::bind(state.config.bind_addr).await?;
         axum::serve(listener, router).await?;
@@ -186,9 +376,29 @@ impl A2AServer {
 /// Builder for creating an A2A server
 pub struct A2AServerBuilder {
     agent_card: Option<AgentCard>,
+    card_handle: Option<AgentCardHandle>,
     request_handler: Option<Arc<dyn RequestHandler>>,
     context_builder: Option<Arc<dyn ServerCallContextBuilder>>,
     extended_agent_card: Option<AgentCard>,
+    quota_store: Option<Arc<dyn QuotaStore>>,
+    replay_protection: Option<ReplayProtection>,
+    request_verifier: Option<HmacRequestVerifier>,
+    #[cfg(feature = "spiffe")]
+    spiffe_tls: Option<crate::a2a::spiffe::SpiffeServerTls>,
+    card_signer: Option<Arc<dyn JwsSigner>>,
+    metrics: Option<Arc<MetricsSubscriber>>,
+    store_metrics: Option<StoreInstrumentation>,
+    timeline: Option<TaskTimeline>,
+    artifact_store: Option<Arc<dyn ArtifactStore>>,
+    uploads: Option<UploadManager>,
+    public_endpoint: Option<PublicEndpoint>,
+    message_validators: Vec<Arc<dyn MessageValidator>>,
+    part_transformers: Vec<Arc<dyn PartTransformer>>,
+    usage: Option<UsageRecorder>,
+    budget_limits: Option<BudgetLimits>,
+    task_search: Option<Arc<dyn TaskStore>>,
+    task_retention: Option<(TaskRetention, Duration)>,
+    strict_validation: bool,
     config: ServerConfig,
 }
 
@@ -197,19 +407,118 @@ impl A2AServerBuilder {
     pub fn new() -> Self {
         Self {
             agent_card: None,
+            card_handle: None,
             request_handler: None,
             context_builder: None,
             extended_agent_card: None,
+            quota_store: None,
+            replay_protection: None,
+            request_verifier: None,
+            #[cfg(feature = "spiffe")]
+            spiffe_tls: None,
+            card_signer: None,
+            metrics: None,
+            store_metrics: None,
+            timeline: None,
+            artifact_store: None,
+            uploads: None,
+            public_endpoint: None,
+            message_validators: Vec::new(),
+            part_transformers: Vec::new(),
+            usage: None,
+            budget_limits: None,
+            task_search: None,
+            task_retention: None,
+            strict_validation: false,
             config: ServerConfig::default(),
         }
     }
 
+    /// Preset with only the default context builder and CORS disabled
+    ///
+    /// The agent card and request handler must still be set.
+    pub fn minimal() -> Self {
+        Self::new()
+            .with_context_builder(Arc::new(DefaultServerCallContextBuilder))
+            .with_config(ServerConfig {
+                enable_cors: false,
+                ..ServerConfig::default()
+            })
+    }
+
+    /// Preset for local development
+    ///
+    /// Uses in-memory task and push config stores with webhook delivery,
+    /// permissive CORS and pretty logs. Only the agent card must still be set.
+    /// Must be called from within a Tokio runtime.
+    pub fn dev() -> Self {
+        init_logging(LogFormat::Pretty);
+        let push_config_store: Arc<dyn PushNotificationConfigStore> = Arc::new(InMemoryPushNotificationConfigStore::new());
+        let push_sender = Arc::new(HttpPushNotificationSender::new(push_config_store.clone()));
+        let handler = DefaultRequestHandler::new(
+            Arc::new(InMemoryTaskStore::new()),
+            Some(push_config_store),
+            Some(push_sender),
+        );
+        Self::new()
+            .with_request_handler(Arc::new(handler))
+            .with_context_builder(Arc::new(DefaultServerCallContextBuilder))
+            .with_config(ServerConfig {
+                enable_cors: true,
+                ..ServerConfig::default()
+            })
+    }
+
+    /// Preset for production deployments
+    ///
+    /// Requires SQL task and push config stores, disables CORS, validates the
+    /// agent card strictly on `build`, writes JSON logs and serves event and
+    /// store metrics, logging store operations slower than 500ms. The agent
+    /// card advertises the public endpoint found by
+    /// `PublicEndpoint::from_environment`, if any. Only the agent card must
+    /// still be set. Must be called from within a Tokio runtime.
+    pub fn production(task_store: SqliteTaskStore, push_config_store: SqlitePushNotificationConfigStore) -> Self {
+        init_logging(LogFormat::Json);
+        let store_metrics = StoreInstrumentation::new().with_slow_threshold(Duration::from_millis(500));
+        let task_store = store_metrics.task_store(Arc::new(task_store));
+        let push_config_store = store_metrics.push_config_store(Arc::new(push_config_store));
+        let push_sender = Arc::new(HttpPushNotificationSender::new(push_config_store.clone()));
+        let handler = DefaultRequestHandler::new(task_store, Some(push_config_store), Some(push_sender));
+        let metrics = Arc::new(MetricsSubscriber::new());
+        handler.event_bus().spawn_subscriber(metrics.clone());
+
+        let mut builder = Self::new()
+            .with_request_handler(Arc::new(handler))
+            .with_context_builder(Arc::new(DefaultServerCallContextBuilder))
+            .with_strict_validation(true)
+            .with_store_metrics(store_metrics)
+            .with_config(ServerConfig {
+                enable_cors: false,
+                ..ServerConfig::default()
+            });
+        builder.metrics = Some(metrics);
+        match PublicEndpoint::from_environment() {
+            Ok(endpoint) => builder.public_endpoint = endpoint,
+            Err(e) => warn!("Ignoring public endpoint settings: {}", e),
+        }
+        builder
+    }
+
     /// Set the agent card
     pub fn with_agent_card(mut self, card: AgentCard) -> Self {
         self.agent_card = Some(card);
         self
     }
 
+    /// Serve the cards held by a handle, so they can be swapped at runtime
+    ///
+    /// Takes precedence over `with_agent_card`. An extended card set on the
+    /// builder replaces the one held by the handle.
+    pub fn with_agent_card_handle(mut self, handle: AgentCardHandle) -> Self {
+        self.card_handle = Some(handle);
+        self
+    }
+
     /// Set the request handler
     pub fn with_request_handler(mut self, handler: Arc<dyn RequestHandler>) -> Self {
         self.request_handler = Some(handler);
@@ -228,27 +537,332 @@ impl A2AServerBuilder {
         self
     }
 
+    /// Set the quota store
+    pub fn with_quota_store(mut self, quota_store: Arc<dyn QuotaStore>) -> Self {
+        self.quota_store = Some(quota_store);
+        self
+    }
+
+    /// Require signed nonces on JSON-RPC requests, rejecting replays
+    ///
+    /// Clients sign their requests with `ReplayProtectionInterceptor`.
+    pub fn with_replay_protection(mut self, replay_protection: ReplayProtection) -> Self {
+        self.replay_protection = Some(replay_protection);
+        self
+    }
+
+    /// Require requests to be HMAC-signed by one of the verifier's peers
+    ///
+    /// Peers sign their requests with `HmacSigningInterceptor`.
+    pub fn with_request_verifier(mut self, verifier: HmacRequestVerifier) -> Self {
+        self.request_verifier = Some(verifier);
+        self
+    }
+
+    /// Serve mTLS with a SPIFFE SVID, authorizing peers by SPIFFE ID
+    ///
+    /// Takes precedence over `ServerConfig::tls`.
+    #[cfg(feature = "spiffe")]
+    pub fn with_spiffe_tls(mut self, spiffe_tls: crate::a2a::spiffe::SpiffeServerTls) -> Self {
+        self.spiffe_tls = Some(spiffe_tls);
+        self
+    }
+
     /// Set the server configuration
     pub fn with_config(mut self, config: ServerConfig) -> Self {
         self.config = config;
         self
     }
 
+    /// Serve the agent card as a signed JWS to clients accepting `application/jose`
+    pub fn with_card_signer(mut self, signer: Arc<dyn JwsSigner>) -> Self {
+        self.card_signer = Some(signer);
+        self
+    }
+
+    /// Serve the counters of a metrics subscriber at the metrics path
+    pub fn with_metrics(mut self, metrics: Arc<MetricsSubscriber>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Serve the store counters of an instrumentation at the metrics path
+    ///
+    /// The stores themselves are wrapped where they are created, for
+    /// example with `DefaultRequestHandler::with_store_instrumentation`.
+    pub fn with_store_metrics(mut self, instrumentation: StoreInstrumentation) -> Self {
+        self.store_metrics = Some(instrumentation);
+        self
+    }
+
+    /// Serve task timelines at `{metrics_path}/timeline/{task_id}`
+    ///
+    /// The timeline is filled by the request handler, for example with
+    /// `DefaultRequestHandler::with_task_timeline`.
+    pub fn with_task_timeline(mut self, timeline: TaskTimeline) -> Self {
+        self.timeline = Some(timeline);
+        self
+    }
+
+    /// Serve the file artifacts of returned tasks from `store`
+    ///
+    /// Inline file bytes of artifacts are moved into the store on their way
+    /// to the client and replaced by URLs of the
+    /// `{artifacts_path}/{task_id}/{artifact_id}` endpoint on the agent
+    /// card's host, which streams them with range request support.
+    pub fn with_artifact_store(mut self, store: Arc<dyn ArtifactStore>) -> Self {
+        self.artifact_store = Some(store);
+        self
+    }
+
+    /// Accept resumable uploads under the path of the manager's base URL
+    ///
+    /// Clients upload large files first and reference the finished uploads
+    /// by URI in their file parts. Incoming messages are validated by the
+    /// manager, which marks referenced uploads; garbage collection of the
+    /// others is started with `UploadManager::spawn_garbage_collector`.
+    pub fn with_uploads(mut self, uploads: UploadManager) -> Self {
+        self.message_validators.push(Arc::new(uploads.clone()));
+        self.uploads = Some(uploads);
+        self
+    }
+
+    /// Advertise `endpoint` in the URLs of the agent cards
+    ///
+    /// The cards are rewritten on `build`; see `PublicEndpoint::from_environment`
+    /// for reading the endpoint in containerized deployments.
+    pub fn with_public_endpoint(mut self, endpoint: PublicEndpoint) -> Self {
+        self.public_endpoint = Some(endpoint);
+        self
+    }
+
+    /// Check every incoming message with `validator` before it is handled
+    ///
+    /// Validators run in the order they were added, and the first violation
+    /// is returned to the client as an invalid params error.
+    pub fn with_message_validator(mut self, validator: Arc<dyn MessageValidator>) -> Self {
+        self.message_validators.push(validator);
+        self
+    }
+
+    /// Rewrite every part of incoming messages with `transformer`
+    ///
+    /// Transformers run in the order they were added, after the message
+    /// validators, so validators see the message as the client sent it and
+    /// the agent executor sees the transformed parts.
+    pub fn with_part_transformer(mut self, transformer: Arc<dyn PartTransformer>) -> Self {
+        self.part_transformers.push(transformer);
+        self
+    }
+
+    /// Expose the usage totals `recorder` collects in task metadata
+    ///
+    /// Executors report usage through a clone of the same recorder.
+    pub fn with_usage_recorder(mut self, recorder: UsageRecorder) -> Self {
+        self.usage = Some(recorder);
+        self
+    }
+
+    /// Enforce `limits` on the usage collected by the usage recorder
+    ///
+    /// Requires `with_usage_recorder`; see `BudgetRequestHandler`. Budget
+    /// failures are only published on the event bus of a handler wrapped
+    /// with `BudgetRequestHandler::with_task_events`.
+    pub fn with_budget_limits(mut self, limits: BudgetLimits) -> Self {
+        self.budget_limits = Some(limits);
+        self
+    }
+
+    /// Serve the `tasks/search` JSON-RPC extension method from `store`
+    ///
+    /// See `JSONRPCHandler::with_task_search`; the store must support
+    /// `TaskStore::search_tasks`, e.g. a `SqlTaskStore` with its search
+    /// index enabled.
+    pub fn with_task_search(mut self, store: Arc<dyn TaskStore>) -> Self {
+        self.task_search = Some(store);
+        self
+    }
+
+    /// Purge expired deleted tasks every `interval` while the server runs
+    pub fn with_task_retention(mut self, retention: TaskRetention, interval: Duration) -> Self {
+        self.task_retention = Some((retention, interval));
+        self
+    }
+
+    /// Reject agent cards with missing or malformed required fields on `build`
+    pub fn with_strict_validation(mut self, strict: bool) -> Self {
+        self.strict_validation = strict;
+        self
+    }
+
+    /// Create a builder from a configuration file
+    ///
+    /// Loads the file with environment overrides (see `A2AConfig`), and
+    /// sets the server configuration, a `DefaultRequestHandler` over the
+    /// configured stores and the configured context builder. The agent card
+    /// must still be set before building.
+    pub async fn from_config(path: impl AsRef<Path>) -> Result<Self, A2AError> {
+        let config = A2AConfig::load(path)?;
+        Self::from_a2a_config(&config).await
+    }
+
+    /// Create a builder from an already loaded configuration
+    pub async fn from_a2a_config(config: &A2AConfig) -> Result<Self, A2AError> {
+        let task_store = config.task_store().await?;
+        let mut builder = Self::new()
+            .with_config(config.server.clone())
+            .with_request_handler(Arc::new(config.request_handler_with_task_store(task_store.clone()).await?))
+            .with_context_builder(config.context_builder());
+        if let Some((retention, interval)) = config.task_retention(task_store.clone()) {
+            builder = builder.with_task_retention(retention, interval);
+        }
+        Ok(if config.store.search_index { builder.with_task_search(task_store) } else { builder })
+    }
+
+    /// Check the configuration without building the server
+    ///
+    /// Reports every problem `build` would fail on: missing pieces, budget
+    /// limits without a usage recorder, an extended card the agent card does
+    /// not declare, TLS files that do not exist, artifact links that cannot
+    /// be derived from the card url and, with strict validation, malformed
+    /// agent cards. Several problems are returned as `BuildError::Multiple`.
+    pub fn validate(&self) -> Result<(), BuildError> {
+        let mut problems = Vec::new();
+        let card = match (&self.card_handle, &self.agent_card) {
+            (Some(handle), _) => Some((*handle.current()).clone()),
+            (None, card) => card.clone(),
+        };
+        let card = card.map(|mut card| {
+            if let Some(endpoint) = &self.public_endpoint {
+                endpoint.apply(&mut card);
+            }
+            card
+        });
+        if card.is_none() {
+            problems.push(BuildError::MissingAgentCard);
+        }
+        if self.request_handler.is_none() {
+            problems.push(BuildError::MissingRequestHandler);
+        }
+        if self.context_builder.is_none() {
+            problems.push(BuildError::MissingContextBuilder);
+        }
+        if self.budget_limits.is_some() && self.usage.is_none() {
+            problems.push(BuildError::BudgetWithoutUsageRecorder);
+        }
+        #[cfg(feature = "spiffe")]
+        let uses_config_tls = self.spiffe_tls.is_none();
+        #[cfg(not(feature = "spiffe"))]
+        let uses_config_tls = true;
+        if let Some(tls) = self.config.tls.as_ref().filter(|_| uses_config_tls) {
+            if !tls.cert_path.is_file() {
+                problems.push(BuildError::TlsCertificateMissing(tls.cert_path.clone()));
+            }
+            if !tls.key_path.is_file() {
+                problems.push(BuildError::TlsKeyMissing(tls.key_path.clone()));
+            }
+        }
+
+        if let Some(card) = &card {
+            let extended = self.extended_agent_card.is_some()
+                || self.card_handle.as_ref().is_some_and(|handle| handle.extended().is_some());
+            if extended && card.supports_authenticated_extended_card != Some(true) {
+                problems.push(BuildError::ExtendedCardNotDeclared);
+            }
+            if self.strict_validation {
+                problems.extend(agent_card_problems(card));
+            }
+            if self.artifact_store.is_some() {
+                if let Err(e) = artifacts_base_url(card, &self.config) {
+                    problems.push(e);
+                }
+            }
+        }
+        BuildError::from_problems(problems).map_or(Ok(()), Err)
+    }
+
     /// Build the server
-    pub fn build(self) -> Result<A2AServer, String> {
-        let agent_card = self.agent_card.ok_or("Agent card is required")?;
-        let request_handler = self.request_handler.ok_or("Request handler is required")?;
-        let context_builder = self.context_builder
-            .ok_or("Context builder is required")?;
+    ///
+    /// Fails with the problems reported by `validate`.
+    pub fn build(mut self) -> Result<A2AServer, BuildError> {
+        self.validate()?;
+        if let Some(endpoint) = &self.public_endpoint {
+            self.agent_card.iter_mut().chain(self.extended_agent_card.iter_mut()).for_each(|card| endpoint.apply(card));
+        }
+        let cards = match (self.card_handle, self.agent_card) {
+            (Some(handle), _) => {
+                if let Some(endpoint) = &self.public_endpoint {
+                    let mut card = (*handle.current()).clone();
+                    endpoint.apply(&mut card);
+                    handle.replace(card);
+                }
+                handle
+            }
+            (None, Some(card)) => AgentCardHandle::new(card),
+            (None, None) => return Err(BuildError::MissingAgentCard),
+        };
+        let mut request_handler = self.request_handler.ok_or(BuildError::MissingRequestHandler)?;
+        if !self.part_transformers.is_empty() {
+            let transforming = self
+                .part_transformers
+                .into_iter()
+                .fold(TransformingRequestHandler::new(request_handler), TransformingRequestHandler::with_transformer);
+            request_handler = Arc::new(transforming);
+        }
+        if !self.message_validators.is_empty() {
+            let validating = self
+                .message_validators
+                .into_iter()
+                .fold(ValidatingRequestHandler::new(request_handler), ValidatingRequestHandler::with_validator);
+            request_handler = Arc::new(validating);
+        }
+        if let Some(usage) = self.usage {
+            request_handler = Arc::new(UsageMetadataRequestHandler::new(request_handler, usage.clone()));
+            if let Some(limits) = self.budget_limits {
+                request_handler = Arc::new(BudgetRequestHandler::new(request_handler, usage, limits));
+            }
+        } else if self.budget_limits.is_some() {
+            return Err(BuildError::BudgetWithoutUsageRecorder);
+        }
+        let context_builder = self.context_builder.ok_or(BuildError::MissingContextBuilder)?;
+        if self.extended_agent_card.is_some() {
+            cards.update_extended(self.extended_agent_card);
+        }
+        let artifacts = match self.artifact_store {
+            Some(store) => {
+                let base_url = artifacts_base_url(&cards.current(), &self.config)?;
+                let endpoint = artifacts::ArtifactEndpoint {
+                    store: store.clone(),
+                    handler: request_handler.clone(),
+                };
+                request_handler = Arc::new(ArtifactLinkingRequestHandler::new(request_handler, store, base_url));
+                Some(endpoint)
+            }
+            None => None,
+        };
+
+        let mut handler =
+            JSONRPCHandler::with_card_handle(cards.clone(), request_handler).with_strict_protocol(self.config.strict_protocol);
+        if let Some(store) = self.task_search {
+            handler = handler.with_task_search(store);
+        }
 
         let state = ServerState {
-            agent_card: agent_card.clone(),
-            extended_agent_card: self.extended_agent_card,
-            handler: Arc::new(JSONRPCHandler::new(
-                agent_card.clone(),
-                request_handler,
-            )),
+            cards,
+            handler: Arc::new(handler),
             context_builder,
+            quota_store: self.quota_store,
+            replay_protection: self.replay_protection,
+            request_verifier: self.request_verifier,
+            #[cfg(feature = "spiffe")]
+            spiffe_tls: self.spiffe_tls,
+            card_signer: self.card_signer,
+            metrics: self.metrics,
+            store_metrics: self.store_metrics,
+            timeline: self.timeline,
+            artifacts,
+            uploads: self.uploads,
+            task_retention: self.task_retention,
             config: self.config,
         };
 
@@ -264,18 +878,141 @@ impl Default for A2AServerBuilder {
     }
 }
 
+/// The problems with the fields of an agent card that clients rely on
+fn agent_card_problems(card: &AgentCard) -> Vec<BuildError> {
+    let mut problems = Vec::new();
+    if card.name.trim().is_empty() {
+        problems.push(BuildError::EmptyCardName);
+    }
+    if card.version.trim().is_empty() {
+        problems.push(BuildError::EmptyCardVersion);
+    }
+    if let Err(e) = url::Url::parse(&card.url) {
+        problems.push(BuildError::InvalidCardUrl {
+            url: card.url.clone(),
+            reason: e.to_string(),
+        });
+    }
+    if card.default_input_modes.is_empty() || card.default_output_modes.is_empty() {
+        problems.push(BuildError::MissingDefaultModes);
+    }
+    let mut skill_ids = std::collections::HashSet::new();
+    for skill in &card.skills {
+        if !skill_ids.insert(skill.id.as_str()) {
+            problems.push(BuildError::DuplicateSkill(skill.id.clone()));
+        }
+    }
+    problems
+}
+
+/// The URL stored artifacts are served under, on the card's host
+fn artifacts_base_url(card: &AgentCard, config: &ServerConfig) -> Result<url::Url, BuildError> {
+    let card_url = url::Url::parse(&card.url)
+        .map_err(|e| BuildError::InvalidArtifactsUrl(format!("agent card url is invalid: {}", e)))?;
+    card_url
+        .join(&config.artifacts_path)
+        .map_err(|e| BuildError::InvalidArtifactsUrl(format!("artifacts path is invalid: {}", e)))
+}
+
+/// HTTP handler serving the timeline of a task
+async fn get_task_timeline(State(state): State<ServerState>, UrlPath(task_id): UrlPath<String>) -> Response {
+    match state.timeline.as_ref().and_then(|timeline| timeline.get(&task_id)) {
+        Some(entries) => Json(entries).into_response(),
+        None => (StatusCode::NOT_FOUND, format!("No timeline for task {}", task_id)).into_response(),
+    }
+}
+
+/// HTTP handler for the event metrics, with the store metrics under `stores`
+async fn get_metrics(State(state): State<ServerState>) -> impl IntoResponse {
+    let snapshot = state.metrics.as_ref().map(|metrics| metrics.snapshot()).unwrap_or_default();
+    let mut body = serde_json::to_value(snapshot).unwrap();
+    if let Some(store_metrics) = &state.store_metrics {
+        body["stores"] = serde_json::to_value(store_metrics.snapshot()).unwrap();
+    }
+    Json(body)
+}
+
 /// HTTP handler for getting the agent card
+///
+/// Serves JSON by default, a JWS over the canonical card for `application/jose`
+/// when a signer is configured and YAML for `application/yaml`, with `ETag`
+/// and `Last-Modified` validators.
 async fn get_agent_card(
     State(state): State<ServerState>,
-) -> impl IntoResponse {
-    Json(serde_json::to_value(&state.agent_card).unwrap())
+    headers: HeaderMap,
+) -> Response {
+    let card = state.cards.current();
+    let accept = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+    let accepts = |media_type: &str| {
+        accept
+            .split(',')
+            .any(|range| range.split(';').next().unwrap_or("").trim().eq_ignore_ascii_case(media_type))
+    };
+
+    let rendered = match serde_json::to_vec(&*card) {
+        Ok(json) => match &state.card_signer {
+            Some(signer) if accepts(JOSE_CONTENT_TYPE) => to_canonical_vec(&*card)
+                .map_err(A2AError::from)
+                .and_then(|payload| sign_compact(&payload, "application/json", signer.as_ref()))
+                .map(|jws| (JOSE_CONTENT_TYPE, jws.into_bytes()))
+                .map_err(|e| e.to_string()),
+            _ if accepts("application/yaml") || accepts("text/yaml") || accepts("application/x-yaml") => {
+                serde_yaml::to_string(&*card)
+                    .map(|yaml| ("application/yaml", yaml.into_bytes()))
+                    .map_err(|e| e.to_string())
+            }
+            _ => Ok(("application/json", json)),
+        },
+        Err(e) => Err(e.to_string()),
+    };
+    let (content_type, body) = match rendered {
+        Ok(rendered) => rendered,
+        Err(e) => {
+            error!("Failed to render agent card: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let digest = sha2::Sha256::digest(&body);
+    let etag = format!(
+        "\"{}\"",
+        digest[..16].iter().map(|byte| format!("{:02x}", byte)).collect::<String>()
+    );
+    let last_modified = state.cards.updated_at();
+
+    let not_modified = match headers.get(header::IF_NONE_MATCH).and_then(|value| value.to_str().ok()) {
+        Some(tags) => tags.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"),
+        None => headers
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|since| chrono::DateTime::parse_from_rfc2822(since).ok())
+            .is_some_and(|since| since.timestamp() >= last_modified.timestamp()),
+    };
+
+    let mut response = if not_modified {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        ([(header::CONTENT_TYPE, content_type)], body).into_response()
+    };
+    let response_headers = response.headers_mut();
+    response_headers.insert(header::VARY, HeaderValue::from_static("Accept"));
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        response_headers.insert(header::ETAG, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&last_modified.format("%a, %d %b %Y %H:%M:%S GMT").to_string()) {
+        response_headers.insert(header::LAST_MODIFIED, value);
+    }
+    response
 }
 
 /// HTTP handler for getting the authenticated extended agent card
 async fn get_authenticated_extended_agent_card(
     State(state): State<ServerState>,
 ) -> impl IntoResponse {
-    if !state.agent_card.supports_authenticated_extended_card.unwrap_or(false) {
+    if !state.cards.current().supports_authenticated_extended_card.unwrap_or(false) {
         return (
             StatusCode::NOT_FOUND,
             Json(serde_json::json!({
@@ -284,8 +1021,8 @@ async fn get_authenticated_extended_agent_card(
         );
     }
 
-    if let Some(card) = &state.extended_agent_card {
-        (StatusCode::OK, Json(serde_json::to_value(card).unwrap()))
+    if let Some(card) = state.cards.extended() {
+        (StatusCode::OK, Json(serde_json::to_value(&*card).unwrap()))
     } else {
         (
             StatusCode::NOT_FOUND,
@@ -319,19 +1056,22 @@ async fn handle_jsonrpc_request(
         }
     }
 
-    // Parse request body
-    let body = match axum::body::to_bytes(request.into_body(), usize::MAX).await {
+    #[cfg(feature = "spiffe")]
+    let peer_spiffe_id = request
+        .extensions()
+        .get::<crate::a2a::spiffe::PeerSpiffeId>()
+        .and_then(|peer| peer.0.clone());
+
+    let path = request.uri().path().to_string();
+
+    // Read the body incrementally so oversized or misrouted requests abort early
+    let limits = body::BodyLimits {
+        max_body_bytes: state.config.max_content_length,
+        max_string_bytes: state.config.max_part_bytes,
+    };
+    let body = match body::read_body(request.into_body(), limits, JSONRPCHandler::METHODS).await {
         Ok(body) => body,
-        Err(e) => {
-            error!("Failed to read request body: {}", e);
-            return error_response(
-                None,
-                &crate::a2a::jsonrpc::JSONRPCError::new(
-                    crate::a2a::jsonrpc::standard_error_codes::INVALID_REQUEST,
-                    "Failed to read request body".to_string(),
-                ),
-            );
-        }
+        Err((id, error)) => return error_response(id, &error),
     };
 
     // Parse JSON
@@ -351,14 +1091,45 @@ async fn handle_jsonrpc_request(
 
     // Check if this is a streaming request
     let method = json_value.get("method").and_then(|m| m.as_str()).unwrap_or("");
-    let is_streaming = method == "message/stream";
+
+    // Authorize the peer's SPIFFE ID for the method, if serving with SPIFFE mTLS
+    #[cfg(feature = "spiffe")]
+    if let Some(policy) = state.spiffe_tls.as_ref().and_then(|tls| tls.policy()) {
+        if let Err(error) = policy.authorize(peer_spiffe_id.as_ref(), method) {
+            info!("Rejecting {} request: {}", method, error);
+            return error_response(json_value.get("id").cloned(), &error.into());
+        }
+    }
+
+    // Reject requests not signed by a known peer, if required
+    if let Some(verifier) = &state.request_verifier {
+        match verifier.verify(&headers, method, &body) {
+            Ok(peer) => debug!("Verified {} request signed by '{}'", method, peer),
+            Err(e) => {
+                info!("Rejecting {} request: {}", method, e);
+                let error: crate::a2a::error::A2AError = e.into();
+                return error_response(json_value.get("id").cloned(), &error.into());
+            }
+        }
+    }
+
+    // Reject requests without a fresh signed nonce, if required
+    if let Some(replay_protection) = &state.replay_protection {
+        let replay_request = crate::a2a::utils::replay::ReplayRequest::new(method, &path, &body);
+        if let Err(e) = replay_protection.verify(&headers, &replay_request).await {
+            info!("Rejecting {} request: {}", method, e);
+            let error: crate::a2a::error::A2AError = e.into();
+            return error_response(json_value.get("id").cloned(), &error.into());
+        }
+    }
+    let is_streaming = method == "message/stream" || method == "tasks/resubscribe";
 
     if is_streaming {
         // Handle streaming request
-        handle_streaming_request(state, headers, json_value).await
+        handle_streaming_request(state, headers, json_value, body.len()).await
     } else {
         // Handle non-streaming request
-        handle_non_streaming_request(state, headers, json_value).await
+        handle_non_streaming_request(state, headers, json_value, body.len()).await
     }
 }
 
@@ -366,10 +1137,34 @@ async fn handle_jsonrpc_request(
 async fn handle_streaming_request(
     state: ServerState,
     headers: HeaderMap,
-    json_value: Value,
+    mut json_value: Value,
+    bytes_in: usize,
 ) -> Response {
+    let resubscribe = json_value.get("method").and_then(|m| m.as_str()) == Some("tasks/resubscribe");
+    let method = if resubscribe { "tasks/resubscribe" } else { "message/stream" };
+
     // Build server call context
-    let context = state.context_builder.build(&headers).await;
+    let mut context = build_call_context(&state, &headers).await;
+    let trace_context = context.trace_context_or_root();
+    let span = request_span(method, &trace_context);
+    let request_span = span.clone();
+
+    // Enforce tenant quotas
+    let tenant = quota::tenant_for(&context);
+    if let Err(error) = check_quota(&state, &tenant, method, bytes_in).await {
+        return error_response(json_value.get("id").cloned(), &error);
+    }
+
+    // A reconnecting SSE client sends the last event id it saw as a header
+    if resubscribe {
+        if let Some(last_event_id) = headers.get("last-event-id").and_then(|v| v.to_str().ok()) {
+            if let Some(params) = json_value.get_mut("params").and_then(Value::as_object_mut) {
+                params
+                    .entry("from_event_id")
+                    .or_insert_with(|| Value::String(last_event_id.to_string()));
+            }
+        }
+    }
 
     // Parse the JSON-RPC request to get the ID
     let jsonrpc_request = match state.handler.parse_request(json_value.clone()) {
@@ -382,8 +1177,26 @@ async fn handle_streaming_request(
         }
     };
 
-    // Get the streaming SSE stream
-    match state.handler.handle_message_stream_sse(jsonrpc_request, &context).await {
+    // Get the streaming SSE stream, isolating panics as for other requests
+    let opened = if resubscribe {
+        AssertUnwindSafe(
+            trace_context
+                .scope(state.handler.handle_resubscribe_sse(jsonrpc_request, &context))
+                .instrument(span),
+        )
+        .catch_unwind()
+        .await
+    } else {
+        AssertUnwindSafe(
+            trace_context
+                .scope(state.handler.handle_message_stream_sse(jsonrpc_request, &context))
+                .instrument(span),
+        )
+        .catch_unwind()
+        .await
+    };
+    let result = opened.unwrap_or_else(|panic| Err(record_panic(&state, method, panic.as_ref())));
+    match result {
         Ok(sse_stream) => {
             let mut response_headers = HeaderMap::new();
             
@@ -402,14 +1215,54 @@ async fn handle_streaming_request(
                 );
             }
 
-            // Convert SSE stream to Axum response
-            let body_stream = sse_stream.map(|result| {
-                match result {
-                    Ok(sse_data) => Ok::<axum::body::Bytes, axum::Error>(axum::body::Bytes::from(sse_data)),
-                    Err(_) => Ok::<axum::body::Bytes, axum::Error>(axum::body::Bytes::from("data: {\"error\":\"Stream error\"}\n\n")),
+            // Convert SSE stream to Axum response, metering each chunk. A panic
+            // while producing an event ends this stream with an error event.
+            let quota_store = state.quota_store.clone();
+            let request_id = json_value.get("id").cloned();
+            let panic_state = state.clone();
+            let body_stream = AssertUnwindSafe(sse_stream).catch_unwind().then(move |result| {
+                let quota_store = quota_store.clone();
+                let tenant = tenant.clone();
+                let request_id = request_id.clone();
+                let panic_state = panic_state.clone();
+                async move {
+                    let sse_data = match result {
+                        Ok(Ok(sse_data)) => sse_data,
+                        Ok(Err(_)) => "data: {\"error\":\"Stream error\"}\n\n".to_string(),
+                        Err(panic) => {
+                            let error = record_panic(&panic_state, method, panic.as_ref());
+                            let response = crate::a2a::jsonrpc::JSONRPCErrorResponse::new(
+                                request_id.and_then(|id| serde_json::from_value(id).ok()),
+                                error,
+                            );
+                            format!("data: {}\n\n", serde_json::to_string(&response).unwrap_or_default())
+                        }
+                    };
+                    if let Some(quota_store) = quota_store {
+                        let artifact_bytes = sse_data
+                            .lines()
+                            .find_map(|line| line.strip_prefix("data: "))
+                            .and_then(|data| serde_json::from_str::<Value>(data.trim()).ok())
+                            .and_then(|json| json.get("result").map(|result| quota::artifact_bytes(method, result)))
+                            .unwrap_or(0);
+                        if let Err(e) = quota_store
+                            .record_response(&tenant, sse_data.len() as u64, artifact_bytes)
+                            .await
+                        {
+                            error!("Failed to record response usage: {}", e);
+                        }
+                    }
+                    Ok::<axum::body::Bytes, axum::Error>(axum::body::Bytes::from(sse_data))
                 }
             });
 
+            let body_stream = stream_tracing::TracedStream::new(Box::pin(body_stream), &request_span, method);
+            let body_stream = heartbeat::HeartbeatStream::new(
+                Box::pin(body_stream),
+                state.config.heartbeat_settings(),
+                state.metrics.clone(),
+            );
+
             let response = axum::response::Response::builder()
                 .status(StatusCode::OK)
                 .header("Content-Type", "text/event-stream")
@@ -432,12 +1285,30 @@ async fn handle_non_streaming_request(
     state: ServerState,
     headers: HeaderMap,
     json_value: Value,
+    bytes_in: usize,
 ) -> Response {
     // Build server call context
-    let context = state.context_builder.build(&headers).await;
+    let mut context = build_call_context(&state, &headers).await;
+    let trace_context = context.trace_context_or_root();
+    let method = json_value.get("method").and_then(|m| m.as_str()).unwrap_or("");
+    let span = request_span(method, &trace_context);
+
+    // Enforce tenant quotas
+    let tenant = quota::tenant_for(&context);
+    if let Err(error) = check_quota(&state, &tenant, method, bytes_in).await {
+        return error_response(json_value.get("id").cloned(), &error);
+    }
 
-    // Handle the request
-    match state.handler.handle_request(json_value.clone(), &context).await {
+    // Handle the request, catching a panic so that it only fails this request
+    let handled = AssertUnwindSafe(
+        trace_context
+            .scope(state.handler.handle_request(json_value.clone(), &context))
+            .instrument(span),
+    )
+    .catch_unwind()
+    .await;
+    let result = handled.unwrap_or_else(|panic| Err(record_panic(&state, method, panic.as_ref())));
+    match result {
         Ok(response) => {
             let mut response_headers = HeaderMap::new();
             
@@ -451,12 +1322,105 @@ async fn handle_non_streaming_request(
                 );
             }
 
+            if let Some(quota_store) = &state.quota_store {
+                let bytes_out = serde_json::to_vec(&response).map(|v| v.len()).unwrap_or(0);
+                let artifact_bytes = response
+                    .get("result")
+                    .map(|result| quota::artifact_bytes(method, result))
+                    .unwrap_or(0);
+                if let Err(e) = quota_store
+                    .record_response(&tenant, bytes_out as u64, artifact_bytes)
+                    .await
+                {
+                    error!("Failed to record response usage: {}", e);
+                }
+            }
+
             (StatusCode::OK, response_headers, Json(response)).into_response()
         }
         Err(error) => error_response(json_value.get("id").cloned(), &error),
     }
 }
 
+/// Build the server call context, extracting the W3C trace context from the
+/// request headers if the configured builder did not already do so
+async fn build_call_context(state: &ServerState, headers: &HeaderMap) -> ServerCallContext {
+    let mut context = state.context_builder.build(headers).await;
+    if context.trace_context.is_none() {
+        context.trace_context = TraceContext::from_headers(headers).map(|parent| parent.child());
+    }
+    if context.accepted_languages.is_empty() {
+        context.accepted_languages = crate::a2a::server::context::accepted_languages(headers);
+    }
+    context
+}
+
+/// Check the tenant's quotas and record the incoming request
+async fn check_quota(
+    state: &ServerState,
+    tenant: &str,
+    method: &str,
+    bytes_in: usize,
+) -> Result<(), crate::a2a::jsonrpc::JSONRPCError> {
+    let Some(quota_store) = &state.quota_store else {
+        return Ok(());
+    };
+
+    let is_message = method == "message/send" || method == "message/stream";
+    match quota_store
+        .check_and_record_request(tenant, is_message, bytes_in as u64)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            info!("Rejecting request from tenant '{}': {}", tenant, e);
+            let error: crate::a2a::error::A2AError = e.into();
+            Err(error.into())
+        }
+    }
+}
+
+/// Create the tracing span for a single A2A request
+fn request_span(method: &str, trace_context: &TraceContext) -> tracing::Span {
+    tracing::info_span!(
+        "a2a.request",
+        method = %method,
+        trace_id = %trace_context.trace_id,
+        span_id = %trace_context.span_id,
+        parent_span_id = trace_context.parent_span_id.as_deref().unwrap_or(""),
+    )
+}
+
+/// Logs and counts a handler panic, returning the error sent to the client
+///
+/// The client only sees a correlation id; the panic message stays in the log
+/// entry carrying the same id.
+fn record_panic(
+    state: &ServerState,
+    method: &str,
+    panic: &(dyn std::any::Any + Send),
+) -> crate::a2a::jsonrpc::JSONRPCError {
+    let correlation_id = uuid::Uuid::new_v4().to_string();
+    let message = panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("non-string panic payload");
+    error!(correlation_id = %correlation_id, method, "Request handler panicked: {}", message);
+    if let Some(metrics) = &state.metrics {
+        metrics.record_handler_panic();
+    }
+
+    let error: A2AError = crate::a2a::error::InternalError {
+        code: crate::a2a::jsonrpc::standard_error_codes::INTERNAL_ERROR,
+        message: format!("Internal error while handling the request (correlation id {})", correlation_id),
+        data: Some(serde_json::json!({ "correlation_id": correlation_id })),
+        origin: None,
+    }
+    .into();
+    error.into()
+}
+
 /// Create an error response
 fn error_response(
     request_id: Option<Value>,
@@ -0,0 +1,238 @@
+//! Tracing of SSE responses
+//!
+//! Every streaming response gets an `a2a.stream` span under its request
+//! span. Each event sent opens a short debug level `a2a.stream.event` span
+//! under it, carrying the event kind, its sequence number from 1 and its
+//! size in bytes. When the stream closes, the stream span records the number of
+//! events and bytes sent, the duration and why it ended:
+//!
+//! - `ended`: the event stream finished
+//! - `error`: the event stream finished with an error event
+//! - `dropped`: the response was dropped before the event stream finished,
+//!   because the client went away or the stream was reaped as stale
+//!
+//! Heartbeats are not events and are not traced.
+
+use axum::body::Bytes;
+use futures::stream::{BoxStream, Stream};
+use serde_json::Value;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tracing::field::Empty;
+
+type BodyStream = BoxStream<'static, Result<Bytes, axum::Error>>;
+
+/// An SSE body tracing the events it passes through
+pub(crate) struct TracedStream {
+    inner: BodyStream,
+    span: tracing::Span,
+    started: Instant,
+    events: u64,
+    bytes: u64,
+    last_kind: Option<String>,
+    closed: bool,
+}
+
+impl TracedStream {
+    /// Wraps `inner`, opening the stream span under `parent`
+    pub(crate) fn new(inner: BodyStream, parent: &tracing::Span, method: &str) -> Self {
+        let span = tracing::info_span!(
+            parent: parent,
+            "a2a.stream",
+            method = %method,
+            task_id = Empty,
+            events_sent = Empty,
+            bytes_sent = Empty,
+            duration_ms = Empty,
+            termination = Empty,
+        );
+        Self {
+            inner,
+            span,
+            started: Instant::now(),
+            events: 0,
+            bytes: 0,
+            last_kind: None,
+            closed: false,
+        }
+    }
+
+    fn record_event(&mut self, chunk: &Bytes) {
+        self.events += 1;
+        self.bytes += chunk.len() as u64;
+        let data = event_data(chunk);
+        let kind = data.as_ref().map_or("unknown", event_kind).to_string();
+        if let Some(task_id) = data.as_ref().and_then(task_id) {
+            self.span.record("task_id", task_id);
+        }
+        let event_span = tracing::debug_span!(
+            parent: &self.span,
+            "a2a.stream.event",
+            kind = %kind,
+            sequence = self.events,
+            bytes = chunk.len(),
+        );
+        event_span.in_scope(|| tracing::debug!("Sent stream event"));
+        self.last_kind = Some(kind);
+    }
+
+    fn close(&mut self, termination: &str) {
+        if self.closed {
+            return;
+        }
+        self.closed = true;
+        let duration_ms = self.started.elapsed().as_millis() as u64;
+        self.span.record("events_sent", self.events);
+        self.span.record("bytes_sent", self.bytes);
+        self.span.record("duration_ms", duration_ms);
+        self.span.record("termination", termination);
+        self.span.in_scope(|| {
+            tracing::info!(
+                events_sent = self.events,
+                duration_ms,
+                termination,
+                "Stream closed"
+            )
+        });
+    }
+}
+
+impl Stream for TracedStream {
+    type Item = Result<Bytes, axum::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let polled = self.inner.as_mut().poll_next(cx);
+        match &polled {
+            Poll::Ready(Some(Ok(chunk))) => self.record_event(chunk),
+            Poll::Ready(Some(Err(_))) => self.last_kind = Some("error".to_string()),
+            Poll::Ready(None) => {
+                let termination = if self.last_kind.as_deref() == Some("error") { "error" } else { "ended" };
+                self.close(termination);
+            }
+            Poll::Pending => {}
+        }
+        polled
+    }
+}
+
+impl Drop for TracedStream {
+    fn drop(&mut self) {
+        self.close("dropped");
+    }
+}
+
+/// The JSON payload of an SSE chunk
+fn event_data(chunk: &[u8]) -> Option<Value> {
+    let text = std::str::from_utf8(chunk).ok()?;
+    let data = text.lines().find_map(|line| line.strip_prefix("data:"))?;
+    serde_json::from_str(data.trim()).ok()
+}
+
+/// `error`, or the kind of the streamed result
+fn event_kind(data: &Value) -> &str {
+    if data.get("error").is_some() {
+        return "error";
+    }
+    data.get("result")
+        .and_then(|result| result.get("kind"))
+        .and_then(Value::as_str)
+        .unwrap_or("unknown")
+}
+
+fn task_id(data: &Value) -> Option<&str> {
+    let result = data.get("result")?;
+    let id = match result.get("kind").and_then(Value::as_str) {
+        Some("task") => result.get("id"),
+        _ => result.get("taskId"),
+    };
+    id.and_then(Value::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use std::collections::HashMap;
+    use std::fmt::Debug;
+    use std::sync::{Arc, Mutex};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing_subscriber::layer::{Context as LayerContext, SubscriberExt};
+    use tracing_subscriber::Layer;
+
+    /// Collects the fields of every span, by span name
+    #[derive(Clone, Default)]
+    struct Spans(Arc<Mutex<Vec<(String, HashMap<String, String>)>>>);
+
+    struct Fields<'a>(&'a mut HashMap<String, String>);
+
+    impl tracing::field::Visit for Fields<'_> {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn Debug) {
+            self.0.insert(field.name().to_string(), format!("{:?}", value).trim_matches('"').to_string());
+        }
+    }
+
+    impl<S: tracing::Subscriber> Layer<S> for Spans {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _ctx: LayerContext<'_, S>) {
+            let mut fields = HashMap::from([("id".to_string(), id.into_u64().to_string())]);
+            attrs.record(&mut Fields(&mut fields));
+            self.0.lock().unwrap().push((attrs.metadata().name().to_string(), fields));
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: LayerContext<'_, S>) {
+            let mut spans = self.0.lock().unwrap();
+            let id = id.into_u64().to_string();
+            if let Some((_, fields)) = spans.iter_mut().find(|(_, fields)| fields["id"] == id) {
+                values.record(&mut Fields(fields));
+            }
+        }
+    }
+
+    impl Spans {
+        fn named(&self, name: &str) -> Vec<HashMap<String, String>> {
+            let spans = self.0.lock().unwrap();
+            spans.iter().filter(|(span, _)| span == name).map(|(_, fields)| fields.clone()).collect()
+        }
+    }
+
+    fn chunks(chunks: Vec<&'static str>) -> BodyStream {
+        Box::pin(futures::stream::iter(chunks.into_iter().map(|chunk| Ok(Bytes::from_static(chunk.as_bytes())))))
+    }
+
+    #[tokio::test]
+    async fn test_events_and_summary_are_traced() {
+        let spans = Spans::default();
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(spans.clone()));
+        let request = tracing::info_span!("a2a.request");
+        let body = chunks(vec![
+            "id: 1\ndata: {\"result\":{\"kind\":\"task\",\"id\":\"task-1\"}}\n\n",
+            "data: {\"result\":{\"kind\":\"status-update\",\"taskId\":\"task-1\"}}\n\n",
+        ]);
+        let stream = TracedStream::new(body, &request, "message/stream");
+        assert_eq!(stream.collect::<Vec<_>>().await.len(), 2);
+
+        let events = spans.named("a2a.stream.event");
+        let kinds: Vec<_> = events.iter().map(|event| (event["kind"].as_str(), event["sequence"].as_str())).collect();
+        assert_eq!(kinds, vec![("task", "1"), ("status-update", "2")]);
+        let summary = &spans.named("a2a.stream")[0];
+        assert_eq!(summary["task_id"], "task-1");
+        assert_eq!(summary["events_sent"], "2");
+        assert_eq!(summary["termination"], "ended");
+    }
+
+    #[tokio::test]
+    async fn test_termination_reason_is_recorded() {
+        let spans = Spans::default();
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(spans.clone()));
+        let request = tracing::info_span!("a2a.request");
+
+        let failing = TracedStream::new(chunks(vec!["data: {\"error\":{\"code\":-32603}}\n\n"]), &request, "message/stream");
+        failing.collect::<Vec<_>>().await;
+        let mut dropped = TracedStream::new(chunks(vec!["data: {}\n\n", "data: {}\n\n"]), &request, "tasks/resubscribe");
+        dropped.next().await;
+        drop(dropped);
+
+        let terminations: Vec<_> = spans.named("a2a.stream").into_iter().map(|span| span["termination"].clone()).collect();
+        assert_eq!(terminations, vec!["error", "dropped"]);
+    }
+}
@@ -0,0 +1,167 @@
+//! The resumable upload endpoint
+//!
+//! Served under the path of the `UploadManager`'s base URL:
+//!
+//! - `POST {path}` with `Upload-Length`, and optionally `Upload-Content-Type`,
+//!   creates an upload, answering `201 Created` with its URI in `Location`
+//!   and the `Upload` as JSON
+//! - `PATCH {path}/{id}` with `Upload-Offset` appends the body as the chunk
+//!   at that offset, answering `204 No Content` with the new `Upload-Offset`,
+//!   or `409 Conflict` with the expected one
+//! - `HEAD {path}/{id}` reports `Upload-Offset` and `Upload-Length`, so an
+//!   interrupted client knows where to resume
+//! - `GET {path}/{id}` streams a finished upload, with range support
+//! - `DELETE {path}/{id}` discards an upload
+//!
+//! Uploads are only visible to the user who created them.
+
+use super::artifacts::{authenticated_context, stored_response};
+use super::ServerState;
+use crate::a2a::server::uploads::{
+    Upload, UploadError, UploadManager, UPLOAD_CONTENT, UPLOAD_CONTENT_TYPE_HEADER, UPLOAD_LENGTH_HEADER,
+    UPLOAD_OFFSET_HEADER,
+};
+use axum::body::Body;
+use axum::extract::{Path as UrlPath, Request, State};
+use axum::http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Json, Response};
+use tracing::error;
+
+/// The uploads and the caller's user name, or the response refusing the call
+async fn caller(state: &ServerState, headers: &HeaderMap) -> Result<(UploadManager, String), Response> {
+    let Some(uploads) = state.uploads.clone() else {
+        return Err(StatusCode::NOT_FOUND.into_response());
+    };
+    let context = authenticated_context(state, headers).await?;
+    Ok((uploads, context.user.username().to_string()))
+}
+
+fn u64_header(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+fn progress_headers(upload: &Upload) -> [(HeaderName, HeaderValue); 3] {
+    [
+        (HeaderName::from_static(UPLOAD_OFFSET_HEADER), HeaderValue::from(upload.offset)),
+        (HeaderName::from_static(UPLOAD_LENGTH_HEADER), HeaderValue::from(upload.length)),
+        (header::CACHE_CONTROL, HeaderValue::from_static("no-store")),
+    ]
+}
+
+fn error_response(error: UploadError) -> Response {
+    match &error {
+        UploadError::NotFound(_) => StatusCode::NOT_FOUND.into_response(),
+        UploadError::OffsetMismatch { expected, .. } => (
+            StatusCode::CONFLICT,
+            [(HeaderName::from_static(UPLOAD_OFFSET_HEADER), HeaderValue::from(*expected))],
+            error.to_string(),
+        )
+            .into_response(),
+        UploadError::TooLarge { .. } => (StatusCode::PAYLOAD_TOO_LARGE, error.to_string()).into_response(),
+        UploadError::Incomplete { .. } => (StatusCode::CONFLICT, error.to_string()).into_response(),
+        UploadError::Store(_) => {
+            error!("Upload failed: {}", error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// HTTP handler creating an upload
+pub(super) async fn create_upload(State(state): State<ServerState>, headers: HeaderMap) -> Response {
+    let (uploads, owner) = match caller(&state, &headers).await {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+    let Some(length) = u64_header(&headers, UPLOAD_LENGTH_HEADER) else {
+        return (StatusCode::BAD_REQUEST, "Upload-Length header is required").into_response();
+    };
+    let mime_type = headers
+        .get(UPLOAD_CONTENT_TYPE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    match uploads.create(&owner, length, mime_type).await {
+        Ok(upload) => {
+            let mut response = (StatusCode::CREATED, progress_headers(&upload), Json(&upload)).into_response();
+            if let Ok(location) = HeaderValue::from_str(&upload.uri) {
+                response.headers_mut().insert(header::LOCATION, location);
+            }
+            response
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+/// HTTP handler appending a chunk to an upload
+pub(super) async fn append_upload(
+    State(state): State<ServerState>,
+    UrlPath(upload_id): UrlPath<String>,
+    headers: HeaderMap,
+    request: Request,
+) -> Response {
+    let (uploads, owner) = match caller(&state, &headers).await {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+    let Some(offset) = u64_header(&headers, UPLOAD_OFFSET_HEADER) else {
+        return (StatusCode::BAD_REQUEST, "Upload-Offset header is required").into_response();
+    };
+    let limit = state.config.max_content_length.unwrap_or(usize::MAX);
+    let chunk = match axum::body::to_bytes(request.into_body(), limit).await {
+        Ok(chunk) => chunk,
+        Err(_) => return (StatusCode::PAYLOAD_TOO_LARGE, "Upload chunk is too large").into_response(),
+    };
+
+    match uploads.append(&owner, &upload_id, offset, chunk.to_vec()).await {
+        Ok(upload) => (StatusCode::NO_CONTENT, progress_headers(&upload)).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// HTTP handler reporting the progress of an upload
+pub(super) async fn upload_status(
+    State(state): State<ServerState>,
+    UrlPath(upload_id): UrlPath<String>,
+    headers: HeaderMap,
+) -> Response {
+    let (uploads, owner) = match caller(&state, &headers).await {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+    match uploads.status(&owner, &upload_id).await {
+        Ok(upload) => (StatusCode::OK, progress_headers(&upload), Body::empty()).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// HTTP handler streaming a finished upload
+pub(super) async fn get_upload(
+    State(state): State<ServerState>,
+    UrlPath(upload_id): UrlPath<String>,
+    headers: HeaderMap,
+) -> Response {
+    let (uploads, owner) = match caller(&state, &headers).await {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+    match uploads.stored(&owner, &upload_id).await {
+        Ok(stored) => stored_response(uploads.store().as_ref(), &upload_id, UPLOAD_CONTENT, stored, &headers).await,
+        Err(e) => error_response(e),
+    }
+}
+
+/// HTTP handler discarding an upload
+pub(super) async fn delete_upload(
+    State(state): State<ServerState>,
+    UrlPath(upload_id): UrlPath<String>,
+    headers: HeaderMap,
+) -> Response {
+    let (uploads, owner) = match caller(&state, &headers).await {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+    match uploads.delete(&owner, &upload_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => error_response(e),
+    }
+}
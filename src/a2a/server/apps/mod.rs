@@ -3,7 +3,11 @@
 //! This module contains server implementations for different protocols
 //! supported by the A2A specification.
 
+#[cfg(feature = "admin-api")]
+pub mod admin;
+#[cfg(feature = "dashboard")]
+pub mod dashboard;
 pub mod jsonrpc;
 
 // Re-export commonly used types
-pub use jsonrpc::{A2AServer, A2AServerBuilder};
+pub use jsonrpc::{A2AServer, A2AServerBuilder, BuildError};
@@ -0,0 +1,670 @@
+//! Artifact bytes served over a dedicated HTTP endpoint
+//!
+//! Large files inside JSON-RPC results make every `tasks/get` expensive and
+//! cannot be fetched partially. With an `ArtifactStore` configured, the
+//! `ArtifactLinkingRequestHandler` moves the inline bytes of file artifacts
+//! into the store and replaces them with a URL of the server's
+//! `/artifacts/{task_id}/{artifact_id}` endpoint, which streams the stored
+//! bytes with range request support.
+//!
+//! A stored artifact holds the content of one file part, so only artifacts
+//! made of a single inline file part are linked; others are returned as is.
+//! Stored bytes are keyed by task and artifact id and never rewritten by a
+//! later response, while artifact update events with `append` extend them.
+
+use crate::a2a::core_types::{FileContent, FileWithUri, Part, PartRoot};
+use crate::a2a::error::A2AError;
+use crate::a2a::models::*;
+use crate::a2a::server::context::ServerCallContext;
+use crate::a2a::server::request_handlers::request_handler::{
+    Event, MessageSendResult, RequestHandler, StreamEvent, TaskPushNotificationConfigQueryParams,
+};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio::sync::RwLock;
+use tracing::warn;
+
+/// Size of the chunks stored bytes are streamed in
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Description of stored artifact bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredArtifact {
+    /// Number of stored bytes
+    pub size: u64,
+    /// MIME type the bytes are served with, if known
+    pub mime_type: Option<String>,
+}
+
+/// Storage of artifact bytes, keyed by task and artifact id
+#[async_trait]
+pub trait ArtifactStore: Send + Sync {
+    /// Stores `bytes` as the artifact, replacing any stored before
+    async fn put(
+        &self,
+        task_id: &str,
+        artifact_id: &str,
+        bytes: Vec<u8>,
+        mime_type: Option<String>,
+    ) -> Result<(), A2AError>;
+
+    /// Appends `bytes` to the artifact, storing them if there is none yet
+    async fn append(&self, task_id: &str, artifact_id: &str, bytes: Vec<u8>) -> Result<(), A2AError>;
+
+    /// Describes the stored artifact, or None if there is none
+    async fn stat(&self, task_id: &str, artifact_id: &str) -> Result<Option<StoredArtifact>, A2AError>;
+
+    /// Streams the stored bytes within `range`, which must lie within the artifact
+    async fn read(
+        &self,
+        task_id: &str,
+        artifact_id: &str,
+        range: Range<u64>,
+    ) -> Result<BoxStream<'static, Result<Vec<u8>, A2AError>>, A2AError>;
+
+    /// Deletes every artifact stored for the task
+    async fn delete_task(&self, task_id: &str) -> Result<(), A2AError>;
+}
+
+/// MIME type and bytes of an artifact, keyed by task and artifact id
+type StoredBytes = HashMap<(String, String), (Option<String>, Arc<Vec<u8>>)>;
+
+/// Artifact store keeping the bytes in memory
+#[derive(Default)]
+pub struct InMemoryArtifactStore {
+    artifacts: RwLock<StoredBytes>,
+}
+
+impl InMemoryArtifactStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for InMemoryArtifactStore {
+    async fn put(
+        &self,
+        task_id: &str,
+        artifact_id: &str,
+        bytes: Vec<u8>,
+        mime_type: Option<String>,
+    ) -> Result<(), A2AError> {
+        let key = (task_id.to_string(), artifact_id.to_string());
+        self.artifacts.write().await.insert(key, (mime_type, Arc::new(bytes)));
+        Ok(())
+    }
+
+    async fn append(&self, task_id: &str, artifact_id: &str, bytes: Vec<u8>) -> Result<(), A2AError> {
+        let key = (task_id.to_string(), artifact_id.to_string());
+        let mut artifacts = self.artifacts.write().await;
+        let (_, stored) = artifacts.entry(key).or_insert_with(|| (None, Arc::new(Vec::new())));
+        Arc::make_mut(stored).extend_from_slice(&bytes);
+        Ok(())
+    }
+
+    async fn stat(&self, task_id: &str, artifact_id: &str) -> Result<Option<StoredArtifact>, A2AError> {
+        let key = (task_id.to_string(), artifact_id.to_string());
+        Ok(self.artifacts.read().await.get(&key).map(|(mime_type, bytes)| StoredArtifact {
+            size: bytes.len() as u64,
+            mime_type: mime_type.clone(),
+        }))
+    }
+
+    async fn read(
+        &self,
+        task_id: &str,
+        artifact_id: &str,
+        range: Range<u64>,
+    ) -> Result<BoxStream<'static, Result<Vec<u8>, A2AError>>, A2AError> {
+        let key = (task_id.to_string(), artifact_id.to_string());
+        let bytes = match self.artifacts.read().await.get(&key) {
+            Some((_, bytes)) => bytes.clone(),
+            None => return Err(A2AError::invalid_params(&format!("No artifact {} stored", artifact_id))),
+        };
+        if range.end > bytes.len() as u64 || range.start > range.end {
+            return Err(A2AError::invalid_params("Range is outside the artifact"));
+        }
+        let chunks: Vec<Result<Vec<u8>, A2AError>> = bytes[range.start as usize..range.end as usize]
+            .chunks(CHUNK_SIZE)
+            .map(|chunk| Ok(chunk.to_vec()))
+            .collect();
+        Ok(stream::iter(chunks).boxed())
+    }
+
+    async fn delete_task(&self, task_id: &str) -> Result<(), A2AError> {
+        self.artifacts.write().await.retain(|(stored_task_id, _), _| stored_task_id != task_id);
+        Ok(())
+    }
+}
+
+/// Artifact store keeping the bytes in files under a directory
+///
+/// Each task gets a subdirectory holding one file per artifact, next to a
+/// `.mime` file with its MIME type. Ids are hex encoded in the file names, so
+/// any id is a safe path component.
+#[derive(Debug, Clone)]
+pub struct FileArtifactStore {
+    root: PathBuf,
+}
+
+impl FileArtifactStore {
+    /// Stores artifacts under `root`, which is created on the first write
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn task_dir(&self, task_id: &str) -> PathBuf {
+        self.root.join(hex_name(task_id))
+    }
+
+    fn artifact_path(&self, task_id: &str, artifact_id: &str) -> PathBuf {
+        self.task_dir(task_id).join(hex_name(artifact_id))
+    }
+
+    fn mime_path(&self, task_id: &str, artifact_id: &str) -> PathBuf {
+        self.task_dir(task_id).join(format!("{}.mime", hex_name(artifact_id)))
+    }
+
+    async fn create_task_dir(&self, task_id: &str) -> Result<(), A2AError> {
+        tokio::fs::create_dir_all(self.task_dir(task_id)).await.map_err(io_error)
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for FileArtifactStore {
+    async fn put(
+        &self,
+        task_id: &str,
+        artifact_id: &str,
+        bytes: Vec<u8>,
+        mime_type: Option<String>,
+    ) -> Result<(), A2AError> {
+        self.create_task_dir(task_id).await?;
+        tokio::fs::write(self.artifact_path(task_id, artifact_id), bytes).await.map_err(io_error)?;
+        let mime_path = self.mime_path(task_id, artifact_id);
+        match mime_type {
+            Some(mime_type) => tokio::fs::write(mime_path, mime_type).await.map_err(io_error),
+            None => match tokio::fs::remove_file(mime_path).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_error(e)),
+                _ => Ok(()),
+            },
+        }
+    }
+
+    async fn append(&self, task_id: &str, artifact_id: &str, bytes: Vec<u8>) -> Result<(), A2AError> {
+        use tokio::io::AsyncWriteExt;
+
+        self.create_task_dir(task_id).await?;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.artifact_path(task_id, artifact_id))
+            .await
+            .map_err(io_error)?;
+        file.write_all(&bytes).await.map_err(io_error)?;
+        file.flush().await.map_err(io_error)
+    }
+
+    async fn stat(&self, task_id: &str, artifact_id: &str) -> Result<Option<StoredArtifact>, A2AError> {
+        let size = match tokio::fs::metadata(self.artifact_path(task_id, artifact_id)).await {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(io_error(e)),
+        };
+        let mime_type = match tokio::fs::read_to_string(self.mime_path(task_id, artifact_id)).await {
+            Ok(mime_type) => Some(mime_type),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(io_error(e)),
+        };
+        Ok(Some(StoredArtifact { size, mime_type }))
+    }
+
+    async fn read(
+        &self,
+        task_id: &str,
+        artifact_id: &str,
+        range: Range<u64>,
+    ) -> Result<BoxStream<'static, Result<Vec<u8>, A2AError>>, A2AError> {
+        let mut file = tokio::fs::File::open(self.artifact_path(task_id, artifact_id))
+            .await
+            .map_err(io_error)?;
+        file.seek(std::io::SeekFrom::Start(range.start)).await.map_err(io_error)?;
+        let mut remaining = range.end.saturating_sub(range.start);
+        Ok(Box::pin(async_stream::stream! {
+            while remaining > 0 {
+                let mut chunk = vec![0; remaining.min(CHUNK_SIZE as u64) as usize];
+                match file.read(&mut chunk).await {
+                    Ok(0) => {
+                        yield Err(A2AError::internal("Artifact file ended before the requested range"));
+                        break;
+                    }
+                    Ok(read) => {
+                        chunk.truncate(read);
+                        remaining -= read as u64;
+                        yield Ok(chunk);
+                    }
+                    Err(e) => {
+                        yield Err(io_error(e));
+                        break;
+                    }
+                }
+            }
+        }))
+    }
+
+    async fn delete_task(&self, task_id: &str) -> Result<(), A2AError> {
+        match tokio::fs::remove_dir_all(self.task_dir(task_id)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_error(e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn hex_name(id: &str) -> String {
+    id.bytes().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn io_error(error: std::io::Error) -> A2AError {
+    A2AError::internal(&format!("Artifact store I/O failed: {}", error))
+}
+
+/// Request handler that links file artifacts to the artifact endpoint
+///
+/// Inline file bytes of artifacts in returned tasks and streamed events are
+/// moved into the store, and the parts point at
+/// `{base_url}/{task_id}/{artifact_id}` instead.
+pub struct ArtifactLinkingRequestHandler {
+    inner: Arc<dyn RequestHandler>,
+    linker: ArtifactLinker,
+}
+
+/// Moves inline artifact files into the store, shared with linked streams
+#[derive(Clone)]
+struct ArtifactLinker {
+    store: Arc<dyn ArtifactStore>,
+    base_url: url::Url,
+    min_bytes: usize,
+}
+
+impl ArtifactLinkingRequestHandler {
+    /// Links artifacts to URLs under `base_url`, such as
+    /// `https://agent.example.com/artifacts`
+    pub fn new(inner: Arc<dyn RequestHandler>, store: Arc<dyn ArtifactStore>, base_url: url::Url) -> Self {
+        Self {
+            inner,
+            linker: ArtifactLinker {
+                store,
+                base_url,
+                min_bytes: 0,
+            },
+        }
+    }
+
+    /// Keeps files smaller than `min_bytes` inline
+    pub fn with_min_bytes(mut self, min_bytes: usize) -> Self {
+        self.linker.min_bytes = min_bytes;
+        self
+    }
+
+    /// The URL the artifact is served at
+    pub fn artifact_url(&self, task_id: &str, artifact_id: &str) -> String {
+        self.linker.artifact_url(task_id, artifact_id)
+    }
+
+    fn link_events(
+        &self,
+        events: BoxStream<'static, Result<Event, A2AError>>,
+    ) -> BoxStream<'static, Result<Event, A2AError>> {
+        let linker = self.linker.clone();
+        events
+            .then(move |event| {
+                let linker = linker.clone();
+                async move {
+                    match event {
+                        Ok(event) => Ok(linker.link_event(event).await),
+                        Err(e) => Err(e),
+                    }
+                }
+            })
+            .boxed()
+    }
+
+    fn link_stream_events(
+        &self,
+        events: BoxStream<'static, Result<StreamEvent, A2AError>>,
+    ) -> BoxStream<'static, Result<StreamEvent, A2AError>> {
+        let linker = self.linker.clone();
+        events
+            .then(move |event| {
+                let linker = linker.clone();
+                async move {
+                    match event {
+                        Ok(event) => Ok(StreamEvent::new(linker.link_event(event.event).await, event.event_id)),
+                        Err(e) => Err(e),
+                    }
+                }
+            })
+            .boxed()
+    }
+}
+
+/// The URL of the artifact endpoint under `base_url` serving the artifact
+pub(crate) fn artifact_url(base_url: &url::Url, task_id: &str, artifact_id: &str) -> String {
+    let mut url = base_url.clone();
+    if let Ok(mut segments) = url.path_segments_mut() {
+        segments.pop_if_empty().push(task_id).push(artifact_id);
+    }
+    url.to_string()
+}
+
+impl ArtifactLinker {
+    fn artifact_url(&self, task_id: &str, artifact_id: &str) -> String {
+        artifact_url(&self.base_url, task_id, artifact_id)
+    }
+
+    async fn link_task(&self, mut task: Task) -> Task {
+        if let Some(artifacts) = task.artifacts.as_mut() {
+            for artifact in artifacts {
+                self.link_artifact(&task.id, artifact, false).await;
+            }
+        }
+        task
+    }
+
+    async fn link_event(&self, event: Event) -> Event {
+        match event {
+            Event::Task(task) => Event::Task(self.link_task(task).await),
+            Event::TaskArtifactUpdate(mut update) => {
+                let append = update.append.unwrap_or(false);
+                self.link_artifact(&update.task_id, &mut update.artifact, append).await;
+                Event::TaskArtifactUpdate(update)
+            }
+            event => event,
+        }
+    }
+
+    /// Stores the inline file of `artifact` and points its part at the endpoint
+    ///
+    /// Bytes already stored for the artifact are kept, unless `append` adds
+    /// these to them. A failing store leaves the artifact inline.
+    async fn link_artifact(&self, task_id: &str, artifact: &mut Artifact, append: bool) {
+        let [part] = artifact.parts.as_mut_slice() else {
+            return;
+        };
+        let (Part::Direct(PartRoot::File(file_part)) | Part::WithRoot { root: PartRoot::File(file_part) }) = part else {
+            return;
+        };
+        let FileContent::Bytes(file) = &file_part.file else {
+            return;
+        };
+        let Ok(bytes) = STANDARD.decode(&file.bytes) else {
+            return;
+        };
+        if bytes.len() < self.min_bytes {
+            return;
+        }
+
+        let stored = match self.store.stat(task_id, &artifact.artifact_id).await {
+            Ok(stored) => stored,
+            Err(e) => {
+                warn!("Keeping artifact {} inline: {}", artifact.artifact_id, e);
+                return;
+            }
+        };
+        let result = match (stored, append) {
+            (Some(_), false) => Ok(()),
+            (Some(_), true) => self.store.append(task_id, &artifact.artifact_id, bytes).await,
+            (None, _) => {
+                self.store
+                    .put(task_id, &artifact.artifact_id, bytes, file.mime_type.clone())
+                    .await
+            }
+        };
+        if let Err(e) = result {
+            warn!("Keeping artifact {} inline: {}", artifact.artifact_id, e);
+            return;
+        }
+
+        file_part.file = FileContent::Uri(FileWithUri {
+            uri: self.artifact_url(task_id, &artifact.artifact_id),
+            mime_type: file.mime_type.clone(),
+            name: file.name.clone(),
+        });
+    }
+}
+
+#[async_trait]
+impl RequestHandler for ArtifactLinkingRequestHandler {
+    async fn on_get_task(
+        &self,
+        params: TaskQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        match self.inner.on_get_task(params, context).await? {
+            Some(task) => Ok(Some(self.linker.link_task(task).await)),
+            None => Ok(None),
+        }
+    }
+
+    async fn on_cancel_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        match self.inner.on_cancel_task(params, context).await? {
+            Some(task) => Ok(Some(self.linker.link_task(task).await)),
+            None => Ok(None),
+        }
+    }
+
+    async fn on_message_send(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<MessageSendResult, A2AError> {
+        match self.inner.on_message_send(params, context).await? {
+            MessageSendResult::Task(task) => Ok(MessageSendResult::Task(self.linker.link_task(task).await)),
+            message => Ok(message),
+        }
+    }
+
+    async fn on_message_send_stream(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        let events = self.inner.on_message_send_stream(params, context).await?;
+        Ok(self.link_events(events))
+    }
+
+    async fn on_message_send_stream_resumable(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        let events = self.inner.on_message_send_stream_resumable(params, context).await?;
+        Ok(self.link_stream_events(events))
+    }
+
+    async fn on_set_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfig,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_set_task_push_notification_config(params, context).await
+    }
+
+    async fn on_get_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfigQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_get_task_push_notification_config(params, context).await
+    }
+
+    async fn on_resubscribe_to_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        let events = self.inner.on_resubscribe_to_task(params, context).await?;
+        Ok(self.link_events(events))
+    }
+
+    async fn on_resubscribe_to_task_resumable(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        let events = self.inner.on_resubscribe_to_task_resumable(params, context).await?;
+        Ok(self.link_stream_events(events))
+    }
+
+    async fn on_list_task_push_notification_config(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Vec<TaskPushNotificationConfig>, A2AError> {
+        self.inner.on_list_task_push_notification_config(params, context).await
+    }
+
+    async fn on_delete_task_push_notification_config(
+        &self,
+        params: DeleteTaskPushNotificationConfigParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<(), A2AError> {
+        self.inner.on_delete_task_push_notification_config(params, context).await
+    }
+}
+
+/// Parses a `Range` header value into the byte range of an artifact of `size` bytes
+///
+/// Returns `Ok(None)` for headers that are not a single byte range, which
+/// are answered with the whole artifact, and `Err(())` for ranges that lie
+/// outside the artifact.
+#[allow(clippy::result_unit_err)]
+pub fn parse_byte_range(header: &str, size: u64) -> Result<Option<Range<u64>>, ()> {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return Ok(None);
+    };
+    if spec.contains(',') {
+        return Ok(None);
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return Ok(None);
+    };
+    let (start, end) = (start.trim(), end.trim());
+    let range = if start.is_empty() {
+        let Ok(suffix) = end.parse::<u64>() else {
+            return Ok(None);
+        };
+        if suffix == 0 {
+            return Err(());
+        }
+        size.saturating_sub(suffix)..size
+    } else {
+        let Ok(start) = start.parse::<u64>() else {
+            return Ok(None);
+        };
+        let end = if end.is_empty() {
+            size
+        } else {
+            match end.parse::<u64>() {
+                Ok(end) if end >= start => end.saturating_add(1).min(size),
+                _ => return Ok(None),
+            }
+        };
+        start..end
+    };
+    if range.start >= size {
+        return Err(());
+    }
+    Ok(Some(range))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::{FilePart, TaskState, TaskStatus};
+    use crate::a2a::server::request_handlers::request_handler::MockRequestHandler;
+
+    async fn read_all(store: &dyn ArtifactStore, range: Range<u64>) -> Vec<u8> {
+        let chunks: Vec<_> = store.read("task-1", "artifact-1", range).await.unwrap().collect().await;
+        chunks.into_iter().flat_map(Result::unwrap).collect()
+    }
+
+    #[tokio::test]
+    async fn test_stores_read_ranges_and_append() {
+        let dir = std::env::temp_dir().join(format!("a2a-artifacts-{}", uuid::Uuid::new_v4()));
+        let stores: Vec<Box<dyn ArtifactStore>> =
+            vec![Box::new(InMemoryArtifactStore::new()), Box::new(FileArtifactStore::new(&dir))];
+        for store in stores {
+            let bytes: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+            store
+                .put("task-1", "artifact-1", bytes.clone(), Some("video/mp4".to_string()))
+                .await
+                .unwrap();
+            store.append("task-1", "artifact-1", vec![1, 2, 3]).await.unwrap();
+
+            let stored = store.stat("task-1", "artifact-1").await.unwrap().unwrap();
+            assert_eq!(stored, StoredArtifact { size: 200_003, mime_type: Some("video/mp4".to_string()) });
+            assert_eq!(read_all(store.as_ref(), 100_000..100_010).await, bytes[100_000..100_010]);
+            assert_eq!(read_all(store.as_ref(), 200_000..200_003).await, vec![1, 2, 3]);
+
+            store.delete_task("task-1").await.unwrap();
+            assert_eq!(store.stat("task-1", "artifact-1").await.unwrap(), None);
+        }
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_parse_byte_range() {
+        assert_eq!(parse_byte_range("bytes=0-9", 100), Ok(Some(0..10)));
+        assert_eq!(parse_byte_range("bytes=90-", 100), Ok(Some(90..100)));
+        assert_eq!(parse_byte_range("bytes=-10", 100), Ok(Some(90..100)));
+        assert_eq!(parse_byte_range("bytes=50-500", 100), Ok(Some(50..100)));
+        assert_eq!(parse_byte_range("bytes=100-", 100), Err(()));
+        assert_eq!(parse_byte_range("bytes=0-1,5-6", 100), Ok(None));
+        assert_eq!(parse_byte_range("items=0-1", 100), Ok(None));
+    }
+
+    #[tokio::test]
+    async fn test_links_single_file_artifacts() {
+        let store = Arc::new(InMemoryArtifactStore::new());
+        let handler = ArtifactLinkingRequestHandler::new(
+            Arc::new(MockRequestHandler::new()),
+            store.clone(),
+            url::Url::parse("https://agent.example.com/artifacts").unwrap(),
+        );
+        let mut file = FilePart::new_bytes(STANDARD.encode(b"report"));
+        if let FileContent::Bytes(bytes) = &mut file.file {
+            bytes.mime_type = Some("application/pdf".to_string());
+        }
+        let linked = Artifact::new(vec![file.into()]).with_artifact_id("report 1".to_string());
+        let mixed = Artifact::new(vec![Part::text("two".to_string()), Part::file_bytes(STANDARD.encode(b"parts"))]);
+        let task = Task::new("ctx-1".to_string(), TaskStatus::new(TaskState::Completed))
+            .with_task_id("task-1".to_string())
+            .with_artifacts(vec![linked, mixed.clone()]);
+
+        let task = handler.linker.link_task(task).await;
+        let artifacts = task.artifacts.unwrap();
+        let PartRoot::File(FilePart { file: FileContent::Uri(file), .. }) = artifacts[0].parts[0].root() else {
+            panic!("expected a linked file");
+        };
+        assert_eq!(file.uri, "https://agent.example.com/artifacts/task-1/report%201");
+        assert_eq!(file.mime_type.as_deref(), Some("application/pdf"));
+        assert_eq!(artifacts[1], mixed);
+
+        let stored = store.stat("task-1", "report 1").await.unwrap().unwrap();
+        assert_eq!(stored.size, 6);
+    }
+}
@@ -0,0 +1,561 @@
+//! Budget limits on agent consumption
+//!
+//! `BudgetRequestHandler` enforces limits on the usage executors report
+//! through a `UsageRecorder`, per context and per principal. A budget is
+//! exhausted once the usage it covers reaches its limit:
+//!
+//! - New `message/send` and `message/stream` calls over budget are rejected
+//!   with a `BudgetExceeded` error.
+//! - Streams already running end with a terminal `failed` status explaining
+//!   which budget ran out. The failed status is saved through a
+//!   `TaskManager`, the task is canceled in the wrapped handler, and no
+//!   further events of the task are forwarded.
+//!
+//! With `with_task_events`, the failure is published on the event bus of a
+//! `DefaultRequestHandler` like its own task events, so resubscribers, push
+//! notifications and the event store see it too.
+//!
+//! Budgets are checked against the context and principal totals the task
+//! store sums on every call and streamed event.
+
+use crate::a2a::core_types::{Message, Part, Role, TaskState, TaskStatus};
+use crate::a2a::error::{A2AError, JSONRPCError};
+use crate::a2a::jsonrpc::error_codes;
+use crate::a2a::models::*;
+use crate::a2a::server::context::ServerCallContext;
+use crate::a2a::server::events::{event_id, EventBus};
+use crate::a2a::server::quota::{tenant_for, ANONYMOUS_TENANT};
+use crate::a2a::server::request_handlers::request_handler::{
+    Event, MessageSendResult, RequestHandler, StreamEvent, TaskPushNotificationConfigQueryParams,
+};
+use crate::a2a::server::request_handlers::DefaultRequestHandler;
+use crate::a2a::server::tasks::{StateTransitionHistory, TaskManager, Usage};
+use crate::a2a::server::usage::UsageRecorder;
+use async_trait::async_trait;
+use futures::stream::{BoxStream, StreamExt};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Limits on the usage a context or principal may consume; `None` means unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Budget {
+    /// Maximum number of input and output tokens together
+    pub max_tokens: Option<u64>,
+    /// Maximum number of compute units
+    pub max_compute_units: Option<f64>,
+}
+
+impl Budget {
+    /// Creates an unlimited budget
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the token limit
+    pub fn with_max_tokens(mut self, max: u64) -> Self {
+        self.max_tokens = Some(max);
+        self
+    }
+
+    /// Sets the compute unit limit
+    pub fn with_max_compute_units(mut self, max: f64) -> Self {
+        self.max_compute_units = Some(max);
+        self
+    }
+
+    /// The limit `used` exhausts, if any
+    fn exhausted_by(&self, scope: BudgetScope, subject: &str, used: Usage) -> Option<BudgetExceeded> {
+        let exceeded = |resource, limit: f64, used: f64| {
+            (used >= limit).then(|| BudgetExceeded {
+                scope,
+                subject: subject.to_string(),
+                resource,
+                limit,
+                used,
+            })
+        };
+        self.max_tokens
+            .and_then(|limit| exceeded(BudgetResource::Tokens, limit as f64, used.total_tokens() as f64))
+            .or_else(|| {
+                self.max_compute_units
+                    .and_then(|limit| exceeded(BudgetResource::ComputeUnits, limit, used.compute_units))
+            })
+    }
+}
+
+/// The budgets enforced by `BudgetRequestHandler`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BudgetLimits {
+    /// Budget of every context
+    pub per_context: Budget,
+    /// Budget of principals without a budget of their own
+    pub per_principal: Budget,
+    /// Budgets of specific principals
+    pub principals: HashMap<String, Budget>,
+}
+
+impl BudgetLimits {
+    /// Creates unlimited budgets
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the budget of every context
+    pub fn with_context_budget(mut self, budget: Budget) -> Self {
+        self.per_context = budget;
+        self
+    }
+
+    /// Sets the budget of principals without a budget of their own
+    pub fn with_principal_budget(mut self, budget: Budget) -> Self {
+        self.per_principal = budget;
+        self
+    }
+
+    /// Sets the budget of `principal`
+    pub fn with_budget_for(mut self, principal: impl Into<String>, budget: Budget) -> Self {
+        self.principals.insert(principal.into(), budget);
+        self
+    }
+
+    /// The budget applied to `principal`
+    pub fn principal_budget(&self, principal: &str) -> &Budget {
+        self.principals.get(principal).unwrap_or(&self.per_principal)
+    }
+}
+
+/// What a budget is kept for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetScope {
+    Context,
+    Principal,
+}
+
+impl fmt::Display for BudgetScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetScope::Context => write!(f, "context"),
+            BudgetScope::Principal => write!(f, "principal"),
+        }
+    }
+}
+
+/// What a budget limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetResource {
+    Tokens,
+    ComputeUnits,
+}
+
+impl fmt::Display for BudgetResource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetResource::Tokens => write!(f, "tokens"),
+            BudgetResource::ComputeUnits => write!(f, "compute units"),
+        }
+    }
+}
+
+/// A budget that has been used up
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("Budget of {limit} {resource} exhausted for {scope} '{subject}' ({used} used)")]
+pub struct BudgetExceeded {
+    pub scope: BudgetScope,
+    /// The context id or principal the budget belongs to
+    pub subject: String,
+    pub resource: BudgetResource,
+    pub limit: f64,
+    pub used: f64,
+}
+
+impl BudgetExceeded {
+    /// Returns the structured error data sent to the client
+    pub fn data(&self) -> serde_json::Value {
+        let resource = match self.resource {
+            BudgetResource::Tokens => "max_tokens",
+            BudgetResource::ComputeUnits => "max_compute_units",
+        };
+        serde_json::json!({
+            "scope": self.scope.to_string(),
+            "subject": self.subject,
+            "budget": resource,
+            "limit": self.limit,
+            "used": self.used,
+        })
+    }
+}
+
+impl From<BudgetExceeded> for A2AError {
+    fn from(err: BudgetExceeded) -> Self {
+        A2AError::Generic(JSONRPCError {
+            code: error_codes::BUDGET_EXCEEDED,
+            message: err.to_string(),
+            data: Some(err.data()),
+        })
+    }
+}
+
+/// Request handler enforcing `BudgetLimits` on the usage `UsageRecorder` collects
+pub struct BudgetRequestHandler {
+    inner: Arc<dyn RequestHandler>,
+    guard: Arc<BudgetGuard>,
+}
+
+struct BudgetGuard {
+    inner: Arc<dyn RequestHandler>,
+    usage: UsageRecorder,
+    limits: BudgetLimits,
+    /// Bus failures are published on, if any
+    event_bus: Option<EventBus>,
+    /// Whether published failures are retained and can be resumed after
+    resumable: bool,
+    state_transition_history: Option<StateTransitionHistory>,
+}
+
+impl BudgetGuard {
+    /// The first budget of `context_id` or `principal` that is exhausted
+    async fn check(&self, context_id: Option<&str>, principal: &str) -> Result<Option<BudgetExceeded>, A2AError> {
+        if let Some(context_id) = context_id {
+            let used = self.usage.context_usage(context_id).await?;
+            if let Some(exceeded) = self.limits.per_context.exhausted_by(BudgetScope::Context, context_id, used) {
+                return Ok(Some(exceeded));
+            }
+        }
+        let used = self.usage.principal_usage(principal).await?;
+        Ok(self
+            .limits
+            .principal_budget(principal)
+            .exhausted_by(BudgetScope::Principal, principal, used))
+    }
+
+    fn task_manager(&self, task_id: &str, context_id: &str) -> Result<TaskManager, A2AError> {
+        let mut task_manager = TaskManager::new(
+            Some(task_id.to_string()),
+            Some(context_id.to_string()),
+            self.usage.task_store().clone(),
+            None,
+            None,
+        )?;
+        if let Some(event_bus) = &self.event_bus {
+            task_manager = task_manager.with_event_bus(event_bus.clone());
+        }
+        Ok(match self.state_transition_history {
+            Some(history) => task_manager.with_state_transition_history(history),
+            None => task_manager,
+        })
+    }
+
+    /// Fails the task whose budget ran out and cancels it in the inner
+    /// handler, returning the terminal status event as saved
+    async fn fail(
+        &self,
+        task_id: &str,
+        context_id: &str,
+        exceeded: &BudgetExceeded,
+        context: Option<&ServerCallContext>,
+    ) -> Result<StreamEvent, A2AError> {
+        let message = Message::new(Role::Agent, vec![Part::text(exceeded.to_string())])
+            .with_task_id(task_id.to_string())
+            .with_context_id(context_id.to_string());
+        let failed = TaskStatusUpdateEvent::new(
+            task_id.to_string(),
+            context_id.to_string(),
+            TaskStatus::new(TaskState::Failed).with_message(message),
+            true,
+        );
+        let mut task_manager = self.task_manager(task_id, context_id)?;
+        let event = task_manager.process_event(&Event::TaskStatusUpdate(failed).into()).await?;
+        let published = task_manager.last_sequence().filter(|_| self.resumable);
+
+        // The task is already failed, so handlers that refuse to cancel
+        // terminal tasks only stop the work they track
+        if let Err(e) = self.inner.on_cancel_task(TaskIdParams::new(task_id.to_string()), context).await {
+            tracing::debug!("Canceling task {} over budget: {}", task_id, e);
+        }
+        Ok(StreamEvent::new(Event::from(event), published.map(event_id)))
+    }
+
+    /// The context a message is sent in, from the message or its task
+    async fn context_of(&self, message: &Message) -> Result<Option<String>, A2AError> {
+        if let Some(context_id) = &message.context_id {
+            return Ok(Some(context_id.clone()));
+        }
+        match &message.task_id {
+            Some(task_id) => Ok(self.usage.task_store().get(task_id).await?.map(|task| task.context_id)),
+            None => Ok(None),
+        }
+    }
+
+    async fn admit(&self, message: &Message, principal: &str) -> Result<(), A2AError> {
+        let context_id = self.context_of(message).await?;
+        match self.check(context_id.as_deref(), principal).await? {
+            Some(exceeded) => Err(exceeded.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Forwards `events` until a budget of their task runs out
+    fn guard<T: Send + 'static>(
+        self: Arc<Self>,
+        context: Option<&ServerCallContext>,
+        mut events: BoxStream<'static, Result<T, A2AError>>,
+        event_of: fn(&T) -> &Event,
+        wrap: fn(StreamEvent) -> T,
+    ) -> BoxStream<'static, Result<T, A2AError>> {
+        let principal = principal_of(context);
+        let context = context.cloned();
+        Box::pin(async_stream::stream! {
+            while let Some(item) = events.next().await {
+                let ids = match &item {
+                    Ok(event) => running_task(event_of(event)),
+                    Err(_) => None,
+                };
+                let Some((task_id, context_id)) = ids else {
+                    yield item;
+                    continue;
+                };
+                match self.check(Some(&context_id), &principal).await {
+                    Ok(None) => yield item,
+                    Ok(Some(exceeded)) => {
+                        yield self.fail(&task_id, &context_id, &exceeded, context.as_ref()).await.map(wrap);
+                        break;
+                    }
+                    Err(e) => {
+                        yield Err(e);
+                        break;
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// The task and context of an event that leaves its task running
+fn running_task(event: &Event) -> Option<(String, String)> {
+    match event {
+        Event::TaskStatusUpdate(update) if update.r#final || update.status.state.is_terminal() => None,
+        Event::TaskStatusUpdate(update) => Some((update.task_id.clone(), update.context_id.clone())),
+        Event::TaskArtifactUpdate(update) => Some((update.task_id.clone(), update.context_id.clone())),
+        Event::Task(task) if task.status.state.is_terminal() => None,
+        Event::Task(task) => Some((task.id.clone(), task.context_id.clone())),
+        Event::Message(message) => Some((message.task_id.clone()?, message.context_id.clone()?)),
+    }
+}
+
+fn principal_of(context: Option<&ServerCallContext>) -> String {
+    context.map(tenant_for).unwrap_or_else(|| ANONYMOUS_TENANT.to_string())
+}
+
+impl BudgetRequestHandler {
+    /// Wraps `inner`, enforcing `limits` on the totals of `usage`
+    pub fn new(inner: Arc<dyn RequestHandler>, usage: UsageRecorder, limits: BudgetLimits) -> Self {
+        Self {
+            inner: inner.clone(),
+            guard: Arc::new(BudgetGuard {
+                inner,
+                usage,
+                limits,
+                event_bus: None,
+                resumable: false,
+                state_transition_history: None,
+            }),
+        }
+    }
+
+    /// Saves budget failures the way `handler` saves its task events
+    ///
+    /// Failures are published on its event bus, tagged with resumption ids
+    /// if it retains events in an event store, and record the status
+    /// transition if it records transitions. `handler` should be the one
+    /// `inner` wraps.
+    pub fn with_task_events(mut self, handler: &DefaultRequestHandler) -> Self {
+        let guard = Arc::get_mut(&mut self.guard).expect("the budget guard is only shared by running streams");
+        guard.event_bus = Some(handler.event_bus().clone());
+        guard.resumable = handler.event_store().is_some();
+        guard.state_transition_history = handler.state_transition_history();
+        self
+    }
+}
+
+#[async_trait]
+impl RequestHandler for BudgetRequestHandler {
+    async fn on_get_task(
+        &self,
+        params: TaskQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        self.inner.on_get_task(params, context).await
+    }
+
+    async fn on_cancel_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        self.inner.on_cancel_task(params, context).await
+    }
+
+    async fn on_message_send(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<MessageSendResult, A2AError> {
+        self.guard.admit(&params.message, &principal_of(context)).await?;
+        self.inner.on_message_send(params, context).await
+    }
+
+    async fn on_message_send_stream(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        self.guard.admit(&params.message, &principal_of(context)).await?;
+        let events = self.inner.on_message_send_stream(params, context).await?;
+        Ok(self.guard.clone().guard(context, events, |event| event, |event| event.event))
+    }
+
+    async fn on_message_send_stream_resumable(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        self.guard.admit(&params.message, &principal_of(context)).await?;
+        let events = self.inner.on_message_send_stream_resumable(params, context).await?;
+        Ok(self.guard.clone().guard(context, events, |event| &event.event, |event| event))
+    }
+
+    async fn on_set_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfig,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_set_task_push_notification_config(params, context).await
+    }
+
+    async fn on_get_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfigQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_get_task_push_notification_config(params, context).await
+    }
+
+    async fn on_resubscribe_to_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        let events = self.inner.on_resubscribe_to_task(params, context).await?;
+        Ok(self.guard.clone().guard(context, events, |event| event, |event| event.event))
+    }
+
+    async fn on_resubscribe_to_task_resumable(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        let events = self.inner.on_resubscribe_to_task_resumable(params, context).await?;
+        Ok(self.guard.clone().guard(context, events, |event| &event.event, |event| event))
+    }
+
+    async fn on_list_task_push_notification_config(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Vec<TaskPushNotificationConfig>, A2AError> {
+        self.inner.on_list_task_push_notification_config(params, context).await
+    }
+
+    async fn on_delete_task_push_notification_config(
+        &self,
+        params: DeleteTaskPushNotificationConfigParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<(), A2AError> {
+        self.inner.on_delete_task_push_notification_config(params, context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::PartRoot;
+    use crate::a2a::server::request_handlers::request_handler::MockRequestHandler;
+    use crate::a2a::server::tasks::{InMemoryTaskStore, TaskStore};
+
+    const TASK_ID: &str = "mock-task-123";
+
+    async fn handler(limits: BudgetLimits) -> (BudgetRequestHandler, UsageRecorder, Arc<InMemoryTaskStore>) {
+        let store = Arc::new(InMemoryTaskStore::new());
+        let task = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id(TASK_ID.to_string());
+        store.save(task).await.unwrap();
+        let usage = UsageRecorder::new(store.clone());
+        let handler = BudgetRequestHandler::new(Arc::new(MockRequestHandler::new()), usage.clone(), limits);
+        (handler, usage, store)
+    }
+
+    fn params() -> MessageSendParams {
+        let message = Message::new(Role::User, vec![Part::text("hi".to_string())]).with_context_id("ctx".to_string());
+        MessageSendParams::new(message)
+    }
+
+    #[tokio::test]
+    async fn test_messages_over_budget_are_rejected() {
+        let limits = BudgetLimits::new()
+            .with_principal_budget(Budget::new().with_max_compute_units(1.0))
+            .with_budget_for("anonymous", Budget::new().with_max_tokens(100));
+        let (handler, usage, _) = handler(limits).await;
+        usage.record_task(TASK_ID, "anonymous", Usage::tokens(60, 30)).await.unwrap();
+        assert!(handler.on_message_send(params(), None).await.is_ok());
+
+        usage.record_task(TASK_ID, "anonymous", Usage::tokens(10, 0).with_compute_units(5.0)).await.unwrap();
+        let err = handler.on_message_send(params(), None).await.unwrap_err();
+        assert_eq!(err.code(), error_codes::BUDGET_EXCEEDED);
+        assert_eq!(err.kind(), "budget_exceeded");
+        assert_eq!(err.data().unwrap()["budget"], "max_tokens");
+        assert!(handler.on_message_send_stream(params(), None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_streams_fail_when_budget_runs_out() {
+        let limits = BudgetLimits::new().with_context_budget(Budget::new().with_max_tokens(100));
+        let (handler, usage, store) = handler(limits).await;
+        let mut events = handler.on_message_send_stream(params(), None).await.unwrap();
+        usage.record_task(TASK_ID, "anonymous", Usage::tokens(150, 0)).await.unwrap();
+
+        let Some(Ok(Event::TaskStatusUpdate(update))) = events.next().await else {
+            panic!("expected a status update");
+        };
+        assert!(update.r#final);
+        assert_eq!(update.status.state, TaskState::Failed);
+        assert!(events.next().await.is_none());
+
+        let task = store.get(TASK_ID).await.unwrap().unwrap();
+        assert_eq!(task.status.state, TaskState::Failed);
+        let Some(PartRoot::Text(text)) = task.status.message.as_ref().map(|message| message.parts[0].root().clone()) else {
+            panic!("expected an explanation");
+        };
+        assert!(text.text.contains("context 'ctx'"), "{}", text.text);
+    }
+
+    #[tokio::test]
+    async fn test_failures_are_published_like_task_events() {
+        let limits = BudgetLimits::new().with_principal_budget(Budget::new().with_max_compute_units(1.0));
+        let (handler, usage, store) = handler(limits).await;
+        let task_events = DefaultRequestHandler::new(store, None, None)
+            .with_event_store(Arc::new(crate::a2a::server::events::InMemoryEventStore::new()));
+        let handler = handler.with_task_events(&task_events);
+        let mut published = task_events.event_bus().subscribe();
+        let mut events = handler.on_message_send_stream_resumable(params(), None).await.unwrap();
+        usage.record_task(TASK_ID, "anonymous", Usage::default().with_compute_units(2.0)).await.unwrap();
+
+        let Some(Ok(failed)) = events.next().await else {
+            panic!("expected a status update");
+        };
+        let bus_event = published.recv().await.unwrap();
+        assert_eq!(failed.event_id, Some(event_id(bus_event.sequence)));
+        assert!(bus_event.is_final());
+        assert_eq!(bus_event.task.status.state, TaskState::Failed);
+    }
+}
@@ -0,0 +1,273 @@
+//! Externally reachable agent card URLs for containerized deployments
+//!
+//! An agent usually binds to an address that clients cannot reach, such as
+//! `0.0.0.0:8080` inside a container behind an ingress. `PublicEndpoint`
+//! describes the address clients use instead: public host name, port, path
+//! prefix and whether TLS is terminated in front of the agent. It is read
+//! from environment variables or from Kubernetes downward API metadata, and
+//! rewrites the URLs of an agent card so the card advertises it.
+//!
+//! Environment variables:
+//!
+//! | Variable | Meaning |
+//! |----------|---------|
+//! | `A2A_PUBLIC_HOST` | Public host name |
+//! | `A2A_PUBLIC_PORT` | Public port; the scheme's default if unset |
+//! | `A2A_PUBLIC_PATH_PREFIX` | Path the ingress routes to the agent |
+//! | `A2A_PUBLIC_TLS` | `false` for plain HTTP; TLS is assumed otherwise |
+//!
+//! Without `A2A_PUBLIC_HOST`, the pod annotations `a2a/public-host`,
+//! `a2a/public-port`, `a2a/path-prefix` and `a2a/tls` are read from a
+//! downward API volume, and as a last resort the pod IP from `POD_IP` is
+//! advertised over plain HTTP, which only suits clients within the cluster.
+
+use crate::a2a::error::A2AError;
+use crate::a2a::models::AgentCard;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Where a downward API volume conventionally exposes the pod annotations
+pub const DEFAULT_ANNOTATIONS_PATH: &str = "/etc/podinfo/annotations";
+
+/// The address clients reach the agent at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicEndpoint {
+    host: String,
+    port: Option<u16>,
+    path_prefix: String,
+    tls: bool,
+}
+
+impl PublicEndpoint {
+    /// An endpoint at `host` on the default HTTPS port
+    pub fn new(host: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            port: None,
+            path_prefix: String::new(),
+            tls: true,
+        }
+    }
+
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Sets the path routed to the agent, e.g. `/agents/weather`
+    pub fn with_path_prefix(mut self, path_prefix: impl Into<String>) -> Self {
+        let path_prefix = path_prefix.into();
+        self.path_prefix = match path_prefix.trim_matches('/') {
+            "" => String::new(),
+            trimmed => format!("/{}", trimmed),
+        };
+        self
+    }
+
+    pub fn with_tls(mut self, tls: bool) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Reads the endpoint from the process environment, the pod annotations
+    /// at `DEFAULT_ANNOTATIONS_PATH` or the pod IP, in that order
+    ///
+    /// Returns `None` when none of them describes an endpoint.
+    pub fn from_environment() -> Result<Option<Self>, A2AError> {
+        let vars: HashMap<String, String> = std::env::vars().collect();
+        if let Some(endpoint) = Self::from_vars(&vars)? {
+            return Ok(Some(endpoint));
+        }
+        if Path::new(DEFAULT_ANNOTATIONS_PATH).exists() {
+            if let Some(endpoint) = Self::from_annotations_file(DEFAULT_ANNOTATIONS_PATH)? {
+                return Ok(Some(endpoint));
+            }
+        }
+        Ok(vars.get("POD_IP").map(|ip| {
+            let endpoint = Self::new(ip.clone()).with_tls(false);
+            match vars.get("A2A_PUBLIC_PORT").and_then(|port| port.parse().ok()) {
+                Some(port) => endpoint.with_port(port),
+                None => endpoint,
+            }
+        }))
+    }
+
+    /// Reads the endpoint from `A2A_PUBLIC_*` variables
+    pub fn from_vars(vars: &HashMap<String, String>) -> Result<Option<Self>, A2AError> {
+        Self::from_settings(
+            vars.get("A2A_PUBLIC_HOST"),
+            vars.get("A2A_PUBLIC_PORT"),
+            vars.get("A2A_PUBLIC_PATH_PREFIX"),
+            vars.get("A2A_PUBLIC_TLS"),
+        )
+    }
+
+    /// Reads the endpoint from a downward API file of pod annotations
+    pub fn from_annotations_file(path: impl AsRef<Path>) -> Result<Option<Self>, A2AError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| A2AError::internal(&format!("Failed to read {}: {}", path.display(), e)))?;
+        Self::from_annotations(&parse_downward_api(&contents))
+    }
+
+    /// Reads the endpoint from `a2a/*` pod annotations
+    pub fn from_annotations(annotations: &HashMap<String, String>) -> Result<Option<Self>, A2AError> {
+        Self::from_settings(
+            annotations.get("a2a/public-host"),
+            annotations.get("a2a/public-port"),
+            annotations.get("a2a/path-prefix"),
+            annotations.get("a2a/tls"),
+        )
+    }
+
+    fn from_settings(
+        host: Option<&String>,
+        port: Option<&String>,
+        path_prefix: Option<&String>,
+        tls: Option<&String>,
+    ) -> Result<Option<Self>, A2AError> {
+        let Some(host) = host.map(|host| host.trim()).filter(|host| !host.is_empty()) else {
+            return Ok(None);
+        };
+        let mut endpoint = Self::new(host);
+        if let Some(port) = port {
+            let port = port
+                .trim()
+                .parse()
+                .map_err(|_| A2AError::invalid_params(&format!("Invalid public port: {}", port)))?;
+            endpoint = endpoint.with_port(port);
+        }
+        if let Some(path_prefix) = path_prefix {
+            endpoint = endpoint.with_path_prefix(path_prefix.as_str());
+        }
+        if let Some(tls) = tls {
+            let tls = match tls.trim().to_ascii_lowercase().as_str() {
+                "true" | "1" | "yes" | "on" => true,
+                "false" | "0" | "no" | "off" => false,
+                _ => return Err(A2AError::invalid_params(&format!("Invalid public TLS flag: {}", tls))),
+            };
+            endpoint = endpoint.with_tls(tls);
+        }
+        Ok(Some(endpoint))
+    }
+
+    /// The base URL of the agent, without a trailing slash
+    pub fn base_url(&self) -> String {
+        let scheme = if self.tls { "https" } else { "http" };
+        let default_port = if self.tls { 443 } else { 80 };
+        match self.port {
+            Some(port) if port != default_port => format!("{}://{}:{}{}", scheme, self.host, port, self.path_prefix),
+            _ => format!("{}://{}{}", scheme, self.host, self.path_prefix),
+        }
+    }
+
+    /// Moves a URL to this endpoint, keeping its path below the prefix
+    pub fn rebase(&self, url: &str) -> String {
+        let path = match url::Url::parse(url) {
+            Ok(parsed) => {
+                let mut path = parsed.path().to_string();
+                if let Some(query) = parsed.query() {
+                    path.push('?');
+                    path.push_str(query);
+                }
+                path
+            }
+            Err(_) => "/".to_string(),
+        };
+        format!("{}{}", self.base_url(), path)
+    }
+
+    /// Rewrites the URL and interface URLs of `card` to this endpoint
+    pub fn apply(&self, card: &mut AgentCard) {
+        card.url = self.rebase(&card.url);
+        for interface in card.additional_interfaces.iter_mut().flatten() {
+            interface.url = self.rebase(&interface.url);
+        }
+    }
+}
+
+/// Parses the `key="value"` lines of a downward API file
+fn parse_downward_api(contents: &str) -> HashMap<String, String> {
+    contents
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .map(|quoted| quoted.replace("\\\"", "\"").replace("\\\\", "\\"))
+                .unwrap_or_else(|| value.to_string());
+            (key.trim().to_string(), value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::models::{AgentCapabilities, AgentInterface};
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_endpoint_from_vars() {
+        assert_eq!(PublicEndpoint::from_vars(&vars(&[])).unwrap(), None);
+
+        let endpoint = PublicEndpoint::from_vars(&vars(&[
+            ("A2A_PUBLIC_HOST", "agents.example.com"),
+            ("A2A_PUBLIC_PATH_PREFIX", "weather/"),
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(endpoint.base_url(), "https://agents.example.com/weather");
+
+        let endpoint = PublicEndpoint::from_vars(&vars(&[
+            ("A2A_PUBLIC_HOST", "10.0.0.7"),
+            ("A2A_PUBLIC_PORT", "8080"),
+            ("A2A_PUBLIC_TLS", "false"),
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(endpoint.base_url(), "http://10.0.0.7:8080");
+
+        assert!(PublicEndpoint::from_vars(&vars(&[("A2A_PUBLIC_HOST", "h"), ("A2A_PUBLIC_PORT", "http")])).is_err());
+        assert!(PublicEndpoint::from_vars(&vars(&[("A2A_PUBLIC_HOST", "h"), ("A2A_PUBLIC_TLS", "maybe")])).is_err());
+    }
+
+    #[test]
+    fn test_endpoint_from_annotations() {
+        let annotations = parse_downward_api(
+            "kubernetes.io/config.seen=\"2026-01-01T00:00:00Z\"\na2a/public-host=\"agents.example.com\"\na2a/public-port=\"8443\"\n",
+        );
+        let endpoint = PublicEndpoint::from_annotations(&annotations).unwrap().unwrap();
+        assert_eq!(endpoint.base_url(), "https://agents.example.com:8443");
+    }
+
+    #[test]
+    fn test_apply_rewrites_card_urls() {
+        let mut card = AgentCard::new(
+            "agent".to_string(),
+            "test agent".to_string(),
+            "http://0.0.0.0:8080/".to_string(),
+            "1.0.0".to_string(),
+            vec!["text".to_string()],
+            vec!["text".to_string()],
+            AgentCapabilities::new(),
+            vec![],
+        );
+        card.additional_interfaces = Some(vec![AgentInterface::new(
+            "http://0.0.0.0:8080/v1?format=json".to_string(),
+            "HTTP+JSON".to_string(),
+        )]);
+
+        PublicEndpoint::new("agents.example.com").with_path_prefix("/weather").apply(&mut card);
+        assert_eq!(card.url, "https://agents.example.com/weather/");
+        assert_eq!(
+            card.additional_interfaces.unwrap()[0].url,
+            "https://agents.example.com/weather/v1?format=json"
+        );
+    }
+}
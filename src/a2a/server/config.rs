@@ -0,0 +1,509 @@
+//! Configuration file loading for server setup
+//!
+//! `A2AConfig` collects everything a deployment usually wires by hand: the
+//! HTTP server settings (including TLS), the task store backend, push
+//! notification delivery and API key authentication. It is loaded from a TOML
+//! or YAML file, chosen by extension, and then overridden by environment
+//! variables named `A2A_<SECTION>__<FIELD>`:
+//!
+//! ```toml
+//! [server]
+//! bind_addr = "0.0.0.0:8080"
+//!
+//! [store]
+//! backend = "sqlite"
+//! url = "sqlite:///var/lib/agent/tasks.db"
+//!
+//! [push]
+//! enabled = true
+//! payload_format = "slim"
+//!
+//! [auth.api_keys]
+//! ci = "s3cr3t"
+//! ```
+//!
+//! With `A2A_SERVER__BIND_ADDR=127.0.0.1:9000` set, the server binds to port
+//! 9000 instead. Nested keys are separated by a double underscore, and names are
+//! matched in lowercase. `A2AServerBuilder::from_config` turns a file into a
+//! ready builder.
+
+use crate::a2a::error::A2AError;
+use crate::a2a::server::apps::jsonrpc::ServerConfig;
+use crate::a2a::server::context::{ApiKeyContextBuilder, DefaultServerCallContextBuilder, ServerCallContextBuilder};
+use crate::a2a::server::request_handlers::DefaultRequestHandler;
+use crate::a2a::server::tasks::{
+    formatter_for, CallbackTokenSigner, CloudEventsPayloadFormatter, HttpPushNotificationSender,
+    InMemoryPushNotificationConfigStore, InMemoryTaskStore, PushNotificationConfigStore, PushNotificationSender,
+    EventSourcedTaskStore, FileTaskEventLog, SqlitePushNotificationConfigStore, SqliteTaskStore, TaskRetention,
+    TaskStore, DEFAULT_PURGE_INTERVAL,
+};
+use crate::NotificationPayloadFormat;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Prefix of the environment variables overriding file settings
+pub const ENV_PREFIX: &str = "A2A_";
+
+/// SQLite database used when the sqlite backend has no `url`
+pub const DEFAULT_SQLITE_URL: &str = "sqlite://a2a.db";
+
+/// Event log file used by the eventlog backend when it has no `url`
+pub const DEFAULT_EVENT_LOG_PATH: &str = "a2a-events.jsonl";
+
+/// Complete server configuration
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct A2AConfig {
+    /// HTTP server settings
+    pub server: ServerConfig,
+    /// Task and push config storage
+    pub store: StoreConfig,
+    /// Push notification delivery
+    pub push: PushConfig,
+    /// Request authentication
+    pub auth: AuthConfig,
+}
+
+/// Storage backend of tasks and push notification configs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StoreBackend {
+    /// Process memory; everything is lost on restart
+    #[default]
+    Memory,
+    /// A SQLite database file
+    Sqlite,
+    /// A PostgreSQL database
+    Postgres,
+    /// An append-only file of task changes, with push configs in memory
+    EventLog,
+}
+
+/// Storage settings
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StoreConfig {
+    /// The backend to use
+    pub backend: StoreBackend,
+    /// Database URL, e.g. `sqlite://tasks.db` or `postgres://host/db`, or the
+    /// path of the event log file
+    pub url: Option<String>,
+    /// URL of a read replica serving `tasks/get`, which may lag behind `url`
+    pub read_replica_url: Option<String>,
+    /// Record push notification intents in a transactional outbox
+    pub outbox: bool,
+    /// Index message texts and serve the `tasks/search` JSON-RPC method,
+    /// with the memory and SQLite backends; tasks record the tenant that
+    /// created them, and searches only find the caller's
+    pub search_index: bool,
+    /// Base64 encoded 32 byte key encrypting stored push notification configs
+    pub encryption_key: Option<String>,
+    /// Purge tasks deleted longer than this many seconds ago, with the
+    /// memory and SQLite backends; deleted tasks are otherwise kept until
+    /// purged through the admin API
+    pub deleted_retention_secs: Option<u64>,
+}
+
+/// Push notification settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PushConfig {
+    /// Accept push notification configs and deliver notifications
+    pub enabled: bool,
+    /// Timeout of a single webhook request, in seconds
+    pub timeout_secs: u64,
+    /// Default payload format of notifications
+    pub payload_format: NotificationPayloadFormat,
+    /// CloudEvents `source` attribute, used with the `cloud-events` format
+    pub cloudevents_source: Option<String>,
+    /// Key signing per-notification callback tokens; static tokens are sent if unset
+    pub callback_token_key: Option<String>,
+    /// Lifetime of callback tokens, in seconds
+    pub callback_token_ttl_secs: Option<u64>,
+}
+
+impl Default for PushConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timeout_secs: 30,
+            payload_format: NotificationPayloadFormat::Full,
+            cloudevents_source: None,
+            callback_token_key: None,
+            callback_token_ttl_secs: None,
+        }
+    }
+}
+
+/// Authentication settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// Header carrying the API key
+    pub api_key_header: String,
+    /// Accepted API keys, by the user name they authenticate as
+    pub api_keys: HashMap<String, String>,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            api_key_header: "X-API-Key".to_string(),
+            api_keys: HashMap::new(),
+        }
+    }
+}
+
+impl A2AConfig {
+    /// Loads a TOML or YAML file and applies the environment overrides
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, A2AError> {
+        Self::from_file(path)?.with_overrides(ENV_PREFIX, std::env::vars())
+    }
+
+    /// Loads a TOML or YAML file without environment overrides
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, A2AError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            A2AError::invalid_params(&format!("Failed to read configuration file {}: {}", path.display(), e))
+        })?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml_str(&contents),
+            Some("yaml") | Some("yml") => Self::from_yaml_str(&contents),
+            _ => Err(A2AError::invalid_params(&format!(
+                "Unsupported configuration file format: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Parses a TOML document
+    pub fn from_toml_str(contents: &str) -> Result<Self, A2AError> {
+        toml::from_str(contents).map_err(|e| A2AError::invalid_params(&format!("Invalid configuration: {}", e)))
+    }
+
+    /// Parses a YAML document
+    pub fn from_yaml_str(contents: &str) -> Result<Self, A2AError> {
+        serde_yaml::from_str(contents).map_err(|e| A2AError::invalid_params(&format!("Invalid configuration: {}", e)))
+    }
+
+    /// Applies overrides from `vars` named `<prefix><SECTION>__<FIELD>`
+    ///
+    /// Values are taken as strings for string settings and parsed as JSON
+    /// otherwise; list settings also accept comma separated values. Variables
+    /// without a section and field are ignored.
+    pub fn with_overrides<I>(self, prefix: &str, vars: I) -> Result<Self, A2AError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut value = serde_json::to_value(&self)?;
+        for (name, raw) in vars {
+            let Some(path) = name.strip_prefix(prefix) else {
+                continue;
+            };
+            let keys: Vec<String> = path.split("__").map(str::to_lowercase).collect();
+            if keys.len() < 2 || keys.iter().any(String::is_empty) {
+                continue;
+            }
+            set_path(&mut value, &keys, &raw);
+        }
+        serde_json::from_value(value).map_err(|e| A2AError::invalid_params(&format!("Invalid configuration: {}", e)))
+    }
+
+    /// Opens the configured task store
+    pub async fn task_store(&self) -> Result<Arc<dyn TaskStore>, A2AError> {
+        match self.store.backend {
+            StoreBackend::Memory => Ok(Arc::new(InMemoryTaskStore::new())),
+            StoreBackend::Sqlite => {
+                let store = match &self.store.read_replica_url {
+                    Some(replica_url) => SqliteTaskStore::connect_with_read_replica(self.sqlite_url(), replica_url).await?,
+                    None => SqliteTaskStore::connect(self.sqlite_url()).await?,
+                };
+                let store = if self.store.outbox { store.with_outbox() } else { store };
+                Ok(Arc::new(if self.store.search_index { store.with_search_index() } else { store }))
+            }
+            StoreBackend::Postgres => Err(postgres_unsupported()),
+            StoreBackend::EventLog => {
+                let log = FileTaskEventLog::open(self.store.url.as_deref().unwrap_or(DEFAULT_EVENT_LOG_PATH)).await?;
+                let store = EventSourcedTaskStore::new(Arc::new(log));
+                store.rebuild().await?;
+                Ok(Arc::new(store))
+            }
+        }
+    }
+
+    /// Opens the configured push notification config store, if push is enabled
+    pub async fn push_config_store(&self) -> Result<Option<Arc<dyn PushNotificationConfigStore>>, A2AError> {
+        if !self.push.enabled {
+            return Ok(None);
+        }
+        match self.store.backend {
+            StoreBackend::Memory | StoreBackend::EventLog => Ok(Some(Arc::new(InMemoryPushNotificationConfigStore::new()))),
+            StoreBackend::Sqlite => {
+                let store = SqlitePushNotificationConfigStore::connect(self.sqlite_url(), self.encryption_key()?).await?;
+                Ok(Some(Arc::new(store)))
+            }
+            StoreBackend::Postgres => Err(postgres_unsupported()),
+        }
+    }
+
+    /// Builds the webhook sender reading configs from `config_store`
+    pub fn push_sender(
+        &self,
+        config_store: Arc<dyn PushNotificationConfigStore>,
+    ) -> Result<HttpPushNotificationSender, A2AError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.push.timeout_secs))
+            .build()
+            .map_err(|e| A2AError::internal(&format!("Failed to build HTTP client: {}", e)))?;
+
+        let formatter = match (self.push.payload_format, &self.push.cloudevents_source) {
+            (NotificationPayloadFormat::CloudEvents, Some(source)) => Arc::new(CloudEventsPayloadFormatter::new(source)),
+            (format, _) => formatter_for(format),
+        };
+        let mut sender = HttpPushNotificationSender::with_client(client, config_store).with_formatter(formatter);
+        if let Some(key) = &self.push.callback_token_key {
+            let mut signer = CallbackTokenSigner::new(key.as_bytes());
+            if let Some(ttl) = self.push.callback_token_ttl_secs {
+                signer = signer.with_ttl(Duration::from_secs(ttl));
+            }
+            sender = sender.with_callback_tokens(signer);
+        }
+        Ok(sender)
+    }
+
+    /// Builds a `DefaultRequestHandler` over the configured stores
+    pub async fn request_handler(&self) -> Result<DefaultRequestHandler, A2AError> {
+        self.request_handler_with_task_store(self.task_store().await?).await
+    }
+
+    /// Builds a `DefaultRequestHandler` over `task_store` and the configured
+    /// push notification stores, recording task tenants when the search
+    /// index is enabled
+    pub async fn request_handler_with_task_store(
+        &self,
+        task_store: Arc<dyn TaskStore>,
+    ) -> Result<DefaultRequestHandler, A2AError> {
+        let push_config_store = self.push_config_store().await?;
+        let push_sender = match &push_config_store {
+            Some(store) => Some(Arc::new(self.push_sender(store.clone())?) as Arc<dyn PushNotificationSender>),
+            None => None,
+        };
+        let handler = DefaultRequestHandler::new(task_store, push_config_store, push_sender);
+        Ok(if self.store.search_index { handler.with_tenant_metadata() } else { handler })
+    }
+
+    /// The retention of deleted tasks in `task_store` and how often to
+    /// purge them, if configured
+    pub fn task_retention(&self, task_store: Arc<dyn TaskStore>) -> Option<(TaskRetention, Duration)> {
+        self.store.deleted_retention_secs.map(|secs| {
+            let window = Duration::from_secs(secs);
+            let interval = window.clamp(Duration::from_secs(1), DEFAULT_PURGE_INTERVAL);
+            (TaskRetention::new(task_store, window), interval)
+        })
+    }
+
+    /// Builds the context builder, authenticating API keys if any are configured
+    pub fn context_builder(&self) -> Arc<dyn ServerCallContextBuilder> {
+        if self.auth.api_keys.is_empty() {
+            return Arc::new(DefaultServerCallContextBuilder);
+        }
+        let builder = self
+            .auth
+            .api_keys
+            .iter()
+            .fold(ApiKeyContextBuilder::new(&self.auth.api_key_header), |builder, (user, key)| {
+                builder.with_key(key, user)
+            });
+        Arc::new(builder)
+    }
+
+    fn sqlite_url(&self) -> &str {
+        self.store.url.as_deref().unwrap_or(DEFAULT_SQLITE_URL)
+    }
+
+    fn encryption_key(&self) -> Result<Option<[u8; 32]>, A2AError> {
+        let Some(encoded) = &self.store.encryption_key else {
+            return Ok(None);
+        };
+        let bytes = STANDARD
+            .decode(encoded)
+            .map_err(|e| A2AError::invalid_params(&format!("Invalid store encryption key: {}", e)))?;
+        let key: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| A2AError::invalid_params("Store encryption key must be 32 bytes"))?;
+        Ok(Some(key))
+    }
+}
+
+fn postgres_unsupported() -> A2AError {
+    A2AError::unsupported_operation("PostgreSQL stores are not available in this build")
+}
+
+/// Sets the value at `keys`, creating intermediate tables as needed
+fn set_path(value: &mut Value, keys: &[String], raw: &str) {
+    if !value.is_object() {
+        *value = Value::Object(serde_json::Map::new());
+    }
+    let map = value.as_object_mut().expect("value is an object");
+    let (key, rest) = keys.split_first().expect("keys are not empty");
+    if !rest.is_empty() {
+        set_path(map.entry(key.clone()).or_insert(Value::Null), rest, raw);
+        return;
+    }
+
+    let parsed = match map.get(key) {
+        Some(Value::String(_)) => Value::String(raw.to_string()),
+        Some(Value::Array(_)) => match serde_json::from_str(raw) {
+            Ok(Value::Array(items)) => Value::Array(items),
+            _ => Value::Array(raw.split(',').map(|item| Value::String(item.trim().to_string())).collect()),
+        },
+        _ => serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string())),
+    };
+    map.insert(key.clone(), parsed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_toml_and_yaml_are_equivalent() {
+        let toml = A2AConfig::from_toml_str(
+            r#"
+            [server]
+            bind_addr = "0.0.0.0:9000"
+            enable_cors = false
+
+            [server.tls]
+            cert_path = "cert.pem"
+            key_path = "key.pem"
+
+            [store]
+            backend = "sqlite"
+            url = "sqlite://tasks.db"
+
+            [push]
+            enabled = true
+            payload_format = "slim"
+
+            [auth.api_keys]
+            ci = "s3cr3t"
+            "#,
+        )
+        .unwrap();
+        let yaml = A2AConfig::from_yaml_str(
+            r#"
+server:
+  bind_addr: "0.0.0.0:9000"
+  enable_cors: false
+  tls:
+    cert_path: cert.pem
+    key_path: key.pem
+store:
+  backend: sqlite
+  url: "sqlite://tasks.db"
+push:
+  enabled: true
+  payload_format: slim
+auth:
+  api_keys:
+    ci: s3cr3t
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(toml, yaml);
+        assert_eq!(toml.server.bind_addr.port(), 9000);
+        assert_eq!(toml.server.rpc_path, ServerConfig::default().rpc_path);
+        assert_eq!(toml.store.backend, StoreBackend::Sqlite);
+        assert_eq!(toml.push.payload_format, NotificationPayloadFormat::Slim);
+        assert_eq!(toml.push.timeout_secs, 30);
+        assert_eq!(toml.auth.api_keys["ci"], "s3cr3t");
+    }
+
+    #[test]
+    fn test_environment_overrides() {
+        let config = A2AConfig::default()
+            .with_overrides(
+                ENV_PREFIX,
+                vars(&[
+                    ("A2A_SERVER__BIND_ADDR", "127.0.0.1:7000"),
+                    ("A2A_SERVER__MAX_CONTENT_LENGTH", "1024"),
+                    ("A2A_STORE__BACKEND", "sqlite"),
+                    ("A2A_STORE__URL", "sqlite::memory:"),
+                    ("A2A_PUSH__ENABLED", "true"),
+                    ("A2A_AUTH__API_KEYS__CI", "s3cr3t"),
+                    ("A2A_UNRELATED", "ignored"),
+                    ("PATH", "/usr/bin"),
+                ]),
+            )
+            .unwrap();
+
+        assert_eq!(config.server.bind_addr.port(), 7000);
+        assert_eq!(config.server.max_content_length, Some(1024));
+        assert_eq!(config.store.backend, StoreBackend::Sqlite);
+        assert_eq!(config.store.url.as_deref(), Some("sqlite::memory:"));
+        assert!(config.push.enabled);
+        assert_eq!(config.auth.api_keys["ci"], "s3cr3t");
+
+        let invalid = A2AConfig::default().with_overrides(ENV_PREFIX, vars(&[("A2A_PUSH__ENABLED", "maybe")]));
+        assert!(invalid.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_builds_stores_from_config() {
+        let mut config = A2AConfig::default();
+        config.store.backend = StoreBackend::Sqlite;
+        config.store.url = Some("sqlite::memory:".to_string());
+        config.push.enabled = true;
+
+        assert!(config.task_store().await.is_ok());
+        assert!(config.push_config_store().await.unwrap().is_some());
+        assert!(config.request_handler().await.is_ok());
+
+        config.store.backend = StoreBackend::Postgres;
+        assert!(config.task_store().await.is_err());
+
+        let path = std::env::temp_dir().join(format!("a2a-config-events-{}.jsonl", uuid::Uuid::new_v4()));
+        config.store.backend = StoreBackend::EventLog;
+        config.store.url = Some(path.display().to_string());
+        assert!(config.task_store().await.is_ok());
+        assert!(config.push_config_store().await.unwrap().is_some());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_deleted_task_retention_is_configured() {
+        let mut config = A2AConfig::default();
+        let store = config.task_store().await.unwrap();
+        assert!(config.task_retention(store.clone()).is_none());
+
+        config.store.deleted_retention_secs = Some(7 * 24 * 3600);
+        let (_, interval) = config.task_retention(store.clone()).unwrap();
+        assert_eq!(interval, DEFAULT_PURGE_INTERVAL);
+        config.store.deleted_retention_secs = Some(0);
+        let (retention, interval) = config.task_retention(store).unwrap();
+        assert_eq!(interval, Duration::from_secs(1));
+        assert_eq!(retention.purge_expired().await.unwrap(), 0);
+    }
+
+    #[test]
+    fn test_encryption_key_must_be_32_bytes() {
+        let mut config = A2AConfig::default();
+        config.store.encryption_key = Some(STANDARD.encode([7u8; 32]));
+        assert_eq!(config.encryption_key().unwrap(), Some([7u8; 32]));
+
+        config.store.encryption_key = Some(STANDARD.encode([7u8; 16]));
+        assert!(config.encryption_key().is_err());
+    }
+}
@@ -0,0 +1,413 @@
+//! Scanning of incoming file and data parts
+//!
+//! `ScanningRequestHandler` wraps a `RequestHandler` and passes every file and
+//! data part of an incoming message through a chain of `ContentScanner`s before
+//! the message reaches the wrapped handler, and with it the agent. Scanners are
+//! async, so they can call out to a virus scanner or DLP service; size limits
+//! and MIME type allowlists are built in. A disallowed MIME type is reported as
+//! `ContentTypeNotSupported`, every other rejection as a content rejected error
+//! carrying the index of the offending part.
+
+use crate::a2a::core_types::{FileContent, Message, Part, PartRoot};
+use crate::a2a::error::{A2AError, ContentTypeNotSupportedError, JSONRPCError};
+use crate::a2a::jsonrpc::error_codes;
+use crate::a2a::models::*;
+use crate::a2a::server::context::ServerCallContext;
+use crate::a2a::server::request_handlers::request_handler::{
+    Event, MessageSendResult, RequestHandler, StreamEvent, TaskPushNotificationConfigQueryParams,
+};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// MIME type assumed for file parts that do not declare one
+pub const DEFAULT_FILE_MIME_TYPE: &str = "application/octet-stream";
+
+/// MIME type of data parts
+pub const DATA_PART_MIME_TYPE: &str = "application/json";
+
+/// Why a part was refused
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ContentRejection {
+    #[error("Part {part_index} is {size} bytes, exceeding the limit of {limit} bytes")]
+    TooLarge { part_index: usize, size: u64, limit: u64 },
+
+    #[error("Part {part_index} has MIME type '{mime_type}', which is not accepted")]
+    MimeTypeNotAllowed { part_index: usize, mime_type: String },
+
+    #[error("Part {part_index} was rejected: {reason}")]
+    Rejected { part_index: usize, reason: String },
+}
+
+impl ContentRejection {
+    /// Returns the structured error data sent to the client
+    pub fn data(&self) -> serde_json::Value {
+        match self {
+            ContentRejection::TooLarge { part_index, size, limit } => serde_json::json!({
+                "part_index": part_index,
+                "size": size,
+                "limit": limit,
+            }),
+            ContentRejection::MimeTypeNotAllowed { part_index, mime_type } => serde_json::json!({
+                "part_index": part_index,
+                "mime_type": mime_type,
+            }),
+            ContentRejection::Rejected { part_index, reason } => serde_json::json!({
+                "part_index": part_index,
+                "reason": reason,
+            }),
+        }
+    }
+}
+
+impl From<ContentRejection> for A2AError {
+    fn from(err: ContentRejection) -> Self {
+        let data = Some(err.data());
+        match err {
+            ContentRejection::MimeTypeNotAllowed { .. } => A2AError::ContentTypeNotSupported(ContentTypeNotSupportedError {
+                code: error_codes::CONTENT_TYPE_NOT_SUPPORTED,
+                message: err.to_string(),
+                data,
+            }),
+            _ => A2AError::Generic(JSONRPCError {
+                code: error_codes::CONTENT_REJECTED,
+                message: err.to_string(),
+                data,
+            }),
+        }
+    }
+}
+
+/// A file or data part submitted for scanning
+#[derive(Debug, Clone, Copy)]
+pub struct ScannedPart<'a> {
+    /// Position of the part in the message
+    pub index: usize,
+    /// The part itself
+    pub part: &'a Part,
+}
+
+impl ScannedPart<'_> {
+    /// The declared MIME type, defaulting by part kind
+    pub fn mime_type(&self) -> &str {
+        match self.part.root() {
+            PartRoot::File(file) => match &file.file {
+                FileContent::Bytes(bytes) => bytes.mime_type.as_deref(),
+                FileContent::Uri(uri) => uri.mime_type.as_deref(),
+            }
+            .unwrap_or(DEFAULT_FILE_MIME_TYPE),
+            _ => DATA_PART_MIME_TYPE,
+        }
+    }
+
+    /// Size of the inline content in bytes
+    ///
+    /// For files this is the decoded size of the base64 payload; files given
+    /// by URI have no inline content and report zero.
+    pub fn size(&self) -> u64 {
+        match self.part.root() {
+            PartRoot::File(file) => match &file.file {
+                FileContent::Bytes(bytes) => {
+                    let encoded = bytes.bytes.trim_end_matches('=').len() as u64;
+                    encoded * 3 / 4
+                }
+                FileContent::Uri(_) => 0,
+            },
+            PartRoot::Data(data) => serde_json::to_vec(&data.data).map(|v| v.len() as u64).unwrap_or(0),
+            PartRoot::Text(text) => text.text.len() as u64,
+        }
+    }
+
+    /// A rejection of this part with `reason`
+    pub fn reject(&self, reason: impl Into<String>) -> ContentRejection {
+        ContentRejection::Rejected {
+            part_index: self.index,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Inspects incoming parts before execution
+#[async_trait]
+pub trait ContentScanner: Send + Sync {
+    /// Accepts the part or explains why it is refused
+    async fn scan(&self, part: ScannedPart<'_>, context: Option<&ServerCallContext>) -> Result<(), ContentRejection>;
+}
+
+/// Refuses parts larger than a limit
+#[derive(Debug, Clone)]
+pub struct SizeLimitScanner {
+    max_bytes: u64,
+}
+
+impl SizeLimitScanner {
+    /// Creates a scanner refusing parts above `max_bytes`
+    pub fn new(max_bytes: u64) -> Self {
+        Self { max_bytes }
+    }
+}
+
+#[async_trait]
+impl ContentScanner for SizeLimitScanner {
+    async fn scan(&self, part: ScannedPart<'_>, _context: Option<&ServerCallContext>) -> Result<(), ContentRejection> {
+        let size = part.size();
+        if size > self.max_bytes {
+            return Err(ContentRejection::TooLarge {
+                part_index: part.index,
+                size,
+                limit: self.max_bytes,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Accepts only parts whose MIME type matches an allowlist
+///
+/// Entries are exact types or wildcards such as `image/*`.
+#[derive(Debug, Clone)]
+pub struct MimeAllowlistScanner {
+    allowed: Vec<String>,
+}
+
+impl MimeAllowlistScanner {
+    /// Creates a scanner accepting the given MIME types
+    pub fn new<I, S>(allowed: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            allowed: allowed.into_iter().map(|s| s.into().to_lowercase()).collect(),
+        }
+    }
+
+    /// Whether `mime_type` matches the allowlist
+    pub fn allows(&self, mime_type: &str) -> bool {
+        // Parameters such as `; charset=utf-8` do not affect the match
+        let mime_type = mime_type.split(';').next().unwrap_or_default().trim().to_lowercase();
+        self.allowed.iter().any(|allowed| match allowed.strip_suffix("/*") {
+            Some(prefix) => mime_type.split('/').next() == Some(prefix),
+            None => *allowed == mime_type || allowed == "*/*",
+        })
+    }
+}
+
+#[async_trait]
+impl ContentScanner for MimeAllowlistScanner {
+    async fn scan(&self, part: ScannedPart<'_>, _context: Option<&ServerCallContext>) -> Result<(), ContentRejection> {
+        let mime_type = part.mime_type();
+        if !self.allows(mime_type) {
+            return Err(ContentRejection::MimeTypeNotAllowed {
+                part_index: part.index,
+                mime_type: mime_type.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Request handler running content scanners before delegating
+pub struct ScanningRequestHandler {
+    inner: Arc<dyn RequestHandler>,
+    scanners: Vec<Arc<dyn ContentScanner>>,
+}
+
+impl ScanningRequestHandler {
+    /// Wraps `inner` without any scanners
+    pub fn new(inner: Arc<dyn RequestHandler>) -> Self {
+        Self {
+            inner,
+            scanners: Vec::new(),
+        }
+    }
+
+    /// Adds a scanner; scanners run in the order they were added
+    pub fn with_scanner(mut self, scanner: Arc<dyn ContentScanner>) -> Self {
+        self.scanners.push(scanner);
+        self
+    }
+
+    /// Runs all scanners over the file and data parts of `message`
+    pub async fn scan_message(&self, message: &Message, context: Option<&ServerCallContext>) -> Result<(), A2AError> {
+        for (index, part) in message.parts.iter().enumerate() {
+            if matches!(part.root(), PartRoot::Text(_)) {
+                continue;
+            }
+            for scanner in &self.scanners {
+                scanner.scan(ScannedPart { index, part }, context).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RequestHandler for ScanningRequestHandler {
+    async fn on_get_task(
+        &self,
+        params: TaskQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        self.inner.on_get_task(params, context).await
+    }
+
+    async fn on_cancel_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        self.inner.on_cancel_task(params, context).await
+    }
+
+    async fn on_message_send(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<MessageSendResult, A2AError> {
+        self.scan_message(&params.message, context).await?;
+        self.inner.on_message_send(params, context).await
+    }
+
+    async fn on_message_send_stream(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        self.scan_message(&params.message, context).await?;
+        self.inner.on_message_send_stream(params, context).await
+    }
+
+    async fn on_message_send_stream_resumable(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        self.scan_message(&params.message, context).await?;
+        self.inner.on_message_send_stream_resumable(params, context).await
+    }
+
+    async fn on_set_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfig,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_set_task_push_notification_config(params, context).await
+    }
+
+    async fn on_get_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfigQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_get_task_push_notification_config(params, context).await
+    }
+
+    async fn on_resubscribe_to_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        self.inner.on_resubscribe_to_task(params, context).await
+    }
+
+    async fn on_resubscribe_to_task_resumable(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        self.inner.on_resubscribe_to_task_resumable(params, context).await
+    }
+
+    async fn on_list_task_push_notification_config(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Vec<TaskPushNotificationConfig>, A2AError> {
+        self.inner.on_list_task_push_notification_config(params, context).await
+    }
+
+    async fn on_delete_task_push_notification_config(
+        &self,
+        params: DeleteTaskPushNotificationConfigParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<(), A2AError> {
+        self.inner.on_delete_task_push_notification_config(params, context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::{FilePart, Role};
+    use crate::a2a::server::request_handlers::MockRequestHandler;
+
+    struct DenyKeyword;
+
+    #[async_trait]
+    impl ContentScanner for DenyKeyword {
+        async fn scan(&self, part: ScannedPart<'_>, _context: Option<&ServerCallContext>) -> Result<(), ContentRejection> {
+            match part.part.root() {
+                PartRoot::Data(data) if data.data.to_string().contains("secret") => Err(part.reject("contains secrets")),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    fn image(bytes: &str, mime_type: &str) -> Part {
+        let mut file = FilePart::new_bytes(bytes.to_string());
+        if let FileContent::Bytes(content) = &mut file.file {
+            content.mime_type = Some(mime_type.to_string());
+        }
+        Part::Direct(PartRoot::File(file))
+    }
+
+    fn handler() -> ScanningRequestHandler {
+        ScanningRequestHandler::new(Arc::new(MockRequestHandler))
+            .with_scanner(Arc::new(SizeLimitScanner::new(8)))
+            .with_scanner(Arc::new(MimeAllowlistScanner::new(["image/*", "application/json"])))
+            .with_scanner(Arc::new(DenyKeyword))
+    }
+
+    async fn send(parts: Vec<Part>) -> Result<MessageSendResult, A2AError> {
+        handler()
+            .on_message_send(MessageSendParams::new(Message::new(Role::User, parts)), None)
+            .await
+    }
+
+    #[test]
+    fn test_mime_allowlist_matching() {
+        let scanner = MimeAllowlistScanner::new(["image/*", "text/plain"]);
+        assert!(scanner.allows("image/png"));
+        assert!(scanner.allows("Text/Plain; charset=utf-8"));
+        assert!(!scanner.allows("application/pdf"));
+    }
+
+    #[tokio::test]
+    async fn test_accepted_parts_reach_inner_handler() {
+        let parts = vec![
+            Part::text("a very long text part is never scanned".to_string()),
+            image("aGk=", "image/png"),
+            Part::data(serde_json::json!({"a": 1})),
+        ];
+        assert!(send(parts).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rejections_map_to_error_codes() {
+        let err = send(vec![image("aGk=", "application/pdf")]).await.unwrap_err();
+        assert_eq!(err.code(), error_codes::CONTENT_TYPE_NOT_SUPPORTED);
+        assert_eq!(err.data().unwrap()["mime_type"], "application/pdf");
+
+        let err = send(vec![image("aGVsbG8gd29ybGQh", "image/png")]).await.unwrap_err();
+        assert_eq!(err.code(), error_codes::CONTENT_REJECTED);
+        assert_eq!(err.data().unwrap()["size"], 12);
+
+        let err = send(vec![Part::text("hi".to_string()), Part::data(serde_json::json!("secret"))])
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), error_codes::CONTENT_REJECTED);
+        assert_eq!(err.data().unwrap()["part_index"], 1);
+    }
+}
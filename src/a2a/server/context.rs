@@ -6,6 +6,8 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use crate::a2a::extensions::locale::{parse_accept_language, ACCEPT_LANGUAGE_HEADER};
+use crate::a2a::utils::telemetry::TraceContext;
 
 /// Trait for building server call contexts from HTTP requests
 #[async_trait]
@@ -19,8 +21,71 @@ pub struct DefaultServerCallContextBuilder;
 
 #[async_trait]
 impl ServerCallContextBuilder for DefaultServerCallContextBuilder {
-    async fn build(&self, _headers: &axum::http::HeaderMap) -> ServerCallContext {
-        ServerCallContext::new()
+    async fn build(&self, headers: &axum::http::HeaderMap) -> ServerCallContext {
+        let mut context = ServerCallContext::new();
+        context.trace_context = TraceContext::from_headers(headers).map(|parent| parent.child());
+        context.accepted_languages = accepted_languages(headers);
+        context
+    }
+}
+
+/// The language ranges of the `Accept-Language` header, most preferred first
+pub fn accepted_languages(headers: &axum::http::HeaderMap) -> Vec<String> {
+    headers
+        .get_all(ACCEPT_LANGUAGE_HEADER)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(parse_accept_language)
+        .collect()
+}
+
+/// Builds call contexts authenticated by an API key header
+///
+/// Requests carrying one of the configured keys get the key's user as the
+/// context user; all other requests stay unauthenticated.
+#[derive(Clone)]
+pub struct ApiKeyContextBuilder {
+    header: String,
+    keys: HashMap<String, String>,
+}
+
+impl ApiKeyContextBuilder {
+    /// Creates a builder reading keys from the given header
+    pub fn new(header: &str) -> Self {
+        Self {
+            header: header.to_string(),
+            keys: HashMap::new(),
+        }
+    }
+
+    /// Accepts `key`, authenticating requests carrying it as `username`
+    pub fn with_key(mut self, key: &str, username: &str) -> Self {
+        self.keys.insert(key.to_string(), username.to_string());
+        self
+    }
+}
+
+impl std::fmt::Debug for ApiKeyContextBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiKeyContextBuilder")
+            .field("header", &self.header)
+            .field("keys", &self.keys.len())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl ServerCallContextBuilder for ApiKeyContextBuilder {
+    async fn build(&self, headers: &axum::http::HeaderMap) -> ServerCallContext {
+        let mut context = DefaultServerCallContextBuilder.build(headers).await;
+        let username = headers
+            .get(self.header.as_str())
+            .and_then(|value| value.to_str().ok())
+            .and_then(|key| self.keys.get(key));
+        if let Some(username) = username {
+            context.user = crate::a2a::auth::user::AuthenticatedUser::new(username.clone());
+        }
+        context
     }
 }
 
@@ -45,6 +110,14 @@ pub struct ServerCallContext {
     /// Set of extensions that were activated for this request
     #[serde(default, skip_serializing_if = "std::collections::HashSet::is_empty")]
     pub activated_extensions: std::collections::HashSet<String>,
+
+    /// W3C trace context for this request, derived from the caller's `traceparent`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_context: Option<TraceContext>,
+
+    /// Language ranges the caller accepts, most preferred first, from `Accept-Language`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accepted_languages: Vec<String>,
 }
 
 impl Default for ServerCallContext {
@@ -54,6 +127,8 @@ impl Default for ServerCallContext {
             user: crate::a2a::auth::user::AuthenticatedUser::default(),
             requested_extensions: std::collections::HashSet::new(),
             activated_extensions: std::collections::HashSet::new(),
+            trace_context: None,
+            accepted_languages: Vec::new(),
         }
     }
 }
@@ -112,6 +187,14 @@ impl ServerCallContext {
         self.requested_extensions.iter().cloned().collect()
     }
 
+    /// Returns the trace context for this request, starting a new trace if the
+    /// caller did not send one
+    pub fn trace_context_or_root(&mut self) -> TraceContext {
+        self.trace_context
+            .get_or_insert_with(TraceContext::new_root)
+            .clone()
+    }
+
     /// Gets the activated extensions as a vector
     pub fn get_activated_extensions(&self) -> Vec<String> {
         self.activated_extensions.iter().cloned().collect()
@@ -187,6 +270,43 @@ mod tests {
         assert!(activated.contains(&"ext1".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_default_builder_extracts_trace_context() {
+        let mut headers = axum::http::HeaderMap::new();
+        headers.insert(
+            "traceparent",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".parse().unwrap(),
+        );
+
+        let context = DefaultServerCallContextBuilder.build(&headers).await;
+        let trace_context = context.trace_context.unwrap();
+        assert_eq!(trace_context.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(trace_context.parent_span_id.as_deref(), Some("00f067aa0ba902b7"));
+
+        let context = DefaultServerCallContextBuilder.build(&axum::http::HeaderMap::new()).await;
+        assert!(context.trace_context.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_default_builder_reads_accept_language() {
+        let mut headers = axum::http::HeaderMap::new();
+        headers.insert("accept-language", "de;q=0.5, en-GB".parse().unwrap());
+        let context = DefaultServerCallContextBuilder.build(&headers).await;
+        assert_eq!(context.accepted_languages, vec!["en-GB", "de"]);
+    }
+
+    #[tokio::test]
+    async fn test_api_key_builder_authenticates_known_keys() {
+        let builder = ApiKeyContextBuilder::new("x-api-key").with_key("k1", "alice");
+
+        let mut headers = axum::http::HeaderMap::new();
+        headers.insert("x-api-key", "k1".parse().unwrap());
+        assert_eq!(builder.build(&headers).await.user.username(), "alice");
+
+        headers.insert("x-api-key", "unknown".parse().unwrap());
+        assert_eq!(builder.build(&headers).await.user.username(), "");
+    }
+
     #[test]
     fn test_serialization() {
         let mut context = ServerCallContext::new();
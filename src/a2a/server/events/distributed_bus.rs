@@ -0,0 +1,275 @@
+//! Distributed event bus for multi-replica servers
+//!
+//! An `EventBus` only reaches subscribers in the same process. When a server runs
+//! as several replicas behind a load balancer, a task may be executed on one
+//! replica while an SSE resubscriber or push dispatch worker lives on another.
+//! `DistributedEventBridge` connects a local bus to a message broker through the
+//! `EventBusTransport` trait: locally produced events are forwarded to the shared
+//! subject, and events published by other replicas are re-published on the local
+//! bus with their origin set.
+//!
+//! `InMemoryEventBusTransport` connects buses within one process (useful in
+//! tests); a NATS transport is available behind the `nats` feature.
+
+use crate::a2a::error::A2AError;
+use crate::a2a::server::events::event_bus::{EventBus, DEFAULT_EVENT_BUS_CAPACITY};
+use crate::a2a::server::events::Event;
+use crate::Task;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// Default subject task events are exchanged on
+pub const DEFAULT_EVENT_SUBJECT: &str = "a2a.task-events";
+
+/// A message broker carrying serialized bus events between replicas
+#[async_trait]
+pub trait EventBusTransport: Send + Sync {
+    /// Publishes a payload on a subject
+    async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<(), A2AError>;
+
+    /// Subscribes to the payloads published on a subject
+    async fn subscribe(&self, subject: &str) -> Result<BoxStream<'static, Vec<u8>>, A2AError>;
+}
+
+/// Configuration of a `DistributedEventBridge`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistributedEventBusConfig {
+    /// Subject shared by all replicas
+    pub subject: String,
+    /// Identifier of this replica, used to drop its own events coming back
+    pub replica_id: String,
+}
+
+impl DistributedEventBusConfig {
+    /// Creates a configuration with the default subject and a random replica id
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the subject
+    pub fn with_subject(mut self, subject: &str) -> Self {
+        self.subject = subject.to_string();
+        self
+    }
+
+    /// Sets the replica id
+    pub fn with_replica_id(mut self, replica_id: &str) -> Self {
+        self.replica_id = replica_id.to_string();
+        self
+    }
+}
+
+impl Default for DistributedEventBusConfig {
+    fn default() -> Self {
+        Self {
+            subject: DEFAULT_EVENT_SUBJECT.to_string(),
+            replica_id: Uuid::new_v4().to_string(),
+        }
+    }
+}
+
+/// Wire format of an event exchanged between replicas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WireEvent {
+    origin: String,
+    event: Event,
+    task: Task,
+}
+
+/// Connects a local `EventBus` to other replicas through a transport
+pub struct DistributedEventBridge<T: EventBusTransport + 'static> {
+    bus: EventBus,
+    transport: Arc<T>,
+    config: DistributedEventBusConfig,
+}
+
+impl<T: EventBusTransport + 'static> DistributedEventBridge<T> {
+    /// Creates a bridge for `bus` over `transport`
+    pub fn new(bus: EventBus, transport: Arc<T>, config: DistributedEventBusConfig) -> Self {
+        Self {
+            bus,
+            transport,
+            config,
+        }
+    }
+
+    /// Returns the bridge configuration
+    pub fn config(&self) -> &DistributedEventBusConfig {
+        &self.config
+    }
+
+    /// Starts forwarding events in both directions
+    ///
+    /// Returns the handles of the outbound and inbound tasks. The subscription
+    /// to the transport is established before this returns, so remote events
+    /// published afterwards are not missed. Must be called from within a Tokio
+    /// runtime.
+    pub async fn start(&self) -> Result<(JoinHandle<()>, JoinHandle<()>), A2AError> {
+        let mut inbound = self.transport.subscribe(&self.config.subject).await?;
+        let mut outbound = self.bus.subscribe();
+
+        let transport = self.transport.clone();
+        let config = self.config.clone();
+        let outbound_handle = tokio::spawn(async move {
+            loop {
+                let bus_event = match outbound.recv().await {
+                    Ok(bus_event) => bus_event,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Event bridge lagged, {} events were not forwarded", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                // Events received from other replicas are not forwarded again
+                if bus_event.origin.is_some() {
+                    continue;
+                }
+                let wire = WireEvent {
+                    origin: config.replica_id.clone(),
+                    event: bus_event.event,
+                    task: bus_event.task,
+                };
+                match serde_json::to_vec(&wire) {
+                    Ok(payload) => {
+                        if let Err(e) = transport.publish(&config.subject, payload).await {
+                            warn!("Failed to forward task event to '{}': {}", config.subject, e);
+                        }
+                    }
+                    Err(e) => warn!("Failed to serialize task event: {}", e),
+                }
+            }
+        });
+
+        let bus = self.bus.clone();
+        let replica_id = self.config.replica_id.clone();
+        let inbound_handle = tokio::spawn(async move {
+            while let Some(payload) = inbound.next().await {
+                let wire: WireEvent = match serde_json::from_slice(&payload) {
+                    Ok(wire) => wire,
+                    Err(e) => {
+                        warn!("Dropping malformed remote task event: {}", e);
+                        continue;
+                    }
+                };
+                if wire.origin == replica_id {
+                    continue;
+                }
+                debug!("Received task event for {} from replica {}", wire.task.id, wire.origin);
+                bus.publish_remote(wire.event, wire.task, wire.origin);
+            }
+        });
+
+        Ok((outbound_handle, inbound_handle))
+    }
+}
+
+/// An in-process transport, mainly useful for tests
+///
+/// Clones share the same channels, so buses bridged over clones of one
+/// transport behave like replicas connected to one broker.
+#[derive(Clone, Default)]
+pub struct InMemoryEventBusTransport {
+    subjects: Arc<Mutex<HashMap<String, broadcast::Sender<Vec<u8>>>>>,
+}
+
+impl InMemoryEventBusTransport {
+    /// Creates a new transport
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn sender(&self, subject: &str) -> broadcast::Sender<Vec<u8>> {
+        self.subjects
+            .lock()
+            .unwrap()
+            .entry(subject.to_string())
+            .or_insert_with(|| broadcast::channel(DEFAULT_EVENT_BUS_CAPACITY).0)
+            .clone()
+    }
+}
+
+#[async_trait]
+impl EventBusTransport for InMemoryEventBusTransport {
+    async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<(), A2AError> {
+        // An error only means there are currently no subscribers
+        let _ = self.sender(subject).send(payload);
+        Ok(())
+    }
+
+    async fn subscribe(&self, subject: &str) -> Result<BoxStream<'static, Vec<u8>>, A2AError> {
+        let mut receiver = self.sender(subject).subscribe();
+        Ok(Box::pin(async_stream::stream! {
+            loop {
+                match receiver.recv().await {
+                    Ok(payload) => yield payload,
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TaskState, TaskStatus, TaskStatusUpdateEvent};
+    use std::time::Duration;
+
+    fn task(id: &str, state: TaskState) -> Task {
+        Task::new("ctx".to_string(), TaskStatus::new(state)).with_task_id(id.to_string())
+    }
+
+    fn status_event(id: &str, state: TaskState) -> Event {
+        let r#final = state.is_terminal();
+        Event::TaskStatusUpdate(TaskStatusUpdateEvent::new(
+            id.to_string(),
+            "ctx".to_string(),
+            TaskStatus::new(state),
+            r#final,
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_events_reach_other_replicas() {
+        let transport = Arc::new(InMemoryEventBusTransport::new());
+        let bus_a = EventBus::new();
+        let bus_b = EventBus::new();
+        DistributedEventBridge::new(bus_a.clone(), transport.clone(), DistributedEventBusConfig::new().with_replica_id("a"))
+            .start()
+            .await
+            .unwrap();
+        DistributedEventBridge::new(bus_b.clone(), transport.clone(), DistributedEventBusConfig::new().with_replica_id("b"))
+            .start()
+            .await
+            .unwrap();
+
+        let mut local_a = bus_a.subscribe();
+        let remote_b = bus_b.subscribe_task("t1");
+
+        bus_a.publish(status_event("t1", TaskState::Working), task("t1", TaskState::Working));
+        bus_a.publish(status_event("t1", TaskState::Completed), task("t1", TaskState::Completed));
+
+        let events: Vec<_> = tokio::time::timeout(Duration::from_secs(5), remote_b.collect::<Vec<_>>())
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e.origin.as_deref() == Some("a")));
+        assert_eq!(events[1].task.status.state, TaskState::Completed);
+
+        // Replica a sees only its own two events, not echoes from b
+        assert!(local_a.recv().await.unwrap().origin.is_none());
+        assert!(local_a.recv().await.unwrap().origin.is_none());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(local_a.try_recv().is_err());
+    }
+}
@@ -0,0 +1,526 @@
+//! Internal event bus for task events
+//!
+//! The `EventBus` broadcasts every task event persisted by a `TaskManager` to any
+//! number of in-process consumers: push notification delivery, metrics, audit
+//! logging and SSE resubscription streams all subscribe to the same bus instead
+//! of being wired into the request handler individually.
+//!
+//! The bus is built on `tokio::sync::broadcast`. Slow subscribers that fall more
+//! than the channel capacity behind skip the oldest events and log a warning.
+
+use crate::a2a::server::events::task_outcomes::{OutcomeTracker, SkillMetrics, SloMetrics, SloObjective};
+use crate::a2a::server::events::Event;
+use crate::a2a::server::tasks::PushNotificationSender;
+use crate::{NotificationEventKind, Task};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::{broadcast, Notify};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Default number of events buffered per subscriber
+pub const DEFAULT_EVENT_BUS_CAPACITY: usize = 1024;
+
+/// An event published on the bus
+#[derive(Debug, Clone)]
+pub struct BusEvent {
+    /// Monotonically increasing sequence number assigned by the bus
+    pub sequence: u64,
+    /// The event that was applied to the task
+    pub event: Event,
+    /// The task state after the event was applied
+    pub task: Task,
+    /// The replica that produced the event, or `None` if it was produced locally
+    pub origin: Option<String>,
+}
+
+impl BusEvent {
+    /// Returns true if this is the last event for the task
+    pub fn is_final(&self) -> bool {
+        match &self.event {
+            Event::TaskStatusUpdate(update) => update.r#final || update.status.state.is_terminal(),
+            _ => self.task.status.state.is_terminal(),
+        }
+    }
+}
+
+/// A record of one task event, as produced by the Kafka sink and the SSE relay
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskEventRecord<'a> {
+    /// Sequence number assigned by the event bus
+    pub sequence: u64,
+    /// `status-update`, `artifact-update` or `task`
+    pub event_type: &'static str,
+    /// The task id, also used as the Kafka record key
+    pub task_id: &'a str,
+    /// The context id of the task
+    pub context_id: &'a str,
+    /// The replica that produced the event, if it was not produced locally
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<&'a str>,
+    /// The event itself
+    pub event: &'a Event,
+    /// The task state after the event was applied
+    pub task: &'a Task,
+}
+
+impl<'a> TaskEventRecord<'a> {
+    /// Builds the record for a bus event
+    ///
+    /// Returns `None` for events that are not task lifecycle events, such as
+    /// plain messages.
+    pub fn from_bus_event(event: &'a BusEvent) -> Option<Self> {
+        let event_type = match &event.event {
+            Event::TaskStatusUpdate(_) => "status-update",
+            Event::TaskArtifactUpdate(_) => "artifact-update",
+            Event::Task(_) => "task",
+            Event::Message(_) => return None,
+        };
+        Some(Self {
+            sequence: event.sequence,
+            event_type,
+            task_id: &event.task.id,
+            context_id: &event.task.context_id,
+            origin: event.origin.as_deref(),
+            event: &event.event,
+            task: &event.task,
+        })
+    }
+}
+
+/// A consumer of bus events
+#[async_trait]
+pub trait EventBusSubscriber: Send + Sync {
+    /// A short name used in logs
+    fn name(&self) -> &str;
+
+    /// Handles a single event; errors should be handled by the subscriber itself
+    async fn on_event(&self, event: &BusEvent);
+
+    /// Called when the subscriber fell behind and `skipped` events were
+    /// dropped before it could handle them
+    async fn on_lagged(&self, _skipped: u64) {}
+}
+
+/// Progress of a spawned subscriber, used by `EventBus::flush`
+#[derive(Debug, Default)]
+struct SubscriberProgress {
+    processed: AtomicU64,
+    finished: AtomicBool,
+}
+
+/// Broadcast bus for task events
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<BusEvent>,
+    sequence: Arc<Mutex<u64>>,
+    subscribers: Arc<Mutex<Vec<Arc<SubscriberProgress>>>>,
+    progress: Arc<Notify>,
+}
+
+impl EventBus {
+    /// Creates a new bus with the default capacity
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_EVENT_BUS_CAPACITY)
+    }
+
+    /// Creates a new bus buffering up to `capacity` events per subscriber
+    pub fn with_capacity(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self {
+            sender,
+            sequence: Arc::new(Mutex::new(0)),
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+            progress: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Publishes an event, returning its sequence number
+    ///
+    /// Publishing never blocks and succeeds even if nobody is subscribed.
+    pub fn publish(&self, event: Event, task: Task) -> u64 {
+        self.publish_with_origin(event, task, None)
+    }
+
+    /// Publishes an event received from another replica
+    ///
+    /// Used by `DistributedEventBridge`; the origin lets the bridge avoid
+    /// echoing the event back to the transport.
+    pub fn publish_remote(&self, event: Event, task: Task, origin: String) -> u64 {
+        self.publish_with_origin(event, task, Some(origin))
+    }
+
+    fn publish_with_origin(&self, event: Event, task: Task, origin: Option<String>) -> u64 {
+        let mut sequence = self.sequence.lock().unwrap();
+        *sequence += 1;
+        let bus_event = BusEvent {
+            sequence: *sequence,
+            event,
+            task,
+            origin,
+        };
+        // An error only means there are currently no receivers
+        let _ = self.sender.send(bus_event);
+        *sequence
+    }
+
+    /// Returns the sequence number of the last published event
+    pub fn last_sequence(&self) -> u64 {
+        *self.sequence.lock().unwrap()
+    }
+
+    /// Subscribes to all future events
+    pub fn subscribe(&self) -> broadcast::Receiver<BusEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Returns the number of active receivers
+    pub fn receiver_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Subscribes to the future events of a single task
+    ///
+    /// The stream ends after the task's final event.
+    pub fn subscribe_task(&self, task_id: &str) -> BoxStream<'static, BusEvent> {
+        let mut receiver = self.subscribe();
+        let task_id = task_id.to_string();
+        Box::pin(async_stream::stream! {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if event.task.id == task_id => {
+                        let is_final = event.is_final();
+                        yield event;
+                        if is_final {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Task {} subscriber lagged, skipped {} events", task_id, skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    /// Spawns a Tokio task feeding every future event to `subscriber`
+    ///
+    /// The task runs until the bus is dropped. Must be called from within a
+    /// Tokio runtime.
+    pub fn spawn_subscriber(&self, subscriber: Arc<dyn EventBusSubscriber>) -> JoinHandle<()> {
+        let mut receiver = self.subscribe();
+        let progress = Arc::new(SubscriberProgress {
+            processed: AtomicU64::new(self.last_sequence()),
+            finished: AtomicBool::new(false),
+        });
+        self.subscribers.lock().unwrap().push(progress.clone());
+        let notify = self.progress.clone();
+
+        tokio::spawn(async move {
+            debug!("Event bus subscriber '{}' started", subscriber.name());
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        subscriber.on_event(&event).await;
+                        progress.processed.store(event.sequence, Ordering::SeqCst);
+                        notify.notify_waiters();
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "Event bus subscriber '{}' lagged, skipped {} events",
+                            subscriber.name(),
+                            skipped
+                        );
+                        subscriber.on_lagged(skipped).await;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+            progress.finished.store(true, Ordering::SeqCst);
+            notify.notify_waiters();
+            debug!("Event bus subscriber '{}' stopped", subscriber.name());
+        })
+    }
+
+    /// Waits until every spawned subscriber has processed all events published
+    /// before this call
+    pub async fn flush(&self) {
+        let target = self.last_sequence();
+        loop {
+            let notified = self.progress.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            let done = self.subscribers.lock().unwrap().iter().all(|p| {
+                p.finished.load(Ordering::SeqCst) || p.processed.load(Ordering::SeqCst) >= target
+            });
+            if done {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Delivers push notifications for every task event
+pub struct PushNotificationSubscriber {
+    sender: Arc<dyn PushNotificationSender>,
+}
+
+impl PushNotificationSubscriber {
+    /// Creates a subscriber delivering through `sender`
+    pub fn new(sender: Arc<dyn PushNotificationSender>) -> Self {
+        Self { sender }
+    }
+}
+
+#[async_trait]
+impl EventBusSubscriber for PushNotificationSubscriber {
+    fn name(&self) -> &str {
+        "push-notifications"
+    }
+
+    async fn on_event(&self, event: &BusEvent) {
+        let result = match notification_kind(&event.event) {
+            Some(kind) => self.sender.send_event_notification(&event.task, kind).await,
+            None => self.sender.send_notification(&event.task).await,
+        };
+        if let Err(e) = result {
+            tracing::error!("Failed to send push notification: {}", e);
+        }
+    }
+}
+
+/// A snapshot of the counters collected by `MetricsSubscriber`
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct EventMetrics {
+    /// Total number of events observed
+    pub total_events: u64,
+    /// Number of events per event type
+    pub events_by_type: HashMap<String, u64>,
+    /// Number of tasks that reached each terminal state
+    pub terminal_states: HashMap<String, u64>,
+    /// Number of requests whose handler panicked
+    pub handler_panics: u64,
+    /// Number of SSE streams currently open
+    pub active_streams: u64,
+    /// Number of SSE streams dropped because their client stopped reading
+    pub reaped_streams: u64,
+    /// Number of task executions per outcome
+    pub task_outcomes: HashMap<String, u64>,
+    /// Execution outcomes and durations per skill
+    pub skills: BTreeMap<String, SkillMetrics>,
+    /// Gauges of the service level objective, if one is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slo: Option<SloMetrics>,
+}
+
+/// Collects in-process counters about task events
+///
+/// Task outcomes and execution durations are described in
+/// [`task_outcomes`](crate::a2a::server::events::task_outcomes).
+#[derive(Debug, Default)]
+pub struct MetricsSubscriber {
+    metrics: Mutex<EventMetrics>,
+    outcomes: Mutex<OutcomeTracker>,
+}
+
+impl MetricsSubscriber {
+    /// Creates a subscriber with zeroed counters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Computes the burn rate of `objective` from the task outcomes
+    pub fn with_slo(self, objective: SloObjective) -> Self {
+        let outcomes = self.outcomes.into_inner().unwrap().with_slo(objective);
+        Self {
+            metrics: self.metrics,
+            outcomes: Mutex::new(outcomes),
+        }
+    }
+
+    /// Returns a snapshot of the collected counters
+    pub fn snapshot(&self) -> EventMetrics {
+        let mut snapshot = self.metrics.lock().unwrap().clone();
+        let mut outcomes = self.outcomes.lock().unwrap();
+        snapshot.task_outcomes = outcomes.outcomes();
+        snapshot.skills = outcomes.skills();
+        snapshot.slo = outcomes.slo(Instant::now());
+        snapshot
+    }
+
+    /// Counts a request whose handler panicked
+    pub fn record_handler_panic(&self) {
+        self.metrics.lock().unwrap().handler_panics += 1;
+    }
+
+    /// Counts an SSE stream being opened
+    pub fn record_stream_opened(&self) {
+        self.metrics.lock().unwrap().active_streams += 1;
+    }
+
+    /// Counts an SSE stream being closed, however it ended
+    pub fn record_stream_closed(&self) {
+        let mut metrics = self.metrics.lock().unwrap();
+        metrics.active_streams = metrics.active_streams.saturating_sub(1);
+    }
+
+    /// Counts an SSE stream dropped because its client stopped reading
+    pub fn record_stream_reaped(&self) {
+        self.metrics.lock().unwrap().reaped_streams += 1;
+    }
+}
+
+#[async_trait]
+impl EventBusSubscriber for MetricsSubscriber {
+    fn name(&self) -> &str {
+        "metrics"
+    }
+
+    async fn on_event(&self, event: &BusEvent) {
+        if !matches!(event.event, Event::Message(_)) {
+            self.outcomes.lock().unwrap().record(&event.task, event.is_final(), Instant::now());
+        }
+        let mut metrics = self.metrics.lock().unwrap();
+        metrics.total_events += 1;
+        *metrics
+            .events_by_type
+            .entry(event_type(&event.event).to_string())
+            .or_insert(0) += 1;
+        if event.is_final() {
+            let state = serde_json::to_value(&event.task.status.state)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default();
+            *metrics.terminal_states.entry(state).or_insert(0) += 1;
+        }
+    }
+}
+
+/// Writes an audit record for every task event to the `a2a::audit` tracing target
+#[derive(Debug, Default)]
+pub struct AuditLogSubscriber;
+
+impl AuditLogSubscriber {
+    /// Creates a new audit log subscriber
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl EventBusSubscriber for AuditLogSubscriber {
+    fn name(&self) -> &str {
+        "audit-log"
+    }
+
+    async fn on_event(&self, event: &BusEvent) {
+        info!(
+            target: "a2a::audit",
+            sequence = event.sequence,
+            task_id = %event.task.id,
+            context_id = %event.task.context_id,
+            event_type = event_type(&event.event),
+            state = ?event.task.status.state,
+            "task event"
+        );
+    }
+}
+
+fn notification_kind(event: &Event) -> Option<NotificationEventKind> {
+    match event {
+        Event::Message(_) => None,
+        Event::Task(_) => Some(NotificationEventKind::Task),
+        Event::TaskStatusUpdate(_) => Some(NotificationEventKind::StatusUpdate),
+        Event::TaskArtifactUpdate(_) => Some(NotificationEventKind::ArtifactUpdate),
+    }
+}
+
+pub(crate) fn event_type(event: &Event) -> &'static str {
+    match event {
+        Event::Message(_) => "message",
+        Event::Task(_) => "task",
+        Event::TaskStatusUpdate(_) => "status-update",
+        Event::TaskArtifactUpdate(_) => "artifact-update",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TaskState, TaskStatus, TaskStatusUpdateEvent};
+    use futures::StreamExt;
+
+    fn task(id: &str, state: TaskState) -> Task {
+        Task::new("ctx".to_string(), TaskStatus::new(state)).with_task_id(id.to_string())
+    }
+
+    fn status_event(id: &str, state: TaskState, r#final: bool) -> Event {
+        Event::TaskStatusUpdate(TaskStatusUpdateEvent::new(
+            id.to_string(),
+            "ctx".to_string(),
+            TaskStatus::new(state),
+            r#final,
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_publish_without_subscribers() {
+        let bus = EventBus::new();
+        assert_eq!(bus.publish(Event::Task(task("t1", TaskState::Working)), task("t1", TaskState::Working)), 1);
+        assert_eq!(bus.publish(Event::Task(task("t1", TaskState::Working)), task("t1", TaskState::Working)), 2);
+        bus.flush().await;
+    }
+
+    #[tokio::test]
+    async fn test_spawned_subscribers_receive_events() {
+        let bus = EventBus::new();
+        let metrics = Arc::new(MetricsSubscriber::new());
+        bus.spawn_subscriber(metrics.clone());
+        bus.spawn_subscriber(Arc::new(AuditLogSubscriber::new()));
+
+        bus.publish(Event::Task(task("t1", TaskState::Working)), task("t1", TaskState::Working));
+        bus.publish(status_event("t1", TaskState::Completed, true), task("t1", TaskState::Completed));
+        bus.flush().await;
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.total_events, 2);
+        assert_eq!(snapshot.events_by_type["task"], 1);
+        assert_eq!(snapshot.events_by_type["status-update"], 1);
+        assert_eq!(snapshot.terminal_states["completed"], 1);
+        assert_eq!(snapshot.task_outcomes["completed"], 1);
+        assert_eq!(snapshot.skills[crate::a2a::server::events::UNSPECIFIED_SKILL].outcomes["completed"], 1);
+        assert!(snapshot.slo.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_task_filters_and_ends_on_final() {
+        let bus = EventBus::new();
+        let stream = bus.subscribe_task("t1");
+
+        bus.publish(status_event("t2", TaskState::Working, false), task("t2", TaskState::Working));
+        bus.publish(status_event("t1", TaskState::Working, false), task("t1", TaskState::Working));
+        bus.publish(status_event("t1", TaskState::Completed, true), task("t1", TaskState::Completed));
+        bus.publish(status_event("t1", TaskState::Working, false), task("t1", TaskState::Working));
+
+        let events: Vec<BusEvent> = stream.collect().await;
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e.task.id == "t1"));
+        assert!(events[1].is_final());
+    }
+}
@@ -0,0 +1,301 @@
+//! Retained task events for stream resumption
+//!
+//! Every event on the `EventBus` carries a sequence number. Streaming
+//! responses expose it to clients as an opaque event id (the SSE `id` field);
+//! a client that loses its connection passes the last id it saw as
+//! `from_event_id` to `tasks/resubscribe`, and the server replays the events
+//! retained by the `EventStore` after that point before continuing live.
+//!
+//! `InMemoryEventStore` keeps at most a number of events per task and drops
+//! events once they are older than its retention, along with the tasks left
+//! without events, so finished tasks do not pile up.
+
+use crate::a2a::error::A2AError;
+use crate::a2a::server::events::{BusEvent, EventBusSubscriber};
+use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Default number of events retained per task by `InMemoryEventStore`
+pub const DEFAULT_MAX_EVENTS_PER_TASK: usize = 1000;
+
+/// Default time `InMemoryEventStore` retains events for
+pub const DEFAULT_EVENT_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+const EVENT_ID_PREFIX: &str = "ev1-";
+
+/// Encodes a bus sequence number as an opaque event id
+pub fn event_id(sequence: u64) -> String {
+    format!("{}{:x}", EVENT_ID_PREFIX, sequence)
+}
+
+/// Decodes an event id produced by [`event_id`]
+pub fn parse_event_id(event_id: &str) -> Result<u64, A2AError> {
+    event_id
+        .strip_prefix(EVENT_ID_PREFIX)
+        .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+        .ok_or_else(|| A2AError::invalid_params(&format!("Invalid event id: {}", event_id)))
+}
+
+impl BusEvent {
+    /// The opaque id clients resume the stream after
+    pub fn event_id(&self) -> String {
+        event_id(self.sequence)
+    }
+}
+
+/// Storage of recent task events
+#[async_trait]
+pub trait EventStore: Send + Sync {
+    /// Retains an event published on the bus
+    async fn append(&self, event: &BusEvent) -> Result<(), A2AError>;
+
+    /// Returns the events of a task published after `sequence`, oldest first
+    ///
+    /// Fails if events after `sequence` were already discarded, since the
+    /// replay would otherwise silently skip them.
+    async fn events_after(&self, task_id: &str, sequence: u64) -> Result<Vec<BusEvent>, A2AError>;
+
+    /// Drops all events of a task (optional implementation)
+    async fn remove_task(&self, _task_id: &str) -> Result<(), A2AError> {
+        Err(A2AError::unsupported_operation("Removing task events not supported"))
+    }
+}
+
+#[derive(Debug, Default)]
+struct TaskEvents {
+    events: VecDeque<(Instant, BusEvent)>,
+    // Sequence of the newest event discarded to stay within the limits
+    discarded_up_to: u64,
+}
+
+impl TaskEvents {
+    fn discard_front(&mut self) {
+        if let Some((_, discarded)) = self.events.pop_front() {
+            self.discarded_up_to = discarded.sequence;
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Retained {
+    tasks: HashMap<String, TaskEvents>,
+    // Sequence of the newest event of the tasks removed once expired
+    removed_up_to: u64,
+    last_expiry: Instant,
+}
+
+/// In-memory event store keeping the recent events of each task
+#[derive(Debug)]
+pub struct InMemoryEventStore {
+    retained: Mutex<Retained>,
+    max_events_per_task: usize,
+    retention: Duration,
+}
+
+impl InMemoryEventStore {
+    /// Creates a store retaining up to `DEFAULT_MAX_EVENTS_PER_TASK` events
+    /// per task for `DEFAULT_EVENT_RETENTION`
+    pub fn new() -> Self {
+        Self {
+            retained: Mutex::new(Retained {
+                tasks: HashMap::new(),
+                removed_up_to: 0,
+                last_expiry: Instant::now(),
+            }),
+            max_events_per_task: DEFAULT_MAX_EVENTS_PER_TASK,
+            retention: DEFAULT_EVENT_RETENTION,
+        }
+    }
+
+    /// Sets how many events are retained per task
+    pub fn with_max_events_per_task(mut self, max_events_per_task: usize) -> Self {
+        self.max_events_per_task = max_events_per_task.max(1);
+        self
+    }
+
+    /// Sets how long events are retained
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Drops the expired events, checking every task at most once per
+    /// tenth of the retention
+    fn expire(&self, retained: &mut Retained, now: Instant) {
+        if now.duration_since(retained.last_expiry) < self.retention / 10 {
+            return;
+        }
+        retained.last_expiry = now;
+        let mut removed_up_to = retained.removed_up_to;
+        retained.tasks.retain(|_, entry| {
+            while entry.events.front().is_some_and(|(at, _)| now.duration_since(*at) >= self.retention) {
+                entry.discard_front();
+            }
+            if entry.events.is_empty() {
+                removed_up_to = removed_up_to.max(entry.discarded_up_to);
+                return false;
+            }
+            true
+        });
+        retained.removed_up_to = removed_up_to;
+    }
+}
+
+impl Default for InMemoryEventStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl EventStore for InMemoryEventStore {
+    async fn append(&self, event: &BusEvent) -> Result<(), A2AError> {
+        let now = Instant::now();
+        let mut retained = self.retained.lock().unwrap();
+        self.expire(&mut retained, now);
+        let entry = retained.tasks.entry(event.task.id.clone()).or_default();
+        entry.events.push_back((now, event.clone()));
+        while entry.events.len() > self.max_events_per_task {
+            entry.discard_front();
+        }
+        Ok(())
+    }
+
+    async fn events_after(&self, task_id: &str, sequence: u64) -> Result<Vec<BusEvent>, A2AError> {
+        let now = Instant::now();
+        let mut retained = self.retained.lock().unwrap();
+        self.expire(&mut retained, now);
+        // The events of a removed task may have been discarded
+        let discarded_up_to = match retained.tasks.get(task_id) {
+            Some(entry) => entry.discarded_up_to,
+            None => retained.removed_up_to,
+        };
+        if sequence < discarded_up_to {
+            return Err(A2AError::invalid_params(&format!(
+                "Events after {} are no longer available for task {}",
+                event_id(sequence),
+                task_id
+            )));
+        }
+        Ok(retained
+            .tasks
+            .get(task_id)
+            .into_iter()
+            .flat_map(|entry| entry.events.iter())
+            .filter(|(at, event)| event.sequence > sequence && now.duration_since(*at) < self.retention)
+            .map(|(_, event)| event.clone())
+            .collect())
+    }
+
+    async fn remove_task(&self, task_id: &str) -> Result<(), A2AError> {
+        let mut retained = self.retained.lock().unwrap();
+        if let Some(entry) = retained.tasks.remove(task_id) {
+            let newest = entry.events.back().map_or(entry.discarded_up_to, |(_, event)| event.sequence);
+            retained.removed_up_to = retained.removed_up_to.max(newest);
+        }
+        Ok(())
+    }
+}
+
+/// Records every bus event in an `EventStore`
+pub struct EventStoreSubscriber {
+    store: Arc<dyn EventStore>,
+}
+
+impl EventStoreSubscriber {
+    /// Creates a subscriber appending to `store`
+    pub fn new(store: Arc<dyn EventStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl EventBusSubscriber for EventStoreSubscriber {
+    fn name(&self) -> &str {
+        "event-store"
+    }
+
+    async fn on_event(&self, event: &BusEvent) {
+        if let Err(e) = self.store.append(event).await {
+            tracing::error!("Failed to store event {}: {}", event.sequence, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::server::events::{Event, EventBus};
+    use crate::{Task, TaskState, TaskStatus};
+
+    fn task(id: &str) -> Task {
+        Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id(id.to_string())
+    }
+
+    #[test]
+    fn test_event_id_round_trip() {
+        assert_eq!(parse_event_id(&event_id(300)).unwrap(), 300);
+        assert!(parse_event_id("300").is_err());
+        assert!(parse_event_id("ev1-zz").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_events_after_returns_task_tail() {
+        let bus = EventBus::new();
+        let store = Arc::new(InMemoryEventStore::new());
+        bus.spawn_subscriber(Arc::new(EventStoreSubscriber::new(store.clone())));
+
+        for id in ["t1", "t2", "t1", "t1"] {
+            bus.publish(Event::Task(task(id)), task(id));
+        }
+        bus.flush().await;
+
+        let tail = store.events_after("t1", 1).await.unwrap();
+        assert_eq!(tail.iter().map(|e| e.sequence).collect::<Vec<_>>(), vec![3, 4]);
+        assert!(store.events_after("t1", 4).await.unwrap().is_empty());
+        assert!(store.events_after("unknown", 0).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_discarded_events_fail_replay() {
+        let bus = EventBus::new();
+        let store = InMemoryEventStore::new().with_max_events_per_task(2);
+        for _ in 0..4 {
+            let sequence = bus.publish(Event::Task(task("t1")), task("t1"));
+            store
+                .append(&BusEvent {
+                    sequence,
+                    event: Event::Task(task("t1")),
+                    task: task("t1"),
+                    origin: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(store.events_after("t1", 2).await.unwrap().len(), 2);
+        assert!(store.events_after("t1", 1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expired_events_are_dropped() {
+        let store = InMemoryEventStore::new().with_retention(Duration::from_millis(20));
+        let event = |sequence| BusEvent {
+            sequence,
+            event: Event::Task(task("t1")),
+            task: task("t1"),
+            origin: None,
+        };
+        store.append(&event(1)).await.unwrap();
+        store.append(&event(2)).await.unwrap();
+        assert_eq!(store.events_after("t1", 0).await.unwrap().len(), 2);
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(store.events_after("t1", 0).await.is_err());
+        assert!(store.retained.lock().unwrap().tasks.is_empty());
+        // Nothing after the newest expired event was lost
+        assert!(store.events_after("t1", 2).await.unwrap().is_empty());
+    }
+}
@@ -7,7 +7,8 @@ use crate::a2a::error::A2AError;
 use crate::a2a::server::events::{Event, EventQueue, QueueConfig, QueueError};
 use async_trait::async_trait;
 use std::collections::VecDeque;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use crate::a2a::utils::sequence::{event_sequence, set_event_sequence};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{broadcast, Notify, Mutex};
@@ -29,6 +30,8 @@ pub struct InMemoryEventQueue {
     event_sender: broadcast::Sender<Event>,
     /// Current queue size for atomic access
     current_size: Arc<AtomicUsize>,
+    /// Last sequence number assigned to an unnumbered update event
+    last_sequence: Arc<AtomicU64>,
 }
 
 impl InMemoryEventQueue {
@@ -51,11 +54,34 @@ impl InMemoryEventQueue {
             children: Arc::new(Mutex::new(Vec::new())),
             event_sender,
             current_size: Arc::new(AtomicUsize::new(0)),
+            last_sequence: Arc::new(AtomicU64::new(0)),
         })
     }
 
+    /// Numbers update events that do not carry a sequence number yet
+    ///
+    /// Numbers already present are kept and continued from, so events
+    /// numbered by a `TaskManager` pass through unchanged.
+    fn assign_sequence(&self, event: &mut Event) {
+        let metadata_sequence = match event {
+            Event::TaskStatusUpdate(update) => event_sequence(update),
+            Event::TaskArtifactUpdate(update) => event_sequence(update),
+            _ => return,
+        };
+        if let Some(sequence) = metadata_sequence {
+            self.last_sequence.fetch_max(sequence, Ordering::SeqCst);
+            return;
+        }
+        let sequence = self.last_sequence.fetch_add(1, Ordering::SeqCst) + 1;
+        match event {
+            Event::TaskStatusUpdate(update) => set_event_sequence(update, sequence),
+            Event::TaskArtifactUpdate(update) => set_event_sequence(update, sequence),
+            _ => {}
+        }
+    }
+
     /// Internal method to add an event to the queue
-    async fn push_internal(&self, event: Event) -> Result<(), A2AError> {
+    async fn push_internal(&self, mut event: Event) -> Result<(), A2AError> {
         if self.is_closed.load(Ordering::Relaxed) {
             return Err(QueueError::Closed.into());
         }
@@ -66,6 +92,7 @@ impl InMemoryEventQueue {
                 return Err(QueueError::Full.into());
             }
 
+            self.assign_sequence(&mut event);
             queue.push_back(event.clone());
         }
 
@@ -387,4 +414,34 @@ mod tests {
         let result = queue.dequeue_event(true).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_update_events_are_numbered() {
+        let queue = InMemoryEventQueue::new().unwrap();
+        let status = |sequence: Option<u64>| {
+            let mut update = crate::TaskStatusUpdateEvent::new(
+                "t1".to_string(),
+                "c1".to_string(),
+                TaskStatus::new(TaskState::Working),
+                false,
+            );
+            if let Some(sequence) = sequence {
+                set_event_sequence(&mut update, sequence);
+            }
+            Event::TaskStatusUpdate(update)
+        };
+
+        queue.enqueue_event(status(None)).await.unwrap();
+        queue.enqueue_event(Event::Message(Message::new(Role::Agent, vec![]))).await.unwrap();
+        queue.enqueue_event(status(Some(5))).await.unwrap();
+        queue.enqueue_event(status(None)).await.unwrap();
+
+        let mut sequences = Vec::new();
+        while let Ok(event) = queue.dequeue_event(true).await {
+            if let Event::TaskStatusUpdate(update) = event {
+                sequences.push(event_sequence(&update));
+            }
+        }
+        assert_eq!(sequences, vec![Some(1), Some(5), Some(6)]);
+    }
 }
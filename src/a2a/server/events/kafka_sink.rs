@@ -0,0 +1,366 @@
+//! Kafka sink for task lifecycle events
+//!
+//! Available with the `kafka` feature. `KafkaEventSink` is an `EventBusSubscriber`
+//! that produces a JSON record for every task state change and artifact event to
+//! a Kafka topic, keyed by task id so all records of a task land on the same
+//! partition in order. Records can alternatively be encoded as CloudEvents.
+//!
+//! Delivery is at-least-once: the producer is idempotent with `acks=all`, and a
+//! record that fails to be delivered is retried with a doubling backoff, up to
+//! `max_delivery_attempts` times. A record that still fails, and the events
+//! the sink missed because it fell behind the bus, are logged and counted in
+//! `KafkaEventSink::dropped_events`. Consumers should deduplicate on
+//! `(taskId, sequence)` if exactly-once processing matters to them.
+
+use crate::a2a::error::A2AError;
+use crate::a2a::server::events::event_bus::{BusEvent, EventBusSubscriber};
+pub use crate::a2a::server::events::event_bus::TaskEventRecord;
+use crate::a2a::server::tasks::DEFAULT_CLOUDEVENTS_SOURCE;
+use crate::a2a::utils::cloudevents::IntoCloudEvent;
+use async_trait::async_trait;
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord, Producer};
+use rdkafka::util::Timeout;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::{error, warn};
+
+/// Default topic task events are produced to
+pub const DEFAULT_KAFKA_TOPIC: &str = "a2a.task-events";
+
+/// Configuration of a `KafkaEventSink`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KafkaSinkConfig {
+    /// Comma separated list of bootstrap brokers
+    pub brokers: String,
+    /// Topic the records are produced to
+    pub topic: String,
+    /// Client id reported to the brokers
+    pub client_id: String,
+    /// Time allowed for a single delivery attempt
+    pub delivery_timeout: Duration,
+    /// Delay before the second delivery attempt of a failed record, doubled
+    /// for every further attempt
+    pub retry_backoff: Duration,
+    /// Longest delay between delivery attempts
+    pub max_retry_backoff: Duration,
+    /// Delivery attempts of a record before it is dropped
+    pub max_delivery_attempts: u32,
+    /// Encoding of the produced records
+    pub format: KafkaRecordFormat,
+    /// CloudEvents `source` attribute, used with `KafkaRecordFormat::CloudEvents`
+    pub cloudevents_source: String,
+    /// Additional librdkafka properties, e.g. security settings
+    pub properties: HashMap<String, String>,
+}
+
+/// Encoding of the records produced by `KafkaEventSink`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KafkaRecordFormat {
+    /// A `TaskEventRecord` as JSON
+    #[default]
+    Json,
+    /// A CloudEvent in structured JSON mode, with the bus sequence number in
+    /// the `a2asequence` extension attribute
+    CloudEvents,
+}
+
+impl KafkaSinkConfig {
+    /// Creates a configuration for the given brokers and topic
+    pub fn new(brokers: &str, topic: &str) -> Self {
+        Self {
+            brokers: brokers.to_string(),
+            topic: topic.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the client id
+    pub fn with_client_id(mut self, client_id: &str) -> Self {
+        self.client_id = client_id.to_string();
+        self
+    }
+
+    /// Sets the delivery timeout
+    pub fn with_delivery_timeout(mut self, timeout: Duration) -> Self {
+        self.delivery_timeout = timeout;
+        self
+    }
+
+    /// Sets the delay between delivery attempts
+    pub fn with_retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_backoff = backoff;
+        self
+    }
+
+    /// Sets the longest delay between delivery attempts
+    pub fn with_max_retry_backoff(mut self, backoff: Duration) -> Self {
+        self.max_retry_backoff = backoff;
+        self
+    }
+
+    /// Sets the delivery attempts of a record before it is dropped
+    pub fn with_max_delivery_attempts(mut self, attempts: u32) -> Self {
+        self.max_delivery_attempts = attempts.max(1);
+        self
+    }
+
+    /// The delay after the failed delivery attempt `attempt`, counted from one
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.retry_backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_retry_backoff)
+    }
+
+    /// Sets the record format
+    pub fn with_format(mut self, format: KafkaRecordFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Produces CloudEvents with the given `source` attribute
+    pub fn with_cloudevents(mut self, source: &str) -> Self {
+        self.format = KafkaRecordFormat::CloudEvents;
+        self.cloudevents_source = source.to_string();
+        self
+    }
+
+    /// Sets an additional librdkafka property
+    pub fn with_property(mut self, key: &str, value: &str) -> Self {
+        self.properties.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    fn client_config(&self) -> ClientConfig {
+        let mut config = ClientConfig::new();
+        config
+            .set("bootstrap.servers", &self.brokers)
+            .set("client.id", &self.client_id)
+            .set("acks", "all")
+            .set("enable.idempotence", "true")
+            .set("message.timeout.ms", self.delivery_timeout.as_millis().to_string());
+        for (key, value) in &self.properties {
+            config.set(key, value);
+        }
+        config
+    }
+}
+
+impl Default for KafkaSinkConfig {
+    fn default() -> Self {
+        Self {
+            brokers: "localhost:9092".to_string(),
+            topic: DEFAULT_KAFKA_TOPIC.to_string(),
+            client_id: "a2a-rust".to_string(),
+            delivery_timeout: Duration::from_secs(30),
+            retry_backoff: Duration::from_secs(1),
+            max_retry_backoff: Duration::from_secs(30),
+            max_delivery_attempts: 10,
+            format: KafkaRecordFormat::Json,
+            cloudevents_source: DEFAULT_CLOUDEVENTS_SOURCE.to_string(),
+            properties: HashMap::new(),
+        }
+    }
+}
+
+/// Publishes task lifecycle events to Kafka
+pub struct KafkaEventSink {
+    producer: FutureProducer,
+    config: KafkaSinkConfig,
+    dropped: AtomicU64,
+}
+
+impl KafkaEventSink {
+    /// Creates a sink, connecting the producer lazily
+    pub fn new(config: KafkaSinkConfig) -> Result<Self, A2AError> {
+        let producer = config
+            .client_config()
+            .create()
+            .map_err(|e| A2AError::transport_error(format!("Failed to create Kafka producer: {}", e)))?;
+        Ok(Self {
+            producer,
+            config,
+            dropped: AtomicU64::new(0),
+        })
+    }
+
+    /// Returns the sink configuration
+    pub fn config(&self) -> &KafkaSinkConfig {
+        &self.config
+    }
+
+    /// The number of events that were never delivered, because every
+    /// delivery attempt failed or the sink fell behind the bus
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Encodes a record in the configured format
+    pub fn encode(&self, record: &TaskEventRecord<'_>) -> Result<Vec<u8>, A2AError> {
+        match self.config.format {
+            KafkaRecordFormat::Json => Ok(serde_json::to_vec(record)?),
+            KafkaRecordFormat::CloudEvents => record
+                .event
+                .to_cloud_event(&self.config.cloudevents_source)?
+                .with_extension("a2asequence", record.sequence)?
+                .to_json_bytes(),
+        }
+    }
+
+    /// Produces a single record, retrying up to the configured attempts
+    pub async fn send(&self, record: &TaskEventRecord<'_>) -> Result<(), A2AError> {
+        let payload = self.encode(record)?;
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let kafka_record = FutureRecord::to(&self.config.topic)
+                .key(record.task_id)
+                .payload(&payload);
+            match self
+                .producer
+                .send(kafka_record, Timeout::After(self.config.delivery_timeout))
+                .await
+            {
+                Ok(_) => return Ok(()),
+                Err((e, _)) if attempt >= self.config.max_delivery_attempts => {
+                    return Err(A2AError::transport_error(format!(
+                        "Failed to deliver event {} of task {} to Kafka after {} attempts: {}",
+                        record.sequence, record.task_id, attempt, e
+                    )));
+                }
+                Err((e, _)) => {
+                    warn!(
+                        "Failed to deliver event {} of task {} to Kafka, retrying: {}",
+                        record.sequence, record.task_id, e
+                    );
+                    tokio::time::sleep(self.config.backoff(attempt)).await;
+                }
+            }
+        }
+    }
+
+    /// Waits for all in-flight records to be delivered
+    pub fn flush(&self, timeout: Duration) -> Result<(), A2AError> {
+        self.producer
+            .flush(Timeout::After(timeout))
+            .map_err(|e| A2AError::transport_error(format!("Failed to flush Kafka producer: {}", e)))
+    }
+}
+
+#[async_trait]
+impl EventBusSubscriber for KafkaEventSink {
+    fn name(&self) -> &str {
+        "kafka-sink"
+    }
+
+    async fn on_event(&self, event: &BusEvent) {
+        let Some(record) = TaskEventRecord::from_bus_event(event) else {
+            return;
+        };
+        if let Err(e) = self.send(&record).await {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            error!("Failed to publish task event to Kafka: {}", e);
+        }
+    }
+
+    async fn on_lagged(&self, skipped: u64) {
+        self.dropped.fetch_add(skipped, Ordering::Relaxed);
+        error!("Kafka sink fell behind the event bus, {} events were not published", skipped);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::{Message, Role};
+    use crate::a2a::server::events::Event;
+    use crate::{Task, TaskState, TaskStatus, TaskStatusUpdateEvent};
+
+    fn bus_event(event: Event) -> BusEvent {
+        BusEvent {
+            sequence: 7,
+            event,
+            task: Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id("t1".to_string()),
+            origin: None,
+        }
+    }
+
+    #[test]
+    fn test_record_for_status_update() {
+        let event = bus_event(Event::TaskStatusUpdate(TaskStatusUpdateEvent::new(
+            "t1".to_string(),
+            "ctx".to_string(),
+            TaskStatus::new(TaskState::Working),
+            false,
+        )));
+        let record = TaskEventRecord::from_bus_event(&event).unwrap();
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["sequence"], 7);
+        assert_eq!(json["eventType"], "status-update");
+        assert_eq!(json["taskId"], "t1");
+        assert_eq!(json["contextId"], "ctx");
+        assert!(json.get("origin").is_none());
+    }
+
+    #[test]
+    fn test_messages_are_not_recorded() {
+        let event = bus_event(Event::Message(Message::new(Role::Agent, vec![])));
+        assert!(TaskEventRecord::from_bus_event(&event).is_none());
+    }
+
+    #[test]
+    fn test_cloudevents_record_format() {
+        let sink = KafkaEventSink::new(KafkaSinkConfig::default().with_cloudevents("https://agent.example.com")).unwrap();
+        let event = bus_event(Event::Task(
+            Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id("t1".to_string()),
+        ));
+        let record = TaskEventRecord::from_bus_event(&event).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&sink.encode(&record).unwrap()).unwrap();
+        assert_eq!(json["type"], "a2a.task");
+        assert_eq!(json["subject"], "t1");
+        assert_eq!(json["source"], "https://agent.example.com");
+        assert_eq!(json["a2asequence"], 7);
+    }
+
+    #[test]
+    fn test_retry_backoff_doubles_up_to_the_limit() {
+        let config = KafkaSinkConfig::default()
+            .with_retry_backoff(Duration::from_millis(100))
+            .with_max_retry_backoff(Duration::from_secs(1));
+        assert_eq!(config.backoff(1), Duration::from_millis(100));
+        assert_eq!(config.backoff(2), Duration::from_millis(200));
+        assert_eq!(config.backoff(4), Duration::from_millis(800));
+        assert_eq!(config.backoff(5), Duration::from_secs(1));
+        assert_eq!(config.backoff(40), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_undeliverable_records_are_dropped_and_counted() {
+        let sink = KafkaEventSink::new(
+            KafkaSinkConfig::new("127.0.0.1:1", "agents")
+                .with_delivery_timeout(Duration::from_millis(100))
+                .with_retry_backoff(Duration::from_millis(1))
+                .with_max_delivery_attempts(2),
+        )
+        .unwrap();
+        let event = bus_event(Event::Task(
+            Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id("t1".to_string()),
+        ));
+        sink.on_event(&event).await;
+        assert_eq!(sink.dropped_events(), 1);
+
+        sink.on_lagged(3).await;
+        assert_eq!(sink.dropped_events(), 4);
+    }
+
+    #[test]
+    fn test_client_config_overrides() {
+        let config = KafkaSinkConfig::new("broker:9092", "agents")
+            .with_property("security.protocol", "SASL_SSL")
+            .client_config();
+        assert_eq!(config.get("bootstrap.servers"), Some("broker:9092"));
+        assert_eq!(config.get("acks"), Some("all"));
+        assert_eq!(config.get("security.protocol"), Some("SASL_SSL"));
+    }
+}
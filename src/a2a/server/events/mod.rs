@@ -3,14 +3,41 @@
 //! This module provides the event queue system that handles asynchronous
 //! communication between the agent executor and request handlers.
 
+pub mod distributed_bus;
+pub mod event_bus;
+pub mod event_store;
 pub mod event_queue;
 pub mod event_consumer;
 pub mod queue_manager;
+pub mod sse_relay;
+pub mod task_outcomes;
+pub mod timeline;
 pub mod in_memory_queue_manager;
 pub mod in_memory_queue;
+#[cfg(feature = "kafka")]
+pub mod kafka_sink;
+#[cfg(feature = "nats")]
+pub mod nats_bus;
 
+pub use event_bus::{
+    AuditLogSubscriber, BusEvent, EventBus, EventBusSubscriber, EventMetrics, MetricsSubscriber,
+    PushNotificationSubscriber, TaskEventRecord,
+};
+pub use distributed_bus::{
+    DistributedEventBridge, DistributedEventBusConfig, EventBusTransport, InMemoryEventBusTransport,
+};
+pub use event_store::{
+    event_id, parse_event_id, EventStore, EventStoreSubscriber, InMemoryEventStore,
+};
 pub use event_queue::{Event, EventQueue, QueueConfig, QueueError};
 pub use event_consumer::EventConsumer;
+pub use sse_relay::{sse_frame, SseRelay, SseRelayConfig};
+pub use task_outcomes::{SkillMetrics, SloMetrics, SloObjective, DURATION_BUCKETS_MS, UNSPECIFIED_SKILL};
+pub use timeline::{TaskTimeline, TimelineEntry, TimelinePhase};
 pub use queue_manager::{QueueManager, QueueManagerConfig, QueueManagerError, validate_queue_id};
 pub use in_memory_queue_manager::InMemoryQueueManager;
 pub use in_memory_queue::{InMemoryEventQueue, InMemoryEventQueueChild};
+#[cfg(feature = "nats")]
+pub use nats_bus::{NatsConfig, NatsEventBusTransport};
+#[cfg(feature = "kafka")]
+pub use kafka_sink::{KafkaEventSink, KafkaRecordFormat, KafkaSinkConfig};
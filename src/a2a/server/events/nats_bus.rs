@@ -0,0 +1,164 @@
+//! NATS transport for the distributed event bus
+//!
+//! Available with the `nats` feature. Events are published with core NATS
+//! (at-most-once); replicas that are disconnected miss the events published in
+//! the meantime and should fall back to reading the task store.
+
+use crate::a2a::error::A2AError;
+use crate::a2a::server::events::distributed_bus::EventBusTransport;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Default NATS server URL
+pub const DEFAULT_NATS_URL: &str = "nats://127.0.0.1:4222";
+
+/// Connection configuration for `NatsEventBusTransport`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NatsConfig {
+    /// Comma separated server URLs
+    pub url: String,
+    /// Connection name reported to the server
+    pub name: Option<String>,
+    /// Authentication token
+    pub token: Option<String>,
+    /// User for user and password authentication
+    pub user: Option<String>,
+    /// Password for user and password authentication
+    pub password: Option<String>,
+    /// Path to a `.creds` file for JWT authentication
+    pub credentials_file: Option<PathBuf>,
+    /// Timeout of the initial connection
+    pub connection_timeout: Duration,
+    /// Require TLS for the connection
+    pub require_tls: bool,
+}
+
+impl NatsConfig {
+    /// Creates a configuration for the given server URL
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the connection name
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Authenticates with a token
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    /// Authenticates with a user and password
+    pub fn with_user_and_password(mut self, user: &str, password: &str) -> Self {
+        self.user = Some(user.to_string());
+        self.password = Some(password.to_string());
+        self
+    }
+
+    /// Authenticates with a credentials file
+    pub fn with_credentials_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.credentials_file = Some(path.into());
+        self
+    }
+
+    /// Sets the connection timeout
+    pub fn with_connection_timeout(mut self, timeout: Duration) -> Self {
+        self.connection_timeout = timeout;
+        self
+    }
+
+    /// Requires TLS
+    pub fn with_require_tls(mut self, require_tls: bool) -> Self {
+        self.require_tls = require_tls;
+        self
+    }
+
+    /// Connects to the NATS server
+    pub async fn connect(&self) -> Result<NatsEventBusTransport, A2AError> {
+        let mut options = match &self.credentials_file {
+            Some(path) => async_nats::ConnectOptions::with_credentials_file(path.clone())
+                .await
+                .map_err(|e| A2AError::transport_error(format!("Invalid NATS credentials: {}", e)))?,
+            None => async_nats::ConnectOptions::new(),
+        };
+        if let Some(name) = &self.name {
+            options = options.name(name);
+        }
+        if let Some(token) = &self.token {
+            options = options.token(token.clone());
+        }
+        if let (Some(user), Some(password)) = (&self.user, &self.password) {
+            options = options.user_and_password(user.clone(), password.clone());
+        }
+        options = options
+            .connection_timeout(self.connection_timeout)
+            .require_tls(self.require_tls);
+
+        let client = options
+            .connect(self.url.as_str())
+            .await
+            .map_err(|e| A2AError::transport_error(format!("Failed to connect to NATS: {}", e)))?;
+        Ok(NatsEventBusTransport::new(client))
+    }
+}
+
+impl Default for NatsConfig {
+    fn default() -> Self {
+        Self {
+            url: DEFAULT_NATS_URL.to_string(),
+            name: None,
+            token: None,
+            user: None,
+            password: None,
+            credentials_file: None,
+            connection_timeout: Duration::from_secs(5),
+            require_tls: false,
+        }
+    }
+}
+
+/// Event bus transport backed by a NATS connection
+#[derive(Clone)]
+pub struct NatsEventBusTransport {
+    client: async_nats::Client,
+}
+
+impl NatsEventBusTransport {
+    /// Wraps an existing NATS client
+    pub fn new(client: async_nats::Client) -> Self {
+        Self { client }
+    }
+
+    /// Returns the underlying client
+    pub fn client(&self) -> &async_nats::Client {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl EventBusTransport for NatsEventBusTransport {
+    async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<(), A2AError> {
+        self.client
+            .publish(subject.to_string(), payload.into())
+            .await
+            .map_err(|e| A2AError::transport_error(format!("Failed to publish to NATS: {}", e)))
+    }
+
+    async fn subscribe(&self, subject: &str) -> Result<BoxStream<'static, Vec<u8>>, A2AError> {
+        let subscriber = self
+            .client
+            .subscribe(subject.to_string())
+            .await
+            .map_err(|e| A2AError::transport_error(format!("Failed to subscribe to NATS: {}", e)))?;
+        Ok(Box::pin(subscriber.map(|message| message.payload.to_vec())))
+    }
+}
@@ -0,0 +1,350 @@
+//! Outbound SSE relay of task events
+//!
+//! Push notifications open one webhook request per event and task. Consumers
+//! that would rather receive a single firehose configure an `SseRelay`
+//! instead: an `EventBusSubscriber` that keeps one long-lived outbound POST
+//! open to the consumer endpoint and writes every task event to its body as
+//! a server-sent event. The `id` of each event is the bus sequence number,
+//! its `event` the record type and its `data` a `TaskEventRecord` as JSON:
+//!
+//! ```text
+//! id: 42
+//! event: status-update
+//! data: {"sequence":42,"eventType":"status-update","taskId":"...",...}
+//! ```
+//!
+//! A relay with a tenant only forwards the tasks whose `tenant` metadata
+//! names it, which `DefaultRequestHandler::with_tenant_metadata` records.
+//! Comment lines keep idle connections alive. When the connection drops the
+//! relay reconnects with exponential backoff and continues with the events
+//! buffered meanwhile; an event written to a connection as it failed may be
+//! lost, so consumers should watch the ids for gaps and fetch the affected
+//! tasks with `tasks/get`.
+//!
+//! ```ignore
+//! let relay = SseRelay::spawn(SseRelayConfig::new("https://billing.internal/a2a-events").with_tenant("team-a"));
+//! handler.event_bus().spawn_subscriber(Arc::new(relay));
+//! ```
+
+use crate::a2a::server::events::event_bus::{BusEvent, EventBusSubscriber, TaskEventRecord};
+use crate::a2a::server::quota::TENANT_METADATA_KEY;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, warn};
+
+/// Default number of events buffered while the consumer is unreachable
+pub const DEFAULT_RELAY_BUFFER: usize = 1024;
+
+/// Content type of the relayed request body
+pub const EVENT_STREAM_CONTENT_TYPE: &str = "text/event-stream";
+
+/// Frame written to idle connections
+const KEEPALIVE_FRAME: &str = ": keepalive\n\n";
+
+/// Configuration of an `SseRelay`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseRelayConfig {
+    /// Consumer endpoint the events are posted to
+    pub endpoint: String,
+    /// Only relay the tasks of this tenant; `None` relays every task
+    pub tenant: Option<String>,
+    /// Bearer token sent to the consumer
+    pub token: Option<String>,
+    /// Additional request headers
+    pub headers: HashMap<String, String>,
+    /// Idle time after which a keepalive comment is written
+    pub keepalive_interval: Duration,
+    /// Delay before the first reconnection attempt
+    pub reconnect_backoff: Duration,
+    /// Longest delay between reconnection attempts
+    pub max_reconnect_backoff: Duration,
+    /// Number of events buffered while the consumer is unreachable
+    pub buffer_capacity: usize,
+}
+
+impl SseRelayConfig {
+    /// Creates a configuration relaying every task to `endpoint`
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            tenant: None,
+            token: None,
+            headers: HashMap::new(),
+            keepalive_interval: Duration::from_secs(15),
+            reconnect_backoff: Duration::from_secs(1),
+            max_reconnect_backoff: Duration::from_secs(30),
+            buffer_capacity: DEFAULT_RELAY_BUFFER,
+        }
+    }
+
+    /// Only relays the tasks of `tenant`
+    pub fn with_tenant(mut self, tenant: &str) -> Self {
+        self.tenant = Some(tenant.to_string());
+        self
+    }
+
+    /// Authenticates to the consumer with a bearer token
+    pub fn with_bearer_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    /// Sets an additional request header
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Sets the idle time after which a keepalive comment is written
+    pub fn with_keepalive_interval(mut self, interval: Duration) -> Self {
+        self.keepalive_interval = interval;
+        self
+    }
+
+    /// Sets the first and the longest delay between reconnection attempts
+    pub fn with_reconnect_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.reconnect_backoff = initial;
+        self.max_reconnect_backoff = max;
+        self
+    }
+
+    /// Sets the number of events buffered while the consumer is unreachable
+    pub fn with_buffer_capacity(mut self, capacity: usize) -> Self {
+        self.buffer_capacity = capacity.max(1);
+        self
+    }
+
+    /// Whether the relay forwards the event
+    fn relays(&self, event: &BusEvent) -> bool {
+        let Some(tenant) = &self.tenant else {
+            return true;
+        };
+        event
+            .task
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get(TENANT_METADATA_KEY))
+            .and_then(serde_json::Value::as_str)
+            .is_some_and(|task_tenant| task_tenant == tenant)
+    }
+}
+
+/// Encodes a record as a server-sent event
+pub fn sse_frame(record: &TaskEventRecord<'_>) -> Result<String, serde_json::Error> {
+    Ok(format!(
+        "id: {}\nevent: {}\ndata: {}\n\n",
+        record.sequence,
+        record.event_type,
+        serde_json::to_string(record)?
+    ))
+}
+
+/// Streams task events to a consumer over one outbound SSE connection
+pub struct SseRelay {
+    config: SseRelayConfig,
+    frames: mpsc::Sender<String>,
+    connection: JoinHandle<()>,
+}
+
+impl SseRelay {
+    /// Spawns the connection to the consumer with a default HTTP client
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn spawn(config: SseRelayConfig) -> Self {
+        Self::spawn_with_client(reqwest::Client::new(), config)
+    }
+
+    /// Spawns the connection to the consumer with a custom HTTP client
+    pub fn spawn_with_client(client: reqwest::Client, config: SseRelayConfig) -> Self {
+        let (frames, receiver) = mpsc::channel(config.buffer_capacity);
+        let connection = tokio::spawn(relay_frames(client, config.clone(), receiver));
+        Self { config, frames, connection }
+    }
+
+    /// Returns the relay configuration
+    pub fn config(&self) -> &SseRelayConfig {
+        &self.config
+    }
+}
+
+impl Drop for SseRelay {
+    fn drop(&mut self) {
+        self.connection.abort();
+    }
+}
+
+#[async_trait]
+impl EventBusSubscriber for SseRelay {
+    fn name(&self) -> &str {
+        "sse-relay"
+    }
+
+    async fn on_event(&self, event: &BusEvent) {
+        if !self.config.relays(event) {
+            return;
+        }
+        let Some(record) = TaskEventRecord::from_bus_event(event) else {
+            return;
+        };
+        match sse_frame(&record) {
+            // Waits while the buffer is full, so the bus skips events for this
+            // subscriber rather than the relay growing without bound
+            Ok(frame) => {
+                if self.frames.send(frame).await.is_err() {
+                    warn!("SSE relay to {} has stopped", self.config.endpoint);
+                }
+            }
+            Err(e) => tracing::error!("Failed to encode task event for the SSE relay: {}", e),
+        }
+    }
+}
+
+/// Writes frames to the consumer, reconnecting whenever the connection ends
+async fn relay_frames(client: reqwest::Client, config: SseRelayConfig, mut frames: mpsc::Receiver<String>) {
+    let mut pending: Option<String> = None;
+    let mut backoff = config.reconnect_backoff;
+    loop {
+        let (body, body_frames) = mpsc::channel::<Result<String, std::io::Error>>(1);
+        let mut request = client
+            .post(&config.endpoint)
+            .header(reqwest::header::CONTENT_TYPE, EVENT_STREAM_CONTENT_TYPE)
+            .body(reqwest::Body::wrap_stream(ReceiverStream::new(body_frames)));
+        if let Some(token) = &config.token {
+            request = request.bearer_auth(token);
+        }
+        for (name, value) in &config.headers {
+            request = request.header(name, value);
+        }
+        let mut response = tokio::spawn(request.send());
+        debug!("SSE relay connecting to {}", config.endpoint);
+
+        let mut delivered = false;
+        let ended = loop {
+            let frame = match pending.take() {
+                Some(frame) => frame,
+                None => tokio::select! {
+                    frame = frames.recv() => match frame {
+                        Some(frame) => frame,
+                        None => {
+                            response.abort();
+                            return;
+                        }
+                    },
+                    _ = tokio::time::sleep(config.keepalive_interval) => KEEPALIVE_FRAME.to_string(),
+                    ended = &mut response => break ended,
+                },
+            };
+            let keepalive = frame == KEEPALIVE_FRAME;
+            tokio::select! {
+                sent = body.send(Ok(frame.clone())) => {
+                    if sent.is_err() {
+                        if !keepalive {
+                            pending = Some(frame);
+                        }
+                        break (&mut response).await;
+                    }
+                    delivered |= !keepalive;
+                }
+                ended = &mut response => {
+                    if !keepalive {
+                        pending = Some(frame);
+                    }
+                    break ended;
+                }
+            }
+        };
+
+        match ended {
+            Ok(Ok(response)) => warn!("SSE relay consumer {} closed the connection with {}", config.endpoint, response.status()),
+            Ok(Err(e)) => warn!("SSE relay connection to {} failed: {}", config.endpoint, e),
+            Err(e) => warn!("SSE relay connection to {} was interrupted: {}", config.endpoint, e),
+        }
+        if delivered {
+            backoff = config.reconnect_backoff;
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(config.max_reconnect_backoff);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::server::events::{Event, EventBus};
+    use crate::{Task, TaskState, TaskStatus, TaskStatusUpdateEvent};
+    use axum::body::Body;
+    use axum::extract::State;
+    use axum::http::HeaderMap;
+    use futures::StreamExt;
+
+    fn task(id: &str, tenant: &str) -> Task {
+        let metadata = HashMap::from([(TENANT_METADATA_KEY.to_string(), serde_json::json!(tenant))]);
+        Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working))
+            .with_task_id(id.to_string())
+            .with_metadata(metadata)
+    }
+
+    fn status(task: &Task) -> Event {
+        Event::TaskStatusUpdate(TaskStatusUpdateEvent::new(
+            task.id.clone(),
+            task.context_id.clone(),
+            TaskStatus::new(TaskState::Working),
+            false,
+        ))
+    }
+
+    #[test]
+    fn test_frames_carry_the_sequence_and_type() {
+        let task = task("t1", "team-a");
+        let event = BusEvent { sequence: 3, event: status(&task), task, origin: None };
+        let frame = sse_frame(&TaskEventRecord::from_bus_event(&event).unwrap()).unwrap();
+        let mut lines = frame.lines();
+        assert_eq!(lines.next(), Some("id: 3"));
+        assert_eq!(lines.next(), Some("event: status-update"));
+        let data: serde_json::Value = serde_json::from_str(lines.next().unwrap().strip_prefix("data: ").unwrap()).unwrap();
+        assert_eq!(data["taskId"], "t1");
+        assert!(frame.ends_with("\n\n"));
+    }
+
+    #[tokio::test]
+    async fn test_relay_streams_the_tasks_of_its_tenant() {
+        let (received, mut chunks) = mpsc::unbounded_channel::<String>();
+        let consumer = axum::Router::new()
+            .route(
+                "/events",
+                axum::routing::post(
+                    |State(received): State<mpsc::UnboundedSender<String>>, headers: HeaderMap, body: Body| async move {
+                        assert_eq!(headers["authorization"], "Bearer s3cret");
+                        let mut body = body.into_data_stream();
+                        while let Some(Ok(chunk)) = body.next().await {
+                            let _ = received.send(String::from_utf8_lossy(&chunk).into_owned());
+                        }
+                    },
+                ),
+            )
+            .with_state(received);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/events", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, consumer).await });
+
+        let config = SseRelayConfig::new(&endpoint).with_tenant("team-a").with_bearer_token("s3cret");
+        let bus = EventBus::new();
+        bus.spawn_subscriber(std::sync::Arc::new(SseRelay::spawn(config)));
+        for task in [task("t1", "team-a"), task("t2", "team-b"), task("t3", "team-a")] {
+            bus.publish(status(&task), task);
+        }
+
+        let mut stream = String::new();
+        while stream.matches("\n\n").count() < 2 {
+            let chunk = tokio::time::timeout(Duration::from_secs(5), chunks.recv()).await.unwrap().unwrap();
+            stream.push_str(&chunk);
+        }
+        assert!(stream.contains(r#""taskId":"t1""#));
+        assert!(stream.contains(r#""taskId":"t3""#));
+        assert!(!stream.contains(r#""taskId":"t2""#));
+    }
+}
@@ -0,0 +1,270 @@
+//! Task outcome and execution duration telemetry
+//!
+//! `MetricsSubscriber` counts how task executions end and how long they
+//! take, per skill. An execution starts with the first event of a task and
+//! ends with its final event; a task waiting for input ends one execution
+//! and starts another when it resumes. Its skill is the `skill_id` metadata
+//! of the latest message in the task history.
+//!
+//! With an `SloObjective`, the subscriber also computes the burn rate of the
+//! objective over a rolling window: the share of bad executions divided by
+//! the share the objective allows. A burn rate of 1 uses up the error budget
+//! exactly over the window; dashboards usually alert well above it.
+
+use crate::a2a::utils::constants::SKILL_ID_METADATA_KEY;
+use crate::{Task, TaskState};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Upper bounds in milliseconds of the execution duration histogram buckets
+///
+/// Executions slower than the last bound are counted in an extra bucket.
+pub const DURATION_BUCKETS_MS: [u64; 10] = [100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000, 60_000, 300_000];
+
+/// Skill of executions whose messages name none
+pub const UNSPECIFIED_SKILL: &str = "unspecified";
+
+/// Most executions tracked at once; further tasks are not timed until some end
+const MAX_OPEN_EXECUTIONS: usize = 10_000;
+
+/// Outcome counters and durations of the executions of one skill
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct SkillMetrics {
+    /// Number of executions per outcome: `completed`, `failed`, `canceled`,
+    /// `rejected`, `input_required` or `auth_required`
+    pub outcomes: HashMap<String, u64>,
+    /// Number of executions per bucket of `DURATION_BUCKETS_MS`, plus one for slower ones
+    pub duration_buckets: Vec<u64>,
+    /// Sum of the durations of all executions, in milliseconds
+    pub total_duration_ms: u64,
+}
+
+impl SkillMetrics {
+    fn record(&mut self, outcome: &str, elapsed: Duration) {
+        if self.duration_buckets.is_empty() {
+            self.duration_buckets = vec![0; DURATION_BUCKETS_MS.len() + 1];
+        }
+        let millis = elapsed.as_millis();
+        let bucket = DURATION_BUCKETS_MS
+            .iter()
+            .position(|bound| millis <= u128::from(*bound))
+            .unwrap_or(DURATION_BUCKETS_MS.len());
+        self.duration_buckets[bucket] += 1;
+        self.total_duration_ms += millis as u64;
+        *self.outcomes.entry(outcome.to_string()).or_insert(0) += 1;
+    }
+}
+
+/// A service level objective for task executions
+///
+/// An execution is bad when it fails or is rejected, or when it takes longer
+/// than the latency threshold, if one is set. Canceled executions and
+/// executions waiting for input are good.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SloObjective {
+    target: f64,
+    window: Duration,
+    latency_threshold: Option<Duration>,
+}
+
+impl SloObjective {
+    /// An objective of `target` good executions, e.g. 0.99, over a rolling
+    /// `window`
+    pub fn new(target: f64, window: Duration) -> Self {
+        Self {
+            target: target.clamp(0.0, 1.0),
+            window,
+            latency_threshold: None,
+        }
+    }
+
+    /// Counts executions slower than `threshold` as bad
+    pub fn with_latency_threshold(mut self, threshold: Duration) -> Self {
+        self.latency_threshold = Some(threshold);
+        self
+    }
+}
+
+/// Gauges of an `SloObjective` over its window
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct SloMetrics {
+    /// The objective, as a share of good executions
+    pub target: f64,
+    /// Length of the rolling window, in seconds
+    pub window_secs: u64,
+    /// Executions that ended within the window
+    pub executions: u64,
+    /// Bad executions that ended within the window
+    pub bad_executions: u64,
+    /// Share of bad executions within the window
+    pub error_rate: f64,
+    /// `error_rate` divided by the error rate the objective allows
+    pub burn_rate: f64,
+}
+
+/// The execution outcomes collected by `MetricsSubscriber`
+#[derive(Debug, Default)]
+pub(crate) struct OutcomeTracker {
+    /// Start and skill of the running execution of each task
+    open: HashMap<String, (Instant, String)>,
+    outcomes: HashMap<String, u64>,
+    skills: BTreeMap<String, SkillMetrics>,
+    slo: Option<SloObjective>,
+    /// End and badness of the executions within the SLO window, oldest first
+    window: VecDeque<(Instant, bool)>,
+}
+
+impl OutcomeTracker {
+    pub(crate) fn with_slo(mut self, slo: SloObjective) -> Self {
+        self.slo = Some(slo);
+        self
+    }
+
+    /// Records the task state after an event observed at `now`
+    pub(crate) fn record(&mut self, task: &Task, is_final: bool, now: Instant) {
+        let Some(outcome) = outcome(&task.status.state).filter(|_| is_final) else {
+            if !self.open.contains_key(&task.id) && self.open.len() < MAX_OPEN_EXECUTIONS {
+                self.open.insert(task.id.clone(), (now, skill(task)));
+            }
+            return;
+        };
+        *self.outcomes.entry(outcome.to_string()).or_insert(0) += 1;
+        let (started, skill) = self.open.remove(&task.id).unwrap_or_else(|| (now, skill(task)));
+        let elapsed = now.saturating_duration_since(started);
+        self.skills.entry(skill).or_default().record(outcome, elapsed);
+
+        if let Some(slo) = &self.slo {
+            let slow = slo.latency_threshold.is_some_and(|threshold| elapsed > threshold);
+            let bad = matches!(task.status.state, TaskState::Failed | TaskState::Rejected) || slow;
+            self.window.push_back((now, bad));
+        }
+    }
+
+    pub(crate) fn outcomes(&self) -> HashMap<String, u64> {
+        self.outcomes.clone()
+    }
+
+    pub(crate) fn skills(&self) -> BTreeMap<String, SkillMetrics> {
+        self.skills.clone()
+    }
+
+    /// The SLO gauges at `now`, dropping executions that left the window
+    pub(crate) fn slo(&mut self, now: Instant) -> Option<SloMetrics> {
+        let slo = self.slo.as_ref()?;
+        while self
+            .window
+            .front()
+            .is_some_and(|(ended, _)| now.saturating_duration_since(*ended) > slo.window)
+        {
+            self.window.pop_front();
+        }
+        let executions = self.window.len() as u64;
+        let bad_executions = self.window.iter().filter(|(_, bad)| *bad).count() as u64;
+        let error_rate = if executions == 0 { 0.0 } else { bad_executions as f64 / executions as f64 };
+        let budget = 1.0 - slo.target;
+        let burn_rate = if bad_executions == 0 {
+            0.0
+        } else if budget <= 0.0 {
+            f64::INFINITY
+        } else {
+            error_rate / budget
+        };
+        Some(SloMetrics {
+            target: slo.target,
+            window_secs: slo.window.as_secs(),
+            executions,
+            bad_executions,
+            error_rate,
+            burn_rate,
+        })
+    }
+}
+
+/// The outcome an execution ends with in `state`, if it ends
+fn outcome(state: &TaskState) -> Option<&'static str> {
+    match state {
+        TaskState::Completed => Some("completed"),
+        TaskState::Failed => Some("failed"),
+        TaskState::Canceled => Some("canceled"),
+        TaskState::Rejected => Some("rejected"),
+        TaskState::InputRequired => Some("input_required"),
+        TaskState::AuthRequired => Some("auth_required"),
+        TaskState::Submitted | TaskState::Working | TaskState::Unknown => None,
+    }
+}
+
+/// The skill named by the latest message of the task history
+fn skill(task: &Task) -> String {
+    task.history
+        .iter()
+        .flatten()
+        .rev()
+        .find_map(|message| message.metadata.as_ref()?.get(SKILL_ID_METADATA_KEY)?.as_str())
+        .unwrap_or(UNSPECIFIED_SKILL)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Message, Part, Role, TaskStatus};
+
+    fn task(id: &str, state: TaskState, skill: Option<&str>) -> Task {
+        let mut message = Message::new(Role::User, vec![Part::text("hi".to_string())]);
+        if let Some(skill) = skill {
+            message.metadata = Some(HashMap::from([(SKILL_ID_METADATA_KEY.to_string(), serde_json::json!(skill))]));
+        }
+        Task::new("ctx".to_string(), TaskStatus::new(state))
+            .with_task_id(id.to_string())
+            .with_history(vec![message])
+    }
+
+    #[test]
+    fn test_outcomes_and_durations_per_skill() {
+        let mut tracker = OutcomeTracker::default();
+        let start = Instant::now();
+        tracker.record(&task("t1", TaskState::Working, Some("search")), false, start);
+        tracker.record(&task("t1", TaskState::Completed, Some("search")), true, start + Duration::from_millis(300));
+        tracker.record(&task("t2", TaskState::Working, None), false, start);
+        tracker.record(&task("t2", TaskState::InputRequired, None), true, start + Duration::from_secs(2));
+        tracker.record(&task("t2", TaskState::Working, None), false, start + Duration::from_secs(10));
+        tracker.record(&task("t2", TaskState::Failed, None), true, start + Duration::from_secs(11));
+
+        let outcomes = tracker.outcomes();
+        assert_eq!(outcomes["completed"], 1);
+        assert_eq!(outcomes["input_required"], 1);
+        assert_eq!(outcomes["failed"], 1);
+
+        let skills = tracker.skills();
+        let search = &skills["search"];
+        assert_eq!(search.outcomes["completed"], 1);
+        assert_eq!(search.duration_buckets[2], 1); // 250ms < 300ms <= 500ms
+        let unspecified = &skills[UNSPECIFIED_SKILL];
+        assert_eq!(unspecified.duration_buckets.iter().sum::<u64>(), 2);
+        assert_eq!(unspecified.total_duration_ms, 3_000);
+        assert!(tracker.slo(start).is_none());
+    }
+
+    #[test]
+    fn test_slo_burn_rate_over_the_window() {
+        let slo = SloObjective::new(0.9, Duration::from_secs(60)).with_latency_threshold(Duration::from_secs(5));
+        let mut tracker = OutcomeTracker::default().with_slo(slo);
+        let start = Instant::now();
+        for i in 0..8 {
+            tracker.record(&task(&format!("ok{}", i), TaskState::Completed, None), true, start);
+        }
+        tracker.record(&task("failed", TaskState::Failed, None), true, start);
+        tracker.record(&task("slow", TaskState::Working, None), false, start);
+        tracker.record(&task("slow", TaskState::Completed, None), true, start + Duration::from_secs(30));
+
+        let gauges = tracker.slo(start + Duration::from_secs(30)).unwrap();
+        assert_eq!(gauges.executions, 10);
+        assert_eq!(gauges.bad_executions, 2);
+        assert!((gauges.burn_rate - 2.0).abs() < 1e-9);
+
+        // Only the slow execution is still within the window
+        let gauges = tracker.slo(start + Duration::from_secs(61)).unwrap();
+        assert_eq!((gauges.executions, gauges.bad_executions), (1, 1));
+        assert_eq!(tracker.slo(start + Duration::from_secs(200)).unwrap().burn_rate, 0.0);
+    }
+}
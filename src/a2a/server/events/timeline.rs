@@ -0,0 +1,201 @@
+//! Per-task timelines of execution phases
+//!
+//! `TaskTimeline` records when a task was received, queued for a worker,
+//! started and completed, and when each of its events was published, with
+//! timings from a monotonic clock relative to the first phase. The request
+//! handler records the phases it drives; subscribed to the event bus, the
+//! timeline records the events. Every phase is also emitted as a debug event
+//! on the `a2a::timeline` tracing target, so the same breakdown is available
+//! in logs. Timelines of the least recently started tasks are evicted once
+//! the capacity is reached.
+
+use crate::a2a::server::events::event_bus::{event_type, BusEvent, EventBusSubscriber};
+use crate::a2a::server::events::Event;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Default number of tasks whose timelines are kept
+pub const DEFAULT_TIMELINE_CAPACITY: usize = 1000;
+
+/// Metadata key under which `on_get_task` includes the timeline, if enabled
+pub const TIMELINE_METADATA_KEY: &str = "timeline";
+
+/// A phase in the life of a task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "phase", rename_all = "snake_case")]
+pub enum TimelinePhase {
+    /// The message creating or continuing the task arrived
+    Received,
+    /// The task waits for a free worker
+    Queued,
+    /// Execution began
+    Started,
+    /// An event was published for the task
+    Event { event_type: String, state: String },
+    /// The final event was published
+    Completed { state: String },
+}
+
+/// A phase with its timing
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimelineEntry {
+    #[serde(flatten)]
+    pub phase: TimelinePhase,
+    /// Milliseconds since the first phase of the task, on a monotonic clock
+    pub elapsed_ms: f64,
+    /// Wall clock time of the phase
+    pub recorded_at: DateTime<Utc>,
+}
+
+struct TaskRecord {
+    started: Instant,
+    entries: Vec<TimelineEntry>,
+}
+
+#[derive(Default)]
+struct TimelineState {
+    tasks: HashMap<String, TaskRecord>,
+    order: VecDeque<String>,
+}
+
+/// Recorder of task timelines, shared between the handler and the bus
+#[derive(Clone)]
+pub struct TaskTimeline {
+    state: Arc<Mutex<TimelineState>>,
+    capacity: usize,
+    in_metadata: bool,
+}
+
+impl TaskTimeline {
+    /// Creates a recorder keeping `DEFAULT_TIMELINE_CAPACITY` timelines
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_TIMELINE_CAPACITY)
+    }
+
+    /// Creates a recorder keeping the timelines of up to `capacity` tasks
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(TimelineState::default())),
+            capacity: capacity.max(1),
+            in_metadata: false,
+        }
+    }
+
+    /// Include the timeline in the metadata of tasks returned by `tasks/get`
+    pub fn with_metadata(mut self, in_metadata: bool) -> Self {
+        self.in_metadata = in_metadata;
+        self
+    }
+
+    /// Whether timelines are included in task metadata
+    pub fn in_metadata(&self) -> bool {
+        self.in_metadata
+    }
+
+    /// Records that `task_id` reached `phase`
+    pub fn record(&self, task_id: &str, phase: TimelinePhase) {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        if !state.tasks.contains_key(task_id) {
+            while state.order.len() >= self.capacity {
+                if let Some(evicted) = state.order.pop_front() {
+                    state.tasks.remove(&evicted);
+                }
+            }
+            state.order.push_back(task_id.to_string());
+            state.tasks.insert(
+                task_id.to_string(),
+                TaskRecord {
+                    started: now,
+                    entries: Vec::new(),
+                },
+            );
+        }
+        let record = state.tasks.get_mut(task_id).expect("record was just inserted");
+        let elapsed_ms = now.duration_since(record.started).as_secs_f64() * 1000.0;
+        tracing::debug!(target: "a2a::timeline", task_id, phase = ?phase, elapsed_ms, "Task phase");
+        record.entries.push(TimelineEntry {
+            phase,
+            elapsed_ms,
+            recorded_at: Utc::now(),
+        });
+    }
+
+    /// The timeline of `task_id`, if it is still kept
+    pub fn get(&self, task_id: &str) -> Option<Vec<TimelineEntry>> {
+        self.state.lock().unwrap().tasks.get(task_id).map(|record| record.entries.clone())
+    }
+}
+
+impl Default for TaskTimeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn state_name(event: &BusEvent) -> String {
+    let state = match &event.event {
+        Event::TaskStatusUpdate(update) => &update.status.state,
+        _ => &event.task.status.state,
+    };
+    serde_json::to_value(state)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl EventBusSubscriber for TaskTimeline {
+    fn name(&self) -> &str {
+        "timeline"
+    }
+
+    async fn on_event(&self, event: &BusEvent) {
+        let state = state_name(event);
+        self.record(
+            &event.task.id,
+            TimelinePhase::Event {
+                event_type: event_type(&event.event).to_string(),
+                state: state.clone(),
+            },
+        );
+        if event.is_final() {
+            self.record(&event.task.id, TimelinePhase::Completed { state });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phases_are_timed_from_the_first() {
+        let timeline = TaskTimeline::new();
+        timeline.record("t1", TimelinePhase::Received);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        timeline.record("t1", TimelinePhase::Started);
+
+        let entries = timeline.get("t1").unwrap();
+        assert_eq!(entries[0].phase, TimelinePhase::Received);
+        assert_eq!(entries[0].elapsed_ms, 0.0);
+        assert!(entries[1].elapsed_ms >= 5.0);
+        assert_eq!(serde_json::to_value(&entries[1]).unwrap()["phase"], "started");
+        assert!(timeline.get("t2").is_none());
+    }
+
+    #[test]
+    fn test_oldest_timelines_are_evicted() {
+        let timeline = TaskTimeline::with_capacity(2);
+        for task_id in ["a", "b", "c"] {
+            timeline.record(task_id, TimelinePhase::Received);
+        }
+        assert!(timeline.get("a").is_none());
+        assert!(timeline.get("b").is_some());
+        assert!(timeline.get("c").is_some());
+    }
+}
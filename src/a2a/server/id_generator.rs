@@ -9,7 +9,7 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 /// Context for providing additional information to ID generators
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct IDGeneratorContext {
     /// Optional task ID
     pub task_id: Option<String>,
@@ -17,15 +17,6 @@ pub struct IDGeneratorContext {
     pub context_id: Option<String>,
 }
 
-impl Default for IDGeneratorContext {
-    fn default() -> Self {
-        Self {
-            task_id: None,
-            context_id: None,
-        }
-    }
-}
-
 impl IDGeneratorContext {
     /// Creates a new IDGeneratorContext
     pub fn new() -> Self {
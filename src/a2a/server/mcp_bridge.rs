@@ -0,0 +1,351 @@
+//! Model Context Protocol bridge
+//!
+//! Available with the `mcp-bridge` feature. `McpBridge` lets MCP-only hosts call
+//! an agent served by this crate: every skill of the agent card is advertised
+//! as an MCP tool, and a `tools/call` is translated into a `message/send` on the
+//! `RequestHandler`. The tool takes a `message` text argument and an optional
+//! `context_id` to continue a conversation; the reply is the text of the
+//! returned message, or of the task's status message and artifacts.
+//!
+//! Two transports are provided: newline-delimited JSON-RPC over stdio
+//! (`serve_stdio`), and the HTTP+SSE transport (`sse_router`), where clients
+//! open `GET /sse`, receive the message endpoint as the first event and POST
+//! their requests to it.
+
+use crate::a2a::core_types::{Message, Part, Role, TaskState};
+use crate::a2a::models::{AgentSkill, MessageSendParams, Task};
+use crate::a2a::server::agent_card_handle::AgentCardHandle;
+use crate::a2a::server::request_handlers::{MessageSendResult, RequestHandler};
+use crate::a2a::utils::{get_artifact_text, get_message_text};
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event as SseEvent, KeepAlive, Sse};
+use axum::response::IntoResponse;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures::stream::Stream;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+/// MCP protocol revision implemented by the bridge
+pub const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Message metadata key carrying the skill a tool call was made for
+pub const MCP_SKILL_METADATA_KEY: &str = crate::a2a::utils::constants::SKILL_ID_METADATA_KEY;
+
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+const PARSE_ERROR: i32 = -32700;
+
+/// Exposes the skills of an agent as MCP tools
+#[derive(Clone)]
+pub struct McpBridge {
+    cards: AgentCardHandle,
+    handler: Arc<dyn RequestHandler>,
+}
+
+impl McpBridge {
+    /// Creates a bridge advertising the skills of the cards held by `cards`
+    pub fn new(cards: AgentCardHandle, handler: Arc<dyn RequestHandler>) -> Self {
+        Self { cards, handler }
+    }
+
+    /// Handles one JSON-RPC message, returning the response if it was a request
+    pub async fn handle_message(&self, message: Value) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let method = message.get("method").and_then(Value::as_str).unwrap_or_default();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(self.initialize()),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": self.tools() })),
+            "tools/call" => self.call_tool(params).await,
+            _ if method.starts_with("notifications/") => return None,
+            _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        };
+        // Requests without an id are notifications and get no response
+        let id = id?;
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            }),
+        })
+    }
+
+    /// Serves the bridge over the process' stdin and stdout
+    pub async fn serve_stdio(&self) -> std::io::Result<()> {
+        self.serve_io(tokio::io::stdin(), tokio::io::stdout()).await
+    }
+
+    /// Serves newline-delimited JSON-RPC messages read from `reader`
+    pub async fn serve_io<R, W>(&self, reader: R, mut writer: W) -> std::io::Result<()>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut lines = BufReader::new(reader).lines();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = match serde_json::from_str::<Value>(&line) {
+                Ok(message) => self.handle_message(message).await,
+                Err(e) => Some(json!({
+                    "jsonrpc": "2.0",
+                    "id": null,
+                    "error": { "code": PARSE_ERROR, "message": format!("Invalid JSON: {}", e) },
+                })),
+            };
+            if let Some(response) = response {
+                writer.write_all(response.to_string().as_bytes()).await?;
+                writer.write_all(b"\n").await?;
+                writer.flush().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds a router serving the HTTP+SSE transport at `/sse` and `/messages`
+    pub fn sse_router(self) -> Router {
+        let state = Arc::new(SseState {
+            bridge: self,
+            sessions: Mutex::new(HashMap::new()),
+        });
+        Router::new()
+            .route("/sse", get(open_sse_session))
+            .route("/messages", post(post_sse_message))
+            .with_state(state)
+    }
+
+    fn initialize(&self) -> Value {
+        let card = self.cards.current();
+        json!({
+            "protocolVersion": MCP_PROTOCOL_VERSION,
+            "capabilities": { "tools": { "listChanged": false } },
+            "serverInfo": { "name": card.name, "version": card.version },
+            "instructions": card.description,
+        })
+    }
+
+    fn tools(&self) -> Vec<Value> {
+        self.cards.current().skills.iter().map(skill_to_tool).collect()
+    }
+
+    async fn call_tool(&self, params: Value) -> Result<Value, (i32, String)> {
+        let name = params.get("name").and_then(Value::as_str).unwrap_or_default();
+        let card = self.cards.current();
+        let skill = card
+            .skills
+            .iter()
+            .find(|skill| skill.id == name)
+            .ok_or_else(|| (INVALID_PARAMS, format!("Unknown tool: {}", name)))?;
+
+        let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
+        let text = arguments
+            .get("message")
+            .and_then(Value::as_str)
+            .ok_or_else(|| (INVALID_PARAMS, "Missing 'message' argument".to_string()))?;
+
+        let mut message = Message::new(Role::User, vec![Part::text(text.to_string())])
+            .with_metadata(HashMap::from([(MCP_SKILL_METADATA_KEY.to_string(), json!(skill.id))]));
+        if let Some(context_id) = arguments.get("context_id").and_then(Value::as_str) {
+            message = message.with_context_id(context_id.to_string());
+        }
+
+        debug!("MCP tool call for skill {}", skill.id);
+        let (text, is_error) = match self.handler.on_message_send(MessageSendParams::new(message), None).await {
+            Ok(MessageSendResult::Message(reply)) => (get_message_text(&reply, "\n"), false),
+            Ok(MessageSendResult::Task(task)) => {
+                let failed = matches!(task.status.state, TaskState::Failed | TaskState::Rejected);
+                (task_text(&task), failed)
+            }
+            Err(e) => (e.to_string(), true),
+        };
+        Ok(json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": is_error,
+        }))
+    }
+}
+
+/// Describes a skill as an MCP tool
+pub fn skill_to_tool(skill: &AgentSkill) -> Value {
+    let mut description = skill.description.clone();
+    if let Some(examples) = skill.examples.as_ref().filter(|examples| !examples.is_empty()) {
+        description.push_str("\n\nExamples:\n- ");
+        description.push_str(&examples.join("\n- "));
+    }
+    json!({
+        "name": skill.id,
+        "title": skill.name,
+        "description": description,
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "message": { "type": "string", "description": "The request for the agent" },
+                "context_id": { "type": "string", "description": "Continue an earlier conversation" },
+            },
+            "required": ["message"],
+        },
+    })
+}
+
+/// Collects the text of a task's status message and artifacts
+fn task_text(task: &Task) -> String {
+    let status = task.status.message.iter().map(|message| get_message_text(message, "\n"));
+    let artifacts = task
+        .artifacts
+        .iter()
+        .flatten()
+        .map(|artifact| get_artifact_text(artifact, "\n"));
+    status
+        .chain(artifacts)
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+struct SseState {
+    bridge: McpBridge,
+    sessions: Mutex<HashMap<String, mpsc::UnboundedSender<Value>>>,
+}
+
+#[derive(Deserialize)]
+struct SessionQuery {
+    session_id: String,
+}
+
+async fn open_sse_session(
+    State(state): State<Arc<SseState>>,
+) -> Sse<impl Stream<Item = Result<SseEvent, Infallible>>> {
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    state.sessions.lock().unwrap().insert(session_id.clone(), sender);
+
+    let stream = async_stream::stream! {
+        yield Ok(SseEvent::default()
+            .event("endpoint")
+            .data(format!("/messages?session_id={}", session_id)));
+        while let Some(message) = receiver.recv().await {
+            yield Ok(SseEvent::default().event("message").data(message.to_string()));
+        }
+        state.sessions.lock().unwrap().remove(&session_id);
+    };
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+async fn post_sse_message(
+    State(state): State<Arc<SseState>>,
+    Query(query): Query<SessionQuery>,
+    Json(message): Json<Value>,
+) -> impl IntoResponse {
+    let Some(sender) = state.sessions.lock().unwrap().get(&query.session_id).cloned() else {
+        return StatusCode::NOT_FOUND;
+    };
+    if let Some(response) = state.bridge.handle_message(message).await {
+        if sender.send(response).is_err() {
+            warn!("MCP SSE session {} closed before the response was sent", query.session_id);
+            return StatusCode::GONE;
+        }
+    }
+    StatusCode::ACCEPTED
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::models::{AgentCapabilities, AgentCard};
+    use crate::a2a::server::request_handlers::request_handler::MockRequestHandler;
+
+    fn bridge() -> McpBridge {
+        let card = AgentCard::new(
+            "Echo".to_string(),
+            "Echoes messages".to_string(),
+            "http://localhost:8080".to_string(),
+            "1.0.0".to_string(),
+            vec!["text/plain".to_string()],
+            vec!["text/plain".to_string()],
+            AgentCapabilities::new(),
+            vec![AgentSkill::new(
+                "echo".to_string(),
+                "Echo".to_string(),
+                "Repeats the message".to_string(),
+                vec![],
+            )],
+        );
+        McpBridge::new(AgentCardHandle::new(card), Arc::new(MockRequestHandler::new()))
+    }
+
+    #[tokio::test]
+    async fn test_lists_skills_as_tools() {
+        let response = bridge()
+            .handle_message(json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}))
+            .await
+            .unwrap();
+        let tools = response["result"]["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0]["name"], "echo");
+        assert_eq!(tools[0]["inputSchema"]["required"][0], "message");
+    }
+
+    #[tokio::test]
+    async fn test_tool_call_sends_message() {
+        let response = bridge()
+            .handle_message(json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": { "name": "echo", "arguments": { "message": "hello" } },
+            }))
+            .await
+            .unwrap();
+        assert_eq!(response["result"]["isError"], false);
+        assert_eq!(response["result"]["content"][0]["text"], "hello");
+
+        let unknown = bridge()
+            .handle_message(json!({
+                "jsonrpc": "2.0",
+                "id": 3,
+                "method": "tools/call",
+                "params": { "name": "missing", "arguments": { "message": "hello" } },
+            }))
+            .await
+            .unwrap();
+        assert_eq!(unknown["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_stdio_transport() {
+        let input = concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"ping"}"#,
+            "\n",
+        );
+        let mut output = Vec::new();
+        bridge().serve_io(input.as_bytes(), &mut output).await.unwrap();
+
+        let responses: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["result"]["serverInfo"]["name"], "Echo");
+        assert_eq!(responses[0]["result"]["protocolVersion"], MCP_PROTOCOL_VERSION);
+        assert_eq!(responses[1]["id"], 2);
+    }
+}
@@ -0,0 +1,555 @@
+//! Business rule validation of incoming messages
+//!
+//! `ValidatingRequestHandler` wraps a `RequestHandler` and passes every
+//! incoming message through a chain of `MessageValidator`s before it reaches
+//! the wrapped handler. Unlike a `ContentScanner`, which looks at file and data
+//! parts one at a time, a validator sees the whole message together with the
+//! call context, so it can enforce length limits, forbidden MIME types or
+//! tenant-specific rules. The first violation is reported as an invalid params
+//! error whose data names the rule and carries its details.
+
+use crate::a2a::core_types::{Message, PartRoot};
+use crate::a2a::error::{A2AError, InvalidParamsError};
+use crate::a2a::models::*;
+use crate::a2a::server::content_scan::{MimeAllowlistScanner, ScannedPart};
+use crate::a2a::server::context::ServerCallContext;
+use crate::a2a::server::request_handlers::request_handler::{
+    Event, MessageSendResult, RequestHandler, StreamEvent, TaskPushNotificationConfigQueryParams,
+};
+use crate::a2a::utils::constants::SKILL_ID_METADATA_KEY;
+use crate::a2a::utils::mime::{accepted_input_modes, validate_part_mime_types};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use std::collections::HashMap;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// A message breaking a validation rule
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("Message violates rule '{rule}': {reason}")]
+pub struct MessageViolation {
+    /// Short identifier of the rule, such as `max_text_length`
+    pub rule: String,
+    /// Human readable explanation
+    pub reason: String,
+    /// Structured details sent to the client
+    pub details: Option<serde_json::Value>,
+}
+
+impl MessageViolation {
+    pub fn new(rule: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            rule: rule.into(),
+            reason: reason.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+impl From<MessageViolation> for A2AError {
+    fn from(violation: MessageViolation) -> Self {
+        InvalidParamsError {
+            code: -32602,
+            message: violation.to_string(),
+            data: Some(serde_json::json!({
+                "rule": violation.rule,
+                "reason": violation.reason,
+                "details": violation.details,
+            })),
+        }
+        .into()
+    }
+}
+
+/// Checks an incoming message before it is handled
+#[async_trait]
+pub trait MessageValidator: Send + Sync {
+    /// Accepts the message or explains which rule it breaks
+    async fn validate(&self, message: &Message, context: Option<&ServerCallContext>) -> Result<(), MessageViolation>;
+}
+
+#[async_trait]
+impl<F> MessageValidator for F
+where
+    F: Fn(&Message, Option<&ServerCallContext>) -> Result<(), MessageViolation> + Send + Sync,
+{
+    async fn validate(&self, message: &Message, context: Option<&ServerCallContext>) -> Result<(), MessageViolation> {
+        self(message, context)
+    }
+}
+
+/// Refuses messages whose text parts are longer than a limit in total
+#[derive(Debug, Clone)]
+pub struct MaxTextLength {
+    max_chars: usize,
+}
+
+impl MaxTextLength {
+    /// Creates a validator refusing more than `max_chars` characters of text
+    pub fn new(max_chars: usize) -> Self {
+        Self { max_chars }
+    }
+}
+
+#[async_trait]
+impl MessageValidator for MaxTextLength {
+    async fn validate(&self, message: &Message, _context: Option<&ServerCallContext>) -> Result<(), MessageViolation> {
+        let length: usize = message
+            .parts
+            .iter()
+            .filter_map(|part| match part.root() {
+                PartRoot::Text(text) => Some(text.text.chars().count()),
+                _ => None,
+            })
+            .sum();
+        if length > self.max_chars {
+            return Err(MessageViolation::new(
+                "max_text_length",
+                format!("{} characters of text exceed the limit of {}", length, self.max_chars),
+            )
+            .with_details(serde_json::json!({ "length": length, "limit": self.max_chars })));
+        }
+        Ok(())
+    }
+}
+
+/// Refuses messages carrying files of the given MIME types
+///
+/// Entries are exact types or wildcards such as `video/*`.
+#[derive(Debug, Clone)]
+pub struct ForbiddenMimeTypes {
+    forbidden: MimeAllowlistScanner,
+}
+
+impl ForbiddenMimeTypes {
+    pub fn new<I, S>(forbidden: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            forbidden: MimeAllowlistScanner::new(forbidden),
+        }
+    }
+}
+
+#[async_trait]
+impl MessageValidator for ForbiddenMimeTypes {
+    async fn validate(&self, message: &Message, _context: Option<&ServerCallContext>) -> Result<(), MessageViolation> {
+        for (index, part) in message.parts.iter().enumerate() {
+            if !matches!(part.root(), PartRoot::File(_)) {
+                continue;
+            }
+            let scanned = ScannedPart { index, part };
+            let mime_type = scanned.mime_type();
+            if self.forbidden.allows(mime_type) {
+                return Err(MessageViolation::new(
+                    "forbidden_mime_type",
+                    format!("Part {} has forbidden MIME type '{}'", index, mime_type),
+                )
+                .with_details(serde_json::json!({ "part_index": index, "mime_type": mime_type })));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Refuses messages with parts outside the input modes an agent accepts
+///
+/// Parts are judged by their effective MIME type, so aliases such as
+/// `image/jpg` match `image/jpeg` and files without a declared type are
+/// sniffed from their content.
+#[derive(Debug, Clone)]
+pub struct AcceptedInputModes {
+    modes: Vec<String>,
+}
+
+impl AcceptedInputModes {
+    /// Creates a validator accepting the given modes
+    pub fn new<I, S>(modes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            modes: modes.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Creates a validator accepting the input modes of `card` and its skills
+    pub fn from_card(card: &AgentCard) -> Self {
+        Self::new(accepted_input_modes(card))
+    }
+}
+
+#[async_trait]
+impl MessageValidator for AcceptedInputModes {
+    async fn validate(&self, message: &Message, _context: Option<&ServerCallContext>) -> Result<(), MessageViolation> {
+        validate_part_mime_types(&message.parts, &self.modes).map_err(|unaccepted| {
+            MessageViolation::new("accepted_input_modes", unaccepted.to_string()).with_details(serde_json::json!({
+                "part_index": unaccepted.part_index,
+                "mime_type": unaccepted.mime_type,
+                "accepted": self.modes,
+            }))
+        })
+    }
+}
+
+/// Validates the data parts of messages against their skill's JSON Schema
+///
+/// Skills declare the schema of their structured input with
+/// `AgentSkill::with_data_schema`. A message selects a skill through the
+/// `skill_id` key of its metadata; data parts of messages addressed to a
+/// skill with a schema must match it, and are reported with the offending
+/// locations otherwise. Messages for unknown skills are refused, messages
+/// naming no skill are not checked.
+#[derive(Clone)]
+pub struct DataPartSchemas {
+    schemas: HashMap<String, Arc<jsonschema::Validator>>,
+    skills: Vec<String>,
+}
+
+impl DataPartSchemas {
+    /// Compiles the data schemas declared by the skills of `card`
+    ///
+    /// Fails with an invalid params error naming the skill when a schema is
+    /// not a valid JSON Schema.
+    pub fn from_card(card: &AgentCard) -> Result<Self, A2AError> {
+        let mut schemas = HashMap::new();
+        for skill in &card.skills {
+            if let Some(schema) = skill.data_schema() {
+                let validator = jsonschema::validator_for(schema).map_err(|e| {
+                    A2AError::invalid_params(&format!("Invalid data schema for skill '{}': {}", skill.id, e))
+                })?;
+                schemas.insert(skill.id.clone(), Arc::new(validator));
+            }
+        }
+        Ok(Self {
+            schemas,
+            skills: card.skills.iter().map(|skill| skill.id.clone()).collect(),
+        })
+    }
+}
+
+#[async_trait]
+impl MessageValidator for DataPartSchemas {
+    async fn validate(&self, message: &Message, _context: Option<&ServerCallContext>) -> Result<(), MessageViolation> {
+        let Some(skill_id) = message
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get(SKILL_ID_METADATA_KEY))
+            .and_then(|skill_id| skill_id.as_str())
+        else {
+            return Ok(());
+        };
+        if !self.skills.iter().any(|skill| skill == skill_id) {
+            return Err(MessageViolation::new("unknown_skill", format!("The agent has no skill '{}'", skill_id))
+                .with_details(serde_json::json!({ "skill_id": skill_id })));
+        }
+        let Some(schema) = self.schemas.get(skill_id) else {
+            return Ok(());
+        };
+
+        for (index, part) in message.parts.iter().enumerate() {
+            let PartRoot::Data(data) = part.root() else { continue };
+            let errors: Vec<serde_json::Value> = schema
+                .iter_errors(&data.data)
+                .map(|error| {
+                    serde_json::json!({
+                        "instance_path": error.instance_path.to_string(),
+                        "schema_path": error.schema_path.to_string(),
+                        "message": error.to_string(),
+                    })
+                })
+                .collect();
+            if let Some(first) = errors.first() {
+                let path = first["instance_path"].as_str().filter(|path| !path.is_empty()).unwrap_or("/");
+                return Err(MessageViolation::new(
+                    "data_schema",
+                    format!(
+                        "Part {} does not match the data schema of skill '{}' at {}: {}",
+                        index,
+                        skill_id,
+                        path,
+                        first["message"].as_str().unwrap_or_default()
+                    ),
+                )
+                .with_details(serde_json::json!({
+                    "skill_id": skill_id,
+                    "part_index": index,
+                    "errors": errors,
+                })));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Request handler running message validators before delegating
+pub struct ValidatingRequestHandler {
+    inner: Arc<dyn RequestHandler>,
+    validators: Vec<Arc<dyn MessageValidator>>,
+}
+
+impl ValidatingRequestHandler {
+    /// Wraps `inner` without any validators
+    pub fn new(inner: Arc<dyn RequestHandler>) -> Self {
+        Self {
+            inner,
+            validators: Vec::new(),
+        }
+    }
+
+    /// Adds a validator; validators run in the order they were added
+    pub fn with_validator(mut self, validator: Arc<dyn MessageValidator>) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    /// Runs the validators over `message`, stopping at the first violation
+    pub async fn validate_message(&self, message: &Message, context: Option<&ServerCallContext>) -> Result<(), A2AError> {
+        for validator in &self.validators {
+            validator.validate(message, context).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RequestHandler for ValidatingRequestHandler {
+    async fn on_get_task(
+        &self,
+        params: TaskQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        self.inner.on_get_task(params, context).await
+    }
+
+    async fn on_cancel_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        self.inner.on_cancel_task(params, context).await
+    }
+
+    async fn on_message_send(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<MessageSendResult, A2AError> {
+        self.validate_message(&params.message, context).await?;
+        self.inner.on_message_send(params, context).await
+    }
+
+    async fn on_message_send_stream(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        self.validate_message(&params.message, context).await?;
+        self.inner.on_message_send_stream(params, context).await
+    }
+
+    async fn on_message_send_stream_resumable(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        self.validate_message(&params.message, context).await?;
+        self.inner.on_message_send_stream_resumable(params, context).await
+    }
+
+    async fn on_set_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfig,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_set_task_push_notification_config(params, context).await
+    }
+
+    async fn on_get_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfigQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_get_task_push_notification_config(params, context).await
+    }
+
+    async fn on_resubscribe_to_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        self.inner.on_resubscribe_to_task(params, context).await
+    }
+
+    async fn on_resubscribe_to_task_resumable(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        self.inner.on_resubscribe_to_task_resumable(params, context).await
+    }
+
+    async fn on_list_task_push_notification_config(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Vec<TaskPushNotificationConfig>, A2AError> {
+        self.inner.on_list_task_push_notification_config(params, context).await
+    }
+
+    async fn on_delete_task_push_notification_config(
+        &self,
+        params: DeleteTaskPushNotificationConfigParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<(), A2AError> {
+        self.inner.on_delete_task_push_notification_config(params, context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::{FileContent, FilePart, Part, Role};
+    use crate::a2a::server::request_handlers::MockRequestHandler;
+
+    fn file(mime_type: &str) -> Part {
+        let mut file = FilePart::new_bytes("aGk=".to_string());
+        if let FileContent::Bytes(content) = &mut file.file {
+            content.mime_type = Some(mime_type.to_string());
+        }
+        Part::Direct(PartRoot::File(file))
+    }
+
+    fn handler() -> ValidatingRequestHandler {
+        let closed_context = |message: &Message, _: Option<&ServerCallContext>| match message.context_id.as_deref() {
+            Some("restricted") => Err(MessageViolation::new("restricted_context", "Context is closed to messages")),
+            _ => Ok(()),
+        };
+        ValidatingRequestHandler::new(Arc::new(MockRequestHandler))
+            .with_validator(Arc::new(MaxTextLength::new(10)))
+            .with_validator(Arc::new(ForbiddenMimeTypes::new(["video/*"])))
+            .with_validator(Arc::new(closed_context))
+    }
+
+    async fn send(message: Message) -> Result<MessageSendResult, A2AError> {
+        handler().on_message_send(MessageSendParams::new(message), None).await
+    }
+
+    #[tokio::test]
+    async fn test_valid_messages_reach_inner_handler() {
+        let message = Message::new(Role::User, vec![Part::text("hello".to_string()), file("image/png")]);
+        assert!(send(message).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_violations_are_invalid_params_with_details() {
+        let err = send(Message::new(Role::User, vec![Part::text("far too long a message".to_string())]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, A2AError::InvalidParams(_)));
+        let data = err.data().unwrap();
+        assert_eq!(data["rule"], "max_text_length");
+        assert_eq!(data["details"]["limit"], 10);
+
+        let err = send(Message::new(Role::User, vec![file("video/mp4")])).await.unwrap_err();
+        assert_eq!(err.data().unwrap()["details"]["mime_type"], "video/mp4");
+
+        let mut message = Message::new(Role::User, vec![Part::text("hi".to_string())]);
+        message.context_id = Some("restricted".to_string());
+        let err = send(message).await.unwrap_err();
+        assert_eq!(err.data().unwrap()["rule"], "restricted_context");
+    }
+
+    #[tokio::test]
+    async fn test_validators_run_in_order() {
+        // Both rules are broken; the first registered one is reported
+        let message = Message::new(Role::User, vec![Part::text("far too long a message".to_string()), file("video/mp4")]);
+        let err = send(message).await.unwrap_err();
+        assert_eq!(err.data().unwrap()["rule"], "max_text_length");
+    }
+
+    #[tokio::test]
+    async fn test_accepted_input_modes_use_effective_types() {
+        let card = AgentCard::new(
+            "Vision Agent".to_string(),
+            "An agent reading pictures".to_string(),
+            "http://localhost:8080".to_string(),
+            "1.0.0".to_string(),
+            vec!["text/plain".to_string()],
+            vec!["text/plain".to_string()],
+            AgentCapabilities::new(),
+            vec![AgentSkill::new(
+                "describe".to_string(),
+                "Describe".to_string(),
+                "Describes a picture".to_string(),
+                vec![],
+            )
+            .with_input_modes(vec!["image/jpeg".to_string()])],
+        );
+        let validator = AcceptedInputModes::from_card(&card);
+
+        let message = Message::new(Role::User, vec![Part::text("what is this?".to_string()), file("image/JPG")]);
+        assert!(validator.validate(&message, None).await.is_ok());
+
+        let violation = validator
+            .validate(&Message::new(Role::User, vec![file("image/png")]), None)
+            .await
+            .unwrap_err();
+        assert_eq!(violation.rule, "accepted_input_modes");
+        assert_eq!(violation.details.unwrap()["mime_type"], "image/png");
+    }
+
+    #[tokio::test]
+    async fn test_data_parts_are_checked_against_the_skill_schema() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": { "amount": { "type": "number", "minimum": 0 } },
+            "required": ["amount"],
+        });
+        let card = AgentCard::new(
+            "Billing Agent".to_string(),
+            "An agent issuing refunds".to_string(),
+            "http://localhost:8080".to_string(),
+            "1.0.0".to_string(),
+            vec!["application/json".to_string()],
+            vec!["text/plain".to_string()],
+            AgentCapabilities::new(),
+            vec![
+                AgentSkill::new("refund".to_string(), "Refund".to_string(), "Issues a refund".to_string(), vec![])
+                    .with_data_schema(schema),
+                AgentSkill::new("chat".to_string(), "Chat".to_string(), "Talks".to_string(), vec![]),
+            ],
+        );
+        let validator = DataPartSchemas::from_card(&card).unwrap();
+        let message = |skill_id: &str, data: serde_json::Value| {
+            Message::new(Role::User, vec![Part::text("refund please".to_string()), Part::data(data)])
+                .with_metadata(std::collections::HashMap::from([(
+                    SKILL_ID_METADATA_KEY.to_string(),
+                    serde_json::json!(skill_id),
+                )]))
+        };
+
+        assert!(validator.validate(&message("refund", serde_json::json!({"amount": 5})), None).await.is_ok());
+        assert!(validator.validate(&message("chat", serde_json::json!("anything")), None).await.is_ok());
+
+        let violation = validator
+            .validate(&message("refund", serde_json::json!({"amount": "five"})), None)
+            .await
+            .unwrap_err();
+        assert_eq!(violation.rule, "data_schema");
+        let details = violation.details.unwrap();
+        assert_eq!(details["part_index"], 1);
+        assert_eq!(details["errors"][0]["instance_path"], "/amount");
+
+        let violation = validator.validate(&message("teleport", serde_json::json!({})), None).await.unwrap_err();
+        assert_eq!(violation.rule, "unknown_skill");
+    }
+}
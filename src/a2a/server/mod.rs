@@ -3,12 +3,48 @@
 //! This module provides the core server components for implementing an A2A agent,
 //! including HTTP server, WebSocket support, and request handling.
 
+pub mod agent_card_handle;
+pub mod agent_execution;
 pub mod apps;
+pub mod artifacts;
+pub mod budget;
+pub mod card_bootstrap;
+pub mod config;
+pub mod content_scan;
 pub mod context;
 pub mod events;
+pub mod id_generator;
+#[cfg(feature = "mcp-bridge")]
+pub mod mcp_bridge;
+pub mod message_validation;
+pub mod part_transform;
+pub mod quota;
+pub mod replay;
+pub mod request_signing;
 pub mod request_handlers;
 pub mod tasks;
+pub mod uploads;
+pub mod usage;
 
 // Re-export commonly used types
-pub use context::{ServerCallContext, ServerCallContextBuilder};
+pub use agent_card_handle::{AgentCardHandle, AgentCardListener, RegistryNotifier};
+pub use budget::{Budget, BudgetExceeded, BudgetLimits, BudgetRequestHandler, BudgetResource, BudgetScope};
+pub use artifacts::{ArtifactLinkingRequestHandler, ArtifactStore, FileArtifactStore, InMemoryArtifactStore, StoredArtifact};
+pub use card_bootstrap::PublicEndpoint;
+pub use config::A2AConfig;
+pub use content_scan::{ContentRejection, ContentScanner, MimeAllowlistScanner, ScanningRequestHandler, SizeLimitScanner};
+pub use message_validation::{
+    AcceptedInputModes, DataPartSchemas, ForbiddenMimeTypes, MaxTextLength, MessageValidator, MessageViolation, ValidatingRequestHandler,
+};
+pub use part_transform::{PartTransformer, TransformingRequestHandler};
+#[cfg(feature = "image-transform")]
+pub use part_transform::ResizeImageTransformer;
+pub use context::{ApiKeyContextBuilder, ServerCallContext, ServerCallContextBuilder};
 pub use request_handlers::{RequestHandler, JSONRPCHandler};
+pub use quota::{InMemoryQuotaStore, QuotaError, QuotaLimits, QuotaStore, TenantUsage};
+pub use replay::{InMemoryNonceStore, NonceStore, ReplayError, ReplayProtection};
+#[cfg(feature = "redis")]
+pub use replay::RedisNonceStore;
+pub use request_signing::{HmacRequestVerifier, SignatureError};
+pub use uploads::{Upload, UploadError, UploadManager};
+pub use usage::{UsageMetadataRequestHandler, UsageRecorder};
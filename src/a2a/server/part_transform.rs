@@ -0,0 +1,339 @@
+//! Transformation of incoming message parts
+//!
+//! `TransformingRequestHandler` wraps a `RequestHandler` and passes every
+//! part of an incoming message through a chain of `PartTransformer`s before
+//! the wrapped handler, and so the agent executor, sees it. Transformers
+//! rewrite parts into the shape an agent works with best: transcoding images
+//! to a maximum resolution, converting documents to text or stripping
+//! metadata. Where validators only accept or refuse a message, transformers
+//! change it, and an error from any of them fails the call.
+//!
+//! `ResizeImageTransformer`, behind the `image-transform` feature, is a
+//! reference transformer scaling inline PNG and JPEG images down.
+
+use crate::a2a::core_types::{Message, Part};
+use crate::a2a::error::A2AError;
+use crate::a2a::models::*;
+use crate::a2a::server::context::ServerCallContext;
+use crate::a2a::server::request_handlers::request_handler::{
+    Event, MessageSendResult, RequestHandler, StreamEvent, TaskPushNotificationConfigQueryParams,
+};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use std::sync::Arc;
+
+/// Rewrites a part of an incoming message
+#[async_trait]
+pub trait PartTransformer: Send + Sync {
+    /// Returns the part the agent should see in place of `part`
+    ///
+    /// Parts a transformer does not handle are returned unchanged.
+    async fn transform(&self, part: Part, context: Option<&ServerCallContext>) -> Result<Part, A2AError>;
+}
+
+#[async_trait]
+impl<F> PartTransformer for F
+where
+    F: Fn(Part, Option<&ServerCallContext>) -> Result<Part, A2AError> + Send + Sync,
+{
+    async fn transform(&self, part: Part, context: Option<&ServerCallContext>) -> Result<Part, A2AError> {
+        self(part, context)
+    }
+}
+
+/// Request handler transforming message parts before delegating
+pub struct TransformingRequestHandler {
+    inner: Arc<dyn RequestHandler>,
+    transformers: Vec<Arc<dyn PartTransformer>>,
+}
+
+impl TransformingRequestHandler {
+    /// Wraps `inner` without any transformers
+    pub fn new(inner: Arc<dyn RequestHandler>) -> Self {
+        Self {
+            inner,
+            transformers: Vec::new(),
+        }
+    }
+
+    /// Adds a transformer; each one sees the output of those added before it
+    pub fn with_transformer(mut self, transformer: Arc<dyn PartTransformer>) -> Self {
+        self.transformers.push(transformer);
+        self
+    }
+
+    /// Runs every part of `message` through the transformers
+    pub async fn transform_message(
+        &self,
+        mut message: Message,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Message, A2AError> {
+        let mut parts = Vec::with_capacity(message.parts.len());
+        for mut part in std::mem::take(&mut message.parts) {
+            for transformer in &self.transformers {
+                part = transformer.transform(part, context).await?;
+            }
+            parts.push(part);
+        }
+        message.parts = parts;
+        Ok(message)
+    }
+
+    async fn transform_params(
+        &self,
+        mut params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<MessageSendParams, A2AError> {
+        params.message = self.transform_message(params.message, context).await?;
+        Ok(params)
+    }
+}
+
+#[async_trait]
+impl RequestHandler for TransformingRequestHandler {
+    async fn on_get_task(
+        &self,
+        params: TaskQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        self.inner.on_get_task(params, context).await
+    }
+
+    async fn on_cancel_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        self.inner.on_cancel_task(params, context).await
+    }
+
+    async fn on_message_send(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<MessageSendResult, A2AError> {
+        let params = self.transform_params(params, context).await?;
+        self.inner.on_message_send(params, context).await
+    }
+
+    async fn on_message_send_stream(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        let params = self.transform_params(params, context).await?;
+        self.inner.on_message_send_stream(params, context).await
+    }
+
+    async fn on_message_send_stream_resumable(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        let params = self.transform_params(params, context).await?;
+        self.inner.on_message_send_stream_resumable(params, context).await
+    }
+
+    async fn on_set_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfig,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_set_task_push_notification_config(params, context).await
+    }
+
+    async fn on_get_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfigQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_get_task_push_notification_config(params, context).await
+    }
+
+    async fn on_resubscribe_to_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        self.inner.on_resubscribe_to_task(params, context).await
+    }
+
+    async fn on_resubscribe_to_task_resumable(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        self.inner.on_resubscribe_to_task_resumable(params, context).await
+    }
+
+    async fn on_list_task_push_notification_config(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Vec<TaskPushNotificationConfig>, A2AError> {
+        self.inner.on_list_task_push_notification_config(params, context).await
+    }
+
+    async fn on_delete_task_push_notification_config(
+        &self,
+        params: DeleteTaskPushNotificationConfigParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<(), A2AError> {
+        self.inner.on_delete_task_push_notification_config(params, context).await
+    }
+}
+
+#[cfg(feature = "image-transform")]
+pub use resize::ResizeImageTransformer;
+
+#[cfg(feature = "image-transform")]
+mod resize {
+    use super::PartTransformer;
+    use crate::a2a::core_types::{FileContent, Part, PartRoot};
+    use crate::a2a::error::A2AError;
+    use crate::a2a::server::context::ServerCallContext;
+    use crate::a2a::utils::mime::effective_mime_type;
+    use async_trait::async_trait;
+    use base64::Engine;
+    use image::imageops::FilterType;
+    use image::ImageFormat;
+    use std::io::Cursor;
+
+    /// Scales inline PNG and JPEG images down to fit a maximum resolution
+    ///
+    /// The aspect ratio is kept. Images already within bounds pass through
+    /// untouched; resized ones are re-encoded in their original format,
+    /// which also drops metadata such as EXIF. Images given by URI are left
+    /// alone.
+    #[derive(Debug, Clone)]
+    pub struct ResizeImageTransformer {
+        max_width: u32,
+        max_height: u32,
+    }
+
+    impl ResizeImageTransformer {
+        /// Creates a transformer fitting images into `max_width` by `max_height` pixels
+        pub fn new(max_width: u32, max_height: u32) -> Self {
+            Self {
+                max_width: max_width.max(1),
+                max_height: max_height.max(1),
+            }
+        }
+
+        fn resize(&self, encoded: &str, format: ImageFormat) -> Result<Option<String>, A2AError> {
+            let engine = base64::engine::general_purpose::STANDARD;
+            let bytes = engine
+                .decode(encoded)
+                .map_err(|e| A2AError::invalid_params(&format!("Image part is not valid base64: {}", e)))?;
+            let image = image::load_from_memory_with_format(&bytes, format)
+                .map_err(|e| A2AError::invalid_params(&format!("Image part could not be decoded: {}", e)))?;
+            if image.width() <= self.max_width && image.height() <= self.max_height {
+                return Ok(None);
+            }
+
+            let resized = image.resize(self.max_width, self.max_height, FilterType::Lanczos3);
+            let mut output = Cursor::new(Vec::new());
+            resized
+                .write_to(&mut output, format)
+                .map_err(|e| A2AError::internal(&format!("Failed to encode resized image: {}", e)))?;
+            Ok(Some(engine.encode(output.into_inner())))
+        }
+    }
+
+    #[async_trait]
+    impl PartTransformer for ResizeImageTransformer {
+        async fn transform(&self, mut part: Part, _context: Option<&ServerCallContext>) -> Result<Part, A2AError> {
+            let mime_type = effective_mime_type(&part);
+            let format = match mime_type.split(';').next().unwrap_or_default().trim() {
+                "image/png" => ImageFormat::Png,
+                "image/jpeg" => ImageFormat::Jpeg,
+                _ => return Ok(part),
+            };
+            let root = match &mut part {
+                Part::WithRoot { root } => root,
+                Part::Direct(root) => root,
+            };
+            let PartRoot::File(file) = root else { return Ok(part) };
+            let FileContent::Bytes(content) = &mut file.file else { return Ok(part) };
+
+            // Decoding and scaling are CPU bound
+            let transformer = self.clone();
+            let encoded = std::mem::take(&mut content.bytes);
+            let (encoded, resized) = tokio::task::spawn_blocking(move || {
+                let resized = transformer.resize(&encoded, format);
+                (encoded, resized)
+            })
+            .await
+            .map_err(|e| A2AError::internal(&format!("Image resizing failed: {}", e)))?;
+            content.bytes = match resized? {
+                Some(resized) => {
+                    content.mime_type = Some(mime_type);
+                    resized
+                }
+                None => encoded,
+            };
+            Ok(part)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::{PartRoot, Role};
+    use crate::a2a::server::request_handlers::MockRequestHandler;
+    use crate::a2a::utils::get_message_text;
+
+    fn shout(part: Part, _: Option<&ServerCallContext>) -> Result<Part, A2AError> {
+        match part.root() {
+            PartRoot::Text(text) => Ok(Part::text(text.text.to_uppercase())),
+            _ => Ok(part),
+        }
+    }
+
+    fn handler() -> TransformingRequestHandler {
+        let exclaim = |part: Part, _: Option<&ServerCallContext>| match part.root() {
+            PartRoot::Text(text) if text.text.is_empty() => Err(A2AError::invalid_params("Empty text part")),
+            PartRoot::Text(text) => Ok(Part::text(format!("{}!", text.text))),
+            _ => Ok(part),
+        };
+        TransformingRequestHandler::new(Arc::new(MockRequestHandler))
+            .with_transformer(Arc::new(shout))
+            .with_transformer(Arc::new(exclaim))
+    }
+
+    #[tokio::test]
+    async fn test_transformers_run_in_order_before_the_inner_handler() {
+        let message = Message::new(Role::User, vec![Part::text("hi".to_string()), Part::data(serde_json::json!({}))]);
+        let MessageSendResult::Message(seen) = handler().on_message_send(MessageSendParams::new(message), None).await.unwrap()
+        else {
+            panic!("expected the mock handler to echo the message");
+        };
+        assert_eq!(get_message_text(&seen, "\n"), "HI!");
+        assert!(matches!(seen.parts[1].root(), PartRoot::Data(_)));
+
+        let message = Message::new(Role::User, vec![Part::text(String::new())]);
+        let err = handler().on_message_send(MessageSendParams::new(message), None).await.unwrap_err();
+        assert!(matches!(err, A2AError::InvalidParams(_)));
+    }
+
+    #[cfg(feature = "image-transform")]
+    #[tokio::test]
+    async fn test_large_images_are_scaled_down() {
+        use crate::a2a::core_types::{FileContent, FilePart};
+        use base64::Engine;
+
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::RgbImage::new(400, 100).write_to(&mut png, image::ImageFormat::Png).unwrap();
+        let file = FilePart::new_bytes(base64::engine::general_purpose::STANDARD.encode(png.into_inner()));
+
+        let part = ResizeImageTransformer::new(100, 100).transform(file.into(), None).await.unwrap();
+        let PartRoot::File(FilePart { file: FileContent::Bytes(content), .. }) = part.root() else {
+            panic!("expected an inline file");
+        };
+        let bytes = base64::engine::general_purpose::STANDARD.decode(&content.bytes).unwrap();
+        let resized = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((resized.width(), resized.height()), (100, 25));
+        assert_eq!(content.mime_type.as_deref(), Some("image/png"));
+    }
+}
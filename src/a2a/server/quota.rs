@@ -0,0 +1,400 @@
+//! Per-tenant payload metrics and quotas
+//!
+//! This module tracks request/response payload sizes per authenticated principal
+//! and enforces per-tenant quotas (daily message count, total artifact bytes).
+//! Quota state is kept behind the `QuotaStore` trait so that deployments can
+//! back it with a shared store; `InMemoryQuotaStore` is provided for single
+//! replica servers and tests.
+
+use crate::a2a::error::{A2AError, JSONRPCError};
+use crate::a2a::jsonrpc::error_codes;
+use crate::a2a::server::context::ServerCallContext;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::RwLock;
+
+/// Tenant name used for requests without an authenticated principal
+pub const ANONYMOUS_TENANT: &str = "anonymous";
+
+/// Task metadata key recording the tenant a task was created by
+pub const TENANT_METADATA_KEY: &str = "tenant";
+
+/// Quota limits applied to a single tenant; `None` means unlimited
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotaLimits {
+    /// Maximum number of message/send and message/stream calls per UTC day
+    pub max_daily_messages: Option<u64>,
+    /// Maximum total number of artifact bytes returned to the tenant
+    pub max_artifact_bytes: Option<u64>,
+}
+
+impl QuotaLimits {
+    /// Creates unlimited quota limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the daily message limit
+    pub fn with_max_daily_messages(mut self, max: u64) -> Self {
+        self.max_daily_messages = Some(max);
+        self
+    }
+
+    /// Sets the total artifact bytes limit
+    pub fn with_max_artifact_bytes(mut self, max: u64) -> Self {
+        self.max_artifact_bytes = Some(max);
+        self
+    }
+}
+
+/// Usage counters recorded for a single tenant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenantUsage {
+    /// The tenant these counters belong to
+    pub tenant: String,
+    /// The UTC day the daily counters refer to
+    pub day: NaiveDate,
+    /// Number of messages sent during `day`
+    pub daily_message_count: u64,
+    /// Total artifact bytes returned to the tenant
+    pub total_artifact_bytes: u64,
+    /// Total request payload bytes received from the tenant
+    pub bytes_in: u64,
+    /// Total response payload bytes sent to the tenant
+    pub bytes_out: u64,
+}
+
+impl TenantUsage {
+    /// Creates empty usage counters for a tenant
+    pub fn new(tenant: &str) -> Self {
+        Self {
+            tenant: tenant.to_string(),
+            day: today(),
+            daily_message_count: 0,
+            total_artifact_bytes: 0,
+            bytes_in: 0,
+            bytes_out: 0,
+        }
+    }
+
+    /// Resets the daily counters if the day has rolled over
+    fn roll_over(&mut self, day: NaiveDate) {
+        if self.day != day {
+            self.day = day;
+            self.daily_message_count = 0;
+        }
+    }
+}
+
+/// Errors raised by quota enforcement
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum QuotaError {
+    #[error("Daily message quota of {limit} exceeded for tenant '{tenant}'")]
+    DailyMessagesExceeded { tenant: String, limit: u64 },
+
+    #[error("Artifact byte quota of {limit} exceeded for tenant '{tenant}' ({used} bytes used)")]
+    ArtifactBytesExceeded { tenant: String, limit: u64, used: u64 },
+
+    #[error("Quota store error: {0}")]
+    Store(String),
+}
+
+impl QuotaError {
+    /// Returns the structured error data sent to the client
+    pub fn data(&self) -> Option<serde_json::Value> {
+        match self {
+            QuotaError::DailyMessagesExceeded { tenant, limit } => Some(serde_json::json!({
+                "tenant": tenant,
+                "quota": "max_daily_messages",
+                "limit": limit,
+            })),
+            QuotaError::ArtifactBytesExceeded { tenant, limit, used } => Some(serde_json::json!({
+                "tenant": tenant,
+                "quota": "max_artifact_bytes",
+                "limit": limit,
+                "used": used,
+            })),
+            QuotaError::Store(_) => None,
+        }
+    }
+}
+
+impl From<QuotaError> for A2AError {
+    fn from(err: QuotaError) -> Self {
+        match err {
+            QuotaError::Store(_) => A2AError::internal(&err.to_string()),
+            _ => {
+                let data = err.data();
+                A2AError::Generic(JSONRPCError {
+                    code: error_codes::QUOTA_EXCEEDED,
+                    message: err.to_string(),
+                    data,
+                })
+            }
+        }
+    }
+}
+
+/// Storage for per-tenant quota limits and usage counters
+#[async_trait]
+pub trait QuotaStore: Send + Sync {
+    /// Returns the limits configured for a tenant
+    async fn get_limits(&self, tenant: &str) -> Result<QuotaLimits, QuotaError>;
+
+    /// Returns the current usage counters for a tenant
+    async fn get_usage(&self, tenant: &str) -> Result<TenantUsage, QuotaError>;
+
+    /// Checks the tenant's quotas and records an incoming request
+    ///
+    /// `is_message` is true for message/send and message/stream calls, which
+    /// count towards the daily message quota. Nothing is recorded when the
+    /// request is rejected.
+    async fn check_and_record_request(
+        &self,
+        tenant: &str,
+        is_message: bool,
+        bytes_in: u64,
+    ) -> Result<TenantUsage, QuotaError>;
+
+    /// Records a response sent to the tenant
+    async fn record_response(
+        &self,
+        tenant: &str,
+        bytes_out: u64,
+        artifact_bytes: u64,
+    ) -> Result<(), QuotaError>;
+}
+
+/// In-memory implementation of QuotaStore
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryQuotaStore {
+    default_limits: QuotaLimits,
+    tenant_limits: Arc<RwLock<HashMap<String, QuotaLimits>>>,
+    usage: Arc<RwLock<HashMap<String, TenantUsage>>>,
+}
+
+impl InMemoryQuotaStore {
+    /// Creates a new store where every tenant is unlimited
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new store applying `limits` to tenants without explicit limits
+    pub fn with_default_limits(limits: QuotaLimits) -> Self {
+        Self {
+            default_limits: limits,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the limits for a specific tenant
+    pub async fn set_limits(&self, tenant: &str, limits: QuotaLimits) {
+        self.tenant_limits.write().await.insert(tenant.to_string(), limits);
+    }
+}
+
+#[async_trait]
+impl QuotaStore for InMemoryQuotaStore {
+    async fn get_limits(&self, tenant: &str) -> Result<QuotaLimits, QuotaError> {
+        Ok(self
+            .tenant_limits
+            .read()
+            .await
+            .get(tenant)
+            .cloned()
+            .unwrap_or_else(|| self.default_limits.clone()))
+    }
+
+    async fn get_usage(&self, tenant: &str) -> Result<TenantUsage, QuotaError> {
+        let mut usage = self
+            .usage
+            .read()
+            .await
+            .get(tenant)
+            .cloned()
+            .unwrap_or_else(|| TenantUsage::new(tenant));
+        usage.roll_over(today());
+        Ok(usage)
+    }
+
+    async fn check_and_record_request(
+        &self,
+        tenant: &str,
+        is_message: bool,
+        bytes_in: u64,
+    ) -> Result<TenantUsage, QuotaError> {
+        let limits = self.get_limits(tenant).await?;
+        let mut usage_map = self.usage.write().await;
+        let usage = usage_map
+            .entry(tenant.to_string())
+            .or_insert_with(|| TenantUsage::new(tenant));
+        usage.roll_over(today());
+
+        if let Some(limit) = limits.max_artifact_bytes {
+            if usage.total_artifact_bytes >= limit {
+                return Err(QuotaError::ArtifactBytesExceeded {
+                    tenant: tenant.to_string(),
+                    limit,
+                    used: usage.total_artifact_bytes,
+                });
+            }
+        }
+        if is_message {
+            if let Some(limit) = limits.max_daily_messages {
+                if usage.daily_message_count >= limit {
+                    return Err(QuotaError::DailyMessagesExceeded {
+                        tenant: tenant.to_string(),
+                        limit,
+                    });
+                }
+            }
+            usage.daily_message_count += 1;
+        }
+        usage.bytes_in += bytes_in;
+
+        Ok(usage.clone())
+    }
+
+    async fn record_response(
+        &self,
+        tenant: &str,
+        bytes_out: u64,
+        artifact_bytes: u64,
+    ) -> Result<(), QuotaError> {
+        let mut usage_map = self.usage.write().await;
+        let usage = usage_map
+            .entry(tenant.to_string())
+            .or_insert_with(|| TenantUsage::new(tenant));
+        usage.bytes_out += bytes_out;
+        usage.total_artifact_bytes += artifact_bytes;
+        Ok(())
+    }
+}
+
+/// Returns the tenant a request is accounted to
+pub fn tenant_for(context: &ServerCallContext) -> String {
+    let username = context.user.username();
+    if username.is_empty() {
+        ANONYMOUS_TENANT.to_string()
+    } else {
+        username.to_string()
+    }
+}
+
+/// Returns the number of serialized artifact bytes a JSON-RPC result of `method` produced
+///
+/// Only `message/send` and `message/stream` produce artifacts, counted from
+/// the `artifacts` array of a `Task` result and the `artifact` object of an
+/// artifact update event. Other methods such as `tasks/get` and
+/// `tasks/resubscribe` return artifacts that were already produced.
+pub fn artifact_bytes(method: &str, result: &serde_json::Value) -> u64 {
+    if method != "message/send" && method != "message/stream" {
+        return 0;
+    }
+    let serialized_len = |value: &serde_json::Value| {
+        serde_json::to_vec(value).map(|v| v.len() as u64).unwrap_or(0)
+    };
+
+    let mut total = 0;
+    if let Some(artifacts) = result.get("artifacts").and_then(|a| a.as_array()) {
+        total += artifacts.iter().map(serialized_len).sum::<u64>();
+    }
+    if let Some(artifact) = result.get("artifact") {
+        total += serialized_len(artifact);
+    }
+    total
+}
+
+fn today() -> NaiveDate {
+    chrono::Utc::now().date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_daily_message_quota() {
+        let store = InMemoryQuotaStore::with_default_limits(
+            QuotaLimits::new().with_max_daily_messages(2),
+        );
+
+        store.check_and_record_request("acme", true, 10).await.unwrap();
+        store.check_and_record_request("acme", true, 10).await.unwrap();
+        // Non-message calls are not counted against the message quota
+        store.check_and_record_request("acme", false, 10).await.unwrap();
+
+        let err = store.check_and_record_request("acme", true, 10).await.unwrap_err();
+        assert!(matches!(err, QuotaError::DailyMessagesExceeded { limit: 2, .. }));
+
+        let usage = store.get_usage("acme").await.unwrap();
+        assert_eq!(usage.daily_message_count, 2);
+        assert_eq!(usage.bytes_in, 30);
+
+        // Other tenants are unaffected
+        store.check_and_record_request("globex", true, 10).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_artifact_bytes_quota() {
+        let store = InMemoryQuotaStore::new();
+        store
+            .set_limits("acme", QuotaLimits::new().with_max_artifact_bytes(100))
+            .await;
+
+        store.check_and_record_request("acme", true, 0).await.unwrap();
+        store.record_response("acme", 500, 150).await.unwrap();
+
+        let err = store.check_and_record_request("acme", true, 0).await.unwrap_err();
+        assert!(matches!(err, QuotaError::ArtifactBytesExceeded { used: 150, .. }));
+
+        let usage = store.get_usage("acme").await.unwrap();
+        assert_eq!(usage.bytes_out, 500);
+    }
+
+    #[test]
+    fn test_quota_error_conversion() {
+        let err: A2AError = QuotaError::DailyMessagesExceeded {
+            tenant: "acme".to_string(),
+            limit: 5,
+        }
+        .into();
+        assert_eq!(err.code(), error_codes::QUOTA_EXCEEDED);
+        assert_eq!(err.data().unwrap()["quota"], "max_daily_messages");
+    }
+
+    #[test]
+    fn test_artifact_bytes() {
+        let result = serde_json::json!({
+            "id": "task-1",
+            "artifacts": [{"artifactId": "a", "parts": []}],
+        });
+        assert!(artifact_bytes("message/send", &result) > 0);
+        assert_eq!(artifact_bytes("message/send", &serde_json::json!({"id": "task-1"})), 0);
+        // Reading a task does not produce its artifacts again
+        assert_eq!(artifact_bytes("tasks/get", &result), 0);
+
+        let event = serde_json::json!({
+            "kind": "artifact-update",
+            "artifact": {"artifactId": "a", "parts": []},
+        });
+        assert!(artifact_bytes("message/stream", &event) > 0);
+        // Resubscribing replays artifacts that were already charged
+        assert_eq!(artifact_bytes("tasks/resubscribe", &event), 0);
+    }
+
+    #[test]
+    fn test_tenant_for() {
+        let context = ServerCallContext::new();
+        assert_eq!(tenant_for(&context), ANONYMOUS_TENANT);
+
+        let context = ServerCallContext::with_user(
+            crate::a2a::auth::user::AuthenticatedUser::new("acme".to_string()),
+        );
+        assert_eq!(tenant_for(&context), "acme");
+    }
+}
@@ -0,0 +1,411 @@
+//! Replay protection for authenticated requests
+//!
+//! A captured request carrying a bearer token or API key can otherwise be
+//! resent verbatim for as long as the credential is valid. With
+//! `ReplayProtection` configured, every JSON-RPC request must carry the
+//! nonce, timestamp and signature headers described in
+//! [`crate::a2a::utils::replay`]; the server rejects requests outside the
+//! time window, with a signature not made with the replay key over the
+//! method, path and body of the request, or reusing a nonce seen within the
+//! window. The replay key is a secret shared with the clients for this
+//! purpose only, never the credential the request is authenticated with.
+//! Seen nonces are kept per credential in a `NonceStore`, in memory for a
+//! single instance or in Redis (with the `redis` feature) when several
+//! instances share the traffic.
+//!
+//! Clients sign their requests with `ReplayProtectionInterceptor`.
+
+use crate::a2a::error::{A2AError, JSONRPCError};
+use crate::a2a::jsonrpc::error_codes;
+use crate::a2a::utils::replay::{verify_replay_signature, ReplayRequest, NONCE_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use async_trait::async_trait;
+use axum::http::HeaderMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::sync::Mutex;
+
+/// Default accepted clock difference, and how long nonces are remembered
+pub const DEFAULT_REPLAY_WINDOW: Duration = Duration::from_secs(300);
+
+/// Errors raised by replay protection
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ReplayError {
+    #[error("Missing replay protection header '{0}'")]
+    MissingHeader(&'static str),
+
+    #[error("Request timestamp is outside the accepted window of {window_secs}s")]
+    Stale { window_secs: u64 },
+
+    #[error("Invalid nonce signature")]
+    BadSignature,
+
+    #[error("Nonce has already been used")]
+    Replayed,
+
+    #[error("Nonce store error: {0}")]
+    Store(String),
+}
+
+impl ReplayError {
+    /// Returns the structured error data sent to the client
+    pub fn data(&self) -> Option<serde_json::Value> {
+        let reason = match self {
+            ReplayError::MissingHeader(header) => {
+                return Some(serde_json::json!({ "reason": "missing_header", "header": header }))
+            }
+            ReplayError::Stale { window_secs } => {
+                return Some(serde_json::json!({ "reason": "stale", "window_secs": window_secs }))
+            }
+            ReplayError::BadSignature => "bad_signature",
+            ReplayError::Replayed => "replayed",
+            ReplayError::Store(_) => return None,
+        };
+        Some(serde_json::json!({ "reason": reason }))
+    }
+}
+
+impl From<ReplayError> for A2AError {
+    fn from(err: ReplayError) -> Self {
+        match err {
+            ReplayError::Store(_) => A2AError::internal(&err.to_string()),
+            _ => {
+                let data = err.data();
+                A2AError::Generic(JSONRPCError {
+                    code: error_codes::REPLAY_REJECTED,
+                    message: err.to_string(),
+                    data,
+                })
+            }
+        }
+    }
+}
+
+/// Storage for the nonces seen within the replay window
+#[async_trait]
+pub trait NonceStore: Send + Sync {
+    /// Records `nonce` for `credential_key` for `ttl`
+    ///
+    /// Returns false, recording nothing, if the nonce is already recorded.
+    async fn check_and_insert(&self, credential_key: &str, nonce: &str, ttl: Duration) -> Result<bool, ReplayError>;
+}
+
+#[derive(Default)]
+struct SeenNonces {
+    expiry: HashMap<String, Instant>,
+    order: VecDeque<(Instant, String)>,
+}
+
+/// In-memory sliding window of seen nonces
+///
+/// Expired nonces are pruned on every insert, so memory stays proportional to
+/// the request rate times the window.
+#[derive(Clone, Default)]
+pub struct InMemoryNonceStore {
+    seen: Arc<Mutex<SeenNonces>>,
+}
+
+impl InMemoryNonceStore {
+    /// Creates an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of nonces currently remembered
+    pub async fn len(&self) -> usize {
+        self.seen.lock().await.expiry.len()
+    }
+
+    /// Whether no nonces are remembered
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+}
+
+#[async_trait]
+impl NonceStore for InMemoryNonceStore {
+    async fn check_and_insert(&self, credential_key: &str, nonce: &str, ttl: Duration) -> Result<bool, ReplayError> {
+        let now = Instant::now();
+        let mut seen = self.seen.lock().await;
+        while let Some((expires, _)) = seen.order.front() {
+            if *expires > now {
+                break;
+            }
+            let (expires, key) = seen.order.pop_front().expect("front was just checked");
+            // A key re-inserted after expiring has a later entry of its own
+            if seen.expiry.get(&key) == Some(&expires) {
+                seen.expiry.remove(&key);
+            }
+        }
+
+        let key = format!("{}:{}", credential_key, nonce);
+        if seen.expiry.contains_key(&key) {
+            return Ok(false);
+        }
+        let expires = now + ttl;
+        seen.expiry.insert(key.clone(), expires);
+        seen.order.push_back((expires, key));
+        Ok(true)
+    }
+}
+
+/// Nonce store shared by several server instances through Redis
+///
+/// Each nonce is a key set with `SET NX PX`, so Redis expires it at the end
+/// of the window.
+#[cfg(feature = "redis")]
+#[derive(Clone)]
+pub struct RedisNonceStore {
+    connection: redis::aio::ConnectionManager,
+    prefix: String,
+}
+
+#[cfg(feature = "redis")]
+impl RedisNonceStore {
+    /// Connects to the Redis server at `url`, e.g. `redis://127.0.0.1/`
+    pub async fn connect(url: &str) -> Result<Self, ReplayError> {
+        let client = redis::Client::open(url).map_err(|e| ReplayError::Store(e.to_string()))?;
+        let connection = redis::aio::ConnectionManager::new(client)
+            .await
+            .map_err(|e| ReplayError::Store(e.to_string()))?;
+        Ok(Self::new(connection))
+    }
+
+    /// Uses an existing connection
+    pub fn new(connection: redis::aio::ConnectionManager) -> Self {
+        Self {
+            connection,
+            prefix: "a2a:nonce:".to_string(),
+        }
+    }
+
+    /// Sets the prefix of the nonce keys, `a2a:nonce:` by default
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl NonceStore for RedisNonceStore {
+    async fn check_and_insert(&self, credential_key: &str, nonce: &str, ttl: Duration) -> Result<bool, ReplayError> {
+        let mut connection = self.connection.clone();
+        let inserted: Option<String> = redis::cmd("SET")
+            .arg(format!("{}{}:{}", self.prefix, credential_key, nonce))
+            .arg(1)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl.as_millis().max(1) as u64)
+            .query_async(&mut connection)
+            .await
+            .map_err(|e| ReplayError::Store(e.to_string()))?;
+        Ok(inserted.is_some())
+    }
+}
+
+/// Nonces are scoped per credential, keyed by a digest so stores never hold
+/// the credential itself
+fn credential_key(credential: &str) -> String {
+    use base64::Engine;
+    use sha2::Digest;
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(sha2::Sha256::digest(credential.as_bytes()))
+}
+
+/// Verifies the replay protection headers of incoming requests
+#[derive(Clone)]
+pub struct ReplayProtection {
+    store: Arc<dyn NonceStore>,
+    key: Arc<[u8]>,
+    window: Duration,
+    credential_header: Option<String>,
+}
+
+impl std::fmt::Debug for ReplayProtection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReplayProtection")
+            .field("window", &self.window)
+            .field("credential_header", &self.credential_header)
+            .finish()
+    }
+}
+
+impl ReplayProtection {
+    /// Verifies signatures made with `key` and remembers nonces in `store`
+    /// for `DEFAULT_REPLAY_WINDOW`
+    ///
+    /// Nonces are scoped to the credential of the `Authorization` header,
+    /// without its scheme.
+    pub fn new(store: Arc<dyn NonceStore>, key: &[u8]) -> Self {
+        Self {
+            store,
+            key: Arc::from(key),
+            window: DEFAULT_REPLAY_WINDOW,
+            credential_header: None,
+        }
+    }
+
+    /// Replay protection with an `InMemoryNonceStore`
+    pub fn in_memory(key: &[u8]) -> Self {
+        Self::new(Arc::new(InMemoryNonceStore::new()), key)
+    }
+
+    /// Sets the accepted clock difference, which is also how long nonces are
+    /// remembered
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Scopes nonces to the value of `header`, e.g. an API key header,
+    /// instead of the `Authorization` credential
+    pub fn with_credential_header(mut self, header: &str) -> Self {
+        self.credential_header = Some(header.to_string());
+        self
+    }
+
+    fn credential<'a>(&self, headers: &'a HeaderMap) -> Option<&'a str> {
+        match &self.credential_header {
+            Some(header) => headers.get(header.as_str()).and_then(|v| v.to_str().ok()),
+            None => headers
+                .get("authorization")
+                .and_then(|v| v.to_str().ok())
+                .map(|value| value.split_once(' ').map_or(value, |(_, credential)| credential.trim())),
+        }
+        .filter(|credential| !credential.is_empty())
+    }
+
+    /// Checks the replay protection headers of `request` and records its nonce
+    pub async fn verify(&self, headers: &HeaderMap, request: &ReplayRequest<'_>) -> Result<(), ReplayError> {
+        let header = |name: &'static str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .filter(|v| !v.is_empty())
+                .ok_or(ReplayError::MissingHeader(name))
+        };
+        let nonce = header(NONCE_HEADER)?;
+        let timestamp = header(TIMESTAMP_HEADER)?;
+        let signature = header(SIGNATURE_HEADER)?;
+        // Unauthenticated requests share one scope
+        let credential = self.credential(headers).unwrap_or_default();
+
+        let window_secs = self.window.as_secs();
+        let timestamp: i64 = timestamp.parse().map_err(|_| ReplayError::Stale { window_secs })?;
+        if chrono::Utc::now().timestamp().abs_diff(timestamp) > window_secs {
+            return Err(ReplayError::Stale { window_secs });
+        }
+        if !verify_replay_signature(&self.key, request, timestamp, nonce, signature) {
+            return Err(ReplayError::BadSignature);
+        }
+
+        // A nonce stays usable until its timestamp leaves the window on
+        // either side, so it is remembered for twice the window
+        if self.store.check_and_insert(&credential_key(credential), nonce, self.window * 2).await? {
+            Ok(())
+        } else {
+            Err(ReplayError::Replayed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::utils::replay::{replay_headers, replay_signature};
+
+    const KEY: &[u8] = b"replay-key";
+    const BODY: &[u8] = br#"{"jsonrpc":"2.0","id":1,"method":"message/send"}"#;
+
+    fn request(method: &str) -> ReplayRequest<'_> {
+        ReplayRequest::new(method, "/", BODY)
+    }
+
+    fn signed(key: &[u8], request: &ReplayRequest<'_>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", "Bearer token".parse().unwrap());
+        for (name, value) in replay_headers(key, request) {
+            headers.insert(name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[tokio::test]
+    async fn test_signed_nonce_is_accepted_once() {
+        let protection = ReplayProtection::in_memory(KEY);
+        let headers = signed(KEY, &request("message/send"));
+
+        protection.verify(&headers, &request("message/send")).await.unwrap();
+        assert_eq!(
+            protection.verify(&headers, &request("message/send")).await,
+            Err(ReplayError::Replayed)
+        );
+
+        let error: A2AError = ReplayError::Replayed.into();
+        assert_eq!(error.code(), error_codes::REPLAY_REJECTED);
+        assert_eq!(error.kind(), "replay_rejected");
+    }
+
+    #[tokio::test]
+    async fn test_unauthenticated_requests_are_verified() {
+        let protection = ReplayProtection::in_memory(KEY);
+        let mut headers = signed(KEY, &request("message/send"));
+        headers.remove("authorization");
+
+        protection.verify(&headers, &request("message/send")).await.unwrap();
+        assert_eq!(
+            protection.verify(&headers, &request("message/send")).await,
+            Err(ReplayError::Replayed)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_missing_stale_and_forged_headers() {
+        let protection = ReplayProtection::in_memory(KEY).with_window(Duration::from_secs(60));
+
+        let mut headers = signed(KEY, &request("message/send"));
+        headers.remove(NONCE_HEADER);
+        assert_eq!(
+            protection.verify(&headers, &request("message/send")).await,
+            Err(ReplayError::MissingHeader(NONCE_HEADER))
+        );
+
+        // Signed for another method, path or body, or with another key
+        let headers = signed(KEY, &request("tasks/get"));
+        assert_eq!(protection.verify(&headers, &request("message/send")).await, Err(ReplayError::BadSignature));
+        let headers = signed(KEY, &ReplayRequest::new("message/send", "/other", BODY));
+        assert_eq!(protection.verify(&headers, &request("message/send")).await, Err(ReplayError::BadSignature));
+        let headers = signed(KEY, &ReplayRequest::new("message/send", "/", b"{}"));
+        assert_eq!(protection.verify(&headers, &request("message/send")).await, Err(ReplayError::BadSignature));
+        let headers = signed(b"token", &request("message/send"));
+        assert_eq!(protection.verify(&headers, &request("message/send")).await, Err(ReplayError::BadSignature));
+
+        let mut headers = signed(KEY, &request("message/send"));
+        let old = chrono::Utc::now().timestamp() - 120;
+        let nonce = headers[NONCE_HEADER].to_str().unwrap().to_string();
+        headers.insert(TIMESTAMP_HEADER, old.to_string().parse().unwrap());
+        headers.insert(
+            SIGNATURE_HEADER,
+            replay_signature(KEY, &request("message/send"), old, &nonce).parse().unwrap(),
+        );
+        assert_eq!(
+            protection.verify(&headers, &request("message/send")).await,
+            Err(ReplayError::Stale { window_secs: 60 })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_store_forgets_expired_nonces() {
+        let store = InMemoryNonceStore::new();
+        let ttl = Duration::from_millis(20);
+        assert!(store.check_and_insert("k", "n1", ttl).await.unwrap());
+        assert!(!store.check_and_insert("k", "n1", ttl).await.unwrap());
+        assert!(store.check_and_insert("other", "n1", ttl).await.unwrap());
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(store.check_and_insert("k", "n2", ttl).await.unwrap());
+        assert_eq!(store.len().await, 1);
+        assert!(store.check_and_insert("k", "n1", ttl).await.unwrap());
+    }
+}
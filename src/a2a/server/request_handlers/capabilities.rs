@@ -0,0 +1,335 @@
+//! Request handling split by capability
+//!
+//! `RequestHandler` covers the whole protocol. Agents can implement the
+//! smaller traits of this module instead and get `RequestHandler` through a
+//! blanket implementation:
+//!
+//! - `MessageHandler` handles `message/send`. It is always required.
+//! - `TaskQueryHandler` handles `tasks/get`, and `tasks/cancel` if the agent
+//!   supports canceling. It is always required.
+//! - `StreamingHandler` handles `message/stream` and `tasks/resubscribe`.
+//! - `PushConfigHandler` handles the `tasks/pushNotificationConfig/*` methods.
+//!
+//! An agent offers the optional capabilities by implementing their trait and
+//! returning itself from `MessageHandler::streaming` or
+//! `MessageHandler::push_configs`. Methods of capabilities it does not offer
+//! are refused like the `RequestHandler` defaults refuse them, and
+//! `MessageHandler::capabilities` reports the ones it offers for the card:
+//!
+//! ```ignore
+//! #[async_trait]
+//! impl MessageHandler for MyAgent {
+//!     async fn on_message_send(&self, params: MessageSendParams, context: Option<&ServerCallContext>)
+//!         -> Result<MessageSendResult, A2AError> { ... }
+//!
+//!     fn streaming(&self) -> Option<&dyn StreamingHandler> {
+//!         Some(self)
+//!     }
+//! }
+//! ```
+//!
+//! The capability traits share method names with `RequestHandler`. Calls on
+//! an agent with both in scope need the trait spelled out, as in
+//! `RequestHandler::on_message_send(&agent, params, None)`.
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+
+use crate::a2a::error::A2AError;
+use crate::a2a::models::*;
+use crate::a2a::server::context::ServerCallContext;
+use crate::a2a::server::request_handlers::request_handler::{
+    Event, MessageSendResult, RequestHandler, TaskPushNotificationConfigQueryParams,
+};
+
+/// Handles 'message/send', and knows the optional capabilities of the agent
+#[async_trait]
+pub trait MessageHandler: Send + Sync {
+    /// Handles the 'message/send' method (non-streaming)
+    async fn on_message_send(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<MessageSendResult, A2AError>;
+
+    /// The streaming capability of the agent, if it has one
+    fn streaming(&self) -> Option<&dyn StreamingHandler> {
+        None
+    }
+
+    /// The push notification config capability of the agent, if it has one
+    fn push_configs(&self) -> Option<&dyn PushConfigHandler> {
+        None
+    }
+
+    /// The capabilities to declare on the agent card
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new()
+            .with_streaming(self.streaming().is_some())
+            .with_push_notifications(self.push_configs().is_some())
+    }
+}
+
+/// Handles 'tasks/get' and 'tasks/cancel'
+#[async_trait]
+pub trait TaskQueryHandler: Send + Sync {
+    /// Handles the 'tasks/get' method
+    async fn on_get_task(
+        &self,
+        params: TaskQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError>;
+
+    /// Handles the 'tasks/cancel' method; refused unless overridden
+    async fn on_cancel_task(
+        &self,
+        _params: TaskIdParams,
+        _context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        Err(A2AError::unsupported_operation("Task cancellation is not supported"))
+    }
+}
+
+/// Handles 'message/stream' and 'tasks/resubscribe'
+#[async_trait]
+pub trait StreamingHandler: Send + Sync {
+    /// Handles the 'message/stream' method
+    async fn on_message_send_stream(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError>;
+
+    /// Handles the 'tasks/resubscribe' method; refused unless overridden
+    async fn on_resubscribe_to_task(
+        &self,
+        _params: TaskIdParams,
+        _context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        Err(A2AError::unsupported_operation("Resubscription is not supported"))
+    }
+}
+
+/// Handles the 'tasks/pushNotificationConfig/*' methods
+#[async_trait]
+pub trait PushConfigHandler: Send + Sync {
+    async fn on_set_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfig,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError>;
+
+    async fn on_get_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfigQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError>;
+
+    async fn on_list_task_push_notification_config(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Vec<TaskPushNotificationConfig>, A2AError>;
+
+    async fn on_delete_task_push_notification_config(
+        &self,
+        params: DeleteTaskPushNotificationConfigParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<(), A2AError>;
+}
+
+#[async_trait]
+impl<T: MessageHandler + TaskQueryHandler> RequestHandler for T {
+    async fn on_get_task(
+        &self,
+        params: TaskQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        TaskQueryHandler::on_get_task(self, params, context).await
+    }
+
+    async fn on_cancel_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        TaskQueryHandler::on_cancel_task(self, params, context).await
+    }
+
+    async fn on_message_send(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<MessageSendResult, A2AError> {
+        MessageHandler::on_message_send(self, params, context).await
+    }
+
+    async fn on_message_send_stream(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        match self.streaming() {
+            Some(streaming) => streaming.on_message_send_stream(params, context).await,
+            None => Err(A2AError::unsupported_operation("Streaming is not supported")),
+        }
+    }
+
+    async fn on_resubscribe_to_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        match self.streaming() {
+            Some(streaming) => streaming.on_resubscribe_to_task(params, context).await,
+            None => Err(A2AError::unsupported_operation("Resubscription is not supported")),
+        }
+    }
+
+    async fn on_set_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfig,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        match self.push_configs() {
+            Some(push_configs) => push_configs.on_set_task_push_notification_config(params, context).await,
+            None => Err(A2AError::push_notification_not_supported()),
+        }
+    }
+
+    async fn on_get_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfigQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        match self.push_configs() {
+            Some(push_configs) => push_configs.on_get_task_push_notification_config(params, context).await,
+            None => Err(A2AError::push_notification_not_supported()),
+        }
+    }
+
+    async fn on_list_task_push_notification_config(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Vec<TaskPushNotificationConfig>, A2AError> {
+        match self.push_configs() {
+            Some(push_configs) => push_configs.on_list_task_push_notification_config(params, context).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn on_delete_task_push_notification_config(
+        &self,
+        params: DeleteTaskPushNotificationConfigParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<(), A2AError> {
+        match self.push_configs() {
+            Some(push_configs) => push_configs.on_delete_task_push_notification_config(params, context).await,
+            None => Err(A2AError::push_notification_not_supported()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::{Message, Part, Role, TaskState, TaskStatus};
+    use futures::StreamExt;
+    use std::sync::Arc;
+
+    /// Answers messages and nothing else
+    struct Minimal;
+
+    #[async_trait]
+    impl MessageHandler for Minimal {
+        async fn on_message_send(
+            &self,
+            params: MessageSendParams,
+            _context: Option<&ServerCallContext>,
+        ) -> Result<MessageSendResult, A2AError> {
+            Ok(MessageSendResult::Message(params.message))
+        }
+    }
+
+    #[async_trait]
+    impl TaskQueryHandler for Minimal {
+        async fn on_get_task(
+            &self,
+            _params: TaskQueryParams,
+            _context: Option<&ServerCallContext>,
+        ) -> Result<Option<Task>, A2AError> {
+            Ok(None)
+        }
+    }
+
+    /// Also streams a single working task
+    struct Streaming;
+
+    #[async_trait]
+    impl MessageHandler for Streaming {
+        async fn on_message_send(
+            &self,
+            params: MessageSendParams,
+            _context: Option<&ServerCallContext>,
+        ) -> Result<MessageSendResult, A2AError> {
+            Ok(MessageSendResult::Message(params.message))
+        }
+
+        fn streaming(&self) -> Option<&dyn StreamingHandler> {
+            Some(self)
+        }
+    }
+
+    #[async_trait]
+    impl TaskQueryHandler for Streaming {
+        async fn on_get_task(
+            &self,
+            _params: TaskQueryParams,
+            _context: Option<&ServerCallContext>,
+        ) -> Result<Option<Task>, A2AError> {
+            Ok(None)
+        }
+    }
+
+    #[async_trait]
+    impl StreamingHandler for Streaming {
+        async fn on_message_send_stream(
+            &self,
+            _params: MessageSendParams,
+            _context: Option<&ServerCallContext>,
+        ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+            let task = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working));
+            Ok(futures::stream::iter(vec![Ok(Event::Task(task))]).boxed())
+        }
+    }
+
+    fn params() -> MessageSendParams {
+        MessageSendParams::new(Message::new(Role::User, vec![Part::text("hi".to_string())]))
+    }
+
+    #[tokio::test]
+    async fn test_core_traits_make_a_request_handler() {
+        let handler: Arc<dyn RequestHandler> = Arc::new(Minimal);
+        assert!(matches!(handler.on_message_send(params(), None).await, Ok(MessageSendResult::Message(_))));
+        assert!(handler.on_message_send_stream(params(), None).await.is_err());
+        let task = TaskIdParams::new("task-1".to_string());
+        assert!(matches!(
+            handler.on_cancel_task(task.clone(), None).await,
+            Err(A2AError::UnsupportedOperation(_))
+        ));
+        assert!(handler.on_list_task_push_notification_config(task, None).await.unwrap().is_empty());
+        assert_eq!(Minimal.capabilities(), AgentCapabilities::new().with_streaming(false).with_push_notifications(false));
+    }
+
+    #[tokio::test]
+    async fn test_optional_capabilities_are_routed_and_reported() {
+        let handler: Arc<dyn RequestHandler> = Arc::new(Streaming);
+        let events: Vec<_> = handler.on_message_send_stream(params(), None).await.unwrap().collect().await;
+        assert!(matches!(events.as_slice(), [Ok(Event::Task(_))]));
+        let resubscribe = handler.on_resubscribe_to_task(TaskIdParams::new("task-1".to_string()), None).await;
+        assert!(matches!(resubscribe, Err(A2AError::UnsupportedOperation(_))));
+        assert_eq!(Streaming.capabilities().streaming, Some(true));
+        assert_eq!(Streaming.capabilities().push_notifications, Some(false));
+    }
+}
@@ -0,0 +1,390 @@
+//! Concurrency limits for message handling
+//!
+//! `ConcurrencyLimits` bounds how many messages are handled at once for the
+//! same task, context or tenant. Each limit keeps one async semaphore per
+//! key; a message over a limit is rejected or waits for a permit, depending
+//! on `OverLimit`. Serializing messages per task keeps two executor runs
+//! from interleaving their updates to the same task.
+//!
+//! `WorkerPool` caps how many tasks execute at once across the server, so a
+//! burst of messages does not overload the models an agent calls.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::a2a::error::{A2AError, JSONRPCError};
+use crate::a2a::jsonrpc::error_codes;
+
+/// What happens to a message that exceeds a concurrency limit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverLimit {
+    /// Fail the message with a concurrency limit error
+    #[default]
+    Reject,
+    /// Wait for a permit, failing after `timeout` if one is given
+    Wait { timeout: Option<Duration> },
+}
+
+/// Concurrency limits applied by `DefaultRequestHandler`; `None` means unlimited
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConcurrencyLimits {
+    /// Maximum number of concurrent messages for the same task id
+    pub max_per_task: Option<usize>,
+    /// Maximum number of concurrent messages for the same context id
+    pub max_per_context: Option<usize>,
+    /// Maximum number of concurrent messages for the same tenant
+    pub max_per_tenant: Option<usize>,
+    /// Behavior when a limit is reached
+    pub over_limit: OverLimit,
+}
+
+impl ConcurrencyLimits {
+    /// Creates unlimited concurrency limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handles messages for the same task one at a time
+    pub fn with_serialized_tasks(mut self) -> Self {
+        self.max_per_task = Some(1);
+        self
+    }
+
+    /// Sets the limit of concurrent messages per context id
+    pub fn with_max_per_context(mut self, max: usize) -> Self {
+        self.max_per_context = Some(max);
+        self
+    }
+
+    /// Sets the limit of concurrent messages per tenant
+    pub fn with_max_per_tenant(mut self, max: usize) -> Self {
+        self.max_per_tenant = Some(max);
+        self
+    }
+
+    /// Sets the behavior when a limit is reached
+    pub fn with_over_limit(mut self, over_limit: OverLimit) -> Self {
+        self.over_limit = over_limit;
+        self
+    }
+}
+
+/// The key a concurrency limit is counted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitScope {
+    /// The task id of the message
+    Task,
+    /// The context id of the message
+    Context,
+    /// The tenant the request is accounted to
+    Tenant,
+}
+
+impl LimitScope {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LimitScope::Task => "task",
+            LimitScope::Context => "context",
+            LimitScope::Tenant => "tenant",
+        }
+    }
+}
+
+/// Semaphores of one limit, created on first use and dropped when idle
+#[derive(Debug)]
+struct KeyedSemaphores {
+    limit: usize,
+    semaphores: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl KeyedSemaphores {
+    fn new(limit: usize) -> Arc<Self> {
+        Arc::new(Self {
+            limit: limit.max(1),
+            semaphores: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn semaphore(&self, key: &str) -> Arc<Semaphore> {
+        self.semaphores
+            .lock()
+            .unwrap()
+            .entry(key.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.limit)))
+            .clone()
+    }
+
+    /// Removes the semaphore of `key` once no permit or waiter refers to it
+    fn release(&self, key: &str) {
+        let mut semaphores = self.semaphores.lock().unwrap();
+        if semaphores.get(key).is_some_and(|semaphore| Arc::strong_count(semaphore) == 1) {
+            semaphores.remove(key);
+        }
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.semaphores.lock().unwrap().len()
+    }
+}
+
+/// A permit held for one key of one limit
+#[derive(Debug)]
+struct KeyedPermit {
+    owner: Arc<KeyedSemaphores>,
+    key: String,
+    permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Drop for KeyedPermit {
+    fn drop(&mut self) {
+        drop(self.permit.take());
+        self.owner.release(&self.key);
+    }
+}
+
+/// Permits held while a message is handled; dropping it releases them
+#[derive(Debug, Default)]
+pub struct ConcurrencyPermits {
+    permits: Vec<KeyedPermit>,
+}
+
+/// Enforces `ConcurrencyLimits`
+///
+/// Permits are always taken in task, context, tenant order, so waiting
+/// messages cannot deadlock each other.
+#[derive(Debug)]
+pub struct ConcurrencyLimiter {
+    per_task: Option<Arc<KeyedSemaphores>>,
+    per_context: Option<Arc<KeyedSemaphores>>,
+    per_tenant: Option<Arc<KeyedSemaphores>>,
+    over_limit: OverLimit,
+}
+
+impl ConcurrencyLimiter {
+    pub fn new(limits: &ConcurrencyLimits) -> Self {
+        Self {
+            per_task: limits.max_per_task.map(KeyedSemaphores::new),
+            per_context: limits.max_per_context.map(KeyedSemaphores::new),
+            per_tenant: limits.max_per_tenant.map(KeyedSemaphores::new),
+            over_limit: limits.over_limit,
+        }
+    }
+
+    /// Takes a permit for `key` under the `scope` limit, if that limit is set
+    pub async fn acquire(
+        &self,
+        permits: &mut ConcurrencyPermits,
+        scope: LimitScope,
+        key: &str,
+    ) -> Result<(), A2AError> {
+        let owner = match scope {
+            LimitScope::Task => &self.per_task,
+            LimitScope::Context => &self.per_context,
+            LimitScope::Tenant => &self.per_tenant,
+        };
+        let Some(owner) = owner else {
+            return Ok(());
+        };
+
+        let semaphore = owner.semaphore(key);
+        let permit = match self.over_limit {
+            OverLimit::Reject => semaphore.try_acquire_owned().ok(),
+            OverLimit::Wait { timeout: None } => semaphore.acquire_owned().await.ok(),
+            OverLimit::Wait { timeout: Some(timeout) } => tokio::time::timeout(timeout, semaphore.acquire_owned())
+                .await
+                .ok()
+                .and_then(Result::ok),
+        };
+        let Some(permit) = permit else {
+            owner.release(key);
+            return Err(limit_error(scope, key, owner.limit));
+        };
+        permits.permits.push(KeyedPermit {
+            owner: owner.clone(),
+            key: key.to_string(),
+            permit: Some(permit),
+        });
+        Ok(())
+    }
+}
+
+/// What happens to a message when every worker is busy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WhenBusy {
+    /// Return the task in `submitted` state and execute it once a worker is free
+    #[default]
+    Defer,
+    /// Wait for a worker, failing after `timeout` if one is given
+    Wait { timeout: Option<Duration> },
+}
+
+/// Caps the number of concurrently executing tasks
+#[derive(Debug, Clone)]
+pub struct WorkerPool {
+    workers: Arc<Semaphore>,
+    max_workers: usize,
+    when_busy: WhenBusy,
+}
+
+impl WorkerPool {
+    /// Creates a pool executing at most `max_workers` tasks at once
+    pub fn new(max_workers: usize) -> Self {
+        let max_workers = max_workers.max(1);
+        Self {
+            workers: Arc::new(Semaphore::new(max_workers)),
+            max_workers,
+            when_busy: WhenBusy::default(),
+        }
+    }
+
+    /// Sets the behavior when every worker is busy
+    pub fn with_when_busy(mut self, when_busy: WhenBusy) -> Self {
+        self.when_busy = when_busy;
+        self
+    }
+
+    pub fn when_busy(&self) -> WhenBusy {
+        self.when_busy
+    }
+
+    /// Number of workers currently free
+    pub fn available(&self) -> usize {
+        self.workers.available_permits()
+    }
+
+    /// Takes a worker if one is free
+    pub fn try_acquire(&self) -> Option<OwnedSemaphorePermit> {
+        self.workers.clone().try_acquire_owned().ok()
+    }
+
+    /// Waits for a worker, up to the timeout of `WhenBusy::Wait`
+    ///
+    /// With `WhenBusy::Defer` this waits without a timeout.
+    pub async fn acquire(&self) -> Result<OwnedSemaphorePermit, A2AError> {
+        let acquire = self.workers.clone().acquire_owned();
+        let permit = match self.when_busy {
+            WhenBusy::Wait { timeout: Some(timeout) } => {
+                tokio::time::timeout(timeout, acquire).await.ok().and_then(Result::ok)
+            }
+            _ => acquire.await.ok(),
+        };
+        permit.ok_or_else(|| {
+            A2AError::Generic(JSONRPCError {
+                code: error_codes::CONCURRENCY_LIMIT_EXCEEDED,
+                message: format!("All {} workers are busy", self.max_workers),
+                data: Some(serde_json::json!({ "scope": "server", "limit": self.max_workers })),
+            })
+        })
+    }
+}
+
+fn limit_error(scope: LimitScope, key: &str, limit: usize) -> A2AError {
+    A2AError::Generic(JSONRPCError {
+        code: error_codes::CONCURRENCY_LIMIT_EXCEEDED,
+        message: format!(
+            "Too many concurrent messages for {} {} (limit {})",
+            scope.as_str(),
+            key,
+            limit
+        ),
+        data: Some(serde_json::json!({ "scope": scope.as_str(), "key": key, "limit": limit })),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reject_over_limit() {
+        let limiter = ConcurrencyLimiter::new(&ConcurrencyLimits::new().with_serialized_tasks());
+        let mut first = ConcurrencyPermits::default();
+        limiter.acquire(&mut first, LimitScope::Task, "t1").await.unwrap();
+
+        let err = limiter
+            .acquire(&mut ConcurrencyPermits::default(), LimitScope::Task, "t1")
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), error_codes::CONCURRENCY_LIMIT_EXCEEDED);
+        assert_eq!(err.data().unwrap()["scope"], "task");
+
+        // Other keys and unset limits are unaffected
+        limiter.acquire(&mut ConcurrencyPermits::default(), LimitScope::Task, "t2").await.unwrap();
+        limiter.acquire(&mut ConcurrencyPermits::default(), LimitScope::Context, "c1").await.unwrap();
+
+        drop(first);
+        limiter.acquire(&mut ConcurrencyPermits::default(), LimitScope::Task, "t1").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_permit() {
+        let limiter = Arc::new(ConcurrencyLimiter::new(
+            &ConcurrencyLimits::new()
+                .with_max_per_context(1)
+                .with_over_limit(OverLimit::Wait { timeout: None }),
+        ));
+        let mut first = ConcurrencyPermits::default();
+        limiter.acquire(&mut first, LimitScope::Context, "c1").await.unwrap();
+
+        let waiting = tokio::spawn({
+            let limiter = limiter.clone();
+            async move {
+                let mut permits = ConcurrencyPermits::default();
+                limiter.acquire(&mut permits, LimitScope::Context, "c1").await
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiting.is_finished());
+
+        drop(first);
+        waiting.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wait_times_out() {
+        let limiter = ConcurrencyLimiter::new(
+            &ConcurrencyLimits::new()
+                .with_max_per_tenant(1)
+                .with_over_limit(OverLimit::Wait { timeout: Some(Duration::from_millis(10)) }),
+        );
+        let mut first = ConcurrencyPermits::default();
+        limiter.acquire(&mut first, LimitScope::Tenant, "alice").await.unwrap();
+
+        let err = limiter
+            .acquire(&mut ConcurrencyPermits::default(), LimitScope::Tenant, "alice")
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), error_codes::CONCURRENCY_LIMIT_EXCEEDED);
+    }
+
+    #[tokio::test]
+    async fn test_worker_pool_wait_times_out() {
+        let pool = WorkerPool::new(1).with_when_busy(WhenBusy::Wait { timeout: Some(Duration::from_millis(10)) });
+        let worker = pool.try_acquire().unwrap();
+        assert!(pool.try_acquire().is_none());
+
+        let err = pool.acquire().await.unwrap_err();
+        assert_eq!(err.code(), error_codes::CONCURRENCY_LIMIT_EXCEEDED);
+        assert_eq!(err.data().unwrap()["scope"], "server");
+
+        drop(worker);
+        assert_eq!(pool.available(), 1);
+        let _worker = pool.acquire().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_idle_keys_are_dropped() {
+        let limiter = ConcurrencyLimiter::new(&ConcurrencyLimits::new().with_serialized_tasks());
+        let mut permits = ConcurrencyPermits::default();
+        limiter.acquire(&mut permits, LimitScope::Task, "t1").await.unwrap();
+        let _ = limiter.acquire(&mut ConcurrencyPermits::default(), LimitScope::Task, "t1").await;
+        assert_eq!(limiter.per_task.as_ref().unwrap().len(), 1);
+
+        drop(permits);
+        assert_eq!(limiter.per_task.as_ref().unwrap().len(), 0);
+    }
+}
@@ -3,46 +3,592 @@
 //! This module provides the DefaultRequestHandler which coordinates between
 //! TaskStore, PushNotificationSender, and other components, mirroring the
 //! Python implementation.
+//!
+//! Task events are persisted through a `TaskManager` and published on an
+//! `EventBus`; push notifications, metrics and resubscription streams consume
+//! the bus rather than being called by the handler directly. With an
+//! `EventStore` attached, streamed events carry resumption ids and
+//! `tasks/resubscribe` with `from_event_id` replays the events a client missed,
+//! and a `ResubscribeFilter` limits the stream to some event kinds or to
+//! events after a timestamp.
+//! `ConcurrencyLimits` bound concurrent messages per task, context and tenant,
+//! and a `WorkerPool` caps how many tasks execute at once. After a restart,
+//! `recover_tasks` applies the `RecoveryPolicy` to tasks the previous process
+//! left in the submitted or working state. A `TaskTimeline` records the
+//! phases of each task for latency breakdowns.
+//!
+//! Messages sent as a dry run (`MessageSendParams::with_dry_run`) are
+//! answered with the task they would create or continue, in the submitted
+//! state and marked with `dry_run` metadata. Nothing is executed, persisted
+//! or published, and no concurrency permits are taken.
 
 use async_trait::async_trait;
 use futures::stream::{BoxStream, StreamExt};
 use std::sync::Arc;
-use tracing::error;
+use tokio::sync::Mutex;
 
 use crate::a2a::models::*;
-use crate::a2a::core_types::{TaskStatus, TaskState};
+use crate::a2a::core_types::{Message, Part, Role, TaskStatus, TaskState};
 use crate::a2a::server::context::ServerCallContext;
-use crate::a2a::server::request_handlers::request_handler::{RequestHandler, MessageSendResult, Event};
-use crate::a2a::server::tasks::{TaskStore, PushNotificationConfigStore, PushNotificationSender, TaskManager};
+use crate::a2a::server::events::{
+    event_id, parse_event_id, BusEvent, EventBus, EventStore, EventStoreSubscriber, PushNotificationSubscriber,
+    TaskTimeline, TimelinePhase,
+};
+use crate::a2a::server::events::timeline::TIMELINE_METADATA_KEY;
+use crate::a2a::server::quota;
+use crate::a2a::server::request_handlers::concurrency::{ConcurrencyLimiter, ConcurrencyLimits, ConcurrencyPermits, LimitScope, WhenBusy, WorkerPool};
+use crate::a2a::server::request_handlers::request_handler::{RequestHandler, MessageSendResult, Event, StreamEvent};
+use crate::a2a::server::tasks::{TaskStore, InvalidatingPushConfigStore, PushNotificationConfigStore, PushNotificationSender, ReadConsistency, StateTransitionHistory, StoreInstrumentation, TaskManager, TaskEvent};
 use crate::a2a::error::A2AError;
+use crate::a2a::utils::constants::DRY_RUN_METADATA_KEY;
 
 /// Default Request Handler
 pub struct DefaultRequestHandler {
     task_store: Arc<dyn TaskStore>,
     push_config_store: Option<Arc<dyn PushNotificationConfigStore>>,
-    push_sender: Option<Arc<dyn PushNotificationSender>>,
+    event_bus: EventBus,
+    event_store: Option<Arc<dyn EventStore>>,
+    terminal_task_policy: TerminalTaskPolicy,
+    concurrency: Option<ConcurrencyLimiter>,
+    worker_pool: Option<WorkerPool>,
+    recovery_policy: RecoveryPolicy,
+    timeline: Option<TaskTimeline>,
+    state_transition_history: Option<StateTransitionHistory>,
+    tenant_metadata: bool,
+}
+
+/// How a message for a task in a terminal state is handled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TerminalTaskPolicy {
+    /// Reject the message with an invalid params error, as a2a-python does
+    #[default]
+    Reject,
+    /// Move the task back to working and append the message to its history
+    Reopen,
+}
+
+/// What `recover_tasks` does with tasks interrupted by a restart
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecoveryPolicy {
+    /// Leave the tasks in their current state
+    #[default]
+    Leave,
+    /// Mark the tasks failed, explaining that the server restarted
+    MarkFailed,
+    /// Execute the tasks again, through the worker pool if one is configured
+    Requeue,
+}
+
+/// The status message of tasks failed by `RecoveryPolicy::MarkFailed`
+pub const RESTART_FAILURE_REASON: &str = "Task was interrupted by a server restart";
+
+/// Converts a bus event to a stream event, carrying its resumption id if
+/// the event is `resumable` from an event store
+fn stream_event(bus_event: BusEvent, resumable: bool) -> StreamEvent {
+    let event_id = resumable.then(|| bus_event.event_id());
+    StreamEvent::new(Event::from(bus_event.event), event_id)
+}
+
+/// A `ResubscribeFilter` with its timestamp parsed
+struct EventFilter {
+    kinds: Option<Vec<StreamEventKind>>,
+    since: Option<chrono::DateTime<chrono::FixedOffset>>,
+}
+
+impl EventFilter {
+    fn new(filter: &ResubscribeFilter) -> Result<Self, A2AError> {
+        if filter.kinds.as_ref().is_some_and(Vec::is_empty) {
+            return Err(A2AError::invalid_params("The resubscribe filter must name at least one event kind"));
+        }
+        let since = filter
+            .since
+            .as_deref()
+            .map(chrono::DateTime::parse_from_rfc3339)
+            .transpose()
+            .map_err(|e| A2AError::invalid_params(&format!("Invalid resubscribe filter timestamp: {}", e)))?;
+        Ok(Self {
+            kinds: filter.kinds.clone(),
+            since,
+        })
+    }
+
+    fn matches(&self, event: &Event) -> bool {
+        let (kind, timestamp) = match event {
+            Event::Task(task) => (StreamEventKind::Task, task.status.timestamp.as_deref()),
+            Event::Message(_) => (StreamEventKind::Message, None),
+            Event::TaskStatusUpdate(update) => (StreamEventKind::StatusUpdate, update.status.timestamp.as_deref()),
+            Event::TaskArtifactUpdate(_) => (StreamEventKind::ArtifactUpdate, None),
+        };
+        if self.kinds.as_ref().is_some_and(|kinds| !kinds.contains(&kind)) {
+            return false;
+        }
+        match (self.since, timestamp.and_then(|timestamp| chrono::DateTime::parse_from_rfc3339(timestamp).ok())) {
+            (Some(since), Some(timestamp)) => timestamp > since,
+            _ => true,
+        }
+    }
+}
+
+/// Moves a submitted task to working once `pool` has a free worker
+///
+/// The permits are held until the task has started.
+fn spawn_deferred_start(
+    pool: WorkerPool,
+    mut task_manager: TaskManager,
+    task_id: String,
+    context_id: String,
+    permits: ConcurrencyPermits,
+    timeline: Option<TaskTimeline>,
+) {
+    tokio::spawn(async move {
+        let Ok(_worker) = pool.acquire().await else {
+            return;
+        };
+        let _permits = permits;
+        if let Some(timeline) = &timeline {
+            timeline.record(&task_id, TimelinePhase::Started);
+        }
+        let working = TaskStatusUpdateEvent::new(
+            task_id.clone(),
+            context_id,
+            TaskStatus::new(TaskState::Working),
+            false,
+        );
+        if let Err(e) = task_manager.save_task_event(TaskEvent::StatusUpdate(working)).await {
+            tracing::warn!(task_id = %task_id, error = %e, "Deferred task failed to start");
+        }
+    });
 }
 
 impl DefaultRequestHandler {
-    /// Create a new DefaultRequestHandler
+    /// Create a new DefaultRequestHandler with its own event bus
+    ///
+    /// If a push sender is given, it is subscribed to the bus; this spawns a
+    /// Tokio task and must be called from within a runtime. The config store
+    /// is then wrapped in an `InvalidatingPushConfigStore`, so changes reach
+    /// the configs the sender cached before they return.
     pub fn new(
         task_store: Arc<dyn TaskStore>,
         push_config_store: Option<Arc<dyn PushNotificationConfigStore>>,
         push_sender: Option<Arc<dyn PushNotificationSender>>,
     ) -> Self {
+        Self::with_event_bus(task_store, push_config_store, push_sender, EventBus::new())
+    }
+
+    /// Create a new DefaultRequestHandler publishing on a shared event bus
+    pub fn with_event_bus(
+        task_store: Arc<dyn TaskStore>,
+        push_config_store: Option<Arc<dyn PushNotificationConfigStore>>,
+        push_sender: Option<Arc<dyn PushNotificationSender>>,
+        event_bus: EventBus,
+    ) -> Self {
+        let mut push_config_store = push_config_store;
+        if let Some(sender) = push_sender {
+            push_config_store = push_config_store.map(|store| {
+                Arc::new(InvalidatingPushConfigStore::new(store, sender.clone())) as Arc<dyn PushNotificationConfigStore>
+            });
+            event_bus.spawn_subscriber(Arc::new(PushNotificationSubscriber::new(sender)));
+        }
         Self {
             task_store,
             push_config_store,
-            push_sender,
+            event_bus,
+            event_store: None,
+            terminal_task_policy: TerminalTaskPolicy::default(),
+            concurrency: None,
+            worker_pool: None,
+            recovery_policy: RecoveryPolicy::default(),
+            timeline: None,
+            state_transition_history: None,
+            tenant_metadata: false,
+        }
+    }
+
+    /// The bus this handler publishes task events on
+    pub fn event_bus(&self) -> &EventBus {
+        &self.event_bus
+    }
+
+    /// The push config store of this handler
+    ///
+    /// Other writers, such as `AdminApi` and `DataSubjectRequests`, should
+    /// use this store so their changes invalidate the configs the push
+    /// sender cached.
+    pub fn push_config_store(&self) -> Option<Arc<dyn PushNotificationConfigStore>> {
+        self.push_config_store.clone()
+    }
+
+    /// The store retaining the published events, if any
+    pub fn event_store(&self) -> Option<Arc<dyn EventStore>> {
+        self.event_store.clone()
+    }
+
+    /// Retain published events in `event_store` so streams can be resumed
+    ///
+    /// Spawns a Tokio task and must be called from within a runtime.
+    pub fn with_event_store(mut self, event_store: Arc<dyn EventStore>) -> Self {
+        self.event_bus
+            .spawn_subscriber(Arc::new(EventStoreSubscriber::new(event_store.clone())));
+        self.event_store = Some(event_store);
+        self
+    }
+
+    /// Set how messages for tasks in a terminal state are handled
+    pub fn with_terminal_task_policy(mut self, policy: TerminalTaskPolicy) -> Self {
+        self.terminal_task_policy = policy;
+        self
+    }
+
+    /// Limit concurrent messages per task, context or tenant
+    pub fn with_concurrency_limits(mut self, limits: ConcurrencyLimits) -> Self {
+        self.concurrency = Some(ConcurrencyLimiter::new(&limits));
+        self
+    }
+
+    /// Execute tasks on a worker pool
+    ///
+    /// A `message/send` arriving while every worker is busy either returns
+    /// the task in `submitted` state and executes it later, or waits for a
+    /// worker, depending on `WhenBusy`. Streaming messages always wait, since
+    /// their events are delivered on the open stream; with `WhenBusy::Defer`
+    /// they wait without a timeout.
+    pub fn with_worker_pool(mut self, worker_pool: WorkerPool) -> Self {
+        self.worker_pool = Some(worker_pool);
+        self
+    }
+
+    /// Set what `recover_tasks` does with interrupted tasks
+    pub fn with_recovery_policy(mut self, policy: RecoveryPolicy) -> Self {
+        self.recovery_policy = policy;
+        self
+    }
+
+    /// Applies the recovery policy to tasks left submitted or working
+    ///
+    /// Meant to be called once on startup, before requests are served, since
+    /// any task still running at that point is taken for interrupted. Returns
+    /// the ids of the tasks that were failed or requeued.
+    pub async fn recover_tasks(&self) -> Result<Vec<String>, A2AError> {
+        if self.recovery_policy == RecoveryPolicy::Leave {
+            return Ok(Vec::new());
+        }
+        let interrupted: Vec<Task> = self
+            .task_store
+            .list()
+            .await?
+            .into_iter()
+            .filter(|task| matches!(task.status.state, TaskState::Submitted | TaskState::Working))
+            .collect();
+
+        let mut recovered = Vec::with_capacity(interrupted.len());
+        for task in interrupted {
+            let mut task_manager = self.task_manager(&task.id, &task.context_id, None)?;
+            match (self.recovery_policy, &self.worker_pool) {
+                (RecoveryPolicy::MarkFailed, _) => {
+                    let reason = Message::new(Role::Agent, vec![Part::text(RESTART_FAILURE_REASON.to_string())]);
+                    let failed = TaskStatusUpdateEvent::new(
+                        task.id.clone(),
+                        task.context_id.clone(),
+                        TaskStatus::new(TaskState::Failed).with_message(reason),
+                        true,
+                    );
+                    task_manager.save_task_event(TaskEvent::StatusUpdate(failed)).await?;
+                }
+                (RecoveryPolicy::Requeue, Some(pool)) => {
+                    let submitted = TaskStatusUpdateEvent::new(
+                        task.id.clone(),
+                        task.context_id.clone(),
+                        TaskStatus::new(TaskState::Submitted),
+                        false,
+                    );
+                    task_manager.save_task_event(TaskEvent::StatusUpdate(submitted)).await?;
+                    self.record(&task.id, TimelinePhase::Queued);
+                    spawn_deferred_start(
+                        pool.clone(),
+                        task_manager,
+                        task.id.clone(),
+                        task.context_id.clone(),
+                        ConcurrencyPermits::default(),
+                        self.timeline.clone(),
+                    );
+                }
+                (RecoveryPolicy::Requeue, None) => {
+                    let working = TaskStatusUpdateEvent::new(
+                        task.id.clone(),
+                        task.context_id.clone(),
+                        TaskStatus::new(TaskState::Working),
+                        false,
+                    );
+                    self.record(&task.id, TimelinePhase::Started);
+                    task_manager.save_task_event(TaskEvent::StatusUpdate(working)).await?;
+                }
+                (RecoveryPolicy::Leave, _) => continue,
+            }
+            tracing::info!(task_id = %task.id, policy = ?self.recovery_policy, "Recovered interrupted task");
+            recovered.push(task.id);
+        }
+        Ok(recovered)
+    }
+
+    /// Record the phases of every task on `timeline`
+    ///
+    /// Subscribes the timeline to the bus; this spawns a Tokio task and must
+    /// be called from within a runtime.
+    pub fn with_task_timeline(mut self, timeline: TaskTimeline) -> Self {
+        self.event_bus.spawn_subscriber(Arc::new(timeline.clone()));
+        self.timeline = Some(timeline);
+        self
+    }
+
+    /// Record the status transitions of every task, see `StateTransitionHistory`
+    pub fn with_state_transition_history(mut self, history: StateTransitionHistory) -> Self {
+        self.state_transition_history = Some(history);
+        self
+    }
+
+    /// The rules status transitions are recorded by, if enabled
+    pub fn state_transition_history(&self) -> Option<StateTransitionHistory> {
+        self.state_transition_history
+    }
+
+    /// Record status transitions if `capabilities` advertises the state
+    /// transition history
+    pub fn with_capabilities(mut self, capabilities: &AgentCapabilities) -> Self {
+        self.state_transition_history = StateTransitionHistory::from_capabilities(capabilities);
+        self
+    }
+
+    /// Record the tenant that created each task in its `tenant` metadata
+    ///
+    /// Lets consumers of task events, such as `SseRelay`, tell the tasks of
+    /// different tenants apart, and scopes `tasks/search` to the caller.
+    pub fn with_tenant_metadata(mut self) -> Self {
+        self.tenant_metadata = true;
+        self
+    }
+
+    fn record(&self, task_id: &str, phase: TimelinePhase) {
+        if let Some(timeline) = &self.timeline {
+            timeline.record(task_id, phase);
+        }
+    }
+
+    /// Record latency and errors of the task and push config stores
+    ///
+    /// Only the handler's own calls are recorded; a push sender reading the
+    /// config store must be given the instrumented store itself.
+    pub fn with_store_instrumentation(mut self, instrumentation: &StoreInstrumentation) -> Self {
+        self.task_store = instrumentation.task_store(self.task_store);
+        self.push_config_store = self.push_config_store.map(|store| instrumentation.push_config_store(store));
+        self
+    }
+
+    /// Takes a permit for `key` if a concurrency limit applies to `scope`
+    async fn limit(&self, permits: &mut ConcurrencyPermits, scope: LimitScope, key: &str) -> Result<(), A2AError> {
+        match &self.concurrency {
+            Some(limiter) => limiter.acquire(permits, scope, key).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Takes the permits a message needs before it is handled
+    ///
+    /// The task permit is taken before the task is loaded, so that a second
+    /// message for the same task sees the state the first one left behind.
+    /// Returns the task the message continues along with the permits.
+    async fn admit(
+        &self,
+        message: &crate::a2a::core_types::Message,
+        context: Option<&ServerCallContext>,
+    ) -> Result<(Option<Task>, String, ConcurrencyPermits), A2AError> {
+        let mut permits = ConcurrencyPermits::default();
+        if let Some(task_id) = &message.task_id {
+            self.limit(&mut permits, LimitScope::Task, task_id).await?;
+        }
+        let existing = self.existing_task(message).await?;
+        let context_id = existing
+            .as_ref()
+            .map(|task| task.context_id.clone())
+            .or_else(|| message.context_id.clone())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        self.limit(&mut permits, LimitScope::Context, &context_id).await?;
+        if let Some(context) = context {
+            self.limit(&mut permits, LimitScope::Tenant, &quota::tenant_for(context)).await?;
         }
+        Ok((existing, context_id, permits))
     }
 
-    async fn send_push_notification_if_needed(&self, task: &Task) {
-        if let Some(ref sender) = self.push_sender {
-            if let Err(e) = sender.send_notification(task).await {
-                error!("Failed to send push notification: {}", e);
+    /// Loads the task a message continues, if it names one
+    ///
+    /// Like a2a-python, a task id that does not exist is a task not found
+    /// error. A task in a terminal state is rejected with an invalid params
+    /// error unless the policy reopens it.
+    async fn existing_task(&self, message: &crate::a2a::core_types::Message) -> Result<Option<Task>, A2AError> {
+        let Some(task_id) = &message.task_id else {
+            return Ok(None);
+        };
+        let task = self
+            .task_store
+            .get(task_id)
+            .await?
+            .ok_or_else(|| A2AError::task_not_found(task_id))?;
+        if task.status.state.is_terminal() && self.terminal_task_policy == TerminalTaskPolicy::Reject {
+            let state = serde_json::to_value(&task.status.state)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default();
+            return Err(A2AError::invalid_params(&format!(
+                "Task {} is in terminal state: {}",
+                task.id, state
+            )));
+        }
+        Ok(Some(task))
+    }
+
+    /// The task a dry-run message would create or continue
+    async fn dry_run_task(&self, params: &MessageSendParams) -> Result<Task, A2AError> {
+        let message = &params.message;
+        let mut task = match self.existing_task(message).await? {
+            Some(mut task) => {
+                task.history.get_or_insert_with(Vec::new).push(message.clone());
+                task
+            }
+            None => Task {
+                id: message.task_id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+                context_id: message.context_id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+                status: TaskStatus::new(TaskState::Submitted),
+                artifacts: None,
+                history: Some(vec![message.clone()]),
+                state_transitions: None,
+                metadata: None,
+                kind: "task".to_string(),
+            },
+        };
+        task.status = TaskStatus::new(TaskState::Submitted);
+        task.metadata
+            .get_or_insert_with(Default::default)
+            .insert(DRY_RUN_METADATA_KEY.to_string(), serde_json::Value::Bool(true));
+        Ok(task)
+    }
+
+    /// Builds the working task for a message, continuing `existing` if given
+    async fn working_task(
+        &self,
+        task_manager: &TaskManager,
+        existing: Option<Task>,
+        task_id: &str,
+        context_id: &str,
+        message: &crate::a2a::core_types::Message,
+        context: Option<&ServerCallContext>,
+    ) -> Task {
+        let mut task = match existing {
+            Some(task) => {
+                let mut task = task_manager.update_with_message(message.clone(), task).await;
+                task.status = TaskStatus::new(TaskState::Working);
+                task
             }
+            None => Task {
+                id: task_id.to_string(),
+                context_id: context_id.to_string(),
+                status: TaskStatus::new(TaskState::Working),
+                artifacts: None,
+                history: Some(vec![message.clone()]),
+                state_transitions: None,
+                metadata: None,
+                kind: "task".to_string(),
+            },
+        };
+        if self.tenant_metadata {
+            let tenant = context.map(quota::tenant_for).unwrap_or_else(|| quota::ANONYMOUS_TENANT.to_string());
+            task.metadata
+                .get_or_insert_with(Default::default)
+                .entry(quota::TENANT_METADATA_KEY.to_string())
+                .or_insert(serde_json::Value::String(tenant));
         }
+        task
+    }
+
+    /// The unfiltered event stream of `tasks/resubscribe`
+    async fn resubscribe_stream(
+        &self,
+        params: TaskIdParams,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        // Subscribe before reading the task so no event is missed in between
+        let updates = self.event_bus.subscribe_task(&params.id);
+        if let Some(from_event_id) = &params.from_event_id {
+            return self.resume_from(&params.id, from_event_id, updates).await;
+        }
+        let task = self
+            .task_store
+            .get(&params.id)
+            .await?
+            .ok_or_else(|| A2AError::task_not_found(&params.id))?;
+
+        let is_terminal = task.status.state.is_terminal();
+        let initial = futures::stream::once(async move { Ok(StreamEvent::from(Event::Task(task))) });
+        if is_terminal {
+            return Ok(Box::pin(initial));
+        }
+
+        let resumable = self.event_store.is_some();
+        let updates = updates.map(move |bus_event| Ok(stream_event(bus_event, resumable)));
+        Ok(Box::pin(initial.chain(updates)))
+    }
+
+    /// Replays the retained events after `from_event_id`, then continues with
+    /// the live `updates`
+    async fn resume_from(
+        &self,
+        task_id: &str,
+        from_event_id: &str,
+        updates: BoxStream<'static, BusEvent>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        let event_store = self
+            .event_store
+            .as_ref()
+            .ok_or_else(|| A2AError::unsupported_operation("Resuming from an event id requires an event store"))?;
+        let after = parse_event_id(from_event_id)?;
+        let task = self
+            .task_store
+            .get(task_id)
+            .await?
+            .ok_or_else(|| A2AError::task_not_found(task_id))?;
+
+        // Wait until the store holds every event published so far; later
+        // events arrive on `updates`, which was subscribed before this point
+        self.event_bus.flush().await;
+        let missed = event_store.events_after(task_id, after).await?;
+        let replayed_up_to = missed.last().map_or(after, |event| event.sequence);
+        let replayed_final = task.status.state.is_terminal() || missed.iter().any(BusEvent::is_final);
+
+        let replay = futures::stream::iter(missed.into_iter().map(|bus_event| Ok(stream_event(bus_event, true))));
+        if replayed_final {
+            return Ok(Box::pin(replay));
+        }
+        let live = updates
+            .filter(move |bus_event| futures::future::ready(bus_event.sequence > replayed_up_to))
+            .map(|bus_event| Ok(stream_event(bus_event, true)));
+        Ok(Box::pin(replay.chain(live)))
+    }
+
+    fn task_manager(
+        &self,
+        task_id: &str,
+        context_id: &str,
+        initial_message: Option<crate::a2a::core_types::Message>,
+    ) -> Result<TaskManager, A2AError> {
+        let task_manager = TaskManager::new(
+            Some(task_id.to_string()),
+            Some(context_id.to_string()),
+            self.task_store.clone(),
+            initial_message,
+            None,
+        )?
+        .with_event_bus(self.event_bus.clone());
+        Ok(match self.state_transition_history {
+            Some(history) => task_manager.with_state_transition_history(history),
+            None => task_manager,
+        })
     }
 }
 
@@ -53,7 +599,22 @@ impl RequestHandler for DefaultRequestHandler {
         params: TaskQueryParams,
         _context: Option<&ServerCallContext>,
     ) -> Result<Option<Task>, A2AError> {
-        self.task_store.get(&params.id).await
+        // Clients poll this; a replica lagging slightly behind is acceptable
+        let task = if params.history_length == Some(0) {
+            // Only the status is wanted, so large histories are not read
+            self.task_store.get_meta(&params.id, ReadConsistency::Eventual).await?
+        } else {
+            self.task_store.get_with_consistency(&params.id, ReadConsistency::Eventual).await?
+        };
+        let timeline = self.timeline.as_ref().filter(|timeline| timeline.in_metadata());
+        Ok(task.map(|mut task| {
+            if let Some(entries) = timeline.and_then(|timeline| timeline.get(&task.id)) {
+                task.metadata
+                    .get_or_insert_with(Default::default)
+                    .insert(TIMELINE_METADATA_KEY.to_string(), serde_json::to_value(entries).unwrap_or_default());
+            }
+            task
+        }))
     }
 
     async fn on_cancel_task(
@@ -62,14 +623,20 @@ impl RequestHandler for DefaultRequestHandler {
         _context: Option<&ServerCallContext>,
     ) -> Result<Option<Task>, A2AError> {
         let task = self.task_store.get(&params.id).await?;
-        if let Some(mut task) = task {
-            task.status.state = TaskState::Canceled;
-            task.status.timestamp = Some(chrono::Utc::now().to_string());
-            self.task_store.save(task.clone()).await?;
-            
-            // Trigger push notification on cancellation
-            self.send_push_notification_if_needed(&task).await;
-            
+        if let Some(task) = task {
+            if task.status.state.is_terminal() {
+                return Err(A2AError::task_not_cancelable("the task is in a terminal state"));
+            }
+            let mut task_manager = self.task_manager(&task.id, &task.context_id, None)?;
+            let task = task_manager
+                .save_task_event(TaskEvent::StatusUpdate(TaskStatusUpdateEvent::new(
+                    task.id.clone(),
+                    task.context_id.clone(),
+                    TaskStatus::new(TaskState::Canceled),
+                    true,
+                )))
+                .await?;
+
             Ok(Some(task))
         } else {
             Ok(None)
@@ -79,18 +646,16 @@ impl RequestHandler for DefaultRequestHandler {
     async fn on_message_send(
         &self,
         params: MessageSendParams,
-        _context: Option<&ServerCallContext>,
+        context: Option<&ServerCallContext>,
     ) -> Result<MessageSendResult, A2AError> {
+        if params.is_dry_run() {
+            return Ok(MessageSendResult::Task(self.dry_run_task(&params).await?));
+        }
+        let (existing, context_id, permits) = self.admit(&params.message, context).await?;
         let task_id = params.message.task_id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-        let context_id = params.message.context_id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        self.record(&task_id, TimelinePhase::Received);
 
-        let mut task_manager = TaskManager::new(
-            Some(task_id.clone()),
-            Some(context_id.clone()),
-            self.task_store.clone(),
-            Some(params.message.clone()),
-            None,
-        )?;
+        let mut task_manager = self.task_manager(&task_id, &context_id, Some(params.message.clone()))?;
 
         // Handle push config if provided in params
         if let Some(ref config_store) = self.push_config_store {
@@ -100,18 +665,26 @@ impl RequestHandler for DefaultRequestHandler {
         }
 
         // Mock execution: just return a task in Working state
-        let task = task_manager.save_task_event(crate::a2a::server::tasks::TaskEvent::Task(Task {
-            id: task_id,
-            context_id,
-            status: TaskStatus::new(TaskState::Working),
-            artifacts: None,
-            history: Some(vec![params.message.clone()]),
-            metadata: None,
-            kind: "task".to_string(),
-        })).await?;
-
-        // Trigger push notification
-        self.send_push_notification_if_needed(&task).await;
+        let task = self
+            .working_task(&task_manager, existing, &task_id, &context_id, &params.message, context)
+            .await;
+        let _worker = match &self.worker_pool {
+            Some(pool) => match (pool.try_acquire(), pool.when_busy()) {
+                (Some(worker), _) => Some(worker),
+                (None, WhenBusy::Defer) => {
+                    let mut submitted = task;
+                    submitted.status = TaskStatus::new(TaskState::Submitted);
+                    let submitted = task_manager.save_task_event(TaskEvent::Task(submitted)).await?;
+                    self.record(&task_id, TimelinePhase::Queued);
+                    spawn_deferred_start(pool.clone(), task_manager, task_id, context_id, permits, self.timeline.clone());
+                    return Ok(MessageSendResult::Task(submitted));
+                }
+                (None, WhenBusy::Wait { .. }) => Some(pool.acquire().await?),
+            },
+            None => None,
+        };
+        self.record(&task_id, TimelinePhase::Started);
+        let task = task_manager.save_task_event(TaskEvent::Task(task)).await?;
 
         Ok(MessageSendResult::Task(task))
     }
@@ -119,10 +692,24 @@ impl RequestHandler for DefaultRequestHandler {
     async fn on_message_send_stream(
         &self,
         params: MessageSendParams,
-        _context: Option<&ServerCallContext>,
+        context: Option<&ServerCallContext>,
     ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        let stream = self.on_message_send_stream_resumable(params, context).await?;
+        Ok(Box::pin(stream.map(|event| event.map(|event| event.event))))
+    }
+
+    async fn on_message_send_stream_resumable(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        if params.is_dry_run() {
+            let task = self.dry_run_task(&params).await?;
+            return Ok(Box::pin(futures::stream::iter([Ok(StreamEvent::from(Event::Task(task)))])));
+        }
+        let (existing, context_id, permits) = self.admit(&params.message, context).await?;
         let task_id = params.message.task_id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-        let context_id = params.message.context_id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        self.record(&task_id, TimelinePhase::Received);
 
         // Handle push config
         if let Some(ref config_store) = self.push_config_store {
@@ -131,20 +718,19 @@ impl RequestHandler for DefaultRequestHandler {
             }
         }
 
-        let task = Task {
-            id: task_id.clone(),
-            context_id: context_id.clone(),
-            status: TaskStatus::new(TaskState::Working),
-            artifacts: None,
-            history: Some(vec![params.message.clone()]),
-            metadata: None,
-            kind: "task".to_string(),
+        // Mock execution: every event is persisted through the TaskManager, which
+        // publishes it on the event bus
+        let task_manager = self.task_manager(&task_id, &context_id, Some(params.message.clone()))?;
+        let task = self
+            .working_task(&task_manager, existing, &task_id, &context_id, &params.message, context)
+            .await;
+        let task_manager = Arc::new(Mutex::new(task_manager));
+        let resumable = self.event_store.is_some();
+        let worker = match &self.worker_pool {
+            Some(pool) => Some(pool.acquire().await?),
+            None => None,
         };
-
-        // In a real implementation, we would wrap the stream to trigger push notifications
-        // on each event. For now, we'll just return a mock stream.
-        let sender = self.push_sender.clone();
-        let task_clone = task.clone();
+        self.record(&task_id, TimelinePhase::Started);
 
         let stream = futures::stream::iter(vec![
             Ok(Event::Task(task.clone())),
@@ -154,22 +740,54 @@ impl RequestHandler for DefaultRequestHandler {
                 TaskStatus::new(TaskState::Completed),
                 true,
             ))),
-        ]).then(move |res| {
-            let sender = sender.clone();
-            let task = task_clone.clone();
+        ]).then(move |res: Result<Event, A2AError>| {
+            let task_manager = task_manager.clone();
             async move {
-                if let Ok(_) = res {
-                    if let Some(ref s) = sender {
-                        let _ = s.send_notification(&task).await;
-                    }
-                }
-                res
+                let event = res?;
+                let mut task_manager = task_manager.lock().await;
+                let before = task_manager.last_sequence();
+                let event = Event::from(task_manager.process_event(&event.into()).await?);
+                // Only events published on the bus and retained in the event
+                // store can be resumed after
+                let published = task_manager
+                    .last_sequence()
+                    .filter(|sequence| resumable && Some(*sequence) != before);
+                Ok(StreamEvent::new(event, published.map(event_id)))
             }
         });
+        // The permits and the worker are held until the stream is dropped
+        let stream = stream.map(move |event| {
+            let _ = (&permits, &worker);
+            event
+        });
 
         Ok(Box::pin(stream))
     }
 
+    async fn on_resubscribe_to_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        let stream = self.on_resubscribe_to_task_resumable(params, context).await?;
+        Ok(Box::pin(stream.map(|event| event.map(|event| event.event))))
+    }
+
+    async fn on_resubscribe_to_task_resumable(
+        &self,
+        params: TaskIdParams,
+        _context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        let filter = params.filter.as_ref().map(EventFilter::new).transpose()?;
+        let stream = self.resubscribe_stream(params).await?;
+        Ok(match filter {
+            Some(filter) => Box::pin(stream.filter(move |event| {
+                futures::future::ready(event.as_ref().map_or(true, |event| filter.matches(&event.event)))
+            })),
+            None => stream,
+        })
+    }
+
     async fn on_set_task_push_notification_config(
         &self,
         params: TaskPushNotificationConfig,
@@ -8,7 +8,11 @@
 //! - message/stream + tasks/resubscribe require streaming capability
 //! - set push_notification requires push_notifications capability
 //! - get push_notification DOES NOT gate on push capability
-//! - tasks/get + tasks/cancel return Option<Task>; transport maps None -> TaskNotFound
+//! - tasks/get + tasks/cancel map None -> TaskNotFoundError, and tasks/get
+//!   trims the history to `history_length`
+//!
+//! The checks themselves live in `protocol_core`, shared with the other
+//! transports.
 
 use std::pin::Pin;
 use std::sync::Arc;
@@ -18,6 +22,7 @@ use futures::Stream;
 use crate::a2a::error::A2AError;
 use crate::a2a::models::*;
 use crate::a2a::server::context::ServerCallContext;
+use crate::a2a::server::request_handlers::protocol_core;
 use crate::a2a::server::request_handlers::{
     Event, MessageSendResult, RequestHandler, TaskPushNotificationConfigQueryParams,
 };
@@ -58,7 +63,7 @@ impl GRPCHandler {
         params: MessageSendParams,
         context: &ServerCallContext,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<Event, A2AError>> + Send>>, A2AError> {
-        self.ensure_streaming_supported()?;
+        protocol_core::ensure_streaming_supported(&self.agent_card)?;
 
         self.request_handler
             .on_message_send_stream(params, Some(context))
@@ -70,10 +75,12 @@ impl GRPCHandler {
         &self,
         params: TaskQueryParams,
         context: &ServerCallContext,
-    ) -> Result<Option<Task>, A2AError> {
-        self.request_handler
-            .on_get_task(params, Some(context))
-            .await
+    ) -> Result<Task, A2AError> {
+        let task = self
+            .request_handler
+            .on_get_task(params.clone(), Some(context))
+            .await?;
+        protocol_core::task_query_result(task, &params)
     }
 
     /// Handle tasks/cancel
@@ -81,10 +88,13 @@ impl GRPCHandler {
         &self,
         params: TaskIdParams,
         context: &ServerCallContext,
-    ) -> Result<Option<Task>, A2AError> {
-        self.request_handler
+    ) -> Result<Task, A2AError> {
+        let task_id = params.id.clone();
+        let task = self
+            .request_handler
             .on_cancel_task(params, Some(context))
-            .await
+            .await?;
+        protocol_core::task_or_not_found(task, &task_id)
     }
 
     /// Handle tasks/pushNotificationConfig/set with capability check
@@ -93,7 +103,7 @@ impl GRPCHandler {
         params: TaskPushNotificationConfig,
         context: &ServerCallContext,
     ) -> Result<TaskPushNotificationConfig, A2AError> {
-        self.ensure_push_supported()?;
+        protocol_core::ensure_push_supported(&self.agent_card)?;
 
         self.request_handler
             .on_set_task_push_notification_config(params, Some(context))
@@ -119,7 +129,7 @@ impl GRPCHandler {
         params: TaskIdParams,
         context: &ServerCallContext,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<Event, A2AError>> + Send>>, A2AError> {
-        self.ensure_streaming_supported()?;
+        protocol_core::ensure_streaming_supported(&self.agent_card)?;
 
         self.request_handler
             .on_resubscribe_to_task(params, Some(context))
@@ -131,16 +141,7 @@ impl GRPCHandler {
         &self,
         _context: &ServerCallContext,
     ) -> Result<AgentCard, A2AError> {
-        if !self
-            .agent_card
-            .supports_authenticated_extended_card
-            .unwrap_or(false)
-        {
-            return Err(A2AError::unsupported_operation(
-                "Authenticated extended card is not supported by this agent",
-            ));
-        }
-
+        protocol_core::ensure_extended_card_supported(&self.agent_card)?;
         Ok(self.agent_card.clone())
     }
 
@@ -151,25 +152,4 @@ impl GRPCHandler {
     ) -> Result<AgentCard, A2AError> {
         Ok(self.agent_card.clone())
     }
-
-    // -------------------------
-    // Capability helpers
-    // -------------------------
-
-    fn ensure_streaming_supported(&self) -> Result<(), A2AError> {
-        if !self.agent_card.capabilities.streaming.unwrap_or(false) {
-            // Match Python validate message as closely as possible
-            return Err(A2AError::unsupported_operation(
-                "Streaming is not supported by the agent",
-            ));
-        }
-        Ok(())
-    }
-
-    fn ensure_push_supported(&self) -> Result<(), A2AError> {
-        if !self.agent_card.capabilities.push_notifications.unwrap_or(false) {
-            return Err(A2AError::push_notification_not_supported());
-        }
-        Ok(())
-    }
 }
@@ -4,8 +4,12 @@
 //! to the appropriate request handler methods and formats responses.
 
 use crate::a2a::models::*;
+use crate::a2a::server::agent_card_handle::AgentCardHandle;
 use crate::a2a::server::context::ServerCallContext;
-use crate::a2a::server::request_handlers::RequestHandler;
+use crate::a2a::server::quota::tenant_for;
+use crate::a2a::server::request_handlers::protocol_core;
+use crate::a2a::server::request_handlers::{RequestHandler, StreamEvent};
+use crate::a2a::server::tasks::{TaskSearchParams, TaskStore};
 use crate::a2a::jsonrpc::*;
 use serde_json::Value;
 use std::sync::Arc;
@@ -16,12 +20,18 @@ use std::pin::Pin;
 /// 
 /// Maps incoming JSON-RPC requests to the appropriate request handler methods
 /// and formats responses according to the A2A specification.
+#[derive(Clone)]
 pub struct JSONRPCHandler {
-    agent_card: AgentCard,
+    agent_card: AgentCardHandle,
     #[allow(dead_code)]
     request_handler: Arc<dyn RequestHandler>,
+    strict_protocol: bool,
+    task_search: Option<Arc<dyn TaskStore>>,
 }
 
+/// Top-level members of a JSON-RPC 2.0 request
+const REQUEST_MEMBERS: &[&str] = &["jsonrpc", "method", "params", "id"];
+
 impl JSONRPCHandler {
     /// Create a new JSON-RPC handler
     /// 
@@ -31,13 +41,50 @@ impl JSONRPCHandler {
     pub fn new(
         agent_card: AgentCard,
         request_handler: Arc<dyn RequestHandler>,
+    ) -> Self {
+        Self::with_card_handle(AgentCardHandle::new(agent_card), request_handler)
+    }
+
+    /// Create a JSON-RPC handler reading the agent card from a shared handle
+    ///
+    /// Capability checks always use the card currently held by the handle.
+    pub fn with_card_handle(
+        agent_card: AgentCardHandle,
+        request_handler: Arc<dyn RequestHandler>,
     ) -> Self {
         Self {
             agent_card,
             request_handler,
+            strict_protocol: false,
+            task_search: None,
         }
     }
 
+    /// Enforce the JSON-RPC 2.0 request object exactly
+    ///
+    /// Strict parsing rejects requests with members other than `jsonrpc`,
+    /// `method`, `params` and `id`, with an `id` that is not a string, an
+    /// integer or null, or with `params` that are neither an object nor an
+    /// array. Lenient parsing, the default, ignores extra members and treats
+    /// such ids as absent.
+    pub fn with_strict_protocol(mut self, strict: bool) -> Self {
+        self.strict_protocol = strict;
+        self
+    }
+
+    /// Serve the `tasks/search` extension method from `store`
+    ///
+    /// The method takes the words to look for as `query`, plus the members
+    /// of a `TaskSearchFilter`, and returns the matching tasks; see
+    /// `TaskStore::search_tasks`. Callers only find the tasks of their own
+    /// tenant, so the request handler must record it with
+    /// `DefaultRequestHandler::with_tenant_metadata`. Without a store the
+    /// method is not found.
+    pub fn with_task_search(mut self, store: Arc<dyn TaskStore>) -> Self {
+        self.task_search = Some(store);
+        self
+    }
+
     /// Convert JSONRPCId to serde_json::Value
     fn id_to_value(id: &Option<crate::a2a::jsonrpc::JSONRPCId>) -> Value {
         match id {
@@ -48,6 +95,31 @@ impl JSONRPCHandler {
         }
     }
 
+    /// Convert a request handler error, keeping its A2A error code and data
+    fn handler_error(error: crate::a2a::error::A2AError) -> JSONRPCError {
+        protocol_core::jsonrpc_error(&error)
+    }
+
+    /// Convert a params deserialization error, naming the offending field
+    fn params_error(error: serde_json::Error) -> JSONRPCError {
+        Self::handler_error(crate::a2a::error::A2AError::invalid_params(&format!("Invalid params: {}", error)))
+    }
+
+    /// Methods routed by [`JSONRPCHandler::handle_request`]
+    pub const METHODS: &'static [&'static str] = &[
+        "message/send",
+        "message/stream",
+        "tasks/get",
+        "tasks/cancel",
+        "tasks/pushNotificationConfig/set",
+        "tasks/pushNotificationConfig/get",
+        "tasks/pushNotificationConfig/list",
+        "tasks/pushNotificationConfig/delete",
+        "tasks/resubscribe",
+        "agent/authenticatedExtendedCard",
+        "tasks/search",
+    ];
+
     /// Handle a JSON-RPC request
     /// 
     /// # Arguments
@@ -76,6 +148,7 @@ impl JSONRPCHandler {
             "tasks/pushNotificationConfig/delete" => self.handle_delete_push_notification_config(jsonrpc_request, context).await,
             "tasks/resubscribe" => self.handle_resubscribe_task(jsonrpc_request, context).await,
             "agent/authenticatedExtendedCard" => self.handle_get_authenticated_extended_card(jsonrpc_request, context).await,
+            "tasks/search" => self.handle_search_tasks(jsonrpc_request, context).await,
             _ => Err(JSONRPCError::new(
                 standard_error_codes::METHOD_NOT_FOUND,
                 format!("Method '{}' not found", jsonrpc_request.method),
@@ -85,6 +158,10 @@ impl JSONRPCHandler {
 
     /// Parse a JSON-RPC request
     pub fn parse_request(&self, request: Value) -> Result<JSONRPCRequest, JSONRPCError> {
+        if self.strict_protocol {
+            Self::check_request_object(&request)?;
+        }
+
         // Check for required JSON-RPC 2.0 fields
         if !request.get("jsonrpc").and_then(|v| v.as_str()).map(|s| s == "2.0").unwrap_or(false) {
             return Err(JSONRPCError::new(
@@ -122,6 +199,49 @@ impl JSONRPCHandler {
         })
     }
 
+    /// Check the members of a request object for strict parsing
+    ///
+    /// Each violation is an invalid request naming the `member` at fault in
+    /// its data.
+    fn check_request_object(request: &Value) -> Result<(), JSONRPCError> {
+        let invalid = |member: &str, message: String| {
+            JSONRPCError::new(standard_error_codes::INVALID_REQUEST, message)
+                .with_data(serde_json::json!({ "member": member }))
+        };
+        let Some(members) = request.as_object() else {
+            return Err(JSONRPCError::new(
+                standard_error_codes::INVALID_REQUEST,
+                "Request must be a JSON object".to_string(),
+            ));
+        };
+        if let Some(name) = members.keys().find(|name| !REQUEST_MEMBERS.contains(&name.as_str())) {
+            return Err(invalid(name, format!("Unknown request member '{}'", name)));
+        }
+        match members.get("jsonrpc") {
+            Some(Value::String(version)) if version == "2.0" => {}
+            Some(Value::String(version)) => {
+                return Err(invalid("jsonrpc", format!("Unsupported 'jsonrpc' version '{}', expected '2.0'", version)));
+            }
+            Some(_) => return Err(invalid("jsonrpc", "'jsonrpc' must be the string '2.0'".to_string())),
+            None => return Err(invalid("jsonrpc", "Missing 'jsonrpc' version field".to_string())),
+        }
+        match members.get("method") {
+            Some(Value::String(_)) => {}
+            Some(_) => return Err(invalid("method", "'method' must be a string".to_string())),
+            None => return Err(invalid("method", "Missing 'method' field".to_string())),
+        }
+        match members.get("params") {
+            None | Some(Value::Object(_)) | Some(Value::Array(_)) => {}
+            Some(_) => return Err(invalid("params", "'params' must be an object or an array".to_string())),
+        }
+        match members.get("id") {
+            None | Some(Value::String(_)) | Some(Value::Null) => Ok(()),
+            Some(Value::Number(n)) if n.is_i64() => Ok(()),
+            Some(Value::Number(_)) => Err(invalid("id", "'id' must be an integer when it is a number".to_string())),
+            Some(_) => Err(invalid("id", "'id' must be a string, a number or null".to_string())),
+        }
+    }
+
     /// Handle message/send requests
     async fn handle_message_send(
         &self,
@@ -138,23 +258,13 @@ impl JSONRPCHandler {
 
         // Deserialize MessageSendParams
         let message_send_params: MessageSendParams = serde_json::from_value(params.clone())
-            .map_err(|e| {
-                JSONRPCError::new(
-                    standard_error_codes::INVALID_PARAMS,
-                    format!("Invalid params: {}", e),
-                )
-            })?;
+            .map_err(Self::params_error)?;
 
         // Call the request handler
         let result = self.request_handler
             .on_message_send(message_send_params, Some(context))
             .await
-            .map_err(|e| {
-                JSONRPCError::new(
-                    standard_error_codes::INTERNAL_ERROR,
-                    format!("Handler error: {}", e),
-                )
-            })?;
+            .map_err(Self::handler_error)?;
 
         // Convert the result to the expected format
         let result_value = match result {
@@ -190,13 +300,7 @@ impl JSONRPCHandler {
         request: JSONRPCRequest,
         context: &ServerCallContext,
     ) -> Result<Value, JSONRPCError> {
-        // Check if streaming is supported
-        if !self.agent_card.capabilities.streaming.unwrap_or(false) {
-            return Err(JSONRPCError::new(
-                standard_error_codes::INVALID_REQUEST,
-                "Streaming is not supported by this agent".to_string(),
-            ));
-        }
+        self.ensure_streaming_supported()?;
 
         // Parse the params
         let params = request.params.as_ref().ok_or_else(|| {
@@ -208,23 +312,13 @@ impl JSONRPCHandler {
 
         // Deserialize MessageSendParams
         let message_send_params: MessageSendParams = serde_json::from_value(params.clone())
-            .map_err(|e| {
-                JSONRPCError::new(
-                    standard_error_codes::INVALID_PARAMS,
-                    format!("Invalid params: {}", e),
-                )
-            })?;
+            .map_err(Self::params_error)?;
 
         // Call the request handler's streaming method
         let event_stream = self.request_handler
             .on_message_send_stream(message_send_params, Some(context))
             .await
-            .map_err(|e| {
-                JSONRPCError::new(
-                    standard_error_codes::INTERNAL_ERROR,
-                    format!("Handler error: {}", e),
-                )
-            })?;
+            .map_err(Self::handler_error)?;
 
         // Convert the event stream to SSE format and return as JSON-RPC response
         // This is a simplified implementation that converts the stream to a JSON array
@@ -250,13 +344,7 @@ impl JSONRPCHandler {
         request: JSONRPCRequest,
         context: &ServerCallContext,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<String, JSONRPCError>> + Send>>, JSONRPCError> {
-        // Check if streaming is supported
-        if !self.agent_card.capabilities.streaming.unwrap_or(false) {
-            return Err(JSONRPCError::new(
-                standard_error_codes::INVALID_REQUEST,
-                "Streaming is not supported by this agent".to_string(),
-            ));
-        }
+        self.ensure_streaming_supported()?;
 
         // Parse the params
         let params = request.params.as_ref().ok_or_else(|| {
@@ -268,35 +356,44 @@ impl JSONRPCHandler {
 
         // Deserialize MessageSendParams
         let message_send_params: MessageSendParams = serde_json::from_value(params.clone())
-            .map_err(|e| {
-                JSONRPCError::new(
-                    standard_error_codes::INVALID_PARAMS,
-                    format!("Invalid params: {}", e),
-                )
-            })?;
+            .map_err(Self::params_error)?;
 
         // Call the request handler's streaming method
         let event_stream = self.request_handler
-            .on_message_send_stream(message_send_params, Some(context))
+            .on_message_send_stream_resumable(message_send_params, Some(context))
             .await
-            .map_err(|e| {
-                JSONRPCError::new(
-                    standard_error_codes::INTERNAL_ERROR,
-                    format!("Handler error: {}", e),
-                )
-            })?;
-
-        // Get the request ID as serde_json::Value
-        let request_id = request.id.as_ref().map(|id| {
-            match id {
-                crate::a2a::jsonrpc::JSONRPCId::String(s) => Value::String(s.clone()),
-                crate::a2a::jsonrpc::JSONRPCId::Number(n) => Value::Number(serde_json::Number::from(*n)),
-                crate::a2a::jsonrpc::JSONRPCId::Null => Value::Null,
-            }
-        });
+            .map_err(Self::handler_error)?;
 
         // Convert the event stream to SSE format
-        Ok(Box::pin(self.events_to_sse_stream(event_stream, request_id)))
+        Ok(Box::pin(self.events_to_sse_stream(event_stream, request.id.as_ref().map(|_| Self::id_to_value(&request.id)))))
+    }
+
+    /// Handle tasks/resubscribe requests with SSE streaming
+    ///
+    /// Each event carries its resumption id in the SSE `id` field; passing it
+    /// back as `from_event_id` resumes the stream after that event.
+    pub async fn handle_resubscribe_sse(
+        &self,
+        request: JSONRPCRequest,
+        context: &ServerCallContext,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String, JSONRPCError>> + Send>>, JSONRPCError> {
+        self.ensure_streaming_supported()?;
+
+        let params = request.params.as_ref().ok_or_else(|| {
+            JSONRPCError::new(
+                standard_error_codes::INVALID_PARAMS,
+                "Missing params field".to_string(),
+            )
+        })?;
+        let task_id_params: TaskIdParams = serde_json::from_value(params.clone())
+            .map_err(Self::params_error)?;
+
+        let event_stream = self.request_handler
+            .on_resubscribe_to_task_resumable(task_id_params, Some(context))
+            .await
+            .map_err(Self::handler_error)?;
+
+        Ok(Box::pin(self.events_to_sse_stream(event_stream, request.id.as_ref().map(|_| Self::id_to_value(&request.id)))))
     }
 
     /// Collect events from a stream into a JSON array
@@ -361,12 +458,12 @@ impl JSONRPCHandler {
     /// Convert events to SSE (Server-Sent Events) format stream
     fn events_to_sse_stream(
         &self,
-        event_stream: Pin<Box<dyn Stream<Item = Result<crate::a2a::server::request_handlers::request_handler::Event, crate::a2a::error::A2AError>> + Send>>,
+        event_stream: Pin<Box<dyn Stream<Item = Result<StreamEvent, crate::a2a::error::A2AError>> + Send>>,
         request_id: Option<serde_json::Value>,
     ) -> impl Stream<Item = Result<String, crate::a2a::jsonrpc::JSONRPCError>> {
         event_stream.map(move |event_result| {
             match event_result {
-                Ok(event) => {
+                Ok(StreamEvent { event, event_id }) => {
                     // Convert the event to SendStreamingMessageResult
                     let result = match event {
                         crate::a2a::server::request_handlers::request_handler::Event::TaskStatusUpdate(update) => {
@@ -391,8 +488,11 @@ impl JSONRPCHandler {
                     
                     match serde_json::to_value(&response) {
                         Ok(json) => {
-                            // Format as SSE: data: {json}\n\n
-                            Ok(format!("data: {}\n\n", json.to_string()))
+                            // Format as SSE: [id: {event_id}\n]data: {json}\n\n
+                            match event_id {
+                                Some(event_id) => Ok(format!("id: {}\ndata: {}\n\n", event_id, json)),
+                                None => Ok(format!("data: {}\n\n", json)),
+                            }
                         }
                         Err(e) => Err(crate::a2a::jsonrpc::JSONRPCError::new(
                             standard_error_codes::INTERNAL_ERROR,
@@ -442,13 +542,7 @@ impl JSONRPCHandler {
         request: JSONRPCRequest,
         _context: &ServerCallContext,
     ) -> Result<Value, JSONRPCError> {
-        // Check if push notifications are supported
-        if !self.agent_card.capabilities.push_notifications.unwrap_or(false) {
-            return Err(JSONRPCError::new(
-                standard_error_codes::INVALID_REQUEST,
-                "Push notifications are not supported by this agent".to_string(),
-            ));
-        }
+        self.ensure_push_supported()?;
 
         let response = serde_json::json!({
             "jsonrpc": "2.0",
@@ -506,6 +600,8 @@ impl JSONRPCHandler {
         request: JSONRPCRequest,
         _context: &ServerCallContext,
     ) -> Result<Value, JSONRPCError> {
+        self.ensure_streaming_supported()?;
+
         let response = serde_json::json!({
             "jsonrpc": "2.0",
             "result": "tasks/resubscribe handled",
@@ -521,20 +617,53 @@ impl JSONRPCHandler {
         _context: &ServerCallContext,
     ) -> Result<Value, JSONRPCError> {
         // Check if authenticated extended card is supported
-        if !self.agent_card.supports_authenticated_extended_card.unwrap_or(false) {
+        protocol_core::ensure_extended_card_supported(&self.agent_card.current())
+            .map_err(Self::handler_error)?;
+
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "result": "agent/authenticatedExtendedCard handled",
+            "id": Self::id_to_value(&request.id)
+        });
+        Ok(response)
+    }
+
+    /// Handle tasks/search requests
+    async fn handle_search_tasks(
+        &self,
+        request: JSONRPCRequest,
+        context: &ServerCallContext,
+    ) -> Result<Value, JSONRPCError> {
+        let Some(store) = &self.task_search else {
             return Err(JSONRPCError::new(
-                standard_error_codes::INVALID_REQUEST,
-                "Authenticated extended card is not supported by this agent".to_string(),
+                standard_error_codes::METHOD_NOT_FOUND,
+                "Method 'tasks/search' not found".to_string(),
             ));
-        }
+        };
+        let mut params: TaskSearchParams = serde_json::from_value(request.params.clone().unwrap_or(Value::Null))
+            .map_err(Self::params_error)?;
+        params.filter.tenant = Some(tenant_for(context));
+
+        let tasks = store
+            .search_tasks(&params.query, &params.filter)
+            .await
+            .map_err(Self::handler_error)?;
 
         let response = serde_json::json!({
             "jsonrpc": "2.0",
-            "result": "agent/authenticatedExtendedCard handled",
+            "result": tasks,
             "id": Self::id_to_value(&request.id)
         });
         Ok(response)
     }
+
+    fn ensure_streaming_supported(&self) -> Result<(), JSONRPCError> {
+        protocol_core::ensure_streaming_supported(&self.agent_card.current()).map_err(Self::handler_error)
+    }
+
+    fn ensure_push_supported(&self) -> Result<(), JSONRPCError> {
+        protocol_core::ensure_push_supported(&self.agent_card.current()).map_err(Self::handler_error)
+    }
 }
 
 #[cfg(test)]
@@ -584,6 +713,32 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_strict_protocol_rejects_non_conforming_requests() {
+        let lenient = create_test_handler();
+        let strict = create_test_handler().with_strict_protocol(true);
+        let cases = [
+            (serde_json::json!({"jsonrpc": "2.0", "method": "test", "id": 1, "extra": true}), "extra"),
+            (serde_json::json!({"jsonrpc": 2.0, "method": "test", "id": 1}), "jsonrpc"),
+            (serde_json::json!({"jsonrpc": "2.0", "method": "test", "id": {"n": 1}}), "id"),
+            (serde_json::json!({"jsonrpc": "2.0", "method": "test", "id": 1.5}), "id"),
+            (serde_json::json!({"jsonrpc": "2.0", "method": "test", "params": "text", "id": 1}), "params"),
+        ];
+        for (request, member) in cases {
+            let error = strict.parse_request(request.clone()).unwrap_err();
+            assert_eq!(error.code, standard_error_codes::INVALID_REQUEST);
+            assert_eq!(error.data.unwrap()["member"], member);
+            if member != "jsonrpc" {
+                assert!(lenient.parse_request(request).is_ok());
+            }
+        }
+
+        let request = serde_json::json!({"jsonrpc": "2.0", "method": "test", "params": [], "id": "a"});
+        assert!(strict.parse_request(request).is_ok());
+        let notification = serde_json::json!({"jsonrpc": "2.0", "method": "test"});
+        assert!(strict.parse_request(notification).is_ok());
+    }
+
     #[tokio::test]
     async fn test_handle_unknown_method() {
         let handler = create_test_handler();
@@ -723,10 +878,71 @@ mod tests {
         assert!(result.is_err());
 
         let error = result.unwrap_err();
-        assert_eq!(error.code, -32600); // INVALID_REQUEST
+        assert_eq!(error.code, -32004); // UnsupportedOperationError
         assert!(error.message.contains("Streaming is not supported"));
     }
 
+    #[tokio::test]
+    async fn test_capability_gating_returns_typed_errors() {
+        // Default capabilities: no streaming, no push notifications
+        let handler = create_test_handler();
+        let context = ServerCallContext::new();
+
+        let resubscribe = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "tasks/resubscribe",
+            "params": {"id": "task-1"},
+            "id": 1
+        });
+        let error = handler.handle_request(resubscribe, &context).await.unwrap_err();
+        assert_eq!(error.code, -32004); // UnsupportedOperationError
+
+        let set_push = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "tasks/pushNotificationConfig/set",
+            "params": {"taskId": "task-1", "pushNotificationConfig": {"url": "https://example.com/hook"}},
+            "id": 2
+        });
+        let error = handler.handle_request(set_push, &context).await.unwrap_err();
+        assert_eq!(error.code, -32003); // PushNotificationNotSupportedError
+    }
+
+    #[tokio::test]
+    async fn test_task_search_extension_method() {
+        use crate::a2a::server::quota::{ANONYMOUS_TENANT, TENANT_METADATA_KEY};
+        use crate::a2a::server::tasks::InMemoryTaskStore;
+        use crate::{Message, Part, Role, TaskState, TaskStatus};
+
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "tasks/search",
+            "params": {"query": "lost parcel", "contextId": "ctx-1"},
+            "id": 7
+        });
+        let context = ServerCallContext::new();
+        let error = create_test_handler().handle_request(request.clone(), &context).await.unwrap_err();
+        assert_eq!(error.code, standard_error_codes::METHOD_NOT_FOUND);
+
+        let store = Arc::new(InMemoryTaskStore::new());
+        for (id, context_id, tenant) in [("task-1", "ctx-1", ANONYMOUS_TENANT), ("task-2", "ctx-2", ANONYMOUS_TENANT), ("task-3", "ctx-1", "alice")] {
+            let message = Message::new(Role::User, vec![Part::text("My parcel got lost".to_string())]);
+            let mut task = Task::new(context_id.to_string(), TaskStatus::new(TaskState::Working))
+                .with_task_id(id.to_string())
+                .with_history(vec![message]);
+            task.metadata = Some(std::collections::HashMap::from([(TENANT_METADATA_KEY.to_string(), serde_json::json!(tenant))]));
+            store.save(task).await.unwrap();
+        }
+        let handler = create_test_handler().with_task_search(store);
+        let response = handler.handle_request(request, &context).await.unwrap();
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["result"].as_array().unwrap().len(), 1);
+        assert_eq!(response["result"][0]["id"], "task-1");
+
+        let missing_query = serde_json::json!({"jsonrpc": "2.0", "method": "tasks/search", "params": {}, "id": 8});
+        let error = handler.handle_request(missing_query, &context).await.unwrap_err();
+        assert_eq!(error.code, standard_error_codes::INVALID_PARAMS);
+    }
+
     fn create_test_handler() -> JSONRPCHandler {
         let agent_card = AgentCard::new(
             "Test Agent".to_string(),
@@ -4,10 +4,21 @@
 //! matching the functionality provided in a2a-python/src/a2a/server/request_handlers/
 
 pub mod request_handler;
+pub mod capabilities;
+pub mod protocol_core;
 pub mod jsonrpc_handler;
+pub mod grpc_handler;
+pub mod rest_handler;
 pub mod default_request_handler;
+pub mod concurrency;
+pub mod poll_hints;
 
 // Re-export main types for convenience
 pub use request_handler::*;
+pub use capabilities::{MessageHandler, PushConfigHandler, StreamingHandler, TaskQueryHandler};
 pub use jsonrpc_handler::*;
+pub use grpc_handler::GRPCHandler;
+pub use rest_handler::{RestErrorResponse, RestHandler};
 pub use default_request_handler::*;
+pub use concurrency::{ConcurrencyLimits, OverLimit, WhenBusy, WorkerPool};
+pub use poll_hints::{PollHintRequestHandler, PollHints};
@@ -0,0 +1,231 @@
+//! Poll interval hints in task responses
+//!
+//! Clients that poll `tasks/get` cannot tell a task that finishes in a
+//! second from one that runs for an hour, so they either poll too often or
+//! notice too late. `PollHintRequestHandler` adds a hint to every unsettled
+//! task it returns: the number of milliseconds to wait before the next poll,
+//! under `POLL_AFTER_METADATA_KEY` in the task metadata, picked from the
+//! task state by `PollHints`. Tasks that are done or wait for the caller get
+//! no hint, and a hint the agent set itself is kept.
+//!
+//! `ClientExt::send_and_wait` and `ClientExt::wait_for_task` honor the hint
+//! in place of their exponential backoff.
+
+use crate::a2a::core_types::TaskState;
+use crate::a2a::error::A2AError;
+use crate::a2a::models::*;
+use crate::a2a::server::context::ServerCallContext;
+use crate::a2a::server::request_handlers::request_handler::{
+    Event, MessageSendResult, RequestHandler, StreamEvent, TaskPushNotificationConfigQueryParams,
+};
+use crate::a2a::utils::constants::POLL_AFTER_METADATA_KEY;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The poll intervals suggested per task state
+#[derive(Debug, Clone, PartialEq)]
+pub struct PollHints {
+    intervals: Vec<(TaskState, Duration)>,
+}
+
+impl Default for PollHints {
+    fn default() -> Self {
+        Self {
+            intervals: vec![
+                (TaskState::Submitted, Duration::from_secs(1)),
+                (TaskState::Working, Duration::from_secs(2)),
+                (TaskState::Unknown, Duration::from_secs(5)),
+            ],
+        }
+    }
+}
+
+impl PollHints {
+    /// One second for submitted tasks, two for working ones and five for
+    /// tasks in an unknown state
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Suggests `interval` for tasks in `state`
+    ///
+    /// Settled states, terminal or waiting for input or authentication,
+    /// never get a hint.
+    pub fn with_interval(mut self, state: TaskState, interval: Duration) -> Self {
+        self.intervals.retain(|(existing, _)| *existing != state);
+        self.intervals.push((state, interval));
+        self
+    }
+
+    /// The interval suggested for a task in `state`
+    pub fn interval(&self, state: &TaskState) -> Option<Duration> {
+        if state.is_terminal() || matches!(state, TaskState::InputRequired | TaskState::AuthRequired) {
+            return None;
+        }
+        self.intervals.iter().find(|(existing, _)| existing == state).map(|(_, interval)| *interval)
+    }
+
+    /// Adds the hint for its state to `task`, unless it has one
+    pub fn apply(&self, mut task: Task) -> Task {
+        if let Some(interval) = self.interval(&task.status.state) {
+            task.metadata
+                .get_or_insert_with(HashMap::new)
+                .entry(POLL_AFTER_METADATA_KEY.to_string())
+                .or_insert_with(|| serde_json::json!(interval.as_millis() as u64));
+        }
+        task
+    }
+}
+
+/// Adds poll interval hints to the tasks returned by another handler
+pub struct PollHintRequestHandler {
+    inner: Arc<dyn RequestHandler>,
+    hints: PollHints,
+}
+
+impl PollHintRequestHandler {
+    /// Hints the intervals of `hints` on the tasks returned by `inner`
+    pub fn new(inner: Arc<dyn RequestHandler>, hints: PollHints) -> Self {
+        Self { inner, hints }
+    }
+}
+
+#[async_trait]
+impl RequestHandler for PollHintRequestHandler {
+    async fn on_get_task(
+        &self,
+        params: TaskQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        Ok(self.inner.on_get_task(params, context).await?.map(|task| self.hints.apply(task)))
+    }
+
+    async fn on_cancel_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        Ok(self.inner.on_cancel_task(params, context).await?.map(|task| self.hints.apply(task)))
+    }
+
+    async fn on_message_send(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<MessageSendResult, A2AError> {
+        match self.inner.on_message_send(params, context).await? {
+            MessageSendResult::Task(task) => Ok(MessageSendResult::Task(self.hints.apply(task))),
+            message => Ok(message),
+        }
+    }
+
+    async fn on_message_send_stream(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        self.inner.on_message_send_stream(params, context).await
+    }
+
+    async fn on_message_send_stream_resumable(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        self.inner.on_message_send_stream_resumable(params, context).await
+    }
+
+    async fn on_set_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfig,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_set_task_push_notification_config(params, context).await
+    }
+
+    async fn on_get_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfigQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_get_task_push_notification_config(params, context).await
+    }
+
+    async fn on_resubscribe_to_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        self.inner.on_resubscribe_to_task(params, context).await
+    }
+
+    async fn on_resubscribe_to_task_resumable(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        self.inner.on_resubscribe_to_task_resumable(params, context).await
+    }
+
+    async fn on_list_task_push_notification_config(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Vec<TaskPushNotificationConfig>, A2AError> {
+        self.inner.on_list_task_push_notification_config(params, context).await
+    }
+
+    async fn on_delete_task_push_notification_config(
+        &self,
+        params: DeleteTaskPushNotificationConfigParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<(), A2AError> {
+        self.inner.on_delete_task_push_notification_config(params, context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::TaskStatus;
+
+    #[test]
+    fn test_hints_follow_the_task_state() {
+        let hints = PollHints::new().with_interval(TaskState::Working, Duration::from_secs(30));
+        let working = hints.apply(Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)));
+        assert_eq!(working.metadata.unwrap()[POLL_AFTER_METADATA_KEY], serde_json::json!(30_000));
+        let submitted = hints.apply(Task::new("ctx".to_string(), TaskStatus::new(TaskState::Submitted)));
+        assert_eq!(submitted.metadata.unwrap()[POLL_AFTER_METADATA_KEY], serde_json::json!(1_000));
+        let completed = hints.apply(Task::new("ctx".to_string(), TaskStatus::new(TaskState::Completed)));
+        assert!(completed.metadata.is_none());
+        assert!(hints.interval(&TaskState::InputRequired).is_none());
+
+        let mut own = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working));
+        own.metadata = Some(HashMap::from([(POLL_AFTER_METADATA_KEY.to_string(), serde_json::json!(500))]));
+        assert_eq!(hints.apply(own).metadata.unwrap()[POLL_AFTER_METADATA_KEY], serde_json::json!(500));
+    }
+
+    /// Returns a working task
+    struct Working;
+
+    #[async_trait]
+    impl RequestHandler for Working {
+        async fn on_get_task(&self, params: TaskQueryParams, _: Option<&ServerCallContext>) -> Result<Option<Task>, A2AError> {
+            Ok(Some(Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id(params.id)))
+        }
+
+        async fn on_message_send(&self, params: MessageSendParams, _: Option<&ServerCallContext>) -> Result<MessageSendResult, A2AError> {
+            Ok(MessageSendResult::Message(params.message))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handler_hints_returned_tasks() {
+        let handler = PollHintRequestHandler::new(Arc::new(Working), PollHints::new());
+        let task = handler.on_get_task(TaskQueryParams::new("t1".to_string()), None).await.unwrap().unwrap();
+        assert_eq!(task.metadata.unwrap()[POLL_AFTER_METADATA_KEY], serde_json::json!(2_000));
+    }
+}
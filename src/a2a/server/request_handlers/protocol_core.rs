@@ -0,0 +1,111 @@
+//! Transport-agnostic protocol rules
+//!
+//! Everything a transport decides the same way regardless of its wire
+//! format lives here: which calls the agent's capabilities allow, how the
+//! history of returned tasks is trimmed to `history_length`, what a missing
+//! task becomes, and how an `A2AError` maps to a JSON-RPC error or an HTTP
+//! status. `JSONRPCHandler`, `GRPCHandler` and `RestHandler` call these
+//! around the `RequestHandler` so the transports cannot drift apart.
+
+use crate::a2a::error::A2AError;
+use crate::a2a::jsonrpc::JSONRPCError;
+use crate::a2a::models::*;
+use crate::a2a::utils::task::apply_history_length;
+
+/// Rejects streaming calls (message/stream, tasks/resubscribe) when the
+/// agent card does not advertise streaming
+pub fn ensure_streaming_supported(agent_card: &AgentCard) -> Result<(), A2AError> {
+    if !agent_card.capabilities.streaming.unwrap_or(false) {
+        // Match Python validate message as closely as possible
+        return Err(A2AError::unsupported_operation(
+            "Streaming is not supported by the agent",
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects setting push notification configs when the agent card does not
+/// advertise push notifications
+///
+/// Reading, listing and deleting configs are not gated, matching Python.
+pub fn ensure_push_supported(agent_card: &AgentCard) -> Result<(), A2AError> {
+    if !agent_card.capabilities.push_notifications.unwrap_or(false) {
+        return Err(A2AError::push_notification_not_supported());
+    }
+    Ok(())
+}
+
+/// Rejects agent/authenticatedExtendedCard when the agent card does not
+/// advertise an extended card
+pub fn ensure_extended_card_supported(agent_card: &AgentCard) -> Result<(), A2AError> {
+    if !agent_card.supports_authenticated_extended_card.unwrap_or(false) {
+        return Err(A2AError::unsupported_operation(
+            "Authenticated extended card is not supported by the agent",
+        ));
+    }
+    Ok(())
+}
+
+/// Maps a task the request handler did not find to `TaskNotFoundError`
+pub fn task_or_not_found(task: Option<Task>, task_id: &str) -> Result<Task, A2AError> {
+    task.ok_or_else(|| A2AError::task_not_found(task_id))
+}
+
+/// The tasks/get result: the task with its history trimmed to the
+/// requested `history_length`, or `TaskNotFoundError`
+pub fn task_query_result(task: Option<Task>, params: &TaskQueryParams) -> Result<Task, A2AError> {
+    task_or_not_found(task, &params.id).map(|task| apply_history_length(task, params.history_length))
+}
+
+/// The JSON-RPC error of `error`, keeping its code, message and details
+pub fn jsonrpc_error(error: &A2AError) -> JSONRPCError {
+    error.into()
+}
+
+/// The HTTP status of a REST response failing with `error`
+///
+/// Errors that already carry an HTTP status, like those of a remote agent,
+/// keep it.
+pub fn http_status(error: &A2AError) -> u16 {
+    if let Some(status) = error.http_status() {
+        return status;
+    }
+    match error {
+        A2AError::JSONParse(_) | A2AError::InvalidRequest(_) => 400,
+        A2AError::MethodNotFound(_) | A2AError::TaskNotFound(_) => 404,
+        A2AError::AuthenticatedExtendedCardNotConfigured(_) => 404,
+        A2AError::TaskNotCancelable(_) => 409,
+        A2AError::ContentTypeNotSupported(_) => 415,
+        A2AError::InvalidParams(_) => 422,
+        A2AError::PushNotificationNotSupported(_) | A2AError::UnsupportedOperation(_) => 501,
+        A2AError::InvalidAgentResponse(_) => 502,
+        A2AError::Internal(_) | A2AError::Generic(_) => 500,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::{Message, Part, Role, TaskState, TaskStatus};
+
+    fn task_with_history(messages: usize) -> Task {
+        let history = (0..messages)
+            .map(|i| Message::new(Role::User, vec![Part::text(format!("message {}", i))]))
+            .collect();
+        Task::new("ctx-1".to_string(), TaskStatus::new(TaskState::Working))
+            .with_task_id("task-1".to_string())
+            .with_history(history)
+    }
+
+    #[test]
+    fn test_task_query_result_trims_history_and_maps_missing_tasks() {
+        let params = TaskQueryParams::new("task-1".to_string()).with_history_length(2);
+        let task = task_query_result(Some(task_with_history(5)), &params).unwrap();
+        assert_eq!(task.history.unwrap().len(), 2);
+
+        let error = task_query_result(None, &params).unwrap_err();
+        assert!(matches!(error, A2AError::TaskNotFound(_)));
+        assert_eq!(http_status(&error), 404);
+        assert_eq!(jsonrpc_error(&error).code, -32001);
+    }
+}
@@ -4,7 +4,7 @@
 //! in a2a-python/src/a2a/server/request_handlers/request_handler.py
 
 use async_trait::async_trait;
-use futures::stream::BoxStream;
+use futures::stream::{BoxStream, StreamExt};
 
 use crate::a2a::models::*;
 use crate::a2a::core_types::{Message, Role, TaskState, TaskStatus, Part, PartRoot};
@@ -15,6 +15,13 @@ use crate::a2a::error::A2AError;
 /// 
 /// This trait defines the methods that an A2A server implementation must
 /// provide to handle incoming JSON-RPC requests.
+///
+/// Only `on_get_task` and `on_message_send` are required. The other methods
+/// default to refusing the operation: canceling and streaming with an
+/// unsupported operation error, and push notification configs with a push
+/// notification not supported error, except that listing them returns none.
+/// Agents can also implement the capability traits of the `capabilities`
+/// module, which provide this trait.
 #[async_trait]
 pub trait RequestHandler: Send + Sync {
     /// Handles the 'tasks/get' method
@@ -31,9 +38,11 @@ pub trait RequestHandler: Send + Sync {
     /// Requests the agent to cancel an ongoing task.
     async fn on_cancel_task(
         &self,
-        params: TaskIdParams,
-        context: Option<&ServerCallContext>,
-    ) -> Result<Option<Task>, A2AError>;
+        _params: TaskIdParams,
+        _context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        Err(A2AError::unsupported_operation("Task cancellation is not supported"))
+    }
 
     /// Handles the 'message/send' method (non-streaming)
     /// 
@@ -63,18 +72,22 @@ pub trait RequestHandler: Send + Sync {
     /// Sets or updates the push notification configuration for a task.
     async fn on_set_task_push_notification_config(
         &self,
-        params: TaskPushNotificationConfig,
-        context: Option<&ServerCallContext>,
-    ) -> Result<TaskPushNotificationConfig, A2AError>;
+        _params: TaskPushNotificationConfig,
+        _context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        Err(A2AError::push_notification_not_supported())
+    }
 
     /// Handles the 'tasks/pushNotificationConfig/get' method
     /// 
     /// Retrieves the current push notification configuration for a task.
     async fn on_get_task_push_notification_config(
         &self,
-        params: TaskPushNotificationConfigQueryParams,
-        context: Option<&ServerCallContext>,
-    ) -> Result<TaskPushNotificationConfig, A2AError>;
+        _params: TaskPushNotificationConfigQueryParams,
+        _context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        Err(A2AError::push_notification_not_supported())
+    }
 
     /// Handles the 'tasks/resubscribe' method
     /// 
@@ -88,23 +101,57 @@ pub trait RequestHandler: Send + Sync {
         Err(A2AError::unsupported_operation("Resubscription is not supported"))
     }
 
+    /// Handles the 'message/stream' method, tagging events with resumption ids
+    ///
+    /// Events carrying an id can be resumed after with `tasks/resubscribe`.
+    /// The default implementation streams `on_message_send_stream` without ids.
+    async fn on_message_send_stream_resumable(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        let stream = self.on_message_send_stream(params, context).await?;
+        Ok(Box::pin(stream.map(|event| event.map(StreamEvent::from))))
+    }
+
+    /// Handles the 'tasks/resubscribe' method, tagging events with resumption ids
+    ///
+    /// If `params.from_event_id` is set, only the events after it are sent.
+    /// The default implementation delegates to `on_resubscribe_to_task` and
+    /// rejects `from_event_id`, since it has no retained events to replay.
+    async fn on_resubscribe_to_task_resumable(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        if params.from_event_id.is_some() {
+            return Err(A2AError::unsupported_operation("Resuming from an event id is not supported"));
+        }
+        let stream = self.on_resubscribe_to_task(params, context).await?;
+        Ok(Box::pin(stream.map(|event| event.map(StreamEvent::from))))
+    }
+
     /// Handles the 'tasks/pushNotificationConfig/list' method
     /// 
     /// Retrieves the current push notification configurations for a task.
     async fn on_list_task_push_notification_config(
         &self,
-        params: TaskIdParams,
-        context: Option<&ServerCallContext>,
-    ) -> Result<Vec<TaskPushNotificationConfig>, A2AError>;
+        _params: TaskIdParams,
+        _context: Option<&ServerCallContext>,
+    ) -> Result<Vec<TaskPushNotificationConfig>, A2AError> {
+        Ok(Vec::new())
+    }
 
     /// Handles the 'tasks/pushNotificationConfig/delete' method
     /// 
     /// Deletes a push notification configuration associated with a task.
     async fn on_delete_task_push_notification_config(
         &self,
-        params: DeleteTaskPushNotificationConfigParams,
-        context: Option<&ServerCallContext>,
-    ) -> Result<(), A2AError>;
+        _params: DeleteTaskPushNotificationConfigParams,
+        _context: Option<&ServerCallContext>,
+    ) -> Result<(), A2AError> {
+        Err(A2AError::push_notification_not_supported())
+    }
 }
 
 /// Result type for message send operations
@@ -132,6 +179,50 @@ pub enum Event {
     Task(Task),
 }
 
+/// A streamed event with the id a client can resume the stream after
+#[derive(Debug, Clone)]
+pub struct StreamEvent {
+    pub event: Event,
+    /// Opaque resumption id, if the event was retained for replay
+    pub event_id: Option<String>,
+}
+
+impl StreamEvent {
+    pub fn new(event: Event, event_id: Option<String>) -> Self {
+        Self { event, event_id }
+    }
+}
+
+impl From<Event> for StreamEvent {
+    fn from(event: Event) -> Self {
+        Self::new(event, None)
+    }
+}
+
+impl From<crate::a2a::server::events::Event> for Event {
+    fn from(event: crate::a2a::server::events::Event) -> Self {
+        use crate::a2a::server::events::Event as QueueEvent;
+        match event {
+            QueueEvent::TaskStatusUpdate(update) => Event::TaskStatusUpdate(update),
+            QueueEvent::TaskArtifactUpdate(update) => Event::TaskArtifactUpdate(update),
+            QueueEvent::Message(message) => Event::Message(message),
+            QueueEvent::Task(task) => Event::Task(task),
+        }
+    }
+}
+
+impl From<Event> for crate::a2a::server::events::Event {
+    fn from(event: Event) -> Self {
+        use crate::a2a::server::events::Event as QueueEvent;
+        match event {
+            Event::TaskStatusUpdate(update) => QueueEvent::TaskStatusUpdate(update),
+            Event::TaskArtifactUpdate(update) => QueueEvent::TaskArtifactUpdate(update),
+            Event::Message(message) => QueueEvent::Message(message),
+            Event::Task(task) => QueueEvent::Task(task),
+        }
+    }
+}
+
 /// Mock request handler for testing
 pub struct MockRequestHandler;
 
@@ -274,4 +365,41 @@ mod tests {
         assert!(result.is_ok());
         assert!(result.unwrap().is_none());
     }
+
+    /// Implements only the required methods
+    struct MinimalHandler;
+
+    #[async_trait]
+    impl RequestHandler for MinimalHandler {
+        async fn on_get_task(
+            &self,
+            _params: TaskQueryParams,
+            _context: Option<&ServerCallContext>,
+        ) -> Result<Option<Task>, A2AError> {
+            Ok(None)
+        }
+
+        async fn on_message_send(
+            &self,
+            params: MessageSendParams,
+            _context: Option<&ServerCallContext>,
+        ) -> Result<MessageSendResult, A2AError> {
+            Ok(MessageSendResult::Message(params.message))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_optional_methods_refuse_by_default() {
+        let handler = MinimalHandler;
+        let task = || TaskIdParams::new("task-1".to_string());
+
+        let err = handler.on_cancel_task(task(), None).await.unwrap_err();
+        assert!(matches!(err, A2AError::UnsupportedOperation(_)));
+        assert!(handler.on_resubscribe_to_task(task(), None).await.is_err());
+        assert!(handler.on_list_task_push_notification_config(task(), None).await.unwrap().is_empty());
+
+        let params = DeleteTaskPushNotificationConfigParams::new("task-1".to_string(), "config-1".to_string());
+        let err = handler.on_delete_task_push_notification_config(params, None).await.unwrap_err();
+        assert!(matches!(err, A2AError::PushNotificationNotSupported(_)));
+    }
 }
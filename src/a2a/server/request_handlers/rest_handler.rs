@@ -8,18 +8,23 @@
 //!     * set_push_notification requires push_notifications capability
 //!     * get_push_notification DOES NOT gate on push capability (matches Python)
 //! - tasks/get + tasks/cancel map None -> TaskNotFoundError (matches Python raising ServerError(TaskNotFoundError()))
+//!   and tasks/get trims the history to `history_length`
 //! - list_push_notifications and list_tasks are NOT implemented (matches Python raising NotImplementedError)
+//!
+//! The checks and the error mapping live in `protocol_core`, shared with the
+//! other transports.
 
 use std::pin::Pin;
 use std::sync::Arc;
 
 use futures::{Stream, StreamExt};
 use serde::Serialize;
-use serde_json::{json, Value};
+use serde_json::Value;
 
-use crate::a2a::error::{A2AError, TaskNotFoundError};
+use crate::a2a::error::A2AError;
 use crate::a2a::models::*;
 use crate::a2a::server::context::ServerCallContext;
+use crate::a2a::server::request_handlers::protocol_core;
 use crate::a2a::server::request_handlers::{
     Event, MessageSendResult, RequestHandler, TaskPushNotificationConfigQueryParams,
 };
@@ -27,10 +32,23 @@ use crate::a2a::server::request_handlers::{
 /// REST error envelope (matches Python "ServerError" concept at transport boundary)
 #[derive(Debug, Clone, Serialize)]
 pub struct RestErrorResponse {
+    /// HTTP status of the response
+    #[serde(skip)]
+    pub status: u16,
     pub code: i32,
     pub message: String,
 }
 
+impl From<A2AError> for RestErrorResponse {
+    fn from(err: A2AError) -> Self {
+        Self {
+            status: protocol_core::http_status(&err),
+            code: err.code(),
+            message: err.message().to_string(),
+        }
+    }
+}
+
 /// REST Handler (Python-equivalent semantics)
 pub struct RestHandler {
     agent_card: AgentCard,
@@ -76,7 +94,7 @@ impl RestHandler {
         context: &ServerCallContext,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<String, RestErrorResponse>> + Send>>, RestErrorResponse>
     {
-        self.ensure_streaming_supported()?;
+        protocol_core::ensure_streaming_supported(&self.agent_card)?;
 
         let event_stream = self
             .request_handler
@@ -96,16 +114,13 @@ impl RestHandler {
         params: TaskIdParams,
         context: &ServerCallContext,
     ) -> Result<Value, RestErrorResponse> {
+        let task_id = params.id.clone();
         let result = self
             .request_handler
             .on_cancel_task(params, Some(context))
             .await;
 
-        match result {
-            Ok(Some(task)) => self.wrap_json(Ok(task)),
-            Ok(None) => Err(self.task_not_found()),
-            Err(e) => Err(self.error_from_a2a(e)),
-        }
+        self.wrap_json(result.and_then(|task| protocol_core::task_or_not_found(task, &task_id)))
     }
 
     // -------------------------------
@@ -119,7 +134,7 @@ impl RestHandler {
         context: &ServerCallContext,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<String, RestErrorResponse>> + Send>>, RestErrorResponse>
     {
-        self.ensure_streaming_supported()?;
+        protocol_core::ensure_streaming_supported(&self.agent_card)?;
 
         let event_stream = self
             .request_handler
@@ -156,7 +171,7 @@ impl RestHandler {
         params: TaskPushNotificationConfig,
         context: &ServerCallContext,
     ) -> Result<Value, RestErrorResponse> {
-        self.ensure_push_supported()?;
+        protocol_core::ensure_push_supported(&self.agent_card)?;
 
         let result = self
             .request_handler
@@ -177,14 +192,10 @@ impl RestHandler {
     ) -> Result<Value, RestErrorResponse> {
         let result = self
             .request_handler
-            .on_get_task(params, Some(context))
+            .on_get_task(params.clone(), Some(context))
             .await;
 
-        match result {
-            Ok(Some(task)) => self.wrap_json(Ok(task)),
-            Ok(None) => Err(self.task_not_found()),
-            Err(e) => Err(self.error_from_a2a(e)),
-        }
+        self.wrap_json(result.and_then(|task| protocol_core::task_query_result(task, &params)))
     }
 
     // ------------------------
@@ -211,40 +222,8 @@ impl RestHandler {
     // Helpers (Python-equivalent)
     // ========================
 
-    fn ensure_streaming_supported(&self) -> Result<(), RestErrorResponse> {
-        if !self.agent_card.capabilities.streaming.unwrap_or(false) {
-            let err = A2AError::unsupported_operation("Streaming is not supported by the agent");
-            return Err(RestErrorResponse {
-                code: err.code(),
-                message: err.message().to_string(),
-            });
-        }
-        Ok(())
-    }
-
-    fn ensure_push_supported(&self) -> Result<(), RestErrorResponse> {
-        if !self.agent_card.capabilities.push_notifications.unwrap_or(false) {
-            let err = A2AError::push_notification_not_supported();
-            return Err(RestErrorResponse {
-                code: err.code(),
-                message: err.message().to_string(),
-            });
-        }
-        Ok(())
-    }
-
-    fn task_not_found(&self) -> RestErrorResponse {
-        // Match Python raising ServerError(error=TaskNotFoundError())
-        let err = A2AError::TaskNotFound(TaskNotFoundError::default());
-        self.error_from_a2a(err)
-    }
-
     fn not_implemented(&self, msg: &str) -> RestErrorResponse {
-        let err = A2AError::unsupported_operation(msg);
-        RestErrorResponse {
-            code: err.code(),
-            message: err.message().to_string(),
-        }
+        A2AError::unsupported_operation(msg).into()
     }
 
     fn wrap_json<T: Serialize>(&self, result: Result<T, A2AError>) -> Result<Value, RestErrorResponse> {
@@ -257,16 +236,10 @@ impl RestHandler {
     }
 
     fn error_from_a2a(&self, err: A2AError) -> RestErrorResponse {
-        RestErrorResponse {
-            code: err.code(),
-            message: err.message().to_string(),
-        }
+        err.into()
     }
 
     /// Convert MessageSendResult -> JSON.
-    ///
-    /// NOTE: This assumes MessageSendResult has variants `Task(Task)` and `Message(Message)`.
-    /// If your enum uses different variant names, adjust the match arms accordingly.
     fn message_send_result_to_json(&self, msr: MessageSendResult) -> Result<Value, RestErrorResponse> {
         match msr {
             MessageSendResult::Task(task) => serde_json::to_value(task).map_err(|e| {
@@ -277,14 +250,6 @@ impl RestHandler {
                 let err = A2AError::internal(&format!("Failed to serialize Message: {}", e));
                 self.error_from_a2a(err)
             }),
-
-            // 如果你们 MessageSendResult 还有其他分支（例如带 envelope 的 oneof），
-            // 你可以在这里按 REST schema 包一层，比如：
-            // MessageSendResult::Task(task) => Ok(json!({"task": task})),
-            // MessageSendResult::Message(msg) => Ok(json!({"message": msg})),
-            _ => Ok(json!({
-                "error": "Unsupported MessageSendResult variant for REST serialization"
-            })),
         }
     }
 
@@ -305,20 +270,14 @@ impl RestHandler {
                 let response = SendStreamingMessageResponse::success(None, result);
 
                 serde_json::to_string(&response).map_err(|e| {
-                    let err = A2AError::internal(&format!(
+                    A2AError::internal(&format!(
                         "Failed to serialize streaming response to JSON: {}",
                         e
-                    ));
-                    RestErrorResponse {
-                        code: err.code(),
-                        message: err.message().to_string(),
-                    }
+                    ))
+                    .into()
                 })
             }
-            Err(e) => Err(RestErrorResponse {
-                code: e.code(),
-                message: e.message().to_string(),
-            }),
+            Err(e) => Err(e.into()),
         })
     }
 }
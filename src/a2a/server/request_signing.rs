@@ -0,0 +1,171 @@
+//! Verification of HMAC-signed requests
+//!
+//! For deployments without a PKI that still need message integrity between
+//! known peers, `HmacRequestVerifier` holds a shared secret per peer agent and
+//! rejects JSON-RPC requests whose [`crate::a2a::utils::request_signing`]
+//! signature is missing, made with an unknown key, outside the time window, or
+//! not matching the canonical form of the body received. Peers sign their requests with
+//! `HmacSigningInterceptor`.
+
+use crate::a2a::error::{A2AError, JSONRPCError};
+use crate::a2a::jsonrpc::error_codes;
+use crate::a2a::utils::request_signing::{RequestSignature, REQUEST_SIGNATURE_HEADER};
+use axum::http::HeaderMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Default accepted difference between the signing time and the server clock
+pub const DEFAULT_SIGNATURE_WINDOW: Duration = Duration::from_secs(300);
+
+/// Errors raised by request signature verification
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SignatureError {
+    #[error("Missing request signature header")]
+    Missing,
+
+    #[error("Malformed request signature header")]
+    Malformed,
+
+    #[error("Unknown signing key '{0}'")]
+    UnknownKey(String),
+
+    #[error("Request was signed outside the accepted window of {window_secs}s")]
+    Stale { window_secs: u64 },
+
+    #[error("Request signature does not match")]
+    Mismatch,
+}
+
+impl SignatureError {
+    /// Returns the structured error data sent to the client
+    pub fn data(&self) -> Option<serde_json::Value> {
+        let reason = match self {
+            SignatureError::Missing => "missing",
+            SignatureError::Malformed => "malformed",
+            SignatureError::UnknownKey(_) => "unknown_key",
+            SignatureError::Stale { window_secs } => {
+                return Some(serde_json::json!({ "reason": "stale", "window_secs": window_secs }))
+            }
+            SignatureError::Mismatch => "mismatch",
+        };
+        Some(serde_json::json!({ "reason": reason }))
+    }
+}
+
+impl From<SignatureError> for A2AError {
+    fn from(err: SignatureError) -> Self {
+        let data = err.data();
+        A2AError::Generic(JSONRPCError {
+            code: error_codes::REQUEST_SIGNATURE_INVALID,
+            message: err.to_string(),
+            data,
+        })
+    }
+}
+
+/// Verifies request signatures against the secrets of known peers
+#[derive(Clone, Default)]
+pub struct HmacRequestVerifier {
+    secrets: Arc<HashMap<String, Vec<u8>>>,
+    window: Option<Duration>,
+}
+
+impl HmacRequestVerifier {
+    /// Creates a verifier without peers, rejecting every request
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accepts requests signed with `secret` under `key_id`
+    pub fn with_peer(mut self, key_id: &str, secret: &[u8]) -> Self {
+        Arc::make_mut(&mut self.secrets).insert(key_id.to_string(), secret.to_vec());
+        self
+    }
+
+    /// Sets the accepted clock difference, `DEFAULT_SIGNATURE_WINDOW` by default
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    /// Verifies the signature of a request to `method` with `body`,
+    /// returning the key id of the signing peer
+    pub fn verify(&self, headers: &HeaderMap, method: &str, body: &[u8]) -> Result<String, SignatureError> {
+        let header = headers
+            .get(REQUEST_SIGNATURE_HEADER)
+            .ok_or(SignatureError::Missing)?
+            .to_str()
+            .map_err(|_| SignatureError::Malformed)?;
+        let signature = RequestSignature::parse(header).ok_or(SignatureError::Malformed)?;
+
+        let window_secs = self.window.unwrap_or(DEFAULT_SIGNATURE_WINDOW).as_secs();
+        if chrono::Utc::now().timestamp().abs_diff(signature.timestamp) > window_secs {
+            return Err(SignatureError::Stale { window_secs });
+        }
+        let secret = self
+            .secrets
+            .get(&signature.key_id)
+            .ok_or_else(|| SignatureError::UnknownKey(signature.key_id.clone()))?;
+        if !signature.verify(secret, method, body) {
+            return Err(SignatureError::Mismatch);
+        }
+        Ok(signature.key_id)
+    }
+}
+
+impl std::fmt::Debug for HmacRequestVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HmacRequestVerifier")
+            .field("peers", &self.secrets.keys().collect::<Vec<_>>())
+            .field("window", &self.window)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed(key_id: &str, secret: &[u8], body: &[u8], timestamp: i64) -> HeaderMap {
+        let signature = RequestSignature::sign(key_id, secret, "message/send", body, timestamp);
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUEST_SIGNATURE_HEADER, signature.to_header().parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_verifies_peer_signatures() {
+        let verifier = HmacRequestVerifier::new()
+            .with_peer("peer-a", b"secret-a")
+            .with_peer("peer-b", b"secret-b");
+        let now = chrono::Utc::now().timestamp();
+        let body = br#"{"id":1}"#;
+
+        assert_eq!(verifier.verify(&signed("peer-b", b"secret-b", body, now), "message/send", body), Ok("peer-b".to_string()));
+        assert_eq!(
+            verifier.verify(&signed("peer-a", b"secret-b", body, now), "message/send", body),
+            Err(SignatureError::Mismatch)
+        );
+        assert_eq!(
+            verifier.verify(&signed("peer-a", b"secret-a", body, now), "message/send", br#"{"id":2}"#),
+            Err(SignatureError::Mismatch)
+        );
+        assert_eq!(
+            verifier.verify(&signed("peer-c", b"secret-a", body, now), "message/send", body),
+            Err(SignatureError::UnknownKey("peer-c".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_rejects_missing_and_stale_signatures() {
+        let verifier = HmacRequestVerifier::new().with_peer("peer-a", b"secret").with_window(Duration::from_secs(30));
+        assert_eq!(verifier.verify(&HeaderMap::new(), "message/send", b""), Err(SignatureError::Missing));
+
+        let old = chrono::Utc::now().timestamp() - 60;
+        let error = verifier.verify(&signed("peer-a", b"secret", b"", old), "message/send", b"").unwrap_err();
+        assert_eq!(error, SignatureError::Stale { window_secs: 30 });
+        assert_eq!(A2AError::from(error).kind(), "request_signature_invalid");
+    }
+}
@@ -0,0 +1,212 @@
+//! Signed, time-limited push notification tokens
+//!
+//! A static `token` on a push notification config stays valid for as long as
+//! the config exists, so a leaked webhook secret can be replayed indefinitely.
+//! `CallbackTokenSigner` instead mints a fresh token for every notification,
+//! bound to the task and config and expiring after a configurable time:
+//!
+//! ```text
+//! v1.<expires-at-unix-seconds>.<base64url(HMAC-SHA256(key, signed))>
+//! signed = "v1|" len(task_id) ":" task_id "|" len(config_id) ":" config_id "|" expires
+//! ```
+//!
+//! Lengths are the decimal byte lengths of the ids, so no pair of ids can sign
+//! the same bytes as another pair, whatever characters they contain.
+//!
+//! Receivers holding the same key verify the token they receive, or echo it back
+//! to the agent, which verifies it with `CallbackTokenSigner::verify`.
+
+use crate::A2AError;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt;
+use std::time::Duration;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Version prefix of the token format
+const TOKEN_VERSION: &str = "v1";
+
+/// Default token lifetime
+pub const DEFAULT_CALLBACK_TOKEN_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// The verified contents of a callback token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallbackTokenClaims {
+    /// The task the token was issued for
+    pub task_id: String,
+    /// The push notification config the token was issued for
+    pub config_id: String,
+    /// When the token expires
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Issues and verifies HMAC-signed callback tokens
+#[derive(Clone)]
+pub struct CallbackTokenSigner {
+    key: Vec<u8>,
+    ttl: Duration,
+}
+
+impl CallbackTokenSigner {
+    /// Creates a signer with the given secret key and the default lifetime
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: key.to_vec(),
+            ttl: DEFAULT_CALLBACK_TOKEN_TTL,
+        }
+    }
+
+    /// Sets the token lifetime
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Returns the token lifetime
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Issues a token for a task and config, valid from now
+    pub fn issue(&self, task_id: &str, config_id: &str) -> String {
+        self.issue_at(task_id, config_id, Utc::now())
+    }
+
+    /// Issues a token for a task and config, valid from `now`
+    pub fn issue_at(&self, task_id: &str, config_id: &str, now: DateTime<Utc>) -> String {
+        let ttl = chrono::Duration::from_std(self.ttl).unwrap_or_else(|_| chrono::Duration::zero());
+        let expires = (now + ttl).timestamp();
+        let signature = self.sign(task_id, config_id, expires);
+        format!("{}.{}.{}", TOKEN_VERSION, expires, URL_SAFE_NO_PAD.encode(signature))
+    }
+
+    /// Verifies a token for a task and config against the current time
+    pub fn verify(&self, token: &str, task_id: &str, config_id: &str) -> Result<CallbackTokenClaims, A2AError> {
+        self.verify_at(token, task_id, config_id, Utc::now())
+    }
+
+    /// Verifies a token for a task and config against `now`
+    pub fn verify_at(
+        &self,
+        token: &str,
+        task_id: &str,
+        config_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<CallbackTokenClaims, A2AError> {
+        let invalid = || A2AError::invalid_params("Invalid callback token");
+
+        let mut parts = token.splitn(3, '.');
+        let (version, expires, signature) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(version), Some(expires), Some(signature)) => (version, expires, signature),
+            _ => return Err(invalid()),
+        };
+        if version != TOKEN_VERSION {
+            return Err(invalid());
+        }
+        let expires: i64 = expires.parse().map_err(|_| invalid())?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| invalid())?;
+
+        // Check the signature before the expiry so that a forged token is never
+        // reported as merely expired
+        self.mac(task_id, config_id, expires)
+            .verify_slice(&signature)
+            .map_err(|_| invalid())?;
+
+        let expires_at = DateTime::from_timestamp(expires, 0).ok_or_else(invalid)?;
+        if expires_at <= now {
+            return Err(A2AError::invalid_params("Callback token has expired"));
+        }
+
+        Ok(CallbackTokenClaims {
+            task_id: task_id.to_string(),
+            config_id: config_id.to_string(),
+            expires_at,
+        })
+    }
+
+    fn mac(&self, task_id: &str, config_id: &str, expires: i64) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(
+            format!(
+                "{}|{}:{}|{}:{}|{}",
+                TOKEN_VERSION,
+                task_id.len(),
+                task_id,
+                config_id.len(),
+                config_id,
+                expires
+            )
+            .as_bytes(),
+        );
+        mac
+    }
+
+    fn sign(&self, task_id: &str, config_id: &str, expires: i64) -> Vec<u8> {
+        self.mac(task_id, config_id, expires).finalize().into_bytes().to_vec()
+    }
+}
+
+impl fmt::Debug for CallbackTokenSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackTokenSigner")
+            .field("key", &"<redacted>")
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_and_verify() {
+        let signer = CallbackTokenSigner::new(b"secret");
+        let token = signer.issue("t1", "cfg1");
+        assert!(token.starts_with("v1."));
+
+        let claims = signer.verify(&token, "t1", "cfg1").unwrap();
+        assert_eq!(claims.task_id, "t1");
+        assert!(claims.expires_at > Utc::now());
+    }
+
+    #[test]
+    fn test_token_is_bound_to_task_config_and_key() {
+        let signer = CallbackTokenSigner::new(b"secret");
+        let token = signer.issue("t1", "cfg1");
+
+        assert!(signer.verify(&token, "t2", "cfg1").is_err());
+        assert!(signer.verify(&token, "t1", "cfg2").is_err());
+        assert!(CallbackTokenSigner::new(b"other").verify(&token, "t1", "cfg1").is_err());
+        assert!(signer.verify("v1.123.not-base64!", "t1", "cfg1").is_err());
+        assert!(signer.verify("garbage", "t1", "cfg1").is_err());
+    }
+
+    #[test]
+    fn test_ids_containing_the_separator_cannot_be_swapped() {
+        let signer = CallbackTokenSigner::new(b"secret");
+        let token = signer.issue("t1|cfg", "1");
+        assert!(signer.verify(&token, "t1|cfg", "1").is_ok());
+        assert!(signer.verify(&token, "t1", "cfg|1").is_err());
+    }
+
+    #[test]
+    fn test_token_expires() {
+        let signer = CallbackTokenSigner::new(b"secret").with_ttl(Duration::from_secs(60));
+        let issued = Utc::now();
+        let token = signer.issue_at("t1", "cfg1", issued);
+
+        assert!(signer.verify_at(&token, "t1", "cfg1", issued + chrono::Duration::seconds(59)).is_ok());
+        assert!(signer.verify_at(&token, "t1", "cfg1", issued + chrono::Duration::seconds(61)).is_err());
+
+        // Tampering with the expiry invalidates the signature
+        let (_, rest) = token.split_once('.').unwrap();
+        let (_, signature) = rest.split_once('.').unwrap();
+        let extended = format!("v1.{}.{}", (issued + chrono::Duration::days(1)).timestamp(), signature);
+        assert!(signer.verify_at(&extended, "t1", "cfg1", issued).is_err());
+    }
+}
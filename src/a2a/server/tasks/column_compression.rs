@@ -0,0 +1,93 @@
+//! Compression of large JSON columns of the SQL stores
+//!
+//! Histories and artifacts with inline files can be far larger than the rest
+//! of a task. With the `compression` feature, `SqlTaskStore::with_compression`
+//! stores them zstd compressed. The columns stay text: a compressed value is
+//! `~` followed by the base64 of a version byte and the compressed JSON.
+//! JSON never starts with `~`, so rows written before compression was enabled,
+//! or by a store without it, keep reading as plain JSON.
+
+use crate::A2AError;
+#[cfg(feature = "compression")]
+use base64::engine::general_purpose::STANDARD;
+#[cfg(feature = "compression")]
+use base64::Engine;
+
+/// Marks a compressed column value
+const COMPRESSED_MARKER: char = '~';
+
+/// Version byte of zstd compressed JSON
+#[cfg(feature = "compression")]
+const VERSION_ZSTD: u8 = 1;
+
+/// Compresses `json` at the zstd `level`, or keeps it as is without a level
+#[cfg(feature = "compression")]
+pub(crate) fn encode(json: String, level: Option<i32>) -> Result<String, A2AError> {
+    let Some(level) = level else { return Ok(json) };
+    let compressed = zstd::encode_all(json.as_bytes(), level)
+        .map_err(|e| A2AError::internal(&format!("Failed to compress column: {}", e)))?;
+    let mut blob = Vec::with_capacity(compressed.len() + 1);
+    blob.push(VERSION_ZSTD);
+    blob.extend_from_slice(&compressed);
+    Ok(format!("{}{}", COMPRESSED_MARKER, STANDARD.encode(blob)))
+}
+
+#[cfg(not(feature = "compression"))]
+pub(crate) fn encode(json: String, _level: Option<i32>) -> Result<String, A2AError> {
+    Ok(json)
+}
+
+/// The JSON of a column value, compressed or not
+#[cfg(feature = "compression")]
+pub(crate) fn decode(value: String) -> Result<String, A2AError> {
+    let Some(encoded) = value.strip_prefix(COMPRESSED_MARKER) else { return Ok(value) };
+    let blob = STANDARD
+        .decode(encoded)
+        .map_err(|e| A2AError::internal(&format!("Failed to decode compressed column: {}", e)))?;
+    match blob.split_first() {
+        Some((&VERSION_ZSTD, compressed)) => {
+            let json = zstd::decode_all(compressed)
+                .map_err(|e| A2AError::internal(&format!("Failed to decompress column: {}", e)))?;
+            String::from_utf8(json)
+                .map_err(|e| A2AError::internal(&format!("Decompressed column is not UTF-8: {}", e)))
+        }
+        Some((version, _)) => Err(A2AError::internal(&format!(
+            "Unknown compressed column version {}",
+            version
+        ))),
+        None => Err(A2AError::internal("Compressed column is empty")),
+    }
+}
+
+#[cfg(not(feature = "compression"))]
+pub(crate) fn decode(value: String) -> Result<String, A2AError> {
+    if value.starts_with(COMPRESSED_MARKER) {
+        return Err(A2AError::internal(
+            "Column is compressed; enable the compression feature to read it",
+        ));
+    }
+    Ok(value)
+}
+
+#[cfg(all(test, feature = "compression"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compressed_values_round_trip() {
+        let json = format!("[{{\"bytes\":\"{}\"}}]", "QUJD".repeat(1000));
+        let compressed = encode(json.clone(), Some(3)).unwrap();
+        assert!(compressed.starts_with(COMPRESSED_MARKER));
+        assert!(compressed.len() < json.len() / 10);
+        assert_eq!(decode(compressed).unwrap(), json);
+    }
+
+    #[test]
+    fn test_plain_values_are_read_as_is() {
+        assert_eq!(encode("[]".to_string(), None).unwrap(), "[]");
+        assert_eq!(decode("null".to_string()).unwrap(), "null");
+
+        let unknown = format!("{}{}", COMPRESSED_MARKER, STANDARD.encode([9, 1, 2]));
+        assert!(decode(unknown).is_err());
+    }
+}
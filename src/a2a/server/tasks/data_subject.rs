@@ -0,0 +1,370 @@
+//! Export and erasure of the data of a data subject
+//!
+//! `DataSubjectRequests` answers the access and erasure requests of privacy
+//! law for a principal or a context. The tasks of a principal are the tasks
+//! it owns, stamped under `TENANT_METADATA_KEY` by `DefaultRequestHandler`,
+//! and the tasks billed to it in the usage records; the tasks of a context
+//! are the tasks with that context ID. Deleted tasks that can still be
+//! restored count too.
+//!
+//! `export` gathers the tasks with their messages, artifacts and labels, the
+//! push configs of the tasks and their usage into a `DataExport`, a JSON
+//! document that can be handed over as is. `erase` replaces every task by an
+//! audit stub with `TaskStore::erase`, deletes its push configs and drops
+//! the events retained to resume its streams: the stub keeps the ID,
+//! context, states and timestamps of the task and records when it was
+//! erased under `ERASED_AT_METADATA_KEY`, while the messages, artifacts,
+//! metadata and labels are gone. Usage records are kept for billing.
+//!
+//! Finding the tasks a principal owns means listing every task, so requests
+//! for a principal fail on task stores that cannot list their tasks.
+
+use crate::a2a::server::events::EventStore;
+use crate::a2a::server::quota::TENANT_METADATA_KEY;
+use crate::a2a::server::tasks::labels::Labels;
+use crate::a2a::server::tasks::push_notification_config_store::PushNotificationConfigStore;
+use crate::a2a::server::tasks::task_store::{DeletedTask, TaskStore};
+use crate::a2a::server::tasks::usage::UsageRecord;
+use crate::{A2AError, PushNotificationConfig, Task, TaskStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Metadata key of an erased task, holding when it was erased as RFC 3339
+pub const ERASED_AT_METADATA_KEY: &str = "erased_at";
+
+/// Whose data a request is about
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "id", rename_all = "snake_case")]
+pub enum DataSubject {
+    /// The tasks owned by or billed to a principal
+    Principal(String),
+    /// The tasks of a context
+    Context(String),
+}
+
+/// Everything stored about a data subject
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataExport {
+    pub subject: DataSubject,
+    pub exported_at: DateTime<Utc>,
+    pub tasks: Vec<Task>,
+    /// Deleted tasks that can still be restored
+    pub deleted_tasks: Vec<DeletedTask>,
+    /// Push configs per task ID
+    pub push_configs: BTreeMap<String, Vec<PushNotificationConfig>>,
+    /// Labels per task ID, of the tasks that have any
+    pub labels: BTreeMap<String, Labels>,
+    pub usage: Vec<UsageRecord>,
+}
+
+/// What an erasure removed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErasureReport {
+    /// Tasks replaced by their audit stubs
+    pub tasks: usize,
+    /// Push configs deleted
+    pub push_configs: usize,
+}
+
+/// The audit stub of `task`, erased at `erased_at`
+pub fn erased_task(task: &Task, erased_at: DateTime<Utc>) -> Task {
+    let state_transitions = task.state_transitions.as_ref().map(|transitions| {
+        transitions
+            .iter()
+            .cloned()
+            .map(|mut transition| {
+                transition.message = None;
+                transition
+            })
+            .collect()
+    });
+    Task {
+        id: task.id.clone(),
+        context_id: task.context_id.clone(),
+        kind: task.kind.clone(),
+        status: TaskStatus {
+            state: task.status.state.clone(),
+            message: None,
+            timestamp: task.status.timestamp.clone(),
+        },
+        artifacts: None,
+        history: None,
+        state_transitions,
+        metadata: Some(HashMap::from([(
+            ERASED_AT_METADATA_KEY.to_string(),
+            serde_json::json!(erased_at.to_rfc3339()),
+        )])),
+    }
+}
+
+/// Answers export and erasure requests over a task store and, optionally, a
+/// push config store
+#[derive(Clone)]
+pub struct DataSubjectRequests {
+    task_store: Arc<dyn TaskStore>,
+    push_config_store: Option<Arc<dyn PushNotificationConfigStore>>,
+    event_store: Option<Arc<dyn EventStore>>,
+}
+
+impl DataSubjectRequests {
+    pub fn new(task_store: Arc<dyn TaskStore>) -> Self {
+        Self {
+            task_store,
+            push_config_store: None,
+            event_store: None,
+        }
+    }
+
+    /// Also export and delete the push configs held by `store`
+    ///
+    /// Pass `DefaultRequestHandler::push_config_store`, so erasures
+    /// invalidate the configs the push sender cached.
+    pub fn with_push_config_store(mut self, store: Arc<dyn PushNotificationConfigStore>) -> Self {
+        self.push_config_store = Some(store);
+        self
+    }
+
+    /// Also drop the events `store` retains for the erased tasks
+    pub fn with_event_store(mut self, store: Arc<dyn EventStore>) -> Self {
+        self.event_store = Some(store);
+        self
+    }
+
+    /// Everything stored about `subject`
+    pub async fn export(&self, subject: &DataSubject) -> Result<DataExport, A2AError> {
+        let (tasks, deleted_tasks, usage) = self.collect(subject).await?;
+        let mut labels = BTreeMap::new();
+        for task in &tasks {
+            let task_labels = match self.task_store.get_labels(&task.id).await {
+                Ok(task_labels) => task_labels,
+                Err(A2AError::UnsupportedOperation(_)) => break,
+                Err(e) => return Err(e),
+            };
+            if !task_labels.is_empty() {
+                labels.insert(task.id.clone(), task_labels);
+            }
+        }
+        let mut push_configs = BTreeMap::new();
+        if let Some(store) = &self.push_config_store {
+            for task_id in tasks.iter().map(|task| &task.id).chain(deleted_tasks.iter().map(|task| &task.task.id)) {
+                let configs = store.get_info(task_id).await?;
+                if !configs.is_empty() {
+                    push_configs.insert(task_id.clone(), configs);
+                }
+            }
+        }
+        Ok(DataExport {
+            subject: subject.clone(),
+            exported_at: Utc::now(),
+            tasks,
+            deleted_tasks,
+            push_configs,
+            labels,
+            usage,
+        })
+    }
+
+    /// Replaces the tasks of `subject` by their audit stubs, deletes their
+    /// push configs and drops their retained events
+    pub async fn erase(&self, subject: &DataSubject) -> Result<ErasureReport, A2AError> {
+        let (tasks, deleted_tasks, _) = self.collect(subject).await?;
+        let task_ids: Vec<String> = tasks
+            .into_iter()
+            .map(|task| task.id)
+            .chain(deleted_tasks.into_iter().map(|task| task.task.id))
+            .collect();
+        let mut report = ErasureReport {
+            tasks: self.task_store.erase(&task_ids).await?,
+            push_configs: 0,
+        };
+        if let Some(store) = &self.push_config_store {
+            for task_id in &task_ids {
+                report.push_configs += store.get_info(task_id).await?.len();
+                store.delete_info(task_id, None).await?;
+            }
+        }
+        if let Some(store) = &self.event_store {
+            for task_id in &task_ids {
+                store.remove_task(task_id).await?;
+            }
+        }
+        Ok(report)
+    }
+
+    /// The live tasks, deleted tasks and usage records of `subject`
+    async fn collect(&self, subject: &DataSubject) -> Result<(Vec<Task>, Vec<DeletedTask>, Vec<UsageRecord>), A2AError> {
+        let usage = match self.task_store.list_usage().await {
+            Ok(usage) => usage,
+            Err(A2AError::UnsupportedOperation(_)) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        let deleted = match self.task_store.list_deleted().await {
+            Ok(deleted) => deleted,
+            Err(A2AError::UnsupportedOperation(_)) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        match subject {
+            DataSubject::Principal(principal) => {
+                let usage: Vec<UsageRecord> = usage.into_iter().filter(|record| record.principal == *principal).collect();
+                let belongs = |task: &Task| {
+                    is_owned_by(task, principal) || usage.iter().any(|record| record.task_id == task.id)
+                };
+                let tasks = self.task_store.list().await?.into_iter().filter(|task| belongs(task)).collect();
+                let deleted = deleted.into_iter().filter(|task| belongs(&task.task)).collect();
+                Ok((tasks, deleted, usage))
+            }
+            DataSubject::Context(context_id) => {
+                let tasks = self.task_store.list_by_context(context_id).await?;
+                let deleted = deleted.into_iter().filter(|task| task.task.context_id == *context_id).collect();
+                let usage = usage.into_iter().filter(|record| record.context_id == *context_id).collect();
+                Ok((tasks, deleted, usage))
+            }
+        }
+    }
+}
+
+/// Whether `principal` is the tenant stamped on `task`
+fn is_owned_by(task: &Task, principal: &str) -> bool {
+    task.metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get(TENANT_METADATA_KEY))
+        .and_then(|tenant| tenant.as_str())
+        == Some(principal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::server::events::{BusEvent, Event, InMemoryEventStore};
+    use crate::a2a::server::tasks::{InMemoryPushNotificationConfigStore, InMemoryTaskStore, Usage};
+    use crate::{Message, Part, Role, TaskState};
+    use async_trait::async_trait;
+
+    fn task(id: &str, context_id: &str, text: &str) -> Task {
+        Task::new(context_id.to_string(), TaskStatus::new(TaskState::Completed))
+            .with_task_id(id.to_string())
+            .with_history(vec![Message::new(Role::User, vec![Part::text(text.to_string())])])
+    }
+
+    #[tokio::test]
+    async fn test_export_and_erase_the_tasks_of_a_principal() {
+        let store = Arc::new(InMemoryTaskStore::new());
+        let push_configs = Arc::new(InMemoryPushNotificationConfigStore::new());
+        for (id, principal) in [("t1", "alice"), ("t2", "alice"), ("t3", "bob")] {
+            store.save(task(id, "ctx", "my address is 1 Main St")).await.unwrap();
+            store.record_usage(id, principal, Usage::tokens(1, 1)).await.unwrap();
+        }
+        push_configs
+            .set_info("t1", PushNotificationConfig::new(url::Url::parse("https://example.com/hook").unwrap()))
+            .await
+            .unwrap();
+        store.set_labels("t1", Labels::from([("team".to_string(), "billing".to_string())])).await.unwrap();
+        store.delete("t2").await.unwrap();
+        let events = Arc::new(InMemoryEventStore::new());
+        let retained = BusEvent {
+            sequence: 1,
+            event: Event::Task(task("t1", "ctx", "my address is 1 Main St")),
+            task: task("t1", "ctx", "my address is 1 Main St"),
+            origin: None,
+        };
+        events.append(&retained).await.unwrap();
+
+        let requests = DataSubjectRequests::new(store.clone())
+            .with_push_config_store(push_configs.clone())
+            .with_event_store(events.clone());
+        let alice = DataSubject::Principal("alice".to_string());
+        let export = requests.export(&alice).await.unwrap();
+        assert_eq!(export.tasks.len(), 1);
+        assert_eq!(export.deleted_tasks[0].task.id, "t2");
+        assert_eq!(export.push_configs["t1"].len(), 1);
+        assert_eq!(export.labels["t1"]["team"], "billing");
+        assert_eq!(export.usage.len(), 2);
+        let json = serde_json::to_value(&export).unwrap();
+        assert_eq!(json["subject"], serde_json::json!({"type": "principal", "id": "alice"}));
+
+        let report = requests.erase(&alice).await.unwrap();
+        assert_eq!(report, ErasureReport { tasks: 2, push_configs: 1 });
+        let stub = store.get("t1").await.unwrap().unwrap();
+        assert!(stub.history.is_none());
+        assert_eq!(stub.status.state, TaskState::Completed);
+        assert!(stub.metadata.unwrap().contains_key(ERASED_AT_METADATA_KEY));
+        assert!(store.get("t2").await.unwrap().is_none());
+        assert!(store.list_deleted().await.unwrap()[0].task.history.is_none());
+        assert!(push_configs.get_info("t1").await.unwrap().is_empty());
+        assert!(events.events_after("t1", 1).await.unwrap().is_empty());
+        assert!(events.events_after("t1", 0).await.is_err());
+        assert!(store.get("t3").await.unwrap().unwrap().history.is_some());
+        assert_eq!(store.list_usage().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_principal_requests_cover_owned_tasks_without_usage() {
+        let store = Arc::new(InMemoryTaskStore::new());
+        let owned = |id: &str, tenant: &str| {
+            let mut owned = task(id, "ctx", "hello");
+            owned.metadata = Some(HashMap::from([(TENANT_METADATA_KEY.to_string(), serde_json::json!(tenant))]));
+            owned
+        };
+        store.save(owned("t1", "alice")).await.unwrap();
+        store.save(owned("t2", "alice")).await.unwrap();
+        store.save(owned("t3", "bob")).await.unwrap();
+        store.delete("t2").await.unwrap();
+
+        let requests = DataSubjectRequests::new(store.clone());
+        let alice = DataSubject::Principal("alice".to_string());
+        let export = requests.export(&alice).await.unwrap();
+        assert_eq!(export.tasks.len(), 1);
+        assert_eq!(export.tasks[0].id, "t1");
+        assert_eq!(export.deleted_tasks[0].task.id, "t2");
+        assert!(export.usage.is_empty());
+
+        assert_eq!(requests.erase(&alice).await.unwrap().tasks, 2);
+        assert!(store.get("t1").await.unwrap().unwrap().history.is_none());
+        assert!(store.list_deleted().await.unwrap()[0].task.history.is_none());
+        assert!(store.get("t3").await.unwrap().unwrap().history.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_context_requests_cover_tasks_without_usage() {
+        let store = Arc::new(InMemoryTaskStore::new());
+        store.save(task("t1", "ctx-1", "hello")).await.unwrap();
+        store.save(task("t2", "ctx-2", "hello")).await.unwrap();
+
+        let requests = DataSubjectRequests::new(store.clone());
+        let subject = DataSubject::Context("ctx-1".to_string());
+        assert_eq!(requests.export(&subject).await.unwrap().tasks.len(), 1);
+        assert_eq!(requests.erase(&subject).await.unwrap().tasks, 1);
+        assert!(store.get("t1").await.unwrap().unwrap().history.is_none());
+        assert!(store.get("t2").await.unwrap().unwrap().history.is_some());
+    }
+
+    /// Stores tasks without listing them or tracking usage
+    struct NoUsageStore(InMemoryTaskStore);
+
+    #[async_trait]
+    impl TaskStore for NoUsageStore {
+        async fn save(&self, task: Task) -> Result<(), A2AError> {
+            self.0.save(task).await
+        }
+
+        async fn get(&self, task_id: &str) -> Result<Option<Task>, A2AError> {
+            self.0.get(task_id).await
+        }
+
+        async fn delete(&self, task_id: &str) -> Result<(), A2AError> {
+            self.0.delete(task_id).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_principal_requests_fail_without_task_listing() {
+        let store = NoUsageStore(InMemoryTaskStore::new());
+        store.save(task("t1", "ctx", "hello")).await.unwrap();
+        let requests = DataSubjectRequests::new(Arc::new(store));
+        let alice = DataSubject::Principal("alice".to_string());
+        assert!(matches!(requests.export(&alice).await, Err(A2AError::UnsupportedOperation(_))));
+        assert!(matches!(requests.erase(&alice).await, Err(A2AError::UnsupportedOperation(_))));
+    }
+}
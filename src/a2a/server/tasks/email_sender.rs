@@ -0,0 +1,277 @@
+//! Email notification sender
+//!
+//! Available with the `email` feature. `EmailNotificationSender` is a
+//! `PushNotificationSender` that emails a fixed list of recipients when a task
+//! reaches one of the configured states (completed or failed by default). It
+//! suits long-running, human-facing workflows where nobody is polling the task.
+//!
+//! The subject and body are templates with the placeholders `{task_id}`,
+//! `{context_id}`, `{state}`, `{timestamp}` and `{summary}`, where the summary is
+//! the text of the task's artifacts.
+
+use crate::a2a::server::tasks::PushNotificationSender;
+use crate::a2a::utils::get_artifact_text;
+use crate::{A2AError, Task, TaskState};
+use async_trait::async_trait;
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use tracing::info;
+
+/// Default subject template
+pub const DEFAULT_EMAIL_SUBJECT: &str = "Task {task_id} {state}";
+
+/// Default body template
+pub const DEFAULT_EMAIL_BODY: &str =
+    "Task {task_id} (context {context_id}) is {state} as of {timestamp}.\n\n{summary}\n";
+
+/// How the SMTP connection is secured
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Implicit TLS, usually on port 465
+    Tls,
+    /// Upgrade with STARTTLS, usually on port 587
+    #[default]
+    StartTls,
+    /// No encryption; only for local relays and tests
+    None,
+}
+
+/// Configuration of an `EmailNotificationSender`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailSenderConfig {
+    /// SMTP server host
+    pub smtp_host: String,
+    /// SMTP server port; the default port of the security mode if `None`
+    pub smtp_port: Option<u16>,
+    /// Connection security
+    pub security: SmtpSecurity,
+    /// SMTP user name
+    pub username: Option<String>,
+    /// SMTP password
+    pub password: Option<String>,
+    /// Sender address
+    pub from: String,
+    /// Recipient addresses
+    pub to: Vec<String>,
+    /// Task states that trigger an email
+    pub notify_states: Vec<TaskState>,
+    /// Subject template
+    pub subject_template: String,
+    /// Body template
+    pub body_template: String,
+}
+
+impl EmailSenderConfig {
+    /// Creates a configuration sending from `from` to `to` through `smtp_host`
+    pub fn new(smtp_host: &str, from: &str, to: &str) -> Self {
+        Self {
+            smtp_host: smtp_host.to_string(),
+            smtp_port: None,
+            security: SmtpSecurity::default(),
+            username: None,
+            password: None,
+            from: from.to_string(),
+            to: vec![to.to_string()],
+            notify_states: vec![TaskState::Completed, TaskState::Failed],
+            subject_template: DEFAULT_EMAIL_SUBJECT.to_string(),
+            body_template: DEFAULT_EMAIL_BODY.to_string(),
+        }
+    }
+
+    /// Sets the SMTP port
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.smtp_port = Some(port);
+        self
+    }
+
+    /// Sets the connection security
+    pub fn with_security(mut self, security: SmtpSecurity) -> Self {
+        self.security = security;
+        self
+    }
+
+    /// Sets the SMTP credentials
+    pub fn with_credentials(mut self, username: &str, password: &str) -> Self {
+        self.username = Some(username.to_string());
+        self.password = Some(password.to_string());
+        self
+    }
+
+    /// Adds a recipient
+    pub fn with_recipient(mut self, to: &str) -> Self {
+        self.to.push(to.to_string());
+        self
+    }
+
+    /// Sets the task states that trigger an email
+    pub fn with_notify_states(mut self, states: Vec<TaskState>) -> Self {
+        self.notify_states = states;
+        self
+    }
+
+    /// Sets the subject and body templates
+    pub fn with_templates(mut self, subject: &str, body: &str) -> Self {
+        self.subject_template = subject.to_string();
+        self.body_template = body.to_string();
+        self
+    }
+
+    /// Builds an SMTP transport from this configuration
+    pub fn smtp_transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>, A2AError> {
+        let builder = match self.security {
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&self.smtp_host),
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.smtp_host),
+            SmtpSecurity::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&self.smtp_host)),
+        }
+        .map_err(|e| A2AError::transport_error(format!("Invalid SMTP configuration: {}", e)))?;
+
+        let builder = match self.smtp_port {
+            Some(port) => builder.port(port),
+            None => builder,
+        };
+        let builder = match (&self.username, &self.password) {
+            (Some(username), Some(password)) => builder.credentials(Credentials::new(username.clone(), password.clone())),
+            _ => builder,
+        };
+        Ok(builder.build())
+    }
+}
+
+/// Emails recipients when tasks reach the configured states
+pub struct EmailNotificationSender<T = AsyncSmtpTransport<Tokio1Executor>> {
+    transport: T,
+    config: EmailSenderConfig,
+}
+
+impl EmailNotificationSender {
+    /// Creates a sender using an SMTP transport built from `config`
+    pub fn new(config: EmailSenderConfig) -> Result<Self, A2AError> {
+        let transport = config.smtp_transport()?;
+        Ok(Self { transport, config })
+    }
+}
+
+impl<T> EmailNotificationSender<T>
+where
+    T: AsyncTransport + Send + Sync,
+    T::Error: std::fmt::Display,
+{
+    /// Creates a sender using a custom transport
+    pub fn with_transport(transport: T, config: EmailSenderConfig) -> Self {
+        Self { transport, config }
+    }
+
+    /// Builds the email for a task
+    pub fn build_message(&self, task: &Task) -> Result<Message, A2AError> {
+        let parse = |address: &str| {
+            address
+                .parse::<Mailbox>()
+                .map_err(|e| A2AError::invalid_params(&format!("Invalid email address '{}': {}", address, e)))
+        };
+
+        let mut builder = Message::builder()
+            .from(parse(&self.config.from)?)
+            .subject(render_email_template(&self.config.subject_template, task))
+            .header(ContentType::TEXT_PLAIN);
+        for to in &self.config.to {
+            builder = builder.to(parse(to)?);
+        }
+        builder
+            .body(render_email_template(&self.config.body_template, task))
+            .map_err(|e| A2AError::internal(&format!("Failed to build email: {}", e)))
+    }
+}
+
+#[async_trait]
+impl<T> PushNotificationSender for EmailNotificationSender<T>
+where
+    T: AsyncTransport + Send + Sync,
+    T::Error: std::fmt::Display,
+{
+    async fn send_notification(&self, task: &Task) -> Result<(), A2AError> {
+        if !self.config.notify_states.contains(&task.status.state) {
+            return Ok(());
+        }
+
+        let message = self.build_message(task)?;
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| A2AError::transport_error(format!("Failed to send email: {}", e)))?;
+        info!("Email notification sent for task_id={}", task.id);
+        Ok(())
+    }
+}
+
+/// Substitutes the task placeholders in a template
+pub fn render_email_template(template: &str, task: &Task) -> String {
+    let state = serde_json::to_value(&task.status.state)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let summary = task
+        .artifacts
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .map(|artifact| get_artifact_text(artifact, "\n"))
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    template
+        .replace("{task_id}", &task.id)
+        .replace("{context_id}", &task.context_id)
+        .replace("{state}", &state)
+        .replace("{timestamp}", task.status.timestamp.as_deref().unwrap_or(""))
+        .replace("{summary}", &summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::utils::new_text_artifact;
+    use crate::TaskStatus;
+    use lettre::transport::stub::AsyncStubTransport;
+
+    fn config() -> EmailSenderConfig {
+        EmailSenderConfig::new("smtp.example.com", "agent@example.com", "ops@example.com")
+    }
+
+    fn task(state: TaskState) -> Task {
+        let mut task = Task::new("ctx".to_string(), TaskStatus::new(state)).with_task_id("t1".to_string());
+        task.artifacts = Some(vec![new_text_artifact("report".to_string(), "All 3 files processed".to_string(), None)]);
+        task
+    }
+
+    #[test]
+    fn test_render_email_template() {
+        let rendered = render_email_template("{task_id}/{context_id}: {state} - {summary}", &task(TaskState::Completed));
+        assert_eq!(rendered, "t1/ctx: completed - All 3 files processed");
+    }
+
+    #[tokio::test]
+    async fn test_emails_only_configured_states() {
+        let transport = AsyncStubTransport::new_ok();
+        let sender = EmailNotificationSender::with_transport(transport.clone(), config());
+
+        sender.send_notification(&task(TaskState::Working)).await.unwrap();
+        assert!(transport.messages().await.is_empty());
+
+        sender.send_notification(&task(TaskState::Failed)).await.unwrap();
+        let messages = transport.messages().await;
+        assert_eq!(messages.len(), 1);
+        let (envelope, raw) = &messages[0];
+        assert_eq!(envelope.to()[0].to_string(), "ops@example.com");
+        assert!(raw.contains("Subject: Task t1 failed"));
+        assert!(raw.contains("All 3 files processed"));
+    }
+
+    #[test]
+    fn test_smtp_transport_from_config() {
+        let config = config().with_port(2525).with_security(SmtpSecurity::None).with_credentials("user", "pass");
+        assert!(config.smtp_transport().is_ok());
+    }
+}
@@ -0,0 +1,643 @@
+//! Event-sourced task persistence
+//!
+//! `EventSourcedTaskStore` keeps an append-only log of task changes as its
+//! source of truth. Each save is recorded as the changes it makes to the
+//! stored task: a status change, appended messages or artifacts, replaced
+//! metadata, or a full snapshot when the change is not expressible as an
+//! update. Tasks are materialized by replaying their changes on top of a
+//! snapshot taken every `snapshot_interval` changes.
+//!
+//! The log shows how every task evolved, which helps debugging agent
+//! behavior and auditing. `rebuild` reconstructs all tasks from the log
+//! alone, e.g. to seed a different store; the `rebuild_tasks` example does
+//! this for a `FileTaskEventLog`.
+//!
+//! The log is only ever rewritten to erase tasks: `erase` strips the content
+//! of every recorded change of the tasks, keeping their versions, states and
+//! timestamps, and records their audit stubs.
+
+use crate::a2a::error::A2AError;
+use crate::a2a::server::tasks::data_subject::erased_task;
+use crate::a2a::server::tasks::task_store::TaskStore;
+use crate::a2a::models::TaskStateTransition;
+use crate::{Artifact, Message, Task, TaskStatus};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+/// Default number of changes after which a task snapshot is materialized
+pub const DEFAULT_SNAPSHOT_INTERVAL: u64 = 50;
+
+/// A change to a task recorded in the event log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskChange {
+    /// The task was saved as a whole
+    Snapshot { task: Box<Task> },
+    /// The status of the task changed
+    StatusChanged { status: TaskStatus },
+    /// Messages were appended to the history
+    HistoryAppended { messages: Vec<Message> },
+    /// Artifacts were appended
+    ArtifactsAppended { artifacts: Vec<Artifact> },
+    /// Status transitions were appended to the state transition history
+    StateTransitionsAppended { transitions: Vec<TaskStateTransition> },
+    /// The metadata was replaced
+    MetadataReplaced { metadata: Option<HashMap<String, serde_json::Value>> },
+    /// The task was deleted
+    Deleted,
+}
+
+impl TaskChange {
+    /// Applies the change to a task
+    ///
+    /// Updates of a task that does not exist are ignored.
+    pub fn apply(&self, task: Option<Task>) -> Option<Task> {
+        let mut task = match self {
+            TaskChange::Snapshot { task } => return Some((**task).clone()),
+            TaskChange::Deleted => return None,
+            _ => task?,
+        };
+        match self {
+            TaskChange::StatusChanged { status } => task.status = status.clone(),
+            TaskChange::HistoryAppended { messages } => {
+                task.history.get_or_insert_with(Vec::new).extend(messages.iter().cloned())
+            }
+            TaskChange::ArtifactsAppended { artifacts } => {
+                task.artifacts.get_or_insert_with(Vec::new).extend(artifacts.iter().cloned())
+            }
+            TaskChange::StateTransitionsAppended { transitions } => task
+                .state_transitions
+                .get_or_insert_with(Vec::new)
+                .extend(transitions.iter().cloned()),
+            TaskChange::MetadataReplaced { metadata } => task.metadata = metadata.clone(),
+            TaskChange::Snapshot { .. } | TaskChange::Deleted => {}
+        }
+        Some(task)
+    }
+
+    /// The change without the content `erased_task` drops: messages,
+    /// artifacts and metadata
+    pub fn erased(&self, erased_at: DateTime<Utc>) -> TaskChange {
+        match self {
+            TaskChange::Snapshot { task } => TaskChange::Snapshot {
+                task: Box::new(erased_task(task, erased_at)),
+            },
+            TaskChange::StatusChanged { status } => TaskChange::StatusChanged {
+                status: TaskStatus {
+                    message: None,
+                    ..status.clone()
+                },
+            },
+            TaskChange::HistoryAppended { .. } => TaskChange::HistoryAppended { messages: Vec::new() },
+            TaskChange::ArtifactsAppended { .. } => TaskChange::ArtifactsAppended { artifacts: Vec::new() },
+            TaskChange::StateTransitionsAppended { transitions } => TaskChange::StateTransitionsAppended {
+                transitions: transitions
+                    .iter()
+                    .cloned()
+                    .map(|transition| TaskStateTransition { message: None, ..transition })
+                    .collect(),
+            },
+            TaskChange::MetadataReplaced { .. } => TaskChange::MetadataReplaced { metadata: None },
+            TaskChange::Deleted => TaskChange::Deleted,
+        }
+    }
+
+    /// The changes turning `previous` into `task`
+    ///
+    /// Falls back to a snapshot when the difference is not a sequence of
+    /// updates, such as a history that was rewritten rather than appended to.
+    pub fn diff(previous: Option<&Task>, task: &Task) -> Vec<TaskChange> {
+        let snapshot = || vec![TaskChange::Snapshot { task: Box::new(task.clone()) }];
+        let Some(previous) = previous else {
+            return snapshot();
+        };
+        if previous == task {
+            return Vec::new();
+        }
+        if previous.context_id != task.context_id || previous.kind != task.kind {
+            return snapshot();
+        }
+
+        let mut changes = Vec::new();
+        match appended(&previous.history, &task.history) {
+            Some(messages) if messages.is_empty() => {}
+            Some(messages) => changes.push(TaskChange::HistoryAppended { messages }),
+            None => return snapshot(),
+        }
+        match appended(&previous.artifacts, &task.artifacts) {
+            Some(artifacts) if artifacts.is_empty() => {}
+            Some(artifacts) => changes.push(TaskChange::ArtifactsAppended { artifacts }),
+            None => return snapshot(),
+        }
+        if previous.status != task.status {
+            changes.push(TaskChange::StatusChanged { status: task.status.clone() });
+        }
+        // A trimmed history is not an extension, so it is saved as a snapshot
+        match appended(&previous.state_transitions, &task.state_transitions) {
+            Some(transitions) if transitions.is_empty() => {}
+            Some(transitions) => changes.push(TaskChange::StateTransitionsAppended { transitions }),
+            None => return snapshot(),
+        }
+        if previous.metadata != task.metadata {
+            changes.push(TaskChange::MetadataReplaced { metadata: task.metadata.clone() });
+        }
+
+        // Guard against differences the updates cannot express
+        let mut replayed = Some(previous.clone());
+        for change in &changes {
+            replayed = change.apply(replayed);
+        }
+        if replayed.as_ref() != Some(task) {
+            return snapshot();
+        }
+        changes
+    }
+}
+
+/// The items appended to `previous` to get `current`, if it is an extension
+fn appended<T: PartialEq + Clone>(previous: &Option<Vec<T>>, current: &Option<Vec<T>>) -> Option<Vec<T>> {
+    let previous = previous.as_deref().unwrap_or_default();
+    let current = current.as_deref().unwrap_or_default();
+    current.starts_with(previous).then(|| current[previous.len()..].to_vec())
+}
+
+/// An entry of the task event log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskLogEntry {
+    pub task_id: String,
+    /// Position of the change among the changes of the task, starting at 1
+    pub version: u64,
+    pub recorded_at: DateTime<Utc>,
+    pub change: TaskChange,
+}
+
+/// Append-only storage of task changes
+#[async_trait]
+pub trait TaskEventLog: Send + Sync {
+    /// Appends entries, all or none
+    async fn append(&self, entries: &[TaskLogEntry]) -> Result<(), A2AError>;
+
+    /// Returns the entries of a task with a version above `after_version`, oldest first
+    async fn entries(&self, task_id: &str, after_version: u64) -> Result<Vec<TaskLogEntry>, A2AError>;
+
+    /// Returns the IDs of all tasks with entries, in the order they first appeared
+    async fn task_ids(&self) -> Result<Vec<String>, A2AError>;
+
+    /// Replaces all entries of a task, to erase their content (optional implementation)
+    async fn rewrite(&self, _task_id: &str, _entries: Vec<TaskLogEntry>) -> Result<(), A2AError> {
+        Err(A2AError::unsupported_operation("Rewriting the task event log not supported"))
+    }
+}
+
+/// Entries indexed by task, in the order tasks first appeared
+#[derive(Debug, Clone, Default)]
+struct LogIndex {
+    order: Vec<String>,
+    entries: HashMap<String, Vec<TaskLogEntry>>,
+}
+
+impl LogIndex {
+    fn insert(&mut self, entry: TaskLogEntry) {
+        if !self.entries.contains_key(&entry.task_id) {
+            self.order.push(entry.task_id.clone());
+        }
+        self.entries.entry(entry.task_id.clone()).or_default().push(entry);
+    }
+
+    fn replace(&mut self, task_id: &str, entries: Vec<TaskLogEntry>) {
+        if let Some(stored) = self.entries.get_mut(task_id) {
+            *stored = entries;
+        }
+    }
+
+    fn entries(&self, task_id: &str, after_version: u64) -> Vec<TaskLogEntry> {
+        self.entries
+            .get(task_id)
+            .map(|entries| entries.iter().filter(|entry| entry.version > after_version).cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Event log held in process memory
+#[derive(Debug, Default)]
+pub struct InMemoryTaskEventLog {
+    index: Mutex<LogIndex>,
+}
+
+impl InMemoryTaskEventLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl TaskEventLog for InMemoryTaskEventLog {
+    async fn append(&self, entries: &[TaskLogEntry]) -> Result<(), A2AError> {
+        let mut index = self.index.lock().await;
+        for entry in entries {
+            index.insert(entry.clone());
+        }
+        Ok(())
+    }
+
+    async fn entries(&self, task_id: &str, after_version: u64) -> Result<Vec<TaskLogEntry>, A2AError> {
+        Ok(self.index.lock().await.entries(task_id, after_version))
+    }
+
+    async fn task_ids(&self) -> Result<Vec<String>, A2AError> {
+        Ok(self.index.lock().await.order.clone())
+    }
+
+    async fn rewrite(&self, task_id: &str, entries: Vec<TaskLogEntry>) -> Result<(), A2AError> {
+        self.index.lock().await.replace(task_id, entries);
+        Ok(())
+    }
+}
+
+/// Event log in a file of JSON lines, one entry per line
+///
+/// The file is read into memory when opened and appended to on every write.
+pub struct FileTaskEventLog {
+    path: PathBuf,
+    state: Mutex<(tokio::fs::File, LogIndex)>,
+}
+
+impl FileTaskEventLog {
+    /// Opens the log at `path`, creating the file if it does not exist
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self, A2AError> {
+        let path = path.as_ref().to_path_buf();
+        let mut index = LogIndex::default();
+        match tokio::fs::read_to_string(&path).await {
+            Ok(contents) => {
+                for (number, line) in contents.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+                    let entry = serde_json::from_str(line).map_err(|e| {
+                        A2AError::internal(&format!("Invalid entry on line {} of {}: {}", number + 1, path.display(), e))
+                    })?;
+                    index.insert(entry);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(A2AError::internal(&format!("Failed to read {}: {}", path.display(), e))),
+        }
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to open {}: {}", path.display(), e)))?;
+        Ok(Self {
+            path,
+            state: Mutex::new((file, index)),
+        })
+    }
+}
+
+#[async_trait]
+impl TaskEventLog for FileTaskEventLog {
+    async fn append(&self, entries: &[TaskLogEntry]) -> Result<(), A2AError> {
+        let mut lines = String::new();
+        for entry in entries {
+            lines.push_str(&serde_json::to_string(entry)?);
+            lines.push('\n');
+        }
+        let mut state = self.state.lock().await;
+        let (file, index) = &mut *state;
+        file.write_all(lines.as_bytes())
+            .await
+            .and(file.sync_data().await)
+            .map_err(|e| A2AError::internal(&format!("Failed to append to {}: {}", self.path.display(), e)))?;
+        for entry in entries {
+            index.insert(entry.clone());
+        }
+        Ok(())
+    }
+
+    async fn entries(&self, task_id: &str, after_version: u64) -> Result<Vec<TaskLogEntry>, A2AError> {
+        Ok(self.state.lock().await.1.entries(task_id, after_version))
+    }
+
+    async fn task_ids(&self) -> Result<Vec<String>, A2AError> {
+        Ok(self.state.lock().await.1.order.clone())
+    }
+
+    /// Writes the whole log to a temporary file replacing the log file
+    async fn rewrite(&self, task_id: &str, entries: Vec<TaskLogEntry>) -> Result<(), A2AError> {
+        let mut state = self.state.lock().await;
+        let (file, index) = &mut *state;
+        let mut rewritten = index.clone();
+        rewritten.replace(task_id, entries);
+        let mut lines = String::new();
+        for entry in rewritten.order.iter().flat_map(|task_id| rewritten.entries(task_id, 0)) {
+            lines.push_str(&serde_json::to_string(&entry)?);
+            lines.push('\n');
+        }
+
+        let io_error = |e: std::io::Error| A2AError::internal(&format!("Failed to rewrite {}: {}", self.path.display(), e));
+        let temporary = self.path.with_extension("rewrite");
+        let mut temporary_file = tokio::fs::File::create(&temporary).await.map_err(io_error)?;
+        temporary_file.write_all(lines.as_bytes()).await.map_err(io_error)?;
+        temporary_file.sync_data().await.map_err(io_error)?;
+        tokio::fs::rename(&temporary, &self.path).await.map_err(io_error)?;
+        *file = tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(io_error)?;
+        *index = rewritten;
+        Ok(())
+    }
+}
+
+/// A materialized task and the version of the last change it includes
+#[derive(Debug, Clone, Default)]
+struct Snapshot {
+    version: u64,
+    task: Option<Task>,
+}
+
+/// Task store whose source of truth is a `TaskEventLog`
+pub struct EventSourcedTaskStore {
+    log: Arc<dyn TaskEventLog>,
+    snapshots: Mutex<HashMap<String, Snapshot>>,
+    snapshot_interval: u64,
+}
+
+impl EventSourcedTaskStore {
+    /// Creates a store over `log`, materializing a snapshot every
+    /// `DEFAULT_SNAPSHOT_INTERVAL` changes
+    pub fn new(log: Arc<dyn TaskEventLog>) -> Self {
+        Self {
+            log,
+            snapshots: Mutex::new(HashMap::new()),
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+        }
+    }
+
+    /// Creates a store over an in-memory log
+    pub fn in_memory() -> Self {
+        Self::new(Arc::new(InMemoryTaskEventLog::new()))
+    }
+
+    /// Sets after how many changes a task snapshot is materialized
+    pub fn with_snapshot_interval(mut self, snapshot_interval: u64) -> Self {
+        self.snapshot_interval = snapshot_interval.max(1);
+        self
+    }
+
+    /// Returns every change recorded for a task, oldest first
+    pub async fn history(&self, task_id: &str) -> Result<Vec<TaskLogEntry>, A2AError> {
+        self.log.entries(task_id, 0).await
+    }
+
+    /// Reconstructs a task as it was after the change with `version`
+    pub async fn task_at_version(&self, task_id: &str, version: u64) -> Result<Option<Task>, A2AError> {
+        Ok(self
+            .history(task_id)
+            .await?
+            .iter()
+            .take_while(|entry| entry.version <= version)
+            .fold(None, |task, entry| entry.change.apply(task)))
+    }
+
+    /// Discards all snapshots and reconstructs every task from the log
+    ///
+    /// Returns the tasks that were not deleted, in the order they first
+    /// appeared in the log.
+    pub async fn rebuild(&self) -> Result<Vec<Task>, A2AError> {
+        let mut snapshots = self.snapshots.lock().await;
+        snapshots.clear();
+        let mut tasks = Vec::new();
+        for task_id in self.log.task_ids().await? {
+            let snapshot = self.materialize(&mut snapshots, &task_id).await?;
+            snapshots.insert(task_id, snapshot.clone());
+            tasks.extend(snapshot.task);
+        }
+        Ok(tasks)
+    }
+
+    /// Replays the changes after the task's snapshot, taking a new snapshot
+    /// once enough of them accumulated
+    async fn materialize(&self, snapshots: &mut HashMap<String, Snapshot>, task_id: &str) -> Result<Snapshot, A2AError> {
+        let base = snapshots.get(task_id).cloned().unwrap_or_default();
+        let tail = self.log.entries(task_id, base.version).await?;
+        let current = tail.iter().fold(base.clone(), |snapshot, entry| Snapshot {
+            version: entry.version,
+            task: entry.change.apply(snapshot.task),
+        });
+        if current.version - base.version >= self.snapshot_interval {
+            snapshots.insert(task_id.to_string(), current.clone());
+        }
+        Ok(current)
+    }
+
+    /// Records the changes of `tasks`, in one append
+    async fn record(&self, tasks: Vec<Task>) -> Result<(), A2AError> {
+        let mut snapshots = self.snapshots.lock().await;
+        let mut pending: HashMap<String, Snapshot> = HashMap::new();
+        let mut entries = Vec::new();
+        for task in tasks {
+            let current = match pending.remove(&task.id) {
+                Some(current) => current,
+                None => self.materialize(&mut snapshots, &task.id).await?,
+            };
+            let mut version = current.version;
+            for change in TaskChange::diff(current.task.as_ref(), &task) {
+                version += 1;
+                entries.push(TaskLogEntry {
+                    task_id: task.id.clone(),
+                    version,
+                    recorded_at: Utc::now(),
+                    change,
+                });
+            }
+            pending.insert(task.id.clone(), Snapshot { version, task: Some(task) });
+        }
+        self.log.append(&entries).await
+    }
+
+    async fn current(&self, task_id: &str) -> Result<Option<Task>, A2AError> {
+        let mut snapshots = self.snapshots.lock().await;
+        Ok(self.materialize(&mut snapshots, task_id).await?.task)
+    }
+}
+
+#[async_trait]
+impl TaskStore for EventSourcedTaskStore {
+    async fn save(&self, task: Task) -> Result<(), A2AError> {
+        self.record(vec![task]).await
+    }
+
+    async fn get(&self, task_id: &str) -> Result<Option<Task>, A2AError> {
+        self.current(task_id).await
+    }
+
+    async fn delete(&self, task_id: &str) -> Result<(), A2AError> {
+        let mut snapshots = self.snapshots.lock().await;
+        let current = self.materialize(&mut snapshots, task_id).await?;
+        if current.task.is_none() {
+            return Ok(());
+        }
+        self.log
+            .append(&[TaskLogEntry {
+                task_id: task_id.to_string(),
+                version: current.version + 1,
+                recorded_at: Utc::now(),
+                change: TaskChange::Deleted,
+            }])
+            .await
+    }
+
+    /// Records all saves in a single append to the log
+    async fn save_many(&self, tasks: Vec<Task>) -> Result<(), A2AError> {
+        self.record(tasks).await
+    }
+
+    async fn list(&self) -> Result<Vec<Task>, A2AError> {
+        let mut tasks = Vec::new();
+        for task_id in self.log.task_ids().await? {
+            tasks.extend(self.current(&task_id).await?);
+        }
+        Ok(tasks)
+    }
+
+    async fn list_by_context(&self, context_id: &str) -> Result<Vec<Task>, A2AError> {
+        Ok(self.list().await?.into_iter().filter(|task| task.context_id == context_id).collect())
+    }
+
+    /// Rewrites the recorded changes of the tasks without their content,
+    /// then records the audit stubs of the tasks not deleted
+    async fn erase(&self, task_ids: &[String]) -> Result<usize, A2AError> {
+        let erased_at = Utc::now();
+        let mut stubs = Vec::new();
+        let mut erased = 0;
+        {
+            let mut snapshots = self.snapshots.lock().await;
+            for task_id in task_ids {
+                let entries = self.log.entries(task_id, 0).await?;
+                if entries.is_empty() {
+                    continue;
+                }
+                let entries = entries
+                    .into_iter()
+                    .map(|entry| TaskLogEntry {
+                        change: entry.change.erased(erased_at),
+                        ..entry
+                    })
+                    .collect();
+                self.log.rewrite(task_id, entries).await?;
+                snapshots.remove(task_id);
+                if let Some(task) = self.materialize(&mut snapshots, task_id).await?.task {
+                    stubs.push(erased_task(&task, erased_at));
+                }
+                erased += 1;
+            }
+        }
+        self.record(stubs).await?;
+        Ok(erased)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Part, Role, TaskState};
+
+    fn task(state: TaskState) -> Task {
+        Task::new("ctx".to_string(), TaskStatus::new(state)).with_task_id("task-1".to_string())
+    }
+
+    #[test]
+    fn test_diff_records_updates() {
+        let submitted = task(TaskState::Submitted);
+        let mut working = submitted.clone();
+        working.status = TaskStatus::new(TaskState::Working);
+        working.history = Some(vec![Message::new(Role::User, vec![Part::text("hi".to_string())])]);
+        working.artifacts = Some(vec![Artifact::new(vec![Part::text("out".to_string())])]);
+
+        let changes = TaskChange::diff(Some(&submitted), &working);
+        assert!(matches!(
+            changes.as_slice(),
+            [TaskChange::HistoryAppended { .. }, TaskChange::ArtifactsAppended { .. }, TaskChange::StatusChanged { .. }]
+        ));
+        assert!(TaskChange::diff(Some(&working), &working).is_empty());
+
+        // A rewritten history is recorded as a snapshot
+        let mut rewritten = working.clone();
+        rewritten.history = Some(Vec::new());
+        assert!(matches!(
+            TaskChange::diff(Some(&working), &rewritten).as_slice(),
+            [TaskChange::Snapshot { .. }]
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_tasks_are_materialized_from_the_log() {
+        let store = EventSourcedTaskStore::in_memory().with_snapshot_interval(2);
+        for state in [TaskState::Submitted, TaskState::Working, TaskState::InputRequired, TaskState::Completed] {
+            store.save(task(state)).await.unwrap();
+        }
+        assert_eq!(store.get("task-1").await.unwrap().unwrap().status.state, TaskState::Completed);
+
+        let history = store.history("task-1").await.unwrap();
+        assert_eq!(history.len(), 4);
+        assert!(matches!(history[0].change, TaskChange::Snapshot { .. }));
+        assert!(matches!(history[3].change, TaskChange::StatusChanged { .. }));
+        let earlier = store.task_at_version("task-1", 2).await.unwrap().unwrap();
+        assert_eq!(earlier.status.state, TaskState::Working);
+
+        store.delete("task-1").await.unwrap();
+        assert!(store.get("task-1").await.unwrap().is_none());
+        assert!(store.list().await.unwrap().is_empty());
+        assert_eq!(store.history("task-1").await.unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_erase_rewrites_the_log() {
+        let path = std::env::temp_dir().join(format!("a2a-events-{}.jsonl", uuid::Uuid::new_v4()));
+        let store = EventSourcedTaskStore::new(Arc::new(FileTaskEventLog::open(&path).await.unwrap()));
+        let mut working = task(TaskState::Working);
+        working.history = Some(vec![Message::new(Role::User, vec![Part::text("my phone number".to_string())])]);
+        store.save(task(TaskState::Submitted)).await.unwrap();
+        store.save(working.clone()).await.unwrap();
+        store.save(Task { id: "task-2".to_string(), ..working }).await.unwrap();
+        store.delete("task-2").await.unwrap();
+
+        let ids = ["task-1".to_string(), "task-2".to_string()];
+        assert_eq!(store.erase(&ids).await.unwrap(), 2);
+        let stub = store.get("task-1").await.unwrap().unwrap();
+        assert_eq!(stub.status.state, TaskState::Working);
+        assert!(stub.history.is_none());
+        assert!(store.get("task-2").await.unwrap().is_none());
+
+        // Nothing of the content is left in the file, and the log still opens
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("phone"));
+        let reopened = EventSourcedTaskStore::new(Arc::new(FileTaskEventLog::open(&path).await.unwrap()));
+        assert_eq!(reopened.rebuild().await.unwrap(), vec![stub]);
+        assert!(reopened.history("task-1").await.unwrap().len() > 2);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_from_file_log() {
+        let path = std::env::temp_dir().join(format!("a2a-events-{}.jsonl", uuid::Uuid::new_v4()));
+        {
+            let store = EventSourcedTaskStore::new(Arc::new(FileTaskEventLog::open(&path).await.unwrap()));
+            store.save(task(TaskState::Submitted)).await.unwrap();
+            let other = Task::new("ctx-2".to_string(), TaskStatus::new(TaskState::Working)).with_task_id("task-2".to_string());
+            store.save_many(vec![task(TaskState::Working), other]).await.unwrap();
+        }
+
+        let store = EventSourcedTaskStore::new(Arc::new(FileTaskEventLog::open(&path).await.unwrap()));
+        let tasks = store.rebuild().await.unwrap();
+        assert_eq!(tasks.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), vec!["task-1", "task-2"]);
+        assert_eq!(tasks[0].status.state, TaskState::Working);
+        assert_eq!(store.list_by_context("ctx-2").await.unwrap().len(), 1);
+        std::fs::remove_file(path).unwrap();
+    }
+}
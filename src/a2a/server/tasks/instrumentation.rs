@@ -0,0 +1,406 @@
+//! Instrumentation of task and push notification config stores
+//!
+//! `StoreInstrumentation` wraps store implementations in decorators that
+//! time every operation. Latencies are collected in per-operation histograms,
+//! failures are counted by error kind, and operations slower than a threshold
+//! are logged to the `a2a::store` tracing target. The decorators forward to
+//! the wrapped store unchanged, so any implementation can be instrumented.
+
+use crate::a2a::server::tasks::labels::{LabelSelector, Labels};
+use crate::a2a::server::tasks::push_notification_config_store::PushNotificationConfigStore;
+use crate::a2a::server::tasks::task_store::{DeletedTask, HistoryPage, ReadConsistency, TaskStore, TaskStoreTransaction};
+use crate::a2a::server::tasks::search::TaskSearchFilter;
+use crate::a2a::server::tasks::usage::{Usage, UsageRecord};
+use crate::{A2AError, PushNotificationConfig, Task};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Upper bounds in milliseconds of the latency histogram buckets
+///
+/// Operations slower than the last bound are counted in an extra bucket.
+pub const LATENCY_BUCKETS_MS: [u64; 10] = [1, 5, 10, 25, 50, 100, 250, 500, 1000, 5000];
+
+/// Latency and error counters of one store operation
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct OperationMetrics {
+    /// Number of calls, successful or not
+    pub calls: u64,
+    /// Number of calls per bucket of `LATENCY_BUCKETS_MS`, plus one for slower calls
+    pub latency_buckets: Vec<u64>,
+    /// Sum of the latencies of all calls, in microseconds
+    pub total_latency_micros: u64,
+    /// Number of calls slower than the slow operation threshold
+    pub slow_calls: u64,
+    /// Number of failed calls per error kind
+    pub errors_by_kind: HashMap<String, u64>,
+}
+
+impl OperationMetrics {
+    /// Number of failed calls
+    pub fn errors(&self) -> u64 {
+        self.errors_by_kind.values().sum()
+    }
+
+    fn record(&mut self, elapsed: Duration, slow: bool, error: Option<&A2AError>) {
+        if self.latency_buckets.is_empty() {
+            self.latency_buckets = vec![0; LATENCY_BUCKETS_MS.len() + 1];
+        }
+        let millis = elapsed.as_millis();
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| millis <= u128::from(*bound))
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.calls += 1;
+        self.latency_buckets[bucket] += 1;
+        self.total_latency_micros += elapsed.as_micros() as u64;
+        if slow {
+            self.slow_calls += 1;
+        }
+        if let Some(error) = error {
+            *self.errors_by_kind.entry(error.kind().to_string()).or_insert(0) += 1;
+        }
+    }
+}
+
+/// A snapshot of the counters collected by `StoreInstrumentation`
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct StoreMetrics {
+    /// Counters per operation, keyed by `<store>.<operation>`
+    pub operations: BTreeMap<String, OperationMetrics>,
+}
+
+impl StoreMetrics {
+    /// The counters of `operation` on `store`, if it was called
+    pub fn operation(&self, store: &str, operation: &str) -> Option<&OperationMetrics> {
+        self.operations.get(&format!("{}.{}", store, operation))
+    }
+}
+
+/// Collects store metrics and wraps stores to feed them
+///
+/// Clones share their counters, so one instrumentation can wrap every
+/// store of a server.
+#[derive(Debug, Clone, Default)]
+pub struct StoreInstrumentation {
+    metrics: Arc<Mutex<StoreMetrics>>,
+    slow_threshold: Option<Duration>,
+}
+
+impl StoreInstrumentation {
+    /// Creates an instrumentation with zeroed counters that logs no slow operations
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Logs operations taking longer than `threshold` as warnings
+    pub fn with_slow_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_threshold = Some(threshold);
+        self
+    }
+
+    /// Returns a snapshot of the collected counters
+    pub fn snapshot(&self) -> StoreMetrics {
+        self.metrics.lock().unwrap().clone()
+    }
+
+    /// Wraps a task store
+    pub fn task_store(&self, inner: Arc<dyn TaskStore>) -> Arc<dyn TaskStore> {
+        Arc::new(InstrumentedTaskStore::new(inner, self.clone()))
+    }
+
+    /// Wraps a push notification config store
+    pub fn push_config_store(&self, inner: Arc<dyn PushNotificationConfigStore>) -> Arc<dyn PushNotificationConfigStore> {
+        Arc::new(InstrumentedPushNotificationConfigStore::new(inner, self.clone()))
+    }
+
+    /// Runs `operation`, recording its latency and outcome
+    async fn observe<T>(
+        &self,
+        store: &str,
+        operation: &str,
+        future: impl Future<Output = Result<T, A2AError>>,
+    ) -> Result<T, A2AError> {
+        let started = Instant::now();
+        let result = future.await;
+        let elapsed = started.elapsed();
+        let slow = self.slow_threshold.is_some_and(|threshold| elapsed > threshold);
+        if slow {
+            tracing::warn!(
+                target: "a2a::store",
+                store,
+                operation,
+                elapsed_ms = elapsed.as_millis() as u64,
+                "slow store operation"
+            );
+        }
+        self.metrics
+            .lock()
+            .unwrap()
+            .operations
+            .entry(format!("{}.{}", store, operation))
+            .or_default()
+            .record(elapsed, slow, result.as_ref().err());
+        result
+    }
+}
+
+/// Name under which task store operations are recorded
+const TASK_STORE: &str = "task_store";
+
+/// Name under which push notification config store operations are recorded
+const PUSH_CONFIG_STORE: &str = "push_config_store";
+
+/// A task store recording the latency and errors of the store it wraps
+pub struct InstrumentedTaskStore {
+    inner: Arc<dyn TaskStore>,
+    instrumentation: StoreInstrumentation,
+}
+
+impl InstrumentedTaskStore {
+    pub fn new(inner: Arc<dyn TaskStore>, instrumentation: StoreInstrumentation) -> Self {
+        Self { inner, instrumentation }
+    }
+}
+
+#[async_trait]
+impl TaskStore for InstrumentedTaskStore {
+    async fn save(&self, task: Task) -> Result<(), A2AError> {
+        self.instrumentation.observe(TASK_STORE, "save", self.inner.save(task)).await
+    }
+
+    async fn get(&self, task_id: &str) -> Result<Option<Task>, A2AError> {
+        self.instrumentation.observe(TASK_STORE, "get", self.inner.get(task_id)).await
+    }
+
+    async fn delete(&self, task_id: &str) -> Result<(), A2AError> {
+        self.instrumentation.observe(TASK_STORE, "delete", self.inner.delete(task_id)).await
+    }
+
+    async fn get_with_consistency(&self, task_id: &str, consistency: ReadConsistency) -> Result<Option<Task>, A2AError> {
+        self.instrumentation
+            .observe(TASK_STORE, "get", self.inner.get_with_consistency(task_id, consistency))
+            .await
+    }
+
+    async fn get_meta(&self, task_id: &str, consistency: ReadConsistency) -> Result<Option<Task>, A2AError> {
+        self.instrumentation
+            .observe(TASK_STORE, "get_meta", self.inner.get_meta(task_id, consistency))
+            .await
+    }
+
+    async fn get_history(&self, task_id: &str, offset: usize, limit: usize) -> Result<Option<HistoryPage>, A2AError> {
+        self.instrumentation
+            .observe(TASK_STORE, "get_history", self.inner.get_history(task_id, offset, limit))
+            .await
+    }
+
+    async fn save_many(&self, tasks: Vec<Task>) -> Result<(), A2AError> {
+        self.instrumentation.observe(TASK_STORE, "save_many", self.inner.save_many(tasks)).await
+    }
+
+    async fn get_many(&self, task_ids: &[String]) -> Result<Vec<Task>, A2AError> {
+        self.instrumentation.observe(TASK_STORE, "get_many", self.inner.get_many(task_ids)).await
+    }
+
+    async fn delete_many(&self, task_ids: &[String]) -> Result<(), A2AError> {
+        self.instrumentation.observe(TASK_STORE, "delete_many", self.inner.delete_many(task_ids)).await
+    }
+
+    async fn restore(&self, task_id: &str) -> Result<(), A2AError> {
+        self.instrumentation.observe(TASK_STORE, "restore", self.inner.restore(task_id)).await
+    }
+
+    async fn purge(&self, task_id: &str) -> Result<(), A2AError> {
+        self.instrumentation.observe(TASK_STORE, "purge", self.inner.purge(task_id)).await
+    }
+
+    async fn purge_deleted(&self, older_than: DateTime<Utc>) -> Result<u64, A2AError> {
+        self.instrumentation
+            .observe(TASK_STORE, "purge_deleted", self.inner.purge_deleted(older_than))
+            .await
+    }
+
+    async fn list_deleted(&self) -> Result<Vec<DeletedTask>, A2AError> {
+        self.instrumentation.observe(TASK_STORE, "list_deleted", self.inner.list_deleted()).await
+    }
+
+    async fn erase(&self, task_ids: &[String]) -> Result<usize, A2AError> {
+        self.instrumentation.observe(TASK_STORE, "erase", self.inner.erase(task_ids)).await
+    }
+
+    async fn begin(&self) -> Result<Box<dyn TaskStoreTransaction>, A2AError> {
+        self.instrumentation.observe(TASK_STORE, "begin", self.inner.begin()).await
+    }
+
+    async fn list(&self) -> Result<Vec<Task>, A2AError> {
+        self.instrumentation.observe(TASK_STORE, "list", self.inner.list()).await
+    }
+
+    async fn list_with_consistency(&self, consistency: ReadConsistency) -> Result<Vec<Task>, A2AError> {
+        self.instrumentation
+            .observe(TASK_STORE, "list", self.inner.list_with_consistency(consistency))
+            .await
+    }
+
+    async fn list_by_context(&self, context_id: &str) -> Result<Vec<Task>, A2AError> {
+        self.instrumentation
+            .observe(TASK_STORE, "list_by_context", self.inner.list_by_context(context_id))
+            .await
+    }
+
+    async fn set_labels(&self, task_id: &str, labels: Labels) -> Result<(), A2AError> {
+        self.instrumentation
+            .observe(TASK_STORE, "set_labels", self.inner.set_labels(task_id, labels))
+            .await
+    }
+
+    async fn get_labels(&self, task_id: &str) -> Result<Labels, A2AError> {
+        self.instrumentation.observe(TASK_STORE, "get_labels", self.inner.get_labels(task_id)).await
+    }
+
+    async fn list_by_labels(&self, selector: &LabelSelector) -> Result<Vec<Task>, A2AError> {
+        self.instrumentation
+            .observe(TASK_STORE, "list_by_labels", self.inner.list_by_labels(selector))
+            .await
+    }
+
+    async fn search_tasks(&self, query: &str, filter: &TaskSearchFilter) -> Result<Vec<Task>, A2AError> {
+        self.instrumentation
+            .observe(TASK_STORE, "search_tasks", self.inner.search_tasks(query, filter))
+            .await
+    }
+
+    async fn record_usage(&self, task_id: &str, principal: &str, usage: Usage) -> Result<UsageRecord, A2AError> {
+        self.instrumentation
+            .observe(TASK_STORE, "record_usage", self.inner.record_usage(task_id, principal, usage))
+            .await
+    }
+
+    async fn get_usage(&self, task_id: &str) -> Result<Option<UsageRecord>, A2AError> {
+        self.instrumentation.observe(TASK_STORE, "get_usage", self.inner.get_usage(task_id)).await
+    }
+
+    async fn list_usage(&self) -> Result<Vec<UsageRecord>, A2AError> {
+        self.instrumentation.observe(TASK_STORE, "list_usage", self.inner.list_usage()).await
+    }
+
+    async fn context_usage(&self, context_id: &str) -> Result<Usage, A2AError> {
+        self.instrumentation.observe(TASK_STORE, "context_usage", self.inner.context_usage(context_id)).await
+    }
+
+    async fn principal_usage(&self, principal: &str) -> Result<Usage, A2AError> {
+        self.instrumentation.observe(TASK_STORE, "principal_usage", self.inner.principal_usage(principal)).await
+    }
+}
+
+/// A push notification config store recording the latency and errors of the store it wraps
+pub struct InstrumentedPushNotificationConfigStore {
+    inner: Arc<dyn PushNotificationConfigStore>,
+    instrumentation: StoreInstrumentation,
+}
+
+impl InstrumentedPushNotificationConfigStore {
+    pub fn new(inner: Arc<dyn PushNotificationConfigStore>, instrumentation: StoreInstrumentation) -> Self {
+        Self { inner, instrumentation }
+    }
+}
+
+#[async_trait]
+impl PushNotificationConfigStore for InstrumentedPushNotificationConfigStore {
+    async fn set_info(&self, task_id: &str, config: PushNotificationConfig) -> Result<(), A2AError> {
+        self.instrumentation
+            .observe(PUSH_CONFIG_STORE, "set_info", self.inner.set_info(task_id, config))
+            .await
+    }
+
+    async fn get_info(&self, task_id: &str) -> Result<Vec<PushNotificationConfig>, A2AError> {
+        self.instrumentation
+            .observe(PUSH_CONFIG_STORE, "get_info", self.inner.get_info(task_id))
+            .await
+    }
+
+    async fn delete_info(&self, task_id: &str, config_id: Option<&str>) -> Result<(), A2AError> {
+        self.instrumentation
+            .observe(PUSH_CONFIG_STORE, "delete_info", self.inner.delete_info(task_id, config_id))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::server::tasks::task_store::InMemoryTaskStore;
+    use crate::a2a::server::tasks::push_notification_config_store::InMemoryPushNotificationConfigStore;
+    use crate::{TaskState, TaskStatus};
+
+    /// A task store that never finds a task and takes a while to say so
+    struct SlowStore;
+
+    #[async_trait]
+    impl TaskStore for SlowStore {
+        async fn save(&self, _task: Task) -> Result<(), A2AError> {
+            Err(A2AError::internal("disk full"))
+        }
+
+        async fn get(&self, _task_id: &str) -> Result<Option<Task>, A2AError> {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok(None)
+        }
+
+        async fn delete(&self, _task_id: &str) -> Result<(), A2AError> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_records_latency_and_errors() {
+        let instrumentation = StoreInstrumentation::new();
+        let store = instrumentation.task_store(Arc::new(InMemoryTaskStore::new()));
+
+        let task = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Submitted)).with_task_id("task-1".to_string());
+        store.save(task).await.unwrap();
+        assert!(store.get("task-1").await.unwrap().is_some());
+        assert!(store.get("task-2").await.unwrap().is_none());
+        assert!(store.list_by_context("ctx").await.is_ok());
+
+        let metrics = instrumentation.snapshot();
+        let get = metrics.operation("task_store", "get").unwrap();
+        assert_eq!(get.calls, 2);
+        assert_eq!(get.latency_buckets.len(), LATENCY_BUCKETS_MS.len() + 1);
+        assert_eq!(get.latency_buckets.iter().sum::<u64>(), 2);
+        assert_eq!(get.errors(), 0);
+        assert_eq!(metrics.operation("task_store", "save").unwrap().calls, 1);
+        assert!(metrics.operation("task_store", "delete").is_none());
+
+        let configs = instrumentation.push_config_store(Arc::new(InMemoryPushNotificationConfigStore::new()));
+        configs.get_info("task-1").await.unwrap();
+        assert_eq!(instrumentation.snapshot().operation("push_config_store", "get_info").unwrap().calls, 1);
+    }
+
+    #[tokio::test]
+    async fn test_counts_slow_operations_and_error_kinds() {
+        let instrumentation = StoreInstrumentation::new().with_slow_threshold(Duration::from_millis(5));
+        let store = instrumentation.task_store(Arc::new(SlowStore));
+
+        store.get("task-1").await.unwrap();
+        let task = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Submitted));
+        assert!(store.save(task).await.is_err());
+        assert!(store.list().await.is_err());
+
+        let metrics = instrumentation.snapshot();
+        let get = metrics.operation("task_store", "get").unwrap();
+        assert_eq!(get.slow_calls, 1);
+        assert!(get.total_latency_micros >= 20_000);
+        assert_eq!(get.latency_buckets[0], 0);
+
+        let save = metrics.operation("task_store", "save").unwrap();
+        assert_eq!(save.slow_calls, 0);
+        assert_eq!(save.errors_by_kind.get("internal_error"), Some(&1));
+        let list = metrics.operation("task_store", "list").unwrap();
+        assert_eq!(list.errors_by_kind.get("unsupported_operation"), Some(&1));
+    }
+}
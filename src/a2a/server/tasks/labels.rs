@@ -0,0 +1,267 @@
+//! Task labels and label selectors
+//!
+//! Labels are short string key/value pairs attached to a task at the store level,
+//! separate from the task's free-form `metadata`. They are not part of the A2A wire
+//! format; operators use them to slice stored tasks by customer, environment or
+//! workflow. Selectors follow the familiar Kubernetes syntax:
+//!
+//! - `env=prod` / `env==prod` - the label is present with the value
+//! - `env!=prod` - the label is absent or has a different value
+//! - `env in (prod,staging)` / `env notin (dev)` - set membership
+//! - `env` / `!env` - the label is present / absent
+//!
+//! Requirements are comma separated and must all match.
+
+use crate::A2AError;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Labels attached to a task
+pub type Labels = HashMap<String, String>;
+
+/// A single requirement of a label selector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LabelRequirement {
+    /// The label is present with the given value
+    Equals(String, String),
+    /// The label is absent or has a different value
+    NotEquals(String, String),
+    /// The label is present with one of the given values
+    In(String, Vec<String>),
+    /// The label is absent or has none of the given values
+    NotIn(String, Vec<String>),
+    /// The label is present
+    Exists(String),
+    /// The label is absent
+    NotExists(String),
+}
+
+impl LabelRequirement {
+    /// Returns the label key this requirement applies to
+    pub fn key(&self) -> &str {
+        match self {
+            LabelRequirement::Equals(key, _)
+            | LabelRequirement::NotEquals(key, _)
+            | LabelRequirement::In(key, _)
+            | LabelRequirement::NotIn(key, _)
+            | LabelRequirement::Exists(key)
+            | LabelRequirement::NotExists(key) => key,
+        }
+    }
+
+    /// Checks the requirement against a set of labels
+    pub fn matches(&self, labels: &Labels) -> bool {
+        match self {
+            LabelRequirement::Equals(key, value) => labels.get(key) == Some(value),
+            LabelRequirement::NotEquals(key, value) => labels.get(key) != Some(value),
+            LabelRequirement::In(key, values) => labels.get(key).is_some_and(|v| values.contains(v)),
+            LabelRequirement::NotIn(key, values) => !labels.get(key).is_some_and(|v| values.contains(v)),
+            LabelRequirement::Exists(key) => labels.contains_key(key),
+            LabelRequirement::NotExists(key) => !labels.contains_key(key),
+        }
+    }
+}
+
+impl fmt::Display for LabelRequirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LabelRequirement::Equals(key, value) => write!(f, "{}={}", key, value),
+            LabelRequirement::NotEquals(key, value) => write!(f, "{}!={}", key, value),
+            LabelRequirement::In(key, values) => write!(f, "{} in ({})", key, values.join(",")),
+            LabelRequirement::NotIn(key, values) => write!(f, "{} notin ({})", key, values.join(",")),
+            LabelRequirement::Exists(key) => write!(f, "{}", key),
+            LabelRequirement::NotExists(key) => write!(f, "!{}", key),
+        }
+    }
+}
+
+/// A conjunction of label requirements
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LabelSelector {
+    /// The requirements, all of which must match
+    pub requirements: Vec<LabelRequirement>,
+}
+
+impl LabelSelector {
+    /// Creates an empty selector, which matches every task
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a requirement to the selector
+    pub fn with_requirement(mut self, requirement: LabelRequirement) -> Self {
+        self.requirements.push(requirement);
+        self
+    }
+
+    /// Requires the label to be present with the given value
+    pub fn with_equals(self, key: &str, value: &str) -> Self {
+        self.with_requirement(LabelRequirement::Equals(key.to_string(), value.to_string()))
+    }
+
+    /// Requires the label to be present
+    pub fn with_exists(self, key: &str) -> Self {
+        self.with_requirement(LabelRequirement::Exists(key.to_string()))
+    }
+
+    /// Parses a selector string such as `env=prod,tier in (gold,silver),!legacy`
+    pub fn parse(selector: &str) -> Result<Self, A2AError> {
+        let mut requirements = Vec::new();
+        for raw in split_requirements(selector) {
+            let raw = raw.trim();
+            if raw.is_empty() {
+                continue;
+            }
+            requirements.push(parse_requirement(raw)?);
+        }
+        Ok(Self { requirements })
+    }
+
+    /// Returns true if the selector has no requirements
+    pub fn is_empty(&self) -> bool {
+        self.requirements.is_empty()
+    }
+
+    /// Checks the selector against a set of labels
+    pub fn matches(&self, labels: &Labels) -> bool {
+        self.requirements.iter().all(|r| r.matches(labels))
+    }
+}
+
+impl fmt::Display for LabelSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.requirements.iter().map(|r| r.to_string()).collect();
+        write!(f, "{}", parts.join(","))
+    }
+}
+
+/// Validates a label key and value
+///
+/// Keys must be non-empty and consist of ASCII alphanumerics, `-`, `_`, `.` or `/`.
+/// Values may be empty but follow the same character rules.
+pub fn validate_label(key: &str, value: &str) -> Result<(), A2AError> {
+    let valid_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/');
+    if key.is_empty() || !key.chars().all(valid_char) {
+        return Err(A2AError::invalid_params(&format!("Invalid label key: '{}'", key)));
+    }
+    if !value.chars().all(valid_char) {
+        return Err(A2AError::invalid_params(&format!(
+            "Invalid value for label '{}': '{}'",
+            key, value
+        )));
+    }
+    Ok(())
+}
+
+/// Validates every entry of a label map
+pub fn validate_labels(labels: &Labels) -> Result<(), A2AError> {
+    labels.iter().try_for_each(|(key, value)| validate_label(key, value))
+}
+
+/// Splits a selector on commas that are not inside parentheses
+fn split_requirements(selector: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in selector.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&selector[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&selector[start..]);
+    parts
+}
+
+fn parse_requirement(raw: &str) -> Result<LabelRequirement, A2AError> {
+    let invalid = || A2AError::invalid_params(&format!("Invalid label selector: '{}'", raw));
+
+    if let Some((key, value)) = raw.split_once("!=") {
+        let (key, value) = (key.trim(), value.trim());
+        validate_label(key, value)?;
+        return Ok(LabelRequirement::NotEquals(key.to_string(), value.to_string()));
+    }
+    if let Some((key, value)) = raw.split_once("==").or_else(|| raw.split_once('=')) {
+        let (key, value) = (key.trim(), value.trim());
+        validate_label(key, value)?;
+        return Ok(LabelRequirement::Equals(key.to_string(), value.to_string()));
+    }
+    if let Some(key) = raw.strip_prefix('!') {
+        let key = key.trim();
+        validate_label(key, "")?;
+        return Ok(LabelRequirement::NotExists(key.to_string()));
+    }
+    if let Some(open) = raw.find('(') {
+        let close = raw.rfind(')').filter(|&c| c > open && c == raw.len() - 1).ok_or_else(invalid)?;
+        let head: Vec<&str> = raw[..open].split_whitespace().collect();
+        let values: Vec<String> = raw[open + 1..close]
+            .split(',')
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .collect();
+        let [key, operator] = head.as_slice() else {
+            return Err(invalid());
+        };
+        for value in &values {
+            validate_label(key, value)?;
+        }
+        return match *operator {
+            "in" => Ok(LabelRequirement::In(key.to_string(), values)),
+            "notin" => Ok(LabelRequirement::NotIn(key.to_string(), values)),
+            _ => Err(invalid()),
+        };
+    }
+
+    validate_label(raw, "")?;
+    Ok(LabelRequirement::Exists(raw.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(pairs: &[(&str, &str)]) -> Labels {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_parse_selector() {
+        let selector = LabelSelector::parse("env=prod, tier in (gold, silver),!legacy,team,region!=eu").unwrap();
+        assert_eq!(
+            selector.requirements,
+            vec![
+                LabelRequirement::Equals("env".to_string(), "prod".to_string()),
+                LabelRequirement::In("tier".to_string(), vec!["gold".to_string(), "silver".to_string()]),
+                LabelRequirement::NotExists("legacy".to_string()),
+                LabelRequirement::Exists("team".to_string()),
+                LabelRequirement::NotEquals("region".to_string(), "eu".to_string()),
+            ]
+        );
+        assert_eq!(selector.to_string(), "env=prod,tier in (gold,silver),!legacy,team,region!=eu");
+        assert!(LabelSelector::parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_invalid_selector() {
+        assert!(LabelSelector::parse("env=pr od").is_err());
+        assert!(LabelSelector::parse("tier maybe (gold)").is_err());
+        assert!(LabelSelector::parse("=prod").is_err());
+    }
+
+    #[test]
+    fn test_selector_matches() {
+        let task_labels = labels(&[("env", "prod"), ("tier", "gold"), ("team", "search")]);
+
+        assert!(LabelSelector::parse("env=prod,team").unwrap().matches(&task_labels));
+        assert!(LabelSelector::parse("tier notin (free),!legacy").unwrap().matches(&task_labels));
+        assert!(LabelSelector::parse("region!=eu").unwrap().matches(&task_labels));
+        assert!(!LabelSelector::parse("env=staging").unwrap().matches(&task_labels));
+        assert!(!LabelSelector::parse("tier in (silver)").unwrap().matches(&task_labels));
+        assert!(LabelSelector::new().matches(&Labels::new()));
+    }
+}
@@ -3,16 +3,45 @@
 //! This module provides task management functionality including storage,
 //! lifecycle management, and status tracking.
 
+pub mod callback_token;
+pub mod data_subject;
+pub mod labels;
+pub mod usage;
+pub mod search;
+pub mod retention;
 pub mod task_store;
 pub mod task_manager;
+pub mod state_transitions;
 pub mod sql_task_store;
+mod column_compression;
 pub mod push_notification_config_store;
 pub mod sql_push_notification_config_store;
+#[cfg(feature = "email")]
+pub mod email_sender;
+pub mod notification_payload;
 pub mod push_notification_sender;
+pub mod push_dispatcher;
+pub mod outbox;
+pub mod instrumentation;
+pub mod event_sourced_store;
 
+pub use callback_token::*;
+pub use data_subject::*;
+pub use labels::*;
+pub use usage::*;
+pub use search::*;
+pub use retention::*;
 pub use task_store::*;
 pub use task_manager::*;
+pub use state_transitions::*;
 pub use sql_task_store::*;
 pub use push_notification_config_store::*;
 pub use sql_push_notification_config_store::*;
+#[cfg(feature = "email")]
+pub use email_sender::*;
+pub use notification_payload::*;
 pub use push_notification_sender::*;
+pub use push_dispatcher::*;
+pub use outbox::*;
+pub use instrumentation::*;
+pub use event_sourced_store::*;
@@ -0,0 +1,154 @@
+//! Push notification payload formats
+//!
+//! By default a push notification carries the full `Task` as JSON. Receivers
+//! that only need to know that something happened can ask for the slim format,
+//! and event-driven platforms can receive a CloudEvents envelope. The format is
+//! chosen per server by giving `HttpPushNotificationSender` a formatter, and can
+//! be overridden per config with the `payload_format` extension field.
+
+use crate::a2a::utils::cloudevents::{
+    IntoCloudEvent, ARTIFACT_UPDATE_EVENT_TYPE, CLOUDEVENTS_CONTENT_TYPE, STATUS_UPDATE_EVENT_TYPE, TASK_EVENT_TYPE,
+};
+use crate::{A2AError, NotificationEventKind, NotificationPayloadFormat, Task};
+use serde_json::json;
+use std::sync::Arc;
+
+/// Default CloudEvents `source` attribute
+pub const DEFAULT_CLOUDEVENTS_SOURCE: &str = "urn:a2a:agent";
+
+/// Turns a task into the body of a push notification
+pub trait NotificationPayloadFormatter: Send + Sync {
+    /// The `Content-Type` of the formatted body
+    fn content_type(&self) -> &str;
+
+    /// Formats the notification body for a task event
+    fn format(&self, task: &Task, kind: Option<NotificationEventKind>) -> Result<Vec<u8>, A2AError>;
+}
+
+/// Sends the complete task
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FullPayloadFormatter;
+
+impl NotificationPayloadFormatter for FullPayloadFormatter {
+    fn content_type(&self) -> &str {
+        "application/json"
+    }
+
+    fn format(&self, task: &Task, _kind: Option<NotificationEventKind>) -> Result<Vec<u8>, A2AError> {
+        Ok(serde_json::to_vec(task)?)
+    }
+}
+
+/// Sends only the task id, context id and status state and timestamp
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SlimPayloadFormatter;
+
+impl NotificationPayloadFormatter for SlimPayloadFormatter {
+    fn content_type(&self) -> &str {
+        "application/json"
+    }
+
+    fn format(&self, task: &Task, kind: Option<NotificationEventKind>) -> Result<Vec<u8>, A2AError> {
+        let mut payload = json!({
+            "id": task.id,
+            "context_id": task.context_id,
+            "state": task.status.state,
+            "timestamp": task.status.timestamp,
+        });
+        if let Some(kind) = kind {
+            payload["event_kind"] = serde_json::to_value(kind)?;
+        }
+        Ok(serde_json::to_vec(&payload)?)
+    }
+}
+
+/// Wraps the task in a CloudEvents 1.0 envelope (structured JSON mode)
+#[derive(Debug, Clone)]
+pub struct CloudEventsPayloadFormatter {
+    source: String,
+}
+
+impl CloudEventsPayloadFormatter {
+    /// Creates a formatter using the given `source` attribute
+    pub fn new(source: &str) -> Self {
+        Self {
+            source: source.to_string(),
+        }
+    }
+}
+
+impl Default for CloudEventsPayloadFormatter {
+    fn default() -> Self {
+        Self::new(DEFAULT_CLOUDEVENTS_SOURCE)
+    }
+}
+
+impl NotificationPayloadFormatter for CloudEventsPayloadFormatter {
+    fn content_type(&self) -> &str {
+        CLOUDEVENTS_CONTENT_TYPE
+    }
+
+    fn format(&self, task: &Task, kind: Option<NotificationEventKind>) -> Result<Vec<u8>, A2AError> {
+        let event_type = match kind {
+            Some(NotificationEventKind::StatusUpdate) => STATUS_UPDATE_EVENT_TYPE,
+            Some(NotificationEventKind::ArtifactUpdate) => ARTIFACT_UPDATE_EVENT_TYPE,
+            Some(NotificationEventKind::Task) | None => TASK_EVENT_TYPE,
+        };
+        task.to_cloud_event(&self.source)?.with_type(event_type).to_json_bytes()
+    }
+}
+
+/// Returns the built-in formatter for a format
+pub fn formatter_for(format: NotificationPayloadFormat) -> Arc<dyn NotificationPayloadFormatter> {
+    match format {
+        NotificationPayloadFormat::Full => Arc::new(FullPayloadFormatter),
+        NotificationPayloadFormat::Slim => Arc::new(SlimPayloadFormatter),
+        NotificationPayloadFormat::CloudEvents => Arc::new(CloudEventsPayloadFormatter::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TaskState, TaskStatus};
+    use serde_json::Value;
+
+    fn task() -> Task {
+        Task::new("ctx".to_string(), TaskStatus::new(TaskState::Completed)).with_task_id("t1".to_string())
+    }
+
+    fn parse(formatter: &dyn NotificationPayloadFormatter, kind: Option<NotificationEventKind>) -> Value {
+        serde_json::from_slice(&formatter.format(&task(), kind).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_slim_payload() {
+        let payload = parse(&SlimPayloadFormatter, Some(NotificationEventKind::StatusUpdate));
+        assert_eq!(payload["id"], "t1");
+        assert_eq!(payload["context_id"], "ctx");
+        assert_eq!(payload["state"], "completed");
+        assert_eq!(payload["event_kind"], "status-update");
+        assert!(payload.get("status").is_none());
+    }
+
+    #[test]
+    fn test_cloudevents_payload() {
+        let formatter = CloudEventsPayloadFormatter::new("https://agent.example.com");
+        assert_eq!(formatter.content_type(), "application/cloudevents+json");
+
+        let payload = parse(&formatter, Some(NotificationEventKind::ArtifactUpdate));
+        assert_eq!(payload["specversion"], "1.0");
+        assert_eq!(payload["source"], "https://agent.example.com");
+        assert_eq!(payload["type"], "a2a.task.artifact-update");
+        assert_eq!(payload["subject"], "t1");
+        assert_eq!(payload["data"]["id"], "t1");
+    }
+
+    #[test]
+    fn test_full_payload_is_the_task() {
+        let task = task();
+        let body = formatter_for(NotificationPayloadFormat::Full).format(&task, None).unwrap();
+        let parsed: Task = serde_json::from_slice(&body).unwrap();
+        assert_eq!(parsed, task);
+    }
+}
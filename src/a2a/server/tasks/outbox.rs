@@ -0,0 +1,254 @@
+//! Transactional outbox for reliable push delivery
+//!
+//! With the outbox enabled (`SqliteTaskStore::with_outbox`), every saved task
+//! also records a notification intent in the same database transaction. The
+//! `OutboxRelay` polls for due intents, delivers them to the task's webhooks and
+//! marks them delivered, so a crash between saving a task and sending its
+//! webhook cannot lose the notification.
+//!
+//! Delivery is at-least-once: an intent whose delivery succeeded for some
+//! webhooks but not others is retried for all of them. When the relay is
+//! used, the request handler should be created without a push sender so that
+//! notifications are not sent twice.
+
+use crate::a2a::server::tasks::HttpPushNotificationSender;
+use crate::{A2AError, Task};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// A notification intent waiting to be delivered
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboxEntry {
+    /// Identifier of the entry
+    pub id: i64,
+    /// The task the notification is about
+    pub task_id: String,
+    /// The task snapshot to deliver
+    pub task: Task,
+    /// Number of delivery attempts made so far
+    pub attempts: u32,
+    /// When the intent was recorded
+    pub created_at: DateTime<Utc>,
+}
+
+/// Storage of notification intents
+#[async_trait]
+pub trait OutboxStore: Send + Sync {
+    /// Returns up to `limit` pending entries that are due, oldest first
+    async fn fetch_due(&self, limit: usize) -> Result<Vec<OutboxEntry>, A2AError>;
+
+    /// Marks an entry as delivered
+    async fn mark_delivered(&self, id: i64) -> Result<(), A2AError>;
+
+    /// Records a failed attempt
+    ///
+    /// The entry is retried at `retry_at`, or abandoned if it is `None`.
+    async fn mark_failed(&self, id: i64, error: &str, retry_at: Option<DateTime<Utc>>) -> Result<(), A2AError>;
+
+    /// Deletes delivered entries recorded before `older_than`, returning how many were removed
+    async fn purge_delivered(&self, older_than: DateTime<Utc>) -> Result<u64, A2AError>;
+}
+
+/// Configuration of an `OutboxRelay`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxRelayConfig {
+    /// Delay between polls when the outbox is empty
+    pub poll_interval: Duration,
+    /// Maximum number of entries processed per poll
+    pub batch_size: usize,
+    /// Maximum number of attempts before an entry is abandoned
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled on every further attempt
+    pub initial_backoff: Duration,
+    /// Upper bound of the retry delay
+    pub max_backoff: Duration,
+}
+
+impl OutboxRelayConfig {
+    /// Creates a configuration with the default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the poll interval
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Sets the batch size
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Sets the maximum number of attempts
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets the initial and maximum retry backoff
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    fn backoff(&self, attempts: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+impl Default for OutboxRelayConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(1),
+            batch_size: 100,
+            max_attempts: 10,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Delivers outbox entries to the webhooks of their tasks
+pub struct OutboxRelay {
+    store: Arc<dyn OutboxStore>,
+    sender: Arc<HttpPushNotificationSender>,
+    config: OutboxRelayConfig,
+}
+
+impl OutboxRelay {
+    /// Creates a relay reading from `store` and delivering through `sender`
+    pub fn new(store: Arc<dyn OutboxStore>, sender: Arc<HttpPushNotificationSender>, config: OutboxRelayConfig) -> Self {
+        Self { store, sender, config }
+    }
+
+    /// Processes one batch of due entries, returning how many were processed
+    pub async fn run_once(&self) -> Result<usize, A2AError> {
+        let entries = self.store.fetch_due(self.config.batch_size.max(1)).await?;
+        for entry in &entries {
+            match self.deliver(entry).await {
+                Ok(()) => self.store.mark_delivered(entry.id).await?,
+                Err(e) => {
+                    let attempts = entry.attempts + 1;
+                    let retry_at = (attempts < self.config.max_attempts).then(|| {
+                        Utc::now() + chrono::Duration::from_std(self.config.backoff(attempts)).unwrap_or_default()
+                    });
+                    if retry_at.is_none() {
+                        warn!(
+                            "Abandoning outbox entry {} for task_id={} after {} attempts: {}",
+                            entry.id, entry.task_id, attempts, e
+                        );
+                    }
+                    self.store.mark_failed(entry.id, &e.to_string(), retry_at).await?;
+                }
+            }
+        }
+        Ok(entries.len())
+    }
+
+    /// Spawns a Tokio task running the relay until it is aborted
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            debug!("Outbox relay started");
+            loop {
+                match self.run_once().await {
+                    // A full batch likely means more entries are waiting
+                    Ok(processed) if processed >= self.config.batch_size.max(1) => continue,
+                    Ok(_) => {}
+                    Err(e) => warn!("Outbox relay poll failed: {}", e),
+                }
+                tokio::time::sleep(self.config.poll_interval).await;
+            }
+        })
+    }
+
+    async fn deliver(&self, entry: &OutboxEntry) -> Result<(), A2AError> {
+        let configs = self.sender.configs_for(&entry.task, None).await?;
+        let results =
+            futures::future::join_all(configs.iter().map(|config| self.sender.deliver(&entry.task, config, None))).await;
+        match results.into_iter().find_map(Result::err) {
+            Some(e) => Err(A2AError::transport_error(e.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::server::tasks::{
+        InMemoryPushNotificationConfigStore, PushNotificationConfigStore, SqliteTaskStore, TaskStore,
+    };
+    use crate::{PushNotificationConfig, TaskState, TaskStatus};
+    use mockito::Server;
+
+    async fn setup(url: &str) -> (Arc<SqliteTaskStore>, Arc<HttpPushNotificationSender>) {
+        let store = Arc::new(SqliteTaskStore::connect("sqlite::memory:").await.unwrap().with_outbox());
+        let configs = Arc::new(InMemoryPushNotificationConfigStore::new());
+        configs
+            .set_info("t1", PushNotificationConfig::new(url.parse().unwrap()))
+            .await
+            .unwrap();
+        (store, Arc::new(HttpPushNotificationSender::new(configs)))
+    }
+
+    fn task(state: TaskState) -> Task {
+        Task::new("ctx".to_string(), TaskStatus::new(state)).with_task_id("t1".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_relay_delivers_and_marks_entries() {
+        let mut server = Server::new_async().await;
+        let mock = server.mock("POST", "/").with_status(200).expect(2).create_async().await;
+        let (store, sender) = setup(&server.url()).await;
+
+        store.save(task(TaskState::Working)).await.unwrap();
+        store.save(task(TaskState::Completed)).await.unwrap();
+        assert_eq!(store.fetch_due(10).await.unwrap().len(), 2);
+
+        let relay = OutboxRelay::new(store.clone(), sender, OutboxRelayConfig::new());
+        assert_eq!(relay.run_once().await.unwrap(), 2);
+        assert_eq!(relay.run_once().await.unwrap(), 0);
+        mock.assert_async().await;
+
+        let purged = store.purge_delivered(Utc::now() + chrono::Duration::seconds(1)).await.unwrap();
+        assert_eq!(purged, 2);
+    }
+
+    #[tokio::test]
+    async fn test_relay_retries_and_abandons() {
+        let mut server = Server::new_async().await;
+        let mock = server.mock("POST", "/").with_status(500).expect(2).create_async().await;
+        let (store, sender) = setup(&server.url()).await;
+        store.save(task(TaskState::Working)).await.unwrap();
+
+        let config = OutboxRelayConfig::new()
+            .with_max_attempts(2)
+            .with_backoff(Duration::ZERO, Duration::ZERO);
+        let relay = OutboxRelay::new(store.clone(), sender, config);
+
+        assert_eq!(relay.run_once().await.unwrap(), 1);
+        let due = store.fetch_due(10).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].attempts, 1);
+
+        assert_eq!(relay.run_once().await.unwrap(), 1);
+        assert!(store.fetch_due(10).await.unwrap().is_empty());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_outbox_disabled_by_default() {
+        let store = SqliteTaskStore::connect("sqlite::memory:").await.unwrap();
+        store.save(task(TaskState::Working)).await.unwrap();
+        assert!(store.fetch_due(10).await.unwrap().is_empty());
+    }
+}
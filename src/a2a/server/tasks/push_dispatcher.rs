@@ -0,0 +1,395 @@
+//! Background push notification dispatch
+//!
+//! `PushDispatcher` decouples webhook delivery from the code that produces task
+//! events. `send_notification` only places the task on a bounded queue; a pool
+//! of worker tasks looks up the task's webhook configurations and delivers to
+//! each of them, retrying failed deliveries with exponential backoff.
+//!
+//! When the queue is full, notifications are dropped rather than blocking the
+//! producer, and counted in `PushDispatchStats::dropped`.
+
+use crate::a2a::server::tasks::{HttpPushNotificationSender, PushNotificationSender};
+use crate::{A2AError, NotificationEventKind, PushNotificationConfig, Task};
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex, Notify};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// A queued notification: the task and the kind of event that triggered it
+type DispatchItem = (Task, Option<NotificationEventKind>);
+
+/// Configuration of a `PushDispatcher`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushDispatchConfig {
+    /// Number of worker tasks delivering notifications
+    pub workers: usize,
+    /// Maximum number of notifications waiting for a worker
+    pub queue_capacity: usize,
+    /// Maximum number of attempts per webhook, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound of the delay between retries
+    pub max_backoff: Duration,
+}
+
+impl PushDispatchConfig {
+    /// Creates a configuration with the default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of workers
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    /// Sets the queue capacity
+    pub fn with_queue_capacity(mut self, capacity: usize) -> Self {
+        self.queue_capacity = capacity;
+        self
+    }
+
+    /// Sets the maximum number of attempts per webhook
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets the initial and maximum retry backoff
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Returns the delay before retry number `retry` (starting at 1)
+    fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+impl Default for PushDispatchConfig {
+    fn default() -> Self {
+        Self {
+            workers: 4,
+            queue_capacity: 1024,
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Counters of a `PushDispatcher`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PushDispatchStats {
+    /// Notifications accepted onto the queue
+    pub enqueued: u64,
+    /// Notifications dropped because the queue was full or closed
+    pub dropped: u64,
+    /// Webhook deliveries that succeeded
+    pub delivered: u64,
+    /// Webhook deliveries that failed after all attempts
+    pub failed: u64,
+    /// Retries performed
+    pub retries: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    enqueued: AtomicU64,
+    dropped: AtomicU64,
+    delivered: AtomicU64,
+    failed: AtomicU64,
+    retries: AtomicU64,
+    in_flight: AtomicU64,
+}
+
+/// Delivers push notifications from a bounded queue with a pool of workers
+pub struct PushDispatcher {
+    sender: Arc<HttpPushNotificationSender>,
+    queue: std::sync::Mutex<Option<mpsc::Sender<DispatchItem>>>,
+    workers: Mutex<Vec<JoinHandle<()>>>,
+    counters: Arc<Counters>,
+    idle: Arc<Notify>,
+}
+
+impl PushDispatcher {
+    /// Starts a dispatcher delivering through `sender`
+    ///
+    /// Spawns the worker tasks and must be called from within a Tokio runtime.
+    pub fn new(sender: Arc<HttpPushNotificationSender>, config: PushDispatchConfig) -> Self {
+        let (queue, receiver) = mpsc::channel(config.queue_capacity.max(1));
+        let receiver = Arc::new(Mutex::new(receiver));
+        let counters = Arc::new(Counters::default());
+        let idle = Arc::new(Notify::new());
+        let config = Arc::new(config);
+
+        let workers = (0..config.workers.max(1))
+            .map(|worker| {
+                let receiver = receiver.clone();
+                let sender = sender.clone();
+                let counters = counters.clone();
+                let idle = idle.clone();
+                let config = config.clone();
+                tokio::spawn(async move {
+                    debug!("Push dispatch worker {} started", worker);
+                    loop {
+                        // The lock is only held while waiting for the next task
+                        let Some((task, kind)) = receiver.lock().await.recv().await else {
+                            break;
+                        };
+                        deliver_all(&sender, &config, &counters, &task, kind).await;
+                        if counters.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+                            idle.notify_waiters();
+                        }
+                    }
+                    debug!("Push dispatch worker {} stopped", worker);
+                })
+            })
+            .collect();
+
+        Self {
+            sender,
+            queue: std::sync::Mutex::new(Some(queue)),
+            workers: Mutex::new(workers),
+            counters,
+            idle,
+        }
+    }
+
+    /// Places a task on the queue without waiting
+    ///
+    /// Returns an error if the queue is full or the dispatcher was shut down.
+    pub fn enqueue(&self, task: Task) -> Result<(), A2AError> {
+        self.enqueue_event(task, None)
+    }
+
+    /// Places a task event of the given kind on the queue without waiting
+    ///
+    /// Only configs whose filter accepts the kind are notified.
+    pub fn enqueue_event(&self, task: Task, kind: Option<NotificationEventKind>) -> Result<(), A2AError> {
+        let queue = self.queue.lock().unwrap();
+        let Some(queue) = queue.as_ref() else {
+            self.counters.dropped.fetch_add(1, Ordering::SeqCst);
+            return Err(A2AError::internal("Push dispatcher is shut down"));
+        };
+
+        self.counters.in_flight.fetch_add(1, Ordering::SeqCst);
+        match queue.try_send((task, kind)) {
+            Ok(()) => {
+                self.counters.enqueued.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(e) => {
+                self.counters.in_flight.fetch_sub(1, Ordering::SeqCst);
+                self.counters.dropped.fetch_add(1, Ordering::SeqCst);
+                let task = match e {
+                    mpsc::error::TrySendError::Full((task, _)) | mpsc::error::TrySendError::Closed((task, _)) => task,
+                };
+                warn!("Push dispatch queue is full, dropping notification for task_id={}", task.id);
+                Err(A2AError::internal("Push dispatch queue is full"))
+            }
+        }
+    }
+
+    /// Returns a snapshot of the dispatcher counters
+    pub fn stats(&self) -> PushDispatchStats {
+        PushDispatchStats {
+            enqueued: self.counters.enqueued.load(Ordering::SeqCst),
+            dropped: self.counters.dropped.load(Ordering::SeqCst),
+            delivered: self.counters.delivered.load(Ordering::SeqCst),
+            failed: self.counters.failed.load(Ordering::SeqCst),
+            retries: self.counters.retries.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Returns the number of queued or in-progress notifications
+    pub fn pending(&self) -> u64 {
+        self.counters.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Waits until every accepted notification has been processed
+    pub async fn wait_idle(&self) {
+        loop {
+            let notified = self.idle.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.pending() == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Stops accepting notifications and waits for the queue to drain
+    pub async fn shutdown(&self) {
+        self.queue.lock().unwrap().take();
+        let workers: Vec<JoinHandle<()>> = self.workers.lock().await.drain(..).collect();
+        for worker in workers {
+            let _ = worker.await;
+        }
+    }
+}
+
+#[async_trait]
+impl PushNotificationSender for PushDispatcher {
+    async fn send_notification(&self, task: &Task) -> Result<(), A2AError> {
+        self.enqueue(task.clone())
+    }
+
+    async fn send_event_notification(&self, task: &Task, kind: NotificationEventKind) -> Result<(), A2AError> {
+        self.enqueue_event(task.clone(), Some(kind))
+    }
+
+    fn invalidate_push_configs(&self, task_id: Option<&str>) {
+        self.sender.invalidate_push_configs(task_id);
+    }
+}
+
+async fn deliver_all(
+    sender: &HttpPushNotificationSender,
+    config: &PushDispatchConfig,
+    counters: &Counters,
+    task: &Task,
+    kind: Option<NotificationEventKind>,
+) {
+    let configs = match sender.configs_for(task, kind).await {
+        Ok(configs) => configs,
+        Err(e) => {
+            warn!("Failed to load push configs for task_id={}: {}", task.id, e);
+            return;
+        }
+    };
+    futures::future::join_all(
+        configs
+            .iter()
+            .map(|push_config| deliver_with_retry(sender, config, counters, task, push_config, kind)),
+    )
+    .await;
+}
+
+async fn deliver_with_retry(
+    sender: &HttpPushNotificationSender,
+    config: &PushDispatchConfig,
+    counters: &Counters,
+    task: &Task,
+    push_config: &PushNotificationConfig,
+    kind: Option<NotificationEventKind>,
+) {
+    let max_attempts = config.max_attempts.max(1);
+    for attempt in 1..=max_attempts {
+        match sender.deliver(task, push_config, kind).await {
+            Ok(()) => {
+                counters.delivered.fetch_add(1, Ordering::SeqCst);
+                return;
+            }
+            Err(e) if e.is_retryable() && attempt < max_attempts => {
+                counters.retries.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(config.backoff(attempt)).await;
+            }
+            Err(e) => {
+                warn!(
+                    "Giving up push-notification for task_id={} to URL: {} after {} attempts: {}",
+                    task.id, push_config.url, attempt, e
+                );
+                break;
+            }
+        }
+    }
+    counters.failed.fetch_add(1, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::server::tasks::{InMemoryPushNotificationConfigStore, PushNotificationConfigStore};
+    use crate::{TaskState, TaskStatus};
+    use mockito::Server;
+
+    async fn dispatcher_for(url: &str, config: PushDispatchConfig) -> PushDispatcher {
+        let store = Arc::new(InMemoryPushNotificationConfigStore::new());
+        store
+            .set_info("t1", PushNotificationConfig::new(url.parse().unwrap()))
+            .await
+            .unwrap();
+        PushDispatcher::new(Arc::new(HttpPushNotificationSender::new(store)), config)
+    }
+
+    fn task() -> Task {
+        Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id("t1".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_delivers_in_background() {
+        let mut server = Server::new_async().await;
+        let mock = server.mock("POST", "/").with_status(200).expect(2).create_async().await;
+        let dispatcher = dispatcher_for(&server.url(), PushDispatchConfig::new().with_workers(2)).await;
+
+        dispatcher.send_notification(&task()).await.unwrap();
+        dispatcher.send_notification(&task()).await.unwrap();
+        dispatcher.wait_idle().await;
+
+        mock.assert_async().await;
+        let stats = dispatcher.stats();
+        assert_eq!(stats.enqueued, 2);
+        assert_eq!(stats.delivered, 2);
+        assert_eq!(stats.failed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_retries_server_errors() {
+        let mut server = Server::new_async().await;
+        let mock = server.mock("POST", "/").with_status(503).expect(3).create_async().await;
+        let config = PushDispatchConfig::new()
+            .with_max_attempts(3)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(5));
+        let dispatcher = dispatcher_for(&server.url(), config).await;
+
+        dispatcher.enqueue(task()).unwrap();
+        dispatcher.wait_idle().await;
+
+        mock.assert_async().await;
+        let stats = dispatcher.stats();
+        assert_eq!(stats.retries, 2);
+        assert_eq!(stats.failed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let mut server = Server::new_async().await;
+        let mock = server.mock("POST", "/").with_status(404).expect(1).create_async().await;
+        let config = PushDispatchConfig::new().with_backoff(Duration::from_millis(1), Duration::from_millis(5));
+        let dispatcher = dispatcher_for(&server.url(), config).await;
+
+        dispatcher.enqueue(task()).unwrap();
+        dispatcher.wait_idle().await;
+
+        mock.assert_async().await;
+        assert_eq!(dispatcher.stats().failed, 1);
+        assert_eq!(dispatcher.stats().retries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_rejects_new_notifications() {
+        let dispatcher = dispatcher_for("http://localhost:1/", PushDispatchConfig::new()).await;
+        dispatcher.shutdown().await;
+        assert!(dispatcher.enqueue(task()).is_err());
+        assert_eq!(dispatcher.stats().dropped, 1);
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let config = PushDispatchConfig::new().with_backoff(Duration::from_millis(100), Duration::from_millis(350));
+        assert_eq!(config.backoff(1), Duration::from_millis(100));
+        assert_eq!(config.backoff(2), Duration::from_millis(200));
+        assert_eq!(config.backoff(3), Duration::from_millis(350));
+        assert_eq!(config.backoff(40), Duration::from_millis(350));
+    }
+}
@@ -2,11 +2,25 @@
 //! 
 //! This module defines the interface for sending push notifications
 //! to external services when task events occur.
+//!
+//! `HttpPushNotificationSender::with_config_cache` keeps the configs of
+//! active tasks in memory instead of asking the config store for every event.
+//! Changes must go through an `InvalidatingPushConfigStore`, which drops the
+//! cached configs of a task before the write returns; `DefaultRequestHandler`
+//! wraps its config store in one and hands it out with `push_config_store`
+//! for other writers such as the admin API. A notification racing a change
+//! may still go to the previous configs.
+//!
+//! The cache only sees the changes made in its own process. With several
+//! replicas sharing a config store, leave it off.
 
-use crate::{Task, A2AError};
+use crate::{Task, A2AError, NotificationEventKind, PushNotificationConfig};
+use crate::a2a::server::tasks::notification_payload::{formatter_for, FullPayloadFormatter, NotificationPayloadFormatter};
+use crate::a2a::server::tasks::callback_token::CallbackTokenSigner;
 use crate::a2a::server::tasks::PushNotificationConfigStore;
 use async_trait::async_trait;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use tracing::{info, warn, error};
 
 /// Push Notification Sender interface
@@ -14,12 +28,142 @@ use tracing::{info, warn, error};
 pub trait PushNotificationSender: Send + Sync {
     /// Sends a push notification for a task
     async fn send_notification(&self, task: &Task) -> Result<(), A2AError>;
+
+    /// Sends a push notification for a task event of the given kind
+    ///
+    /// Senders that support per-config filters use the kind to skip configs that
+    /// did not subscribe to it. The default ignores the kind.
+    async fn send_event_notification(&self, task: &Task, kind: NotificationEventKind) -> Result<(), A2AError> {
+        let _ = kind;
+        self.send_notification(task).await
+    }
+
+    /// Forgets cached push configs of a task, or of every task for `None`
+    ///
+    /// Called when configs change; senders without a cache ignore it.
+    fn invalidate_push_configs(&self, task_id: Option<&str>) {
+        let _ = task_id;
+    }
+}
+
+/// Push configs of active tasks, as read from the config store
+struct ConfigCache {
+    state: Mutex<ConfigCacheState>,
+    capacity: usize,
+}
+
+#[derive(Default)]
+struct ConfigCacheState {
+    /// Cached configs by task id, with the tick they were last used at
+    entries: HashMap<String, (Vec<PushNotificationConfig>, u64)>,
+    /// Generation and number of reads in flight, for tasks being read
+    ///
+    /// An invalidation bumps the generation, so a read that started before
+    /// it does not put the configs it got back into the cache.
+    reads: HashMap<String, (u64, usize)>,
+    tick: u64,
+}
+
+impl ConfigCacheState {
+    fn get(&mut self, task_id: &str) -> Option<Vec<PushNotificationConfig>> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(task_id).map(|(configs, last_used)| {
+            *last_used = tick;
+            configs.clone()
+        })
+    }
+
+    /// Registers a read of the store and returns its generation
+    fn start_read(&mut self, task_id: &str) -> u64 {
+        let (generation, readers) = self.reads.entry(task_id.to_string()).or_default();
+        *readers += 1;
+        *generation
+    }
+
+    /// Unregisters a read of the store
+    fn finish_read(&mut self, task_id: &str) {
+        if let Some((_, readers)) = self.reads.get_mut(task_id) {
+            *readers -= 1;
+            if *readers == 0 {
+                self.reads.remove(task_id);
+            }
+        }
+    }
+
+    /// Caches the configs of a task, evicting the least recently used task when full
+    fn insert(&mut self, task_id: &str, configs: Vec<PushNotificationConfig>, capacity: usize) {
+        if !self.entries.contains_key(task_id) && self.entries.len() >= capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(task_id, _)| task_id.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.tick += 1;
+        self.entries.insert(task_id.to_string(), (configs, self.tick));
+    }
+
+    fn invalidate(&mut self, task_id: Option<&str>) {
+        match task_id {
+            Some(task_id) => {
+                self.entries.remove(task_id);
+                if let Some((generation, _)) = self.reads.get_mut(task_id) {
+                    *generation += 1;
+                }
+            }
+            None => {
+                self.entries.clear();
+                for (generation, _) in self.reads.values_mut() {
+                    *generation += 1;
+                }
+            }
+        }
+    }
+}
+
+/// A read of the config store on a cache miss
+///
+/// Dropping it without `finish`, e.g. when the read fails or is cancelled,
+/// still unregisters the read.
+struct StoreRead<'a> {
+    cache: &'a ConfigCache,
+    task_id: &'a str,
+    generation: u64,
+}
+
+impl<'a> StoreRead<'a> {
+    fn start(cache: &'a ConfigCache, state: &mut ConfigCacheState, task_id: &'a str) -> Self {
+        let generation = state.start_read(task_id);
+        Self { cache, task_id, generation }
+    }
+
+    /// Caches the configs read, unless the task was invalidated meanwhile
+    fn finish(self, configs: Vec<PushNotificationConfig>) {
+        let mut state = self.cache.state.lock().unwrap();
+        if state.reads.get(self.task_id).is_some_and(|(generation, _)| *generation == self.generation) {
+            state.insert(self.task_id, configs, self.cache.capacity);
+        }
+        // The read is unregistered when `self` drops
+    }
+}
+
+impl Drop for StoreRead<'_> {
+    fn drop(&mut self) {
+        self.cache.state.lock().unwrap().finish_read(self.task_id);
+    }
 }
 
 /// HTTP implementation of PushNotificationSender
 pub struct HttpPushNotificationSender {
     client: reqwest::Client,
     config_store: Arc<dyn PushNotificationConfigStore>,
+    formatter: Arc<dyn NotificationPayloadFormatter>,
+    token_signer: Option<CallbackTokenSigner>,
+    config_cache: Option<ConfigCache>,
 }
 
 impl HttpPushNotificationSender {
@@ -28,6 +172,9 @@ impl HttpPushNotificationSender {
         Self {
             client: reqwest::Client::new(),
             config_store,
+            formatter: Arc::new(FullPayloadFormatter),
+            token_signer: None,
+            config_cache: None,
         }
     }
 
@@ -36,13 +183,126 @@ impl HttpPushNotificationSender {
         Self {
             client,
             config_store,
+            formatter: Arc::new(FullPayloadFormatter),
+            token_signer: None,
+            config_cache: None,
         }
     }
 
-    async fn dispatch_notification(&self, task: &Task, url: String, token: Option<String>) -> bool {
-        let mut request = self.client.post(&url).json(task);
-        
-        if let Some(ref token) = token {
+    /// Sets the payload formatter used for configs without a `payload_format`
+    pub fn with_formatter(mut self, formatter: Arc<dyn NotificationPayloadFormatter>) -> Self {
+        self.formatter = formatter;
+        self
+    }
+
+    /// Sends a freshly signed, expiring token with every notification
+    ///
+    /// The signed token replaces the static `token` of the configs.
+    pub fn with_callback_tokens(mut self, signer: CallbackTokenSigner) -> Self {
+        self.token_signer = Some(signer);
+        self
+    }
+
+    /// Caches the configs of up to `capacity` tasks
+    ///
+    /// The configs of a task are read once and kept until they are
+    /// invalidated or the task reaches a terminal state. When the cache is
+    /// full the least recently used task is evicted. Only use it on a single
+    /// replica, with all writes going through an `InvalidatingPushConfigStore`.
+    pub fn with_config_cache(mut self, capacity: usize) -> Self {
+        self.config_cache = Some(ConfigCache {
+            state: Mutex::new(ConfigCacheState::default()),
+            capacity: capacity.max(1),
+        });
+        self
+    }
+
+    /// Returns the store the webhook configurations are read from
+    pub fn config_store(&self) -> &Arc<dyn PushNotificationConfigStore> {
+        &self.config_store
+    }
+
+    /// Returns the configs of the task whose filter accepts the event
+    pub async fn configs_for(
+        &self,
+        task: &Task,
+        kind: Option<NotificationEventKind>,
+    ) -> Result<Vec<PushNotificationConfig>, A2AError> {
+        let configs = self.task_configs(task).await?;
+        Ok(configs
+            .into_iter()
+            .filter(|config| config.accepts(kind, &task.status.state))
+            .collect())
+    }
+
+    /// All configs of the task, from the cache if enabled
+    async fn task_configs(&self, task: &Task) -> Result<Vec<PushNotificationConfig>, A2AError> {
+        let Some(cache) = &self.config_cache else {
+            return self.config_store.get_info(&task.id).await;
+        };
+        // A terminal task is not notified much longer, so its entry is dropped
+        let terminal = task.status.state.is_terminal();
+        if terminal {
+            let cached = cache.state.lock().unwrap().entries.remove(&task.id);
+            return match cached {
+                Some((configs, _)) => Ok(configs),
+                None => self.config_store.get_info(&task.id).await,
+            };
+        }
+
+        let read = {
+            let mut state = cache.state.lock().unwrap();
+            if let Some(cached) = state.get(&task.id) {
+                return Ok(cached);
+            }
+            StoreRead::start(cache, &mut state, &task.id)
+        };
+        let configs = self.config_store.get_info(&task.id).await?;
+        read.finish(configs.clone());
+        Ok(configs)
+    }
+
+    async fn notify(&self, task: &Task, kind: Option<NotificationEventKind>) -> Result<(), A2AError> {
+        let configs = self.configs_for(task, kind).await?;
+        if configs.is_empty() {
+            return Ok(());
+        }
+
+        let results = futures::future::join_all(configs.iter().map(|config| self.deliver(task, config, kind))).await;
+
+        if results.iter().any(|r| r.is_err()) {
+            warn!("Some push notifications failed to send for task_id={}", task.id);
+        }
+
+        Ok(())
+    }
+
+    /// Delivers a single notification to one webhook
+    pub async fn deliver(
+        &self,
+        task: &Task,
+        config: &PushNotificationConfig,
+        kind: Option<NotificationEventKind>,
+    ) -> Result<(), PushDeliveryError> {
+        let formatter = match config.payload_format {
+            Some(format) => formatter_for(format),
+            None => self.formatter.clone(),
+        };
+        let body = formatter
+            .format(task, kind)
+            .map_err(|e| PushDeliveryError::Payload(e.to_string()))?;
+
+        let url = config.url.to_string();
+        let mut request = self.client
+            .post(&url)
+            .header(reqwest::header::CONTENT_TYPE, formatter.content_type())
+            .body(body);
+
+        let token = match &self.token_signer {
+            Some(signer) => Some(signer.issue(&task.id, config.id.as_deref().unwrap_or_default())),
+            None => config.token.clone(),
+        };
+        if let Some(token) = token {
             request = request.header("X-A2A-Notification-Token", token);
         }
 
@@ -50,51 +310,103 @@ impl HttpPushNotificationSender {
             Ok(response) => {
                 if response.status().is_success() {
                     info!("Push-notification sent for task_id={} to URL: {}", task.id, url);
-                    true
+                    Ok(())
                 } else {
                     warn!("Push-notification failed for task_id={} to URL: {}. Status: {}", task.id, url, response.status());
-                    false
+                    Err(PushDeliveryError::Status(response.status().as_u16()))
                 }
             }
             Err(e) => {
                 error!("Error sending push-notification for task_id={} to URL: {}. Error: {}", task.id, url, e);
-                false
+                Err(PushDeliveryError::Transport(e.to_string()))
             }
         }
     }
 }
 
+/// Failure to deliver a notification to a webhook
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PushDeliveryError {
+    /// The webhook answered with a non-success status
+    #[error("webhook returned status {0}")]
+    Status(u16),
+    /// The request could not be sent
+    #[error("transport error: {0}")]
+    Transport(String),
+    /// The payload could not be formatted
+    #[error("payload error: {0}")]
+    Payload(String),
+}
+
+impl PushDeliveryError {
+    /// Returns true if retrying the delivery may succeed
+    ///
+    /// Transport errors, `408`, `429` and server errors are retryable; other
+    /// client errors are not.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            PushDeliveryError::Status(status) => matches!(status, 408 | 429 | 500..=599),
+            PushDeliveryError::Transport(_) => true,
+            PushDeliveryError::Payload(_) => false,
+        }
+    }
+}
+
 #[async_trait]
 impl PushNotificationSender for HttpPushNotificationSender {
     async fn send_notification(&self, task: &Task) -> Result<(), A2AError> {
-        let configs = self.config_store.get_info(&task.id).await?;
-        if configs.is_empty() {
-            return Ok(());
-        }
+        self.notify(task, None).await
+    }
 
-        let mut futures = Vec::new();
-        for config in configs {
-            let url = config.url.to_string();
-            let token = config.token.clone();
-            futures.push(self.dispatch_notification(task, url, token));
-        }
+    async fn send_event_notification(&self, task: &Task, kind: NotificationEventKind) -> Result<(), A2AError> {
+        self.notify(task, Some(kind)).await
+    }
 
-        let results = futures::future::join_all(futures).await;
-        
-        if results.iter().any(|&r| !r) {
-            warn!("Some push notifications failed to send for task_id={}", task.id);
+    fn invalidate_push_configs(&self, task_id: Option<&str>) {
+        if let Some(cache) = &self.config_cache {
+            cache.state.lock().unwrap().invalidate(task_id);
         }
+    }
+}
 
-        Ok(())
+/// A config store invalidating the configs a sender cached on every change
+pub struct InvalidatingPushConfigStore {
+    inner: Arc<dyn PushNotificationConfigStore>,
+    sender: Arc<dyn PushNotificationSender>,
+}
+
+impl InvalidatingPushConfigStore {
+    /// Writes to `inner`, then tells `sender` which task's configs changed
+    pub fn new(inner: Arc<dyn PushNotificationConfigStore>, sender: Arc<dyn PushNotificationSender>) -> Self {
+        Self { inner, sender }
+    }
+}
+
+#[async_trait]
+impl PushNotificationConfigStore for InvalidatingPushConfigStore {
+    async fn set_info(&self, task_id: &str, config: PushNotificationConfig) -> Result<(), A2AError> {
+        let result = self.inner.set_info(task_id, config).await;
+        self.sender.invalidate_push_configs(Some(task_id));
+        result
+    }
+
+    async fn get_info(&self, task_id: &str) -> Result<Vec<PushNotificationConfig>, A2AError> {
+        self.inner.get_info(task_id).await
+    }
+
+    async fn delete_info(&self, task_id: &str, config_id: Option<&str>) -> Result<(), A2AError> {
+        let result = self.inner.delete_info(task_id, config_id).await;
+        self.sender.invalidate_push_configs(Some(task_id));
+        result
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{TaskStatus, TaskState};
+    use crate::{NotificationFilter, NotificationPayloadFormat, TaskStatus, TaskState};
+    use crate::a2a::server::tasks::CloudEventsPayloadFormatter;
     use crate::a2a::server::tasks::InMemoryPushNotificationConfigStore;
-    use crate::PushNotificationConfig;
     use mockito::Server;
 
     #[tokio::test]
@@ -116,6 +428,8 @@ mod tests {
             url,
             token: Some("secret-token".to_string()),
             authentication: None,
+            filter: None,
+            payload_format: None,
         }).await.unwrap();
 
         let sender = HttpPushNotificationSender::new(config_store);
@@ -129,6 +443,7 @@ mod tests {
             },
             artifacts: None,
             history: None,
+            state_transitions: None,
             metadata: None,
             kind: "task".to_string(),
         };
@@ -136,4 +451,252 @@ mod tests {
         sender.send_notification(&task).await.unwrap();
         mock.assert_async().await;
     }
+
+    #[tokio::test]
+    async fn test_http_push_sender_respects_filters() {
+        let mut server = Server::new_async().await;
+        let terminal_mock = server.mock("POST", "/terminal")
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+        let artifacts_mock = server.mock("POST", "/artifacts")
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let config_store = Arc::new(InMemoryPushNotificationConfigStore::new());
+        let terminal = PushNotificationConfig::new(format!("{}/terminal", server.url()).parse().unwrap())
+            .with_id("terminal".to_string())
+            .with_filter(NotificationFilter::terminal_states());
+        let artifacts = PushNotificationConfig::new(format!("{}/artifacts", server.url()).parse().unwrap())
+            .with_id("artifacts".to_string())
+            .with_filter(NotificationFilter::new().with_event_kinds(vec![NotificationEventKind::ArtifactUpdate]));
+        config_store.set_info("t1", terminal).await.unwrap();
+        config_store.set_info("t1", artifacts).await.unwrap();
+
+        let sender = HttpPushNotificationSender::new(config_store);
+        let working = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id("t1".to_string());
+        let completed = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Completed)).with_task_id("t1".to_string());
+
+        sender.send_event_notification(&working, NotificationEventKind::StatusUpdate).await.unwrap();
+        sender.send_event_notification(&working, NotificationEventKind::ArtifactUpdate).await.unwrap();
+        sender.send_event_notification(&completed, NotificationEventKind::StatusUpdate).await.unwrap();
+
+        terminal_mock.assert_async().await;
+        artifacts_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_http_push_sender_payload_formats() {
+        let mut server = Server::new_async().await;
+        let slim_mock = server.mock("POST", "/slim")
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "id": "t1",
+                "context_id": "ctx",
+                "state": "completed",
+                "event_kind": "status-update"
+            })))
+            .with_status(200)
+            .create_async()
+            .await;
+        let cloudevents_mock = server.mock("POST", "/cloudevents")
+            .match_header("content-type", "application/cloudevents+json")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "specversion": "1.0",
+                "type": "a2a.task.status-update",
+                "subject": "t1"
+            })))
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let config_store = Arc::new(InMemoryPushNotificationConfigStore::new());
+        let slim = PushNotificationConfig::new(format!("{}/slim", server.url()).parse().unwrap())
+            .with_id("slim".to_string())
+            .with_payload_format(NotificationPayloadFormat::Slim);
+        let cloudevents = PushNotificationConfig::new(format!("{}/cloudevents", server.url()).parse().unwrap())
+            .with_id("cloudevents".to_string());
+        config_store.set_info("t1", slim).await.unwrap();
+        config_store.set_info("t1", cloudevents).await.unwrap();
+
+        // The server-wide formatter applies to configs without their own format
+        let sender = HttpPushNotificationSender::new(config_store)
+            .with_formatter(Arc::new(CloudEventsPayloadFormatter::default()));
+        let completed = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Completed)).with_task_id("t1".to_string());
+        sender.send_event_notification(&completed, NotificationEventKind::StatusUpdate).await.unwrap();
+
+        slim_mock.assert_async().await;
+        cloudevents_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_http_push_sender_signs_callback_tokens() {
+        let signer = CallbackTokenSigner::new(b"webhook-key");
+        let mut server = Server::new_async().await;
+        let mock = server.mock("POST", "/")
+            .match_header("X-A2A-Notification-Token", mockito::Matcher::Regex("^v1\\.".to_string()))
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let config_store = Arc::new(InMemoryPushNotificationConfigStore::new());
+        let config = PushNotificationConfig::new(server.url().parse().unwrap())
+            .with_id("cfg1".to_string())
+            .with_token("static-token".to_string());
+        config_store.set_info("t1", config).await.unwrap();
+
+        let sender = HttpPushNotificationSender::new(config_store).with_callback_tokens(signer);
+        let task = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Completed)).with_task_id("t1".to_string());
+        sender.send_notification(&task).await.unwrap();
+        mock.assert_async().await;
+    }
+
+    /// Counts the reads of the store it wraps; reads wait while `gate` is held
+    struct CountingStore {
+        inner: InMemoryPushNotificationConfigStore,
+        reads: std::sync::atomic::AtomicUsize,
+        gate: tokio::sync::Mutex<()>,
+    }
+
+    impl CountingStore {
+        fn new() -> Self {
+            Self {
+                inner: InMemoryPushNotificationConfigStore::new(),
+                reads: Default::default(),
+                gate: Default::default(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl PushNotificationConfigStore for CountingStore {
+        async fn set_info(&self, task_id: &str, config: PushNotificationConfig) -> Result<(), A2AError> {
+            self.inner.set_info(task_id, config).await
+        }
+
+        async fn get_info(&self, task_id: &str) -> Result<Vec<PushNotificationConfig>, A2AError> {
+            self.reads.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let configs = self.inner.get_info(task_id).await;
+            let _gate = self.gate.lock().await;
+            configs
+        }
+
+        async fn delete_info(&self, task_id: &str, config_id: Option<&str>) -> Result<(), A2AError> {
+            self.inner.delete_info(task_id, config_id).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_http_push_sender_caches_configs() {
+        let store = Arc::new(CountingStore::new());
+        let config = PushNotificationConfig::new("http://localhost:1/hook".parse().unwrap());
+        store.set_info("t1", config.clone()).await.unwrap();
+        let sender = HttpPushNotificationSender::new(store.clone()).with_config_cache(16);
+        let reads = || store.reads.load(std::sync::atomic::Ordering::SeqCst);
+
+        let working = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id("t1".to_string());
+        for _ in 0..3 {
+            assert_eq!(sender.configs_for(&working, None).await.unwrap().len(), 1);
+        }
+        assert_eq!(reads(), 1);
+
+        store.set_info("t1", PushNotificationConfig { id: Some("second".to_string()), ..config }).await.unwrap();
+        sender.invalidate_push_configs(Some("t1"));
+        assert_eq!(sender.configs_for(&working, None).await.unwrap().len(), 2);
+        assert_eq!(reads(), 2);
+
+        // The final notification reads the cached configs and drops them
+        let done = Task { status: TaskStatus::new(TaskState::Completed), ..working.clone() };
+        assert_eq!(sender.configs_for(&done, None).await.unwrap().len(), 2);
+        assert_eq!(reads(), 2);
+        sender.configs_for(&working, None).await.unwrap();
+        assert_eq!(reads(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_read_racing_an_invalidation_is_not_cached() {
+        let store = Arc::new(CountingStore::new());
+        let config = PushNotificationConfig::new("http://localhost:1/hook".parse().unwrap());
+        store.set_info("t1", config.clone()).await.unwrap();
+        let sender = Arc::new(HttpPushNotificationSender::new(store.clone()).with_config_cache(16));
+        let reads = || store.reads.load(std::sync::atomic::Ordering::SeqCst);
+        let working = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id("t1".to_string());
+
+        // The read gets the old configs, then the task is invalidated before it returns
+        let gate = store.gate.lock().await;
+        let read = tokio::spawn({
+            let sender = sender.clone();
+            let working = working.clone();
+            async move { sender.configs_for(&working, None).await.unwrap() }
+        });
+        while reads() == 0 {
+            tokio::task::yield_now().await;
+        }
+        store.delete_info("t1", None).await.unwrap();
+        sender.invalidate_push_configs(Some("t1"));
+        drop(gate);
+        assert_eq!(read.await.unwrap().len(), 1);
+
+        assert!(sender.configs_for(&working, None).await.unwrap().is_empty());
+        assert_eq!(reads(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_config_cache_evicts_least_recently_used_task() {
+        let store = Arc::new(CountingStore::new());
+        let sender = HttpPushNotificationSender::new(store.clone()).with_config_cache(2);
+        let reads = || store.reads.load(std::sync::atomic::Ordering::SeqCst);
+        let task = |id: &str| Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id(id.to_string());
+
+        for id in ["t1", "t2", "t1", "t3"] {
+            sender.configs_for(&task(id), None).await.unwrap();
+        }
+        assert_eq!(reads(), 3);
+        sender.configs_for(&task("t1"), None).await.unwrap();
+        assert_eq!(reads(), 3);
+        sender.configs_for(&task("t2"), None).await.unwrap();
+        assert_eq!(reads(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_invalidating_store_drops_cached_configs_on_write() {
+        let inner = Arc::new(InMemoryPushNotificationConfigStore::new());
+        let config = PushNotificationConfig::new("http://localhost:1/hook".parse().unwrap());
+        inner.set_info("t1", config.clone()).await.unwrap();
+        let sender = Arc::new(HttpPushNotificationSender::new(inner.clone()).with_config_cache(16));
+        let store = InvalidatingPushConfigStore::new(inner, sender.clone());
+
+        let working = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id("t1".to_string());
+        assert_eq!(sender.configs_for(&working, None).await.unwrap().len(), 1);
+        store.set_info("t1", PushNotificationConfig { id: Some("second".to_string()), ..config }).await.unwrap();
+        assert_eq!(sender.configs_for(&working, None).await.unwrap().len(), 2);
+        store.delete_info("t1", None).await.unwrap();
+        assert!(sender.configs_for(&working, None).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_notification_filter_serialization() {
+        let filter = NotificationFilter::terminal_states()
+            .with_event_kinds(vec![NotificationEventKind::StatusUpdate]);
+        let config = PushNotificationConfig::new("https://example.com/hook".parse().unwrap()).with_filter(filter.clone());
+
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["filter"]["states"][0], "completed");
+        assert_eq!(json["filter"]["eventKinds"][0], "status-update");
+
+        let parsed: PushNotificationConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.filter, Some(filter));
+
+        let unfiltered: PushNotificationConfig = serde_json::from_value(serde_json::json!({
+            "id": null,
+            "url": "https://example.com/hook",
+            "token": null,
+            "authentication": null
+        })).unwrap();
+        assert!(unfiltered.filter.is_none());
+        assert!(unfiltered.accepts(None, &TaskState::Working));
+    }
 }
@@ -0,0 +1,107 @@
+//! Retention window of soft-deleted tasks
+//!
+//! Deleting a task only hides it, so that an accidental deletion can be
+//! undone with `TaskStore::restore`. `TaskRetention` purges the tasks that
+//! have stayed deleted longer than the retention window, for good. Servers
+//! built from a configuration file run it when `store.deleted_retention_secs`
+//! is set; otherwise it is spawned by hand:
+//!
+//! ```rust,no_run
+//! # use a2a_rust::a2a::server::tasks::{InMemoryTaskStore, TaskRetention};
+//! # use std::sync::Arc;
+//! # use std::time::Duration;
+//! # async fn example() {
+//! let retention = TaskRetention::new(Arc::new(InMemoryTaskStore::new()), Duration::from_secs(30 * 24 * 3600));
+//! // Purging stops when the handle is dropped
+//! let handle = retention.spawn(Duration::from_secs(3600));
+//! # }
+//! ```
+
+use crate::a2a::server::tasks::TaskStore;
+use crate::A2AError;
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// How often servers built from a configuration file purge deleted tasks,
+/// or every retention window if it is shorter
+pub const DEFAULT_PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Purges the tasks of a store deleted longer than a retention window ago
+#[derive(Clone)]
+pub struct TaskRetention {
+    store: Arc<dyn TaskStore>,
+    window: Duration,
+}
+
+impl TaskRetention {
+    /// Keeps the deleted tasks of `store` restorable for `window`
+    pub fn new(store: Arc<dyn TaskStore>, window: Duration) -> Self {
+        Self { store, window }
+    }
+
+    /// Purges the tasks deleted before the window, returning how many
+    pub async fn purge_expired(&self) -> Result<u64, A2AError> {
+        let window = chrono::Duration::from_std(self.window).unwrap_or(chrono::Duration::MAX);
+        let cutoff = Utc::now().checked_sub_signed(window).unwrap_or_default();
+        let purged = self.store.purge_deleted(cutoff).await?;
+        if purged > 0 {
+            debug!("Purged {} deleted tasks", purged);
+        }
+        Ok(purged)
+    }
+
+    /// Spawns a Tokio task purging expired tasks every `interval` until the
+    /// returned handle is dropped
+    pub fn spawn(&self, interval: Duration) -> RetentionHandle {
+        let retention = self.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if let Err(e) = retention.purge_expired().await {
+                    warn!("Purging deleted tasks failed: {}", e);
+                }
+            }
+        });
+        RetentionHandle { task }
+    }
+}
+
+/// The purging task of a `TaskRetention`, aborted when dropped
+#[derive(Debug)]
+pub struct RetentionHandle {
+    task: JoinHandle<()>,
+}
+
+impl Drop for RetentionHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::server::tasks::InMemoryTaskStore;
+    use crate::{Task, TaskState, TaskStatus};
+
+    #[tokio::test]
+    async fn test_purges_only_tasks_deleted_before_the_window() {
+        let store = Arc::new(InMemoryTaskStore::new());
+        let task = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Completed));
+        store.save(task.clone()).await.unwrap();
+        store.delete(&task.id).await.unwrap();
+
+        let retention = TaskRetention::new(store.clone(), Duration::from_secs(3600));
+        assert_eq!(retention.purge_expired().await.unwrap(), 0);
+        assert_eq!(store.list_deleted().await.unwrap().len(), 1);
+
+        let retention = TaskRetention::new(store.clone(), Duration::ZERO);
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(retention.purge_expired().await.unwrap(), 1);
+        assert!(store.list_deleted().await.unwrap().is_empty());
+        assert!(store.restore(&task.id).await.is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! Task search by message content
+//!
+//! `TaskStore::search_tasks` finds the tasks whose history holds every word
+//! of a query in its text parts, e.g. to find the task where a user
+//! mentioned an order number. Words are runs of letters and digits, compared
+//! case-insensitively; the order of the words and the other parts of the
+//! messages do not matter.
+//!
+//! `InMemoryTaskStore` scans its tasks. `SqlTaskStore` searches a full-text
+//! index of the dialect, SQLite FTS5 for `SqliteTaskStore`, kept up to date
+//! on every save once enabled with `with_search_index`.
+//!
+//! Servers expose the search as the `tasks/search` JSON-RPC extension
+//! method with `JSONRPCHandler::with_task_search`. The method only finds the
+//! tasks of the caller's tenant, as recorded in the `tenant` metadata by
+//! `DefaultRequestHandler::with_tenant_metadata`.
+
+use crate::a2a::server::quota::TENANT_METADATA_KEY;
+use crate::{Message, PartRoot, Task, TaskState};
+use serde::{Deserialize, Serialize};
+
+/// Most tasks a search returns when the filter sets no limit
+pub const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+/// Most tasks a search returns, whatever limit the filter sets
+pub const MAX_SEARCH_LIMIT: usize = 500;
+
+/// Which of the matching tasks a search returns
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskSearchFilter {
+    /// Only tasks of this context
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_id: Option<String>,
+    /// Only tasks in one of these states
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub states: Option<Vec<TaskState>>,
+    /// Most tasks returned, `DEFAULT_SEARCH_LIMIT` if unset and at most
+    /// `MAX_SEARCH_LIMIT`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Only tasks of this tenant; set by the server, never by the caller
+    #[serde(skip)]
+    pub tenant: Option<String>,
+}
+
+impl TaskSearchFilter {
+    /// Only tasks of `context_id`
+    pub fn with_context_id(mut self, context_id: impl Into<String>) -> Self {
+        self.context_id = Some(context_id.into());
+        self
+    }
+
+    /// Only tasks in one of `states`
+    pub fn with_states(mut self, states: Vec<TaskState>) -> Self {
+        self.states = Some(states);
+        self
+    }
+
+    /// At most `limit` tasks
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Only tasks whose `tenant` metadata is `tenant`
+    pub fn with_tenant(mut self, tenant: impl Into<String>) -> Self {
+        self.tenant = Some(tenant.into());
+        self
+    }
+
+    /// The number of tasks a search returns at most
+    pub fn effective_limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).min(MAX_SEARCH_LIMIT)
+    }
+
+    /// Whether the context, state and tenant of `task` pass the filter
+    pub fn matches(&self, task: &Task) -> bool {
+        self.context_id.as_ref().is_none_or(|context_id| *context_id == task.context_id)
+            && self.states.as_ref().is_none_or(|states| states.contains(&task.status.state))
+            && self.tenant.as_ref().is_none_or(|tenant| {
+                let owner = task.metadata.as_ref().and_then(|metadata| metadata.get(TENANT_METADATA_KEY));
+                owner.and_then(|owner| owner.as_str()) == Some(tenant.as_str())
+            })
+    }
+
+    /// Keeps the tasks passing the filter, most recently updated first, up to the limit
+    pub fn apply(&self, tasks: impl IntoIterator<Item = Task>) -> Vec<Task> {
+        let mut tasks: Vec<Task> = tasks.into_iter().filter(|task| self.matches(task)).collect();
+        tasks.sort_by(|a, b| b.status.cmp_recorded(&a.status).then_with(|| a.id.cmp(&b.id)));
+        tasks.truncate(self.effective_limit());
+        tasks
+    }
+}
+
+/// Parameters of the `tasks/search` method
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskSearchParams {
+    /// The words to look for
+    pub query: String,
+    #[serde(flatten)]
+    pub filter: TaskSearchFilter,
+}
+
+/// The lowercase words of `text`
+pub fn search_terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// The text parts of the task history, one per line
+pub fn task_text(task: &Task) -> String {
+    task.history
+        .iter()
+        .flatten()
+        .flat_map(message_texts)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Whether the task history holds every one of `terms`
+pub fn task_matches(task: &Task, terms: &[String]) -> bool {
+    let words = search_terms(&task_text(task));
+    terms.iter().all(|term| words.contains(term))
+}
+
+fn message_texts(message: &Message) -> impl Iterator<Item = &str> {
+    message.parts.iter().filter_map(|part| match part.root() {
+        PartRoot::Text(text) => Some(text.text.as_str()),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Part, Role, TaskStatus};
+
+    #[test]
+    fn test_terms_and_matching() {
+        assert_eq!(search_terms("Order #A-1234, please!"), vec!["order", "a", "1234", "please"]);
+
+        let task = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Completed)).with_history(vec![
+            Message::new(Role::User, vec![Part::text("Where is order 1234?".to_string())]),
+            Message::new(Role::Agent, vec![Part::data(serde_json::json!({"text": "refund"}))]),
+        ]);
+        assert!(task_matches(&task, &search_terms("ORDER 1234")));
+        assert!(!task_matches(&task, &search_terms("order refund")));
+        assert!(!task_matches(&task, &search_terms("123")));
+    }
+
+    #[test]
+    fn test_filter_params() {
+        let params: TaskSearchParams =
+            serde_json::from_value(serde_json::json!({"query": "refund", "contextId": "c1", "states": ["failed"], "limit": 5}))
+                .unwrap();
+        assert_eq!(params.query, "refund");
+        assert_eq!(
+            params.filter,
+            TaskSearchFilter::default().with_context_id("c1").with_states(vec![TaskState::Failed]).with_limit(5)
+        );
+
+        // Callers can neither pick the tenant nor lift the cap
+        let params: TaskSearchParams =
+            serde_json::from_value(serde_json::json!({"query": "refund", "tenant": "bob", "limit": 1_000_000})).unwrap();
+        assert!(params.filter.tenant.is_none());
+        assert_eq!(params.filter.effective_limit(), MAX_SEARCH_LIMIT);
+    }
+
+    #[test]
+    fn test_tenant_filter() {
+        let mut task = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Completed));
+        let alice = TaskSearchFilter::default().with_tenant("alice");
+        assert!(!alice.matches(&task));
+        task.metadata = Some(std::collections::HashMap::from([(
+            TENANT_METADATA_KEY.to_string(),
+            serde_json::json!("alice"),
+        )]));
+        assert!(alice.matches(&task));
+        assert!(!TaskSearchFilter::default().with_tenant("bob").matches(&task));
+    }
+}
@@ -9,14 +9,51 @@ use async_trait::async_trait;
 use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
 use std::str::FromStr;
 use aes_gcm::{
-    aead::{Aead, KeyInit},
+    aead::{Aead, AeadCore, KeyInit, OsRng},
     Aes256Gcm, Nonce
 };
+use sha2::{Digest, Sha256};
+use std::sync::RwLock;
+
+/// Length of the AES-GCM nonce stored in front of every ciphertext
+const NONCE_LEN: usize = 12;
+
+/// Default number of rows re-encrypted per transaction by a key rotation
+pub const DEFAULT_ROTATION_BATCH_SIZE: usize = 500;
+
+/// Progress of `rotate_encryption_key`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KeyRotationProgress {
+    /// Rows re-encrypted with the new key so far
+    pub rotated: u64,
+    /// Rows still encrypted with another key
+    pub remaining: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct EncryptionKeys {
+    current: Option<[u8; 32]>,
+    /// The key being rotated away from, while a rotation is in progress
+    previous: Option<[u8; 32]>,
+}
+
+/// Identifies the key of a row without revealing it
+fn key_id(key: &[u8; 32]) -> String {
+    Sha256::digest(key)[..8].iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
 /// SQLite implementation of PushNotificationConfigStore
+///
+/// Every config is encrypted with a fresh random nonce, stored in front of
+/// the ciphertext. Rows record the id of the key they were encrypted with,
+/// so a key rotation re-encrypts only the rows still under another key and
+/// can be resumed after an interruption by running it again with the same
+/// keys. Rows written by earlier versions, encrypted without a stored nonce,
+/// are still read and get a nonce when rotated or saved again.
 pub struct SqlitePushNotificationConfigStore {
     pool: SqlitePool,
     table_name: String,
-    encryption_key: Option<[u8; 32]>,
+    keys: RwLock<EncryptionKeys>,
 }
 
 impl SqlitePushNotificationConfigStore {
@@ -25,7 +62,10 @@ impl SqlitePushNotificationConfigStore {
         Self {
             pool,
             table_name: "push_notification_configs".to_string(),
-            encryption_key,
+            keys: RwLock::new(EncryptionKeys {
+                current: encryption_key,
+                previous: None,
+            }),
         }
     }
 
@@ -51,6 +91,7 @@ impl SqlitePushNotificationConfigStore {
                 task_id TEXT NOT NULL,
                 config_id TEXT NOT NULL,
                 config_data BLOB NOT NULL,
+                key_id TEXT,
                 PRIMARY KEY (task_id, config_id)
             )",
             self.table_name
@@ -61,44 +102,168 @@ impl SqlitePushNotificationConfigStore {
             .await
             .map_err(|e| A2AError::internal(&format!("Failed to initialize database: {}", e)))?;
 
+        // Tables created before rows recorded their key lack the column
+        let columns: Vec<(String,)> = sqlx::query_as(&format!("SELECT name FROM pragma_table_info('{}')", self.table_name))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to initialize database: {}", e)))?;
+        if !columns.iter().any(|(name,)| name == "key_id") {
+            sqlx::query(&format!("ALTER TABLE {} ADD COLUMN key_id TEXT", self.table_name))
+                .execute(&self.pool)
+                .await
+                .map_err(|e| A2AError::internal(&format!("Failed to initialize database: {}", e)))?;
+        }
+
         Ok(())
     }
 
-    fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, A2AError> {
-        if let Some(key_bytes) = self.encryption_key {
-            let cipher = Aes256Gcm::new_from_slice(&key_bytes)
-                .map_err(|e| A2AError::internal(&format!("Invalid encryption key: {}", e)))?;
-            
-            // In a real implementation, we should use a unique nonce per encryption
-            // and store it alongside the ciphertext. For simplicity and alignment 
-            // with the basic idea, we'll use a fixed nonce here, but NOTE: 
-            // this is NOT secure for production.
-            let nonce = Nonce::from([0u8; 12]); // 12-byte nonce for AES-GCM
-            
-            let ciphertext = cipher.encrypt(&nonce, data)
-                .map_err(|e| A2AError::internal(&format!("Encryption failed: {}", e)))?;
-            
-            Ok(ciphertext)
-        } else {
-            Ok(data.to_vec())
+    fn encrypt_with(key: &[u8; 32], data: &[u8]) -> Result<Vec<u8>, A2AError> {
+        let cipher = Aes256Gcm::new_from_slice(key)
+            .map_err(|e| A2AError::internal(&format!("Invalid encryption key: {}", e)))?;
+        
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher.encrypt(&nonce, data)
+            .map_err(|e| A2AError::internal(&format!("Encryption failed: {}", e)))?;
+
+        let mut stored = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        stored.extend_from_slice(&nonce);
+        stored.extend_from_slice(&ciphertext);
+        Ok(stored)
+    }
+
+    fn decrypt_with(key: &[u8; 32], data: &[u8]) -> Result<Vec<u8>, A2AError> {
+        let cipher = Aes256Gcm::new_from_slice(key)
+            .map_err(|e| A2AError::internal(&format!("Invalid encryption key: {}", e)))?;
+        
+        // The ciphertext authenticates the nonce, so a legacy row is never
+        // mistaken for one with a stored nonce
+        let prefixed = data.split_at_checked(NONCE_LEN).and_then(|(nonce, ciphertext)| {
+            cipher.decrypt(Nonce::from_slice(nonce), ciphertext).ok()
+        });
+        match prefixed {
+            Some(plaintext) => Ok(plaintext),
+            // Rows written with the fixed all-zero nonce of earlier versions
+            None => cipher.decrypt(&Nonce::from([0u8; NONCE_LEN]), data)
+                .map_err(|e| A2AError::internal(&format!("Decryption failed: {}", e))),
         }
     }
 
-    fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, A2AError> {
-        if let Some(key_bytes) = self.encryption_key {
-            let cipher = Aes256Gcm::new_from_slice(&key_bytes)
-                .map_err(|e| A2AError::internal(&format!("Invalid encryption key: {}", e)))?;
-            
-            let nonce = Nonce::from([0u8; 12]); // 12-byte nonce for AES-GCM
-            
-            let plaintext = cipher.decrypt(&nonce, data)
-                .map_err(|e| A2AError::internal(&format!("Decryption failed: {}", e)))?;
-            
-            Ok(plaintext)
-        } else {
-            Ok(data.to_vec())
+    /// Encrypts with the current key, returning the ciphertext and key id
+    fn encrypt(&self, data: &[u8]) -> Result<(Vec<u8>, Option<String>), A2AError> {
+        match self.keys.read().unwrap().current {
+            Some(key) => Ok((Self::encrypt_with(&key, data)?, Some(key_id(&key)))),
+            None => Ok((data.to_vec(), None)),
         }
     }
+
+    /// Decrypts a row encrypted with the key `row_key_id`, or with an
+    /// unrecorded key for rows written before key ids were stored
+    fn decrypt(&self, data: &[u8], row_key_id: Option<&str>) -> Result<Vec<u8>, A2AError> {
+        let keys = *self.keys.read().unwrap();
+        let candidates: Vec<[u8; 32]> = keys.current.into_iter().chain(keys.previous).collect();
+        if candidates.is_empty() {
+            return Ok(data.to_vec());
+        }
+        match row_key_id {
+            Some(id) => match candidates.iter().find(|key| key_id(key) == id) {
+                Some(key) => Self::decrypt_with(key, data),
+                None => Err(A2AError::internal(&format!("Config is encrypted with unknown key {}", id))),
+            },
+            None => candidates
+                .iter()
+                .find_map(|key| Self::decrypt_with(key, data).ok())
+                .ok_or_else(|| A2AError::internal("Decryption failed")),
+        }
+    }
+
+    /// Re-encrypts every stored config from `old` to `new`
+    ///
+    /// The store encrypts with `new` from the start of the rotation and
+    /// reads rows under either key until it completes. Rows are rotated in
+    /// batches of `DEFAULT_ROTATION_BATCH_SIZE`, one transaction each, and
+    /// progress is logged after every batch. If the rotation is interrupted,
+    /// run it again with the same keys to rotate the remaining rows.
+    pub async fn rotate_encryption_key(&self, old: [u8; 32], new: [u8; 32]) -> Result<KeyRotationProgress, A2AError> {
+        self.rotate_encryption_key_with(old, new, DEFAULT_ROTATION_BATCH_SIZE, |progress| {
+            tracing::info!(
+                "Re-encrypted {} push notification configs, {} remaining",
+                progress.rotated,
+                progress.remaining
+            );
+        })
+        .await
+    }
+
+    /// Like `rotate_encryption_key`, with the batch size and a callback
+    /// receiving the progress after every batch
+    pub async fn rotate_encryption_key_with(
+        &self,
+        old: [u8; 32],
+        new: [u8; 32],
+        batch_size: usize,
+        mut on_progress: impl FnMut(&KeyRotationProgress) + Send,
+    ) -> Result<KeyRotationProgress, A2AError> {
+        let db_error = |e: sqlx::Error| A2AError::internal(&format!("Failed to rotate encryption key: {}", e));
+        let new_id = key_id(&new);
+        *self.keys.write().unwrap() = EncryptionKeys {
+            current: Some(new),
+            previous: Some(old),
+        };
+
+        let (remaining,): (i64,) = sqlx::query_as(&format!(
+            "SELECT COUNT(*) FROM {} WHERE key_id IS NULL OR key_id != ?",
+            self.table_name
+        ))
+        .bind(&new_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(db_error)?;
+        let mut progress = KeyRotationProgress {
+            rotated: 0,
+            remaining: remaining as u64,
+        };
+
+        loop {
+            let mut tx = self.pool.begin().await.map_err(db_error)?;
+            let rows: Vec<(i64, Vec<u8>, Option<String>)> = sqlx::query_as(&format!(
+                "SELECT rowid, config_data, key_id FROM {} WHERE key_id IS NULL OR key_id != ? ORDER BY rowid LIMIT ?",
+                self.table_name
+            ))
+            .bind(&new_id)
+            .bind(batch_size.max(1) as i64)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(db_error)?;
+            if rows.is_empty() {
+                break;
+            }
+
+            let batch = rows.len() as u64;
+            for (rowid, data, row_key_id) in rows {
+                let plaintext = match self.decrypt(&data, row_key_id.as_deref()) {
+                    Ok(plaintext) => plaintext,
+                    // Rows stored before encryption was enabled
+                    Err(_) if row_key_id.is_none() && serde_json::from_slice::<serde_json::Value>(&data).is_ok() => data,
+                    Err(e) => return Err(e),
+                };
+                sqlx::query(&format!("UPDATE {} SET config_data = ?, key_id = ? WHERE rowid = ?", self.table_name))
+                    .bind(Self::encrypt_with(&new, &plaintext)?)
+                    .bind(&new_id)
+                    .bind(rowid)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(db_error)?;
+            }
+            tx.commit().await.map_err(db_error)?;
+
+            progress.rotated += batch;
+            progress.remaining = progress.remaining.saturating_sub(batch);
+            on_progress(&progress);
+        }
+
+        self.keys.write().unwrap().previous = None;
+        Ok(progress)
+    }
 }
 
 #[async_trait]
@@ -108,10 +273,10 @@ impl PushNotificationConfigStore for SqlitePushNotificationConfigStore {
         let json_data = serde_json::to_vec(&config)
             .map_err(|e| A2AError::internal(&format!("Failed to serialize config: {}", e)))?;
         
-        let data_to_store = self.encrypt(&json_data)?;
+        let (data_to_store, key_id) = self.encrypt(&json_data)?;
 
         let query = format!(
-            "INSERT OR REPLACE INTO {} (task_id, config_id, config_data) VALUES (?, ?, ?)",
+            "INSERT OR REPLACE INTO {} (task_id, config_id, config_data, key_id) VALUES (?, ?, ?, ?)",
             self.table_name
         );
 
@@ -119,6 +284,7 @@ impl PushNotificationConfigStore for SqlitePushNotificationConfigStore {
             .bind(task_id)
             .bind(config_id)
             .bind(data_to_store)
+            .bind(key_id)
             .execute(&self.pool)
             .await
             .map_err(|e| A2AError::internal(&format!("Failed to save config: {}", e)))?;
@@ -128,19 +294,19 @@ impl PushNotificationConfigStore for SqlitePushNotificationConfigStore {
 
     async fn get_info(&self, task_id: &str) -> Result<Vec<PushNotificationConfig>, A2AError> {
         let query = format!(
-            "SELECT config_data FROM {} WHERE task_id = ?",
+            "SELECT config_data, key_id FROM {} WHERE task_id = ?",
             self.table_name
         );
 
-        let rows: Vec<(Vec<u8>,)> = sqlx::query_as(&query)
+        let rows: Vec<(Vec<u8>, Option<String>)> = sqlx::query_as(&query)
             .bind(task_id)
             .fetch_all(&self.pool)
             .await
             .map_err(|e| A2AError::internal(&format!("Failed to get configs: {}", e)))?;
 
         let mut configs = Vec::new();
-        for (data,) in rows {
-            let decrypted_data = match self.decrypt(&data) {
+        for (data, key_id) in rows {
+            let decrypted_data = match self.decrypt(&data, key_id.as_deref()) {
                 Ok(d) => d,
                 Err(_) => data.clone(), // Fallback to plain data if decryption fails
             };
@@ -201,4 +367,94 @@ mod tests {
         
         assert!(serde_json::from_slice::<serde_json::Value>(&row.0).is_err());
     }
+
+    #[tokio::test]
+    async fn test_every_config_gets_its_own_nonce_and_legacy_rows_are_read() {
+        let key = [3u8; 32];
+        let plaintext = br#"{"url":"https://example.com/callback"}"#;
+        let first = SqlitePushNotificationConfigStore::encrypt_with(&key, plaintext).unwrap();
+        let second = SqlitePushNotificationConfigStore::encrypt_with(&key, plaintext).unwrap();
+        assert_ne!(first[..NONCE_LEN], second[..NONCE_LEN]);
+        assert_ne!(first, second);
+        assert_eq!(SqlitePushNotificationConfigStore::decrypt_with(&key, &first).unwrap(), plaintext);
+
+        // A row encrypted with the all-zero nonce and no stored nonce
+        let store = SqlitePushNotificationConfigStore::connect("sqlite::memory:", Some(key)).await.unwrap();
+        let legacy = Aes256Gcm::new_from_slice(&key)
+            .unwrap()
+            .encrypt(&Nonce::from([0u8; NONCE_LEN]), &plaintext[..])
+            .unwrap();
+        sqlx::query("INSERT INTO push_notification_configs (task_id, config_id, config_data, key_id) VALUES (?, ?, ?, ?)")
+            .bind("task-1")
+            .bind("config-1")
+            .bind(legacy)
+            .bind(key_id(&key))
+            .execute(&store.pool)
+            .await
+            .unwrap();
+        let configs = store.get_info("task-1").await.unwrap();
+        assert_eq!(configs[0].url.as_str(), "https://example.com/callback");
+    }
+
+    async fn seeded_store(url: &str, key: [u8; 32], configs: usize) -> SqlitePushNotificationConfigStore {
+        let store = SqlitePushNotificationConfigStore::connect(url, Some(key)).await.unwrap();
+        for i in 0..configs {
+            let mut config = PushNotificationConfig::new(Url::parse(&format!("https://example.com/{}", i)).unwrap());
+            config.id = Some(format!("config-{}", i));
+            store.set_info("task-1", config).await.unwrap();
+        }
+        store
+    }
+
+    #[tokio::test]
+    async fn test_rotate_encryption_key_in_batches() {
+        let path = std::env::temp_dir().join(format!("a2a-push-rotation-{}.db", uuid::Uuid::new_v4()));
+        let url = format!("sqlite://{}", path.display());
+        let (old, new) = ([1u8; 32], [2u8; 32]);
+        let store = seeded_store(&url, old, 5).await;
+
+        let mut reports = Vec::new();
+        let progress = store
+            .rotate_encryption_key_with(old, new, 2, |progress| reports.push(*progress))
+            .await
+            .unwrap();
+        assert_eq!(progress, KeyRotationProgress { rotated: 5, remaining: 0 });
+        assert_eq!(reports.iter().map(|p| p.rotated).collect::<Vec<_>>(), vec![2, 4, 5]);
+        assert_eq!(store.get_info("task-1").await.unwrap().len(), 5);
+
+        // Only the new key is needed once the rotation completed
+        let reopened = SqlitePushNotificationConfigStore::connect(&url, Some(new)).await.unwrap();
+        assert_eq!(reopened.get_info("task-1").await.unwrap().len(), 5);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_rotation_resumes_with_remaining_rows() {
+        let path = std::env::temp_dir().join(format!("a2a-push-rotation-{}.db", uuid::Uuid::new_v4()));
+        let url = format!("sqlite://{}", path.display());
+        let (old, new) = ([1u8; 32], [2u8; 32]);
+        let store = seeded_store(&url, old, 5).await;
+
+        // Two rows were rotated before the interruption
+        let rows: Vec<(i64, Vec<u8>)> = sqlx::query_as("SELECT rowid, config_data FROM push_notification_configs ORDER BY rowid LIMIT 2")
+            .fetch_all(&store.pool)
+            .await
+            .unwrap();
+        for (rowid, data) in rows {
+            let plaintext = SqlitePushNotificationConfigStore::decrypt_with(&old, &data).unwrap();
+            sqlx::query("UPDATE push_notification_configs SET config_data = ?, key_id = ? WHERE rowid = ?")
+                .bind(SqlitePushNotificationConfigStore::encrypt_with(&new, &plaintext).unwrap())
+                .bind(key_id(&new))
+                .bind(rowid)
+                .execute(&store.pool)
+                .await
+                .unwrap();
+        }
+
+        let resumed = SqlitePushNotificationConfigStore::connect(&url, Some(new)).await.unwrap();
+        let progress = resumed.rotate_encryption_key(old, new).await.unwrap();
+        assert_eq!(progress, KeyRotationProgress { rotated: 3, remaining: 0 });
+        assert_eq!(resumed.get_info("task-1").await.unwrap().len(), 5);
+        std::fs::remove_file(&path).unwrap();
+    }
 }
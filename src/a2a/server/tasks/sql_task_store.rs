@@ -1,250 +1,1473 @@
 //! SQL implementation of TaskStore using sqlx
 //! 
-//! This module provides a persistent task store implementation using sqlx
-//! with support for SQLite.
+//! This module provides a persistent task store over an sqlx `AnyPool`.
+//! Everything that differs between databases is described by a small
+//! `SqlDialect`: the bind parameter syntax, the upsert statement and the
+//! column types used by the schema. Supporting another database means
+//! implementing the dialect and enabling its sqlx driver; `SqliteTaskStore`
+//! is the store with the SQLite dialect, and can still be created from a
+//! `SqlitePool`.
+//!
+//! With the `compression` feature, `with_compression` stores the history and
+//! artifacts columns zstd compressed. Rows are read whether they were written
+//! compressed or not.
+//!
+//! Deleting a task sets its `deleted_at` column, hiding it from every read
+//! and refusing saves to it, until it is restored or purged with its labels
+//! and index rows.
+//!
+//! With `with_search_index`, the text parts of task histories are also
+//! written to the full-text index of the dialect, which `search_tasks`
+//! queries.
 
 use crate::{Task, A2AError};
-use crate::a2a::server::tasks::task_store::TaskStore;
+use crate::a2a::server::tasks::labels::{validate_labels, LabelRequirement, LabelSelector, Labels};
+use crate::a2a::server::tasks::usage::{Usage, UsageRecord};
+use crate::a2a::server::quota::TENANT_METADATA_KEY;
+use crate::a2a::server::tasks::search::{search_terms, task_text, TaskSearchFilter};
+use crate::a2a::server::tasks::data_subject::erased_task;
+use crate::a2a::server::tasks::task_store::{DeletedTask, HistoryPage, ReadConsistency, TaskStore, TaskStoreTransaction};
+use crate::a2a::server::tasks::outbox::{OutboxEntry, OutboxStore};
+use crate::a2a::server::tasks::column_compression;
 use async_trait::async_trait;
-use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
-use std::str::FromStr;
+use chrono::{DateTime, Utc};
+use sqlx::any::AnyPoolOptions;
+use sqlx::{AnyConnection, AnyPool, ConnectOptions, SqlitePool};
+use std::marker::PhantomData;
 
-/// SQLite implementation of TaskStore
-pub struct SqliteTaskStore {
-    pool: SqlitePool,
+/// Outbox entry states
+const OUTBOX_PENDING: &str = "pending";
+const OUTBOX_DELIVERED: &str = "delivered";
+const OUTBOX_DEAD: &str = "dead";
+
+/// Columns of the task table, in the order of `TaskRow`
+const TASK_COLUMNS: [&str; 8] = [
+    "id",
+    "context_id",
+    "kind",
+    "status",
+    "artifacts",
+    "history",
+    "metadata",
+    "state_transitions",
+];
+
+/// Row layout shared by all task queries
+type TaskRow = (String, String, String, String, String, String, String, String);
+
+/// Row layout of deleted task queries, `TaskRow` and the deletion time in milliseconds
+type DeletedTaskRow = (String, String, String, String, String, String, String, String, i64);
+
+/// Columns of the usage table, in the order of `UsageRow`
+const USAGE_COLUMNS: &str = "task_id, context_id, principal, input_tokens, output_tokens, compute_units";
+
+/// Row layout of usage queries
+type UsageRow = (String, String, String, i64, i64, f64);
+
+/// The SQL that differs between databases
+pub trait SqlDialect: Send + Sync + 'static {
+    /// Bind parameter for the argument at `index`, starting at 1
+    fn placeholder(index: usize) -> String;
+
+    /// Statement inserting `rows` rows into `table`, replacing rows with the
+    /// same `key`; the values are `?` parameters, row by row in column order
+    fn upsert(table: &str, columns: &[&str], key: &str, rows: usize) -> String;
+
+    /// Column type of JSON documents, which are bound and read as text
+    const JSON_TYPE: &'static str;
+
+    /// Column definition of an auto-incrementing integer primary key
+    const AUTO_INCREMENT_KEY: &'static str;
+
+    /// Adjusts a database URL before connecting
+    fn connect_url(url: &str) -> String {
+        url.to_string()
+    }
+
+    /// The full-text index of task texts named `table`, if the database has
+    /// full-text search
+    ///
+    /// On PostgreSQL, for example, the index would be a table with a
+    /// generated `document tsvector GENERATED ALWAYS AS (to_tsvector('simple',
+    /// content)) STORED` column and a GIN index over it, matched with
+    /// `document @@ plainto_tsquery('simple', ?)`.
+    fn search_index(_table: &str) -> Option<SearchIndexSql> {
+        None
+    }
+
+    /// Expression reading the `key` field of the JSON document in `column`
+    /// as text; `({column}::jsonb ->> '{key}')` on PostgreSQL
+    fn json_text(column: &str, key: &str) -> String {
+        format!("json_extract({}, '$.{}')", column, key)
+    }
+}
+
+/// The SQL of a full-text index of task texts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchIndexSql {
+    /// Statements creating the index, a table with `task_id` and `content`
+    /// text columns, if it does not exist
+    pub create: Vec<String>,
+    /// Query selecting the `task_id` of the rows whose content holds every
+    /// word of the `?` parameter, lowercase words separated by spaces
+    pub matching: String,
+}
+
+/// The SQLite dialect
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SqliteDialect;
+
+impl SqlDialect for SqliteDialect {
+    fn placeholder(_index: usize) -> String {
+        "?".to_string()
+    }
+
+    fn upsert(table: &str, columns: &[&str], _key: &str, rows: usize) -> String {
+        format!("INSERT OR REPLACE INTO {} ({}) VALUES {}", table, columns.join(", "), values(columns.len(), rows))
+    }
+
+    const JSON_TYPE: &'static str = "TEXT";
+
+    const AUTO_INCREMENT_KEY: &'static str = "INTEGER PRIMARY KEY AUTOINCREMENT";
+
+    /// An FTS5 table
+    fn search_index(table: &str) -> Option<SearchIndexSql> {
+        Some(SearchIndexSql {
+            create: vec![format!("CREATE VIRTUAL TABLE IF NOT EXISTS {} USING fts5(task_id UNINDEXED, content)", table)],
+            matching: format!("SELECT task_id FROM {} WHERE {} MATCH ?", table, table),
+        })
+    }
+
+    /// Creates file databases that do not exist yet
+    ///
+    /// An in-memory database is named, so that every connection of the pool
+    /// opens the same one.
+    fn connect_url(url: &str) -> String {
+        if url.contains(":memory:") {
+            format!("sqlite:file:a2a-memory-{}?mode=memory&cache=shared", uuid::Uuid::new_v4())
+        } else if url.contains("mode=") {
+            url.to_string()
+        } else if url.contains('?') {
+            format!("{}&mode=rwc", url)
+        } else {
+            format!("{}?mode=rwc", url)
+        }
+    }
+}
+
+/// `rows` tuples of `columns` parameters, for a multi-row `VALUES` clause
+pub fn values(columns: usize, rows: usize) -> String {
+    let row = format!("({})", vec!["?"; columns].join(", "));
+    vec![row; rows].join(", ")
+}
+
+/// The serialized status, artifacts, history, metadata and state transitions
+/// of a task
+type JsonColumns = (String, Option<String>, Option<String>, Option<String>, Option<String>);
+
+/// Number of rows written or matched by a single bulk statement, which keeps
+/// the bind parameters below the limits of every supported database
+const BULK_CHUNK: usize = 100;
+
+/// Task store over any database with a `SqlDialect`
+pub struct SqlTaskStore<D: SqlDialect> {
+    pool: AnyPool,
+    /// The pool a `SqliteTaskStore` was created from, kept open so that a
+    /// shared in-memory database lives as long as the store
+    sqlite_pool: Option<SqlitePool>,
+    read_pool: Option<AnyPool>,
     table_name: String,
+    outbox: bool,
+    search_index: bool,
+    compression_level: Option<i32>,
+    dialect: PhantomData<D>,
 }
 
-impl SqliteTaskStore {
-    /// Creates a new SqliteTaskStore with the given connection pool
-    pub fn new(pool: SqlitePool) -> Self {
+impl<D: SqlDialect> Clone for SqlTaskStore<D> {
+    fn clone(&self) -> Self {
         Self {
-            pool,
-            table_name: "tasks".to_string(),
+            pool: self.pool.clone(),
+            sqlite_pool: self.sqlite_pool.clone(),
+            read_pool: self.read_pool.clone(),
+            table_name: self.table_name.clone(),
+            outbox: self.outbox,
+            search_index: self.search_index,
+            compression_level: self.compression_level,
+            dialect: PhantomData,
         }
     }
+}
+
+/// SQLite implementation of TaskStore
+pub type SqliteTaskStore = SqlTaskStore<SqliteDialect>;
+
+impl SqlTaskStore<SqliteDialect> {
+    /// Creates a new SqliteTaskStore with the given connection pool
+    ///
+    /// The store opens its own connections to the database of `pool`, with the
+    /// same pool limits. Settings that are not part of a SQLite URL, such as
+    /// custom pragmas or collations, do not carry over; use `from_any_pool`
+    /// to control them. Must be called from within a Tokio runtime.
+    pub fn new(pool: SqlitePool) -> Self {
+        Self::with_table_name(pool, "tasks".to_string())
+    }
 
     /// Creates a new SqliteTaskStore with a custom table name
     pub fn with_table_name(pool: SqlitePool, table_name: String) -> Self {
+        sqlx::any::install_default_drivers();
+        let options = pool.options();
+        let any_pool = AnyPoolOptions::new()
+            .max_connections(options.get_max_connections())
+            .min_connections(options.get_min_connections())
+            .acquire_timeout(options.get_acquire_timeout())
+            .idle_timeout(options.get_idle_timeout())
+            .max_lifetime(options.get_max_lifetime())
+            .connect_lazy(pool.connect_options().to_url_lossy().as_str())
+            .expect("a SQLite URL is a valid Any URL");
+        let mut store = Self::from_any_pool_with_table_name(any_pool, table_name);
+        store.sqlite_pool = Some(pool);
+        store
+    }
+}
+
+impl<D: SqlDialect> SqlTaskStore<D> {
+    /// Creates a new store over a pool of the `Any` driver
+    ///
+    /// The sqlx drivers must have been installed, e.g. with
+    /// `sqlx::any::install_default_drivers`.
+    pub fn from_any_pool(pool: AnyPool) -> Self {
+        Self::from_any_pool_with_table_name(pool, "tasks".to_string())
+    }
+
+    /// Creates a new store over a pool of the `Any` driver with a custom table name
+    pub fn from_any_pool_with_table_name(pool: AnyPool, table_name: String) -> Self {
         Self {
             pool,
+            sqlite_pool: None,
+            read_pool: None,
             table_name,
+            outbox: false,
+            search_index: false,
+            compression_level: None,
+            dialect: PhantomData,
         }
     }
 
-    /// Connects to a SQLite database and initializes the store
-    pub async fn connect(url: &str) -> Result<Self, A2AError> {
-        let options = SqliteConnectOptions::from_str(url)
-            .map_err(|e| A2AError::internal(&format!("Invalid database URL: {}", e)))?
-            .create_if_missing(true);
+    /// Records a notification intent in the outbox with every saved task
+    ///
+    /// The intent is written in the same transaction as the task, so an
+    /// `OutboxRelay` delivers it even if the process crashes right after the
+    /// save.
+    pub fn with_outbox(mut self) -> Self {
+        self.outbox = true;
+        self
+    }
 
-        let pool = SqlitePool::connect_with(options)
-            .await
-            .map_err(|e| A2AError::internal(&format!("Failed to connect to database: {}", e)))?;
+    /// Indexes the text parts of task histories on every save, for `search_tasks`
+    ///
+    /// The dialect must have a full-text index. Tasks saved before are not
+    /// found until saved again or indexed with `rebuild_search_index`.
+    pub fn with_search_index(mut self) -> Self {
+        self.search_index = true;
+        self
+    }
+
+    /// Serves eventually consistent reads from a read replica
+    ///
+    /// Writes and strongly consistent reads keep using the primary pool. The
+    /// replica is expected to carry the schema of the primary.
+    pub fn with_read_replica(mut self, read_pool: AnyPool) -> Self {
+        self.read_pool = Some(read_pool);
+        self
+    }
+
+    /// Stores the history and artifacts of tasks zstd compressed at `level`
+    ///
+    /// Tasks saved before keep reading, and are compressed when saved again.
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, level: i32) -> Self {
+        self.compression_level = Some(level);
+        self
+    }
 
-        let store = Self::new(pool);
+    /// Connects to a database and initializes the store
+    pub async fn connect(url: &str) -> Result<Self, A2AError> {
+        let store = Self::from_any_pool(Self::connect_pool(url).await?);
         store.initialize().await?;
         Ok(store)
     }
 
+    /// Connects to a primary database, initializing it, and a read replica
+    pub async fn connect_with_read_replica(url: &str, replica_url: &str) -> Result<Self, A2AError> {
+        let replica = Self::connect_pool(replica_url).await?;
+        Ok(Self::connect(url).await?.with_read_replica(replica))
+    }
+
+    async fn connect_pool(url: &str) -> Result<AnyPool, A2AError> {
+        sqlx::any::install_default_drivers();
+        AnyPool::connect(&D::connect_url(url))
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to connect to database: {}", e)))
+    }
+
+    /// The pool serving reads of the given consistency
+    fn reader(&self, consistency: ReadConsistency) -> &AnyPool {
+        match (consistency, &self.read_pool) {
+            (ReadConsistency::Eventual, Some(read_pool)) => read_pool,
+            _ => &self.pool,
+        }
+    }
+
     /// Initializes the database schema
     pub async fn initialize(&self) -> Result<(), A2AError> {
         let query = format!(
-            "CREATE TABLE IF NOT EXISTS {} (
+            "CREATE TABLE IF NOT EXISTS {table} (
                 id TEXT PRIMARY KEY,
                 context_id TEXT NOT NULL,
                 kind TEXT NOT NULL,
+                status {json} NOT NULL,
+                artifacts {json},
+                history {json},
+                metadata {json},
+                state_transitions {json},
+                deleted_at BIGINT
+            )",
+            table = self.table_name,
+            json = D::JSON_TYPE
+        );
+
+        let labels_table = self.labels_table_name();
+        let labels_query = format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                task_id TEXT NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                PRIMARY KEY (task_id, key)
+            )",
+            labels_table
+        );
+        let index_query = format!(
+            "CREATE INDEX IF NOT EXISTS idx_{}_key_value ON {} (key, value)",
+            labels_table, labels_table
+        );
+
+        let usage_query = format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                task_id TEXT PRIMARY KEY,
+                context_id TEXT NOT NULL,
+                principal TEXT NOT NULL,
+                input_tokens BIGINT NOT NULL,
+                output_tokens BIGINT NOT NULL,
+                compute_units DOUBLE PRECISION NOT NULL
+            )",
+            self.usage_table_name()
+        );
+        let usage_index_queries = ["context_id", "principal"].map(|column| {
+            format!(
+                "CREATE INDEX IF NOT EXISTS idx_{}_{} ON {} ({})",
+                self.usage_table_name(),
+                column,
+                self.usage_table_name(),
+                column
+            )
+        });
+
+        let outbox_table = self.outbox_table_name();
+        let outbox_query = format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                id {},
+                task_id TEXT NOT NULL,
+                payload {} NOT NULL,
                 status TEXT NOT NULL,
-                artifacts TEXT,
-                history TEXT,
-                metadata TEXT
+                attempts INTEGER NOT NULL DEFAULT 0,
+                created_at BIGINT NOT NULL,
+                next_attempt_at BIGINT NOT NULL,
+                last_error TEXT
             )",
-            self.table_name
+            outbox_table,
+            D::AUTO_INCREMENT_KEY,
+            D::JSON_TYPE
+        );
+        let outbox_index_query = format!(
+            "CREATE INDEX IF NOT EXISTS idx_{}_status_next ON {} (status, next_attempt_at)",
+            outbox_table, outbox_table
         );
 
-        sqlx::query(&query)
+        let search_queries = D::search_index(&self.search_table_name()).map(|index| index.create).unwrap_or_default();
+
+        for query in [query, labels_query, index_query, usage_query, outbox_query, outbox_index_query]
+            .into_iter()
+            .chain(usage_index_queries)
+            .chain(search_queries)
+        {
+            sqlx::query(&query)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| A2AError::internal(&format!("Failed to initialize database: {}", e)))?;
+        }
+
+        self.add_missing_column("state_transitions", D::JSON_TYPE).await?;
+        self.add_missing_column("deleted_at", "BIGINT").await
+    }
+
+    /// Adds a column to task tables created before it
+    async fn add_missing_column(&self, column: &str, column_type: &str) -> Result<(), A2AError> {
+        let probe = format!("SELECT {} FROM {} WHERE 1 = 0", column, self.table_name);
+        if sqlx::query(&probe).execute(&self.pool).await.is_ok() {
+            return Ok(());
+        }
+        let alter = format!("ALTER TABLE {} ADD COLUMN {} {}", self.table_name, column, column_type);
+        sqlx::query(&alter)
             .execute(&self.pool)
             .await
-            .map_err(|e| A2AError::internal(&format!("Failed to initialize database: {}", e)))?;
+            .map_err(|e| A2AError::internal(&format!("Failed to migrate database: {}", e)))?;
+        Ok(())
+    }
+
+    /// Name of the table holding task labels
+    fn labels_table_name(&self) -> String {
+        format!("{}_labels", self.table_name)
+    }
+
+    /// Name of the table holding usage totals
+    fn usage_table_name(&self) -> String {
+        format!("{}_usage", self.table_name)
+    }
+
+    /// Sums the usage records whose `column` is `value`
+    async fn usage_total(&self, column: &str, value: &str) -> Result<Usage, A2AError> {
+        let query = Self::sql(&format!(
+            "SELECT CAST(COALESCE(SUM(input_tokens), 0) AS BIGINT), CAST(COALESCE(SUM(output_tokens), 0) AS BIGINT), \
+             CAST(COALESCE(SUM(compute_units), 0) AS DOUBLE PRECISION) FROM {} WHERE {} = ?",
+            self.usage_table_name(),
+            column
+        ));
+
+        let (input_tokens, output_tokens, compute_units): (i64, i64, f64) = sqlx::query_as(&query)
+            .bind(value)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to sum task usage: {}", e)))?;
+
+        Ok(Usage {
+            input_tokens: input_tokens.max(0) as u64,
+            output_tokens: output_tokens.max(0) as u64,
+            compute_units,
+        })
+    }
+
+    fn usage_from_row(row: UsageRow) -> UsageRecord {
+        let (task_id, context_id, principal, input_tokens, output_tokens, compute_units) = row;
+        UsageRecord {
+            task_id,
+            context_id,
+            principal,
+            usage: Usage {
+                input_tokens: input_tokens.max(0) as u64,
+                output_tokens: output_tokens.max(0) as u64,
+                compute_units,
+            },
+        }
+    }
+
+    /// Name of the full-text index of task texts
+    fn search_table_name(&self) -> String {
+        format!("{}_search", self.table_name)
+    }
+
+    /// Indexes the whole task store again
+    ///
+    /// Needed once when enabling `with_search_index` on a store that already
+    /// holds tasks.
+    pub async fn rebuild_search_index(&self) -> Result<(), A2AError> {
+        if D::search_index(&self.search_table_name()).is_none() {
+            return Err(A2AError::unsupported_operation("Task search not supported by the database"));
+        }
+        let mut tasks = self.list().await?;
+        tasks.extend(self.list_deleted().await?.into_iter().map(|deleted| deleted.task));
+        let mut tx = self.pool.begin()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to begin transaction: {}", e)))?;
+
+        sqlx::query(&format!("DELETE FROM {}", self.search_table_name()))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to index tasks: {}", e)))?;
+        for chunk in tasks.chunks(BULK_CHUNK) {
+            self.index_tasks(&mut tx, chunk).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to commit transaction: {}", e)))?;
 
         Ok(())
     }
-}
 
-#[async_trait]
-impl TaskStore for SqliteTaskStore {
-    async fn save(&self, task: Task) -> Result<(), A2AError> {
-        let query = format!(
-            "INSERT OR REPLACE INTO {} (id, context_id, kind, status, artifacts, history, metadata)
-             VALUES (?, ?, ?, ?, ?, ?, ?)",
-            self.table_name
-        );
+    /// Inserts the index rows of tasks on `conn`, which must have none yet
+    async fn index_tasks(&self, conn: &mut AnyConnection, tasks: &[Task]) -> Result<(), A2AError> {
+        let rows: Vec<(&str, String)> = tasks
+            .iter()
+            .map(|task| (task.id.as_str(), task_text(task)))
+            .filter(|(_, text)| !text.is_empty())
+            .collect();
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let query = Self::sql(&format!(
+            "INSERT INTO {} (task_id, content) VALUES {}",
+            self.search_table_name(),
+            values(2, rows.len())
+        ));
+        let mut db_query = sqlx::query(&query);
+        for (task_id, text) in rows {
+            db_query = db_query.bind(task_id).bind(text);
+        }
+        db_query
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to index tasks: {}", e)))?;
+        Ok(())
+    }
+
+    /// Name of the table holding pending notification intents
+    fn outbox_table_name(&self) -> String {
+        format!("{}_outbox", self.table_name)
+    }
+
+    /// Rewrites the `?` parameters of `query` in the syntax of the dialect
+    fn sql(query: &str) -> String {
+        let mut index = 0;
+        query
+            .split('?')
+            .enumerate()
+            .map(|(i, part)| {
+                if i == 0 {
+                    part.to_string()
+                } else {
+                    index += 1;
+                    format!("{}{}", D::placeholder(index), part)
+                }
+            })
+            .collect()
+    }
+
+    /// Selects the task columns of the tasks in `table` that are not
+    /// deleted, optionally with an alias prefix
+    ///
+    /// The query ends with a `WHERE` clause that further conditions extend
+    /// with `AND`. Missing JSON documents are read as `null`, since the `Any`
+    /// driver cannot decode SQL `NULL` into an `Option`.
+    fn select_tasks(table: &str, alias: Option<&str>) -> String {
+        Self::select_task_columns(table, alias, true)
+    }
+
+    /// Like `select_tasks`, reading the history as `null` unless `history`
+    fn select_task_columns(table: &str, alias: Option<&str>, history: bool) -> String {
+        let prefix = alias.map(|alias| format!("{}.", alias)).unwrap_or_default();
+        let columns = Self::task_column_list(&prefix, history);
+        match alias {
+            Some(alias) => format!("SELECT {} FROM {} {} WHERE {}deleted_at IS NULL", columns, table, alias, prefix),
+            None => format!("SELECT {} FROM {} WHERE deleted_at IS NULL", columns, table),
+        }
+    }
+
+    /// The task columns in the order of `TaskRow`, prefixed with `prefix`
+    fn task_column_list(prefix: &str, history: bool) -> String {
+        TASK_COLUMNS
+            .iter()
+            .map(|column| match *column {
+                "history" if !history => "'null'".to_string(),
+                "artifacts" | "history" | "metadata" | "state_transitions" => format!("COALESCE({}{}, 'null')", prefix, column),
+                _ => format!("{}{}", prefix, column),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Writes tasks, and their outbox entries if enabled, on `conn`
+    async fn write_tasks(&self, conn: &mut AnyConnection, tasks: Vec<Task>) -> Result<(), A2AError> {
+        // A statement may not write the same key twice; the last save wins
+        let mut seen = std::collections::HashSet::new();
+        let mut tasks: Vec<Task> = tasks.into_iter().rev().filter(|task| seen.insert(task.id.clone())).collect();
+        tasks.reverse();
+
+        for chunk in tasks.chunks(BULK_CHUNK) {
+            // Only restoring brings a deleted task back
+            let query = Self::sql(&format!(
+                "SELECT id FROM {} WHERE deleted_at IS NOT NULL AND id IN ({})",
+                self.table_name,
+                vec!["?"; chunk.len()].join(", ")
+            ));
+            let mut db_query = sqlx::query_as::<_, (String,)>(&query);
+            for task in chunk {
+                db_query = db_query.bind(task.id.as_str());
+            }
+            let deleted = db_query
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| A2AError::internal(&format!("Failed to save task: {}", e)))?;
+            if let Some((task_id,)) = deleted {
+                return Err(A2AError::task_not_found(&task_id));
+            }
+
+            let query = Self::sql(&D::upsert(&self.table_name, &TASK_COLUMNS, "id", chunk.len()));
+            let mut db_query = sqlx::query(&query);
+            for task in chunk {
+                let (status_json, artifacts_json, history_json, metadata_json, transitions_json) =
+                    self.task_columns(task)?;
+                db_query = db_query
+                    .bind(task.id.clone())
+                    .bind(task.context_id.clone())
+                    .bind(task.kind.clone())
+                    .bind(status_json)
+                    .bind(artifacts_json)
+                    .bind(history_json)
+                    .bind(metadata_json)
+                    .bind(transitions_json);
+            }
+            db_query
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| A2AError::internal(&format!("Failed to save task: {}", e)))?;
 
+            if self.search_index {
+                let query = Self::sql(&format!(
+                    "DELETE FROM {} WHERE task_id IN ({})",
+                    self.search_table_name(),
+                    vec!["?"; chunk.len()].join(", ")
+                ));
+                let mut db_query = sqlx::query(&query);
+                for task in chunk {
+                    db_query = db_query.bind(task.id.as_str());
+                }
+                db_query
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| A2AError::internal(&format!("Failed to index tasks: {}", e)))?;
+                self.index_tasks(conn, chunk).await?;
+            }
+
+            if self.outbox {
+                let now = Utc::now().timestamp_millis();
+                let outbox_query = Self::sql(&format!(
+                    "INSERT INTO {} (task_id, payload, status, attempts, created_at, next_attempt_at) VALUES {}",
+                    self.outbox_table_name(),
+                    vec!["(?, ?, ?, 0, ?, ?)"; chunk.len()].join(", ")
+                ));
+                let mut db_query = sqlx::query(&outbox_query);
+                for task in chunk {
+                    let payload = serde_json::to_string(task)
+                        .map_err(|e| A2AError::internal(&format!("Failed to serialize task: {}", e)))?;
+                    db_query = db_query
+                        .bind(task.id.clone())
+                        .bind(payload)
+                        .bind(OUTBOX_PENDING)
+                        .bind(now)
+                        .bind(now);
+                }
+                db_query
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| A2AError::internal(&format!("Failed to record outbox entry: {}", e)))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads a task on `conn`
+    async fn read_task(&self, conn: &mut AnyConnection, task_id: &str) -> Result<Option<Task>, A2AError> {
+        let query = Self::sql(&format!("{} AND id = ?", Self::select_tasks(&self.table_name, None)));
+
+        let row = sqlx::query_as::<_, TaskRow>(&query)
+            .bind(task_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to get task: {}", e)))?;
+
+        row.map(Self::task_from_row).transpose()
+    }
+
+    /// Serializes the JSON columns of a task, compressing the large ones
+    fn task_columns(&self, task: &Task) -> Result<JsonColumns, A2AError> {
         let status_json = serde_json::to_string(&task.status)
             .map_err(|e| A2AError::internal(&format!("Failed to serialize status: {}", e)))?;
-        
-        let artifacts_json = task.artifacts.as_ref().map(|a| serde_json::to_string(a))
+
+        let artifacts_json = task.artifacts.as_ref().map(serde_json::to_string)
             .transpose()
-            .map_err(|e| A2AError::internal(&format!("Failed to serialize artifacts: {}", e)))?;
-            
-        let history_json = task.history.as_ref().map(|h| serde_json::to_string(h))
+            .map_err(|e| A2AError::internal(&format!("Failed to serialize artifacts: {}", e)))?
+            .map(|json| column_compression::encode(json, self.compression_level))
+            .transpose()?;
+
+        let history_json = task.history.as_ref().map(serde_json::to_string)
             .transpose()
-            .map_err(|e| A2AError::internal(&format!("Failed to serialize history: {}", e)))?;
-            
-        let metadata_json = task.metadata.as_ref().map(|m| serde_json::to_string(m))
+            .map_err(|e| A2AError::internal(&format!("Failed to serialize history: {}", e)))?
+            .map(|json| column_compression::encode(json, self.compression_level))
+            .transpose()?;
+
+        let metadata_json = task.metadata.as_ref().map(serde_json::to_string)
             .transpose()
             .map_err(|e| A2AError::internal(&format!("Failed to serialize metadata: {}", e)))?;
 
-        sqlx::query(&query)
-            .bind(&task.id)
-            .bind(&task.context_id)
-            .bind(task.kind)
-            .bind(status_json)
-            .bind(artifacts_json)
-            .bind(history_json)
-            .bind(metadata_json)
-            .execute(&self.pool)
-            .await
-            .map_err(|e| A2AError::internal(&format!("Failed to save task: {}", e)))?;
+        let transitions_json = task.state_transitions.as_ref().map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| A2AError::internal(&format!("Failed to serialize state transitions: {}", e)))?;
 
-        Ok(())
+        Ok((status_json, artifacts_json, history_json, metadata_json, transitions_json))
+    }
+
+    /// Converts a database row into a Task
+    fn task_from_row(row: TaskRow) -> Result<Task, A2AError> {
+        let (id, context_id, kind, status_json, artifacts_json, history_json, metadata_json, transitions_json) = row;
+
+        let status = serde_json::from_str(&status_json)
+            .map_err(|e| A2AError::internal(&format!("Failed to deserialize status: {}", e)))?;
+
+        let artifacts = serde_json::from_str(&column_compression::decode(artifacts_json)?)
+            .map_err(|e| A2AError::internal(&format!("Failed to deserialize artifacts: {}", e)))?;
+
+        let history = serde_json::from_str(&column_compression::decode(history_json)?)
+            .map_err(|e| A2AError::internal(&format!("Failed to deserialize history: {}", e)))?;
+
+        let metadata = serde_json::from_str(&metadata_json)
+            .map_err(|e| A2AError::internal(&format!("Failed to deserialize metadata: {}", e)))?;
+
+        let state_transitions = serde_json::from_str(&transitions_json)
+            .map_err(|e| A2AError::internal(&format!("Failed to deserialize state transitions: {}", e)))?;
+
+        Ok(Task {
+            id,
+            context_id,
+            kind,
+            status,
+            artifacts,
+            history,
+            state_transitions,
+            metadata,
+        })
+    }
+}
+
+#[async_trait]
+impl<D: SqlDialect> TaskStore for SqlTaskStore<D> {
+    async fn save(&self, task: Task) -> Result<(), A2AError> {
+        self.save_many(vec![task]).await
     }
 
     async fn get(&self, task_id: &str) -> Result<Option<Task>, A2AError> {
-        let query = format!(
-            "SELECT id, context_id, kind, status, artifacts, history, metadata FROM {} WHERE id = ?",
+        self.get_with_consistency(task_id, ReadConsistency::Strong).await
+    }
+
+    async fn get_with_consistency(&self, task_id: &str, consistency: ReadConsistency) -> Result<Option<Task>, A2AError> {
+        let query = Self::sql(&format!("{} AND id = ?", Self::select_tasks(&self.table_name, None)));
+
+        let row = sqlx::query_as::<_, TaskRow>(&query)
+            .bind(task_id)
+            .fetch_optional(self.reader(consistency))
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to get task: {}", e)))?;
+
+        row.map(Self::task_from_row).transpose()
+    }
+
+    /// Leaves the history column unread
+    async fn get_meta(&self, task_id: &str, consistency: ReadConsistency) -> Result<Option<Task>, A2AError> {
+        let query = Self::sql(&format!(
+            "{} AND id = ?",
+            Self::select_task_columns(&self.table_name, None, false)
+        ));
+
+        let row = sqlx::query_as::<_, TaskRow>(&query)
+            .bind(task_id)
+            .fetch_optional(self.reader(consistency))
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to get task: {}", e)))?;
+
+        row.map(Self::task_from_row).transpose()
+    }
+
+    /// Reads only the history column
+    async fn get_history(&self, task_id: &str, offset: usize, limit: usize) -> Result<Option<HistoryPage>, A2AError> {
+        let query = Self::sql(&format!(
+            "SELECT COALESCE(history, 'null') FROM {} WHERE id = ? AND deleted_at IS NULL",
             self.table_name
-        );
+        ));
 
-        let row = sqlx::query_as::<_, (String, String, String, String, Option<String>, Option<String>, Option<String>)>(&query)
+        let row = sqlx::query_as::<_, (String,)>(&query)
             .bind(task_id)
             .fetch_optional(&self.pool)
             .await
-            .map_err(|e| A2AError::internal(&format!("Failed to get task: {}", e)))?;
+            .map_err(|e| A2AError::internal(&format!("Failed to get task history: {}", e)))?;
 
-        if let Some((id, context_id, kind, status_json, artifacts_json, history_json, metadata_json)) = row {
-            let status = serde_json::from_str(&status_json)
-                .map_err(|e| A2AError::internal(&format!("Failed to deserialize status: {}", e)))?;
-                
-            let artifacts = artifacts_json.map(|s| serde_json::from_str(&s))
-                .transpose()
-                .map_err(|e| A2AError::internal(&format!("Failed to deserialize artifacts: {}", e)))?;
-                
-            let history = history_json.map(|s| serde_json::from_str(&s))
-                .transpose()
+        row.map(|(history_json,)| {
+            let history: Option<Vec<crate::Message>> = serde_json::from_str(&column_compression::decode(history_json)?)
                 .map_err(|e| A2AError::internal(&format!("Failed to deserialize history: {}", e)))?;
-                
-            let metadata = metadata_json.map(|s| serde_json::from_str(&s))
-                .transpose()
-                .map_err(|e| A2AError::internal(&format!("Failed to deserialize metadata: {}", e)))?;
-
-            Ok(Some(Task {
-                id,
-                context_id,
-                kind,
-                status,
-                artifacts,
-                history,
-                metadata,
-            }))
-        } else {
-            Ok(None)
-        }
+            Ok(HistoryPage::from_history(history.as_deref().unwrap_or_default(), offset, limit))
+        })
+        .transpose()
     }
 
+    /// Sets the deletion time of the task, keeping its labels and index rows
     async fn delete(&self, task_id: &str) -> Result<(), A2AError> {
-        let query = format!("DELETE FROM {} WHERE id = ?", self.table_name);
+        self.delete_many(&[task_id.to_string()]).await
+    }
 
-        sqlx::query(&query)
+    async fn restore(&self, task_id: &str) -> Result<(), A2AError> {
+        let query = Self::sql(&format!(
+            "UPDATE {} SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL",
+            self.table_name
+        ));
+
+        let restored = sqlx::query(&query)
             .bind(task_id)
             .execute(&self.pool)
             .await
-            .map_err(|e| A2AError::internal(&format!("Failed to delete task: {}", e)))?;
+            .map_err(|e| A2AError::internal(&format!("Failed to restore task: {}", e)))?;
 
+        if restored.rows_affected() == 0 {
+            return Err(A2AError::task_not_found(task_id));
+        }
         Ok(())
     }
 
-    async fn list(&self) -> Result<Vec<Task>, A2AError> {
+    /// Removes the task with its labels and index rows in one transaction
+    async fn purge(&self, task_id: &str) -> Result<(), A2AError> {
+        let mut tx = self.pool.begin()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to begin transaction: {}", e)))?;
+
+        let query = Self::sql(&format!("DELETE FROM {} WHERE id = ?", self.table_name));
+        sqlx::query(&query)
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to purge task: {}", e)))?;
+
+        let labels_query = Self::sql(&format!("DELETE FROM {} WHERE task_id = ?", self.labels_table_name()));
+        sqlx::query(&labels_query)
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to delete task labels: {}", e)))?;
+
+        if D::search_index(&self.search_table_name()).is_some() {
+            let search_query = Self::sql(&format!("DELETE FROM {} WHERE task_id = ?", self.search_table_name()));
+            sqlx::query(&search_query)
+                .bind(task_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| A2AError::internal(&format!("Failed to delete task from the search index: {}", e)))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Saves all tasks in one transaction, with one statement per chunk
+    async fn save_many(&self, tasks: Vec<Task>) -> Result<(), A2AError> {
+        let mut tx = self.pool.begin()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to begin transaction: {}", e)))?;
+
+        self.write_tasks(&mut tx, tasks).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Starts a database transaction on the primary
+    async fn begin(&self) -> Result<Box<dyn TaskStoreTransaction>, A2AError> {
+        let tx = self.pool.begin()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to begin transaction: {}", e)))?;
+        Ok(Box::new(SqlTransaction { store: self.clone(), tx }))
+    }
+
+    async fn get_many(&self, task_ids: &[String]) -> Result<Vec<Task>, A2AError> {
+        let mut found = std::collections::HashMap::new();
+        for chunk in task_ids.chunks(BULK_CHUNK) {
+            let query = Self::sql(&format!(
+                "{} AND id IN ({})",
+                Self::select_tasks(&self.table_name, None),
+                vec!["?"; chunk.len()].join(", ")
+            ));
+            let mut db_query = sqlx::query_as::<_, TaskRow>(&query);
+            for task_id in chunk {
+                db_query = db_query.bind(task_id.as_str());
+            }
+            let rows = db_query
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| A2AError::internal(&format!("Failed to get tasks: {}", e)))?;
+            for row in rows {
+                let task = Self::task_from_row(row)?;
+                found.insert(task.id.clone(), task);
+            }
+        }
+        Ok(task_ids.iter().filter_map(|id| found.get(id).cloned()).collect())
+    }
+
+    /// Sets the deletion time of the tasks in one transaction
+    ///
+    /// Tasks deleted before keep their first deletion time.
+    async fn delete_many(&self, task_ids: &[String]) -> Result<(), A2AError> {
+        let mut tx = self.pool.begin()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to begin transaction: {}", e)))?;
+
+        let deleted_at = Utc::now().timestamp_millis();
+        for chunk in task_ids.chunks(BULK_CHUNK) {
+            let query = Self::sql(&format!(
+                "UPDATE {} SET deleted_at = ? WHERE deleted_at IS NULL AND id IN ({})",
+                self.table_name,
+                vec!["?"; chunk.len()].join(", ")
+            ));
+            let mut db_query = sqlx::query(&query).bind(deleted_at);
+            for task_id in chunk {
+                db_query = db_query.bind(task_id.as_str());
+            }
+            db_query
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| A2AError::internal(&format!("Failed to delete tasks: {}", e)))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Removes the tasks with their labels and index rows in one transaction
+    async fn purge_deleted(&self, older_than: DateTime<Utc>) -> Result<u64, A2AError> {
+        let mut tx = self.pool.begin()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to begin transaction: {}", e)))?;
+
+        let expired = format!("SELECT id FROM {} WHERE deleted_at < ?", self.table_name);
+        let mut queries = vec![format!("DELETE FROM {} WHERE task_id IN ({})", self.labels_table_name(), expired)];
+        if D::search_index(&self.search_table_name()).is_some() {
+            queries.push(format!("DELETE FROM {} WHERE task_id IN ({})", self.search_table_name(), expired));
+        }
+        for query in queries {
+            sqlx::query(&Self::sql(&query))
+                .bind(older_than.timestamp_millis())
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| A2AError::internal(&format!("Failed to purge tasks: {}", e)))?;
+        }
+        let purged = sqlx::query(&Self::sql(&format!("DELETE FROM {} WHERE deleted_at < ?", self.table_name)))
+            .bind(older_than.timestamp_millis())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to purge tasks: {}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(purged.rows_affected())
+    }
+
+    /// Rewrites the task rows in place, keeping their deletion time, and
+    /// removes their labels and index rows and the task copies of their
+    /// outbox entries, in one transaction
+    async fn erase(&self, task_ids: &[String]) -> Result<usize, A2AError> {
+        let mut tx = self.pool.begin()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to begin transaction: {}", e)))?;
+
+        let erased_at = Utc::now();
+        let mut erased = 0;
+        for chunk in task_ids.chunks(BULK_CHUNK) {
+            let placeholders = vec!["?"; chunk.len()].join(", ");
+            let query = Self::sql(&format!(
+                "SELECT {} FROM {} WHERE id IN ({})",
+                Self::task_column_list("", true),
+                self.table_name,
+                placeholders
+            ));
+            let mut db_query = sqlx::query_as::<_, TaskRow>(&query);
+            for task_id in chunk {
+                db_query = db_query.bind(task_id.as_str());
+            }
+            let rows = db_query
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| A2AError::internal(&format!("Failed to get tasks: {}", e)))?;
+
+            for row in rows {
+                let stub = erased_task(&Self::task_from_row(row)?, erased_at);
+                let (status_json, artifacts_json, history_json, metadata_json, transitions_json) = self.task_columns(&stub)?;
+                let query = Self::sql(&format!(
+                    "UPDATE {} SET status = ?, artifacts = ?, history = ?, metadata = ?, state_transitions = ? WHERE id = ?",
+                    self.table_name
+                ));
+                sqlx::query(&query)
+                    .bind(status_json)
+                    .bind(artifacts_json)
+                    .bind(history_json)
+                    .bind(metadata_json)
+                    .bind(transitions_json)
+                    .bind(stub.id.as_str())
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| A2AError::internal(&format!("Failed to erase task: {}", e)))?;
+
+                if self.outbox {
+                    let payload = serde_json::to_string(&stub)
+                        .map_err(|e| A2AError::internal(&format!("Failed to serialize task: {}", e)))?;
+                    let query = Self::sql(&format!("UPDATE {} SET payload = ? WHERE task_id = ?", self.outbox_table_name()));
+                    sqlx::query(&query)
+                        .bind(payload)
+                        .bind(stub.id.as_str())
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(|e| A2AError::internal(&format!("Failed to erase outbox entries: {}", e)))?;
+                }
+                erased += 1;
+            }
+
+            let mut queries = vec![format!("DELETE FROM {} WHERE task_id IN ({})", self.labels_table_name(), placeholders)];
+            if D::search_index(&self.search_table_name()).is_some() {
+                queries.push(format!("DELETE FROM {} WHERE task_id IN ({})", self.search_table_name(), placeholders));
+            }
+            for query in queries {
+                let query = Self::sql(&query);
+                let mut db_query = sqlx::query(&query);
+                for task_id in chunk {
+                    db_query = db_query.bind(task_id.as_str());
+                }
+                db_query
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| A2AError::internal(&format!("Failed to erase tasks: {}", e)))?;
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(erased)
+    }
+
+    async fn list_deleted(&self) -> Result<Vec<DeletedTask>, A2AError> {
         let query = format!(
-            "SELECT id, context_id, kind, status, artifacts, history, metadata FROM {}",
+            "SELECT {}, deleted_at FROM {} WHERE deleted_at IS NOT NULL ORDER BY deleted_at",
+            Self::task_column_list("", true),
             self.table_name
         );
 
-        let rows = sqlx::query_as::<_, (String, String, String, String, Option<String>, Option<String>, Option<String>)>(&query)
+        let rows = sqlx::query_as::<_, DeletedTaskRow>(&query)
             .fetch_all(&self.pool)
             .await
-            .map_err(|e| A2AError::internal(&format!("Failed to list tasks: {}", e)))?;
+            .map_err(|e| A2AError::internal(&format!("Failed to list deleted tasks: {}", e)))?;
 
-        let mut tasks = Vec::new();
-        for (id, context_id, kind, status_json, artifacts_json, history_json, metadata_json) in rows {
-            let status = serde_json::from_str(&status_json)
-                .map_err(|e| A2AError::internal(&format!("Failed to deserialize status: {}", e)))?;
-                
-            let artifacts = artifacts_json.map(|s| serde_json::from_str(&s))
-                .transpose()
-                .map_err(|e| A2AError::internal(&format!("Failed to deserialize artifacts: {}", e)))?;
-                
-            let history = history_json.map(|s| serde_json::from_str(&s))
-                .transpose()
-                .map_err(|e| A2AError::internal(&format!("Failed to deserialize history: {}", e)))?;
-                
-            let metadata = metadata_json.map(|s| serde_json::from_str(&s))
-                .transpose()
-                .map_err(|e| A2AError::internal(&format!("Failed to deserialize metadata: {}", e)))?;
+        rows.into_iter()
+            .map(|(id, context_id, kind, status, artifacts, history, metadata, transitions, deleted_at)| {
+                Ok(DeletedTask {
+                    task: Self::task_from_row((id, context_id, kind, status, artifacts, history, metadata, transitions))?,
+                    deleted_at: DateTime::from_timestamp_millis(deleted_at).unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
 
-            tasks.push(Task {
-                id,
-                context_id,
-                kind,
-                status,
-                artifacts,
-                history,
-                metadata,
-            });
-        }
-        Ok(tasks)
+    async fn list(&self) -> Result<Vec<Task>, A2AError> {
+        self.list_with_consistency(ReadConsistency::Strong).await
+    }
+
+    async fn list_with_consistency(&self, consistency: ReadConsistency) -> Result<Vec<Task>, A2AError> {
+        let query = Self::select_tasks(&self.table_name, None);
+
+        let rows = sqlx::query_as::<_, TaskRow>(&query)
+            .fetch_all(self.reader(consistency))
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to list tasks: {}", e)))?;
+
+        rows.into_iter().map(Self::task_from_row).collect()
     }
 
     async fn list_by_context(&self, context_id: &str) -> Result<Vec<Task>, A2AError> {
-        let query = format!(
-            "SELECT id, context_id, kind, status, artifacts, history, metadata FROM {} WHERE context_id = ?",
-            self.table_name
-        );
+        let query = Self::sql(&format!("{} AND context_id = ?", Self::select_tasks(&self.table_name, None)));
 
-        let rows = sqlx::query_as::<_, (String, String, String, String, Option<String>, Option<String>, Option<String>)>(&query)
+        let rows = sqlx::query_as::<_, TaskRow>(&query)
             .bind(context_id)
             .fetch_all(&self.pool)
             .await
             .map_err(|e| A2AError::internal(&format!("Failed to list tasks by context: {}", e)))?;
 
-        let mut tasks = Vec::new();
-        for (id, context_id, kind, status_json, artifacts_json, history_json, metadata_json) in rows {
-            let status = serde_json::from_str(&status_json)
-                .map_err(|e| A2AError::internal(&format!("Failed to deserialize status: {}", e)))?;
-                
-            let artifacts = artifacts_json.map(|s| serde_json::from_str(&s))
-                .transpose()
-                .map_err(|e| A2AError::internal(&format!("Failed to deserialize artifacts: {}", e)))?;
-                
-            let history = history_json.map(|s| serde_json::from_str(&s))
-                .transpose()
-                .map_err(|e| A2AError::internal(&format!("Failed to deserialize history: {}", e)))?;
-                
-            let metadata = metadata_json.map(|s| serde_json::from_str(&s))
-                .transpose()
-                .map_err(|e| A2AError::internal(&format!("Failed to deserialize metadata: {}", e)))?;
-
-            tasks.push(Task {
-                id,
-                context_id,
-                kind,
-                status,
-                artifacts,
-                history,
-                metadata,
-            });
-        }
-        Ok(tasks)
+        rows.into_iter().map(Self::task_from_row).collect()
+    }
+
+    async fn set_labels(&self, task_id: &str, labels: Labels) -> Result<(), A2AError> {
+        validate_labels(&labels)?;
+        if self.get(task_id).await?.is_none() {
+            return Err(A2AError::task_not_found(task_id));
+        }
+
+        let labels_table = self.labels_table_name();
+        let mut tx = self.pool.begin()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to begin transaction: {}", e)))?;
+
+        sqlx::query(&Self::sql(&format!("DELETE FROM {} WHERE task_id = ?", labels_table)))
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to set task labels: {}", e)))?;
+
+        let insert_query = Self::sql(&format!("INSERT INTO {} (task_id, key, value) VALUES (?, ?, ?)", labels_table));
+        for (key, value) in &labels {
+            sqlx::query(&insert_query)
+                .bind(task_id)
+                .bind(key)
+                .bind(value)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| A2AError::internal(&format!("Failed to set task labels: {}", e)))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_labels(&self, task_id: &str) -> Result<Labels, A2AError> {
+        let query = Self::sql(&format!(
+            "SELECT key, value FROM {} WHERE task_id = ? AND task_id IN (SELECT id FROM {} WHERE deleted_at IS NULL)",
+            self.labels_table_name(),
+            self.table_name
+        ));
+
+        let rows = sqlx::query_as::<_, (String, String)>(&query)
+            .bind(task_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to get task labels: {}", e)))?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    async fn list_by_labels(&self, selector: &LabelSelector) -> Result<Vec<Task>, A2AError> {
+        let labels_table = self.labels_table_name();
+        let mut conditions = Vec::new();
+        let mut params: Vec<&str> = Vec::new();
+
+        for requirement in &selector.requirements {
+            let exists = |filter: &str| {
+                format!(
+                    "EXISTS (SELECT 1 FROM {} l WHERE l.task_id = t.id AND l.key = ?{})",
+                    labels_table, filter
+                )
+            };
+            let placeholders = |n: usize| vec!["?"; n].join(", ");
+            params.push(requirement.key());
+            let condition = match requirement {
+                LabelRequirement::Equals(_, value) => {
+                    params.push(value);
+                    exists(" AND l.value = ?")
+                }
+                LabelRequirement::NotEquals(_, value) => {
+                    params.push(value);
+                    format!("NOT {}", exists(" AND l.value = ?"))
+                }
+                LabelRequirement::In(_, values) => {
+                    params.extend(values.iter().map(String::as_str));
+                    exists(&format!(" AND l.value IN ({})", placeholders(values.len())))
+                }
+                LabelRequirement::NotIn(_, values) => {
+                    params.extend(values.iter().map(String::as_str));
+                    format!("NOT {}", exists(&format!(" AND l.value IN ({})", placeholders(values.len()))))
+                }
+                LabelRequirement::Exists(_) => exists(""),
+                LabelRequirement::NotExists(_) => format!("NOT {}", exists("")),
+            };
+            conditions.push(condition);
+        }
+
+        let mut query = Self::select_tasks(&self.table_name, Some("t"));
+        if !conditions.is_empty() {
+            query.push_str(" AND ");
+            query.push_str(&conditions.join(" AND "));
+        }
+        let query = Self::sql(&query);
+
+        let mut db_query = sqlx::query_as::<_, TaskRow>(&query);
+        for param in params {
+            db_query = db_query.bind(param);
+        }
+
+        let rows = db_query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to list tasks by labels: {}", e)))?;
+
+        rows.into_iter().map(Self::task_from_row).collect()
+    }
+
+    /// Queries the full-text index, which `with_search_index` enables
+    async fn search_tasks(&self, query: &str, filter: &TaskSearchFilter) -> Result<Vec<Task>, A2AError> {
+        let index = match D::search_index(&self.search_table_name()) {
+            Some(index) if self.search_index => index,
+            _ => return Err(A2AError::unsupported_operation("Task search index not enabled")),
+        };
+        let terms = search_terms(query);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let states = filter
+            .states
+            .iter()
+            .flatten()
+            .map(|state| match serde_json::to_value(state) {
+                Ok(serde_json::Value::String(state)) => Ok(state),
+                _ => Err(A2AError::internal("Failed to encode task state")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if filter.states.is_some() && states.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut sql = format!("{} AND id IN ({})", Self::select_tasks(&self.table_name, None), index.matching);
+        if filter.context_id.is_some() {
+            sql.push_str(" AND context_id = ?");
+        }
+        if !states.is_empty() {
+            sql.push_str(&format!(" AND {} IN ({})", D::json_text("status", "state"), vec!["?"; states.len()].join(", ")));
+        }
+        if filter.tenant.is_some() {
+            sql.push_str(&format!(" AND {} = ?", D::json_text("metadata", TENANT_METADATA_KEY)));
+        }
+        sql.push_str(&format!(" ORDER BY {} DESC, id LIMIT ?", D::json_text("status", "timestamp")));
+        let sql = Self::sql(&sql);
+        let mut db_query = sqlx::query_as::<_, TaskRow>(&sql).bind(terms.join(" "));
+        if let Some(context_id) = &filter.context_id {
+            db_query = db_query.bind(context_id.as_str());
+        }
+        for state in &states {
+            db_query = db_query.bind(state.as_str());
+        }
+        if let Some(tenant) = &filter.tenant {
+            db_query = db_query.bind(tenant.as_str());
+        }
+        db_query = db_query.bind(filter.effective_limit() as i64);
+
+        let rows = db_query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to search tasks: {}", e)))?;
+
+        Ok(filter.apply(rows.into_iter().map(Self::task_from_row).collect::<Result<Vec<_>, _>>()?))
+    }
+
+    /// Increments the totals in place, so concurrent reports are not lost
+    async fn record_usage(&self, task_id: &str, principal: &str, usage: Usage) -> Result<UsageRecord, A2AError> {
+        let usage_table = self.usage_table_name();
+        let map_err = |e: sqlx::Error| A2AError::internal(&format!("Failed to record task usage: {}", e));
+        let mut tx = self.pool.begin()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to begin transaction: {}", e)))?;
+
+        let update = Self::sql(&format!(
+            "UPDATE {} SET input_tokens = input_tokens + ?, output_tokens = output_tokens + ?, \
+             compute_units = compute_units + ? WHERE task_id = ?",
+            usage_table
+        ));
+        let updated = sqlx::query(&update)
+            .bind(usage.input_tokens as i64)
+            .bind(usage.output_tokens as i64)
+            .bind(usage.compute_units)
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_err)?;
+
+        if updated.rows_affected() == 0 {
+            let context_query = Self::sql(&format!("SELECT context_id FROM {} WHERE id = ?", self.table_name));
+            let context_id: Option<(String,)> = sqlx::query_as(&context_query)
+                .bind(task_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(map_err)?;
+            let Some((context_id,)) = context_id else {
+                return Err(A2AError::task_not_found(task_id));
+            };
+            let insert = Self::sql(&format!("INSERT INTO {} ({}) VALUES (?, ?, ?, ?, ?, ?)", usage_table, USAGE_COLUMNS));
+            sqlx::query(&insert)
+                .bind(task_id)
+                .bind(context_id)
+                .bind(principal)
+                .bind(usage.input_tokens as i64)
+                .bind(usage.output_tokens as i64)
+                .bind(usage.compute_units)
+                .execute(&mut *tx)
+                .await
+                .map_err(map_err)?;
+        }
+
+        let select = Self::sql(&format!("SELECT {} FROM {} WHERE task_id = ?", USAGE_COLUMNS, usage_table));
+        let row = sqlx::query_as::<_, UsageRow>(&select)
+            .bind(task_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(map_err)?;
+
+        tx.commit()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(Self::usage_from_row(row))
+    }
+
+    async fn get_usage(&self, task_id: &str) -> Result<Option<UsageRecord>, A2AError> {
+        let query = Self::sql(&format!("SELECT {} FROM {} WHERE task_id = ?", USAGE_COLUMNS, self.usage_table_name()));
+
+        let row = sqlx::query_as::<_, UsageRow>(&query)
+            .bind(task_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to get task usage: {}", e)))?;
+
+        Ok(row.map(Self::usage_from_row))
+    }
+
+    async fn list_usage(&self) -> Result<Vec<UsageRecord>, A2AError> {
+        let query = format!("SELECT {} FROM {} ORDER BY task_id", USAGE_COLUMNS, self.usage_table_name());
+
+        let rows = sqlx::query_as::<_, UsageRow>(&query)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to list task usage: {}", e)))?;
+
+        Ok(rows.into_iter().map(Self::usage_from_row).collect())
+    }
+
+    async fn context_usage(&self, context_id: &str) -> Result<Usage, A2AError> {
+        self.usage_total("context_id", context_id).await
+    }
+
+    async fn principal_usage(&self, principal: &str) -> Result<Usage, A2AError> {
+        self.usage_total("principal", principal).await
+    }
+}
+
+/// Database transaction of a `SqlTaskStore`, rolled back when dropped uncommitted
+struct SqlTransaction<D: SqlDialect> {
+    store: SqlTaskStore<D>,
+    tx: sqlx::Transaction<'static, sqlx::Any>,
+}
+
+#[async_trait]
+impl<D: SqlDialect> TaskStoreTransaction for SqlTransaction<D> {
+    async fn get(&mut self, task_id: &str) -> Result<Option<Task>, A2AError> {
+        self.store.read_task(&mut self.tx, task_id).await
+    }
+
+    async fn save(&mut self, task: Task) -> Result<(), A2AError> {
+        self.store.write_tasks(&mut self.tx, vec![task]).await
+    }
+
+    async fn commit(self: Box<Self>) -> Result<(), A2AError> {
+        self.tx.commit()
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to commit transaction: {}", e)))
+    }
+}
+
+#[async_trait]
+impl<D: SqlDialect> OutboxStore for SqlTaskStore<D> {
+    async fn fetch_due(&self, limit: usize) -> Result<Vec<OutboxEntry>, A2AError> {
+        let query = Self::sql(&format!(
+            "SELECT id, task_id, payload, attempts, created_at FROM {}
+             WHERE status = ? AND next_attempt_at <= ? ORDER BY id LIMIT ?",
+            self.outbox_table_name()
+        ));
+
+        let rows = sqlx::query_as::<_, (i64, String, String, i64, i64)>(&query)
+            .bind(OUTBOX_PENDING)
+            .bind(Utc::now().timestamp_millis())
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to fetch outbox entries: {}", e)))?;
+
+        rows.into_iter()
+            .map(|(id, task_id, payload, attempts, created_at)| {
+                let task = serde_json::from_str(&payload)
+                    .map_err(|e| A2AError::internal(&format!("Failed to deserialize outbox entry: {}", e)))?;
+                Ok(OutboxEntry {
+                    id,
+                    task_id,
+                    task,
+                    attempts: attempts as u32,
+                    created_at: DateTime::from_timestamp_millis(created_at).unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+
+    async fn mark_delivered(&self, id: i64) -> Result<(), A2AError> {
+        let query = Self::sql(&format!(
+            "UPDATE {} SET status = ?, attempts = attempts + 1, last_error = NULL WHERE id = ?",
+            self.outbox_table_name()
+        ));
+
+        sqlx::query(&query)
+            .bind(OUTBOX_DELIVERED)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to update outbox entry: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: i64, error: &str, retry_at: Option<DateTime<Utc>>) -> Result<(), A2AError> {
+        let query = Self::sql(&format!(
+            "UPDATE {} SET status = ?, attempts = attempts + 1, last_error = ?,
+             next_attempt_at = COALESCE(?, next_attempt_at) WHERE id = ?",
+            self.outbox_table_name()
+        ));
+
+        sqlx::query(&query)
+            .bind(if retry_at.is_some() { OUTBOX_PENDING } else { OUTBOX_DEAD })
+            .bind(error)
+            .bind(retry_at.map(|t| t.timestamp_millis()))
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to update outbox entry: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn purge_delivered(&self, older_than: DateTime<Utc>) -> Result<u64, A2AError> {
+        let query = Self::sql(&format!(
+            "DELETE FROM {} WHERE status = ? AND created_at < ?",
+            self.outbox_table_name()
+        ));
+
+        let result = sqlx::query(&query)
+            .bind(OUTBOX_DELIVERED)
+            .bind(older_than.timestamp_millis())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| A2AError::internal(&format!("Failed to purge outbox: {}", e)))?;
+
+        Ok(result.rows_affected())
     }
 }
 
@@ -270,6 +1493,7 @@ mod tests {
             },
             artifacts: None,
             history: None,
+            state_transitions: None,
             metadata: None,
             kind: "task".to_string(),
         };
@@ -300,4 +1524,404 @@ mod tests {
         let deleted = store.get(&task_id.to_string()).await.unwrap();
         assert!(deleted.is_none());
     }
+
+    #[tokio::test]
+    async fn test_sqlite_task_store_from_sqlite_pool() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let store = SqliteTaskStore::with_table_name(pool.clone(), "agent_tasks".to_string());
+        store.initialize().await.unwrap();
+
+        let task = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working));
+        store.save(task.clone()).await.unwrap();
+        assert_eq!(store.get(&task.id).await.unwrap().unwrap().id, task.id);
+
+        // The store writes to the in-memory database of the pool it was given
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM agent_tasks")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_state_transitions_column_is_added_to_existing_tables() {
+        sqlx::any::install_default_drivers();
+        let pool = AnyPool::connect(&SqliteDialect::connect_url("sqlite::memory:")).await.unwrap();
+        sqlx::query(
+            "CREATE TABLE tasks (id TEXT PRIMARY KEY, context_id TEXT NOT NULL, kind TEXT NOT NULL,
+             status TEXT NOT NULL, artifacts TEXT, history TEXT, metadata TEXT)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let store = SqliteTaskStore::from_any_pool(pool);
+        store.initialize().await.unwrap();
+        // Initializing again finds the column in place
+        store.initialize().await.unwrap();
+
+        let mut task = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working));
+        task.state_transitions = Some(vec![crate::a2a::models::TaskStateTransition {
+            state: TaskState::Working,
+            timestamp: task.status.timestamp.clone().unwrap(),
+            message: None,
+        }]);
+        store.save(task.clone()).await.unwrap();
+        assert_eq!(store.get(&task.id).await.unwrap().unwrap(), task);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_task_store_usage() {
+        let store = SqliteTaskStore::connect("sqlite::memory:").await.unwrap();
+        store
+            .save(Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id("task-1".to_string()))
+            .await
+            .unwrap();
+
+        store.record_usage("task-1", "alice", Usage::tokens(100, 20)).await.unwrap();
+        let record = store
+            .record_usage("task-1", "bob", Usage::tokens(5, 5).with_compute_units(1.5))
+            .await
+            .unwrap();
+        assert_eq!(record.principal, "alice");
+        assert_eq!(record.context_id, "ctx");
+        assert_eq!(record.usage, Usage::tokens(105, 25).with_compute_units(1.5));
+        assert!(store.record_usage("missing", "alice", Usage::tokens(1, 1)).await.is_err());
+
+        assert_eq!(store.get_usage("task-1").await.unwrap(), Some(record.clone()));
+        assert_eq!(store.get_usage("missing").await.unwrap(), None);
+        assert_eq!(store.list_usage().await.unwrap(), vec![record]);
+
+        assert_eq!(store.context_usage("ctx").await.unwrap(), Usage::tokens(105, 25).with_compute_units(1.5));
+        assert_eq!(store.principal_usage("alice").await.unwrap(), Usage::tokens(105, 25).with_compute_units(1.5));
+        assert_eq!(store.principal_usage("bob").await.unwrap(), Usage::new());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_task_store_labels() {
+        let store = SqliteTaskStore::connect("sqlite::memory:").await.unwrap();
+
+        let make_task = |id: &str| Task {
+            id: id.to_string(),
+            context_id: "ctx".to_string(),
+            status: TaskStatus::new(TaskState::Working),
+            artifacts: None,
+            history: None,
+            state_transitions: None,
+            metadata: None,
+            kind: "task".to_string(),
+        };
+        for id in ["task-1", "task-2", "task-3"] {
+            store.save(make_task(id)).await.unwrap();
+        }
+
+        let labels = |pairs: &[(&str, &str)]| -> Labels {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+        store.set_labels("task-1", labels(&[("customer", "acme"), ("env", "prod")])).await.unwrap();
+        store.set_labels("task-2", labels(&[("customer", "globex"), ("env", "prod")])).await.unwrap();
+        assert!(store.set_labels("missing", Labels::new()).await.is_err());
+
+        assert_eq!(store.get_labels("task-1").await.unwrap(), labels(&[("customer", "acme"), ("env", "prod")]));
+
+        let ids = |tasks: Vec<Task>| {
+            let mut ids: Vec<String> = tasks.into_iter().map(|t| t.id).collect();
+            ids.sort();
+            ids
+        };
+        let select = |s: &str| LabelSelector::parse(s).unwrap();
+
+        assert_eq!(ids(store.list_by_labels(&select("env=prod")).await.unwrap()), vec!["task-1", "task-2"]);
+        assert_eq!(ids(store.list_by_labels(&select("env=prod,customer!=acme")).await.unwrap()), vec!["task-2"]);
+        assert_eq!(ids(store.list_by_labels(&select("customer in (acme,initech)")).await.unwrap()), vec!["task-1"]);
+        assert_eq!(ids(store.list_by_labels(&select("customer notin (acme)")).await.unwrap()), vec!["task-2", "task-3"]);
+        assert_eq!(ids(store.list_by_labels(&select("!env")).await.unwrap()), vec!["task-3"]);
+        assert_eq!(store.list_by_labels(&LabelSelector::new()).await.unwrap().len(), 3);
+
+        // Replacing labels drops keys that are no longer present
+        store.set_labels("task-1", labels(&[("env", "staging")])).await.unwrap();
+        assert_eq!(store.get_labels("task-1").await.unwrap(), labels(&[("env", "staging")]));
+
+        store.delete("task-2").await.unwrap();
+        assert!(store.get_labels("task-2").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_task_store_bulk_operations() {
+        let store = SqliteTaskStore::connect("sqlite::memory:").await.unwrap();
+
+        // More tasks than fit in one statement
+        let tasks: Vec<Task> = (0..250)
+            .map(|i| Task::new("ctx".to_string(), TaskStatus::new(TaskState::Submitted)).with_task_id(format!("task-{}", i)))
+            .collect();
+        store.save_many(tasks).await.unwrap();
+        assert_eq!(store.list().await.unwrap().len(), 250);
+
+        // The last save of a task wins
+        let updates = vec![
+            Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id("task-1".to_string()),
+            Task::new("ctx".to_string(), TaskStatus::new(TaskState::Completed)).with_task_id("task-1".to_string()),
+        ];
+        store.save_many(updates).await.unwrap();
+        assert_eq!(store.get("task-1").await.unwrap().unwrap().status.state, TaskState::Completed);
+
+        let ids: Vec<String> = ["task-200", "missing", "task-1", "task-7"].iter().map(|s| s.to_string()).collect();
+        let found: Vec<String> = store.get_many(&ids).await.unwrap().into_iter().map(|t| t.id).collect();
+        assert_eq!(found, vec!["task-200", "task-1", "task-7"]);
+
+        store.set_labels("task-7", [("env".to_string(), "prod".to_string())].into_iter().collect()).await.unwrap();
+        let doomed: Vec<String> = (0..150).map(|i| format!("task-{}", i)).collect();
+        store.delete_many(&doomed).await.unwrap();
+        assert_eq!(store.list().await.unwrap().len(), 100);
+        assert!(store.get("task-7").await.unwrap().is_none());
+        assert!(store.get_labels("task-7").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_task_store_search() {
+        use crate::{Message, Part, Role};
+
+        let plain = SqliteTaskStore::connect("sqlite::memory:").await.unwrap();
+        let task = |id: &str, context_id: &str, state, text: &str| {
+            Task::new(context_id.to_string(), TaskStatus::new(state))
+                .with_task_id(id.to_string())
+                .with_history(vec![Message::new(Role::User, vec![Part::text(text.to_string())])])
+        };
+        plain.save(task("task-1", "ctx-1", TaskState::Completed, "Where is my order 1234?")).await.unwrap();
+        assert!(plain.search_tasks("order", &TaskSearchFilter::default()).await.is_err());
+
+        // Tasks saved before the index was enabled are found once rebuilt
+        let store = SqliteTaskStore::from_any_pool(plain.pool.clone()).with_search_index();
+        store.save(task("task-2", "ctx-2", TaskState::Failed, "Refund ORDER 1234, please")).await.unwrap();
+        store.save(task("task-3", "ctx-2", TaskState::Working, "Cancel order 99")).await.unwrap();
+        let ids = |tasks: Vec<Task>| {
+            let mut ids: Vec<String> = tasks.into_iter().map(|t| t.id).collect();
+            ids.sort();
+            ids
+        };
+        let all = TaskSearchFilter::default();
+        assert_eq!(ids(store.search_tasks("order 1234", &all).await.unwrap()), vec!["task-2"]);
+        store.rebuild_search_index().await.unwrap();
+        assert_eq!(ids(store.search_tasks("order 1234", &all).await.unwrap()), vec!["task-1", "task-2"]);
+
+        // Filters, and words that would be query syntax
+        let ctx_2 = TaskSearchFilter::default().with_context_id("ctx-2");
+        assert_eq!(ids(store.search_tasks("order", &ctx_2).await.unwrap()), vec!["task-2", "task-3"]);
+        let working = ctx_2.clone().with_states(vec![TaskState::Working]);
+        assert_eq!(ids(store.search_tasks("order", &working).await.unwrap()), vec!["task-3"]);
+        assert_eq!(store.search_tasks("order", &all.clone().with_limit(1)).await.unwrap().len(), 1);
+        assert!(store.search_tasks("\"order\" OR NOT *", &all).await.unwrap().is_empty());
+        assert!(store.search_tasks("  ", &all).await.unwrap().is_empty());
+        assert!(store.search_tasks("order", &all.clone().with_states(Vec::new())).await.unwrap().is_empty());
+
+        // Tenants only find their own tasks
+        let mut owned = task("task-4", "ctx-4", TaskState::Working, "Order for alice");
+        owned.metadata = Some(std::collections::HashMap::from([(TENANT_METADATA_KEY.to_string(), serde_json::json!("alice"))]));
+        store.save(owned).await.unwrap();
+        let alice = all.clone().with_tenant("alice");
+        assert_eq!(ids(store.search_tasks("order", &alice).await.unwrap()), vec!["task-4"]);
+        assert!(store.search_tasks("order", &all.clone().with_tenant("bob")).await.unwrap().is_empty());
+        store.delete("task-4").await.unwrap();
+
+        // Saves replace the indexed text and deletes hide it
+        store.save(task("task-2", "ctx-2", TaskState::Failed, "Never mind")).await.unwrap();
+        assert_eq!(ids(store.search_tasks("1234", &all).await.unwrap()), vec!["task-1"]);
+        store.delete("task-1").await.unwrap();
+        store.delete_many(&["task-3".to_string()]).await.unwrap();
+        assert!(store.search_tasks("order", &all).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_task_store_soft_delete() {
+        let store = SqliteTaskStore::connect("sqlite::memory:").await.unwrap().with_search_index();
+        let task = |id: &str| {
+            Task::new("ctx".to_string(), TaskStatus::new(TaskState::Completed))
+                .with_task_id(id.to_string())
+                .with_history(vec![crate::Message::new(crate::Role::User, vec![crate::Part::text("refund".to_string())])])
+        };
+        store.save(task("task-1")).await.unwrap();
+        store.save(task("task-2")).await.unwrap();
+        store.set_labels("task-1", Labels::from([("team".to_string(), "billing".to_string())])).await.unwrap();
+
+        store.delete("task-1").await.unwrap();
+        assert!(store.get("task-1").await.unwrap().is_none());
+        assert!(store.get_history("task-1", 0, 10).await.unwrap().is_none());
+        assert_eq!(store.list().await.unwrap().len(), 1);
+        assert!(store.get_labels("task-1").await.unwrap().is_empty());
+        assert_eq!(store.search_tasks("refund", &TaskSearchFilter::default()).await.unwrap().len(), 1);
+        let deleted = store.list_deleted().await.unwrap();
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].task.id, "task-1");
+        assert_eq!(deleted[0].task.history.as_ref().map(Vec::len), Some(1));
+
+        store.restore("task-1").await.unwrap();
+        assert!(store.get("task-1").await.unwrap().is_some());
+        assert_eq!(store.get_labels("task-1").await.unwrap().len(), 1);
+        assert_eq!(store.search_tasks("refund", &TaskSearchFilter::default()).await.unwrap().len(), 2);
+        assert!(store.restore("task-1").await.is_err());
+
+        // Saving a deleted task fails and leaves it deleted
+        store.delete("task-2").await.unwrap();
+        let err = store.save(task("task-2")).await.unwrap_err();
+        assert!(matches!(err, A2AError::TaskNotFound(_)));
+        let mut tx = store.begin().await.unwrap();
+        assert!(tx.save(task("task-2")).await.is_err());
+        drop(tx);
+        assert_eq!(store.list_deleted().await.unwrap().len(), 1);
+        store.restore("task-2").await.unwrap();
+
+        store.delete_many(&["task-1".to_string(), "task-2".to_string()]).await.unwrap();
+        assert_eq!(store.purge_deleted(Utc::now() - chrono::Duration::hours(1)).await.unwrap(), 0);
+        store.purge("task-2").await.unwrap();
+        assert_eq!(store.purge_deleted(Utc::now() + chrono::Duration::seconds(1)).await.unwrap(), 1);
+        assert!(store.list_deleted().await.unwrap().is_empty());
+        assert!(store.restore("task-1").await.is_err());
+        store.save(task("task-1")).await.unwrap();
+        assert!(store.get_labels("task-1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_task_store_erase() {
+        let store = SqliteTaskStore::connect("sqlite::memory:").await.unwrap().with_search_index().with_outbox();
+        let task = |id: &str| {
+            Task::new("ctx".to_string(), TaskStatus::new(TaskState::Completed))
+                .with_task_id(id.to_string())
+                .with_history(vec![crate::Message::new(crate::Role::User, vec![crate::Part::text("my IBAN".to_string())])])
+        };
+        store.save_many(vec![task("task-1"), task("task-2"), task("task-3")]).await.unwrap();
+        store.set_labels("task-1", Labels::from([("email".to_string(), "a-at-example.com".to_string())])).await.unwrap();
+        store.delete("task-2").await.unwrap();
+
+        let ids = ["task-1".to_string(), "task-2".to_string(), "missing".to_string()];
+        assert_eq!(store.erase(&ids).await.unwrap(), 2);
+        let stub = store.get("task-1").await.unwrap().unwrap();
+        assert!(stub.history.is_none());
+        assert!(stub.metadata.unwrap().contains_key(crate::a2a::server::tasks::ERASED_AT_METADATA_KEY));
+        assert!(store.get_labels("task-1").await.unwrap().is_empty());
+        assert!(store.get("task-2").await.unwrap().is_none());
+        assert!(store.list_deleted().await.unwrap()[0].task.history.is_none());
+        let found = store.search_tasks("iban", &TaskSearchFilter::default()).await.unwrap();
+        assert_eq!(found.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), vec!["task-3"]);
+        let entries = store.fetch_due(10).await.unwrap();
+        assert_eq!(entries.len(), 3);
+        for entry in entries {
+            assert_eq!(entry.task.history.is_some(), entry.task.id == "task-3");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_task_store_transactions() {
+        let store = SqliteTaskStore::connect("sqlite::memory:").await.unwrap().with_outbox();
+        let task = |state| Task::new("ctx".to_string(), TaskStatus::new(state)).with_task_id("task-1".to_string());
+
+        let mut tx = store.begin().await.unwrap();
+        tx.save(task(TaskState::Working)).await.unwrap();
+        assert_eq!(tx.get("task-1").await.unwrap().unwrap().status.state, TaskState::Working);
+        drop(tx);
+        assert!(store.get("task-1").await.unwrap().is_none());
+        assert!(store.fetch_due(10).await.unwrap().is_empty());
+
+        let mut tx = store.begin().await.unwrap();
+        tx.save(task(TaskState::Working)).await.unwrap();
+        tx.save(task(TaskState::Completed)).await.unwrap();
+        tx.commit().await.unwrap();
+        assert_eq!(store.get("task-1").await.unwrap().unwrap().status.state, TaskState::Completed);
+        assert_eq!(store.fetch_due(10).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_task_store_read_replica() {
+        // Two databases standing in for a primary and a replica that lags behind
+        let replica = SqliteTaskStore::connect("sqlite::memory:").await.unwrap();
+        let submitted = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Submitted)).with_task_id("task-1".to_string());
+        replica.save(submitted).await.unwrap();
+
+        let store = SqliteTaskStore::connect("sqlite::memory:").await.unwrap().with_read_replica(replica.pool.clone());
+        let working = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id("task-1".to_string());
+        store.save(working).await.unwrap();
+
+        assert_eq!(store.get("task-1").await.unwrap().unwrap().status.state, TaskState::Working);
+        let stale = store.get_with_consistency("task-1", ReadConsistency::Eventual).await.unwrap().unwrap();
+        assert_eq!(stale.status.state, TaskState::Submitted);
+        assert_eq!(store.list_with_consistency(ReadConsistency::Eventual).await.unwrap().len(), 1);
+
+        // Without a replica every read goes to the primary
+        let store = SqliteTaskStore::from_any_pool(store.pool.clone());
+        let fresh = store.get_with_consistency("task-1", ReadConsistency::Eventual).await.unwrap().unwrap();
+        assert_eq!(fresh.status.state, TaskState::Working);
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn test_sqlite_task_store_compression() {
+        use crate::{Message, Part, Role};
+
+        let plain = SqliteTaskStore::connect("sqlite::memory:").await.unwrap();
+        let mut task = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id("task-1".to_string());
+        task.history = Some(vec![Message::new(Role::User, vec![Part::text("hello ".repeat(500))])]);
+        plain.save(task.clone()).await.unwrap();
+
+        // Rows written before compression was enabled still read
+        let store = SqliteTaskStore::from_any_pool(plain.pool.clone()).with_compression(3);
+        assert_eq!(store.get("task-1").await.unwrap().unwrap(), task);
+
+        store.save(task.clone()).await.unwrap();
+        let (history,): (String,) = sqlx::query_as("SELECT history FROM tasks WHERE id = 'task-1'")
+            .fetch_one(&store.pool)
+            .await
+            .unwrap();
+        assert!(history.starts_with('~'));
+        assert!(history.len() < 500);
+        assert_eq!(store.get("task-1").await.unwrap().unwrap(), task);
+        assert_eq!(plain.get("task-1").await.unwrap().unwrap(), task);
+    }
+
+    /// A dialect with numbered parameters, as PostgreSQL uses
+    struct NumberedDialect;
+
+    impl SqlDialect for NumberedDialect {
+        fn placeholder(index: usize) -> String {
+            format!("${}", index)
+        }
+
+        fn upsert(table: &str, columns: &[&str], key: &str, rows: usize) -> String {
+            let updates: Vec<String> = columns
+                .iter()
+                .filter(|column| **column != key)
+                .map(|column| format!("{} = EXCLUDED.{}", column, column))
+                .collect();
+            format!(
+                "INSERT INTO {} ({}) VALUES {} ON CONFLICT ({}) DO UPDATE SET {}",
+                table,
+                columns.join(", "),
+                values(columns.len(), rows),
+                key,
+                updates.join(", ")
+            )
+        }
+
+        const JSON_TYPE: &'static str = "TEXT";
+
+        const AUTO_INCREMENT_KEY: &'static str = "BIGSERIAL PRIMARY KEY";
+    }
+
+    #[test]
+    fn test_dialect_placeholders() {
+        type Store = SqlTaskStore<NumberedDialect>;
+        assert_eq!(
+            Store::sql("SELECT key FROM labels WHERE task_id = ? AND key IN (?, ?)"),
+            "SELECT key FROM labels WHERE task_id = $1 AND key IN ($2, $3)"
+        );
+        assert_eq!(
+            Store::sql(&NumberedDialect::upsert("tasks", &["id", "status"], "id", 2)),
+            "INSERT INTO tasks (id, status) VALUES ($1, $2), ($3, $4) ON CONFLICT (id) DO UPDATE SET status = EXCLUDED.status"
+        );
+        assert_eq!(SqliteTaskStore::sql("DELETE FROM tasks WHERE id = ?"), "DELETE FROM tasks WHERE id = ?");
+    }
+
+    #[test]
+    fn test_sqlite_connect_url() {
+        assert_eq!(SqliteDialect::connect_url("sqlite://tasks.db"), "sqlite://tasks.db?mode=rwc");
+        assert_eq!(SqliteDialect::connect_url("sqlite://tasks.db?mode=ro"), "sqlite://tasks.db?mode=ro");
+        assert!(SqliteDialect::connect_url("sqlite::memory:").starts_with("sqlite:file:a2a-memory-"));
+    }
 }
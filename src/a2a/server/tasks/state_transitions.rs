@@ -0,0 +1,132 @@
+//! Recorded status transitions of tasks
+//!
+//! When the agent card advertises the `state_transition_history` capability,
+//! `TaskManager` appends a `TaskStateTransition` to `Task::state_transitions`
+//! for every status it saves, so clients can see when a task entered each
+//! state and why. The transitions are part of the task, so every store
+//! persists them with it.
+//!
+//! A long running task can go through many statuses, so the record is capped
+//! at `max_transitions`. Past the cap the oldest transitions are dropped,
+//! except the first one, which keeps when and how the task started.
+
+use crate::a2a::models::{AgentCapabilities, Task, TaskStateTransition};
+use crate::TaskStatus;
+
+/// Default cap on the transitions recorded per task
+pub const DEFAULT_MAX_STATE_TRANSITIONS: usize = 100;
+
+/// Recording and trimming rules of task status transitions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateTransitionHistory {
+    max_transitions: usize,
+}
+
+impl StateTransitionHistory {
+    /// Records up to `DEFAULT_MAX_STATE_TRANSITIONS` transitions per task
+    pub fn new() -> Self {
+        Self {
+            max_transitions: DEFAULT_MAX_STATE_TRANSITIONS,
+        }
+    }
+
+    /// The default rules if `capabilities` enables state transition history
+    pub fn from_capabilities(capabilities: &AgentCapabilities) -> Option<Self> {
+        capabilities.state_transition_history.unwrap_or(false).then(Self::new)
+    }
+
+    /// Sets the cap on the transitions recorded per task, at least 2
+    pub fn with_max_transitions(mut self, max_transitions: usize) -> Self {
+        self.max_transitions = max_transitions.max(2);
+        self
+    }
+
+    /// The cap on the transitions recorded per task
+    pub fn max_transitions(&self) -> usize {
+        self.max_transitions
+    }
+
+    /// Records `status` as the latest transition of `task`
+    ///
+    /// A status equal to the latest transition, such as a snapshot saved
+    /// again, is not recorded twice.
+    pub fn record(&self, task: &mut Task, status: &TaskStatus) {
+        let transitions = task.state_transitions.get_or_insert_with(Vec::new);
+        let message = status.message.as_deref().cloned();
+        if let Some(last) = transitions.last() {
+            let same_time = status.timestamp.as_ref().is_none_or(|timestamp| *timestamp == last.timestamp);
+            if last.state == status.state && last.message == message && same_time {
+                return;
+            }
+        }
+
+        transitions.push(TaskStateTransition {
+            state: status.state.clone(),
+            timestamp: status
+                .timestamp
+                .clone()
+                .unwrap_or_else(|| chrono::Utc::now().to_rfc3339()),
+            message,
+        });
+        if transitions.len() > self.max_transitions {
+            let excess = transitions.len() - self.max_transitions;
+            transitions.drain(1..=excess);
+        }
+    }
+
+    /// Carries the transitions of `previous` over to a task snapshot that
+    /// has none, then records the snapshot's status
+    pub fn record_snapshot(&self, previous: Option<&Task>, task: &mut Task) {
+        if task.state_transitions.is_none() {
+            task.state_transitions = previous.and_then(|previous| previous.state_transitions.clone());
+        }
+        let status = task.status.clone();
+        self.record(task, &status);
+    }
+}
+
+impl Default for StateTransitionHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Message, Part, Role, TaskState};
+
+    #[test]
+    fn test_records_transitions_and_trims_keeping_the_first() {
+        let history = StateTransitionHistory::new().with_max_transitions(3);
+        let mut task = Task::new("ctx-1".to_string(), TaskStatus::new(TaskState::Submitted));
+        let submitted = task.status.clone();
+        history.record(&mut task, &submitted);
+        history.record(&mut task, &submitted);
+        assert_eq!(task.state_transitions.as_ref().unwrap().len(), 1);
+
+        let working = TaskStatus::new(TaskState::Working)
+            .with_message(Message::new(Role::Agent, vec![Part::text("step".to_string())]));
+        history.record(&mut task, &working);
+        history.record(&mut task, &TaskStatus::new(TaskState::InputRequired));
+        history.record(&mut task, &TaskStatus::new(TaskState::Completed));
+
+        let states: Vec<TaskState> = task
+            .state_transitions
+            .unwrap()
+            .into_iter()
+            .map(|transition| transition.state)
+            .collect();
+        assert_eq!(states, vec![TaskState::Submitted, TaskState::InputRequired, TaskState::Completed]);
+    }
+
+    #[test]
+    fn test_enabled_by_capabilities() {
+        assert_eq!(StateTransitionHistory::from_capabilities(&AgentCapabilities::new()), None);
+        let capabilities = AgentCapabilities::new().with_state_transition_history(true);
+        assert_eq!(
+            StateTransitionHistory::from_capabilities(&capabilities),
+            Some(StateTransitionHistory::new())
+        );
+    }
+}
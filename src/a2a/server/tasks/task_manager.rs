@@ -7,9 +7,11 @@
 //! while adapting to Rust's type system and async patterns.
 
 use crate::{Message, Task, TaskStatus, TaskState, A2AError};
-use crate::a2a::server::events::{Event};
+use crate::a2a::server::events::{Event, EventBus};
 use crate::a2a::models::{TaskStatusUpdateEvent, TaskArtifactUpdateEvent};
-use crate::a2a::server::tasks::TaskStore;
+use crate::a2a::server::tasks::{BufferedTransaction, StateTransitionHistory, TaskStore};
+use crate::a2a::utils::metadata::{merge_metadata, HasMetadata};
+use crate::a2a::utils::sequence::{event_sequence, set_event_sequence};
 use std::sync::Arc;
 use tracing::{debug, info};
 use uuid::Uuid;
@@ -35,6 +37,12 @@ pub struct TaskManager {
     initial_message: Option<Message>,
     /// Current task object in memory
     current_task: Arc<tokio::sync::Mutex<Option<Task>>>,
+    /// Bus that saved task events are published on, if any
+    event_bus: Option<EventBus>,
+    /// Bus sequence number of the last event this manager published
+    last_sequence: Option<u64>,
+    /// Rules for recording status transitions on the task, if enabled
+    state_transition_history: Option<StateTransitionHistory>,
 }
 
 impl TaskManager {
@@ -71,9 +79,29 @@ impl TaskManager {
             task_store,
             initial_message,
             current_task: Arc::new(tokio::sync::Mutex::new(None)),
+            event_bus: None,
+            last_sequence: None,
+            state_transition_history: None,
         })
     }
 
+    /// Publishes every saved task event on the given bus
+    pub fn with_event_bus(mut self, event_bus: EventBus) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// Records every saved status in `Task::state_transitions`
+    pub fn with_state_transition_history(mut self, history: StateTransitionHistory) -> Self {
+        self.state_transition_history = Some(history);
+        self
+    }
+
+    /// Returns the bus sequence number of the last event this manager published
+    pub fn last_sequence(&self) -> Option<u64> {
+        self.last_sequence
+    }
+
     /// Retrieves the current task object, either from memory or the store
     /// 
     /// If task_id is set, it first checks the in-memory current_task,
@@ -118,6 +146,32 @@ impl TaskManager {
     /// # Arguments
     /// * `event` - The task-related event (Task, TaskStatusUpdateEvent, or TaskArtifactUpdateEvent)
     pub async fn save_task_event(&mut self, event: TaskEvent) -> Result<Task, A2AError> {
+        Ok(self.save_sequenced_event(event).await?.0)
+    }
+
+    /// Saves a task event, returning the updated task and the event as
+    /// published, with its sequence number assigned
+    async fn save_sequenced_event(&mut self, event: TaskEvent) -> Result<(Task, TaskEvent), A2AError> {
+        self.check_event_ids(&event)?;
+
+        debug!(
+            "Processing save of task event of type {} for task_id: {}",
+            event.event_type(),
+            event.task_id()
+        );
+
+        let event = self.assign_sequence(event).await?;
+        let task = self.apply_task_event(event.clone()).await?;
+
+        if let Some(event_bus) = &self.event_bus {
+            self.last_sequence = Some(event_bus.publish(Event::from(event.clone()), task.clone()));
+        }
+
+        Ok((task, event))
+    }
+
+    /// Ensures the task and context IDs of an event match, or sets them from it
+    fn check_event_ids(&mut self, event: &TaskEvent) -> Result<(), A2AError> {
         let task_id_from_event = event.task_id();
         let context_id_from_event = event.context_id();
         
@@ -145,14 +199,27 @@ impl TaskManager {
             self.context_id = Some(context_id_from_event.clone());
         }
 
-        debug!(
-            "Processing save of task event of type {} for task_id: {}",
-            event.event_type(),
-            task_id_from_event
-        );
+        Ok(())
+    }
 
+    /// Numbers update events with the task's next sequence number
+    ///
+    /// Any number assigned earlier, e.g. by an event queue, is replaced so the
+    /// numbering stays contiguous across requests. Full task snapshots keep
+    /// the task's current number.
+    async fn assign_sequence(&self, event: TaskEvent) -> Result<TaskEvent, A2AError> {
+        let last = self.get_task().await?.as_ref().and_then(event_sequence).unwrap_or(0);
+        Ok(sequenced(event, last))
+    }
+
+    /// Applies a task event to the current task and persists the result
+    async fn apply_task_event(&self, event: TaskEvent) -> Result<Task, A2AError> {
         match event {
-            TaskEvent::Task(task) => {
+            TaskEvent::Task(mut task) => {
+                if let Some(history) = &self.state_transition_history {
+                    let previous = self.get_task().await?;
+                    history.record_snapshot(previous.as_ref(), &mut task);
+                }
                 self.save_task(task.clone()).await?;
                 Ok(task)
             }
@@ -161,29 +228,8 @@ impl TaskManager {
                 
                 debug!("Updating task {} status to: {:?}", task.id.to_string(), status_event.status.state);
                 
-                // Move current status message to history if present
-                if let Some(ref message) = task.status.message {
-                    if task.history.is_none() {
-                        task.history = Some(vec![*message.clone()]);
-                    } else if let Some(ref mut history) = task.history {
-                        history.push(*message.clone());
-                    }
-                    task.status.message = None;
-                }
-                
-                // Update metadata if provided
-                if let Some(ref metadata) = status_event.metadata {
-                    if task.metadata.is_none() {
-                        task.metadata = Some(std::collections::HashMap::new());
-                    }
-                    if let Some(ref mut task_metadata) = task.metadata {
-                        for (key, value) in metadata {
-                            task_metadata.insert(key.clone(), value.clone());
-                        }
-                    }
-                }
-                
-                task.status = status_event.status.clone();
+                apply_status_update(&mut task, &status_event);
+                self.record_transition(&mut task, &status_event.status);
                 self.save_task(task.clone()).await?;
                 Ok(task)
             }
@@ -192,12 +238,7 @@ impl TaskManager {
                 
                 debug!("Appending artifact to task {}", task.id.to_string());
                 
-                // Append artifact to task
-                if task.artifacts.is_none() {
-                    task.artifacts = Some(vec![artifact_event.artifact.clone()]);
-                } else if let Some(ref mut artifacts) = task.artifacts {
-                    artifacts.push(artifact_event.artifact.clone());
-                }
+                apply_artifact_update(&mut task, &artifact_event);
                 
                 self.save_task(task.clone()).await?;
                 Ok(task)
@@ -238,23 +279,85 @@ impl TaskManager {
     }
 
     /// Processes an event, updates the task state if applicable, stores it, and returns the event
+    ///
+    /// Task events are returned as saved, carrying their sequence number.
     pub async fn process_event(&mut self, event: &Event) -> Result<Event, A2AError> {
-        match event {
-            Event::Task(task) => {
-                self.save_task_event(TaskEvent::Task(task.clone())).await?;
-            }
-            Event::TaskStatusUpdate(status_event) => {
-                self.save_task_event(TaskEvent::StatusUpdate(status_event.clone())).await?;
-            }
-            Event::TaskArtifactUpdate(artifact_event) => {
-                self.save_task_event(TaskEvent::ArtifactUpdate(artifact_event.clone())).await?;
-            }
+        let task_event = match event {
+            Event::Task(task) => TaskEvent::Task(task.clone()),
+            Event::TaskStatusUpdate(status_event) => TaskEvent::StatusUpdate(status_event.clone()),
+            Event::TaskArtifactUpdate(artifact_event) => TaskEvent::ArtifactUpdate(artifact_event.clone()),
             Event::Message(_) => {
                 // Non-task events are just passed through
+                return Ok(event.clone());
             }
+        };
+
+        let (_, saved) = self.save_sequenced_event(task_event).await?;
+        Ok(Event::from(saved))
+    }
+
+    /// Applies a batch of task events atomically
+    ///
+    /// The events are applied in order to the task as read within a store
+    /// transaction, and the resulting task is committed once, so a failure
+    /// or crash part way through leaves the stored task as it was. Stores
+    /// without transactions commit through a `BufferedTransaction`. The
+    /// events are published only after the commit. Returns the resulting
+    /// task, or the current one for an empty batch.
+    pub async fn save_task_events(&mut self, events: Vec<TaskEvent>) -> Result<Option<Task>, A2AError> {
+        for event in &events {
+            self.check_event_ids(event)?;
         }
-        
-        Ok(event.clone())
+        let Some(task_id) = self.task_id.clone() else {
+            return Ok(None);
+        };
+
+        let mut tx = match self.task_store.begin().await {
+            Err(A2AError::UnsupportedOperation(_)) => Box::new(BufferedTransaction::new(self.task_store.clone())),
+            result => result?,
+        };
+        let mut task = tx.get(&task_id).await?;
+        let mut applied = Vec::with_capacity(events.len());
+        for event in events {
+            let last = task.as_ref().and_then(event_sequence).unwrap_or(0);
+            let event = sequenced(event, last);
+            let mut updated = match (&event, task.take()) {
+                (TaskEvent::Task(snapshot), previous) => {
+                    let mut snapshot = snapshot.clone();
+                    if let Some(history) = &self.state_transition_history {
+                        history.record_snapshot(previous.as_ref(), &mut snapshot);
+                    }
+                    snapshot
+                }
+                (_, Some(current)) => current,
+                (_, None) => self.init_task_obj(&event.task_id(), &event.context_id()),
+            };
+            match &event {
+                TaskEvent::Task(_) => {}
+                TaskEvent::StatusUpdate(status_event) => {
+                    apply_status_update(&mut updated, status_event);
+                    self.record_transition(&mut updated, &status_event.status);
+                }
+                TaskEvent::ArtifactUpdate(artifact_event) => apply_artifact_update(&mut updated, artifact_event),
+            }
+            applied.push((event, updated.clone()));
+            task = Some(updated);
+        }
+        let Some(task) = task else {
+            return Ok(None);
+        };
+
+        debug!("Committing {} task events for task_id: {}", applied.len(), task_id);
+        tx.save(task.clone()).await?;
+        tx.commit().await?;
+        *self.current_task.lock().await = Some(task.clone());
+
+        if let Some(event_bus) = &self.event_bus {
+            for (event, snapshot) in applied {
+                self.last_sequence = Some(event_bus.publish(Event::from(event), snapshot));
+            }
+        }
+        Ok(Some(task))
     }
 
     /// Initializes a new task object in memory
@@ -273,18 +376,29 @@ impl TaskManager {
             None
         };
 
-        Task {
+        let mut task = Task {
             id: task_id_uuid.to_string(),
             context_id: context_id_uuid.to_string(),
             status: TaskStatus {
                 state: TaskState::Submitted,
-                timestamp: Some(chrono::Utc::now().to_string()),
+                timestamp: Some(chrono::Utc::now().to_rfc3339()),
                 message: None,
             },
             artifacts: None,
             history,
+            state_transitions: None,
             metadata: None,
             kind: "task".to_string(),
+        };
+        let status = task.status.clone();
+        self.record_transition(&mut task, &status);
+        task
+    }
+
+    /// Records `status` in the task's state transitions, if enabled
+    fn record_transition(&self, task: &mut Task, status: &TaskStatus) {
+        if let Some(history) = &self.state_transition_history {
+            history.record(task, status);
         }
     }
 
@@ -349,6 +463,58 @@ impl TaskManager {
     }
 }
 
+/// Numbers an update event as the successor of `last`
+///
+/// Full task snapshots keep the task's current number.
+fn sequenced(mut event: TaskEvent, last: u64) -> TaskEvent {
+    match &mut event {
+        TaskEvent::StatusUpdate(update) => set_event_sequence(update, last + 1),
+        TaskEvent::ArtifactUpdate(update) => set_event_sequence(update, last + 1),
+        TaskEvent::Task(task) => {
+            if last > 0 && event_sequence(task).is_none() {
+                set_event_sequence(task, last);
+            }
+        }
+    }
+    event
+}
+
+/// Applies a status update to a task
+fn apply_status_update(task: &mut Task, status_event: &TaskStatusUpdateEvent) {
+    // Move current status message to history if present
+    if let Some(ref message) = task.status.message {
+        if task.history.is_none() {
+            task.history = Some(vec![*message.clone()]);
+        } else if let Some(ref mut history) = task.history {
+            history.push(*message.clone());
+        }
+        task.status.message = None;
+    }
+    
+    // Merge metadata from the event into the task's existing metadata
+    if let Some(ref metadata) = status_event.metadata {
+        merge_metadata(task.metadata_mut(), metadata);
+    }
+    
+    task.status = status_event.status.clone();
+}
+
+/// Applies an artifact update to a task
+fn apply_artifact_update(task: &mut Task, artifact_event: &TaskArtifactUpdateEvent) {
+    if let Some(sequence) = event_sequence(artifact_event) {
+        set_event_sequence(task, sequence);
+    }
+
+    // Chunks with `append` extend the artifact with the same ID, others replace it
+    let artifact = &artifact_event.artifact;
+    let artifacts = task.artifacts.get_or_insert_with(Vec::new);
+    match artifacts.iter_mut().find(|existing| existing.artifact_id == artifact.artifact_id) {
+        Some(existing) if artifact_event.append == Some(true) => existing.parts.extend(artifact.parts.iter().cloned()),
+        Some(existing) => *existing = artifact.clone(),
+        None => artifacts.push(artifact.clone()),
+    }
+}
+
 /// Enum representing different types of task-related events
 #[derive(Debug, Clone)]
 pub enum TaskEvent {
@@ -386,6 +552,16 @@ impl TaskEvent {
     }
 }
 
+impl From<TaskEvent> for Event {
+    fn from(event: TaskEvent) -> Self {
+        match event {
+            TaskEvent::Task(task) => Event::Task(task),
+            TaskEvent::StatusUpdate(update) => Event::TaskStatusUpdate(update),
+            TaskEvent::ArtifactUpdate(update) => Event::TaskArtifactUpdate(update),
+        }
+    }
+}
+
 /// Wrapper trait for events that have task_id and context_id
 pub trait TaskEventWrapper: Send + Sync {
     fn task_id(&self) -> &str;
@@ -473,11 +649,12 @@ mod tests {
             context_id: context_id.to_string(),
             status: TaskStatus {
                 state: TaskState::Working,
-                timestamp: Some(chrono::Utc::now().to_string()),
+                timestamp: Some(chrono::Utc::now().to_rfc3339()),
                 message: None,
             },
             artifacts: None,
             history: None,
+            state_transitions: None,
             metadata: None,
             kind: "task".to_string(),
         };
@@ -500,7 +677,7 @@ mod tests {
             context_id: "550e8400-e29b-41d4-a716-446655440003".to_string(),
             status: TaskStatus {
                 state: TaskState::Working,
-                timestamp: Some(chrono::Utc::now().to_string()),
+                timestamp: Some(chrono::Utc::now().to_rfc3339()),
                 message: None,
             },
             r#final: false,
@@ -530,11 +707,12 @@ mod tests {
             context_id: context_id.to_string(),
             status: TaskStatus {
                 state: TaskState::Working,
-                timestamp: Some(chrono::Utc::now().to_string()),
+                timestamp: Some(chrono::Utc::now().to_rfc3339()),
                 message: Some(Box::new(Message::new(Role::Agent, vec![Part::text("Current status".to_string())]))),
             },
             artifacts: None,
             history: None,
+            state_transitions: None,
             metadata: None,
             kind: "task".to_string(),
         };
@@ -548,4 +726,179 @@ mod tests {
         assert_eq!(updated_task.history.as_ref().unwrap()[1].role, Role::User);
         assert!(updated_task.status.message.is_none());
     }
+
+    #[tokio::test]
+    async fn test_status_update_merges_metadata() {
+        let (mut manager, _) = create_test_task_manager();
+
+        let mut task = Task::new(
+            "550e8400-e29b-41d4-a716-446655440001".to_string(),
+            TaskStatus::new(TaskState::Submitted),
+        )
+        .with_task_id("550e8400-e29b-41d4-a716-446655440000".to_string());
+        task.metadata_mut().insert("owner".to_string(), serde_json::json!("alice"));
+        task.metadata_mut().insert("progress".to_string(), serde_json::json!({"step": 1, "total": 3}));
+        manager.save_task_event(TaskEvent::Task(task)).await.unwrap();
+
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("progress".to_string(), serde_json::json!({"step": 2}));
+        let status_event = TaskStatusUpdateEvent {
+            task_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            context_id: "550e8400-e29b-41d4-a716-446655440001".to_string(),
+            status: TaskStatus::new(TaskState::Working),
+            r#final: false,
+            kind: "status-update".to_string(),
+            metadata: Some(metadata),
+        };
+
+        let task = manager.save_task_event(TaskEvent::StatusUpdate(status_event)).await.unwrap();
+        let metadata = task.metadata.unwrap();
+        assert_eq!(metadata["owner"], "alice");
+        assert_eq!(metadata["progress"], serde_json::json!({"step": 2, "total": 3}));
+    }
+
+    #[tokio::test]
+    async fn test_update_events_are_numbered_per_task() {
+        let (mut manager, store) = create_test_task_manager();
+        let task_id = "550e8400-e29b-41d4-a716-446655440000".to_string();
+        let context_id = "550e8400-e29b-41d4-a716-446655440001".to_string();
+        let status = |state| {
+            Event::TaskStatusUpdate(TaskStatusUpdateEvent::new(
+                task_id.clone(),
+                context_id.clone(),
+                TaskStatus::new(state),
+                false,
+            ))
+        };
+
+        let first = manager.process_event(&status(TaskState::Working)).await.unwrap();
+        let artifact = Event::TaskArtifactUpdate(TaskArtifactUpdateEvent::new(
+            task_id.clone(),
+            context_id.clone(),
+            crate::Artifact::new(vec![Part::text("out".to_string())]),
+        ));
+        let second = manager.process_event(&artifact).await.unwrap();
+        match (&first, &second) {
+            (Event::TaskStatusUpdate(first), Event::TaskArtifactUpdate(second)) => {
+                assert_eq!(event_sequence(first), Some(1));
+                assert_eq!(event_sequence(second), Some(2));
+            }
+            other => panic!("unexpected events {:?}", other),
+        }
+
+        // A new manager for the same task continues the numbering
+        let mut manager = TaskManager::new(Some(task_id.clone()), Some(context_id.clone()), store, None, None).unwrap();
+        match manager.process_event(&status(TaskState::Completed)).await.unwrap() {
+            Event::TaskStatusUpdate(update) => assert_eq!(event_sequence(&update), Some(3)),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    /// A store without transactions whose bulk saves fail
+    struct FailingBulkStore(InMemoryTaskStore);
+
+    #[async_trait::async_trait]
+    impl TaskStore for FailingBulkStore {
+        async fn save(&self, task: Task) -> Result<(), A2AError> {
+            self.0.save(task).await
+        }
+
+        async fn get(&self, task_id: &str) -> Result<Option<Task>, A2AError> {
+            self.0.get(task_id).await
+        }
+
+        async fn delete(&self, task_id: &str) -> Result<(), A2AError> {
+            self.0.delete(task_id).await
+        }
+
+        async fn save_many(&self, _tasks: Vec<Task>) -> Result<(), A2AError> {
+            Err(A2AError::internal("connection lost"))
+        }
+    }
+
+    fn status_event(state: TaskState) -> TaskEvent {
+        TaskEvent::StatusUpdate(TaskStatusUpdateEvent::new(
+            "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            "550e8400-e29b-41d4-a716-446655440001".to_string(),
+            TaskStatus::new(state),
+            false,
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_save_task_events_applies_batch() {
+        let (manager, store) = create_test_task_manager();
+        let event_bus = EventBus::new();
+        let mut events = event_bus.subscribe();
+        let mut manager = manager.with_event_bus(event_bus);
+
+        let artifact = TaskEvent::ArtifactUpdate(TaskArtifactUpdateEvent::new(
+            "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            "550e8400-e29b-41d4-a716-446655440001".to_string(),
+            crate::Artifact::new(vec![Part::text("out".to_string())]),
+        ));
+        let task = manager
+            .save_task_events(vec![status_event(TaskState::Working), artifact, status_event(TaskState::Completed)])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.status.state, TaskState::Completed);
+        assert_eq!(task.artifacts.as_ref().unwrap().len(), 1);
+        assert_eq!(event_sequence(&task), Some(3));
+        assert_eq!(store.get(&task.id).await.unwrap().unwrap(), task);
+
+        // Published after the commit, in order, with the task as of each event
+        let first = events.recv().await.unwrap();
+        assert_eq!(first.task.status.state, TaskState::Working);
+        assert!(first.task.artifacts.is_none());
+        assert_eq!(events.recv().await.unwrap().task.artifacts.unwrap().len(), 1);
+        assert_eq!(events.recv().await.unwrap().task.status.state, TaskState::Completed);
+        assert_eq!(manager.last_sequence(), Some(3));
+
+        assert_eq!(manager.save_task_events(Vec::new()).await.unwrap(), Some(task));
+    }
+
+    #[test]
+    fn test_artifact_chunks_are_merged() {
+        let mut task = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working));
+        let chunk = |text: &str| crate::Artifact::new(vec![Part::text(text.to_string())]).with_artifact_id("a1".to_string());
+        let event = |text: &str, append: bool| TaskArtifactUpdateEvent::new(task.id.clone(), "ctx".to_string(), chunk(text)).with_append(append);
+        let (hel, lo, hello) = (event("Hel", false), event("lo", true), event("Hello", false));
+        apply_artifact_update(&mut task, &hel);
+        apply_artifact_update(&mut task, &lo);
+        let artifacts = task.artifacts.as_ref().unwrap();
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].parts.len(), 2);
+        apply_artifact_update(&mut task, &hello);
+        assert_eq!(task.artifacts.unwrap()[0].parts, vec![Part::text("Hello".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_leaves_task_unchanged() {
+        let store = Arc::new(FailingBulkStore(InMemoryTaskStore::new()));
+        let mut manager = TaskManager::new(
+            Some("550e8400-e29b-41d4-a716-446655440000".to_string()),
+            Some("550e8400-e29b-41d4-a716-446655440001".to_string()),
+            store.clone(),
+            None,
+            None,
+        ).unwrap();
+        manager.save_task_event(status_event(TaskState::Working)).await.unwrap();
+
+        let result = manager
+            .save_task_events(vec![status_event(TaskState::InputRequired), status_event(TaskState::Completed)])
+            .await;
+        assert!(result.is_err());
+        let stored = store.get("550e8400-e29b-41d4-a716-446655440000").await.unwrap().unwrap();
+        assert_eq!(stored.status.state, TaskState::Working);
+
+        // An event for another task rejects the whole batch up front
+        let other = TaskEvent::StatusUpdate(TaskStatusUpdateEvent::new(
+            "other".to_string(),
+            "550e8400-e29b-41d4-a716-446655440001".to_string(),
+            TaskStatus::new(TaskState::Failed),
+            false,
+        ));
+        assert!(manager.save_task_events(vec![other]).await.is_err());
+    }
 }
@@ -6,8 +6,71 @@
 //! This implementation aligns with the Python version which uses string IDs
 //! for better compatibility.
 
-use crate::{Task, A2AError};
+use crate::{Message, Task, A2AError};
+use crate::a2a::server::tasks::data_subject::erased_task;
+use crate::a2a::server::tasks::labels::{validate_labels, LabelSelector, Labels};
+use crate::a2a::server::tasks::search::{search_terms, task_matches, TaskSearchFilter};
+use crate::a2a::server::tasks::usage::{Usage, UsageRecord};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+/// How fresh a read must be
+///
+/// Stores with read replicas serve eventually consistent reads from a
+/// replica, which may lag behind the latest writes; other stores treat both
+/// levels alike.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReadConsistency {
+    /// Read the latest write, from the primary
+    #[default]
+    Strong,
+    /// Tolerate a stale result, such as a replica that has not caught up
+    Eventual,
+}
+
+/// A unit of work on a task store
+///
+/// Saves become visible to other callers only on `commit`; dropping the
+/// transaction without committing discards them.
+#[async_trait]
+pub trait TaskStoreTransaction: Send {
+    /// Retrieves a task, seeing the saves of this transaction
+    async fn get(&mut self, task_id: &str) -> Result<Option<Task>, A2AError>;
+
+    /// Saves or updates a task within the transaction
+    async fn save(&mut self, task: Task) -> Result<(), A2AError>;
+
+    /// Makes the saves of the transaction visible, all at once
+    async fn commit(self: Box<Self>) -> Result<(), A2AError>;
+}
+
+/// Messages of a task history, oldest first, as `TaskStore::get_history` returns them
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryPage {
+    /// The messages of the page
+    pub messages: Vec<Message>,
+    /// The number of messages in the whole history
+    pub total: usize,
+}
+
+impl HistoryPage {
+    /// The page of `history` holding up to `limit` messages from `offset`
+    pub fn from_history(history: &[Message], offset: usize, limit: usize) -> Self {
+        Self {
+            messages: history.iter().skip(offset).take(limit).cloned().collect(),
+            total: history.len(),
+        }
+    }
+}
+
+/// A deleted task that can still be restored, as `TaskStore::list_deleted` returns it
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DeletedTask {
+    /// The task as it was when deleted
+    pub task: Task,
+    /// When the task was deleted
+    pub deleted_at: DateTime<Utc>,
+}
 
 /// Task Store interface for persisting and retrieving Task objects
 /// 
@@ -22,25 +85,249 @@ pub trait TaskStore: Send + Sync {
     async fn get(&self, task_id: &str) -> Result<Option<Task>, A2AError>;
     
     /// Deletes a task from the store by ID
+    ///
+    /// Stores with soft deletion keep the task, hidden from every read,
+    /// until it is restored or purged. Saving a deleted task fails with task
+    /// not found; only `restore` brings it back.
     async fn delete(&self, task_id: &str) -> Result<(), A2AError>;
+
+    /// Restores a deleted task (optional implementation)
+    ///
+    /// Fails with task not found if there is no deleted task with the ID.
+    async fn restore(&self, _task_id: &str) -> Result<(), A2AError> {
+        Err(A2AError::unsupported_operation("Task restore not supported"))
+    }
+
+    /// Permanently removes a task, deleted or not, with its labels
+    ///
+    /// The default deletes it, for stores without soft deletion.
+    async fn purge(&self, task_id: &str) -> Result<(), A2AError> {
+        self.delete(task_id).await
+    }
+
+    /// Permanently removes the tasks deleted before `older_than`, returning
+    /// how many were removed (optional implementation)
+    async fn purge_deleted(&self, _older_than: DateTime<Utc>) -> Result<u64, A2AError> {
+        Err(A2AError::unsupported_operation("Soft deletion not supported"))
+    }
+
+    /// Lists the deleted tasks that can still be restored (optional implementation)
+    async fn list_deleted(&self) -> Result<Vec<DeletedTask>, A2AError> {
+        Err(A2AError::unsupported_operation("Soft deletion not supported"))
+    }
+
+    /// Replaces the tasks, deleted or not, by their audit stubs, returning
+    /// how many were erased
+    ///
+    /// A stub is what `erased_task` keeps of a task; labels are dropped and
+    /// deleted tasks stay deleted. The default saves the stubs of the live
+    /// tasks, for stores keeping nothing else about them.
+    async fn erase(&self, task_ids: &[String]) -> Result<usize, A2AError> {
+        let erased_at = Utc::now();
+        let stubs: Vec<Task> = self.get_many(task_ids).await?.iter().map(|task| erased_task(task, erased_at)).collect();
+        let erased = stubs.len();
+        self.save_many(stubs).await?;
+        Ok(erased)
+    }
+
+    /// Retrieves a task, tolerating a stale result if `consistency` allows
+    ///
+    /// `get` is a strongly consistent read; the default ignores `consistency`.
+    async fn get_with_consistency(&self, task_id: &str, _consistency: ReadConsistency) -> Result<Option<Task>, A2AError> {
+        self.get(task_id).await
+    }
+
+    /// Retrieves a task without its history, tolerating a stale result if `consistency` allows
+    ///
+    /// Stores keeping large histories should override this to skip reading
+    /// the history; the default reads the whole task and drops it.
+    async fn get_meta(&self, task_id: &str, consistency: ReadConsistency) -> Result<Option<Task>, A2AError> {
+        Ok(self
+            .get_with_consistency(task_id, consistency)
+            .await?
+            .map(|task| Task { history: None, ..task }))
+    }
+
+    /// Retrieves up to `limit` messages of the history of a task from `offset`
+    ///
+    /// `None` if there is no such task. The default reads the whole task.
+    async fn get_history(&self, task_id: &str, offset: usize, limit: usize) -> Result<Option<HistoryPage>, A2AError> {
+        Ok(self
+            .get(task_id)
+            .await?
+            .map(|task| HistoryPage::from_history(task.history.as_deref().unwrap_or_default(), offset, limit)))
+    }
+
+    /// Saves or updates several tasks
+    ///
+    /// Stores that support it save all tasks or none; the default saves
+    /// them one by one.
+    async fn save_many(&self, tasks: Vec<Task>) -> Result<(), A2AError> {
+        for task in tasks {
+            self.save(task).await?;
+        }
+        Ok(())
+    }
+
+    /// Retrieves the tasks with the given IDs, in the order requested
+    ///
+    /// IDs without a task are skipped.
+    async fn get_many(&self, task_ids: &[String]) -> Result<Vec<Task>, A2AError> {
+        let mut tasks = Vec::with_capacity(task_ids.len());
+        for task_id in task_ids {
+            if let Some(task) = self.get(task_id).await? {
+                tasks.push(task);
+            }
+        }
+        Ok(tasks)
+    }
+
+    /// Deletes the tasks with the given IDs
+    async fn delete_many(&self, task_ids: &[String]) -> Result<(), A2AError> {
+        for task_id in task_ids {
+            self.delete(task_id).await?;
+        }
+        Ok(())
+    }
+    
+    /// Starts a transaction (optional implementation)
+    ///
+    /// Without transactions, `BufferedTransaction` offers atomic commits but
+    /// no isolation of reads.
+    async fn begin(&self) -> Result<Box<dyn TaskStoreTransaction>, A2AError> {
+        Err(A2AError::unsupported_operation("Task store transactions not supported"))
+    }
     
     /// Lists all tasks in the store (optional implementation)
     async fn list(&self) -> Result<Vec<Task>, A2AError> {
         Err(A2AError::unsupported_operation("Task listing not supported"))
     }
     
+    /// Lists all tasks, tolerating a stale result if `consistency` allows
+    async fn list_with_consistency(&self, _consistency: ReadConsistency) -> Result<Vec<Task>, A2AError> {
+        self.list().await
+    }
+
     /// Lists tasks by context ID (optional implementation)
     async fn list_by_context(&self, _context_id: &str) -> Result<Vec<Task>, A2AError> {
         Err(A2AError::unsupported_operation("Task listing by context not supported"))
     }
+
+    /// Replaces the labels attached to a task (optional implementation)
+    async fn set_labels(&self, _task_id: &str, _labels: Labels) -> Result<(), A2AError> {
+        Err(A2AError::unsupported_operation("Task labels not supported"))
+    }
+
+    /// Retrieves the labels attached to a task (optional implementation)
+    async fn get_labels(&self, _task_id: &str) -> Result<Labels, A2AError> {
+        Err(A2AError::unsupported_operation("Task labels not supported"))
+    }
+
+    /// Lists tasks whose labels match the selector (optional implementation)
+    async fn list_by_labels(&self, _selector: &LabelSelector) -> Result<Vec<Task>, A2AError> {
+        Err(A2AError::unsupported_operation("Task listing by labels not supported"))
+    }
+
+    /// Lists the tasks whose history holds every word of `query` in its text
+    /// parts, most recently updated first (optional implementation)
+    ///
+    /// See the `search` module for how words are matched.
+    async fn search_tasks(&self, _query: &str, _filter: &TaskSearchFilter) -> Result<Vec<Task>, A2AError> {
+        Err(A2AError::unsupported_operation("Task search not supported"))
+    }
+
+    /// Adds `usage` to the totals of a task, returning the new totals (optional implementation)
+    ///
+    /// The first report bills the task to `principal`; later reports keep it.
+    async fn record_usage(&self, _task_id: &str, _principal: &str, _usage: Usage) -> Result<UsageRecord, A2AError> {
+        Err(A2AError::unsupported_operation("Task usage not supported"))
+    }
+
+    /// Retrieves the usage totals of a task (optional implementation)
+    async fn get_usage(&self, _task_id: &str) -> Result<Option<UsageRecord>, A2AError> {
+        Err(A2AError::unsupported_operation("Task usage not supported"))
+    }
+
+    /// Lists the usage totals of every task usage was reported for (optional implementation)
+    async fn list_usage(&self) -> Result<Vec<UsageRecord>, A2AError> {
+        Err(A2AError::unsupported_operation("Task usage not supported"))
+    }
+
+    /// Sums the usage of the tasks of a context
+    ///
+    /// The default sums `list_usage`; stores keeping many usage records
+    /// should sum them where they are kept.
+    async fn context_usage(&self, context_id: &str) -> Result<Usage, A2AError> {
+        Ok(self
+            .list_usage()
+            .await?
+            .into_iter()
+            .filter(|record| record.context_id == context_id)
+            .map(|record| record.usage)
+            .sum())
+    }
+
+    /// Sums the usage of the tasks billed to a principal
+    ///
+    /// The default sums `list_usage`, like `context_usage`.
+    async fn principal_usage(&self, principal: &str) -> Result<Usage, A2AError> {
+        Ok(self
+            .list_usage()
+            .await?
+            .into_iter()
+            .filter(|record| record.principal == principal)
+            .map(|record| record.usage)
+            .sum())
+    }
+}
+
+/// A transaction buffering saves and writing them with `save_many` on commit
+///
+/// Works over any store; the commit is atomic if the store's `save_many` is,
+/// but reads are not isolated from concurrent writers.
+pub struct BufferedTransaction {
+    store: std::sync::Arc<dyn TaskStore>,
+    writes: Vec<Task>,
+}
+
+impl BufferedTransaction {
+    pub fn new(store: std::sync::Arc<dyn TaskStore>) -> Self {
+        Self {
+            store,
+            writes: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl TaskStoreTransaction for BufferedTransaction {
+    async fn get(&mut self, task_id: &str) -> Result<Option<Task>, A2AError> {
+        match self.writes.iter().rev().find(|task| task.id == task_id) {
+            Some(task) => Ok(Some(task.clone())),
+            None => self.store.get(task_id).await,
+        }
+    }
+
+    async fn save(&mut self, task: Task) -> Result<(), A2AError> {
+        self.writes.push(task);
+        Ok(())
+    }
+
+    async fn commit(self: Box<Self>) -> Result<(), A2AError> {
+        self.store.save_many(self.writes).await
+    }
 }
 
 /// In-memory implementation of TaskStore
 /// 
 /// Uses a HashMap with string keys to store tasks, compatible with the
-/// Python implementation's string-based identifiers.
+/// Python implementation's string-based identifiers. Deleted tasks are moved
+/// to a second map until restored or purged.
 pub struct InMemoryTaskStore {
     tasks: std::sync::Arc<tokio::sync::RwLock<std::collections::HashMap<String, Task>>>,
+    deleted: std::sync::Arc<tokio::sync::RwLock<std::collections::HashMap<String, DeletedTask>>>,
+    labels: std::sync::Arc<tokio::sync::RwLock<std::collections::HashMap<String, Labels>>>,
+    usage: std::sync::Arc<tokio::sync::RwLock<std::collections::HashMap<String, UsageRecord>>>,
 }
 
 impl InMemoryTaskStore {
@@ -48,6 +335,9 @@ impl InMemoryTaskStore {
     pub fn new() -> Self {
         Self {
             tasks: std::sync::Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
+            deleted: std::sync::Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
+            labels: std::sync::Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
+            usage: std::sync::Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
         }
     }
     
@@ -55,6 +345,9 @@ impl InMemoryTaskStore {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             tasks: std::sync::Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::with_capacity(capacity))),
+            deleted: std::sync::Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
+            labels: std::sync::Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
+            usage: std::sync::Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
         }
     }
 }
@@ -71,6 +364,9 @@ impl TaskStore for InMemoryTaskStore {
         let mut tasks = self.tasks.write().await;
         // Convert UUID to string for storage key
         let task_id_str = task.id.to_string();
+        if self.deleted.read().await.contains_key(&task_id_str) {
+            return Err(A2AError::task_not_found(&task_id_str));
+        }
         tasks.insert(task_id_str, task);
         Ok(())
     }
@@ -79,10 +375,125 @@ impl TaskStore for InMemoryTaskStore {
         let tasks = self.tasks.read().await;
         Ok(tasks.get(task_id).cloned())
     }
+
+    /// Clones the task without its history
+    async fn get_meta(&self, task_id: &str, _consistency: ReadConsistency) -> Result<Option<Task>, A2AError> {
+        let tasks = self.tasks.read().await;
+        Ok(tasks.get(task_id).map(|task| Task {
+            id: task.id.clone(),
+            context_id: task.context_id.clone(),
+            kind: task.kind.clone(),
+            status: task.status.clone(),
+            artifacts: task.artifacts.clone(),
+            history: None,
+            state_transitions: task.state_transitions.clone(),
+            metadata: task.metadata.clone(),
+        }))
+    }
+
+    /// Clones only the messages of the page
+    async fn get_history(&self, task_id: &str, offset: usize, limit: usize) -> Result<Option<HistoryPage>, A2AError> {
+        let tasks = self.tasks.read().await;
+        Ok(tasks
+            .get(task_id)
+            .map(|task| HistoryPage::from_history(task.history.as_deref().unwrap_or_default(), offset, limit)))
+    }
     
+    /// Moves the task to the deleted tasks, keeping its labels
     async fn delete(&self, task_id: &str) -> Result<(), A2AError> {
+        self.delete_many(&[task_id.to_string()]).await
+    }
+
+    async fn restore(&self, task_id: &str) -> Result<(), A2AError> {
+        let mut tasks = self.tasks.write().await;
+        let deleted = self.deleted.write().await.remove(task_id).ok_or_else(|| A2AError::task_not_found(task_id))?;
+        tasks.insert(task_id.to_string(), deleted.task);
+        Ok(())
+    }
+
+    async fn purge(&self, task_id: &str) -> Result<(), A2AError> {
         let mut tasks = self.tasks.write().await;
         tasks.remove(task_id);
+        self.deleted.write().await.remove(task_id);
+        self.labels.write().await.remove(task_id);
+        Ok(())
+    }
+
+    async fn purge_deleted(&self, older_than: DateTime<Utc>) -> Result<u64, A2AError> {
+        let mut deleted = self.deleted.write().await;
+        let expired: Vec<String> = deleted
+            .values()
+            .filter(|task| task.deleted_at < older_than)
+            .map(|task| task.task.id.clone())
+            .collect();
+        let mut labels = self.labels.write().await;
+        for task_id in &expired {
+            deleted.remove(task_id);
+            labels.remove(task_id);
+        }
+        Ok(expired.len() as u64)
+    }
+
+    async fn list_deleted(&self) -> Result<Vec<DeletedTask>, A2AError> {
+        Ok(self.deleted.read().await.values().cloned().collect())
+    }
+
+    async fn erase(&self, task_ids: &[String]) -> Result<usize, A2AError> {
+        let mut tasks = self.tasks.write().await;
+        let mut deleted = self.deleted.write().await;
+        let mut labels = self.labels.write().await;
+        let erased_at = Utc::now();
+        let mut erased = 0;
+        for task_id in task_ids {
+            let task = match tasks.get_mut(task_id) {
+                Some(task) => task,
+                None => match deleted.get_mut(task_id) {
+                    Some(deleted) => &mut deleted.task,
+                    None => continue,
+                },
+            };
+            *task = erased_task(task, erased_at);
+            labels.remove(task_id);
+            erased += 1;
+        }
+        Ok(erased)
+    }
+
+    /// Holds the task map exclusively until the transaction ends
+    async fn begin(&self) -> Result<Box<dyn TaskStoreTransaction>, A2AError> {
+        Ok(Box::new(InMemoryTransaction {
+            tasks: self.tasks.clone().write_owned().await,
+            deleted: self.deleted.clone(),
+            writes: std::collections::HashMap::new(),
+        }))
+    }
+
+    async fn save_many(&self, tasks: Vec<Task>) -> Result<(), A2AError> {
+        let mut stored = self.tasks.write().await;
+        let deleted = self.deleted.read().await;
+        if let Some(task) = tasks.iter().find(|task| deleted.contains_key(&task.id)) {
+            return Err(A2AError::task_not_found(&task.id));
+        }
+        for task in tasks {
+            stored.insert(task.id.clone(), task);
+        }
+        Ok(())
+    }
+
+    async fn get_many(&self, task_ids: &[String]) -> Result<Vec<Task>, A2AError> {
+        let tasks = self.tasks.read().await;
+        Ok(task_ids.iter().filter_map(|id| tasks.get(id).cloned()).collect())
+    }
+
+    async fn delete_many(&self, task_ids: &[String]) -> Result<(), A2AError> {
+        let mut tasks = self.tasks.write().await;
+        let mut deleted = self.deleted.write().await;
+        let deleted_at = Utc::now();
+        for task_id in task_ids {
+            if let Some(task) = tasks.remove(task_id) {
+                deleted.insert(task_id.clone(), DeletedTask { task, deleted_at });
+            }
+        }
         Ok(())
     }
     
@@ -100,6 +511,117 @@ impl TaskStore for InMemoryTaskStore {
             .collect();
         Ok(filtered_tasks)
     }
+
+    async fn set_labels(&self, task_id: &str, labels: Labels) -> Result<(), A2AError> {
+        validate_labels(&labels)?;
+        if !self.tasks.read().await.contains_key(task_id) {
+            return Err(A2AError::task_not_found(task_id));
+        }
+        self.labels.write().await.insert(task_id.to_string(), labels);
+        Ok(())
+    }
+
+    /// Hides the labels of deleted tasks
+    async fn get_labels(&self, task_id: &str) -> Result<Labels, A2AError> {
+        if !self.tasks.read().await.contains_key(task_id) {
+            return Ok(Labels::new());
+        }
+        let labels = self.labels.read().await;
+        Ok(labels.get(task_id).cloned().unwrap_or_default())
+    }
+
+    async fn list_by_labels(&self, selector: &LabelSelector) -> Result<Vec<Task>, A2AError> {
+        let tasks = self.tasks.read().await;
+        let labels = self.labels.read().await;
+        let empty = Labels::new();
+        Ok(tasks
+            .values()
+            .filter(|task| selector.matches(labels.get(&task.id).unwrap_or(&empty)))
+            .cloned()
+            .collect())
+    }
+
+    /// Scans the history of every task
+    async fn search_tasks(&self, query: &str, filter: &TaskSearchFilter) -> Result<Vec<Task>, A2AError> {
+        let terms = search_terms(query);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let tasks = self.tasks.read().await;
+        Ok(filter.apply(
+            tasks
+                .values()
+                .filter(|task| filter.matches(task) && task_matches(task, &terms))
+                .cloned(),
+        ))
+    }
+
+    async fn record_usage(&self, task_id: &str, principal: &str, usage: Usage) -> Result<UsageRecord, A2AError> {
+        let mut records = self.usage.write().await;
+        if !records.contains_key(task_id) {
+            let task = self.get(task_id).await?.ok_or_else(|| A2AError::task_not_found(task_id))?;
+            records.insert(
+                task_id.to_string(),
+                UsageRecord {
+                    task_id: task.id,
+                    context_id: task.context_id,
+                    principal: principal.to_string(),
+                    usage: Usage::new(),
+                },
+            );
+        }
+        let record = records.get_mut(task_id).expect("usage record was just inserted");
+        record.usage += usage;
+        Ok(record.clone())
+    }
+
+    async fn get_usage(&self, task_id: &str) -> Result<Option<UsageRecord>, A2AError> {
+        Ok(self.usage.read().await.get(task_id).cloned())
+    }
+
+    async fn list_usage(&self) -> Result<Vec<UsageRecord>, A2AError> {
+        Ok(self.usage.read().await.values().cloned().collect())
+    }
+
+    async fn context_usage(&self, context_id: &str) -> Result<Usage, A2AError> {
+        let records = self.usage.read().await;
+        Ok(records.values().filter(|record| record.context_id == context_id).map(|record| record.usage).sum())
+    }
+
+    async fn principal_usage(&self, principal: &str) -> Result<Usage, A2AError> {
+        let records = self.usage.read().await;
+        Ok(records.values().filter(|record| record.principal == principal).map(|record| record.usage).sum())
+    }
+}
+
+/// Transaction of an `InMemoryTaskStore`
+struct InMemoryTransaction {
+    tasks: tokio::sync::OwnedRwLockWriteGuard<std::collections::HashMap<String, Task>>,
+    deleted: std::sync::Arc<tokio::sync::RwLock<std::collections::HashMap<String, DeletedTask>>>,
+    writes: std::collections::HashMap<String, Task>,
+}
+
+#[async_trait]
+impl TaskStoreTransaction for InMemoryTransaction {
+    async fn get(&mut self, task_id: &str) -> Result<Option<Task>, A2AError> {
+        Ok(self.writes.get(task_id).or_else(|| self.tasks.get(task_id)).cloned())
+    }
+
+    /// Deletions hold the task map too, so no task is deleted while the
+    /// transaction is open
+    async fn save(&mut self, task: Task) -> Result<(), A2AError> {
+        if self.deleted.read().await.contains_key(&task.id) {
+            return Err(A2AError::task_not_found(&task.id));
+        }
+        self.writes.insert(task.id.clone(), task);
+        Ok(())
+    }
+
+    async fn commit(mut self: Box<Self>) -> Result<(), A2AError> {
+        let writes = std::mem::take(&mut self.writes);
+        self.tasks.extend(writes);
+        Ok(())
+    }
 }
 
 /// Database implementation of TaskStore (placeholder for future implementation)
@@ -153,6 +675,7 @@ mod tests {
             },
             artifacts: None,
             history: None,
+            state_transitions: None,
             metadata: None,
             kind: "task".to_string(),
         }
@@ -176,6 +699,25 @@ mod tests {
         assert!(not_found.is_none());
     }
     
+    #[tokio::test]
+    async fn test_in_memory_task_store_history_pages() {
+        let store = InMemoryTaskStore::new();
+        let history: Vec<Message> = (0..4)
+            .map(|i| Message::new(crate::Role::Agent, vec![crate::Part::text(i.to_string())]))
+            .collect();
+        let task = create_test_task("task-1", "ctx-1").with_history(history.clone());
+        store.save(task).await.unwrap();
+
+        let meta = store.get_meta("task-1", ReadConsistency::Strong).await.unwrap().unwrap();
+        assert!(meta.history.is_none());
+        assert_eq!(meta.status.state, TaskState::Submitted);
+
+        let page = store.get_history("task-1", 1, 2).await.unwrap().unwrap();
+        assert_eq!(page, HistoryPage { messages: history[1..3].to_vec(), total: 4 });
+        assert!(store.get_history("task-1", 9, 2).await.unwrap().unwrap().messages.is_empty());
+        assert!(store.get_meta("missing", ReadConsistency::Strong).await.unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn test_in_memory_task_store_update() {
         let store = InMemoryTaskStore::new();
@@ -230,4 +772,146 @@ mod tests {
         let context2_tasks = store.list_by_context("550e8400-e29b-41d4-a716-446655440002").await.unwrap();
         assert_eq!(context2_tasks.len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_in_memory_task_store_transactions() {
+        let store = InMemoryTaskStore::new();
+
+        let mut tx = store.begin().await.unwrap();
+        tx.save(create_test_task("task-1", "ctx")).await.unwrap();
+        assert!(tx.get("task-1").await.unwrap().is_some());
+        drop(tx);
+        assert!(store.get("task-1").await.unwrap().is_none());
+
+        let mut tx = store.begin().await.unwrap();
+        tx.save(create_test_task("task-1", "ctx")).await.unwrap();
+        tx.save(create_test_task("task-2", "ctx")).await.unwrap();
+        tx.commit().await.unwrap();
+        assert_eq!(store.list().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_task_store_bulk_operations() {
+        let store = InMemoryTaskStore::new();
+        let tasks: Vec<Task> = ["task-1", "task-2", "task-3"].iter().map(|id| create_test_task(id, "ctx")).collect();
+        store.save_many(tasks).await.unwrap();
+
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let found = store.get_many(&ids(&["task-3", "missing", "task-1"])).await.unwrap();
+        assert_eq!(found.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), vec!["task-3", "task-1"]);
+
+        store.delete_many(&ids(&["task-1", "task-2"])).await.unwrap();
+        assert_eq!(store.list().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_task_store_usage() {
+        let store = InMemoryTaskStore::new();
+        store.save(create_test_task("task-1", "ctx")).await.unwrap();
+
+        store.record_usage("task-1", "alice", Usage::tokens(10, 4)).await.unwrap();
+        let record = store.record_usage("task-1", "bob", Usage::tokens(1, 1).with_compute_units(2.0)).await.unwrap();
+        assert_eq!(record.principal, "alice");
+        assert_eq!(record.context_id, "ctx");
+        assert_eq!(record.usage, Usage::tokens(11, 5).with_compute_units(2.0));
+        assert!(store.record_usage("missing", "alice", Usage::tokens(1, 0)).await.is_err());
+
+        // Usage outlives the task it was reported for
+        store.delete("task-1").await.unwrap();
+        assert_eq!(store.get_usage("task-1").await.unwrap(), Some(record));
+        assert_eq!(store.list_usage().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_task_store_labels() {
+        let store = InMemoryTaskStore::new();
+        store.save(create_test_task("task-1", "ctx")).await.unwrap();
+        store.save(create_test_task("task-2", "ctx")).await.unwrap();
+        store.save(create_test_task("task-3", "ctx")).await.unwrap();
+
+        let labels = |pairs: &[(&str, &str)]| -> Labels {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+        store.set_labels("task-1", labels(&[("customer", "acme"), ("env", "prod")])).await.unwrap();
+        store.set_labels("task-2", labels(&[("customer", "acme"), ("env", "staging")])).await.unwrap();
+        assert!(store.set_labels("missing", Labels::new()).await.is_err());
+        assert!(store.set_labels("task-3", labels(&[("bad key", "x")])).await.is_err());
+
+        assert_eq!(store.get_labels("task-1").await.unwrap()["env"], "prod");
+        assert!(store.get_labels("task-3").await.unwrap().is_empty());
+
+        let acme = store.list_by_labels(&LabelSelector::parse("customer=acme").unwrap()).await.unwrap();
+        assert_eq!(acme.len(), 2);
+
+        let prod = store.list_by_labels(&LabelSelector::parse("customer=acme,env=prod").unwrap()).await.unwrap();
+        assert_eq!(prod.len(), 1);
+        assert_eq!(prod[0].id, "task-1");
+
+        let unlabeled = store.list_by_labels(&LabelSelector::parse("!customer").unwrap()).await.unwrap();
+        assert_eq!(unlabeled.len(), 1);
+        assert_eq!(unlabeled[0].id, "task-3");
+
+        store.delete("task-1").await.unwrap();
+        assert!(store.get_labels("task-1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_task_store_search() {
+        let store = InMemoryTaskStore::new();
+        let said = |text: &str| vec![Message::new(crate::Role::User, vec![crate::Part::text(text.to_string())])];
+        store.save(create_test_task("task-1", "ctx-1").with_history(said("My invoice is wrong"))).await.unwrap();
+        store.save(create_test_task("task-2", "ctx-2").with_history(said("Wrong invoice again!"))).await.unwrap();
+        store.save(create_test_task("task-3", "ctx-2").with_history(said("All good"))).await.unwrap();
+
+        let mut found: Vec<String> = store
+            .search_tasks("invoice WRONG", &TaskSearchFilter::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|task| task.id)
+            .collect();
+        found.sort();
+        assert_eq!(found, vec!["task-1", "task-2"]);
+
+        let ctx_2 = TaskSearchFilter::default().with_context_id("ctx-2");
+        let found = store.search_tasks("invoice", &ctx_2).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "task-2");
+        assert!(store.search_tasks("invoices", &TaskSearchFilter::default()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_task_store_soft_delete() {
+        let store = InMemoryTaskStore::new();
+        store.save(create_test_task("task-1", "ctx-1")).await.unwrap();
+        store.save(create_test_task("task-2", "ctx-1")).await.unwrap();
+        store.set_labels("task-1", Labels::from([("team".to_string(), "billing".to_string())])).await.unwrap();
+
+        store.delete("task-1").await.unwrap();
+        assert!(store.get("task-1").await.unwrap().is_none());
+        assert_eq!(store.list_by_context("ctx-1").await.unwrap().len(), 1);
+        assert!(store.get_labels("task-1").await.unwrap().is_empty());
+        let deleted = store.list_deleted().await.unwrap();
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].task.id, "task-1");
+
+        // Saves cannot bring a deleted task back; restoring does, with its labels
+        let err = store.save(create_test_task("task-1", "ctx-1")).await.unwrap_err();
+        assert!(matches!(err, A2AError::TaskNotFound(_)));
+        assert!(store.save_many(vec![create_test_task("task-1", "ctx-1")]).await.is_err());
+        let mut tx = store.begin().await.unwrap();
+        assert!(tx.save(create_test_task("task-1", "ctx-1")).await.is_err());
+        drop(tx);
+        store.restore("task-1").await.unwrap();
+        assert!(store.get("task-1").await.unwrap().is_some());
+        assert_eq!(store.get_labels("task-1").await.unwrap().len(), 1);
+        assert!(store.restore("task-1").await.is_err());
+
+        store.delete_many(&["task-1".to_string(), "task-2".to_string()]).await.unwrap();
+        assert_eq!(store.purge_deleted(Utc::now() - chrono::Duration::hours(1)).await.unwrap(), 0);
+        store.purge("task-2").await.unwrap();
+        assert_eq!(store.purge_deleted(Utc::now() + chrono::Duration::seconds(1)).await.unwrap(), 1);
+        assert!(store.list_deleted().await.unwrap().is_empty());
+        assert!(store.restore("task-1").await.is_err());
+    }
 }
@@ -0,0 +1,111 @@
+//! Resource usage reported for tasks
+//!
+//! Executors report the tokens and compute units a task consumed through a
+//! `UsageRecorder`; the task store keeps running totals per task next to the
+//! task itself, together with the task's context and the principal it is
+//! billed to. Like labels, usage is not part of the A2A wire format, but it
+//! is surfaced in task metadata under `USAGE_METADATA_KEY`. Totals outlive
+//! the task they belong to, so purging finished tasks does not lose what
+//! they cost.
+
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, AddAssign};
+
+/// Task metadata key carrying the usage totals of the task
+pub const USAGE_METADATA_KEY: &str = "usage";
+
+/// Resources consumed by an agent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Usage {
+    /// Tokens read by models, such as the prompt
+    #[serde(default)]
+    pub input_tokens: u64,
+    /// Tokens generated by models
+    #[serde(default)]
+    pub output_tokens: u64,
+    /// Deployment-defined compute units, such as GPU seconds
+    #[serde(default)]
+    pub compute_units: f64,
+}
+
+impl Usage {
+    /// Creates empty usage
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Usage of model tokens
+    pub fn tokens(input_tokens: u64, output_tokens: u64) -> Self {
+        Self {
+            input_tokens,
+            output_tokens,
+            compute_units: 0.0,
+        }
+    }
+
+    /// Sets the compute units consumed
+    pub fn with_compute_units(mut self, compute_units: f64) -> Self {
+        self.compute_units = compute_units;
+        self
+    }
+
+    /// Input and output tokens together
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+}
+
+impl AddAssign for Usage {
+    fn add_assign(&mut self, other: Usage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.compute_units += other.compute_units;
+    }
+}
+
+impl Add for Usage {
+    type Output = Usage;
+
+    fn add(mut self, other: Usage) -> Usage {
+        self += other;
+        self
+    }
+}
+
+impl std::iter::Sum for Usage {
+    fn sum<I: Iterator<Item = Usage>>(iter: I) -> Usage {
+        iter.fold(Usage::new(), Add::add)
+    }
+}
+
+/// The usage totals of a task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    /// The task the usage was reported for
+    #[serde(rename = "task_id")]
+    pub task_id: String,
+    /// The context of the task
+    #[serde(rename = "context_id")]
+    pub context_id: String,
+    /// The principal the task is billed to
+    pub principal: String,
+    /// Everything reported for the task so far
+    pub usage: Usage,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_adds_up() {
+        let total: Usage = [Usage::tokens(10, 5), Usage::tokens(1, 2).with_compute_units(0.5), Usage::new()]
+            .into_iter()
+            .sum();
+        assert_eq!(total, Usage::tokens(11, 7).with_compute_units(0.5));
+        assert_eq!(total.total_tokens(), 18);
+
+        let usage: Usage = serde_json::from_value(serde_json::json!({"output_tokens": 3})).unwrap();
+        assert_eq!(usage, Usage::tokens(0, 3));
+    }
+}
@@ -0,0 +1,433 @@
+//! Resumable uploads of client files
+//!
+//! Sending a large file inline in `message/send` means one huge JSON request
+//! that must be repeated from the start when the connection drops. Instead a
+//! client creates an upload with its total length, sends the bytes in chunks
+//! at increasing offsets, resuming from the offset the server reports, and
+//! then references the finished upload by URI in a `FilePart`.
+//!
+//! `UploadManager` keeps the upload bytes in an `ArtifactStore`, each upload
+//! under its own id, and the upload states in memory. Uploads belong to the
+//! user who created them. The manager is also a `MessageValidator`: message
+//! file parts referencing an upload must name a finished upload of the
+//! caller, and mark it referenced. Uploads no message referenced are deleted
+//! by `collect_garbage` once they have been idle for the TTL.
+
+use crate::a2a::core_types::{FileContent, Message, PartRoot};
+use crate::a2a::error::A2AError;
+use crate::a2a::server::artifacts::{ArtifactStore, StoredArtifact};
+use crate::a2a::server::context::ServerCallContext;
+use crate::a2a::server::message_validation::{MessageValidator, MessageViolation};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// Name the bytes of an upload are stored under, within the upload id
+pub(crate) const UPLOAD_CONTENT: &str = "content";
+
+/// Total length of an upload, sent when creating it
+pub const UPLOAD_LENGTH_HEADER: &str = "upload-length";
+/// Offset of a chunk, or the bytes received so far in responses
+pub const UPLOAD_OFFSET_HEADER: &str = "upload-offset";
+/// MIME type of the uploaded file, sent when creating it
+pub const UPLOAD_CONTENT_TYPE_HEADER: &str = "upload-content-type";
+
+/// Default time an unreferenced upload is kept after its last chunk
+pub const DEFAULT_UPLOAD_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Default limit on the total length of an upload
+pub const DEFAULT_MAX_UPLOAD_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Errors raised by upload operations
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum UploadError {
+    #[error("Upload '{0}' not found")]
+    NotFound(String),
+
+    #[error("Upload offset {offset} does not match the {expected} bytes received")]
+    OffsetMismatch { offset: u64, expected: u64 },
+
+    #[error("Upload of {length} bytes exceeds the limit of {limit}")]
+    TooLarge { length: u64, limit: u64 },
+
+    #[error("Upload '{id}' has {offset} of {length} bytes")]
+    Incomplete { id: String, offset: u64, length: u64 },
+
+    #[error("Upload store error: {0}")]
+    Store(String),
+}
+
+impl From<A2AError> for UploadError {
+    fn from(error: A2AError) -> Self {
+        UploadError::Store(error.to_string())
+    }
+}
+
+/// Progress of an upload, as reported to its client
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Upload {
+    /// Id of the upload
+    #[serde(rename = "upload_id")]
+    pub id: String,
+    /// URI to reference the finished upload by in a `FilePart`
+    pub uri: String,
+    /// Number of bytes received so far, where the next chunk starts
+    pub offset: u64,
+    /// Total length of the upload
+    pub length: u64,
+    /// MIME type of the uploaded file, if given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+impl Upload {
+    /// Whether every byte of the upload has been received
+    pub fn is_complete(&self) -> bool {
+        self.offset == self.length
+    }
+}
+
+#[derive(Debug, Clone)]
+struct UploadState {
+    owner: String,
+    offset: u64,
+    length: u64,
+    mime_type: Option<String>,
+    last_activity: DateTime<Utc>,
+    referenced: bool,
+}
+
+/// Resumable uploads served under a base URL
+///
+/// Cloning is cheap; clones share the uploads.
+#[derive(Clone)]
+pub struct UploadManager {
+    store: Arc<dyn ArtifactStore>,
+    base_url: url::Url,
+    ttl: Duration,
+    max_upload_bytes: u64,
+    states: Arc<Mutex<HashMap<String, UploadState>>>,
+}
+
+impl UploadManager {
+    /// Keeps upload bytes in `store` and serves the uploads under `base_url`,
+    /// such as `https://agent.example.com/uploads`
+    pub fn new(store: Arc<dyn ArtifactStore>, base_url: url::Url) -> Self {
+        Self {
+            store,
+            base_url,
+            ttl: DEFAULT_UPLOAD_TTL,
+            max_upload_bytes: DEFAULT_MAX_UPLOAD_BYTES,
+            states: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Sets how long unreferenced uploads are kept after their last chunk
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Sets the limit on the total length of an upload
+    pub fn with_max_upload_bytes(mut self, max_upload_bytes: u64) -> Self {
+        self.max_upload_bytes = max_upload_bytes;
+        self
+    }
+
+    /// The URL uploads are served under
+    pub fn base_url(&self) -> &url::Url {
+        &self.base_url
+    }
+
+    /// The URI of an upload
+    pub fn upload_uri(&self, upload_id: &str) -> String {
+        let mut url = self.base_url.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.pop_if_empty().push(upload_id);
+        }
+        url.to_string()
+    }
+
+    /// The id of the upload `uri` refers to, if it is an upload URI
+    pub fn upload_id(&self, uri: &str) -> Option<String> {
+        let url = url::Url::parse(uri).ok()?;
+        let base = &self.base_url;
+        if url.origin() != base.origin() {
+            return None;
+        }
+        let prefix = base.path().trim_end_matches('/');
+        let id = url.path().strip_prefix(prefix)?.strip_prefix('/')?;
+        if id.is_empty() || id.contains('/') {
+            return None;
+        }
+        Some(id.to_string())
+    }
+
+    /// Starts an upload of `length` bytes owned by `owner`
+    pub async fn create(&self, owner: &str, length: u64, mime_type: Option<String>) -> Result<Upload, UploadError> {
+        if length > self.max_upload_bytes {
+            return Err(UploadError::TooLarge {
+                length,
+                limit: self.max_upload_bytes,
+            });
+        }
+        let id = Uuid::new_v4().to_string();
+        self.store.put(&id, UPLOAD_CONTENT, Vec::new(), mime_type.clone()).await?;
+        let state = UploadState {
+            owner: owner.to_string(),
+            offset: 0,
+            length,
+            mime_type,
+            last_activity: Utc::now(),
+            referenced: false,
+        };
+        let upload = self.upload(&id, &state);
+        self.states.lock().await.insert(id, state);
+        Ok(upload)
+    }
+
+    /// Appends a chunk starting at `offset`, which must be the number of
+    /// bytes received so far
+    pub async fn append(&self, owner: &str, upload_id: &str, offset: u64, bytes: Vec<u8>) -> Result<Upload, UploadError> {
+        let mut states = self.states.lock().await;
+        let state = owned_state(&mut states, owner, upload_id)?;
+        if offset != state.offset {
+            return Err(UploadError::OffsetMismatch {
+                offset,
+                expected: state.offset,
+            });
+        }
+        let end = offset + bytes.len() as u64;
+        if end > state.length {
+            return Err(UploadError::TooLarge {
+                length: end,
+                limit: state.length,
+            });
+        }
+        self.store.append(upload_id, UPLOAD_CONTENT, bytes).await?;
+        state.offset = end;
+        state.last_activity = Utc::now();
+        Ok(self.upload(upload_id, state))
+    }
+
+    /// The progress of an upload of `owner`
+    pub async fn status(&self, owner: &str, upload_id: &str) -> Result<Upload, UploadError> {
+        let mut states = self.states.lock().await;
+        let state = owned_state(&mut states, owner, upload_id)?;
+        Ok(self.upload(upload_id, state))
+    }
+
+    /// Deletes an upload of `owner`
+    pub async fn delete(&self, owner: &str, upload_id: &str) -> Result<(), UploadError> {
+        let mut states = self.states.lock().await;
+        owned_state(&mut states, owner, upload_id)?;
+        states.remove(upload_id);
+        self.store.delete_task(upload_id).await?;
+        Ok(())
+    }
+
+    /// Marks a finished upload of `owner` as referenced by a message, so it
+    /// is not collected as garbage
+    pub async fn mark_referenced(&self, owner: &str, upload_id: &str) -> Result<Upload, UploadError> {
+        let mut states = self.states.lock().await;
+        let state = owned_state(&mut states, owner, upload_id)?;
+        let upload = self.upload(upload_id, state);
+        if !upload.is_complete() {
+            return Err(UploadError::Incomplete {
+                id: upload.id,
+                offset: upload.offset,
+                length: upload.length,
+            });
+        }
+        state.referenced = true;
+        Ok(upload)
+    }
+
+    /// Streams the bytes of a finished upload, for agents reading a
+    /// referenced file
+    pub async fn read(&self, upload_id: &str) -> Result<BoxStream<'static, Result<Vec<u8>, A2AError>>, UploadError> {
+        let length = {
+            let states = self.states.lock().await;
+            let state = states
+                .get(upload_id)
+                .ok_or_else(|| UploadError::NotFound(upload_id.to_string()))?;
+            if state.offset != state.length {
+                return Err(UploadError::Incomplete {
+                    id: upload_id.to_string(),
+                    offset: state.offset,
+                    length: state.length,
+                });
+            }
+            state.length
+        };
+        Ok(self.store.read(upload_id, UPLOAD_CONTENT, 0..length).await?)
+    }
+
+    /// Deletes unreferenced uploads idle for longer than the TTL, returning
+    /// their ids
+    pub async fn collect_garbage(&self) -> Result<Vec<String>, UploadError> {
+        let ttl = chrono::Duration::from_std(self.ttl).unwrap_or(chrono::Duration::MAX);
+        let cutoff = Utc::now() - ttl;
+        let expired: Vec<String> = {
+            let mut states = self.states.lock().await;
+            let expired: Vec<String> = states
+                .iter()
+                .filter(|(_, state)| !state.referenced && state.last_activity <= cutoff)
+                .map(|(id, _)| id.clone())
+                .collect();
+            for id in &expired {
+                states.remove(id);
+            }
+            expired
+        };
+        for id in &expired {
+            debug!("Collecting unreferenced upload {}", id);
+            self.store.delete_task(id).await?;
+        }
+        Ok(expired)
+    }
+
+    /// Spawns a Tokio task collecting garbage every `interval` until it is aborted
+    pub fn spawn_garbage_collector(&self, interval: Duration) -> JoinHandle<()> {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if let Err(e) = manager.collect_garbage().await {
+                    warn!("Upload garbage collection failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// The stored bytes of a finished upload of `owner`, to serve it
+    pub(crate) async fn stored(&self, owner: &str, upload_id: &str) -> Result<StoredArtifact, UploadError> {
+        let upload = self.status(owner, upload_id).await?;
+        if !upload.is_complete() {
+            return Err(UploadError::Incomplete {
+                id: upload.id,
+                offset: upload.offset,
+                length: upload.length,
+            });
+        }
+        Ok(StoredArtifact {
+            size: upload.length,
+            mime_type: upload.mime_type,
+        })
+    }
+
+    pub(crate) fn store(&self) -> &Arc<dyn ArtifactStore> {
+        &self.store
+    }
+
+    fn upload(&self, upload_id: &str, state: &UploadState) -> Upload {
+        Upload {
+            id: upload_id.to_string(),
+            uri: self.upload_uri(upload_id),
+            offset: state.offset,
+            length: state.length,
+            mime_type: state.mime_type.clone(),
+        }
+    }
+}
+
+/// The state of an upload, hiding uploads of other owners
+fn owned_state<'a>(
+    states: &'a mut HashMap<String, UploadState>,
+    owner: &str,
+    upload_id: &str,
+) -> Result<&'a mut UploadState, UploadError> {
+    match states.get_mut(upload_id) {
+        Some(state) if state.owner == owner => Ok(state),
+        _ => Err(UploadError::NotFound(upload_id.to_string())),
+    }
+}
+
+#[async_trait]
+impl MessageValidator for UploadManager {
+    async fn validate(&self, message: &Message, context: Option<&ServerCallContext>) -> Result<(), MessageViolation> {
+        let owner = context.map(|context| context.user.username()).unwrap_or("");
+        for (index, part) in message.parts.iter().enumerate() {
+            let PartRoot::File(file_part) = part.root() else {
+                continue;
+            };
+            let FileContent::Uri(file) = &file_part.file else {
+                continue;
+            };
+            let Some(upload_id) = self.upload_id(&file.uri) else {
+                continue;
+            };
+            if let Err(e) = self.mark_referenced(owner, &upload_id).await {
+                return Err(MessageViolation::new("upload_reference", format!("Part {}: {}", index, e))
+                    .with_details(serde_json::json!({ "part_index": index, "upload_id": upload_id })));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::{Part, Role};
+    use crate::a2a::server::artifacts::InMemoryArtifactStore;
+    use futures::StreamExt;
+
+    fn manager() -> UploadManager {
+        UploadManager::new(
+            Arc::new(InMemoryArtifactStore::new()),
+            url::Url::parse("https://agent.example.com/uploads").unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_chunks_resume_at_the_received_offset() {
+        let uploads = manager();
+        let upload = uploads.create("alice", 6, Some("text/csv".to_string())).await.unwrap();
+        assert_eq!(upload.uri, format!("https://agent.example.com/uploads/{}", upload.id));
+        assert_eq!(uploads.upload_id(&upload.uri), Some(upload.id.clone()));
+
+        uploads.append("alice", &upload.id, 0, b"abc".to_vec()).await.unwrap();
+        let error = uploads.append("alice", &upload.id, 0, b"abc".to_vec()).await.unwrap_err();
+        assert_eq!(error, UploadError::OffsetMismatch { offset: 0, expected: 3 });
+        let error = uploads.append("alice", &upload.id, 3, b"defg".to_vec()).await.unwrap_err();
+        assert!(matches!(error, UploadError::TooLarge { .. }));
+        assert!(matches!(uploads.status("bob", &upload.id).await, Err(UploadError::NotFound(_))));
+
+        let done = uploads.append("alice", &upload.id, 3, b"def".to_vec()).await.unwrap();
+        assert!(done.is_complete());
+        let chunks: Vec<_> = uploads.read(&upload.id).await.unwrap().collect().await;
+        let bytes: Vec<u8> = chunks.into_iter().flat_map(Result::unwrap).collect();
+        assert_eq!(bytes, b"abcdef");
+    }
+
+    #[tokio::test]
+    async fn test_references_protect_uploads_from_garbage_collection() {
+        let uploads = manager().with_ttl(Duration::ZERO);
+        let referenced = uploads.create("alice", 1, None).await.unwrap();
+        let unreferenced = uploads.create("alice", 1, None).await.unwrap();
+        let message = |uri: &str| {
+            Message::new(Role::User, vec![Part::file_uri(url::Url::parse(uri).unwrap())])
+        };
+        let context = ServerCallContext::with_user(crate::a2a::auth::user::AuthenticatedUser::new("alice".to_string()));
+
+        let violation = uploads.validate(&message(&referenced.uri), Some(&context)).await.unwrap_err();
+        assert_eq!(violation.rule, "upload_reference");
+        uploads.append("alice", &referenced.id, 0, vec![1]).await.unwrap();
+        assert!(uploads.validate(&message(&referenced.uri), None).await.is_err());
+        uploads.validate(&message(&referenced.uri), Some(&context)).await.unwrap();
+
+        assert_eq!(uploads.collect_garbage().await.unwrap(), vec![unreferenced.id.clone()]);
+        assert!(uploads.status("alice", &referenced.id).await.is_ok());
+        assert!(uploads.status("alice", &unreferenced.id).await.is_err());
+    }
+}
@@ -0,0 +1,280 @@
+//! Usage accounting for agent consumption
+//!
+//! Executors report the tokens and compute units they consume with a
+//! `UsageRecorder`, which adds them to the totals the task store keeps per
+//! task. Each task is billed to the principal of the call that first
+//! reported usage for it. Totals are aggregated per task, per context and
+//! per principal for billing, and `UsageMetadataRequestHandler` exposes the
+//! totals of a task to clients in its `usage` metadata.
+//!
+//! ```ignore
+//! let usage = UsageRecorder::new(task_store.clone());
+//! let executor = MyExecutor::new(usage.clone());
+//! // in MyExecutor::execute, after a model call:
+//! self.usage.record(&context, Usage::tokens(prompt_tokens, completion_tokens)).await?;
+//! ```
+
+use crate::a2a::error::A2AError;
+use crate::a2a::models::*;
+use crate::a2a::server::agent_execution::RequestContext;
+use crate::a2a::server::context::ServerCallContext;
+use crate::a2a::server::quota::{tenant_for, ANONYMOUS_TENANT};
+use crate::a2a::server::request_handlers::request_handler::{
+    Event, MessageSendResult, RequestHandler, StreamEvent, TaskPushNotificationConfigQueryParams,
+};
+use crate::a2a::server::tasks::{TaskStore, Usage, UsageRecord, USAGE_METADATA_KEY};
+use crate::a2a::utils::metadata::HasMetadata;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Records the usage executors report, and answers usage queries
+#[derive(Clone)]
+pub struct UsageRecorder {
+    store: Arc<dyn TaskStore>,
+}
+
+impl UsageRecorder {
+    /// Records usage in the task store holding the tasks
+    pub fn new(store: Arc<dyn TaskStore>) -> Self {
+        Self { store }
+    }
+
+    /// Adds `usage` to the task of `context`, billed to the caller
+    pub async fn record(&self, context: &RequestContext, usage: Usage) -> Result<UsageRecord, A2AError> {
+        let task_id = context
+            .task_id
+            .as_deref()
+            .ok_or_else(|| A2AError::invalid_params("Usage can only be recorded for a task"))?;
+        let principal = context
+            .call_context
+            .as_ref()
+            .map(tenant_for)
+            .unwrap_or_else(|| ANONYMOUS_TENANT.to_string());
+        self.record_task(task_id, &principal, usage).await
+    }
+
+    /// Adds `usage` to a task, billing it to `principal` on the first report
+    pub async fn record_task(&self, task_id: &str, principal: &str, usage: Usage) -> Result<UsageRecord, A2AError> {
+        self.store.record_usage(task_id, principal, usage).await
+    }
+
+    /// The usage totals of every task
+    pub async fn records(&self) -> Result<Vec<UsageRecord>, A2AError> {
+        self.store.list_usage().await
+    }
+
+    /// The store the totals are kept in
+    pub(crate) fn task_store(&self) -> &Arc<dyn TaskStore> {
+        &self.store
+    }
+
+    /// The usage totals of a task
+    pub async fn task_usage(&self, task_id: &str) -> Result<Option<UsageRecord>, A2AError> {
+        self.store.get_usage(task_id).await
+    }
+
+    /// The usage of all tasks of a context
+    pub async fn context_usage(&self, context_id: &str) -> Result<Usage, A2AError> {
+        self.store.context_usage(context_id).await
+    }
+
+    /// The usage of all tasks billed to a principal
+    pub async fn principal_usage(&self, principal: &str) -> Result<Usage, A2AError> {
+        self.store.principal_usage(principal).await
+    }
+
+    /// The usage of all tasks, per principal they are billed to
+    pub async fn usage_by_principal(&self) -> Result<BTreeMap<String, Usage>, A2AError> {
+        let mut totals = BTreeMap::new();
+        for record in self.store.list_usage().await? {
+            *totals.entry(record.principal).or_insert_with(Usage::new) += record.usage;
+        }
+        Ok(totals)
+    }
+
+    /// Sets the `usage` metadata of `task` to its totals, if any were reported
+    pub async fn annotate(&self, task: &mut Task) -> Result<(), A2AError> {
+        if let Some(record) = self.task_usage(&task.id).await? {
+            task.metadata_mut()
+                .insert(USAGE_METADATA_KEY.to_string(), serde_json::to_value(record.usage)?);
+        }
+        Ok(())
+    }
+}
+
+/// Request handler exposing the usage totals of tasks in their metadata
+///
+/// Tasks returned by `tasks/get`, `tasks/cancel` and `message/send` carry
+/// their totals under the `usage` metadata key.
+pub struct UsageMetadataRequestHandler {
+    inner: Arc<dyn RequestHandler>,
+    usage: UsageRecorder,
+}
+
+impl UsageMetadataRequestHandler {
+    /// Wraps `inner`, reading totals from `usage`
+    pub fn new(inner: Arc<dyn RequestHandler>, usage: UsageRecorder) -> Self {
+        Self { inner, usage }
+    }
+
+    async fn annotated(&self, task: Option<Task>) -> Result<Option<Task>, A2AError> {
+        let Some(mut task) = task else {
+            return Ok(None);
+        };
+        self.usage.annotate(&mut task).await?;
+        Ok(Some(task))
+    }
+}
+
+#[async_trait]
+impl RequestHandler for UsageMetadataRequestHandler {
+    async fn on_get_task(
+        &self,
+        params: TaskQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        let task = self.inner.on_get_task(params, context).await?;
+        self.annotated(task).await
+    }
+
+    async fn on_cancel_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        let task = self.inner.on_cancel_task(params, context).await?;
+        self.annotated(task).await
+    }
+
+    async fn on_message_send(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<MessageSendResult, A2AError> {
+        match self.inner.on_message_send(params, context).await? {
+            MessageSendResult::Task(mut task) => {
+                self.usage.annotate(&mut task).await?;
+                Ok(MessageSendResult::Task(task))
+            }
+            message => Ok(message),
+        }
+    }
+
+    async fn on_message_send_stream(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        self.inner.on_message_send_stream(params, context).await
+    }
+
+    async fn on_message_send_stream_resumable(
+        &self,
+        params: MessageSendParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        self.inner.on_message_send_stream_resumable(params, context).await
+    }
+
+    async fn on_set_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfig,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_set_task_push_notification_config(params, context).await
+    }
+
+    async fn on_get_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfigQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_get_task_push_notification_config(params, context).await
+    }
+
+    async fn on_resubscribe_to_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        self.inner.on_resubscribe_to_task(params, context).await
+    }
+
+    async fn on_resubscribe_to_task_resumable(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<StreamEvent, A2AError>>, A2AError> {
+        self.inner.on_resubscribe_to_task_resumable(params, context).await
+    }
+
+    async fn on_list_task_push_notification_config(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Vec<TaskPushNotificationConfig>, A2AError> {
+        self.inner.on_list_task_push_notification_config(params, context).await
+    }
+
+    async fn on_delete_task_push_notification_config(
+        &self,
+        params: DeleteTaskPushNotificationConfigParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<(), A2AError> {
+        self.inner.on_delete_task_push_notification_config(params, context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::{TaskState, TaskStatus};
+    use crate::a2a::server::request_handlers::DefaultRequestHandler;
+    use crate::a2a::server::tasks::InMemoryTaskStore;
+
+    async fn recorder() -> (UsageRecorder, Arc<InMemoryTaskStore>) {
+        let store = Arc::new(InMemoryTaskStore::new());
+        for (task_id, context_id) in [("task-1", "ctx-1"), ("task-2", "ctx-1"), ("task-3", "ctx-2")] {
+            let task = Task::new(context_id.to_string(), TaskStatus::new(TaskState::Completed))
+                .with_task_id(task_id.to_string());
+            store.save(task).await.unwrap();
+        }
+        (UsageRecorder::new(store.clone()), store)
+    }
+
+    #[tokio::test]
+    async fn test_usage_is_aggregated_per_context_and_principal() {
+        let (usage, _) = recorder().await;
+        let mut call_context = ServerCallContext::new();
+        call_context.user = crate::a2a::auth::user::AuthenticatedUser::new("alice".to_string());
+        let context = RequestContext::new(None, Some("task-1".to_string()), None, None, None, Some(call_context), None, None)
+            .await
+            .unwrap();
+
+        usage.record(&context, Usage::tokens(100, 10)).await.unwrap();
+        usage.record_task("task-2", "alice", Usage::tokens(5, 5)).await.unwrap();
+        usage.record_task("task-3", "bob", Usage::new().with_compute_units(3.0)).await.unwrap();
+
+        assert_eq!(usage.context_usage("ctx-1").await.unwrap(), Usage::tokens(105, 15));
+        let by_principal = usage.usage_by_principal().await.unwrap();
+        assert_eq!(by_principal["alice"], Usage::tokens(105, 15));
+        assert_eq!(by_principal["bob"].compute_units, 3.0);
+    }
+
+    #[tokio::test]
+    async fn test_tasks_expose_usage_in_metadata() {
+        let (usage, store) = recorder().await;
+        usage.record_task("task-1", "alice", Usage::tokens(7, 3)).await.unwrap();
+        let handler = UsageMetadataRequestHandler::new(
+            Arc::new(DefaultRequestHandler::new(store, None, None)),
+            usage,
+        );
+
+        let task = handler.on_get_task(TaskQueryParams::new("task-1".to_string()), None).await.unwrap().unwrap();
+        assert_eq!(task.metadata.unwrap()[USAGE_METADATA_KEY]["input_tokens"], 7);
+        let task = handler.on_get_task(TaskQueryParams::new("task-2".to_string()), None).await.unwrap().unwrap();
+        assert!(task.metadata.is_none());
+    }
+}
@@ -0,0 +1,209 @@
+//! SPIFFE workload identities for agent-to-agent mTLS
+//!
+//! In service-mesh style deployments every agent holds an X.509 SVID: a
+//! short-lived certificate whose URI SAN is its SPIFFE ID, e.g.
+//! `spiffe://example.org/ns/prod/sa/planner`, issued under the trust bundle
+//! of its trust domain. SVIDs are loaded from PEM files (as written by
+//! `spiffe-helper`) or fetched from the SPIFFE Workload API.
+//!
+//! The server side ([`server`]) serves HTTPS requiring client SVIDs and
+//! authorizes each JSON-RPC method with a `SpiffePolicy` over the peer's
+//! SPIFFE ID; the client side ([`client`]) builds HTTP clients presenting
+//! the local SVID, for use with the client transports.
+
+pub mod client;
+pub mod server;
+
+pub use client::SpiffeClientTls;
+pub use server::{PeerSpiffeId, SpiffeAcceptor, SpiffePolicy, SpiffeServerTls};
+pub use spiffe::SpiffeId;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Errors raised while loading SVIDs or setting up TLS with them
+#[derive(Error, Debug)]
+pub enum SpiffeError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Invalid SVID: {0}")]
+    InvalidSvid(String),
+
+    #[error("Workload API error: {0}")]
+    WorkloadApi(String),
+
+    #[error("TLS setup failed: {0}")]
+    Tls(String),
+}
+
+/// Where an X.509 SVID and its trust bundle come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SvidSource {
+    /// PEM files with the certificate chain, its PKCS#8 private key and the
+    /// trust bundle
+    Files {
+        cert_path: PathBuf,
+        key_path: PathBuf,
+        bundle_path: PathBuf,
+    },
+    /// The Workload API at `socket`, or at `SPIFFE_ENDPOINT_SOCKET` if None
+    WorkloadApi { socket: Option<String> },
+}
+
+impl SvidSource {
+    /// SVID files written by an agent such as `spiffe-helper`
+    pub fn files(cert_path: impl AsRef<Path>, key_path: impl AsRef<Path>, bundle_path: impl AsRef<Path>) -> Self {
+        SvidSource::Files {
+            cert_path: cert_path.as_ref().to_path_buf(),
+            key_path: key_path.as_ref().to_path_buf(),
+            bundle_path: bundle_path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// The Workload API at `SPIFFE_ENDPOINT_SOCKET`
+    pub fn workload_api() -> Self {
+        SvidSource::WorkloadApi { socket: None }
+    }
+
+    /// Loads the current SVID
+    pub async fn load(&self) -> Result<X509Material, SpiffeError> {
+        match self {
+            SvidSource::Files {
+                cert_path,
+                key_path,
+                bundle_path,
+            } => X509Material::from_pem(
+                &read(cert_path).await?,
+                &read(key_path).await?,
+                &read(bundle_path).await?,
+            ),
+            SvidSource::WorkloadApi { socket } => X509Material::from_workload_api(socket.as_deref()).await,
+        }
+    }
+}
+
+async fn read(path: &Path) -> Result<Vec<u8>, SpiffeError> {
+    tokio::fs::read(path).await.map_err(|source| SpiffeError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// An X.509 SVID with the trust bundle of its trust domain, as DER
+#[derive(Clone)]
+pub struct X509Material {
+    /// The SPIFFE ID of the leaf certificate
+    pub spiffe_id: SpiffeId,
+    /// The certificate chain, leaf first
+    pub cert_chain: Vec<Vec<u8>>,
+    /// The PKCS#8 private key of the leaf
+    pub private_key: Vec<u8>,
+    /// The CA certificates peers are verified against
+    pub bundle: Vec<Vec<u8>>,
+}
+
+impl std::fmt::Debug for X509Material {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("X509Material")
+            .field("spiffe_id", &self.spiffe_id.to_string())
+            .field("cert_chain", &self.cert_chain.len())
+            .field("bundle", &self.bundle.len())
+            .finish()
+    }
+}
+
+impl X509Material {
+    /// Parses the PEM certificate chain, PKCS#8 key and trust bundle
+    pub fn from_pem(cert_chain: &[u8], private_key: &[u8], bundle: &[u8]) -> Result<Self, SpiffeError> {
+        let certs = |pem: &[u8], what: &str| {
+            let certs = rustls_pemfile::certs(&mut &pem[..])
+                .map(|cert| cert.map(|cert| cert.to_vec()))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| SpiffeError::InvalidSvid(format!("{}: {}", what, e)))?;
+            if certs.is_empty() {
+                return Err(SpiffeError::InvalidSvid(format!("no certificates in {}", what)));
+            }
+            Ok(certs)
+        };
+        let cert_chain = certs(cert_chain, "certificate chain")?;
+        let bundle = certs(bundle, "trust bundle")?;
+        let private_key = rustls_pemfile::pkcs8_private_keys(&mut &private_key[..])
+            .next()
+            .ok_or_else(|| SpiffeError::InvalidSvid("no PKCS#8 private key".to_string()))?
+            .map_err(|e| SpiffeError::InvalidSvid(e.to_string()))?
+            .secret_pkcs8_der()
+            .to_vec();
+        let spiffe_id = spiffe_id_of(&cert_chain[0])
+            .ok_or_else(|| SpiffeError::InvalidSvid("leaf certificate has no SPIFFE ID".to_string()))?;
+        Ok(Self {
+            spiffe_id,
+            cert_chain,
+            private_key,
+            bundle,
+        })
+    }
+
+    /// Fetches the default SVID and its trust bundle from the Workload API
+    pub async fn from_workload_api(socket: Option<&str>) -> Result<Self, SpiffeError> {
+        fn error(e: impl std::fmt::Display) -> SpiffeError {
+            SpiffeError::WorkloadApi(e.to_string())
+        }
+        let mut client = match socket {
+            Some(socket) => spiffe::WorkloadApiClient::new_from_path(socket).await,
+            None => spiffe::WorkloadApiClient::default().await,
+        }
+        .map_err(error)?;
+        let context = client.fetch_x509_context().await.map_err(error)?;
+        let svid = context
+            .default_svid()
+            .ok_or_else(|| SpiffeError::WorkloadApi("no SVID issued to this workload".to_string()))?;
+        let bundle = context
+            .bundle_set()
+            .get_bundle(svid.spiffe_id().trust_domain())
+            .ok_or_else(|| SpiffeError::WorkloadApi(format!("no bundle for {}", svid.spiffe_id().trust_domain())))?;
+        Ok(Self {
+            spiffe_id: svid.spiffe_id().clone(),
+            cert_chain: svid.cert_chain().iter().map(|cert| cert.content().to_vec()).collect(),
+            private_key: svid.private_key().content().to_vec(),
+            bundle: bundle.authorities().iter().map(|cert| cert.content().to_vec()).collect(),
+        })
+    }
+
+    /// The certificate chain as PEM
+    pub fn cert_chain_pem(&self) -> String {
+        self.cert_chain.iter().map(|der| to_pem("CERTIFICATE", der)).collect()
+    }
+
+    /// The private key as PKCS#8 PEM
+    pub fn private_key_pem(&self) -> String {
+        to_pem("PRIVATE KEY", &self.private_key)
+    }
+}
+
+fn to_pem(label: &str, der: &[u8]) -> String {
+    let encoded = STANDARD.encode(der);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
+
+/// The SPIFFE ID in the URI SAN of a DER certificate, if any
+pub fn spiffe_id_of(cert_der: &[u8]) -> Option<SpiffeId> {
+    let (_, cert) = x509_parser::parse_x509_certificate(cert_der).ok()?;
+    let san = cert.subject_alternative_name().ok()??;
+    san.value.general_names.iter().find_map(|name| match name {
+        x509_parser::extensions::GeneralName::URI(uri) if uri.starts_with("spiffe://") => SpiffeId::new(uri).ok(),
+        _ => None,
+    })
+}
@@ -0,0 +1,242 @@
+//! mTLS serving with SPIFFE client authentication
+//!
+//! `SpiffeServerTls` makes `A2AServer::serve` require a client SVID issued
+//! under the trust bundle. `SpiffeAcceptor` records the SPIFFE ID of each
+//! connection's client as a `PeerSpiffeId` request extension, which the
+//! JSON-RPC endpoint checks against the `SpiffePolicy` before dispatching.
+//! SVIDs are short-lived, so the server certificate and bundle are reloaded
+//! from their source periodically.
+
+use super::{SpiffeError, SpiffeId, SvidSource, X509Material};
+use crate::a2a::error::{A2AError, JSONRPCError};
+use crate::a2a::jsonrpc::error_codes;
+use axum::Router;
+use axum_server::accept::{Accept, DefaultAcceptor};
+use axum_server::tls_rustls::{RustlsAcceptor, RustlsConfig};
+use futures::future::BoxFuture;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use rustls::server::WebPkiClientVerifier;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tower::Layer;
+
+/// Default interval between SVID reloads
+pub const DEFAULT_SVID_REFRESH: Duration = Duration::from_secs(5 * 60);
+
+/// The SPIFFE ID of the client of a connection, set as a request extension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerSpiffeId(pub Option<SpiffeId>);
+
+/// Which JSON-RPC methods peers may call, by SPIFFE ID
+///
+/// A pattern is either an exact SPIFFE ID or one ending in `/*`, matching
+/// every ID under that path, e.g. `spiffe://example.org/ns/prod/*`. A method
+/// of `*` allows every method. Peers matching no rule are rejected.
+#[derive(Debug, Clone, Default)]
+pub struct SpiffePolicy {
+    rules: Vec<(String, Vec<String>)>,
+}
+
+impl SpiffePolicy {
+    /// Creates a policy rejecting every peer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows peers matching `pattern` to call `methods`
+    pub fn allow(mut self, pattern: &str, methods: &[&str]) -> Self {
+        self.rules
+            .push((pattern.to_string(), methods.iter().map(|m| m.to_string()).collect()));
+        self
+    }
+
+    fn matches(pattern: &str, id: &str) -> bool {
+        match pattern.strip_suffix('*') {
+            Some(prefix) if prefix.ends_with('/') => id.starts_with(prefix),
+            _ => pattern == id,
+        }
+    }
+
+    /// Whether `peer` may call `method`
+    pub fn is_allowed(&self, peer: &SpiffeId, method: &str) -> bool {
+        let id = peer.to_string();
+        self.rules.iter().any(|(pattern, methods)| {
+            Self::matches(pattern, &id) && methods.iter().any(|allowed| allowed == "*" || allowed == method)
+        })
+    }
+
+    /// Authorizes `peer`, if the connection has one, to call `method`
+    pub fn authorize(&self, peer: Option<&SpiffeId>, method: &str) -> Result<(), A2AError> {
+        match peer {
+            Some(peer) if self.is_allowed(peer, method) => Ok(()),
+            _ => {
+                let spiffe_id = peer.map(|peer| peer.to_string());
+                Err(A2AError::Generic(JSONRPCError {
+                    code: error_codes::PEER_NOT_AUTHORIZED,
+                    message: match &spiffe_id {
+                        Some(id) => format!("Peer {} is not allowed to call {}", id, method),
+                        None => format!("Peer without a SPIFFE ID is not allowed to call {}", method),
+                    },
+                    data: Some(serde_json::json!({ "spiffe_id": spiffe_id, "method": method })),
+                }))
+            }
+        }
+    }
+}
+
+/// mTLS settings for serving with SPIFFE identities
+#[derive(Debug, Clone)]
+pub struct SpiffeServerTls {
+    source: SvidSource,
+    policy: Option<SpiffePolicy>,
+    refresh: Duration,
+}
+
+impl SpiffeServerTls {
+    /// Serves with the SVID of `source`, requiring client SVIDs of its
+    /// trust bundle
+    pub fn new(source: SvidSource) -> Self {
+        Self {
+            source,
+            policy: None,
+            refresh: DEFAULT_SVID_REFRESH,
+        }
+    }
+
+    /// Authorizes JSON-RPC methods per peer with `policy`
+    ///
+    /// Without a policy every peer holding a valid SVID is allowed.
+    pub fn with_policy(mut self, policy: SpiffePolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Sets how often the SVID is reloaded from its source
+    pub fn with_refresh(mut self, refresh: Duration) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// The authorization policy, if any
+    pub fn policy(&self) -> Option<&SpiffePolicy> {
+        self.policy.as_ref()
+    }
+
+    /// Serves `router` on `addr` until the server fails
+    pub async fn serve(&self, addr: SocketAddr, router: Router) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let material = self.source.load().await?;
+        tracing::info!("Serving mTLS as {}", material.spiffe_id);
+        let config = RustlsConfig::from_config(Arc::new(rustls_server_config(&material)?));
+
+        let source = self.source.clone();
+        let refresh = self.refresh;
+        let reloaded = config.clone();
+        let rotation = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(refresh);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match source.load().await.and_then(|material| rustls_server_config(&material)) {
+                    Ok(server_config) => reloaded.reload_from_config(Arc::new(server_config)),
+                    Err(e) => tracing::warn!("Failed to reload SVID, keeping the current one: {}", e),
+                }
+            }
+        });
+
+        let served = axum_server::bind(addr)
+            .acceptor(SpiffeAcceptor::new(config))
+            .serve(router.into_make_service())
+            .await;
+        rotation.abort();
+        Ok(served?)
+    }
+}
+
+/// A rustls server config presenting the SVID and requiring client
+/// certificates issued under its trust bundle
+pub fn rustls_server_config(material: &X509Material) -> Result<rustls::ServerConfig, SpiffeError> {
+    let tls_error = |e: rustls::Error| SpiffeError::Tls(e.to_string());
+    let mut roots = rustls::RootCertStore::empty();
+    for authority in &material.bundle {
+        roots.add(CertificateDer::from(authority.clone())).map_err(tls_error)?;
+    }
+    let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+        .build()
+        .map_err(|e| SpiffeError::Tls(e.to_string()))?;
+    let chain = material.cert_chain.iter().cloned().map(CertificateDer::from).collect();
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(material.private_key.clone()));
+    let mut config = rustls::ServerConfig::builder()
+        .with_client_cert_verifier(verifier)
+        .with_single_cert(chain, key)
+        .map_err(tls_error)?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+/// TLS acceptor recording the client's SPIFFE ID on every request
+#[derive(Clone)]
+pub struct SpiffeAcceptor {
+    inner: RustlsAcceptor,
+}
+
+impl SpiffeAcceptor {
+    /// Accepts TLS connections with `config`
+    pub fn new(config: RustlsConfig) -> Self {
+        Self {
+            inner: RustlsAcceptor::new(config),
+        }
+    }
+}
+
+impl<I, S> Accept<I, S> for SpiffeAcceptor
+where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    S: Send + 'static,
+{
+    type Stream = <RustlsAcceptor as Accept<I, S>>::Stream;
+    type Service = <axum::Extension<PeerSpiffeId> as Layer<S>>::Service;
+    type Future = BoxFuture<'static, std::io::Result<(Self::Stream, Self::Service)>>;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        let accepted = <RustlsAcceptor<DefaultAcceptor> as Accept<I, S>>::accept(&self.inner, stream, service);
+        Box::pin(async move {
+            let (stream, service) = accepted.await?;
+            let peer = stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .and_then(|certs| certs.first())
+                .and_then(|leaf| super::spiffe_id_of(leaf));
+            Ok((stream, axum::Extension(PeerSpiffeId(peer)).layer(service)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_matches_ids_and_methods() {
+        let policy = SpiffePolicy::new()
+            .allow("spiffe://example.org/ns/prod/sa/planner", &["message/send", "tasks/get"])
+            .allow("spiffe://example.org/ns/admin/*", &["*"]);
+        let planner = SpiffeId::new("spiffe://example.org/ns/prod/sa/planner").unwrap();
+        let admin = SpiffeId::new("spiffe://example.org/ns/admin/sa/ops").unwrap();
+        let other = SpiffeId::new("spiffe://example.org/ns/prod/sa/other").unwrap();
+
+        assert!(policy.is_allowed(&planner, "message/send"));
+        assert!(!policy.is_allowed(&planner, "tasks/cancel"));
+        assert!(policy.is_allowed(&admin, "tasks/cancel"));
+        assert!(!policy.is_allowed(&other, "message/send"));
+
+        let error = policy.authorize(None, "tasks/get").unwrap_err();
+        assert_eq!(error.kind(), "peer_not_authorized");
+        assert_eq!(
+            policy.authorize(Some(&other), "tasks/get").unwrap_err().data().unwrap()["spiffe_id"],
+            "spiffe://example.org/ns/prod/sa/other"
+        );
+    }
+}
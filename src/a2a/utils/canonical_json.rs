@@ -0,0 +1,271 @@
+//! JSON canonicalization (RFC 8785)
+//!
+//! Signatures over JSON must not depend on how a document happens to be
+//! serialized. Every signature feature signs the JSON Canonicalization Scheme
+//! form of its document instead:
+//!
+//! - object members sorted by the UTF-16 code units of their names
+//! - no whitespace between tokens
+//! - strings escaped like ECMAScript's `JSON.stringify`: `\b`, `\t`, `\n`,
+//!   `\f`, `\r`, `\"`, `\\`, other control characters as `\u00xx` and
+//!   everything else literally
+//! - numbers as IEEE 754 doubles in the shortest ECMAScript notation, so
+//!   `4.50` is `4.5`, `1E30` is `1e+30` and integers beyond 2^53 lose precision
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// The canonical form of a JSON value
+pub fn canonicalize(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value);
+    out
+}
+
+/// Serializes `value` and returns the bytes of its canonical form
+pub fn to_canonical_vec<T: Serialize + ?Sized>(value: &T) -> serde_json::Result<Vec<u8>> {
+    Ok(canonicalize(&serde_json::to_value(value)?).into_bytes())
+}
+
+/// The canonical form of a JSON document given as bytes
+pub fn canonicalize_slice(json: &[u8]) -> serde_json::Result<Vec<u8>> {
+    Ok(canonicalize(&serde_json::from_slice(json)?).into_bytes())
+}
+
+fn write_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+        Value::Number(number) => write_number(out, number.as_f64().unwrap_or_default()),
+        Value::String(string) => write_string(out, string),
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_value(out, item);
+            }
+            out.push(']');
+        }
+        Value::Object(members) => {
+            let mut members: Vec<(&String, &Value)> = members.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (index, (name, member)) in members.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_string(out, name);
+                out.push(':');
+                write_value(out, member);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn write_string(out: &mut String, string: &str) {
+    out.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{c}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            c if c < ' ' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Writes a double the way ECMAScript's `Number.prototype.toString` does
+fn write_number(out: &mut String, value: f64) {
+    if value == 0.0 {
+        out.push('0');
+        return;
+    }
+    if value < 0.0 {
+        out.push('-');
+    }
+    // The shortest round-tripping digits, as `d.ddde<exponent>`
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let exponent = exponent.parse::<i32>().unwrap_or_default();
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    let digits = round_half_even(digits.trim_end_matches('0').to_string(), value.abs(), exponent);
+    let digits = digits.as_str();
+    let count = digits.len() as i32;
+    // The position of the decimal point relative to the digits
+    let point = exponent + 1;
+
+    if count <= point && point <= 21 {
+        out.push_str(digits);
+        out.push_str(&"0".repeat((point - count) as usize));
+    } else if 0 < point && point <= 21 {
+        out.push_str(&digits[..point as usize]);
+        out.push('.');
+        out.push_str(&digits[point as usize..]);
+    } else if -6 < point && point <= 0 {
+        out.push_str("0.");
+        out.push_str(&"0".repeat((-point) as usize));
+        out.push_str(digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if count > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        let exponent = point - 1;
+        out.push('e');
+        out.push(if exponent < 0 { '-' } else { '+' });
+        out.push_str(&exponent.abs().to_string());
+    }
+}
+
+/// Picks the even last digit when two shortest digit strings are equally close
+///
+/// Rust rounds such ties up, ECMAScript to the even digit. A tie means the
+/// double is exactly halfway between `digits` and the string one lower in
+/// the last digit.
+fn round_half_even(digits: String, value: f64, exponent: i32) -> String {
+    let Some(last) = digits.bytes().last().filter(|last| (last - b'0') % 2 == 1) else {
+        return digits;
+    };
+    let lower = format!("{}{}", &digits[..digits.len() - 1], (last - 1) as char);
+    // `digits` is `d.ddd * 10^exponent`, so the halfway point `<lower>5` has
+    // one more digit after the decimal point
+    if is_exactly_halfway(value, &lower, exponent - digits.len() as i32) {
+        lower
+    } else {
+        digits
+    }
+}
+
+/// Whether a positive double is exactly `<digits>5 * 10^scale`
+fn is_exactly_halfway(value: f64, digits: &str, scale: i32) -> bool {
+    let Some(halfway) = digits.parse::<u128>().ok().and_then(|d| d.checked_mul(10)).map(|d| d + 5) else {
+        return false;
+    };
+    // The double as `mantissa * 2^binary_exponent` with an odd mantissa
+    let bits = value.to_bits();
+    let (mut mantissa, mut binary_exponent) = match (bits >> 52) as i32 & 0x7ff {
+        0 => (bits & ((1 << 52) - 1), -1074),
+        biased => ((bits & ((1 << 52) - 1)) | (1 << 52), biased - 1075),
+    };
+    let zeros = mantissa.trailing_zeros();
+    mantissa >>= zeros;
+    binary_exponent += zeros as i32;
+
+    // `halfway * 10^scale` is odd times `2^scale`, so the powers of two must
+    // match and the odd parts differ by the power of five
+    if binary_exponent != scale {
+        return false;
+    }
+    let power_of_five = 5u128.checked_pow(scale.unsigned_abs());
+    if scale >= 0 {
+        power_of_five.and_then(|power| halfway.checked_mul(power)) == Some(mantissa as u128)
+    } else {
+        power_of_five.and_then(|power| (mantissa as u128).checked_mul(power)) == Some(halfway)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(json: &str) -> String {
+        String::from_utf8(canonicalize_slice(json.as_bytes()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_rfc8785_example() {
+        let input = r#"{
+            "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+            "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+            "literals": [null, true, false]
+        }"#;
+        assert_eq!(
+            canonical(input),
+            r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+        );
+    }
+
+    #[test]
+    fn test_members_are_sorted_by_utf16_code_units() {
+        let input = r#"{
+            "\u20ac": "Euro Sign",
+            "\r": "Carriage Return",
+            "\ufb33": "Hebrew Letter Dalet With Dagesh",
+            "1": "One",
+            "\ud83d\ude00": "Emoji: Grinning Face",
+            "\u0080": "Control",
+            "\u00f6": "Latin Small Letter O With Diaeresis"
+        }"#;
+        let canonical = canonical(input);
+        let order = [
+            "Carriage Return",
+            "One",
+            "Control",
+            "Latin Small Letter O With Diaeresis",
+            "Euro Sign",
+            "Emoji: Grinning Face",
+            "Hebrew Letter Dalet With Dagesh",
+        ];
+        let positions: Vec<usize> = order.iter().map(|name| canonical.find(name).unwrap()).collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(canonical.starts_with(r#"{"\r":"Carriage Return","1":"One","#));
+    }
+
+    #[test]
+    fn test_number_serialization() {
+        // Vectors from Appendix B of RFC 8785, as IEEE 754 bit patterns
+        let vectors: [(u64, &str); 22] = [
+            (0x0000000000000000, "0"),
+            (0x8000000000000000, "0"),
+            (0x0000000000000001, "5e-324"),
+            (0x8000000000000001, "-5e-324"),
+            (0x7fefffffffffffff, "1.7976931348623157e+308"),
+            (0xffefffffffffffff, "-1.7976931348623157e+308"),
+            (0x4340000000000000, "9007199254740992"),
+            (0xc340000000000000, "-9007199254740992"),
+            (0x4430000000000000, "295147905179352830000"),
+            (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+            (0x44b52d02c7e14af6, "1e+23"),
+            (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+            (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+            (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+            (0x444b1ae4d6e2ef50, "1e+21"),
+            (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+            (0x3eb0c6f7a0b5ed8d, "0.000001"),
+            (0x41b3de4355555553, "333333333.3333332"),
+            (0x41b3de4355555554, "333333333.33333325"),
+            (0x41b3de4355555555, "333333333.3333333"),
+            (0xbecbf647612f3696, "-0.0000033333333333333333"),
+            (0x43143ff3c1cb0959, "1424953923781206.2"),
+        ];
+        for (bits, expected) in vectors {
+            let mut out = String::new();
+            write_number(&mut out, f64::from_bits(bits));
+            assert_eq!(out, expected, "bits {:#018x}", bits);
+        }
+
+        // 2^-25 is exactly halfway between two shortest digit strings
+        let mut out = String::new();
+        write_number(&mut out, 2f64.powi(-25));
+        assert_eq!(out, "2.9802322387695312e-8");
+    }
+
+    #[test]
+    fn test_serialization_order_does_not_matter() {
+        let a = canonical(r#"{"b": [1, 2.0, {"y": true, "x": null}], "a": "text"}"#);
+        let b = canonical(r#"{ "a":"text","b":[1.0,2,{"x":null,"y":true}] }"#);
+        assert_eq!(a, b);
+        assert_eq!(a, r#"{"a":"text","b":[1,2,{"x":null,"y":true}]}"#);
+        assert_eq!(to_canonical_vec(&serde_json::json!({"z": 1, "a": 2})).unwrap(), br#"{"a":2,"z":1}"#);
+    }
+}
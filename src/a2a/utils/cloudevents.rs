@@ -0,0 +1,246 @@
+//! CloudEvents 1.0 binding for task events
+//!
+//! Converts tasks and task update events into CloudEvents in structured JSON
+//! mode, so they can be handed to event-driven platforms (Knative, EventBridge,
+//! Event Grid, ...) without a custom adapter. The mapping is:
+//!
+//! - `type`: `a2a.task`, `a2a.task.status-update` or `a2a.task.artifact-update`
+//! - `source`: the agent, as configured by the caller
+//! - `subject`: the task id
+//! - `data`: the A2A object, as JSON
+//!
+//! The context id, and for status updates the task state, are also exposed as
+//! the `a2acontextid` and `a2astate` extension attributes so that brokers can
+//! route on them without parsing `data`.
+
+use crate::a2a::error::A2AError;
+use crate::a2a::models::{Task, TaskArtifactUpdateEvent, TaskStatusUpdateEvent};
+#[cfg(feature = "server")]
+use crate::a2a::server::events::Event;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The CloudEvents specification version produced
+pub const CLOUDEVENTS_SPEC_VERSION: &str = "1.0";
+
+/// Media type of a CloudEvent in structured JSON mode
+pub const CLOUDEVENTS_CONTENT_TYPE: &str = "application/cloudevents+json";
+
+/// Event type of a task snapshot
+pub const TASK_EVENT_TYPE: &str = "a2a.task";
+
+/// Event type of a task status update
+pub const STATUS_UPDATE_EVENT_TYPE: &str = "a2a.task.status-update";
+
+/// Event type of a task artifact update
+pub const ARTIFACT_UPDATE_EVENT_TYPE: &str = "a2a.task.artifact-update";
+
+/// Extension attribute carrying the context id
+pub const CONTEXT_ID_EXTENSION: &str = "a2acontextid";
+
+/// Extension attribute carrying the task state
+pub const STATE_EXTENSION: &str = "a2astate";
+
+/// A CloudEvent in structured JSON mode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CloudEvent {
+    /// The specification version, always `1.0`
+    pub specversion: String,
+    /// Identifier of the event, unique per source
+    pub id: String,
+    /// URI reference identifying the producer
+    pub source: String,
+    /// The event type
+    #[serde(rename = "type")]
+    pub r#type: String,
+    /// The subject of the event within the source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// RFC 3339 timestamp of the occurrence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    /// Media type of `data`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datacontenttype: Option<String>,
+    /// The event payload
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    /// Extension attributes
+    #[serde(flatten)]
+    pub extensions: HashMap<String, Value>,
+}
+
+impl CloudEvent {
+    /// Creates an event with a fresh id and the current time
+    pub fn new(source: &str, event_type: &str) -> Self {
+        Self {
+            specversion: CLOUDEVENTS_SPEC_VERSION.to_string(),
+            id: uuid::Uuid::new_v4().to_string(),
+            source: source.to_string(),
+            r#type: event_type.to_string(),
+            subject: None,
+            time: Some(chrono::Utc::now().to_rfc3339()),
+            datacontenttype: None,
+            data: None,
+            extensions: HashMap::new(),
+        }
+    }
+
+    /// Sets the subject
+    pub fn with_subject(mut self, subject: &str) -> Self {
+        self.subject = Some(subject.to_string());
+        self
+    }
+
+    /// Sets the event type
+    pub fn with_type(mut self, event_type: &str) -> Self {
+        self.r#type = event_type.to_string();
+        self
+    }
+
+    /// Sets a JSON payload
+    pub fn with_json_data<T: Serialize>(mut self, data: &T) -> Result<Self, A2AError> {
+        self.data = Some(serde_json::to_value(data)?);
+        self.datacontenttype = Some("application/json".to_string());
+        Ok(self)
+    }
+
+    /// Sets an extension attribute
+    ///
+    /// Names must be 1-20 lowercase ASCII letters or digits, as the
+    /// specification requires.
+    pub fn with_extension(mut self, name: &str, value: impl Into<Value>) -> Result<Self, A2AError> {
+        let valid = !name.is_empty()
+            && name.len() <= 20
+            && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+        if !valid {
+            return Err(A2AError::invalid_params(&format!(
+                "Invalid CloudEvents extension name: '{}'",
+                name
+            )));
+        }
+        self.extensions.insert(name.to_string(), value.into());
+        Ok(self)
+    }
+
+    /// Serializes the event in structured JSON mode
+    pub fn to_json_bytes(&self) -> Result<Vec<u8>, A2AError> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Parses an event in structured JSON mode
+    pub fn from_json_bytes(bytes: &[u8]) -> Result<Self, A2AError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+/// Objects that can be published as CloudEvents
+pub trait IntoCloudEvent {
+    /// Converts the object into a CloudEvent produced by `source`
+    fn to_cloud_event(&self, source: &str) -> Result<CloudEvent, A2AError>;
+}
+
+impl IntoCloudEvent for Task {
+    fn to_cloud_event(&self, source: &str) -> Result<CloudEvent, A2AError> {
+        CloudEvent::new(source, TASK_EVENT_TYPE)
+            .with_subject(&self.id)
+            .with_json_data(self)?
+            .with_extension(CONTEXT_ID_EXTENSION, self.context_id.as_str())?
+            .with_extension(STATE_EXTENSION, serde_json::to_value(&self.status.state)?)
+    }
+}
+
+impl IntoCloudEvent for TaskStatusUpdateEvent {
+    fn to_cloud_event(&self, source: &str) -> Result<CloudEvent, A2AError> {
+        let mut event = CloudEvent::new(source, STATUS_UPDATE_EVENT_TYPE)
+            .with_subject(&self.task_id)
+            .with_json_data(self)?
+            .with_extension(CONTEXT_ID_EXTENSION, self.context_id.as_str())?
+            .with_extension(STATE_EXTENSION, serde_json::to_value(&self.status.state)?)?;
+        if let Some(timestamp) = &self.status.timestamp {
+            event.time = Some(timestamp.clone());
+        }
+        Ok(event)
+    }
+}
+
+impl IntoCloudEvent for TaskArtifactUpdateEvent {
+    fn to_cloud_event(&self, source: &str) -> Result<CloudEvent, A2AError> {
+        CloudEvent::new(source, ARTIFACT_UPDATE_EVENT_TYPE)
+            .with_subject(&self.task_id)
+            .with_json_data(self)?
+            .with_extension(CONTEXT_ID_EXTENSION, self.context_id.as_str())
+    }
+}
+
+#[cfg(feature = "server")]
+impl IntoCloudEvent for Event {
+    fn to_cloud_event(&self, source: &str) -> Result<CloudEvent, A2AError> {
+        match self {
+            Event::Task(task) => task.to_cloud_event(source),
+            Event::TaskStatusUpdate(update) => update.to_cloud_event(source),
+            Event::TaskArtifactUpdate(update) => update.to_cloud_event(source),
+            Event::Message(message) => {
+                let mut event = CloudEvent::new(source, "a2a.message").with_json_data(message)?;
+                if let Some(task_id) = &message.task_id {
+                    event = event.with_subject(task_id);
+                }
+                Ok(event)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::{TaskState, TaskStatus};
+    use crate::a2a::models::Artifact;
+
+    const SOURCE: &str = "https://agent.example.com";
+
+    #[test]
+    fn test_status_update_to_cloud_event() {
+        let update = TaskStatusUpdateEvent::new(
+            "t1".to_string(),
+            "ctx".to_string(),
+            TaskStatus::new(TaskState::Completed),
+            true,
+        );
+        let event = update.to_cloud_event(SOURCE).unwrap();
+        assert_eq!(event.r#type, STATUS_UPDATE_EVENT_TYPE);
+        assert_eq!(event.subject.as_deref(), Some("t1"));
+        assert_eq!(event.time, update.status.timestamp);
+
+        let json: Value = serde_json::from_slice(&event.to_json_bytes().unwrap()).unwrap();
+        assert_eq!(json["specversion"], "1.0");
+        assert_eq!(json["type"], "a2a.task.status-update");
+        assert_eq!(json["source"], SOURCE);
+        assert_eq!(json["datacontenttype"], "application/json");
+        assert_eq!(json["a2acontextid"], "ctx");
+        assert_eq!(json["a2astate"], "completed");
+        assert_eq!(json["data"]["taskId"], "t1");
+    }
+
+    #[test]
+    fn test_artifact_update_round_trip() {
+        let update = TaskArtifactUpdateEvent::new(
+            "t1".to_string(),
+            "ctx".to_string(),
+            Artifact::new(vec![]),
+        );
+        let event = Event::TaskArtifactUpdate(update).to_cloud_event(SOURCE).unwrap();
+        let parsed = CloudEvent::from_json_bytes(&event.to_json_bytes().unwrap()).unwrap();
+        assert_eq!(parsed, event);
+        assert_eq!(parsed.r#type, ARTIFACT_UPDATE_EVENT_TYPE);
+        assert_eq!(parsed.extensions["a2acontextid"], "ctx");
+    }
+
+    #[test]
+    fn test_invalid_extension_name() {
+        let event = CloudEvent::new(SOURCE, TASK_EVENT_TYPE);
+        assert!(event.clone().with_extension("Bad-Name", 1).is_err());
+        assert!(event.with_extension("a2apriority", 1).is_ok());
+    }
+}
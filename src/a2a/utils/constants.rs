@@ -15,6 +15,20 @@ pub const EXTENDED_AGENT_CARD_PATH: &str = "/agent/authenticatedExtendedCard";
 /// Default RPC URL
 pub const DEFAULT_RPC_URL: &str = "/";
 
+/// Message metadata key naming the skill a message is addressed to
+pub const SKILL_ID_METADATA_KEY: &str = "skill_id";
+
+/// Skill metadata key holding the JSON Schema of the skill's data parts
+pub const SKILL_DATA_SCHEMA_METADATA_KEY: &str = "data_schema";
+
+/// Request metadata key asking for a dry run of message/send; see
+/// `MessageSendParams::with_dry_run`
+pub const DRY_RUN_METADATA_KEY: &str = "dry_run";
+
+/// Task metadata key suggesting how many milliseconds a client should wait
+/// before polling the task again; see `PollHintRequestHandler`
+pub const POLL_AFTER_METADATA_KEY: &str = "poll_after_ms";
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,144 @@
+//! Compact JSON Web Signatures
+//!
+//! Minimal JWS (RFC 7515) support for signing documents such as the agent card.
+//! Signing is abstracted behind `JwsSigner` so asymmetric algorithms can be
+//! plugged in; `Hs256Signer` is provided for shared-secret deployments.
+//!
+//! JSON payloads should be signed in their canonical form
+//! ([`crate::a2a::utils::canonical_json`]), which verifiers can re-derive
+//! from the document however it was serialized. The header is always
+//! canonical.
+
+use crate::a2a::error::A2AError;
+use crate::a2a::utils::canonical_json::to_canonical_vec;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+
+/// Media type of a compact JWS
+pub const JOSE_CONTENT_TYPE: &str = "application/jose";
+
+/// Produces JWS signatures
+pub trait JwsSigner: Send + Sync {
+    /// The `alg` header value, e.g. `HS256` or `ES256`
+    fn algorithm(&self) -> &str;
+
+    /// The `kid` header value, if any
+    fn key_id(&self) -> Option<&str> {
+        None
+    }
+
+    /// Signs the JWS signing input
+    fn sign(&self, signing_input: &[u8]) -> Result<Vec<u8>, A2AError>;
+}
+
+/// HMAC-SHA256 signer
+#[derive(Clone)]
+pub struct Hs256Signer {
+    key: Vec<u8>,
+    key_id: Option<String>,
+}
+
+impl Hs256Signer {
+    /// Creates a signer with the given secret key
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            key: key.to_vec(),
+            key_id: None,
+        }
+    }
+
+    /// Sets the `kid` header value
+    pub fn with_key_id(mut self, key_id: &str) -> Self {
+        self.key_id = Some(key_id.to_string());
+        self
+    }
+
+    /// Verifies a compact JWS and returns its payload
+    pub fn verify(&self, jws: &str) -> Result<Vec<u8>, A2AError> {
+        let invalid = || A2AError::invalid_params("Invalid JWS");
+        let (signing_input, signature) = jws.rsplit_once('.').ok_or_else(invalid)?;
+        let (header, payload) = signing_input.split_once('.').ok_or_else(invalid)?;
+
+        let header: serde_json::Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(header).map_err(|_| invalid())?)?;
+        if header["alg"] != "HS256" {
+            return Err(invalid());
+        }
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| invalid())?;
+        self.mac(signing_input.as_bytes())
+            .verify_slice(&signature)
+            .map_err(|_| invalid())?;
+        URL_SAFE_NO_PAD.decode(payload).map_err(|_| invalid())
+    }
+
+    fn mac(&self, input: &[u8]) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(input);
+        mac
+    }
+}
+
+impl JwsSigner for Hs256Signer {
+    fn algorithm(&self) -> &str {
+        "HS256"
+    }
+
+    fn key_id(&self) -> Option<&str> {
+        self.key_id.as_deref()
+    }
+
+    fn sign(&self, signing_input: &[u8]) -> Result<Vec<u8>, A2AError> {
+        Ok(self.mac(signing_input).finalize().into_bytes().to_vec())
+    }
+}
+
+impl std::fmt::Debug for Hs256Signer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Hs256Signer")
+            .field("key", &"<redacted>")
+            .field("key_id", &self.key_id)
+            .finish()
+    }
+}
+
+/// Signs `payload` into a compact JWS (`header.payload.signature`)
+pub fn sign_compact(payload: &[u8], content_type: &str, signer: &dyn JwsSigner) -> Result<String, A2AError> {
+    let mut header = json!({
+        "alg": signer.algorithm(),
+        "typ": "JOSE",
+        "cty": content_type,
+    });
+    if let Some(key_id) = signer.key_id() {
+        header["kid"] = json!(key_id);
+    }
+    let signing_input = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(to_canonical_vec(&header)?),
+        URL_SAFE_NO_PAD.encode(payload)
+    );
+    let signature = signer.sign(signing_input.as_bytes())?;
+    Ok(format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(signature)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = Hs256Signer::new(b"secret").with_key_id("card-key-1");
+        let jws = sign_compact(br#"{"name":"agent"}"#, "application/json", &signer).unwrap();
+        assert_eq!(jws.split('.').count(), 3);
+
+        let header: serde_json::Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(jws.split('.').next().unwrap()).unwrap()).unwrap();
+        assert_eq!(header["alg"], "HS256");
+        assert_eq!(header["kid"], "card-key-1");
+
+        assert_eq!(signer.verify(&jws).unwrap(), br#"{"name":"agent"}"#);
+        assert!(Hs256Signer::new(b"other").verify(&jws).is_err());
+    }
+}
@@ -0,0 +1,33 @@
+//! Logging setup helpers
+//!
+//! Thin wrappers around `tracing_subscriber` used by the server builder
+//! presets. The filter is taken from `RUST_LOG` and defaults to `info`.
+
+use tracing_subscriber::EnvFilter;
+
+/// Output format of log lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Multi-line, human friendly output for local development
+    Pretty,
+    /// One line per event
+    #[default]
+    Compact,
+    /// One JSON object per event, for log aggregation
+    Json,
+}
+
+/// Installs a global subscriber writing logs in `format`
+///
+/// Returns `false` if a global subscriber was already installed, in which case
+/// it is left in place.
+pub fn init_logging(format: LogFormat) -> bool {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    match format {
+        LogFormat::Pretty => builder.pretty().try_init(),
+        LogFormat::Compact => builder.compact().try_init(),
+        LogFormat::Json => builder.json().try_init(),
+    }
+    .is_ok()
+}
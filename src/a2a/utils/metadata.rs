@@ -0,0 +1,272 @@
+//! Typed access to A2A metadata maps
+//!
+//! Tasks, messages, artifacts and events carry free-form `metadata` maps. This
+//! module lets extensions declare their metadata keys together with a serde
+//! type, and read/write them on any object implementing `HasMetadata` without
+//! handling raw JSON.
+//!
+//! ```
+//! use a2a_rust::a2a::utils::metadata::{HasMetadata, MetadataKey};
+//! use a2a_rust::{Task, TaskState, TaskStatus};
+//!
+//! const PRIORITY: MetadataKey<u32> = MetadataKey::new("com.example/priority");
+//!
+//! let mut task = Task::new("ctx-1".to_string(), TaskStatus::new(TaskState::Submitted));
+//! task.set_metadata(&PRIORITY, &3).unwrap();
+//! assert_eq!(task.get_metadata(&PRIORITY).unwrap(), Some(3));
+//! ```
+
+use crate::a2a::core_types::Message;
+use crate::a2a::error::A2AError;
+use crate::a2a::models::{AgentSkill, Artifact, Task, TaskArtifactUpdateEvent, TaskStatusUpdateEvent};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A metadata key bound to the type of its value
+pub struct MetadataKey<T> {
+    name: &'static str,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> MetadataKey<T> {
+    /// Declares a new metadata key
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _type: PhantomData,
+        }
+    }
+
+    /// Returns the key name used in the metadata map
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T> Clone for MetadataKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for MetadataKey<T> {}
+
+impl<T> fmt::Debug for MetadataKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MetadataKey").field(&self.name).finish()
+    }
+}
+
+/// Objects carrying an A2A metadata map
+pub trait HasMetadata {
+    /// Returns the metadata map, if any
+    fn metadata(&self) -> Option<&HashMap<String, Value>>;
+
+    /// Returns the metadata map, creating it if missing
+    fn metadata_mut(&mut self) -> &mut HashMap<String, Value>;
+
+    /// Reads a typed metadata value
+    ///
+    /// Returns `Ok(None)` if the key is absent and an invalid params error if
+    /// the stored value does not match the key's type.
+    fn get_metadata<T: DeserializeOwned>(&self, key: &MetadataKey<T>) -> Result<Option<T>, A2AError> {
+        match self.metadata().and_then(|m| m.get(key.name())) {
+            Some(value) => serde_json::from_value(value.clone()).map(Some).map_err(|e| {
+                A2AError::invalid_params(&format!("Invalid metadata value for '{}': {}", key.name(), e))
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// Writes a typed metadata value
+    fn set_metadata<T: Serialize>(&mut self, key: &MetadataKey<T>, value: &T) -> Result<(), A2AError> {
+        let value = serde_json::to_value(value)?;
+        self.metadata_mut().insert(key.name().to_string(), value);
+        Ok(())
+    }
+
+    /// Removes a metadata value, returning the raw JSON if it was present
+    fn remove_metadata<T>(&mut self, key: &MetadataKey<T>) -> Option<Value> {
+        self.metadata_mut().remove(key.name())
+    }
+}
+
+macro_rules! impl_has_metadata {
+    ($($ty:ty),*) => {
+        $(
+            impl HasMetadata for $ty {
+                fn metadata(&self) -> Option<&HashMap<String, Value>> {
+                    self.metadata.as_ref()
+                }
+
+                fn metadata_mut(&mut self) -> &mut HashMap<String, Value> {
+                    self.metadata.get_or_insert_with(HashMap::new)
+                }
+            }
+        )*
+    };
+}
+
+impl_has_metadata!(Task, Message, Artifact, TaskStatusUpdateEvent, TaskArtifactUpdateEvent, AgentSkill);
+
+type Validator = fn(&Value) -> Result<(), serde_json::Error>;
+
+/// A registry of metadata keys and their expected types
+///
+/// Servers can register the extension keys they understand and validate
+/// incoming metadata maps against them before accepting a request.
+#[derive(Debug, Clone, Default)]
+pub struct MetadataRegistry {
+    validators: HashMap<&'static str, Validator>,
+}
+
+impl MetadataRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a metadata key with its serde type
+    pub fn register<T: DeserializeOwned + 'static>(&mut self, key: &MetadataKey<T>) -> &mut Self {
+        fn validate<T: DeserializeOwned>(value: &Value) -> Result<(), serde_json::Error> {
+            T::deserialize(value).map(|_| ())
+        }
+        self.validators.insert(key.name(), validate::<T>);
+        self
+    }
+
+    /// Returns true if the key name has been registered
+    pub fn is_registered(&self, name: &str) -> bool {
+        self.validators.contains_key(name)
+    }
+
+    /// Validates every registered key present in the metadata map
+    ///
+    /// Unregistered keys are passed through untouched.
+    pub fn validate(&self, metadata: &HashMap<String, Value>) -> Result<(), A2AError> {
+        for (name, value) in metadata {
+            if let Some(validator) = self.validators.get(name.as_str()) {
+                validator(value).map_err(|e| {
+                    A2AError::invalid_params(&format!("Invalid metadata value for '{}': {}", name, e))
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Merges `source` into `target`
+///
+/// Nested JSON objects are merged recursively, other values replace the
+/// existing entry, and a `null` value removes the key.
+pub fn merge_metadata(target: &mut HashMap<String, Value>, source: &HashMap<String, Value>) {
+    for (key, value) in source {
+        match (target.get_mut(key), value) {
+            (_, Value::Null) => {
+                target.remove(key);
+            }
+            (Some(Value::Object(existing)), Value::Object(incoming)) => {
+                merge_json_objects(existing, incoming);
+            }
+            _ => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+fn merge_json_objects(target: &mut serde_json::Map<String, Value>, source: &serde_json::Map<String, Value>) {
+    for (key, value) in source {
+        match (target.get_mut(key), value) {
+            (_, Value::Null) => {
+                target.remove(key);
+            }
+            (Some(Value::Object(existing)), Value::Object(incoming)) => {
+                merge_json_objects(existing, incoming);
+            }
+            _ => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::{Part, Role};
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Routing {
+        region: String,
+        replicas: u32,
+    }
+
+    const ROUTING: MetadataKey<Routing> = MetadataKey::new("com.example/routing");
+    const PRIORITY: MetadataKey<u32> = MetadataKey::new("com.example/priority");
+
+    #[test]
+    fn test_get_set_typed_metadata() {
+        let mut message = Message::new(Role::User, vec![Part::text("hi".to_string())]);
+        assert_eq!(message.get_metadata(&ROUTING).unwrap(), None);
+
+        let routing = Routing { region: "eu".to_string(), replicas: 2 };
+        message.set_metadata(&ROUTING, &routing).unwrap();
+        assert_eq!(message.get_metadata(&ROUTING).unwrap(), Some(routing));
+        assert_eq!(message.metadata.as_ref().unwrap()["com.example/routing"]["region"], "eu");
+
+        assert!(message.remove_metadata(&ROUTING).is_some());
+        assert_eq!(message.get_metadata(&ROUTING).unwrap(), None);
+    }
+
+    #[test]
+    fn test_type_mismatch_is_an_error() {
+        let mut message = Message::new(Role::User, vec![]);
+        message.metadata_mut().insert(PRIORITY.name().to_string(), json!("high"));
+        assert!(message.get_metadata(&PRIORITY).is_err());
+    }
+
+    #[test]
+    fn test_registry_validation() {
+        let mut registry = MetadataRegistry::new();
+        registry.register(&PRIORITY).register(&ROUTING);
+        assert!(registry.is_registered("com.example/priority"));
+
+        let mut metadata = HashMap::new();
+        metadata.insert("com.example/priority".to_string(), json!(1));
+        metadata.insert("unregistered".to_string(), json!({"any": "thing"}));
+        assert!(registry.validate(&metadata).is_ok());
+
+        metadata.insert("com.example/routing".to_string(), json!({"region": 5}));
+        assert!(registry.validate(&metadata).is_err());
+    }
+
+    #[test]
+    fn test_merge_metadata() {
+        let mut target: HashMap<String, Value> = serde_json::from_value(json!({
+            "a": 1,
+            "nested": {"x": 1, "y": 2},
+            "gone": true,
+        }))
+        .unwrap();
+        let source: HashMap<String, Value> = serde_json::from_value(json!({
+            "b": 2,
+            "nested": {"y": 3, "z": 4},
+            "gone": null,
+        }))
+        .unwrap();
+
+        merge_metadata(&mut target, &source);
+
+        assert_eq!(target["a"], 1);
+        assert_eq!(target["b"], 2);
+        assert_eq!(target["nested"], json!({"x": 1, "y": 3, "z": 4}));
+        assert!(!target.contains_key("gone"));
+    }
+}
@@ -0,0 +1,258 @@
+//! MIME type detection and normalization for message parts
+//!
+//! Clients often send file parts without a MIME type, or with one of the
+//! non-standard aliases browsers and older tools produce (`image/jpg`,
+//! `audio/mp3`). The helpers here give every part one canonical type:
+//! declared types are normalized, missing ones are sniffed from the leading
+//! bytes of inline content, and the result is checked against the input
+//! modes an agent card accepts. Client interceptors and server validators
+//! share them so both sides agree on what a part is.
+
+use crate::a2a::core_types::{FileContent, Part, PartRoot};
+use crate::a2a::error::{A2AError, ContentTypeNotSupportedError};
+use crate::a2a::models::AgentCard;
+use base64::Engine;
+use thiserror::Error;
+
+/// MIME type assumed for files that neither declare nor reveal one
+pub const OCTET_STREAM_MIME_TYPE: &str = "application/octet-stream";
+
+/// MIME type of text parts
+pub const TEXT_PART_MIME_TYPE: &str = "text/plain";
+
+/// MIME type of data parts
+pub const JSON_MIME_TYPE: &str = "application/json";
+
+/// Base64 characters decoded when sniffing, enough for every known signature
+const SNIFF_PREFIX_CHARS: usize = 512;
+
+/// Non-standard MIME types and their registered equivalents
+const ALIASES: &[(&str, &str)] = &[
+    ("image/jpg", "image/jpeg"),
+    ("image/pjpeg", "image/jpeg"),
+    ("image/x-png", "image/png"),
+    ("image/svg", "image/svg+xml"),
+    ("audio/mp3", "audio/mpeg"),
+    ("audio/x-mp3", "audio/mpeg"),
+    ("audio/x-wav", "audio/wav"),
+    ("audio/wave", "audio/wav"),
+    ("video/x-m4v", "video/mp4"),
+    ("application/x-pdf", "application/pdf"),
+    ("application/x-zip-compressed", "application/zip"),
+    ("application/x-json", "application/json"),
+    ("text/json", "application/json"),
+    ("text/x-markdown", "text/markdown"),
+];
+
+/// A part whose MIME type the agent does not accept
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Part {part_index} has MIME type '{mime_type}', which the agent does not accept")]
+pub struct UnacceptedMimeType {
+    /// Position of the part in the message
+    pub part_index: usize,
+    /// The effective MIME type of the part
+    pub mime_type: String,
+}
+
+impl From<UnacceptedMimeType> for A2AError {
+    fn from(unaccepted: UnacceptedMimeType) -> Self {
+        A2AError::ContentTypeNotSupported(ContentTypeNotSupportedError {
+            message: unaccepted.to_string(),
+            data: Some(serde_json::json!({
+                "part_index": unaccepted.part_index,
+                "mime_type": unaccepted.mime_type,
+            })),
+            ..Default::default()
+        })
+    }
+}
+
+/// Lowercases `mime_type` and replaces known aliases with the registered type
+///
+/// Parameters such as `; charset=utf-8` are kept.
+pub fn normalize_mime_type(mime_type: &str) -> String {
+    let (essence, parameters) = match mime_type.split_once(';') {
+        Some((essence, parameters)) => (essence, Some(parameters.trim())),
+        None => (mime_type, None),
+    };
+    let essence = essence.trim().to_lowercase();
+    let essence = ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == essence)
+        .map(|(_, canonical)| canonical.to_string())
+        .unwrap_or(essence);
+    match parameters {
+        Some(parameters) if !parameters.is_empty() => format!("{}; {}", essence, parameters),
+        _ => essence,
+    }
+}
+
+/// Detects the MIME type of `bytes` from their magic numbers
+pub fn sniff_mime_type(bytes: &[u8]) -> Option<&'static str> {
+    infer::get(bytes).map(|kind| kind.mime_type())
+}
+
+/// Detects the MIME type of base64-encoded content from its leading bytes
+pub fn sniff_base64_mime_type(encoded: &str) -> Option<&'static str> {
+    // Whole quanta of four characters decode without padding
+    let end = encoded.len().min(SNIFF_PREFIX_CHARS) / 4 * 4;
+    let prefix = encoded.get(..end)?;
+    let bytes = base64::engine::general_purpose::STANDARD.decode(prefix).ok()?;
+    sniff_mime_type(&bytes)
+}
+
+/// The MIME type a part is treated as
+///
+/// File parts use their normalized declared type, or the type sniffed from
+/// inline bytes, falling back to `application/octet-stream`. Text parts are
+/// `text/plain` and data parts `application/json`.
+pub fn effective_mime_type(part: &Part) -> String {
+    match part.root() {
+        PartRoot::Text(_) => TEXT_PART_MIME_TYPE.to_string(),
+        PartRoot::Data(_) => JSON_MIME_TYPE.to_string(),
+        PartRoot::File(file) => {
+            let (declared, sniffed) = match &file.file {
+                FileContent::Bytes(content) => (content.mime_type.as_deref(), Some(content.bytes.as_str())),
+                FileContent::Uri(content) => (content.mime_type.as_deref(), None),
+            };
+            match declared.filter(|mime_type| !mime_type.trim().is_empty()) {
+                Some(mime_type) => normalize_mime_type(mime_type),
+                None => sniffed
+                    .and_then(sniff_base64_mime_type)
+                    .unwrap_or(OCTET_STREAM_MIME_TYPE)
+                    .to_string(),
+            }
+        }
+    }
+}
+
+/// Normalizes the declared MIME types of file parts, sniffing missing ones
+///
+/// Files whose type cannot be determined are left without one.
+pub fn normalize_part_mime_types(parts: &mut [Part]) {
+    for part in parts {
+        let root = match part {
+            Part::WithRoot { root } => root,
+            Part::Direct(root) => root,
+        };
+        let PartRoot::File(file) = root else { continue };
+        let (mime_type, sniffed) = match &mut file.file {
+            FileContent::Bytes(content) => {
+                let sniffed = sniff_base64_mime_type(&content.bytes);
+                (&mut content.mime_type, sniffed)
+            }
+            FileContent::Uri(content) => (&mut content.mime_type, None),
+        };
+        *mime_type = match mime_type.as_deref().filter(|declared| !declared.trim().is_empty()) {
+            Some(declared) => Some(normalize_mime_type(declared)),
+            None => sniffed.map(str::to_string),
+        };
+    }
+}
+
+/// Whether `mime_type` matches one of the accepted `modes`
+///
+/// Modes are exact types, wildcards such as `image/*` or `*/*`, or a bare
+/// top-level type such as `text`. Parameters do not affect the match, and
+/// an empty list of modes accepts everything.
+pub fn mime_type_accepted(mime_type: &str, modes: &[String]) -> bool {
+    if modes.is_empty() {
+        return true;
+    }
+    let essence = normalize_mime_type(mime_type.split(';').next().unwrap_or_default());
+    let top_level = essence.split('/').next().unwrap_or_default();
+    modes.iter().any(|mode| {
+        let mode = normalize_mime_type(mode.split(';').next().unwrap_or_default());
+        match mode.strip_suffix("/*") {
+            Some("*") => true,
+            Some(prefix) => prefix == top_level,
+            None if !mode.contains('/') => mode == top_level,
+            None => mode == essence,
+        }
+    })
+}
+
+/// The input modes an agent card accepts
+///
+/// These are the card's default input modes together with the input modes
+/// of its skills.
+pub fn accepted_input_modes(card: &AgentCard) -> Vec<String> {
+    let mut modes = card.default_input_modes.clone();
+    for skill in &card.skills {
+        for mode in skill.input_modes.iter().flatten() {
+            if !modes.contains(mode) {
+                modes.push(mode.clone());
+            }
+        }
+    }
+    modes
+}
+
+/// Checks that every part has a MIME type among the accepted `modes`
+pub fn validate_part_mime_types(parts: &[Part], modes: &[String]) -> Result<(), UnacceptedMimeType> {
+    for (part_index, part) in parts.iter().enumerate() {
+        let mime_type = effective_mime_type(part);
+        if !mime_type_accepted(&mime_type, modes) {
+            return Err(UnacceptedMimeType { part_index, mime_type });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a::core_types::FilePart;
+
+    const PNG: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 0x0D, b'I', b'H', b'D', b'R'];
+
+    fn file(bytes: &[u8], mime_type: Option<&str>) -> Part {
+        let mut file = FilePart::new_bytes(base64::engine::general_purpose::STANDARD.encode(bytes));
+        if let FileContent::Bytes(content) = &mut file.file {
+            content.mime_type = mime_type.map(str::to_string);
+        }
+        Part::Direct(PartRoot::File(file))
+    }
+
+    fn declared(part: &Part) -> Option<String> {
+        match part.root() {
+            PartRoot::File(FilePart { file: FileContent::Bytes(content), .. }) => content.mime_type.clone(),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_normalizes_aliases_and_keeps_parameters() {
+        assert_eq!(normalize_mime_type("Image/JPG"), "image/jpeg");
+        assert_eq!(normalize_mime_type("audio/mp3"), "audio/mpeg");
+        assert_eq!(normalize_mime_type("text/json;charset=UTF-8"), "application/json; charset=UTF-8");
+        assert_eq!(normalize_mime_type("application/pdf"), "application/pdf");
+    }
+
+    #[test]
+    fn test_missing_types_are_sniffed() {
+        let mut parts = vec![file(PNG, None), file(b"plain words", None), file(PNG, Some("image/x-png"))];
+        assert_eq!(effective_mime_type(&parts[0]), "image/png");
+        assert_eq!(effective_mime_type(&parts[1]), OCTET_STREAM_MIME_TYPE);
+
+        normalize_part_mime_types(&mut parts);
+        assert_eq!(declared(&parts[0]).as_deref(), Some("image/png"));
+        assert_eq!(declared(&parts[1]), None);
+        assert_eq!(declared(&parts[2]).as_deref(), Some("image/png"));
+    }
+
+    #[test]
+    fn test_parts_are_validated_against_modes() {
+        let modes = vec!["text".to_string(), "image/*".to_string(), "image/jpg".to_string()];
+        assert!(mime_type_accepted("text/markdown", &modes));
+        assert!(mime_type_accepted("image/jpeg", &["image/jpg".to_string()]));
+        assert!(mime_type_accepted("video/mp4", &[]));
+
+        let parts = vec![Part::text("hi".to_string()), file(PNG, None), Part::data(serde_json::json!({}))];
+        assert_eq!(
+            validate_part_mime_types(&parts, &modes),
+            Err(UnacceptedMimeType { part_index: 2, mime_type: JSON_MIME_TYPE.to_string() })
+        );
+        assert!(validate_part_mime_types(&parts[..2], &modes).is_ok());
+    }
+}
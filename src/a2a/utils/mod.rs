@@ -4,10 +4,22 @@
 //! matching the functionality provided in a2a-python/src/a2a/utils/.
 
 pub mod artifact;
+pub mod canonical_json;
+pub mod cloudevents;
 pub mod constants;
+pub mod jws;
 pub mod message;
+pub mod metadata;
+pub mod mime;
 pub mod parts;
+pub mod replay;
+pub mod request_signing;
+pub mod sequence;
 pub mod task;
+#[cfg(feature = "server")]
+pub mod logging;
+#[cfg(feature = "runtime")]
+pub mod telemetry;
 
 // Re-export utility functions for convenience
 pub use artifact::*;
@@ -27,4 +39,16 @@ pub use parts::{
     get_text_parts as get_parts_text,
 };
 
+pub use canonical_json::{canonicalize, canonicalize_slice, to_canonical_vec};
+pub use cloudevents::{CloudEvent, IntoCloudEvent};
+pub use metadata::{merge_metadata, HasMetadata, MetadataKey, MetadataRegistry};
+pub use mime::{effective_mime_type, normalize_mime_type, normalize_part_mime_types, validate_part_mime_types};
+pub use sequence::{event_sequence, set_event_sequence, SequenceCheck, SequenceTracker, EVENT_SEQUENCE};
 pub use task::*;
+pub use jws::{sign_compact, Hs256Signer, JwsSigner};
+pub use replay::{replay_headers, replay_signature, verify_replay_signature, ReplayRequest};
+pub use request_signing::{RequestSignature, REQUEST_SIGNATURE_HEADER};
+#[cfg(feature = "server")]
+pub use logging::{init_logging, LogFormat};
+#[cfg(feature = "runtime")]
+pub use telemetry::TraceContext;
@@ -0,0 +1,120 @@
+//! Per-task event sequence numbers
+//!
+//! Status and artifact update events carry a sequence number in their
+//! metadata under [`EVENT_SEQUENCE`]. Numbers start at 1 for each task and
+//! increase by one per event, so a consumer can tell a replayed event from a
+//! new one and notice when events went missing. The last number assigned is
+//! kept in the task's own metadata under the same key.
+
+use crate::a2a::utils::metadata::{HasMetadata, MetadataKey};
+use std::collections::HashMap;
+
+/// Metadata key holding an event's sequence number
+pub const EVENT_SEQUENCE: MetadataKey<u64> = MetadataKey::new("a2a/sequence");
+
+/// Reads the sequence number of an event or task, if it carries a valid one
+pub fn event_sequence<M: HasMetadata>(item: &M) -> Option<u64> {
+    item.get_metadata(&EVENT_SEQUENCE).ok().flatten()
+}
+
+/// Sets the sequence number of an event or task
+pub fn set_event_sequence<M: HasMetadata>(item: &mut M, sequence: u64) {
+    item.metadata_mut()
+        .insert(EVENT_SEQUENCE.name().to_string(), sequence.into());
+}
+
+/// Outcome of checking an event's sequence number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceCheck {
+    /// The event directly follows the last one seen, or is the first seen
+    InOrder,
+    /// The event was already seen
+    Duplicate,
+    /// Events between the last one seen and this one are missing
+    Gap { expected: u64, received: u64 },
+    /// The event carries no sequence number
+    Unsequenced,
+}
+
+/// Tracks the last sequence number seen per task
+#[derive(Debug, Clone, Default)]
+pub struct SequenceTracker {
+    last_seen: HashMap<String, u64>,
+}
+
+impl SequenceTracker {
+    /// Creates an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a sequence number for a task and classifies it
+    ///
+    /// Duplicates leave the tracker unchanged; after a gap, tracking continues
+    /// from the received number.
+    pub fn observe(&mut self, task_id: &str, sequence: Option<u64>) -> SequenceCheck {
+        let Some(sequence) = sequence else {
+            return SequenceCheck::Unsequenced;
+        };
+        match self.last_seen.get(task_id).copied() {
+            Some(last) if sequence <= last => SequenceCheck::Duplicate,
+            Some(last) if sequence > last + 1 => {
+                self.last_seen.insert(task_id.to_string(), sequence);
+                SequenceCheck::Gap {
+                    expected: last + 1,
+                    received: sequence,
+                }
+            }
+            _ => {
+                self.last_seen.insert(task_id.to_string(), sequence);
+                SequenceCheck::InOrder
+            }
+        }
+    }
+
+    /// The last sequence number seen for a task
+    pub fn last(&self, task_id: &str) -> Option<u64> {
+        self.last_seen.get(task_id).copied()
+    }
+
+    /// Forgets a task
+    pub fn reset(&mut self, task_id: &str) {
+        self.last_seen.remove(task_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TaskState, TaskStatus, TaskStatusUpdateEvent};
+
+    #[test]
+    fn test_sequence_metadata_round_trip() {
+        let mut event = TaskStatusUpdateEvent::new(
+            "t1".to_string(),
+            "c1".to_string(),
+            TaskStatus::new(TaskState::Working),
+            false,
+        );
+        assert_eq!(event_sequence(&event), None);
+        set_event_sequence(&mut event, 7);
+        assert_eq!(event_sequence(&event), Some(7));
+        assert_eq!(event.metadata.unwrap()["a2a/sequence"], 7);
+    }
+
+    #[test]
+    fn test_tracker_classifies_events() {
+        let mut tracker = SequenceTracker::new();
+        assert_eq!(tracker.observe("t1", Some(1)), SequenceCheck::InOrder);
+        assert_eq!(tracker.observe("t1", Some(2)), SequenceCheck::InOrder);
+        assert_eq!(tracker.observe("t1", Some(2)), SequenceCheck::Duplicate);
+        assert_eq!(tracker.observe("t1", Some(5)), SequenceCheck::Gap { expected: 3, received: 5 });
+        assert_eq!(tracker.observe("t1", Some(6)), SequenceCheck::InOrder);
+        assert_eq!(tracker.observe("t1", None), SequenceCheck::Unsequenced);
+        assert_eq!(tracker.observe("t2", Some(4)), SequenceCheck::InOrder);
+        assert_eq!(tracker.last("t1"), Some(6));
+
+        tracker.reset("t1");
+        assert_eq!(tracker.last("t1"), None);
+    }
+}
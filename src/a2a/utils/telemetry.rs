@@ -0,0 +1,215 @@
+//! W3C Trace Context propagation utilities
+//!
+//! This module implements parsing and formatting of the `traceparent` and
+//! `tracestate` headers defined by the W3C Trace Context specification, so that
+//! multi-hop agent-to-agent calls can be stitched into a single distributed trace.
+//!
+//! The active context is carried in a task-local slot: the server scopes each
+//! request handler with the extracted context, and the client-side
+//! `TraceContextInterceptor` reads it back when an agent calls another agent.
+
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use uuid::Uuid;
+
+/// Header name carrying the trace parent
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Header name carrying vendor-specific trace state
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+/// The only traceparent version this implementation emits
+const TRACEPARENT_VERSION: &str = "00";
+
+/// Trace flag indicating the trace is sampled
+pub const TRACE_FLAG_SAMPLED: u8 = 0x01;
+
+tokio::task_local! {
+    static CURRENT_TRACE_CONTEXT: TraceContext;
+}
+
+/// A W3C trace context
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceContext {
+    /// 32 lowercase hex characters identifying the whole trace
+    pub trace_id: String,
+    /// 16 lowercase hex characters identifying the current span
+    pub span_id: String,
+    /// The span id of the caller, if this context was derived from one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_span_id: Option<String>,
+    /// Trace flags (bit 0 is the sampled flag)
+    pub trace_flags: u8,
+    /// Opaque vendor-specific trace state, propagated unchanged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_state: Option<String>,
+}
+
+impl TraceContext {
+    /// Creates a new root trace context with fresh trace and span ids
+    pub fn new_root() -> Self {
+        Self {
+            trace_id: new_trace_id(),
+            span_id: new_span_id(),
+            parent_span_id: None,
+            trace_flags: TRACE_FLAG_SAMPLED,
+            trace_state: None,
+        }
+    }
+
+    /// Creates a child context in the same trace with a fresh span id
+    pub fn child(&self) -> Self {
+        Self {
+            trace_id: self.trace_id.clone(),
+            span_id: new_span_id(),
+            parent_span_id: Some(self.span_id.clone()),
+            trace_flags: self.trace_flags,
+            trace_state: self.trace_state.clone(),
+        }
+    }
+
+    /// Sets the trace state
+    pub fn with_trace_state(mut self, trace_state: String) -> Self {
+        self.trace_state = Some(trace_state);
+        self
+    }
+
+    /// Returns true if the sampled flag is set
+    pub fn is_sampled(&self) -> bool {
+        self.trace_flags & TRACE_FLAG_SAMPLED != 0
+    }
+
+    /// Parses a `traceparent` header value and an optional `tracestate` value
+    ///
+    /// Returns `None` if the traceparent is malformed, as the specification
+    /// requires receivers to restart the trace in that case.
+    pub fn parse(traceparent: &str, tracestate: Option<&str>) -> Option<Self> {
+        let parts: Vec<&str> = traceparent.trim().split('-').collect();
+        if parts.len() < 4 {
+            return None;
+        }
+
+        let (version, trace_id, span_id, flags) = (parts[0], parts[1], parts[2], parts[3]);
+        if !is_lower_hex(version, 2) || version == "ff" {
+            return None;
+        }
+        // Version 00 has exactly four fields; later versions may append more
+        if version == TRACEPARENT_VERSION && parts.len() != 4 {
+            return None;
+        }
+        if !is_lower_hex(trace_id, 32) || trace_id.chars().all(|c| c == '0') {
+            return None;
+        }
+        if !is_lower_hex(span_id, 16) || span_id.chars().all(|c| c == '0') {
+            return None;
+        }
+        if !is_lower_hex(flags, 2) {
+            return None;
+        }
+        let trace_flags = u8::from_str_radix(flags, 16).ok()?;
+
+        let trace_state = tracestate
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string);
+
+        Some(Self {
+            trace_id: trace_id.to_string(),
+            span_id: span_id.to_string(),
+            parent_span_id: None,
+            trace_flags,
+            trace_state,
+        })
+    }
+
+    /// Extracts a trace context from HTTP headers
+    #[cfg(feature = "server")]
+    pub fn from_headers(headers: &axum::http::HeaderMap) -> Option<Self> {
+        let traceparent = headers.get(TRACEPARENT_HEADER)?.to_str().ok()?;
+        let tracestate = headers
+            .get(TRACESTATE_HEADER)
+            .and_then(|v| v.to_str().ok());
+        Self::parse(traceparent, tracestate)
+    }
+
+    /// Formats this context as a `traceparent` header value
+    pub fn to_traceparent(&self) -> String {
+        format!(
+            "{}-{}-{}-{:02x}",
+            TRACEPARENT_VERSION, self.trace_id, self.span_id, self.trace_flags
+        )
+    }
+
+    /// Returns the trace context active for the current task, if any
+    pub fn current() -> Option<Self> {
+        CURRENT_TRACE_CONTEXT.try_with(|ctx| ctx.clone()).ok()
+    }
+
+    /// Runs a future with this trace context as the current one
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_TRACE_CONTEXT.scope(self, future).await
+    }
+}
+
+fn new_trace_id() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+fn new_span_id() -> String {
+    Uuid::new_v4().simple().to_string()[..16].to_string()
+}
+
+fn is_lower_hex(value: &str, len: usize) -> bool {
+    value.len() == len && value.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_valid_traceparent() {
+        let ctx = TraceContext::parse(
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            Some("congo=t61rcWkgMzE"),
+        )
+        .unwrap();
+        assert_eq!(ctx.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(ctx.span_id, "00f067aa0ba902b7");
+        assert!(ctx.is_sampled());
+        assert_eq!(ctx.trace_state.as_deref(), Some("congo=t61rcWkgMzE"));
+        assert_eq!(
+            ctx.to_traceparent(),
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_traceparent() {
+        assert!(TraceContext::parse("", None).is_none());
+        assert!(TraceContext::parse("00-00000000000000000000000000000000-00f067aa0ba902b7-01", None).is_none());
+        assert!(TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01", None).is_none());
+        assert!(TraceContext::parse("ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01", None).is_none());
+        assert!(TraceContext::parse("00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01", None).is_none());
+        assert!(TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra", None).is_none());
+    }
+
+    #[test]
+    fn test_child_keeps_trace_id() {
+        let root = TraceContext::new_root();
+        let child = root.child();
+        assert_eq!(child.trace_id, root.trace_id);
+        assert_ne!(child.span_id, root.span_id);
+        assert_eq!(child.parent_span_id.as_deref(), Some(root.span_id.as_str()));
+        assert!(TraceContext::parse(&child.to_traceparent(), None).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_scope_sets_current() {
+        assert!(TraceContext::current().is_none());
+        let root = TraceContext::new_root();
+        let expected = root.clone();
+        let current = root.scope(async { TraceContext::current() }).await;
+        assert_eq!(current, Some(expected));
+    }
+}
@@ -0,0 +1,68 @@
+//! Writes a ready-to-run A2A agent project
+//!
+//! ```text
+//! a2a-scaffold <dir> [--name <crate>] [--store memory|sqlite] [--auth none|api-key] [--a2a-path <path>]
+//! ```
+//!
+//! The crate is named after the directory unless `--name` is given.
+
+use a2a_rust::a2a::scaffold::{AuthMode, Scaffold, StoreKind};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str =
+    "Usage: a2a-scaffold <dir> [--name <crate>] [--store memory|sqlite] [--auth none|api-key] [--a2a-path <path>]";
+
+fn run(args: Vec<String>) -> Result<(), String> {
+    let mut dir = None;
+    let mut name = None;
+    let mut store = StoreKind::default();
+    let mut auth = AuthMode::default();
+    let mut a2a_path = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value\n{}", arg, USAGE));
+        match arg.as_str() {
+            "--name" => name = Some(value()?),
+            "--store" => store = value()?.parse().map_err(|e| format!("{}", e))?,
+            "--auth" => auth = value()?.parse().map_err(|e| format!("{}", e))?,
+            "--a2a-path" => a2a_path = Some(PathBuf::from(value()?)),
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if arg.starts_with('-') || dir.is_some() => return Err(format!("Unexpected argument '{}'\n{}", arg, USAGE)),
+            _ => dir = Some(PathBuf::from(arg)),
+        }
+    }
+
+    let dir = dir.ok_or_else(|| USAGE.to_string())?;
+    let name = match name {
+        Some(name) => name,
+        None => dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| format!("Cannot name a crate after '{}', pass --name", dir.display()))?,
+    };
+    let mut scaffold = Scaffold::new(&name)
+        .map_err(|e| e.to_string())?
+        .with_store(store)
+        .with_auth(auth);
+    if let Some(path) = a2a_path {
+        scaffold = scaffold.with_a2a_path(path);
+    }
+
+    for path in scaffold.write_to(&dir).map_err(|e| e.to_string())? {
+        println!("created {}", path.display());
+    }
+    println!("\nRun the agent with: cd {} && cargo run", dir.display());
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1).collect()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
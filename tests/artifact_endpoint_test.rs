@@ -0,0 +1,132 @@
+//! File artifacts are linked to and streamed from the artifact endpoint
+
+use a2a_rust::a2a::core_types::{FileContent, FilePart, Message, Part, PartRoot, Role, TaskState, TaskStatus};
+use a2a_rust::a2a::models::*;
+use a2a_rust::a2a::server::apps::jsonrpc::A2AServerBuilder;
+use a2a_rust::a2a::server::request_handlers::DefaultRequestHandler;
+use a2a_rust::a2a::server::tasks::{InMemoryTaskStore, TaskStore};
+use a2a_rust::a2a::server::{ApiKeyContextBuilder, InMemoryArtifactStore};
+use a2a_rust::a2a::utils::constants::DEFAULT_RPC_URL;
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use base64::Engine;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tower::util::ServiceExt;
+
+const API_KEY: &str = "secret";
+
+fn card(secured: bool) -> AgentCard {
+    let card = AgentCard::new(
+        "Artifact Agent".to_string(),
+        "An agent producing files".to_string(),
+        "http://localhost:8080/a2a".to_string(),
+        "1.0.0".to_string(),
+        vec!["text/plain".to_string()],
+        vec!["text/plain".to_string()],
+        AgentCapabilities::new(),
+        vec![],
+    );
+    if secured {
+        card.with_security(vec![HashMap::from([("apiKey".to_string(), vec![])])])
+    } else {
+        card
+    }
+}
+
+fn payload() -> Vec<u8> {
+    (0..1000u32).map(|i| (i % 251) as u8).collect()
+}
+
+async fn router(secured: bool) -> Router {
+    let store = Arc::new(InMemoryTaskStore::new());
+    let file = FilePart::new_bytes(base64::engine::general_purpose::STANDARD.encode(payload()));
+    let task = Task::new("ctx-1".to_string(), TaskStatus::new(TaskState::InputRequired))
+        .with_task_id("task-1".to_string())
+        .with_artifacts(vec![Artifact::new(vec![file.into()]).with_artifact_id("video".to_string())]);
+    store.save(task).await.unwrap();
+
+    A2AServerBuilder::new()
+        .with_agent_card(card(secured))
+        .with_request_handler(Arc::new(DefaultRequestHandler::new(store, None, None)))
+        .with_context_builder(Arc::new(ApiKeyContextBuilder::new("x-api-key").with_key(API_KEY, "alice")))
+        .with_artifact_store(Arc::new(InMemoryArtifactStore::new()))
+        .build()
+        .unwrap()
+        .build_router()
+        .await
+}
+
+/// Continues the stored task, returning it as the message/send result
+async fn send_message(router: &Router) -> Value {
+    let message = Message::new(Role::User, vec![Part::text("more".to_string())])
+        .with_task_id("task-1".to_string())
+        .with_context_id("ctx-1".to_string());
+    let body = json!({"jsonrpc": "2.0", "id": 1, "method": "message/send", "params": {"message": message}});
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(DEFAULT_RPC_URL)
+        .header("content-type", "application/json")
+        .header("x-api-key", API_KEY)
+        .body(Body::from(serde_json::to_vec(&body).unwrap()))
+        .unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice::<Value>(&bytes).unwrap()["result"].clone()
+}
+
+async fn fetch(router: &Router, path: &str, headers: &[(&str, &str)]) -> (StatusCode, axum::http::HeaderMap, Vec<u8>) {
+    let mut request = Request::builder().uri(path);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let response = router.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+    let (parts, body) = response.into_parts();
+    let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+    (parts.status, parts.headers, bytes.to_vec())
+}
+
+#[tokio::test]
+async fn test_task_links_artifact_and_endpoint_serves_ranges() {
+    let router = router(false).await;
+
+    let task: Task = serde_json::from_value(send_message(&router).await).unwrap();
+    let artifact = &task.artifacts.unwrap()[0];
+    let PartRoot::File(FilePart { file: FileContent::Uri(file), .. }) = artifact.parts[0].root() else {
+        panic!("expected the artifact to be linked");
+    };
+    assert_eq!(file.uri, "http://localhost:8080/artifacts/task-1/video");
+
+    let (status, headers, body) = fetch(&router, "/artifacts/task-1/video", &[]).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::ACCEPT_RANGES], "bytes");
+    assert_eq!(body, payload());
+
+    let (status, headers, body) = fetch(&router, "/artifacts/task-1/video", &[("range", "bytes=100-199")]).await;
+    assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+    assert_eq!(headers[header::CONTENT_RANGE], "bytes 100-199/1000");
+    assert_eq!(body, payload()[100..200]);
+
+    let (status, headers, _) = fetch(&router, "/artifacts/task-1/video", &[("range", "bytes=5000-")]).await;
+    assert_eq!(status, StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(headers[header::CONTENT_RANGE], "bytes */1000");
+
+    let (status, _, _) = fetch(&router, "/artifacts/task-1/missing", &[]).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _, _) = fetch(&router, "/artifacts/other-task/video", &[]).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_secured_agents_require_an_authenticated_caller() {
+    let router = router(true).await;
+    send_message(&router).await;
+
+    let (status, _, _) = fetch(&router, "/artifacts/task-1/video", &[]).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _, body) = fetch(&router, "/artifacts/task-1/video", &[("x-api-key", API_KEY)]).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, payload());
+}
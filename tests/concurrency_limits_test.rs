@@ -0,0 +1,69 @@
+use a2a_rust::a2a::core_types::{Message, Part, Role, TaskState, TaskStatus};
+use a2a_rust::a2a::jsonrpc::error_codes;
+use a2a_rust::a2a::models::*;
+use a2a_rust::a2a::server::request_handlers::{ConcurrencyLimits, DefaultRequestHandler, OverLimit, RequestHandler};
+use a2a_rust::a2a::server::tasks::{InMemoryTaskStore, TaskStore};
+use std::sync::Arc;
+use std::time::Duration;
+
+async fn handler(limits: ConcurrencyLimits) -> Arc<DefaultRequestHandler> {
+    let store = Arc::new(InMemoryTaskStore::new());
+    let task = Task::new("ctx-1".to_string(), TaskStatus::new(TaskState::Working))
+        .with_task_id("task-1".to_string());
+    store.save(task).await.unwrap();
+    Arc::new(DefaultRequestHandler::new(store, None, None).with_concurrency_limits(limits))
+}
+
+fn follow_up(task_id: &str) -> MessageSendParams {
+    let mut message = Message::new(Role::User, vec![Part::text("again".to_string())]);
+    message.task_id = Some(task_id.to_string());
+    MessageSendParams::new(message)
+}
+
+#[tokio::test]
+async fn test_second_message_for_running_task_is_rejected() {
+    let handler = handler(ConcurrencyLimits::new().with_serialized_tasks()).await;
+
+    let stream = handler.on_message_send_stream(follow_up("task-1"), None).await.unwrap();
+    let err = handler.on_message_send(follow_up("task-1"), None).await.unwrap_err();
+    assert_eq!(err.code(), error_codes::CONCURRENCY_LIMIT_EXCEEDED);
+    assert_eq!(err.kind(), "concurrency_limit_exceeded");
+    assert!(err.details().retryable);
+
+    // Dropping the stream releases the task
+    drop(stream);
+    handler.on_message_send(follow_up("task-1"), None).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_second_message_waits_when_configured() {
+    let handler = handler(
+        ConcurrencyLimits::new()
+            .with_serialized_tasks()
+            .with_over_limit(OverLimit::Wait { timeout: Some(Duration::from_secs(5)) }),
+    )
+    .await;
+
+    let stream = handler.on_message_send_stream(follow_up("task-1"), None).await.unwrap();
+    let waiting = tokio::spawn({
+        let handler = handler.clone();
+        async move { handler.on_message_send(follow_up("task-1"), None).await }
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(!waiting.is_finished());
+
+    drop(stream);
+    waiting.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_context_limit_applies_across_tasks() {
+    let handler = handler(ConcurrencyLimits::new().with_max_per_context(1)).await;
+    let mut message = Message::new(Role::User, vec![Part::text("hi".to_string())]);
+    message.context_id = Some("ctx-1".to_string());
+
+    let _stream = handler.on_message_send_stream(follow_up("task-1"), None).await.unwrap();
+    let err = handler.on_message_send(MessageSendParams::new(message), None).await.unwrap_err();
+    assert_eq!(err.code(), error_codes::CONCURRENCY_LIMIT_EXCEEDED);
+    assert_eq!(err.data().unwrap()["scope"], "context");
+}
@@ -0,0 +1,73 @@
+use a2a_rust::a2a::core_types::{Message, Part, Role, TaskState, TaskStatus};
+use a2a_rust::a2a::error::A2AError;
+use a2a_rust::a2a::models::*;
+use a2a_rust::a2a::server::request_handlers::{DefaultRequestHandler, Event, MessageSendResult, RequestHandler};
+use a2a_rust::a2a::server::tasks::{InMemoryTaskStore, TaskStore};
+use a2a_rust::a2a::server::{MaxTextLength, ValidatingRequestHandler};
+use a2a_rust::a2a::utils::constants::DRY_RUN_METADATA_KEY;
+use futures::StreamExt;
+use std::sync::Arc;
+
+fn message(text: &str) -> Message {
+    Message::new(Role::User, vec![Part::text(text.to_string())])
+}
+
+fn task_of(result: MessageSendResult) -> Task {
+    match result {
+        MessageSendResult::Task(task) => task,
+        other => panic!("expected task, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_dry_run_returns_the_task_without_persisting_it() {
+    let store = Arc::new(InMemoryTaskStore::new());
+    let handler = DefaultRequestHandler::new(store.clone(), None, None);
+    let params = MessageSendParams::new(message("hello").with_context_id("ctx-1".to_string())).with_dry_run();
+    assert!(params.is_dry_run());
+
+    let task = task_of(handler.on_message_send(params, None).await.unwrap());
+    assert_eq!(task.context_id, "ctx-1");
+    assert_eq!(task.status.state, TaskState::Submitted);
+    assert_eq!(task.history.as_ref().unwrap().len(), 1);
+    assert_eq!(task.metadata.unwrap()[DRY_RUN_METADATA_KEY], true);
+    assert!(store.list().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_dry_run_of_a_follow_up_leaves_the_task_unchanged() {
+    let store = Arc::new(InMemoryTaskStore::new());
+    let existing = Task::new("ctx-1".to_string(), TaskStatus::new(TaskState::InputRequired))
+        .with_task_id("task-1".to_string())
+        .with_history(vec![message("first")]);
+    store.save(existing.clone()).await.unwrap();
+    let handler = DefaultRequestHandler::new(store.clone(), None, None);
+
+    let params = MessageSendParams::new(message("again").with_task_id("task-1".to_string())).with_dry_run();
+    let task = task_of(handler.on_message_send(params, None).await.unwrap());
+    assert_eq!(task.id, "task-1");
+    assert_eq!(task.context_id, "ctx-1");
+    assert_eq!(task.history.unwrap().len(), 2);
+    assert_eq!(store.get("task-1").await.unwrap().unwrap(), existing);
+
+    let params = MessageSendParams::new(message("again").with_task_id("missing".to_string())).with_dry_run();
+    let err = handler.on_message_send(params, None).await.unwrap_err();
+    assert!(matches!(err, A2AError::TaskNotFound(_)));
+}
+
+#[tokio::test]
+async fn test_dry_run_is_validated_and_streams_a_single_task() {
+    let store = Arc::new(InMemoryTaskStore::new());
+    let handler = ValidatingRequestHandler::new(Arc::new(DefaultRequestHandler::new(store.clone(), None, None)))
+        .with_validator(Arc::new(MaxTextLength::new(10)));
+
+    let params = MessageSendParams::new(message("far too long for the agent")).with_dry_run();
+    let err = handler.on_message_send(params, None).await.unwrap_err();
+    assert!(matches!(err, A2AError::InvalidParams(_)));
+
+    let params = MessageSendParams::new(message("short")).with_dry_run();
+    let events: Vec<_> = handler.on_message_send_stream(params, None).await.unwrap().collect().await;
+    assert_eq!(events.len(), 1);
+    assert!(matches!(&events[0], Ok(Event::Task(task)) if task.status.state == TaskState::Submitted));
+    assert!(store.list().await.unwrap().is_empty());
+}
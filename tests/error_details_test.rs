@@ -0,0 +1,95 @@
+//! Structured error details sent as the JSON-RPC error `data`
+
+use a2a_rust::a2a::error::{A2AError, ErrorDetails};
+use a2a_rust::a2a::jsonrpc::{error_codes, JSONRPCError};
+use a2a_rust::a2a::server::context::ServerCallContext;
+use a2a_rust::a2a::server::request_handlers::{DefaultRequestHandler, JSONRPCHandler};
+use a2a_rust::a2a::server::tasks::InMemoryTaskStore;
+use a2a_rust::{AgentCapabilities, AgentCard};
+use serde_json::json;
+use std::sync::Arc;
+
+fn handler() -> JSONRPCHandler {
+    let card = AgentCard::new(
+        "Test Agent".to_string(),
+        "A test agent".to_string(),
+        "http://localhost:8080".to_string(),
+        "1.0.0".to_string(),
+        vec!["text/plain".to_string()],
+        vec!["text/plain".to_string()],
+        AgentCapabilities::new(),
+        vec![],
+    );
+    let request_handler = DefaultRequestHandler::new(Arc::new(InMemoryTaskStore::new()), None, None);
+    JSONRPCHandler::new(card, Arc::new(request_handler))
+}
+
+#[test]
+fn test_details_follow_the_error_variant() {
+    let details = A2AError::task_not_found("t1").details();
+    assert_eq!(details.kind, "task_not_found");
+    assert!(!details.retryable);
+    assert!(details.hint.is_some());
+    assert!(details.docs_url.starts_with("https://"));
+
+    assert!(A2AError::transport_error("connection reset".to_string()).details().retryable);
+    assert_eq!(
+        A2AError::jsonrpc_error(error_codes::QUOTA_EXCEEDED, "slow down".to_string()).details().kind,
+        "quota_exceeded"
+    );
+}
+
+#[test]
+fn test_invalid_params_name_the_offending_field() {
+    let details = A2AError::invalid_params("Invalid params: missing field `message` at line 1 column 2").details();
+    assert_eq!(details.kind, "invalid_params");
+    assert_eq!(details.field.as_deref(), Some("message"));
+
+    assert_eq!(A2AError::invalid_params("bad task id").details().field, None);
+}
+
+#[test]
+fn test_error_data_keeps_existing_members() {
+    let error: JSONRPCError = A2AError::task_not_found("t1").into();
+    let data = error.data.unwrap();
+    assert_eq!(data["kind"], "task_not_found");
+    assert_eq!(data["retryable"], false);
+    assert_eq!(data["task_id"], "t1");
+    assert_eq!(error.code, -32001);
+}
+
+#[test]
+fn test_remote_details_survive_the_client_conversion() {
+    let wire = JSONRPCError::new(-32603, "Storage unavailable".to_string()).with_data(json!({
+        "kind": "storage_unavailable",
+        "retryable": true,
+        "docsUrl": "https://example.com/errors#storage"
+    }));
+    let error: A2AError = wire.into();
+    assert_eq!(error.code(), -32603);
+
+    let details: ErrorDetails = error.details();
+    assert_eq!(details.kind, "storage_unavailable");
+    assert!(details.retryable);
+    assert_eq!(details.docs_url, "https://example.com/errors#storage");
+}
+
+#[tokio::test]
+async fn test_handler_sends_details_for_bad_params() {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "message/send",
+        "params": {"configuration": {}}
+    });
+    let error = handler()
+        .handle_request(request, &ServerCallContext::new())
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, -32602);
+    let data = error.data.unwrap();
+    assert_eq!(data["kind"], "invalid_params");
+    assert_eq!(data["field"], "message");
+    assert!(data["hint"].is_string());
+}
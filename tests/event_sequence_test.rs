@@ -0,0 +1,167 @@
+//! Client-side validation of streamed event sequence numbers
+
+use a2a_rust::a2a::client::client_trait::{
+    BaseClient, Client, ClientCallContext, ClientEvent, ClientEventOrMessage, ClientTransport, TaskUpdateEvent,
+};
+use a2a_rust::a2a::client::config::ClientConfig;
+use a2a_rust::a2a::client::factory::minimal_agent_card;
+use a2a_rust::a2a::core_types::*;
+use a2a_rust::a2a::error::A2AError;
+use a2a_rust::a2a::models::*;
+use a2a_rust::a2a::utils::sequence::set_event_sequence;
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::pin::Pin;
+
+/// Transport streaming status updates with the given sequence numbers
+struct SequencedTransport {
+    sequences: Vec<u64>,
+}
+
+impl SequencedTransport {
+    fn updates(&self) -> Vec<TaskStatusUpdateEvent> {
+        self.sequences
+            .iter()
+            .map(|sequence| {
+                let mut update = TaskStatusUpdateEvent::new(
+                    "task-1".to_string(),
+                    "ctx-1".to_string(),
+                    TaskStatus::new(TaskState::Working),
+                    false,
+                );
+                set_event_sequence(&mut update, *sequence);
+                update
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl ClientTransport for SequencedTransport {
+    async fn send_message(
+        &self,
+        _params: MessageSendParams,
+        _context: Option<&ClientCallContext>,
+        _extensions: Option<Vec<String>>,
+    ) -> Result<TaskOrMessage, A2AError> {
+        Err(A2AError::unsupported_operation("Only streaming is supported"))
+    }
+
+    async fn send_message_streaming<'a>(
+        &'a self,
+        _params: MessageSendParams,
+        _context: Option<&ClientCallContext>,
+        _extensions: Option<Vec<String>>,
+    ) -> Result<Pin<Box<dyn futures::Stream<Item = Result<TaskOrMessage, A2AError>> + Send + 'a>>, A2AError> {
+        let updates = self.updates().into_iter().map(|update| Ok(TaskOrMessage::TaskUpdate(update)));
+        Ok(Box::pin(futures::stream::iter(updates)))
+    }
+
+    async fn get_task(
+        &self,
+        _request: TaskQueryParams,
+        _context: Option<&ClientCallContext>,
+        _extensions: Option<Vec<String>>,
+    ) -> Result<Task, A2AError> {
+        Err(A2AError::unsupported_operation("Not supported"))
+    }
+
+    async fn cancel_task(
+        &self,
+        _request: TaskIdParams,
+        _context: Option<&ClientCallContext>,
+        _extensions: Option<Vec<String>>,
+    ) -> Result<Task, A2AError> {
+        Err(A2AError::unsupported_operation("Not supported"))
+    }
+
+    async fn set_task_callback(
+        &self,
+        _request: TaskPushNotificationConfig,
+        _context: Option<&ClientCallContext>,
+        _extensions: Option<Vec<String>>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        Err(A2AError::unsupported_operation("Not supported"))
+    }
+
+    async fn get_task_callback(
+        &self,
+        _request: GetTaskPushNotificationConfigParams,
+        _context: Option<&ClientCallContext>,
+        _extensions: Option<Vec<String>>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        Err(A2AError::unsupported_operation("Not supported"))
+    }
+
+    async fn resubscribe<'a>(
+        &'a self,
+        _request: TaskIdParams,
+        _context: Option<&ClientCallContext>,
+        _extensions: Option<Vec<String>>,
+    ) -> Result<Pin<Box<dyn futures::Stream<Item = Result<ClientEvent, A2AError>> + Send + 'a>>, A2AError> {
+        Err(A2AError::unsupported_operation("Not supported"))
+    }
+
+    async fn get_card(
+        &self,
+        _context: Option<&ClientCallContext>,
+        _extensions: Option<Vec<String>>,
+    ) -> Result<AgentCard, A2AError> {
+        Err(A2AError::unsupported_operation("Not supported"))
+    }
+
+    async fn close(&self) -> Result<(), A2AError> {
+        Ok(())
+    }
+}
+
+fn client(sequences: Vec<u64>, validate: bool) -> BaseClient {
+    BaseClient::new(
+        minimal_agent_card("http://localhost".to_string(), None),
+        ClientConfig::new().with_event_sequence_validation(validate),
+        Box::new(SequencedTransport { sequences }),
+        vec![],
+        vec![],
+    )
+}
+
+async fn received(client: &BaseClient) -> Vec<Result<Option<u64>, A2AError>> {
+    let message = Message::new(Role::User, vec![Part::text("hi".to_string())]);
+    client
+        .send_message(message, None, None, None)
+        .await
+        .map(|item| {
+            item.map(|event| match event {
+                ClientEventOrMessage::Event((_, Some(update @ TaskUpdateEvent::Status(_)))) => update.sequence(),
+                _ => None,
+            })
+        })
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn test_duplicates_are_dropped() {
+    let client = client(vec![1, 2, 2, 3], true);
+    let sequences: Vec<Option<u64>> = received(&client).await.into_iter().map(Result::unwrap).collect();
+    assert_eq!(sequences, vec![Some(1), Some(2), Some(3)]);
+
+    // Replaying the same events on a later stream yields nothing new
+    assert!(received(&client).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_gap_fails_stream() {
+    let client = client(vec![1, 3], true);
+    let items = received(&client).await;
+    assert_eq!(items.len(), 2);
+    assert!(items[0].is_ok());
+    let error = items[1].as_ref().unwrap_err().to_string();
+    assert!(error.contains("expected sequence 2, received 3"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_validation_is_opt_in() {
+    let client = client(vec![1, 1, 5], false);
+    assert_eq!(received(&client).await.len(), 3);
+}
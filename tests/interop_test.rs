@@ -91,6 +91,7 @@ fn test_task_serialization_compatibility() {
                 reference_task_ids: None,
             }
         ]),
+        state_transitions: None,
         metadata: None,
     };
 
@@ -102,7 +103,7 @@ fn test_task_serialization_compatibility() {
     
     assert_eq!(parsed["kind"], "task");
     assert_eq!(parsed["id"], "task-123");
-    assert_eq!(parsed["context_id"], "ctx-456");
+    assert_eq!(parsed["contextId"], "ctx-456");
     assert_eq!(parsed["status"]["state"], "working");
     assert_eq!(parsed["status"]["timestamp"], "2023-10-27T10:00:00Z");
     assert!(parsed["artifacts"].is_array());
@@ -133,8 +134,8 @@ fn test_task_status_update_event_compatibility() {
     let parsed: serde_json::Value = serde_json::from_str(&json).expect("Failed to parse JSON");
     
     assert_eq!(parsed["kind"], "status-update");
-    assert_eq!(parsed["task_id"], "task-123");
-    assert_eq!(parsed["context_id"], "ctx-456");
+    assert_eq!(parsed["taskId"], "task-123");
+    assert_eq!(parsed["contextId"], "ctx-456");
     assert_eq!(parsed["status"]["state"], "completed");
     assert_eq!(parsed["final"], true);
 }
@@ -147,6 +148,8 @@ fn test_push_notification_config_compatibility() {
         url,
         token: Some("token-456".to_string()),
         authentication: None,
+        filter: None,
+        payload_format: None,
     };
 
     // Serialize to JSON
@@ -168,6 +171,8 @@ fn test_task_push_notification_config_compatibility() {
         url,
         token: Some("token-456".to_string()),
         authentication: None,
+        filter: None,
+        payload_format: None,
     };
 
     let task_config = TaskPushNotificationConfig {
@@ -181,9 +186,9 @@ fn test_task_push_notification_config_compatibility() {
     // Verify the JSON structure matches Python expectations
     let parsed: serde_json::Value = serde_json::from_str(&json).expect("Failed to parse JSON");
     
-    assert_eq!(parsed["task_id"], "task-789");
-    assert_eq!(parsed["push_notification_config"]["id"], "config-123");
-    assert_eq!(parsed["push_notification_config"]["token"], "token-456");
+    assert_eq!(parsed["taskId"], "task-789");
+    assert_eq!(parsed["pushNotificationConfig"]["id"], "config-123");
+    assert_eq!(parsed["pushNotificationConfig"]["token"], "token-456");
 }
 
 #[test]
@@ -201,7 +206,7 @@ fn test_delete_task_push_notification_config_params_compatibility() {
     let parsed: serde_json::Value = serde_json::from_str(&json).expect("Failed to parse JSON");
     
     assert_eq!(parsed["id"], "task-123");
-    assert_eq!(parsed["push_notification_config_id"], "config-456");
+    assert_eq!(parsed["pushNotificationConfigId"], "config-456");
 }
 
 #[test]
@@ -219,7 +224,7 @@ fn test_get_task_push_notification_config_params_compatibility() {
     let parsed: serde_json::Value = serde_json::from_str(&json).expect("Failed to parse JSON");
     
     assert_eq!(parsed["id"], "task-123");
-    assert_eq!(parsed["push_notification_config_id"], "config-456");
+    assert_eq!(parsed["pushNotificationConfigId"], "config-456");
 }
 
 #[test]
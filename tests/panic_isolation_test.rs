@@ -0,0 +1,220 @@
+//! A panicking request handler fails only the request that hit it
+
+use a2a_rust::a2a::core_types::{TaskState, TaskStatus};
+use a2a_rust::a2a::error::A2AError;
+use a2a_rust::a2a::models::*;
+use a2a_rust::a2a::server::apps::jsonrpc::{A2AServerBuilder, ServerConfig};
+use a2a_rust::a2a::server::context::{DefaultServerCallContextBuilder, ServerCallContext};
+use a2a_rust::a2a::server::events::MetricsSubscriber;
+use a2a_rust::a2a::server::request_handlers::request_handler::{Event, MockRequestHandler, TaskPushNotificationConfigQueryParams};
+use a2a_rust::a2a::server::request_handlers::{MessageSendResult, RequestHandler};
+use a2a_rust::a2a::utils::constants::DEFAULT_RPC_URL;
+use async_trait::async_trait;
+use axum::body::Body;
+use axum::http::{Method, Request};
+use axum::Router;
+use futures::stream::{self, BoxStream, StreamExt};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tower::util::ServiceExt;
+
+/// Panics on `message/send` and halfway through `message/stream`, but takes
+/// a while to answer the `message/send` of message `slow`
+struct PanickingHandler {
+    inner: MockRequestHandler,
+    slow_send_finished: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl RequestHandler for PanickingHandler {
+    async fn on_get_task(
+        &self,
+        params: TaskQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        self.inner.on_get_task(params, context).await
+    }
+
+    async fn on_cancel_task(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Option<Task>, A2AError> {
+        self.inner.on_cancel_task(params, context).await
+    }
+
+    async fn on_message_send(
+        &self,
+        params: MessageSendParams,
+        _context: Option<&ServerCallContext>,
+    ) -> Result<MessageSendResult, A2AError> {
+        if params.message.message_id == "slow" {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            self.slow_send_finished.store(true, Ordering::SeqCst);
+            return Ok(MessageSendResult::Message(params.message));
+        }
+        panic!("executor bug");
+    }
+
+    async fn on_message_send_stream(
+        &self,
+        _params: MessageSendParams,
+        _context: Option<&ServerCallContext>,
+    ) -> Result<BoxStream<'static, Result<Event, A2AError>>, A2AError> {
+        let stream = stream::iter(0..2).map(|i| {
+            if i == 1 {
+                panic!("stream bug");
+            }
+            let task = Task::new("ctx-1".to_string(), TaskStatus::new(TaskState::Working))
+                .with_task_id("task-1".to_string());
+            Ok(Event::Task(task))
+        });
+        Ok(stream.boxed())
+    }
+
+    async fn on_set_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfig,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_set_task_push_notification_config(params, context).await
+    }
+
+    async fn on_get_task_push_notification_config(
+        &self,
+        params: TaskPushNotificationConfigQueryParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<TaskPushNotificationConfig, A2AError> {
+        self.inner.on_get_task_push_notification_config(params, context).await
+    }
+
+    async fn on_list_task_push_notification_config(
+        &self,
+        params: TaskIdParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<Vec<TaskPushNotificationConfig>, A2AError> {
+        self.inner.on_list_task_push_notification_config(params, context).await
+    }
+
+    async fn on_delete_task_push_notification_config(
+        &self,
+        params: DeleteTaskPushNotificationConfigParams,
+        context: Option<&ServerCallContext>,
+    ) -> Result<(), A2AError> {
+        self.inner.on_delete_task_push_notification_config(params, context).await
+    }
+}
+
+async fn router(metrics: Arc<MetricsSubscriber>) -> Router {
+    router_with_flag(metrics, Arc::new(AtomicBool::new(false))).await
+}
+
+async fn router_with_flag(metrics: Arc<MetricsSubscriber>, slow_send_finished: Arc<AtomicBool>) -> Router {
+    let card = AgentCard::new(
+        "Test Agent".to_string(),
+        "A test agent".to_string(),
+        "http://localhost:8080".to_string(),
+        "1.0.0".to_string(),
+        vec!["text/plain".to_string()],
+        vec!["text/plain".to_string()],
+        AgentCapabilities::new().with_streaming(true),
+        vec![],
+    );
+    A2AServerBuilder::new()
+        .with_agent_card(card)
+        .with_request_handler(Arc::new(PanickingHandler {
+            inner: MockRequestHandler::new(),
+            slow_send_finished,
+        }))
+        .with_context_builder(Arc::new(DefaultServerCallContextBuilder))
+        .with_config(ServerConfig::default())
+        .with_metrics(metrics)
+        .build()
+        .unwrap()
+        .build_router()
+        .await
+}
+
+fn rpc(method: &str) -> Request<Body> {
+    rpc_with_message_id(method, "m1")
+}
+
+fn rpc_with_message_id(method: &str, message_id: &str) -> Request<Body> {
+    let body = json!({
+        "jsonrpc": "2.0",
+        "id": 7,
+        "method": method,
+        "params": {
+            "message": {
+                "kind": "message",
+                "messageId": message_id,
+                "role": "user",
+                "parts": [{"kind": "text", "text": "hi"}]
+            }
+        }
+    });
+    Request::builder()
+        .method(Method::POST)
+        .uri(DEFAULT_RPC_URL)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+async fn body_text(response: axum::response::Response) -> String {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_panic_becomes_internal_error_with_correlation_id() {
+    let metrics = Arc::new(MetricsSubscriber::new());
+    let router = router(metrics.clone()).await;
+
+    let response = router.clone().oneshot(rpc("message/send")).await.unwrap();
+    let body: Value = serde_json::from_str(&body_text(response).await).unwrap();
+    assert_eq!(body["id"], 7);
+    assert_eq!(body["error"]["code"], -32603);
+    let correlation_id = body["error"]["data"]["correlation_id"].as_str().unwrap();
+    assert!(body["error"]["message"].as_str().unwrap().contains(correlation_id));
+    assert!(!body.to_string().contains("executor bug"));
+    assert_eq!(metrics.snapshot().handler_panics, 1);
+
+    // The server keeps serving other requests
+    let response = router.oneshot(rpc("tasks/get")).await.unwrap();
+    let body: Value = serde_json::from_str(&body_text(response).await).unwrap();
+    assert!(body.get("error").is_none());
+}
+
+#[tokio::test]
+async fn test_panic_mid_stream_ends_with_error_event() {
+    let metrics = Arc::new(MetricsSubscriber::new());
+    let response = router(metrics.clone()).await.oneshot(rpc("message/stream")).await.unwrap();
+    let body = body_text(response).await;
+
+    let frames: Vec<Value> = body
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .map(|data| serde_json::from_str(data).unwrap())
+        .collect();
+    assert_eq!(frames.len(), 2, "{}", body);
+    assert_eq!(frames[0]["result"]["id"], "task-1");
+    assert_eq!(frames[1]["error"]["code"], -32603);
+    assert_eq!(frames[1]["id"], 7);
+    assert!(frames[1]["error"]["data"]["correlation_id"].is_string());
+    assert_eq!(metrics.snapshot().handler_panics, 1);
+}
+
+#[tokio::test]
+async fn test_dropped_request_cancels_the_handler() {
+    let slow_send_finished = Arc::new(AtomicBool::new(false));
+    let router = router_with_flag(Arc::new(MetricsSubscriber::new()), slow_send_finished.clone()).await;
+
+    let request = router.oneshot(rpc_with_message_id("message/send", "slow"));
+    assert!(tokio::time::timeout(Duration::from_millis(50), request).await.is_err());
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert!(!slow_send_finished.load(Ordering::SeqCst));
+}
@@ -0,0 +1,181 @@
+//! Field name parity of request params with a2a-python
+//!
+//! Params are emitted with the spec's camelCase names and accepted in both
+//! camelCase and snake_case.
+
+use a2a_rust::a2a::server::tasks::{TaskSearchFilter, TaskSearchParams};
+use a2a_rust::{
+    DeleteTaskPushNotificationConfigParams, GetTaskPushNotificationConfigParams, ListTaskPushNotificationConfigParams,
+    Message, MessageSendConfiguration, MessageSendParams, NotificationEventKind, NotificationFilter,
+    NotificationPayloadFormat, Part, PushNotificationAuthenticationInfo, PushNotificationConfig, ResubscribeFilter,
+    Role, StreamEventKind, TaskIdParams, TaskPushNotificationConfig, TaskQueryParams, TaskState,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// Every object key in `value`, at any depth, outside of `metadata`
+fn keys(value: &Value, found: &mut Vec<String>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                found.push(key.clone());
+                if key != "metadata" {
+                    keys(value, found);
+                }
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|value| keys(value, found)),
+        _ => {}
+    }
+}
+
+/// Serializes `params`, checks every field name is camelCase and parses them back
+fn assert_camel_case_round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(params: T) {
+    let json = serde_json::to_value(&params).unwrap();
+    let mut found = Vec::new();
+    keys(&json, &mut found);
+    assert!(found.iter().all(|key| !key.contains('_')), "snake_case field in {}", json);
+    assert_eq!(serde_json::from_value::<T>(json).unwrap(), params);
+}
+
+#[test]
+fn test_query_params_accept_both_spellings() {
+    let camel: TaskQueryParams = serde_json::from_value(json!({"id": "t1", "historyLength": 3})).unwrap();
+    let snake: TaskQueryParams = serde_json::from_value(json!({"id": "t1", "history_length": 3})).unwrap();
+    assert_eq!(camel.history_length, Some(3));
+    assert_eq!(camel, snake);
+}
+
+#[test]
+fn test_send_params_accept_snake_case_configuration() {
+    let params: MessageSendParams = serde_json::from_value(json!({
+        "message": {
+            "kind": "message",
+            "messageId": "m1",
+            "role": "user",
+            "parts": [{"kind": "text", "text": "hi"}]
+        },
+        "configuration": {
+            "accepted_output_modes": ["text/plain"],
+            "history_length": 2,
+            "push_notification_config": {"url": "https://example.com/hook", "payload_format": "slim"}
+        }
+    }))
+    .unwrap();
+
+    let configuration = params.configuration.unwrap();
+    assert_eq!(configuration.accepted_output_modes, Some(vec!["text/plain".to_string()]));
+    assert_eq!(configuration.history_length, Some(2));
+    assert!(configuration.push_notification_config.unwrap().payload_format.is_some());
+}
+
+#[test]
+fn test_params_emit_camel_case() {
+    let configuration = serde_json::to_value(
+        MessageSendConfiguration::new()
+            .with_accepted_output_modes(vec!["text/plain".to_string()])
+            .with_history_length(5),
+    )
+    .unwrap();
+    assert_eq!(configuration["acceptedOutputModes"], json!(["text/plain"]));
+    assert_eq!(configuration["historyLength"], 5);
+    assert!(configuration.get("history_length").is_none());
+
+    let resubscribe =
+        serde_json::to_value(TaskIdParams::new("t1".to_string()).with_from_event_id("ev1-2".to_string())).unwrap();
+    assert_eq!(resubscribe["fromEventId"], "ev1-2");
+
+    let get = serde_json::to_value(
+        GetTaskPushNotificationConfigParams::new("t1".to_string()).with_push_notification_config_id("c1".to_string()),
+    )
+    .unwrap();
+    assert_eq!(get["pushNotificationConfigId"], "c1");
+}
+
+#[test]
+fn test_every_param_type_round_trips_in_camel_case() {
+    let metadata = HashMap::from([("trace".to_string(), json!("abc"))]);
+    let push_config = PushNotificationConfig::new("https://example.com/hook".parse().unwrap())
+        .with_id("c1".to_string())
+        .with_token("secret".to_string())
+        .with_authentication(
+            PushNotificationAuthenticationInfo::new(vec!["Bearer".to_string()]).with_credentials("token".to_string()),
+        )
+        .with_filter(
+            NotificationFilter::new()
+                .with_states(vec![TaskState::Completed])
+                .with_event_kinds(vec![NotificationEventKind::StatusUpdate]),
+        )
+        .with_payload_format(NotificationPayloadFormat::Slim);
+    let configuration = MessageSendConfiguration::new()
+        .with_accepted_output_modes(vec!["text/plain".to_string()])
+        .with_blocking(true)
+        .with_history_length(5)
+        .with_push_notification_config(push_config.clone());
+    let message = Message::new(Role::User, vec![Part::text("hi".to_string())]);
+
+    assert_camel_case_round_trip(push_config.clone());
+    assert_camel_case_round_trip(TaskPushNotificationConfig::new("t1".to_string(), push_config));
+    assert_camel_case_round_trip(
+        MessageSendParams::new(message)
+            .with_configuration(configuration)
+            .with_metadata(metadata.clone()),
+    );
+    assert_camel_case_round_trip(
+        TaskIdParams::new("t1".to_string())
+            .with_metadata(metadata.clone())
+            .with_from_event_id("ev1-2".to_string())
+            .with_filter(ResubscribeFilter::new().with_kinds(vec![StreamEventKind::StatusUpdate])),
+    );
+    assert_camel_case_round_trip(
+        TaskQueryParams::new("t1".to_string())
+            .with_history_length(3)
+            .with_metadata(metadata.clone()),
+    );
+    assert_camel_case_round_trip(
+        DeleteTaskPushNotificationConfigParams::new("t1".to_string(), "c1".to_string()).with_metadata(metadata.clone()),
+    );
+    assert_camel_case_round_trip(
+        GetTaskPushNotificationConfigParams::new("t1".to_string())
+            .with_push_notification_config_id("c1".to_string())
+            .with_metadata(metadata.clone()),
+    );
+    assert_camel_case_round_trip(ListTaskPushNotificationConfigParams::new("t1".to_string()).with_metadata(metadata));
+    assert_camel_case_round_trip(TaskSearchParams {
+        query: "invoice".to_string(),
+        filter: TaskSearchFilter::default().with_context_id("ctx").with_states(vec![TaskState::Working]),
+    });
+}
+
+#[test]
+fn test_task_push_config_round_trips_python_payload() {
+    let python = json!({
+        "task_id": "t1",
+        "push_notification_config": {"id": "c1", "url": "https://example.com/hook"}
+    });
+    let config: TaskPushNotificationConfig = serde_json::from_value(python).unwrap();
+
+    let emitted = serde_json::to_value(&config).unwrap();
+    assert_eq!(emitted["taskId"], "t1");
+    assert_eq!(emitted["pushNotificationConfig"]["id"], "c1");
+}
+
+#[cfg(feature = "strict-params")]
+#[test]
+fn test_strict_params_reject_unknown_fields() {
+    let err = serde_json::from_value::<TaskQueryParams>(json!({"id": "t1", "historyLenght": 3})).unwrap_err();
+    assert!(err.to_string().contains("unknown field"));
+
+    // Both spellings of known fields are still accepted
+    serde_json::from_value::<TaskQueryParams>(json!({"id": "t1", "history_length": 3})).unwrap();
+}
+
+#[cfg(not(feature = "strict-params"))]
+#[test]
+fn test_unknown_fields_are_ignored_by_default() {
+    let params: TaskQueryParams = serde_json::from_value(json!({"id": "t1", "historyLenght": 3})).unwrap();
+    assert_eq!(params.history_length, None);
+}
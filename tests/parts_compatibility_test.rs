@@ -90,7 +90,7 @@ fn test_file_part_with_bytes_compatibility() {
     // Verify the JSON structure
     let json_value: serde_json::Value = serde_json::from_str(&serialized).unwrap();
     assert_eq!(json_value["file"]["bytes"], "SGVsbG8gV29ybGQ=");
-    assert_eq!(json_value["file"]["mime_type"], "text/plain");
+    assert_eq!(json_value["file"]["mimeType"], "text/plain");
     assert_eq!(json_value["file"]["name"], "hello.txt");
     assert_eq!(json_value["kind"], "file");
 }
@@ -124,7 +124,7 @@ fn test_file_part_with_uri_compatibility() {
     // Verify the JSON structure matches Python's format
     let json_value: serde_json::Value = serde_json::from_str(&serialized).unwrap();
     assert_eq!(json_value["file"]["uri"], "https://example.com/file.pdf");
-    assert_eq!(json_value["file"]["mime_type"], "application/pdf");
+    assert_eq!(json_value["file"]["mimeType"], "application/pdf");
     assert_eq!(json_value["file"]["name"], "document.pdf");
     assert_eq!(json_value["kind"], "file");
     assert_eq!(json_value["metadata"]["source"], "external");
@@ -65,6 +65,8 @@ async fn test_encrypted_push_config_persistence() -> Result<(), Box<dyn std::err
         url: Url::parse("https://example.com/push")?,
         token: Some("secret-token-789".to_string()),
         authentication: None,
+        filter: None,
+        payload_format: None,
     };
 
     // 3. Save and retrieve
@@ -82,3 +84,36 @@ async fn test_encrypted_push_config_persistence() -> Result<(), Box<dyn std::err
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_task_status_is_read_without_history() -> Result<(), Box<dyn std::error::Error>> {
+    use a2a_rust::a2a::server::request_handlers::{DefaultRequestHandler, RequestHandler};
+    use a2a_rust::a2a::server::tasks::ReadConsistency;
+    use std::sync::Arc;
+
+    let task_store = SqliteTaskStore::connect("sqlite::memory:").await?;
+    let history: Vec<Message> = (0..5)
+        .map(|i| Message::new(Role::User, vec![Part::text(format!("message {}", i))]))
+        .collect();
+    let task = Task::new("context-1".to_string(), TaskStatus::new(TaskState::Working))
+        .with_task_id("task-1".to_string())
+        .with_history(history.clone());
+    task_store.save(task).await?;
+
+    let meta = task_store.get_meta("task-1", ReadConsistency::Strong).await?.expect("task-1 should exist");
+    assert_eq!(meta.status.state, TaskState::Working);
+    assert!(meta.history.is_none());
+    let page = task_store.get_history("task-1", 3, 10).await?.expect("task-1 should exist");
+    assert_eq!(page.total, 5);
+    assert_eq!(page.messages, history[3..].to_vec());
+    assert!(task_store.get_history("task-2", 0, 10).await?.is_none());
+
+    let handler = DefaultRequestHandler::new(Arc::new(task_store), None, None);
+    let status_only = TaskQueryParams::new("task-1".to_string()).with_history_length(0);
+    let fetched = handler.on_get_task(status_only, None).await?.expect("task-1 should exist");
+    assert!(fetched.history.is_none());
+    let fetched = handler.on_get_task(TaskQueryParams::new("task-1".to_string()), None).await?.expect("task-1 should exist");
+    assert_eq!(fetched.history.map(|history| history.len()), Some(5));
+
+    Ok(())
+}
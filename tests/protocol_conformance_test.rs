@@ -0,0 +1,178 @@
+//! The JSON-RPC, gRPC and REST handlers apply the same protocol rules
+
+use a2a_rust::a2a::core_types::{Message, Part, Role, TaskState, TaskStatus};
+use a2a_rust::a2a::models::*;
+use a2a_rust::a2a::server::context::ServerCallContext;
+use a2a_rust::a2a::server::request_handlers::{
+    DefaultRequestHandler, GRPCHandler, JSONRPCHandler, RequestHandler, RestHandler,
+};
+use a2a_rust::a2a::server::tasks::{InMemoryTaskStore, TaskStore};
+use serde_json::json;
+use std::sync::Arc;
+
+/// Error code and message of a failed call, or None if it succeeded
+type Outcome = Option<(i32, String)>;
+
+struct Transports {
+    jsonrpc: JSONRPCHandler,
+    grpc: GRPCHandler,
+    rest: RestHandler,
+}
+
+async fn transports(capabilities: AgentCapabilities) -> Transports {
+    let card = AgentCard::new(
+        "Conformance Agent".to_string(),
+        "An agent served over every transport".to_string(),
+        "http://localhost:8080".to_string(),
+        "1.0.0".to_string(),
+        vec!["text/plain".to_string()],
+        vec!["text/plain".to_string()],
+        capabilities,
+        vec![],
+    );
+    let store = Arc::new(InMemoryTaskStore::new());
+    let history = (0..4)
+        .map(|i| Message::new(Role::User, vec![Part::text(format!("message {}", i))]))
+        .collect();
+    let task = Task::new("ctx-1".to_string(), TaskStatus::new(TaskState::Working))
+        .with_task_id("task-1".to_string())
+        .with_history(history);
+    store.save(task).await.unwrap();
+    let handler: Arc<dyn RequestHandler> = Arc::new(DefaultRequestHandler::new(store, None, None));
+
+    Transports {
+        jsonrpc: JSONRPCHandler::new(card.clone(), handler.clone()),
+        grpc: GRPCHandler::new(card.clone(), handler.clone()),
+        rest: RestHandler::new(card, handler),
+    }
+}
+
+fn stream_params() -> MessageSendParams {
+    MessageSendParams::new(Message::new(Role::User, vec![Part::text("hello".to_string())]))
+}
+
+fn push_config() -> TaskPushNotificationConfig {
+    TaskPushNotificationConfig::new(
+        "task-1".to_string(),
+        PushNotificationConfig::new("https://example.com/hook".parse().unwrap()),
+    )
+}
+
+impl Transports {
+    async fn jsonrpc(&self, method: &str, params: serde_json::Value) -> Outcome {
+        let request = json!({"jsonrpc": "2.0", "method": method, "params": params, "id": 1});
+        self.jsonrpc
+            .handle_request(request, &ServerCallContext::new())
+            .await
+            .err()
+            .map(|e| (e.code, e.message))
+    }
+
+    /// Outcomes of message/stream on every transport
+    async fn message_stream(&self) -> [Outcome; 3] {
+        let context = ServerCallContext::new();
+        let params = stream_params();
+        [
+            self.jsonrpc("message/stream", serde_json::to_value(&params).unwrap()).await,
+            self.grpc
+                .handle_message_stream(params.clone(), &context)
+                .await
+                .err()
+                .map(|e| (e.code(), e.message().to_string())),
+            self.rest
+                .on_message_send_stream(params, &context)
+                .await
+                .err()
+                .map(|e| (e.code, e.message)),
+        ]
+    }
+
+    /// Outcomes of tasks/resubscribe on every transport
+    async fn resubscribe(&self) -> [Outcome; 3] {
+        let context = ServerCallContext::new();
+        let params = TaskIdParams::new("task-1".to_string());
+        [
+            self.jsonrpc("tasks/resubscribe", json!({"id": "task-1"})).await,
+            self.grpc
+                .handle_resubscribe_task(params.clone(), &context)
+                .await
+                .err()
+                .map(|e| (e.code(), e.message().to_string())),
+            self.rest
+                .on_resubscribe_to_task(params, &context)
+                .await
+                .err()
+                .map(|e| (e.code, e.message)),
+        ]
+    }
+
+    /// Outcomes of tasks/pushNotificationConfig/set on every transport
+    async fn set_push_config(&self) -> [Outcome; 3] {
+        let context = ServerCallContext::new();
+        [
+            self.jsonrpc("tasks/pushNotificationConfig/set", serde_json::to_value(push_config()).unwrap())
+                .await,
+            self.grpc
+                .handle_set_push_notification_config(push_config(), &context)
+                .await
+                .err()
+                .map(|e| (e.code(), e.message().to_string())),
+            self.rest
+                .set_push_notification(push_config(), &context)
+                .await
+                .err()
+                .map(|e| (e.code, e.message)),
+        ]
+    }
+}
+
+fn assert_all_fail_with(outcomes: [Outcome; 3], code: i32) {
+    let expected = outcomes[0].clone();
+    assert_eq!(expected.as_ref().map(|(code, _)| *code), Some(code), "{:?}", outcomes);
+    assert!(outcomes.iter().all(|outcome| *outcome == expected), "{:?}", outcomes);
+}
+
+#[tokio::test]
+async fn test_disabled_capabilities_fail_identically() {
+    let transports = transports(AgentCapabilities::new()).await;
+
+    assert_all_fail_with(transports.message_stream().await, -32004);
+    assert_all_fail_with(transports.resubscribe().await, -32004);
+    assert_all_fail_with(transports.set_push_config().await, -32003);
+
+    let jsonrpc = transports.jsonrpc("agent/authenticatedExtendedCard", json!({})).await;
+    let grpc = transports
+        .grpc
+        .handle_get_authenticated_extended_card(&ServerCallContext::new())
+        .await
+        .err()
+        .map(|e| (e.code(), e.message().to_string()));
+    assert_eq!(jsonrpc.as_ref().map(|(code, _)| *code), Some(-32004));
+    assert_eq!(jsonrpc, grpc);
+}
+
+#[tokio::test]
+async fn test_enabled_capabilities_pass_the_gate_on_every_transport() {
+    let transports = transports(AgentCapabilities::new().with_streaming(true)).await;
+
+    assert_eq!(transports.message_stream().await, [None, None, None]);
+}
+
+#[tokio::test]
+async fn test_task_queries_trim_history_and_report_missing_tasks_identically() {
+    let transports = transports(AgentCapabilities::new()).await;
+    let context = ServerCallContext::new();
+    let query = TaskQueryParams::new("task-1".to_string()).with_history_length(2);
+
+    let grpc = serde_json::to_value(transports.grpc.handle_get_task(query.clone(), &context).await.unwrap()).unwrap();
+    let rest = transports.rest.on_get_task(query, &context).await.unwrap();
+    assert_eq!(grpc["history"].as_array().unwrap().len(), 2);
+    assert_eq!(grpc, rest);
+
+    let missing = TaskQueryParams::new("missing".to_string());
+    let grpc = transports.grpc.handle_get_task(missing.clone(), &context).await.unwrap_err();
+    let rest = transports.rest.on_get_task(missing, &context).await.unwrap_err();
+    assert_eq!((grpc.code(), grpc.message().to_string()), (rest.code, rest.message));
+    assert_eq!(rest.code, -32001);
+    assert_eq!(rest.status, 404);
+}
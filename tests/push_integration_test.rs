@@ -1,5 +1,5 @@
 use a2a_rust::a2a::models::*;
-use a2a_rust::a2a::core_types::{Message, Role, Part, TaskState};
+use a2a_rust::a2a::core_types::{Message, Role, Part, TaskState, TaskStatus};
 use a2a_rust::a2a::server::request_handlers::{DefaultRequestHandler, RequestHandler, MessageSendResult};
 use a2a_rust::a2a::server::tasks::{
     InMemoryTaskStore, InMemoryPushNotificationConfigStore, HttpPushNotificationSender, 
@@ -42,6 +42,8 @@ async fn test_push_notification_with_in_message_config() {
         url,
         token: Some("test-token".to_string()),
         authentication: None,
+        filter: None,
+        payload_format: None,
     };
     
     let params = MessageSendParams::new(message)
@@ -64,6 +66,9 @@ async fn test_push_notification_with_in_message_config() {
     }
 
     // 6. Verify notification was sent
+    // Notifications are delivered asynchronously from the event bus
+    handler.event_bus().flush().await;
+
     mock.assert_async().await;
 }
 
@@ -115,6 +120,8 @@ async fn test_push_notification_after_config_change() {
         url: url.clone(),
         token: Some("new-token".to_string()),
         authentication: None,
+        filter: None,
+        payload_format: None,
     };
     
     let set_config_params = TaskPushNotificationConfig::new(task_id.clone(), config);
@@ -152,6 +159,9 @@ async fn test_push_notification_after_config_change() {
     }
 
     // 7. Verify notification was sent
+    // Notifications are delivered asynchronously from the event bus
+    handler_with_push.event_bus().flush().await;
+
     mock2.assert_async().await;
 }
 
@@ -195,6 +205,8 @@ async fn test_multiple_push_configs() {
         url: url1,
         token: Some("token1".to_string()),
         authentication: None,
+        filter: None,
+        payload_format: None,
     };
     
     let params = MessageSendParams::new(message)
@@ -214,8 +226,13 @@ async fn test_multiple_push_configs() {
         url: url2,
         token: Some("token2".to_string()),
         authentication: None,
+        filter: None,
+        payload_format: None,
     };
     
+    // Notifications are delivered asynchronously from the event bus
+    handler.event_bus().flush().await;
+
     push_config_store.set_info(&task_id, config2).await.unwrap();
 
     // 5. Verify both configs exist
@@ -265,6 +282,8 @@ async fn test_push_notification_with_failed_endpoint() {
         url,
         token: Some("test-token".to_string()),
         authentication: None,
+        filter: None,
+        payload_format: None,
     };
     
     let params = MessageSendParams::new(message)
@@ -281,6 +300,9 @@ async fn test_push_notification_with_failed_endpoint() {
     }
 
     // 5. Verify notification was attempted
+    // Notifications are delivered asynchronously from the event bus
+    handler.event_bus().flush().await;
+
     mock.assert_async().await;
 }
 
@@ -314,6 +336,8 @@ async fn test_push_notification_config_crud() {
         url: "http://example.com/webhook".parse().unwrap(),
         token: Some("test-token".to_string()),
         authentication: None,
+        filter: None,
+        payload_format: None,
     };
     
     let set_params = TaskPushNotificationConfig::new(task_id.clone(), config.clone());
@@ -356,3 +380,36 @@ async fn test_push_notification_config_crud() {
     let configs_after_delete = push_config_store.get_info(&task_id).await.unwrap();
     assert_eq!(configs_after_delete.len(), 0);
 }
+
+/// Config changes made through the handler invalidate the sender's cache
+#[tokio::test]
+async fn test_push_config_cache_is_invalidated_by_config_changes() {
+    let push_config_store = Arc::new(InMemoryPushNotificationConfigStore::new());
+    let push_sender = Arc::new(HttpPushNotificationSender::new(push_config_store.clone()).with_config_cache(64));
+    let handler = DefaultRequestHandler::new(
+        Arc::new(InMemoryTaskStore::new()),
+        Some(push_config_store.clone()),
+        Some(push_sender.clone()),
+    );
+
+    let task = Task::new("ctx".to_string(), TaskStatus::new(TaskState::Working)).with_task_id("task-1".to_string());
+    let first = PushNotificationConfig::new("http://localhost:1/first".parse().unwrap()).with_id("first".to_string());
+    push_config_store.set_info(&task.id, first).await.unwrap();
+    assert_eq!(push_sender.configs_for(&task, None).await.unwrap().len(), 1);
+
+    let second = PushNotificationConfig::new("http://localhost:1/second".parse().unwrap()).with_id("second".to_string());
+    handler
+        .on_set_task_push_notification_config(TaskPushNotificationConfig::new(task.id.clone(), second), None)
+        .await
+        .unwrap();
+    assert_eq!(push_sender.configs_for(&task, None).await.unwrap().len(), 2);
+
+    handler
+        .on_delete_task_push_notification_config(
+            DeleteTaskPushNotificationConfigParams::new(task.id.clone(), "first".to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(push_sender.configs_for(&task, None).await.unwrap().len(), 1);
+}
@@ -0,0 +1,113 @@
+//! Round-trip tests against JSON captured from a2a-python
+//!
+//! Each fixture under `tests/fixtures/a2a_python` is deserialized into the
+//! matching Rust type, serialized again and compared with the original
+//! JSON as is: field names must be emitted in camelCase and unset fields
+//! omitted, as a2a-python does. Security schemes are not covered yet;
+//! their `type` tags differ between the SDKs.
+
+use a2a_rust::a2a::jsonrpc::{JSONRPCRequest, JSONRPCResponse, SendStreamingMessageResult};
+use a2a_rust::{AgentCard, MessageSendParams, Task, TaskState};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+const AGENT_CARD: &str = include_str!("fixtures/a2a_python/agent_card.json");
+const TASK_WITH_ARTIFACTS: &str = include_str!("fixtures/a2a_python/task_with_artifacts.json");
+const MESSAGE_SEND_REQUEST: &str = include_str!("fixtures/a2a_python/message_send_request.json");
+const MESSAGE_SEND_RESPONSE: &str = include_str!("fixtures/a2a_python/message_send_response.json");
+const ERROR_RESPONSE: &str = include_str!("fixtures/a2a_python/error_response.json");
+const STREAM_FRAMES: &str = include_str!("fixtures/a2a_python/stream_frames.jsonl");
+
+/// Deserializes a fixture as `T` and checks that it serializes back to the same JSON
+fn assert_round_trip<T: DeserializeOwned + Serialize>(fixture: &str) -> T {
+    let original: Value = serde_json::from_str(fixture).expect("fixture is valid JSON");
+    let parsed: T = serde_json::from_value(original.clone()).expect("fixture deserializes");
+    let emitted = serde_json::to_value(&parsed).unwrap();
+    assert_eq!(emitted, original);
+    parsed
+}
+
+#[test]
+fn test_agent_card_fixture() {
+    let card: AgentCard = assert_round_trip(AGENT_CARD);
+    assert_eq!(card.protocol_version.as_deref(), Some("0.3.0"));
+    assert_eq!(card.capabilities.push_notifications, Some(true));
+    assert_eq!(card.skills[0].input_modes, Some(vec!["text/plain".to_string()]));
+}
+
+#[test]
+fn test_task_with_artifacts_fixture() {
+    let task: Task = assert_round_trip(TASK_WITH_ARTIFACTS);
+    assert_eq!(task.status.state, TaskState::Completed);
+    assert_eq!(task.history.as_ref().unwrap().len(), 2);
+    let artifacts = task.artifacts.unwrap();
+    assert_eq!(artifacts.len(), 2);
+    assert_eq!(artifacts[1].parts.len(), 2);
+}
+
+#[test]
+fn test_message_send_request_fixture() {
+    let request: JSONRPCRequest = assert_round_trip(MESSAGE_SEND_REQUEST);
+    assert_eq!(request.method, "message/send");
+
+    let params = serde_json::to_string(&request.params.unwrap()).unwrap();
+    let params: MessageSendParams = assert_round_trip(&params);
+    let configuration = params.configuration.unwrap();
+    assert_eq!(configuration.history_length, Some(5));
+    assert!(configuration.push_notification_config.is_some());
+}
+
+#[test]
+fn test_message_send_response_fixture() {
+    let response: JSONRPCResponse = assert_round_trip(MESSAGE_SEND_RESPONSE);
+    let JSONRPCResponse::Success(success) = response else {
+        panic!("expected a success response");
+    };
+    let task: Task = assert_round_trip(&success.result.to_string());
+    assert_eq!(task.status.state, TaskState::InputRequired);
+    assert!(task.status.message.is_some());
+}
+
+#[test]
+fn test_error_response_fixture() {
+    let response: JSONRPCResponse = assert_round_trip(ERROR_RESPONSE);
+    let JSONRPCResponse::Error(error) = response else {
+        panic!("expected an error response");
+    };
+    assert_eq!(error.error.code, -32001);
+}
+
+#[test]
+fn test_stream_frames_fixture() {
+    let results: Vec<SendStreamingMessageResult> = STREAM_FRAMES
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let response: JSONRPCResponse = assert_round_trip(line);
+            let JSONRPCResponse::Success(success) = response else {
+                panic!("expected a success frame: {}", line);
+            };
+            assert_round_trip(&success.result.to_string())
+        })
+        .collect();
+
+    assert_eq!(results.len(), 5);
+    assert!(matches!(results[0], SendStreamingMessageResult::Task(_)));
+    assert!(matches!(results[1], SendStreamingMessageResult::TaskStatusUpdate(_)));
+    assert!(matches!(results[2], SendStreamingMessageResult::TaskArtifactUpdate(_)));
+    match &results[3] {
+        SendStreamingMessageResult::TaskArtifactUpdate(update) => {
+            assert_eq!(update.append, Some(true));
+            assert_eq!(update.last_chunk, Some(true));
+        }
+        other => panic!("expected artifact update, got {:?}", other),
+    }
+    match &results[4] {
+        SendStreamingMessageResult::TaskStatusUpdate(update) => {
+            assert!(update.r#final);
+            assert_eq!(update.status.state, TaskState::Completed);
+        }
+        other => panic!("expected status update, got {:?}", other),
+    }
+}
@@ -0,0 +1,111 @@
+//! Replay protection and request signing through the JSON-RPC endpoint
+
+use a2a_rust::a2a::jsonrpc::error_codes;
+use a2a_rust::a2a::models::*;
+use a2a_rust::a2a::server::apps::jsonrpc::A2AServerBuilder;
+use a2a_rust::a2a::server::context::DefaultServerCallContextBuilder;
+use a2a_rust::a2a::server::request_handlers::request_handler::MockRequestHandler;
+use a2a_rust::a2a::server::{HmacRequestVerifier, ReplayProtection};
+use a2a_rust::a2a::utils::constants::DEFAULT_RPC_URL;
+use a2a_rust::a2a::utils::replay::{replay_headers, ReplayRequest};
+use a2a_rust::a2a::utils::request_signing::{RequestSignature, REQUEST_SIGNATURE_HEADER};
+use axum::body::Body;
+use axum::http::{Method, Request};
+use axum::Router;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tower::util::ServiceExt;
+
+const REPLAY_KEY: &[u8] = b"replay-key";
+
+fn card() -> AgentCard {
+    AgentCard::new(
+        "Test Agent".to_string(),
+        "A test agent".to_string(),
+        "http://localhost:8080".to_string(),
+        "1.0.0".to_string(),
+        vec!["text/plain".to_string()],
+        vec!["text/plain".to_string()],
+        AgentCapabilities::new(),
+        vec![],
+    )
+}
+
+async fn router() -> Router {
+    A2AServerBuilder::new()
+        .with_agent_card(card())
+        .with_request_handler(Arc::new(MockRequestHandler::new()))
+        .with_context_builder(Arc::new(DefaultServerCallContextBuilder))
+        .with_replay_protection(ReplayProtection::in_memory(REPLAY_KEY))
+        .build()
+        .unwrap()
+        .build_router()
+        .await
+}
+
+fn body() -> Vec<u8> {
+    serde_json::to_vec(&json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tasks/get",
+        "params": { "id": "task-1" }
+    }))
+    .unwrap()
+}
+
+fn rpc(signed_headers: &[(&'static str, String)]) -> Request<Body> {
+    let mut request = Request::builder()
+        .method(Method::POST)
+        .uri(DEFAULT_RPC_URL)
+        .header("content-type", "application/json")
+        .header("authorization", "Bearer secret");
+    for (name, value) in signed_headers {
+        request = request.header(*name, value);
+    }
+    request.body(Body::from(body())).unwrap()
+}
+
+async fn error_code(router: &Router, request: Request<Body>) -> Option<i64> {
+    let response = router.clone().oneshot(request).await.unwrap();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    body["error"]["code"].as_i64()
+}
+
+#[tokio::test]
+async fn test_replayed_request_is_rejected() {
+    let router = router().await;
+    let body = body();
+    let headers = replay_headers(REPLAY_KEY, &ReplayRequest::new("tasks/get", DEFAULT_RPC_URL, &body));
+
+    assert_ne!(error_code(&router, rpc(&headers)).await, Some(error_codes::REPLAY_REJECTED as i64));
+    assert_eq!(error_code(&router, rpc(&headers)).await, Some(error_codes::REPLAY_REJECTED as i64));
+}
+
+#[tokio::test]
+async fn test_unsigned_request_is_rejected() {
+    let router = router().await;
+    assert_eq!(error_code(&router, rpc(&[])).await, Some(error_codes::REPLAY_REJECTED as i64));
+}
+
+#[tokio::test]
+async fn test_request_signed_by_known_peer_is_accepted() {
+    let router = A2AServerBuilder::new()
+        .with_agent_card(card())
+        .with_request_handler(Arc::new(MockRequestHandler::new()))
+        .with_context_builder(Arc::new(DefaultServerCallContextBuilder))
+        .with_request_verifier(HmacRequestVerifier::new().with_peer("peer-a", b"secret"))
+        .build()
+        .unwrap()
+        .build_router()
+        .await;
+    let invalid = Some(error_codes::REQUEST_SIGNATURE_INVALID as i64);
+
+    let now = chrono::Utc::now().timestamp();
+    let signature = RequestSignature::sign("peer-a", b"secret", "tasks/get", &body(), now).to_header();
+    assert_ne!(error_code(&router, rpc(&[(REQUEST_SIGNATURE_HEADER, signature)])).await, invalid);
+
+    let forged = RequestSignature::sign("peer-a", b"guess", "tasks/get", &body(), now).to_header();
+    assert_eq!(error_code(&router, rpc(&[(REQUEST_SIGNATURE_HEADER, forged)])).await, invalid);
+    assert_eq!(error_code(&router, rpc(&[])).await, invalid);
+}
@@ -5,11 +5,13 @@
 use a2a_rust::a2a::{
     models::*,
     server::{
-        apps::jsonrpc::{A2AServerBuilder, ServerConfig},
+        apps::jsonrpc::{A2AServerBuilder, BuildError, ServerConfig, TlsConfig},
+        budget::BudgetLimits,
         context::DefaultServerCallContextBuilder,
         request_handlers::request_handler::MockRequestHandler,
+        tasks::{SqlitePushNotificationConfigStore, SqliteTaskStore},
     },
-    utils::constants::*,
+    utils::{constants::*, jws::Hs256Signer},
 };
 use axum::{
     body::Body,
@@ -227,6 +229,92 @@ async fn test_server_jsonrpc_method_not_found() {
     assert_eq!(response_json["error"]["code"], -32601); // Method not found
 }
 
+#[tokio::test]
+async fn test_server_task_search_over_sqlite_index() {
+    use a2a_rust::a2a::server::quota::{ANONYMOUS_TENANT, TENANT_METADATA_KEY};
+    use a2a_rust::a2a::server::tasks::TaskStore;
+    use a2a_rust::{Message, Part, Role, Task, TaskState, TaskStatus};
+
+    // Anonymous callers only find the tasks of the anonymous tenant
+    let store = SqliteTaskStore::connect("sqlite::memory:").await.unwrap().with_search_index();
+    for (id, tenant) in [("task-1", ANONYMOUS_TENANT), ("task-2", "alice")] {
+        let message = Message::new(Role::User, vec![Part::text("The invoice for March is missing".to_string())]);
+        let mut task = Task::new("ctx-1".to_string(), TaskStatus::new(TaskState::InputRequired))
+            .with_task_id(id.to_string())
+            .with_history(vec![message]);
+        task.metadata = Some(std::collections::HashMap::from([(TENANT_METADATA_KEY.to_string(), json!(tenant))]));
+        store.save(task).await.unwrap();
+    }
+
+    let server = A2AServerBuilder::new()
+        .with_agent_card(create_test_agent_card())
+        .with_request_handler(std::sync::Arc::new(MockRequestHandler::new()))
+        .with_context_builder(std::sync::Arc::new(DefaultServerCallContextBuilder))
+        .with_task_search(std::sync::Arc::new(store))
+        .build()
+        .unwrap();
+    let router: Router = server.build_router().await;
+
+    let jsonrpc_request = json!({
+        "jsonrpc": "2.0",
+        "method": "tasks/search",
+        "params": {"query": "march invoice", "states": ["input-required"]},
+        "id": 1
+    });
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(DEFAULT_RPC_URL)
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_string(&jsonrpc_request).unwrap()))
+        .unwrap();
+
+    let response: Response = router.oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let response_json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(response_json["result"][0]["id"], "task-1");
+    assert_eq!(response_json["result"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_server_strict_protocol_rejects_unknown_members() {
+    let config = ServerConfig {
+        bind_addr: "127.0.0.1:0".parse().unwrap(),
+        strict_protocol: true,
+        ..Default::default()
+    };
+
+    let server = A2AServerBuilder::new()
+        .with_agent_card(create_test_agent_card())
+        .with_request_handler(std::sync::Arc::new(MockRequestHandler::new()))
+        .with_context_builder(std::sync::Arc::new(DefaultServerCallContextBuilder))
+        .with_config(config)
+        .build()
+        .unwrap();
+    let router: Router = server.build_router().await;
+
+    let jsonrpc_request = json!({
+        "jsonrpc": "2.0",
+        "method": "tasks/get",
+        "params": {"id": "task-1"},
+        "id": 1,
+        "trace": "abc"
+    });
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(DEFAULT_RPC_URL)
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_string(&jsonrpc_request).unwrap()))
+        .unwrap();
+
+    let response: Response = router.oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let response_json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(response_json["error"]["code"], -32600); // Invalid request
+    assert_eq!(response_json["error"]["data"]["member"], "trace");
+}
+
 #[tokio::test]
 async fn test_server_extended_agent_card_endpoint() {
     let mut agent_card = create_test_agent_card();
@@ -281,6 +369,279 @@ async fn test_server_extended_agent_card_endpoint() {
     assert_eq!(response_json["description"], extended_card.description);
 }
 
+#[tokio::test]
+async fn test_server_rejects_requests_over_quota() {
+    use a2a_rust::a2a::server::quota::{InMemoryQuotaStore, QuotaLimits, QuotaStore, ANONYMOUS_TENANT};
+
+    let quota_store = std::sync::Arc::new(InMemoryQuotaStore::with_default_limits(
+        QuotaLimits::new().with_max_daily_messages(1),
+    ));
+
+    let server = A2AServerBuilder::new()
+        .with_agent_card(create_test_agent_card())
+        .with_request_handler(std::sync::Arc::new(MockRequestHandler::new()))
+        .with_context_builder(std::sync::Arc::new(DefaultServerCallContextBuilder))
+        .with_quota_store(quota_store.clone())
+        .build()
+        .unwrap();
+    let router: Router = server.build_router().await;
+
+    let jsonrpc_request = json!({
+        "jsonrpc": "2.0",
+        "method": "message/send",
+        "params": {
+            "message": {
+                "kind": "message",
+                "messageId": "test-msg-123",
+                "role": "user",
+                "parts": [{"kind": "text", "text": "Hello, world!"}]
+            }
+        },
+        "id": 1
+    });
+    let make_request = || {
+        Request::builder()
+            .method(Method::POST)
+            .uri(DEFAULT_RPC_URL)
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_string(&jsonrpc_request).unwrap()))
+            .unwrap()
+    };
+
+    let response: Response = router.clone().oneshot(make_request()).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let response_json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(response_json.get("error").is_none());
+
+    let response: Response = router.oneshot(make_request()).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let response_json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        response_json["error"]["code"],
+        a2a_rust::a2a::jsonrpc::error_codes::QUOTA_EXCEEDED
+    );
+    assert_eq!(response_json["error"]["data"]["quota"], "max_daily_messages");
+
+    let usage = quota_store.get_usage(ANONYMOUS_TENANT).await.unwrap();
+    assert_eq!(usage.daily_message_count, 1);
+    assert!(usage.bytes_in > 0);
+    assert!(usage.bytes_out > 0);
+}
+
+#[tokio::test]
+async fn test_minimal_preset_requires_handler() {
+    let missing_handler = A2AServerBuilder::minimal()
+        .with_agent_card(create_test_agent_card())
+        .build();
+    assert!(missing_handler.is_err());
+
+    let server = A2AServerBuilder::minimal()
+        .with_agent_card(create_test_agent_card())
+        .with_request_handler(std::sync::Arc::new(MockRequestHandler::new()))
+        .build();
+    assert!(server.is_ok());
+    assert_eq!(missing_handler.err(), Some(BuildError::MissingRequestHandler));
+}
+
+#[tokio::test]
+async fn test_validate_reports_every_problem() {
+    let config = ServerConfig {
+        tls: Some(TlsConfig {
+            cert_path: "/nonexistent/cert.pem".into(),
+            key_path: "/nonexistent/key.pem".into(),
+        }),
+        ..ServerConfig::default()
+    };
+    let builder = A2AServerBuilder::new()
+        .with_budget_limits(BudgetLimits::new())
+        .with_config(config);
+    let err = builder.validate().unwrap_err();
+    assert_eq!(
+        err.problems(),
+        vec![
+            &BuildError::MissingAgentCard,
+            &BuildError::MissingRequestHandler,
+            &BuildError::MissingContextBuilder,
+            &BuildError::BudgetWithoutUsageRecorder,
+            &BuildError::TlsCertificateMissing("/nonexistent/cert.pem".into()),
+            &BuildError::TlsKeyMissing("/nonexistent/key.pem".into()),
+        ]
+    );
+    assert!(err.to_string().starts_with("Agent card is required; Request handler is required"));
+
+    let mut card = create_test_agent_card();
+    card.supports_authenticated_extended_card = None;
+    card.version = String::new();
+    let builder = A2AServerBuilder::minimal()
+        .with_agent_card(card)
+        .with_extended_agent_card(create_test_agent_card())
+        .with_request_handler(std::sync::Arc::new(MockRequestHandler::new()))
+        .with_strict_validation(true);
+    let err = builder.validate().unwrap_err();
+    assert_eq!(
+        err,
+        BuildError::Multiple(vec![BuildError::ExtendedCardNotDeclared, BuildError::EmptyCardVersion])
+    );
+    assert!(matches!(builder.build(), Err(BuildError::Multiple(_))));
+
+    let builder = A2AServerBuilder::minimal()
+        .with_agent_card(create_test_agent_card())
+        .with_request_handler(std::sync::Arc::new(MockRequestHandler::new()));
+    assert!(builder.validate().is_ok());
+    assert!(builder.build().is_ok());
+}
+
+#[tokio::test]
+async fn test_dev_preset_serves_agent_card() {
+    let server = A2AServerBuilder::dev()
+        .with_agent_card(create_test_agent_card())
+        .build()
+        .unwrap();
+    let router: Router = server.build_router().await;
+
+    let request = Request::builder()
+        .uri(AGENT_CARD_WELL_KNOWN_PATH)
+        .body(Body::empty())
+        .unwrap();
+    let response: Response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_production_preset_validates_card_and_serves_metrics() {
+    let task_store = SqliteTaskStore::connect("sqlite::memory:").await.unwrap();
+    let push_config_store = SqlitePushNotificationConfigStore::connect("sqlite::memory:", None).await.unwrap();
+    let mut invalid_card = create_test_agent_card();
+    invalid_card.url = "not a url".to_string();
+    assert!(A2AServerBuilder::production(task_store, push_config_store)
+        .with_agent_card(invalid_card)
+        .build()
+        .is_err());
+
+    let task_store = SqliteTaskStore::connect("sqlite::memory:").await.unwrap();
+    let push_config_store = SqlitePushNotificationConfigStore::connect("sqlite::memory:", None).await.unwrap();
+    let server = A2AServerBuilder::production(task_store, push_config_store)
+        .with_agent_card(create_test_agent_card())
+        .build()
+        .unwrap();
+    let router: Router = server.build_router().await;
+
+    let request = Request::builder().uri("/metrics").body(Body::empty()).unwrap();
+    let response: Response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let metrics: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(metrics["total_events"], 0);
+}
+
+#[tokio::test]
+async fn test_agent_card_hot_reload() {
+    let server = A2AServerBuilder::new()
+        .with_agent_card(create_test_agent_card())
+        .with_request_handler(std::sync::Arc::new(MockRequestHandler::new()))
+        .with_context_builder(std::sync::Arc::new(DefaultServerCallContextBuilder))
+        .build()
+        .unwrap();
+    let router: Router = server.build_router().await;
+
+    server
+        .agent_card_handle()
+        .await
+        .modify(|card| card.name = "Renamed Agent".to_string())
+        .await;
+
+    let request = Request::builder()
+        .uri(AGENT_CARD_WELL_KNOWN_PATH)
+        .body(Body::empty())
+        .unwrap();
+    let response: Response = router.oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let card: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(card["name"], "Renamed Agent");
+}
+
+#[tokio::test]
+async fn test_agent_card_content_negotiation() {
+    let signer = std::sync::Arc::new(Hs256Signer::new(b"card-secret"));
+    let server = A2AServerBuilder::minimal()
+        .with_agent_card(create_test_agent_card())
+        .with_request_handler(std::sync::Arc::new(MockRequestHandler::new()))
+        .with_card_signer(signer.clone())
+        .build()
+        .unwrap();
+    let router: Router = server.build_router().await;
+
+    let fetch = |accept: &'static str| {
+        let router = router.clone();
+        async move {
+            let request = Request::builder()
+                .uri(AGENT_CARD_WELL_KNOWN_PATH)
+                .header("accept", accept)
+                .body(Body::empty())
+                .unwrap();
+            router.oneshot(request).await.unwrap()
+        }
+    };
+
+    let response = fetch("application/json").await;
+    assert_eq!(response.headers()["content-type"], "application/json");
+    assert!(response.headers().contains_key("etag"));
+    assert!(response.headers().contains_key("last-modified"));
+
+    let response = fetch("application/jose").await;
+    assert_eq!(response.headers()["content-type"], "application/jose");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let payload = signer.verify(std::str::from_utf8(&body).unwrap()).unwrap();
+    let card: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+    assert_eq!(card["name"], "Test Agent");
+    assert_eq!(payload, a2a_rust::a2a::utils::canonicalize(&card).into_bytes());
+
+    let response = fetch("application/yaml").await;
+    assert_eq!(response.headers()["content-type"], "application/yaml");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert!(std::str::from_utf8(&body).unwrap().contains("name: Test Agent"));
+}
+
+#[tokio::test]
+async fn test_agent_card_conditional_requests() {
+    let server = A2AServerBuilder::minimal()
+        .with_agent_card(create_test_agent_card())
+        .with_request_handler(std::sync::Arc::new(MockRequestHandler::new()))
+        .build()
+        .unwrap();
+    let router: Router = server.build_router().await;
+
+    let request = Request::builder().uri(AGENT_CARD_WELL_KNOWN_PATH).body(Body::empty()).unwrap();
+    let response: Response = router.clone().oneshot(request).await.unwrap();
+    let etag = response.headers()["etag"].clone();
+    let last_modified = response.headers()["last-modified"].clone();
+
+    let request = Request::builder()
+        .uri(AGENT_CARD_WELL_KNOWN_PATH)
+        .header("if-none-match", etag.clone())
+        .body(Body::empty())
+        .unwrap();
+    let response: Response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    let request = Request::builder()
+        .uri(AGENT_CARD_WELL_KNOWN_PATH)
+        .header("if-modified-since", last_modified)
+        .body(Body::empty())
+        .unwrap();
+    let response: Response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    server.agent_card_handle().await.modify(|card| card.version = "2.0.0".to_string()).await;
+    let request = Request::builder()
+        .uri(AGENT_CARD_WELL_KNOWN_PATH)
+        .header("if-none-match", etag)
+        .body(Body::empty())
+        .unwrap();
+    let response: Response = router.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
 fn create_test_agent_card() -> AgentCard {
     AgentCard::new(
         "Test Agent".to_string(),
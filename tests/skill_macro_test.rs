@@ -0,0 +1,122 @@
+#![cfg(feature = "macros")]
+
+use a2a_rust::a2a::core_types::{Message, Part, PartRoot, Role, TaskState};
+use a2a_rust::a2a::error::A2AError;
+use a2a_rust::a2a::models::MessageSendParams;
+use a2a_rust::a2a::server::agent_execution::{a2a_skills, AgentExecutor, RequestContext};
+use a2a_rust::a2a::server::events::{Event, EventQueue, InMemoryEventQueue};
+use a2a_rust::a2a::utils::constants::SKILL_ID_METADATA_KEY;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+struct AddArgs {
+    numbers: Vec<f64>,
+}
+
+struct Calculator {
+    greeting: String,
+}
+
+#[a2a_skills]
+impl Calculator {
+    /// Adds the numbers of a data part
+    #[a2a_skill(id = "add", tags = ["math"], examples = ["{\"numbers\": [1, 2]}"])]
+    async fn add(&self, args: AddArgs) -> Result<serde_json::Value, A2AError> {
+        if args.numbers.is_empty() {
+            return Err(A2AError::invalid_params("Nothing to add"));
+        }
+        Ok(json!({ "sum": args.numbers.iter().sum::<f64>() }))
+    }
+
+    #[a2a_skill(id = "greet", name = "Greeter", description = "Greets the sender")]
+    async fn greet(&self, name: String, context: &RequestContext) -> String {
+        format!("{} {} in {}", self.greeting, name, context.context_id.clone().unwrap_or_default())
+    }
+}
+
+async fn run(skill: Option<&str>, parts: Vec<Part>) -> (Result<(), A2AError>, Vec<Event>) {
+    let mut message = Message::new(Role::User, parts);
+    if let Some(skill) = skill {
+        message = message.with_metadata(HashMap::from([(SKILL_ID_METADATA_KEY.to_string(), json!(skill))]));
+    }
+    let context = RequestContext::new(
+        Some(MessageSendParams::new(message)),
+        Some("task-1".to_string()),
+        Some("ctx-1".to_string()),
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let executor = Calculator { greeting: "Hello".to_string() };
+    let queue = Arc::new(InMemoryEventQueue::new().unwrap());
+    let result = executor.execute(context, queue.clone()).await;
+    let mut events = Vec::new();
+    while let Ok(event) = queue.dequeue_event(true).await {
+        events.push(event);
+    }
+    (result, events)
+}
+
+fn final_state(events: &[Event]) -> Option<TaskState> {
+    events.iter().rev().find_map(|event| match event {
+        Event::TaskStatusUpdate(update) if update.r#final => Some(update.status.state.clone()),
+        _ => None,
+    })
+}
+
+fn artifact_part(events: &[Event]) -> Option<PartRoot> {
+    events.iter().find_map(|event| match event {
+        Event::TaskArtifactUpdate(update) => Some(update.artifact.parts[0].root().clone()),
+        _ => None,
+    })
+}
+
+#[test]
+fn test_skills_are_listed_for_the_card() {
+    let skills = Calculator::agent_skills();
+    assert_eq!(skills.len(), 2);
+    assert_eq!(skills[0].id, "add");
+    assert_eq!(skills[0].name, "add");
+    assert_eq!(skills[0].description, "Adds the numbers of a data part");
+    assert_eq!(skills[0].tags, vec!["math"]);
+    assert_eq!(skills[0].examples, Some(vec!["{\"numbers\": [1, 2]}".to_string()]));
+    assert_eq!(skills[1].name, "Greeter");
+    assert_eq!(skills[1].examples, None);
+}
+
+#[tokio::test]
+async fn test_messages_are_routed_into_typed_arguments() {
+    let (result, events) = run(Some("add"), vec![Part::data(json!({ "numbers": [1.5, 2.5] }))]).await;
+    result.unwrap();
+    assert_eq!(final_state(&events), Some(TaskState::Completed));
+    assert!(matches!(artifact_part(&events), Some(PartRoot::Data(data)) if data.data == json!({ "sum": 4.0 })));
+
+    let (result, events) = run(Some("greet"), vec![Part::text("Ada".to_string())]).await;
+    result.unwrap();
+    assert!(matches!(artifact_part(&events), Some(PartRoot::Text(text)) if text.text == "Hello Ada in ctx-1"));
+}
+
+#[tokio::test]
+async fn test_skill_errors_fail_the_task() {
+    let (result, events) = run(Some("add"), vec![Part::data(json!({ "numbers": [] }))]).await;
+    assert!(matches!(result, Err(A2AError::InvalidParams(_))));
+    assert_eq!(final_state(&events), Some(TaskState::Failed));
+
+    let (result, events) = run(Some("add"), vec![Part::text("one and two".to_string())]).await;
+    assert!(matches!(result, Err(A2AError::InvalidParams(_))));
+    assert_eq!(final_state(&events), Some(TaskState::Failed));
+
+    let (result, events) = run(Some("divide"), vec![]).await;
+    assert!(matches!(result, Err(A2AError::InvalidParams(_))));
+    assert!(events.is_empty());
+
+    let (result, _) = run(None, vec![Part::text("Ada".to_string())]).await;
+    assert!(matches!(result, Err(A2AError::InvalidParams(_))));
+}
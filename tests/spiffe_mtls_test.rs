@@ -0,0 +1,91 @@
+//! SPIFFE mTLS between a client and a served agent
+#![cfg(feature = "spiffe")]
+
+use a2a_rust::a2a::jsonrpc::error_codes;
+use a2a_rust::a2a::models::*;
+use a2a_rust::a2a::server::apps::jsonrpc::{A2AServerBuilder, ServerConfig};
+use a2a_rust::a2a::server::context::DefaultServerCallContextBuilder;
+use a2a_rust::a2a::server::request_handlers::request_handler::MockRequestHandler;
+use a2a_rust::a2a::spiffe::{SpiffeClientTls, SpiffePolicy, SpiffeServerTls, SvidSource};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn svid(name: &str) -> SvidSource {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/spiffe");
+    SvidSource::files(
+        format!("{}/{}.pem", dir, name),
+        format!("{}/{}.key", dir, name),
+        format!("{}/bundle.pem", dir),
+    )
+}
+
+async fn serve(policy: SpiffePolicy) -> SocketAddr {
+    let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let card = AgentCard::new(
+        "Test Agent".to_string(),
+        "A test agent".to_string(),
+        format!("https://{}", addr),
+        "1.0.0".to_string(),
+        vec!["text/plain".to_string()],
+        vec!["text/plain".to_string()],
+        AgentCapabilities::new(),
+        vec![],
+    );
+    let server = A2AServerBuilder::new()
+        .with_agent_card(card)
+        .with_request_handler(Arc::new(MockRequestHandler::new()))
+        .with_context_builder(Arc::new(DefaultServerCallContextBuilder))
+        .with_config(ServerConfig {
+            bind_addr: addr,
+            ..ServerConfig::default()
+        })
+        .with_spiffe_tls(SpiffeServerTls::new(svid("server")).with_policy(policy))
+        .build()
+        .unwrap();
+    tokio::spawn(server.serve());
+    for _ in 0..50 {
+        if tokio::net::TcpStream::connect(addr).await.is_ok() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    addr
+}
+
+async fn call(client: &reqwest::Client, addr: SocketAddr, method: &str) -> Value {
+    let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": { "id": "task-1" } });
+    client
+        .post(format!("https://{}/", addr))
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_policy_authorizes_peer_by_spiffe_id() {
+    let addr = serve(SpiffePolicy::new().allow("spiffe://example.org/ns/prod/sa/planner", &["tasks/get"])).await;
+    let client = SpiffeClientTls::new(svid("client")).reqwest_client().await.unwrap();
+
+    let allowed = call(&client, addr, "tasks/get").await;
+    assert_ne!(allowed["error"]["code"], error_codes::PEER_NOT_AUTHORIZED);
+
+    let denied = call(&client, addr, "tasks/cancel").await;
+    assert_eq!(denied["error"]["code"], error_codes::PEER_NOT_AUTHORIZED);
+    assert_eq!(denied["error"]["data"]["spiffe_id"], "spiffe://example.org/ns/prod/sa/planner");
+}
+
+#[tokio::test]
+async fn test_client_without_svid_is_refused() {
+    let addr = serve(SpiffePolicy::new().allow("spiffe://example.org/*", &["*"])).await;
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .build()
+        .unwrap();
+    assert!(client.post(format!("https://{}/", addr)).body("{}").send().await.is_err());
+}
@@ -0,0 +1,61 @@
+//! Tasks record their status transitions when the agent advertises the
+//! state transition history capability
+
+use a2a_rust::a2a::core_types::{Message, Part, Role, TaskState};
+use a2a_rust::a2a::models::*;
+use a2a_rust::a2a::server::request_handlers::{DefaultRequestHandler, MessageSendResult, RequestHandler};
+use a2a_rust::a2a::server::tasks::{InMemoryTaskStore, SqliteTaskStore, TaskStore};
+use std::sync::Arc;
+
+async fn send_and_cancel(handler: &DefaultRequestHandler) -> Task {
+    let message = Message::new(Role::User, vec![Part::text("hello".to_string())]);
+    let MessageSendResult::Task(task) = handler.on_message_send(MessageSendParams::new(message), None).await.unwrap() else {
+        panic!("expected a task");
+    };
+    handler
+        .on_cancel_task(TaskIdParams::new(task.id.clone()), None)
+        .await
+        .unwrap()
+        .unwrap();
+    handler
+        .on_get_task(TaskQueryParams::new(task.id), None)
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+fn states(task: &Task) -> Vec<TaskState> {
+    task.state_transitions
+        .iter()
+        .flatten()
+        .map(|transition| transition.state.clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_transitions_are_recorded_and_persisted_when_enabled() {
+    let capabilities = AgentCapabilities::new().with_state_transition_history(true);
+    let store = Arc::new(SqliteTaskStore::connect("sqlite::memory:").await.unwrap());
+    let handler = DefaultRequestHandler::new(store.clone(), None, None).with_capabilities(&capabilities);
+
+    let task = send_and_cancel(&handler).await;
+    assert_eq!(states(&task), vec![TaskState::Working, TaskState::Canceled]);
+    let transitions = task.state_transitions.as_ref().unwrap();
+    assert_eq!(Some(&transitions[1].timestamp), task.status.timestamp.as_ref());
+
+    let stored = store.get(&task.id).await.unwrap().unwrap();
+    assert_eq!(stored.state_transitions, task.state_transitions);
+    let json = serde_json::to_value(&stored).unwrap();
+    assert_eq!(json["stateTransitions"][1]["state"], "canceled");
+}
+
+#[tokio::test]
+async fn test_no_transitions_without_the_capability() {
+    let handler = DefaultRequestHandler::new(Arc::new(InMemoryTaskStore::new()), None, None)
+        .with_capabilities(&AgentCapabilities::new());
+
+    let task = send_and_cancel(&handler).await;
+    assert_eq!(task.status.state, TaskState::Canceled);
+    assert!(task.state_transitions.is_none());
+    assert!(serde_json::to_value(&task).unwrap().get("state_transitions").is_none());
+}
@@ -0,0 +1,125 @@
+use a2a_rust::a2a::core_types::{Message, Part, Role, TaskState};
+use a2a_rust::a2a::error::A2AError;
+use a2a_rust::a2a::models::*;
+use a2a_rust::a2a::server::events::InMemoryEventStore;
+use a2a_rust::a2a::server::request_handlers::{DefaultRequestHandler, Event, RequestHandler, StreamEvent};
+use a2a_rust::a2a::server::tasks::InMemoryTaskStore;
+use futures::StreamExt;
+use std::sync::Arc;
+
+fn stream_params() -> MessageSendParams {
+    MessageSendParams::new(Message::new(Role::User, vec![Part::text("Hello".to_string())]))
+}
+
+#[tokio::test]
+async fn test_streamed_events_carry_event_ids() {
+    let handler = DefaultRequestHandler::new(Arc::new(InMemoryTaskStore::new()), None, None)
+        .with_event_store(Arc::new(InMemoryEventStore::new()));
+
+    let events: Vec<StreamEvent> = handler
+        .on_message_send_stream_resumable(stream_params(), None)
+        .await
+        .unwrap()
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|event| event.event_id.is_some()));
+    assert_ne!(events[0].event_id, events[1].event_id);
+}
+
+#[tokio::test]
+async fn test_events_carry_no_ids_without_event_store() {
+    let handler = DefaultRequestHandler::new(Arc::new(InMemoryTaskStore::new()), None, None);
+
+    let events: Vec<StreamEvent> = handler
+        .on_message_send_stream_resumable(stream_params(), None)
+        .await
+        .unwrap()
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|event| event.event_id.is_none()));
+}
+
+#[tokio::test]
+async fn test_resubscribe_replays_missed_tail() {
+    let handler = DefaultRequestHandler::new(Arc::new(InMemoryTaskStore::new()), None, None)
+        .with_event_store(Arc::new(InMemoryEventStore::new()));
+
+    let mut stream = handler.on_message_send_stream_resumable(stream_params(), None).await.unwrap();
+    let first = stream.next().await.unwrap().unwrap();
+    let task_id = match &first.event {
+        Event::Task(task) => task.id.clone(),
+        other => panic!("expected task, got {:?}", other),
+    };
+    // The client disconnects after the first event
+    while stream.next().await.is_some() {}
+
+    let params = TaskIdParams::new(task_id).with_from_event_id(first.event_id.unwrap());
+    let replayed: Vec<StreamEvent> = handler
+        .on_resubscribe_to_task_resumable(params, None)
+        .await
+        .unwrap()
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(replayed.len(), 1);
+    match &replayed[0].event {
+        Event::TaskStatusUpdate(update) => assert_eq!(update.status.state, TaskState::Completed),
+        other => panic!("expected status update, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_resume_requires_event_store() {
+    let handler = DefaultRequestHandler::new(Arc::new(InMemoryTaskStore::new()), None, None);
+    let params = TaskIdParams::new("task".to_string()).with_from_event_id("ev1-1".to_string());
+    assert!(handler.on_resubscribe_to_task_resumable(params, None).await.is_err());
+}
+
+#[tokio::test]
+async fn test_resubscribe_filters_events() {
+    let handler = DefaultRequestHandler::new(Arc::new(InMemoryTaskStore::new()), None, None)
+        .with_event_store(Arc::new(InMemoryEventStore::new()));
+    let events: Vec<StreamEvent> = handler
+        .on_message_send_stream_resumable(stream_params(), None)
+        .await
+        .unwrap()
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+    let task_id = match &events[0].event {
+        Event::Task(task) => task.id.clone(),
+        other => panic!("expected task, got {:?}", other),
+    };
+    let snapshot_id = events[0].event_id.clone().unwrap();
+    let resubscribe = |params: TaskIdParams| async {
+        let stream = handler.on_resubscribe_to_task_resumable(params, None).await?;
+        Ok::<Vec<Event>, A2AError>(stream.map(|event| event.unwrap().event).collect().await)
+    };
+    let filtered = |filter: ResubscribeFilter| TaskIdParams::new(task_id.clone()).with_filter(filter);
+
+    // The task is completed, so resubscribing streams only its snapshot
+    let snapshot = resubscribe(filtered(ResubscribeFilter::new())).await.unwrap();
+    assert!(matches!(snapshot.as_slice(), [Event::Task(_)]));
+    assert!(resubscribe(filtered(ResubscribeFilter::status_updates())).await.unwrap().is_empty());
+
+    // Replayed events are filtered too
+    let since = |since: &str| ResubscribeFilter::status_updates().with_since(since.to_string());
+    let replayed = resubscribe(filtered(since("2000-01-01T00:00:00Z")).with_from_event_id(snapshot_id.clone()))
+        .await
+        .unwrap();
+    assert!(matches!(replayed.as_slice(), [Event::TaskStatusUpdate(_)]));
+    let replayed = resubscribe(filtered(since("2999-01-01T00:00:00Z")).with_from_event_id(snapshot_id))
+        .await
+        .unwrap();
+    assert!(replayed.is_empty());
+
+    assert!(resubscribe(filtered(ResubscribeFilter::new().with_kinds(vec![]))).await.is_err());
+    assert!(resubscribe(filtered(since("yesterday"))).await.is_err());
+}
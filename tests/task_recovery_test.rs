@@ -0,0 +1,69 @@
+use a2a_rust::a2a::core_types::{PartRoot, TaskState, TaskStatus};
+use a2a_rust::a2a::models::*;
+use a2a_rust::a2a::server::request_handlers::{DefaultRequestHandler, RecoveryPolicy, WorkerPool, RESTART_FAILURE_REASON};
+use a2a_rust::a2a::server::tasks::{InMemoryTaskStore, TaskStore};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A store as a crashed process leaves it: one task per state of interest
+async fn store_after_crash() -> Arc<InMemoryTaskStore> {
+    let store = Arc::new(InMemoryTaskStore::new());
+    for (id, state) in [
+        ("submitted", TaskState::Submitted),
+        ("working", TaskState::Working),
+        ("input", TaskState::InputRequired),
+        ("done", TaskState::Completed),
+    ] {
+        let task = Task::new("ctx-1".to_string(), TaskStatus::new(state)).with_task_id(id.to_string());
+        store.save(task).await.unwrap();
+    }
+    store
+}
+
+async fn state(store: &InMemoryTaskStore, id: &str) -> TaskState {
+    store.get(id).await.unwrap().unwrap().status.state
+}
+
+#[tokio::test]
+async fn test_recovery_leaves_tasks_by_default() {
+    let store = store_after_crash().await;
+    let handler = DefaultRequestHandler::new(store.clone(), None, None);
+
+    assert!(handler.recover_tasks().await.unwrap().is_empty());
+    assert_eq!(state(&store, "submitted").await, TaskState::Submitted);
+    assert_eq!(state(&store, "working").await, TaskState::Working);
+}
+
+#[tokio::test]
+async fn test_recovery_marks_interrupted_tasks_failed() {
+    let store = store_after_crash().await;
+    let handler = DefaultRequestHandler::new(store.clone(), None, None).with_recovery_policy(RecoveryPolicy::MarkFailed);
+
+    let mut recovered = handler.recover_tasks().await.unwrap();
+    recovered.sort();
+    assert_eq!(recovered, vec!["submitted", "working"]);
+
+    let failed = store.get("working").await.unwrap().unwrap();
+    assert_eq!(failed.status.state, TaskState::Failed);
+    let reason = failed.status.message.unwrap();
+    assert!(matches!(reason.parts[0].root(), PartRoot::Text(text) if text.text == RESTART_FAILURE_REASON));
+    assert_eq!(state(&store, "input").await, TaskState::InputRequired);
+    assert_eq!(state(&store, "done").await, TaskState::Completed);
+}
+
+#[tokio::test]
+async fn test_recovery_requeues_through_worker_pool() {
+    let store = store_after_crash().await;
+    let handler = DefaultRequestHandler::new(store.clone(), None, None)
+        .with_worker_pool(WorkerPool::new(1))
+        .with_recovery_policy(RecoveryPolicy::Requeue);
+
+    assert_eq!(handler.recover_tasks().await.unwrap().len(), 2);
+    let started = tokio::time::timeout(Duration::from_secs(5), async {
+        while state(&store, "submitted").await != TaskState::Working || state(&store, "working").await != TaskState::Working {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    assert!(started.is_ok(), "requeued tasks should start");
+}
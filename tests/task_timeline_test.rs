@@ -0,0 +1,66 @@
+use a2a_rust::a2a::core_types::{Message, Part, Role};
+use a2a_rust::a2a::models::*;
+use a2a_rust::a2a::server::events::{TaskTimeline, TimelinePhase};
+use a2a_rust::a2a::server::request_handlers::{DefaultRequestHandler, MessageSendResult, RequestHandler};
+use a2a_rust::a2a::server::tasks::InMemoryTaskStore;
+use std::sync::Arc;
+
+fn message() -> MessageSendParams {
+    MessageSendParams::new(Message::new(Role::User, vec![Part::text("hi".to_string())]))
+}
+
+#[tokio::test]
+async fn test_timeline_records_task_phases() {
+    let timeline = TaskTimeline::new();
+    let handler = DefaultRequestHandler::new(Arc::new(InMemoryTaskStore::new()), None, None)
+        .with_task_timeline(timeline.clone());
+
+    let task = match handler.on_message_send(message(), None).await.unwrap() {
+        MessageSendResult::Task(task) => task,
+        other => panic!("expected task, got {:?}", other),
+    };
+    handler.on_cancel_task(TaskIdParams::new(task.id.clone()), None).await.unwrap();
+    handler.event_bus().flush().await;
+
+    let phases: Vec<TimelinePhase> = timeline.get(&task.id).unwrap().into_iter().map(|entry| entry.phase).collect();
+    assert_eq!(
+        phases,
+        vec![
+            TimelinePhase::Received,
+            TimelinePhase::Started,
+            TimelinePhase::Event {
+                event_type: "task".to_string(),
+                state: "working".to_string(),
+            },
+            TimelinePhase::Event {
+                event_type: "status-update".to_string(),
+                state: "canceled".to_string(),
+            },
+            TimelinePhase::Completed {
+                state: "canceled".to_string(),
+            },
+        ]
+    );
+    let entries = timeline.get(&task.id).unwrap();
+    assert!(entries.windows(2).all(|pair| pair[0].elapsed_ms <= pair[1].elapsed_ms));
+}
+
+#[tokio::test]
+async fn test_timeline_is_included_in_metadata_when_enabled() {
+    let store = Arc::new(InMemoryTaskStore::new());
+    for in_metadata in [false, true] {
+        let handler = DefaultRequestHandler::new(store.clone(), None, None)
+            .with_task_timeline(TaskTimeline::new().with_metadata(in_metadata));
+        let task = match handler.on_message_send(message(), None).await.unwrap() {
+            MessageSendResult::Task(task) => task,
+            other => panic!("expected task, got {:?}", other),
+        };
+
+        let fetched = handler.on_get_task(TaskQueryParams::new(task.id), None).await.unwrap().unwrap();
+        let timeline = fetched.metadata.as_ref().and_then(|metadata| metadata.get("timeline"));
+        assert_eq!(timeline.is_some(), in_metadata);
+        if let Some(timeline) = timeline {
+            assert_eq!(timeline[0]["phase"], "received");
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use a2a_rust::a2a::auth::user::AuthenticatedUser;
+use a2a_rust::a2a::core_types::{Message, Part, Role};
+use a2a_rust::a2a::models::*;
+use a2a_rust::a2a::server::context::ServerCallContext;
+use a2a_rust::a2a::server::quota::TENANT_METADATA_KEY;
+use a2a_rust::a2a::server::request_handlers::{DefaultRequestHandler, MessageSendResult, RequestHandler};
+use a2a_rust::a2a::server::tasks::{InMemoryTaskStore, TaskStore};
+use std::sync::Arc;
+
+async fn send(handler: &DefaultRequestHandler, context: Option<&ServerCallContext>) -> Task {
+    let params = MessageSendParams::new(Message::new(Role::User, vec![Part::text("hi".to_string())]));
+    match handler.on_message_send(params, context).await.unwrap() {
+        MessageSendResult::Task(task) => task,
+        other => panic!("expected task, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_tasks_record_the_tenant_that_created_them() {
+    let store = Arc::new(InMemoryTaskStore::new());
+    let handler = DefaultRequestHandler::new(store.clone(), None, None).with_tenant_metadata();
+    let mut context = ServerCallContext::new();
+    context.user = AuthenticatedUser::new("team-a".to_string());
+
+    let task = send(&handler, Some(&context)).await;
+    let stored = store.get(&task.id).await.unwrap().unwrap();
+    assert_eq!(stored.metadata.unwrap()[TENANT_METADATA_KEY], "team-a");
+
+    let task = send(&handler, None).await;
+    assert_eq!(task.metadata.unwrap()[TENANT_METADATA_KEY], "anonymous");
+}
+
+#[tokio::test]
+async fn test_tenants_are_not_recorded_by_default() {
+    let handler = DefaultRequestHandler::new(Arc::new(InMemoryTaskStore::new()), None, None);
+    assert!(send(&handler, None).await.metadata.is_none());
+}
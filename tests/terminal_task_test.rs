@@ -0,0 +1,98 @@
+use a2a_rust::a2a::core_types::{Message, Part, Role, TaskState, TaskStatus};
+use a2a_rust::a2a::error::A2AError;
+use a2a_rust::a2a::models::*;
+use a2a_rust::a2a::server::request_handlers::{DefaultRequestHandler, MessageSendResult, RequestHandler, TerminalTaskPolicy};
+use a2a_rust::a2a::server::tasks::{InMemoryTaskStore, TaskStore};
+use std::sync::Arc;
+
+const TERMINAL_STATES: [TaskState; 4] = [
+    TaskState::Completed,
+    TaskState::Canceled,
+    TaskState::Failed,
+    TaskState::Rejected,
+];
+
+async fn store_with_task(state: TaskState) -> Arc<InMemoryTaskStore> {
+    let store = Arc::new(InMemoryTaskStore::new());
+    let task = Task::new("ctx-1".to_string(), TaskStatus::new(state))
+        .with_task_id("task-1".to_string())
+        .with_history(vec![Message::new(Role::User, vec![Part::text("first".to_string())])]);
+    store.save(task).await.unwrap();
+    store
+}
+
+fn follow_up(task_id: &str) -> MessageSendParams {
+    let mut message = Message::new(Role::User, vec![Part::text("again".to_string())]);
+    message.task_id = Some(task_id.to_string());
+    MessageSendParams::new(message)
+}
+
+#[tokio::test]
+async fn test_terminal_tasks_are_rejected_by_default() {
+    for state in TERMINAL_STATES {
+        let handler = DefaultRequestHandler::new(store_with_task(state.clone()).await, None, None);
+
+        let err = handler.on_message_send(follow_up("task-1"), None).await.unwrap_err();
+        assert!(matches!(err, A2AError::InvalidParams(_)), "{:?}: {:?}", state, err);
+
+        let err = handler
+            .on_message_send_stream(follow_up("task-1"), None)
+            .await
+            .err()
+            .expect("stream should be rejected");
+        assert!(matches!(err, A2AError::InvalidParams(_)), "{:?}: {:?}", state, err);
+    }
+}
+
+#[tokio::test]
+async fn test_terminal_tasks_reopen_when_configured() {
+    for state in TERMINAL_STATES {
+        let handler = DefaultRequestHandler::new(store_with_task(state.clone()).await, None, None)
+            .with_terminal_task_policy(TerminalTaskPolicy::Reopen);
+
+        let task = match handler.on_message_send(follow_up("task-1"), None).await.unwrap() {
+            MessageSendResult::Task(task) => task,
+            other => panic!("expected task, got {:?}", other),
+        };
+        assert_eq!(task.id, "task-1", "{:?}", state);
+        assert_eq!(task.context_id, "ctx-1");
+        assert_eq!(task.status.state, TaskState::Working);
+        assert_eq!(task.history.unwrap().len(), 2);
+    }
+}
+
+#[tokio::test]
+async fn test_unknown_task_id_is_not_found() {
+    let handler = DefaultRequestHandler::new(Arc::new(InMemoryTaskStore::new()), None, None)
+        .with_terminal_task_policy(TerminalTaskPolicy::Reopen);
+
+    let err = handler.on_message_send(follow_up("missing"), None).await.unwrap_err();
+    assert!(matches!(err, A2AError::TaskNotFound(_)));
+}
+
+#[tokio::test]
+async fn test_active_task_continues() {
+    let handler = DefaultRequestHandler::new(store_with_task(TaskState::InputRequired).await, None, None);
+
+    let task = match handler.on_message_send(follow_up("task-1"), None).await.unwrap() {
+        MessageSendResult::Task(task) => task,
+        other => panic!("expected task, got {:?}", other),
+    };
+    assert_eq!(task.status.state, TaskState::Working);
+    assert_eq!(task.history.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_terminal_tasks_are_not_canceled() {
+    for state in TERMINAL_STATES {
+        let store = store_with_task(state.clone()).await;
+        let handler = DefaultRequestHandler::new(store.clone(), None, None);
+
+        let err = handler
+            .on_cancel_task(TaskIdParams::new("task-1".to_string()), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, A2AError::TaskNotCancelable(_)), "{:?}: {:?}", state, err);
+        assert_eq!(store.get("task-1").await.unwrap().unwrap().status.state, state);
+    }
+}
@@ -188,6 +188,57 @@ fn test_task_status() {
     assert!(status.timestamp.is_some());
 }
 
+#[test]
+fn test_task_status_timestamp_parsing() {
+    let status: TaskStatus =
+        serde_json::from_str(r#"{"state":"working","timestamp":"2025-06-12T09:41:05.101331+00:00"}"#).unwrap();
+    let at = status.recorded_at().unwrap();
+    assert_eq!(at.to_rfc3339(), "2025-06-12T09:41:05.101331+00:00");
+    // The received formatting is kept for round trips
+    assert_eq!(
+        serde_json::to_value(&status).unwrap()["timestamp"],
+        "2025-06-12T09:41:05.101331+00:00"
+    );
+
+    let naive: TaskStatus = serde_json::from_str(r#"{"state":"working","timestamp":"2025-06-12T09:41:05"}"#).unwrap();
+    assert_eq!(naive.recorded_at().unwrap().to_rfc3339(), "2025-06-12T09:41:05+00:00");
+
+    // Tasks stored by earlier versions load, normalized to RFC 3339
+    let legacy: a2a_rust::Task = serde_json::from_str(
+        r#"{"id":"t1","contextId":"c1","kind":"task","status":{"state":"completed","timestamp":"2026-01-01 00:00:00.250 UTC"}}"#,
+    )
+    .unwrap();
+    assert_eq!(legacy.status.timestamp.as_deref(), Some("2026-01-01T00:00:00.250+00:00"));
+    assert_eq!(parse_timestamp("2026-01-01 00:00:00 UTC").unwrap().to_rfc3339(), "2026-01-01T00:00:00+00:00");
+
+    let missing: TaskStatus = serde_json::from_str(r#"{"state":"working"}"#).unwrap();
+    assert!(missing.timestamp.is_none());
+
+    let invalid = serde_json::from_str::<TaskStatus>(r#"{"state":"working","timestamp":"yesterday"}"#);
+    assert!(invalid.is_err());
+}
+
+#[test]
+fn test_task_status_ordering() {
+    // Later as an instant, though earlier as a string
+    let earlier = TaskStatus::new(TaskState::Working).with_timestamp("2025-06-12T10:00:00+02:00".to_string());
+    let later = TaskStatus::new(TaskState::Completed).with_timestamp("2025-06-12T09:00:00Z".to_string());
+    assert!(later.is_newer_than(&earlier));
+    assert!(!earlier.is_newer_than(&later));
+
+    let untimed = TaskStatus {
+        state: TaskState::Submitted,
+        message: None,
+        timestamp: None,
+    };
+    let mut statuses = [later.clone(), untimed.clone(), earlier.clone()];
+    statuses.sort_by(TaskStatus::cmp_recorded);
+    assert_eq!(statuses, [untimed, earlier, later.clone()]);
+
+    let at = later.recorded_at().unwrap();
+    assert_eq!(TaskStatus::new(TaskState::Completed).recorded(at).recorded_at(), Some(at));
+}
+
 #[test]
 fn test_task_status_with_message() {
     let message = Message {
@@ -0,0 +1,150 @@
+//! Clients upload large files in resumable chunks and reference them by URI
+
+use a2a_rust::a2a::core_types::{Message, Part, Role};
+use a2a_rust::a2a::models::*;
+use a2a_rust::a2a::server::apps::jsonrpc::A2AServerBuilder;
+use a2a_rust::a2a::server::request_handlers::DefaultRequestHandler;
+use a2a_rust::a2a::server::tasks::InMemoryTaskStore;
+use a2a_rust::a2a::server::{ApiKeyContextBuilder, InMemoryArtifactStore, Upload, UploadManager};
+use a2a_rust::a2a::utils::constants::DEFAULT_RPC_URL;
+use axum::body::Body;
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tower::util::ServiceExt;
+
+fn uploads() -> UploadManager {
+    UploadManager::new(
+        Arc::new(InMemoryArtifactStore::new()),
+        url::Url::parse("http://localhost:8080/uploads").unwrap(),
+    )
+}
+
+async fn router(uploads: UploadManager) -> Router {
+    let card = AgentCard::new(
+        "Upload Agent".to_string(),
+        "An agent reading uploaded files".to_string(),
+        "http://localhost:8080".to_string(),
+        "1.0.0".to_string(),
+        vec!["text/plain".to_string()],
+        vec!["text/plain".to_string()],
+        AgentCapabilities::new(),
+        vec![],
+    );
+    let server = A2AServerBuilder::new()
+        .with_agent_card(card)
+        .with_request_handler(Arc::new(DefaultRequestHandler::new(Arc::new(InMemoryTaskStore::new()), None, None)))
+        .with_context_builder(Arc::new(
+            ApiKeyContextBuilder::new("x-api-key").with_key("alice-key", "alice").with_key("bob-key", "bob"),
+        ))
+        .with_uploads(uploads)
+        .build()
+        .unwrap();
+    server.build_router().await
+}
+
+async fn call(
+    router: &Router,
+    method: Method,
+    path: &str,
+    key: &str,
+    headers: &[(&str, String)],
+    body: Vec<u8>,
+) -> (StatusCode, axum::http::HeaderMap, Vec<u8>) {
+    let mut request = Request::builder().method(method).uri(path).header("x-api-key", key);
+    for (name, value) in headers {
+        request = request.header(*name, value.as_str());
+    }
+    let response = router.clone().oneshot(request.body(Body::from(body)).unwrap()).await.unwrap();
+    let (parts, body) = response.into_parts();
+    let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+    (parts.status, parts.headers, bytes.to_vec())
+}
+
+async fn send_file(router: &Router, uri: &str) -> Value {
+    let message = Message::new(Role::User, vec![Part::file_uri(url::Url::parse(uri).unwrap())]);
+    let body = json!({"jsonrpc": "2.0", "id": 1, "method": "message/send", "params": {"message": message}});
+    let (_, _, bytes) = call(
+        router,
+        Method::POST,
+        DEFAULT_RPC_URL,
+        "alice-key",
+        &[("content-type", "application/json".to_string())],
+        serde_json::to_vec(&body).unwrap(),
+    )
+    .await;
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[tokio::test]
+async fn test_interrupted_upload_resumes_and_is_referenced() {
+    let router = router(uploads()).await;
+    let (status, headers, body) = call(
+        &router,
+        Method::POST,
+        "/uploads",
+        "alice-key",
+        &[("upload-length", "10".to_string()), ("upload-content-type", "text/csv".to_string())],
+        Vec::new(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let upload: Upload = serde_json::from_slice(&body).unwrap();
+    assert_eq!(headers["location"], upload.uri.as_str());
+    let path = format!("/uploads/{}", upload.id);
+
+    let offset = |offset: u64| vec![("upload-offset", offset.to_string())];
+    let (status, headers, _) = call(&router, Method::PATCH, &path, "alice-key", &offset(0), b"a,b,".to_vec()).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert_eq!(headers["upload-offset"], "4");
+
+    // A message cannot reference the upload before it is finished
+    let response = send_file(&router, &upload.uri).await;
+    assert_eq!(response["error"]["code"], -32602);
+
+    // After a dropped connection the client asks where to resume
+    let (status, headers, _) = call(&router, Method::HEAD, &path, "alice-key", &[], Vec::new()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers["upload-offset"], "4");
+    let (status, headers, _) = call(&router, Method::PATCH, &path, "alice-key", &offset(0), b"a,b,".to_vec()).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(headers["upload-offset"], "4");
+    let (status, _, _) = call(&router, Method::PATCH, &path, "alice-key", &offset(4), b"c\n1,2,3\n".to_vec()).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    let (status, _, _) = call(&router, Method::PATCH, &path, "alice-key", &offset(4), b"c\n1,2\n".to_vec()).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, headers, body) = call(&router, Method::GET, &path, "alice-key", &[], Vec::new()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers["content-type"], "text/csv");
+    assert_eq!(body, b"a,b,c\n1,2\n");
+    let (status, _, _) = call(&router, Method::GET, &path, "bob-key", &[], Vec::new()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let response = send_file(&router, &upload.uri).await;
+    assert!(response.get("error").is_none(), "{}", response);
+}
+
+async fn create_upload(router: &Router, length: u64) -> Upload {
+    let headers = [("upload-length", length.to_string())];
+    let (_, _, body) = call(router, Method::POST, "/uploads", "alice-key", &headers, Vec::new()).await;
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[tokio::test]
+async fn test_unreferenced_uploads_are_collected() {
+    let uploads = uploads().with_ttl(std::time::Duration::ZERO);
+    let router = router(uploads.clone()).await;
+    let kept = create_upload(&router, 1).await;
+    let dropped = create_upload(&router, 1).await;
+    let path = format!("/uploads/{}", kept.id);
+    call(&router, Method::PATCH, &path, "alice-key", &[("upload-offset", "0".to_string())], vec![7]).await;
+    send_file(&router, &kept.uri).await;
+
+    assert_eq!(uploads.collect_garbage().await.unwrap(), vec![dropped.id.clone()]);
+    let (status, _, _) = call(&router, Method::HEAD, &format!("/uploads/{}", dropped.id), "alice-key", &[], Vec::new()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _, body) = call(&router, Method::GET, &path, "alice-key", &[], Vec::new()).await;
+    assert_eq!((status, body), (StatusCode::OK, vec![7]));
+}
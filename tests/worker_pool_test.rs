@@ -0,0 +1,74 @@
+use a2a_rust::a2a::core_types::{Message, Part, Role, TaskState};
+use a2a_rust::a2a::jsonrpc::error_codes;
+use a2a_rust::a2a::models::*;
+use a2a_rust::a2a::server::request_handlers::{
+    DefaultRequestHandler, MessageSendResult, RequestHandler, WhenBusy, WorkerPool,
+};
+use a2a_rust::a2a::server::tasks::{InMemoryTaskStore, TaskStore};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn message() -> MessageSendParams {
+    MessageSendParams::new(Message::new(Role::User, vec![Part::text("hi".to_string())]))
+}
+
+fn task(result: MessageSendResult) -> Task {
+    match result {
+        MessageSendResult::Task(task) => task,
+        other => panic!("expected task, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_busy_pool_defers_execution() {
+    let store = Arc::new(InMemoryTaskStore::new());
+    let handler = DefaultRequestHandler::new(store.clone(), None, None).with_worker_pool(WorkerPool::new(1));
+
+    let running = handler.on_message_send_stream(message(), None).await.unwrap();
+    let submitted = task(handler.on_message_send(message(), None).await.unwrap());
+    assert_eq!(submitted.status.state, TaskState::Submitted);
+    assert_eq!(
+        store.get(&submitted.id).await.unwrap().unwrap().status.state,
+        TaskState::Submitted
+    );
+
+    // The deferred task starts once the running one frees its worker
+    drop(running);
+    let started = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let task = store.get(&submitted.id).await.unwrap().unwrap();
+            if task.status.state == TaskState::Working {
+                return task;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("deferred task should start");
+    assert_eq!(started.history.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_free_pool_executes_immediately() {
+    let handler = DefaultRequestHandler::new(Arc::new(InMemoryTaskStore::new()), None, None)
+        .with_worker_pool(WorkerPool::new(1));
+
+    let first = task(handler.on_message_send(message(), None).await.unwrap());
+    let second = task(handler.on_message_send(message(), None).await.unwrap());
+    assert_eq!(first.status.state, TaskState::Working);
+    assert_eq!(second.status.state, TaskState::Working);
+}
+
+#[tokio::test]
+async fn test_busy_pool_waits_up_to_timeout() {
+    let pool = WorkerPool::new(1).with_when_busy(WhenBusy::Wait { timeout: Some(Duration::from_millis(20)) });
+    let handler = DefaultRequestHandler::new(Arc::new(InMemoryTaskStore::new()), None, None).with_worker_pool(pool);
+
+    let running = handler.on_message_send_stream(message(), None).await.unwrap();
+    let err = handler.on_message_send(message(), None).await.unwrap_err();
+    assert_eq!(err.code(), error_codes::CONCURRENCY_LIMIT_EXCEEDED);
+
+    drop(running);
+    let task = task(handler.on_message_send(message(), None).await.unwrap());
+    assert_eq!(task.status.state, TaskState::Working);
+}